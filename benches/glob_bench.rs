@@ -5,6 +5,7 @@
 //! These benchmarks use real filesystem operations on actual fixtures.
 //! Fixtures must be generated first using `node benches/setup-fixtures.js`
 
+use ahash::AHashSet;
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use std::path::PathBuf;
 use walkdir::WalkDir;
@@ -448,6 +449,51 @@ fn bench_result_collection(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark the two `dedupStrategy` sets on fixture result paths.
+///
+/// `FullString` keeps an owned copy of every path (24 bytes plus the path itself),
+/// while `Hash64` keeps 8 bytes per path; this measures the insert cost of each.
+fn bench_dedup_strategy(c: &mut Criterion) {
+    let fixture = PathBuf::from("benches/fixtures/medium");
+
+    if !fixture.exists() {
+        eprintln!("Skipping dedup strategy benchmarks - fixtures not found");
+        return;
+    }
+
+    let paths: Vec<String> = WalkDir::new(&fixture)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path().to_string_lossy().into_owned())
+        .collect();
+
+    let mut group = c.benchmark_group("dedup_strategy");
+    group.throughput(Throughput::Elements(paths.len() as u64));
+
+    group.bench_function("full_string", |b| {
+        b.iter(|| {
+            let mut seen: AHashSet<String> = AHashSet::with_capacity(paths.len());
+            for path in black_box(&paths) {
+                seen.insert(path.clone());
+            }
+            black_box(seen.len())
+        })
+    });
+
+    group.bench_function("hash64", |b| {
+        b.iter(|| {
+            let state = ahash::RandomState::new();
+            let mut seen: AHashSet<u64> = AHashSet::with_capacity(paths.len());
+            for path in black_box(&paths) {
+                seen.insert(state.hash_one(path.as_str()));
+            }
+            black_box(seen.len())
+        })
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_walkdir_raw,
@@ -457,6 +503,7 @@ criterion_group!(
     bench_glob_simulation,
     bench_glob_simulation_medium,
     bench_result_collection,
+    bench_dedup_strategy,
 );
 
 criterion_main!(benches);
@@ -0,0 +1,82 @@
+//! Benchmark comparison of globlin's own walker strategies
+//!
+//! This benchmark evaluates the same patterns through:
+//! 1. serial - the default walkdir-based walker
+//! 2. parallel - `parallel: true` (jwalk)
+//! 3. native_io - `useNativeIo: true` (getdents64 on Linux)
+//! 4. gcd - `useGcd: true` (Grand Central Dispatch on macOS)
+//!
+//! Strategies a platform doesn't support fall back to the serial walker.
+//!
+//! Run with: cargo bench --bench walker_strategies
+//!
+//! The fixture tree is generated in a temporary directory, so no setup is needed.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use globlin::glob::Glob;
+use globlin::options::GlobOptions;
+use std::fs::{self, File};
+use tempfile::TempDir;
+
+/// 20 x 20 directories with 25 files each, half `.js` and half `.ts`
+fn create_fixture() -> TempDir {
+    let temp = TempDir::new().unwrap();
+    for a in 0..20 {
+        for b in 0..20 {
+            let dir = temp.path().join(format!("d{a}/e{b}"));
+            fs::create_dir_all(&dir).unwrap();
+            for f in 0..25 {
+                let ext = if f % 2 == 0 { "js" } else { "ts" };
+                File::create(dir.join(format!("f{f}.{ext}"))).unwrap();
+            }
+        }
+    }
+    temp
+}
+
+type Configure = fn(GlobOptions) -> GlobOptions;
+
+const STRATEGIES: [(&str, Configure); 4] = [
+    ("serial", |opts| opts),
+    ("parallel", |opts| GlobOptions {
+        parallel: Some(true),
+        ..opts
+    }),
+    ("native_io", |opts| GlobOptions {
+        use_native_io: Some(true),
+        ..opts
+    }),
+    ("gcd", |opts| GlobOptions {
+        use_gcd: Some(true),
+        ..opts
+    }),
+];
+
+/// Benchmark each strategy on each pattern
+fn bench_strategies(c: &mut Criterion) {
+    let fixture = create_fixture();
+    let cwd = fixture.path().to_string_lossy().to_string();
+
+    let mut group = c.benchmark_group("walker_strategies");
+    group.sample_size(20);
+
+    for pattern in ["**/*.js", "**", "d1/**/*.ts"] {
+        for (name, configure) in STRATEGIES {
+            let options = configure(GlobOptions {
+                cwd: Some(cwd.clone()),
+                ..Default::default()
+            });
+            group.bench_with_input(BenchmarkId::new(name, pattern), &options, |b, options| {
+                b.iter(|| {
+                    let glob = Glob::new(pattern.to_string(), options.clone());
+                    black_box(glob.walk_sync_timed())
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_strategies);
+criterion_main!(benches);
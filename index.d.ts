@@ -16,19 +16,355 @@ export interface PathData {
   isFile: boolean
   /** True if this is a symbolic link */
   isSymlink: boolean
+  /**
+   * Where the symlink points, as stored in the link (not resolved).
+   * Only set for symlinks when `readLinkTargets` is enabled.
+   */
+  linkTarget?: string
+  /**
+   * True if this directory has no entries. Only set for directories when
+   * `detectEmptyDirs` is enabled, and left unset if the directory can't be read.
+   */
+  isEmptyDir?: boolean
+}
+export declare function globSync(pattern: string | Array<string | PatternInput>, options?: GlobOptions | undefined | null): Array<string>
+/**
+ * Test whether a path matches the pattern(s) without walking the filesystem.
+ *
+ * The path is interpreted relative to the current directory (or as absolute), the
+ * same way glob results are. A trailing `/` marks the path as a directory.
+ *
+ * @param path - The candidate path
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Matching options
+ * @returns True if any pattern matches the path
+ */
+export declare function matchesPath(path: string, pattern: string | Array<string | PatternInput>, options?: MatchOptions | undefined | null): boolean
+/**
+ * Test a path against several patterns with AND semantics, without walking.
+ *
+ * The path must match every `include` pattern and no `exclude` pattern. Each
+ * pattern is matched exactly as `matches_path` would match it on its own, so an
+ * empty `include` list only checks the exclusions.
+ *
+ * @param path - The candidate path
+ * @param include - Patterns that must all match
+ * @param exclude - Patterns that must not match
+ * @param options - Matching options, applied to every pattern
+ * @returns True if all includes match and no exclude does
+ */
+export declare function matchesAll(path: string, include: Array<string>, exclude: Array<string>, options?: MatchOptions | undefined | null): boolean
+/**
+ * Explain why a path does or doesn't match the pattern(s).
+ *
+ * Takes the same arguments as `matches_path` and reports, per pattern in input
+ * order, whether it matched and whether a fast path or the regex decided, along
+ * with any dot rule or ignore pattern exclusion. Intended for debugging glob rules.
+ *
+ * @param path - The candidate path
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Matching options
+ * @returns A report describing the match decision
+ */
+export declare function explainMatch(path: string, pattern: string | Array<string | PatternInput>, options?: MatchOptions | undefined | null): MatchExplanation
+/**
+ * Test whether a directory could contain matches for the pattern(s).
+ *
+ * This is the check globlin's own walker uses to skip whole subtrees, exposed
+ * for callers that traverse the filesystem themselves: when it returns false,
+ * nothing at or below `dirPath` can match, so the directory need not be read.
+ * A true result only means a match is possible. Ignore patterns that exclude a
+ * directory's children (`dir/**`) also make it prunable.
+ *
+ * @param dirPath - The directory, relative to the current directory (`.` or `""` for cwd itself)
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Matching options
+ * @returns False if the directory can be pruned
+ */
+export declare function canContainMatch(dirPath: string, pattern: string | Array<string | PatternInput>, options?: MatchOptions | undefined | null): boolean
+/** Answer returned by `is_ignored`. */
+export interface IgnoreCheck {
+  /** True if the path itself would be left out of results */
+  ignored: boolean
+  /**
+   * True if everything below the path is ignored (a `dir/**` pattern), so a
+   * walk would not read the directory
+   */
+  childrenIgnored: boolean
+}
+/**
+ * Test whether a path would be excluded by ignore patterns, without walking.
+ *
+ * Uses the same ignore filter as the glob functions, so `defaultIgnore`,
+ * `rootRelative`, `noext` and `windowsPathsNoEscape` in `options` are honored.
+ * `ignore` takes the place of `options.ignore`. Intended for debugging ignore rules.
+ *
+ * @param path - The path, relative to `cwd` (`.` for cwd itself)
+ * @param ignore - Ignore patterns to test
+ * @param options - Glob options (`cwd` resolves absolute ignore patterns)
+ * @returns Whether the path and whether its children are ignored
+ */
+export declare function isIgnored(path: string, ignore: Array<string>, options?: GlobOptions | undefined | null): IgnoreCheck
+/** How a single pattern fared against a candidate path in `explain_match`. */
+export interface PatternExplanation {
+  /** The pattern after brace expansion */
+  pattern: string
+  /** True if the pattern matches the candidate (including the directory requirement) */
+  matched: boolean
+  /** The fast path that decided the result, or `None` if the regex was used */
+  fastPath?: string
+  /** The compiled regex for the whole pattern */
+  regex: string
+  /** True if the pattern ends in `/` and so only matches directories */
+  requiresDirectory: boolean
+}
+/** Diagnostic report returned by `explain_match`. */
+export interface MatchExplanation {
+  /** Whether the path matches, exactly as `matchesPath` would answer */
+  matched: boolean
+  /** The candidate that was tested, after `./`, trailing `/` and normalization handling */
+  candidate: string
+  /** True if the path was treated as a directory (it ended in `/`) */
+  isDirectory: boolean
+  /** True if a dot segment in the path is not allowed by any pattern */
+  excludedByDot: boolean
+  /** True if an ignore pattern matches the path */
+  ignored: boolean
+  /** One entry per pattern, in the order they are tried */
+  patterns: Array<PatternExplanation>
+  /** A short human-readable summary of the outcome */
+  reason: string
+}
+/** Matches and pattern warnings returned together by `glob_sync_checked`. */
+export interface CheckedGlobResult {
+  /** The matching paths, exactly as `globSync` would return them */
+  results: Array<string>
+  /** Warnings for the patterns (empty if no issues detected) */
+  warnings: Array<PatternWarningInfo>
+}
+/**
+ * Synchronous glob pattern matching that also reports pattern warnings.
+ * The patterns are analyzed once while the glob is constructed, so callers
+ * that want both matches and warnings don't need a separate `analyzePatterns` pass.
+ */
+export declare function globSyncChecked(pattern: string | Array<string | PatternInput>, options?: GlobOptions | undefined | null): CheckedGlobResult
+/** A result path with the input patterns that matched it, returned by `globSyncAnnotated`. */
+export interface AnnotatedPath {
+  /** The matching path, formatted as `globSync` would return it */
+  path: string
+  /** Indices into the input patterns of every pattern that matches the path */
+  matchedPatterns: Array<number>
+}
+/**
+ * Synchronous glob pattern matching that reports, for each result, every
+ * input pattern that matches it.
+ *
+ * Each path is checked against all patterns instead of stopping at the first
+ * match, so this is slower than `globSync`; use it for auditing overlaps.
+ *
+ * @param patterns - Glob patterns
+ * @param options - Glob options
+ * @returns The matching paths with the indices of the patterns that matched each
+ */
+export declare function globSyncAnnotated(patterns: Array<string | PatternInput>, options?: GlobOptions | undefined | null): Array<AnnotatedPath>
+/** A result path split into its parts, returned by `globSyncParsed`. */
+export interface ParsedPath {
+  /** Directory part, like `path.parse().dir` (empty for matches directly in cwd) */
+  dir: string
+  /** File name without its extension, like `path.parse().name` */
+  name: string
+  /** Extension including the leading dot, like `path.parse().ext` (empty if none) */
+  ext: string
+}
+/**
+ * Synchronous glob pattern matching that returns each result split into its
+ * directory, name and extension, saving a `path.parse()` per result in JavaScript.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options
+ * @returns The matching paths, each split like `path.parse` would
+ */
+export declare function globSyncParsed(pattern: string | Array<string | PatternInput>, options?: GlobOptions | undefined | null): Array<ParsedPath>
+/** A result path with its position in discovery order, returned by `globSyncIndexed`. */
+export interface IndexedPath {
+  /** The matching path, formatted as `globSync` would return it */
+  path: string
+  /** Zero-based position of the path in the order the walk produced it */
+  index: number
+}
+/**
+ * Synchronous glob pattern matching that numbers each result in the order
+ * the walk discovered it.
+ *
+ * Results are returned in walk order, so `index` is contiguous from 0. Use
+ * it to recover the original order after sorting or grouping the results.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options
+ * @returns The matching paths with their discovery index
+ */
+export declare function globSyncIndexed(pattern: string | Array<string | PatternInput>, options?: GlobOptions | undefined | null): Array<IndexedPath>
+/** A result path with its depth below cwd, returned by `globSyncDepths`. */
+export interface PathDepth {
+  /** The matching path, formatted as `globSync` would return it */
+  path: string
+  /** Number of path segments below cwd (0 for cwd itself, 1 for entries directly in cwd) */
+  depth: number
+}
+/**
+ * Synchronous glob pattern matching that reports how deep below cwd each
+ * result lies.
+ *
+ * Depth counts the path segments below cwd: cwd itself (`.`) is 0, `foo.txt`
+ * is 1 and `src/main.js` is 2. It is taken from the walked path before it is
+ * formatted, so `absolute`, `mark`, `dotRelative` and `resultPrefix` don't
+ * change it.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options
+ * @returns The matching paths with their depth below cwd
+ */
+export declare function globSyncDepths(pattern: string | Array<string | PatternInput>, options?: GlobOptions | undefined | null): Array<PathDepth>
+/** Totals for the matches of a glob, returned by `globAggregate`. */
+export interface GlobAggregate {
+  /** Number of matching paths */
+  count: number
+  /** Sum of the sizes of the matching regular files, in bytes */
+  totalSize: number
+}
+/**
+ * Count the matches and sum the sizes of matching files in a single walk.
+ *
+ * Paths are never collected or sent to JavaScript, which makes this much
+ * cheaper than `globSync` followed by a `stat` per result for large trees.
+ * Options like `nodir` and `ignore` apply as usual.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options
+ * @returns The number of matches and their total size in bytes
+ */
+export declare function globAggregate(pattern: string | Array<string | PatternInput>, options?: GlobOptions | undefined | null): GlobAggregate
+/** The number of matches in one directory, returned by `globHistogram`. */
+export interface DirCount {
+  /**
+   * Directory containing the matches, relative to cwd and cut to the
+   * requested depth (`.` for matches directly in cwd)
+   */
+  dir: string
+  /** Number of matches in that directory or below it */
+  count: number
+}
+/**
+ * Count matches per directory in a single walk.
+ *
+ * Each match is counted against the directory that contains it, cut to its
+ * first `depth` segments, so `depth: 1` gives per-top-level-directory counts.
+ * Matches directly in cwd, and matches shallower than `depth`, are counted under
+ * their own parent. Paths are never sent to JavaScript.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options
+ * @param depth - Number of leading directory segments to group by
+ * @returns One entry per directory with matches, sorted by directory
+ */
+export declare function globHistogram(pattern: string | Array<string | PatternInput>, options: GlobOptions | undefined | null, depth: number): Array<DirCount>
+/** How a glob would be executed, returned by `globPlan`. */
+export interface GlobPlan {
+  /** `Static`, `Shallow`, `MultiBase`, `PrefixScoped` or `FullWalk` */
+  strategy: string
+  /** Directory the walk starts from (cwd unless the strategy is `PrefixScoped`) */
+  walkRoot: string
+  /** Literal prefix shared by all patterns, for `PrefixScoped` */
+  prefix?: string
+}
+/**
+ * Report which optimization path `globSync` would take, without walking.
+ *
+ * `Static` stats each literal path, `Shallow` reads cwd once, `MultiBase` walks
+ * each distinct literal base directory separately, `PrefixScoped` walks once from
+ * the shared literal prefix and `FullWalk` walks once from cwd. Intended for
+ * checking that patterns hit the fast paths.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options
+ * @returns The strategy, walk root and shared prefix
+ */
+export declare function globPlan(pattern: string | Array<string | PatternInput>, options?: GlobOptions | undefined | null): GlobPlan
+/** One page of results, returned by `globPage`. */
+export interface GlobPage {
+  /** Matching paths, in walk order */
+  results: Array<string>
+  /** Pass to the next `globPage` call to continue; unset on the last page */
+  nextCursor?: string
 }
-export declare function globSync(pattern: string | Array<string>, options?: GlobOptions | undefined | null): Array<string>
-export declare function glob(pattern: string | Array<string>, options?: GlobOptions | undefined | null): Promise<Array<string>>
+/**
+ * Return one page of results, resuming after `cursor`.
+ *
+ * Directory entries are read in sorted order on a single thread, so the walk
+ * order is deterministic and a page can pick up where the previous one stopped
+ * without keeping a walker alive in between. Subtrees that lie entirely before
+ * the cursor are not walked again.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options (`includeChildMatches: false` is not supported)
+ * @param cursor - `nextCursor` from the previous page, or unset for the first page
+ * @param pageSize - Maximum number of results in the page
+ * @returns The page's results and the cursor for the next page
+ */
+export declare function globPage(pattern: string | Array<string | PatternInput>, options: GlobOptions | undefined | null, cursor: string | undefined | null, pageSize: number): GlobPage
+/**
+ * Return the first `count` matches in lexical order.
+ *
+ * Any path could sort first, so the whole tree is still walked, but only the
+ * `count` smallest results seen so far are kept: memory is bounded by `count`
+ * rather than by the number of matches, and the answer doesn't depend on the
+ * walk order. Results are compared by Unicode code point.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options
+ * @param count - Maximum number of results to return
+ * @returns Up to `count` matching paths, sorted
+ */
+export declare function globSyncFirst(pattern: string | Array<string | PatternInput>, options: GlobOptions | undefined | null, count: number): Array<string>
+/**
+ * Synchronous glob pattern matching with results grouped by input pattern.
+ *
+ * The filesystem is walked once. The returned array has one entry per input
+ * pattern, in input order. A path matching several patterns appears in each of
+ * their groups, or only in the first one with `firstMatchOnly`.
+ *
+ * @param patterns - Glob patterns
+ * @param options - Glob options
+ * @param firstMatchOnly - Put each path only in the group of the first matching pattern
+ * @returns One result array per input pattern
+ */
+/**
+ * Run the same glob in several directories and return one result array per
+ * directory, in the order of `cwds`.
+ *
+ * Patterns and options are validated and compiled once, then re-rooted at each
+ * directory, so this avoids the per-call setup of calling `globSync` in a loop.
+ * With `parallel: true` the directories are spread over the rayon thread pool
+ * and each one is walked serially.
+ *
+ * @param cwds - Directories to search, overriding `options.cwd`
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options
+ * @returns The matching paths for each directory, relative to that directory
+ */
+export declare function globBatch(cwds: Array<string>, pattern: string | Array<string | PatternInput>, options?: GlobOptions | undefined | null): Array<Array<string>>
+export declare function globSyncGrouped(patterns: Array<string | PatternInput>, options?: GlobOptions | undefined | null, firstMatchOnly?: boolean | undefined | null): Array<Array<string>>
+export declare function glob(pattern: string | Array<string | PatternInput>, options?: GlobOptions | undefined | null): Promise<Array<string>>
 /**
  * Synchronous glob pattern matching with file type information.
  * Returns PathData objects instead of strings.
  */
-export declare function globSyncWithFileTypes(pattern: string | Array<string>, options?: GlobOptions | undefined | null): Array<PathData>
+export declare function globSyncWithFileTypes(pattern: string | Array<string | PatternInput>, options?: GlobOptions | undefined | null): Array<PathData>
 /**
  * Asynchronous glob pattern matching with file type information.
  * Returns PathData objects instead of strings.
  */
-export declare function globWithFileTypes(pattern: string | Array<string>, options?: GlobOptions | undefined | null): Promise<Array<PathData>>
+export declare function globWithFileTypes(pattern: string | Array<string | PatternInput>, options?: GlobOptions | undefined | null): Promise<Array<PathData>>
 /**
  * Streaming glob pattern matching.
  * Streams results back to JavaScript via a callback function.
@@ -39,7 +375,23 @@ export declare function globWithFileTypes(pattern: string | Array<string>, optio
  * @param callback - Function called with each result string
  * @returns Promise that resolves when all results have been streamed
  */
-export declare function globStream(pattern: string | Array<string>, options: GlobOptions | undefined | null, callback: (result: string) => void): void
+export declare function globStream(pattern: string | Array<string | PatternInput>, options: GlobOptions | undefined | null, callback: (result: string) => void): void
+/**
+ * Streaming glob pattern matching that delivers results in batches.
+ * The callback receives up to `batchSize` results per call, so a large result
+ * set crosses into JavaScript once per batch instead of once per path.
+ *
+ * The walk runs off the JavaScript thread, so batches arrive while it is still
+ * in progress.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options
+ * @param batchSize - Number of results per batch (the last batch may be smaller)
+ * @param callback - Function called with each batch of result strings
+ * @returns The number of batches the callback will receive; some may still be
+ *   on their way when it resolves
+ */
+export declare function globStreamBatched(pattern: string | Array<string | PatternInput>, options: GlobOptions | undefined | null, batchSize: number, callback: (results: string[]) => void): Promise<number>
 /**
  * Streaming glob pattern matching with file type information.
  * Streams PathData results back to JavaScript via a callback function.
@@ -49,7 +401,44 @@ export declare function globStream(pattern: string | Array<string>, options: Glo
  * @param callback - Function called with each PathData result
  * @returns Promise that resolves when all results have been streamed
  */
-export declare function globStreamWithFileTypes(pattern: string | Array<string>, options: GlobOptions | undefined | null, callback: (result: { path: string, isDirectory: boolean, isFile: boolean, isSymlink: boolean }) => void): void
+export declare function globStreamWithFileTypes(pattern: string | Array<string | PatternInput>, options: GlobOptions | undefined | null, callback: (result: { path: string, isDirectory: boolean, isFile: boolean, isSymlink: boolean, linkTarget?: string, isEmptyDir?: boolean }) => void): void
+/**
+ * Streaming glob pattern matching with file type information, as a pull-based
+ * iterator with backpressure.
+ *
+ * Unlike `glob_stream_with_file_types`, results are not pushed to JavaScript:
+ * the walk buffers at most `buffer_size` results and then waits for the
+ * consumer to call `nextBatch()`.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options
+ * @param buffer_size - Results to buffer ahead of the consumer (default 256)
+ * @returns An iterator whose `nextBatch()` resolves with PathData results
+ */
+export declare function globIterateWithFileTypes(pattern: string | Array<string | PatternInput>, options?: GlobOptions | undefined | null, bufferSize?: number | undefined | null): GlobFileTypesIterator
+/** Strategy used to drop duplicate results during a walk. */
+export const enum DedupStrategy {
+  /** Store each result path in full. */
+  FullString = 'FullString',
+  /** Store only a 64-bit hash of each result path. */
+  Hash64 = 'Hash64'
+}
+/** Where a directory comes in a walk relative to the entries inside it. */
+export const enum DirOrder {
+  /** A directory comes before its contents. */
+  PreOrder = 'PreOrder',
+  /** A directory comes after its contents. */
+  PostOrder = 'PostOrder'
+}
+/** What makes a file or directory hidden when `dot` is false. */
+export const enum HiddenMode {
+  /** Names starting with `.` are hidden. */
+  DotfilesOnly = 'DotfilesOnly',
+  /** Entries with the Windows hidden attribute are hidden. */
+  AttributeOnly = 'AttributeOnly',
+  /** Both dotfiles and entries with the hidden attribute are hidden. */
+  Both = 'Both'
+}
 /**
  * Complete GlobOptions struct with all glob v13 options.
  *
@@ -80,7 +469,10 @@ export interface GlobOptions {
    * Note that an explicit dot in a portion of the pattern will always match dot files.
    */
   dot?: boolean
-  /** Do not expand `{a,b}` and `{1..3}` brace sets. */
+  /**
+   * Do not expand `{a,b}` and `{1..3}` brace sets.
+   * A brace range is then matched literally; only `globSyncChecked` warns about it.
+   */
   nobrace?: boolean
   /**
    * Do not match `**` against multiple filenames.
@@ -104,7 +496,7 @@ export interface GlobOptions {
    * Treat brace expansion like `{a,b}` as a "magic" pattern.
    * Has no effect if `nobrace` is set.
    *
-   * Only affects the `hasMagic` function.
+   * Only affects the `hasMagic` and `escape` functions; `escape` then escapes braces too.
    */
   magicalBraces?: boolean
   /**
@@ -117,6 +509,247 @@ export interface GlobOptions {
    * pattern, following the same behavior as Bash.
    */
   follow?: boolean
+  /**
+   * Sort the entries of each directory by name before matching them.
+   *
+   * Results (and streamed results) come out in `ls`-like name order within
+   * every directory, without waiting for the walk to finish and sorting the
+   * whole result set. The order across directories still depends on the walker.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  sortDirEntries?: boolean
+  /**
+   * Whether a matched directory comes before (`PreOrder`) or after
+   * (`PostOrder`) the matches inside it.
+   *
+   * `PostOrder` suits recursive deletion: removing results in order empties
+   * each directory before it is removed. Walks then always read depth-first,
+   * so `useNativeIO` and `useGcd` are ignored, and literal patterns are walked
+   * instead of stat'ed. `globPage` always uses `PreOrder`. Default: `PreOrder`.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  dirOrder?: DirOrder
+  /**
+   * Keep backslashes in emitted paths instead of converting them to `/`.
+   *
+   * On Linux and macOS a filename may legitimately contain `\`; by default it
+   * is treated as a separator and the name is corrupted in the results. With
+   * this option, `\` in filesystem paths is an ordinary character and
+   * patterns match it literally (use `\\` or `*` to match it). Ignored on
+   * Windows, where `\` is always a separator.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  preserveBackslashes?: boolean
+  /**
+   * Maximum number of patterns after brace expansion, summed over all input
+   * patterns and `ignore` patterns (including `defaultIgnore`). Defaults to
+   * `100000`.
+   *
+   * A pattern like `{a,b}{c,d}{e,f}...` grows exponentially; once the total would
+   * exceed this limit the call fails with an error naming the offending pattern
+   * instead of expanding without bound.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  maxPatterns?: number
+  /**
+   * Keep the glob inside `cwd`. Patterns that would reach above it (such as
+   * `../secrets/*` or an absolute path elsewhere) are rejected with an error,
+   * and results that resolve outside `cwd` through a symlink are dropped.
+   *
+   * Use this when patterns come from untrusted input.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  restrictToCwd?: boolean
+  /**
+   * Also ignore the built-in list of heavy directories (`**/node_modules/**`
+   * and `**/.git/**`), in addition to any `ignore` patterns. Ignored
+   * directories are not traversed.
+   *
+   * The list is available from `defaultIgnorePatterns()`.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  defaultIgnore?: boolean
+  /**
+   * With `withFileTypes`, read where each symlink points and report it as
+   * `linkTarget`. The target is returned as stored in the link, without
+   * resolving it. Only symlinks cost an extra `readlink` call.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  readLinkTargets?: boolean
+  /**
+   * With `withFileTypes`, report whether each directory is empty as
+   * `isEmptyDir`. Only directories cost an extra `readdir` call.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  detectEmptyDirs?: boolean
+  /**
+   * Treat a leading `/` as anchoring the pattern at `cwd`, like in `.gitignore`,
+   * instead of the filesystem root. `/src/*.ts` then matches `src/a.ts` but not
+   * `lib/src/a.ts`. Applies to `ignore` patterns too.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  rootRelative?: boolean
+  /**
+   * Keep patterns in input order instead of trying the ones with cheap
+   * matching first. Results are the same; this only matters for debugging or
+   * when relying on which pattern is tried first.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  preservePatternOrder?: boolean
+  /**
+   * Treat `!(...)` as literal text while still expanding the other extglobs
+   * (`+(...)`, `@(...)`, `*(...)`, `?(...)`). Finer-grained than `noext`.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  noNegation?: boolean
+  /**
+   * Match every pattern against only the final segment of each path, even
+   * patterns containing `/`: `src/*.ts` then matches any `*.ts` file at any
+   * depth. Stronger than `matchBase`, which leaves patterns with `/` anchored.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  basenameOnly?: boolean
+  /**
+   * What counts as hidden for `dot: false`.
+   *
+   * - `DotfilesOnly` (default): names starting with `.`, as in glob.
+   * - `AttributeOnly`: entries with the Windows hidden attribute
+   *   (`FILE_ATTRIBUTE_HIDDEN`); dotfiles are then matched like other files.
+   * - `Both`: either of the above.
+   *
+   * Attribute checks cost one metadata call per entry and skip the contents of
+   * hidden directories. Nothing has the attribute on other platforms.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  hidden?: HiddenMode
+  /**
+   * Drop duplicate results (default `true`). With `false`, a path is returned
+   * once for every input pattern it matches, e.g. for per-pattern accounting;
+   * each copy directly follows the first. Only `globSync`, `glob` and
+   * `globSyncChecked` repeat paths; other functions always deduplicate.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  unique?: boolean
+  /**
+   * Apply the patterns in order, like tsconfig `include`/`exclude` lists: a
+   * pattern starting with `!` removes the paths matched so far, and a later
+   * pattern can add them back. The last pattern matching a path decides.
+   * Without this option, a leading `!` is a literal character.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  sequential?: boolean
+  /**
+   * When patterns have several distinct bases (e.g. `src/**/*.js` and
+   * `test/**/*.js`), stream each base's results as soon as its walk finds them
+   * instead of after every base has been walked. Results from different bases
+   * interleave in no fixed order. Only affects `globStreamBatched`, the one
+   * function that walks off the JavaScript thread and delivers results while
+   * the walk is still running.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  streamPerBase?: boolean
+  /**
+   * String prepended to every returned path, after all other formatting
+   * (`absolute`, `mark`, `posix`, `dotRelative`) has been applied.
+   *
+   * Useful for feeding results straight into tools that expect a fixed
+   * prefix, such as a URL base or a remote host. The prefix is not applied
+   * to `Path` objects returned with `withFileTypes: true`.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  resultPrefix?: string
+  /**
+   * Only remember the results of directories the walk is still inside, so
+   * deduplication memory stays bounded instead of growing with every result.
+   * Takes precedence over `dedupStrategy`.
+   *
+   * Duplicates from separate walks that reach the same path, or from a
+   * parallel walk, may not be removed; unique paths are never dropped.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  scopedDedup?: boolean
+  /**
+   * Return no results instead of throwing when a directory the walk starts
+   * from (the cwd, or a pattern's literal prefix such as `/srv/data`)
+   * exists but can't be read, e.g. because of permissions.
+   *
+   * By default such a root is an error, so "permission denied" can be told
+   * apart from "nothing matched". Missing roots are never an error, and
+   * unreadable directories below the root are always skipped. Streams report
+   * the error once the walk has finished.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  suppressErrors?: boolean
+  /**
+   * Don't descend into directories on a different filesystem than the walk
+   * root, like `find -xdev`. Keeps recursive patterns out of network mounts
+   * or `/proc`. Such directories are skipped together with their contents.
+   *
+   * Devices are compared by `st_dev` on Unix; on Windows this has no effect.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  oneFileSystem?: boolean
+  /**
+   * Maximum number of directory handles the walk keeps open at once.
+   * Lower it on systems with a small `ulimit -n`, where deep or `parallel`
+   * walks could otherwise fail with "too many open files".
+   *
+   * The cap covers the whole glob, including the concurrent walks of a
+   * multi-base pattern set. With `parallel`, this also caps the number of
+   * walker threads.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  maxOpenDirs?: number
+  /**
+   * Depth past which the walk never descends, whatever `maxDepth` allows
+   * (default 4096). Guards against pathologically deep trees and followed
+   * symlink loops. Reaching it is an error, since the results are incomplete;
+   * with `suppressErrors` the results gathered so far are returned instead.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  hardDepthLimit?: number
+  /**
+   * Handle patterns like lines of a `.gitignore` file: leading and trailing
+   * whitespace is trimmed, and blank patterns and `#` comments are skipped.
+   * A trailing space escaped with a backslash (`foo\ `) is kept.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  trimPatterns?: boolean
+  /**
+   * Turn off every matching and walking shortcut: pattern fast paths, the
+   * static, shallow and multi-base strategies, pattern-derived depth limits
+   * and directory pruning. Each glob then walks the tree and tests every
+   * path against the full pattern regex.
+   *
+   * Results should be identical either way, so this is a debugging aid for
+   * telling a fast-path bug from a pattern bug. It is much slower.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  disableOptimizations?: boolean
   /**
    * Limit the directory traversal to a given depth below the cwd.
    *
@@ -170,6 +803,7 @@ export interface GlobOptions {
   /**
    * Do not match directories, only files.
    * (Note: to match _only_ directories, put a `/` at the end of the pattern.)
+   * Such a pattern matches nothing with `nodir`; only `globSyncChecked` warns about it.
    */
   nodir?: boolean
   /**
@@ -207,6 +841,28 @@ export interface GlobOptions {
    * This incurs a slight performance penalty due to the added system calls.
    */
   realpath?: boolean
+  /**
+   * With `realpath`, keep broken symlinks in the results under their own
+   * path instead of dropping them. Entries that fail to resolve for any
+   * other reason (such as a symlink loop) are still omitted.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  realpathKeepBroken?: boolean
+  /**
+   * Report each file reachable through several paths only once, under its
+   * real path.
+   *
+   * With `follow`, a file under a symlinked directory is found both directly
+   * and through the link. This drops the paths that go through a symlink when
+   * the real path is also among the results. Unlike `realpath`, the results
+   * are not rewritten: a file only reachable through a symlink keeps its path.
+   * It applies to every API, `withFileTypes` and the counting ones included;
+   * streaming APIs buffer the whole walk first.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  canonicalOnly?: boolean
   /**
    * Patterns to exclude from matching.
    * Can be a single pattern string or an array of patterns.
@@ -219,8 +875,21 @@ export interface GlobOptions {
    * of any other settings.
    *
    * Patterns ending in `/**` will ignore the directory and all its children.
+   *
+   * A leading `\!` matches a literal `!`, e.g. `\!important.txt`, and keeps
+   * `\!(draft).md` from being read as an extglob. With `windowsPathsNoEscape`
+   * the `\` is a path separator instead.
    */
   ignore?: string | Array<string>
+  /**
+   * Precompiled ignore patterns, used in place of `ignore`. Build an
+   * `IgnoreSet` once and pass it to every call that shares the same ignore
+   * list, so the patterns aren't recompiled each time. Can't be combined
+   * with `ignore` or `defaultIgnore`; give those to the `IgnoreSet` instead.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  ignoreSet?: IgnoreSet
   /**
    * Do not match any children of any matches.
    *
@@ -336,6 +1005,113 @@ export interface GlobOptions {
    * **Note:** This is a globlin-specific option not present in the original glob package.
    */
   useGcd?: boolean
+  /**
+   * How results are deduplicated while walking.
+   *
+   * - `FullString` (default): remembers every result path. Exact, but memory
+   *   grows with the total length of all matched paths.
+   * - `Hash64`: remembers only a 64-bit hash of each path, using a fixed 8 bytes
+   *   per result regardless of path length.
+   *
+   * With `Hash64`, two different paths hashing to the same value would cause the
+   * second one to be dropped. The chance is roughly `n² / 2⁶⁵` for `n` results
+   * (about 1 in 37 million for 1 million results), so only use it for very
+   * large result sets where the memory saving matters.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   */
+  dedupStrategy?: DedupStrategy
+}
+/**
+ * Options that a single pattern may override on top of the call-wide `GlobOptions`.
+ *
+ * Unset fields inherit the call-wide value.
+ */
+export interface PatternOverrides {
+  /** Perform a case-insensitive match for this pattern only. */
+  nocase?: boolean
+  /** Include `.dot` files in matches for this pattern only. */
+  dot?: boolean
+  /** Do not expand extglob patterns such as `+(a|b)` for this pattern only. */
+  noext?: boolean
+}
+/** A pattern together with its per-pattern option overrides. */
+export interface PatternInput {
+  /** The glob pattern. */
+  pattern: string
+  /** Options that apply to this pattern only. */
+  options?: PatternOverrides
+}
+/**
+ * Options for matching candidate paths against patterns without walking the
+ * filesystem (see `matchesPath`).
+ *
+ * The pattern options mean the same as in `GlobOptions`.
+ */
+export interface MatchOptions {
+  /** Include `.dot` files in normal matches and `globstar` matches. */
+  dot?: boolean
+  /** Do not expand `{a,b}` and `{1..3}` brace sets. */
+  nobrace?: boolean
+  /** Do not match `**` against multiple filenames. */
+  noglobstar?: boolean
+  /** Do not match "extglob" patterns such as `+(a|b)`. */
+  noext?: boolean
+  /**
+   * Perform a case-insensitive match.
+   *
+   * Defaults to `true` on macOS and Windows systems, and `false` on all others.
+   */
+  nocase?: boolean
+  /** Match patterns without slashes against the basename of the path. */
+  matchBase?: boolean
+  /** Use `\\` as a path separator _only_, and _never_ as an escape character. */
+  windowsPathsNoEscape?: boolean
+  /** Platform to use for path handling. Defaults to the current platform. */
+  platform?: string
+  /**
+   * Lexically normalize the candidate path before matching: `.` segments
+   * are dropped and `..` segments remove the preceding segment, so
+   * `src/./lib/../main.ts` is matched as `src/main.ts`.
+   *
+   * No filesystem access is involved, so symlinks are not resolved.
+   */
+  normalizeCandidate?: boolean
+  /**
+   * Keep backslashes in the candidate path instead of treating them as `/`.
+   *
+   * Same semantics as the `preserveBackslashes` glob option: by default a
+   * candidate like `src\\main.ts` (from a Windows API) matches `src/*.ts`.
+   * Ignored on Windows, where `\\` is always a separator.
+   */
+  preserveBackslashes?: boolean
+  /**
+   * The character that delimits segments, for matching keys that aren't
+   * filesystem paths (`a.b.c`, `ns:key`). Defaults to `/`.
+   *
+   * `*`, `?` and character classes never match the separator and `**`
+   * spans any number of segments, exactly as with `/`. Keys are taken as
+   * given: `/` and `\\` are ordinary characters and `./` isn't stripped.
+   * Must be a single character that has no glob meaning of its own.
+   */
+  separator?: string
+  /**
+   * Glob pattern or array of glob patterns; a candidate matching any of
+   * them never matches. Same semantics as the `ignore` glob option.
+   */
+  ignore?: string | Array<string>
+  /**
+   * Directory that absolute candidate paths are made relative to before
+   * matching, so `/project/src/a.ts` with base `/project` is matched as
+   * `src/a.ts`. Relative candidates are matched as given, and an absolute
+   * candidate outside the base never matches.
+   */
+  base?: string
+  /**
+   * Report an absolute candidate outside `base` as an error instead of a
+   * non-match.
+   */
+  strictBase?: boolean
 }
 /**
  * Escape magic glob characters in a pattern.
@@ -343,9 +1119,10 @@ export interface GlobOptions {
  *
  * @param pattern - The glob pattern to escape
  * @param windowsPathsNoEscape - If true, use `[x]` wrapping instead of backslash escapes
+ * @param magicalBraces - If true, escape `{` and `}` too so the result is never brace-expanded
  * @returns The escaped pattern
  */
-export declare function escape(pattern: string, windowsPathsNoEscape?: boolean | undefined | null): string
+export declare function escape(pattern: string, windowsPathsNoEscape?: boolean | undefined | null, magicalBraces?: boolean | undefined | null): string
 /**
  * Unescape magic glob characters in a pattern.
  * This reverses the effect of `escape()`.
@@ -355,6 +1132,35 @@ export declare function escape(pattern: string, windowsPathsNoEscape?: boolean |
  * @returns The unescaped pattern
  */
 export declare function unescape(pattern: string, windowsPathsNoEscape?: boolean | undefined | null): string
+/**
+ * Escape magic glob characters in each of several patterns.
+ * Equivalent to calling `escape()` on each, in a single call.
+ *
+ * @param patterns - The glob patterns to escape
+ * @param windowsPathsNoEscape - If true, use `[x]` wrapping instead of backslash escapes
+ * @param magicalBraces - If true, escape `{` and `}` too so the results are never brace-expanded
+ * @returns The escaped patterns, in the same order
+ */
+export declare function escapeAll(patterns: Array<string>, windowsPathsNoEscape?: boolean | undefined | null, magicalBraces?: boolean | undefined | null): Array<string>
+/**
+ * Unescape magic glob characters in each of several patterns.
+ * Equivalent to calling `unescape()` on each, in a single call.
+ *
+ * @param patterns - The escaped patterns to unescape
+ * @param windowsPathsNoEscape - If true, remove `[x]` wrapping instead of backslash escapes
+ * @returns The unescaped patterns, in the same order
+ */
+export declare function unescapeAll(patterns: Array<string>, windowsPathsNoEscape?: boolean | undefined | null): Array<string>
+/**
+ * Expand a pattern into the concrete patterns its braces stand for, the same
+ * way a glob does before matching: `{a,b}/*.{js,ts}` gives four patterns.
+ * With `nobrace` the pattern is returned unchanged.
+ *
+ * @param pattern - The glob pattern to expand
+ * @param options - Glob options (`nobrace` and `maxPatterns` apply)
+ * @returns The expanded patterns, in expansion order
+ */
+export declare function expandPattern(pattern: string, options?: GlobOptions | undefined | null): Array<string>
 /**
  * Check if a pattern contains any magic glob characters.
  * Takes into account escaped characters.
@@ -364,6 +1170,13 @@ export declare function unescape(pattern: string, windowsPathsNoEscape?: boolean
  * @returns True if the pattern has magic (unescaped) glob characters
  */
 export declare function hasMagic(pattern: string, noext?: boolean | undefined | null, windowsPathsNoEscape?: boolean | undefined | null): boolean
+/**
+ * Get the built-in ignore patterns used by the `defaultIgnore` option.
+ * Useful for inspecting the list or extending it in a custom `ignore` array.
+ *
+ * @returns The default ignore patterns
+ */
+export declare function defaultIgnorePatterns(): Array<string>
 /**
  * A pattern warning with message and optional suggestion.
  * Used for providing helpful feedback about potential pattern issues.
@@ -397,3 +1210,91 @@ export declare function analyzePattern(pattern: string, windowsPathsNoEscape?: b
  * @returns Array of warnings for all patterns (empty if no issues detected)
  */
 export declare function analyzePatterns(patterns: Array<string>, windowsPathsNoEscape?: boolean | undefined | null, platform?: string | undefined | null): Array<PatternWarningInfo>
+/** Warnings of one kind collected across patterns, returned by `analyzePatternsGrouped`. */
+export interface GroupedPatternWarning {
+  /** The type of warning shared by the group (e.g., "performance", "empty_pattern") */
+  warningType: string
+  /** Human-readable message describing the group's problem, without naming a pattern */
+  message: string
+  /** Number of warnings collapsed into this entry */
+  count: number
+  /**
+   * The pattern behind each warning in the group, in input order (`""` for
+   * empty patterns), so there are always `count` of them
+   */
+  patterns: Array<string>
+}
+/**
+ * Analyze multiple patterns and collapse warnings of the same type and reason
+ * into one entry with a count, so a repeated mistake is reported once.
+ *
+ * @param patterns - Array of glob patterns to analyze
+ * @param windowsPathsNoEscape - Whether backslashes are path separators (Windows mode)
+ * @param platform - The target platform ("win32", "darwin", "linux")
+ * @returns One entry per warning type and reason, in order of first occurrence
+ */
+export declare function analyzePatternsGrouped(patterns: Array<string>, windowsPathsNoEscape?: boolean | undefined | null, platform?: string | undefined | null): Array<GroupedPatternWarning>
+/**
+ * Set how many compiled patterns are cached between glob calls.
+ *
+ * The cache evicts the least recently used pattern once full. Lowering the
+ * capacity evicts immediately; long-running processes that see many unique
+ * patterns can use it to bound memory. The default is 1024.
+ *
+ * @param capacity - Maximum number of cached patterns (at least 1)
+ */
+export declare function setPatternCacheCapacity(capacity: number): void
+/**
+ * Get the size, capacity and hit/miss counts of the compiled pattern cache.
+ *
+ * @returns Statistics for the pattern cache
+ */
+export declare function patternCacheStats(): PatternCacheStats
+/** Pattern cache statistics, returned by `patternCacheStats`. */
+export interface PatternCacheStats {
+  /** Number of compiled patterns currently cached */
+  size: number
+  /** Maximum number of compiled patterns kept */
+  capacity: number
+  /** Lookups answered from the cache */
+  hits: number
+  /** Lookups that had to compile the pattern */
+  misses: number
+}
+/**
+ * Pull-based iterator over typed glob results, created by `glob_iterate_with_file_types`.
+ *
+ * The walk runs on a background thread and pauses whenever the buffer is full,
+ * so results are produced only as fast as they are consumed.
+ */
+export declare class GlobFileTypesIterator {
+  /**
+   * Wait for the next results and return up to `max` of them (default: all
+   * that are buffered). An empty array means the walk has finished; it is
+   * an error instead if the walk stopped at the hard depth limit.
+   */
+  nextBatch(max?: number | undefined | null): Promise<Array<PathData>>
+  /**
+   * Stop the walk early. Results already buffered are discarded.
+   *
+   * Waits for an in-flight `nextBatch()` to return rather than skipping the
+   * close, so the walk is always told to stop.
+   */
+  close(): Promise<void>
+}
+/**
+ * Ignore patterns compiled once and shared by every glob given them as the
+ * `ignoreSet` option, so a long ignore list isn't recompiled on each call.
+ */
+export declare class IgnoreSet {
+  /**
+   * Compile `patterns` as the `ignore` option would be compiled.
+   *
+   * `defaultIgnore`, `rootRelative`, `noext` and `windowsPathsNoEscape` in
+   * `options` are honored; `options.ignore` is replaced by `patterns`.
+   *
+   * @param patterns - Ignore patterns
+   * @param options - Glob options the patterns are compiled with
+   */
+  constructor(patterns: Array<string>, options?: GlobOptions | undefined | null)
+}
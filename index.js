@@ -310,16 +310,44 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { globSync, glob, globSyncWithFileTypes, globWithFileTypes, globStream, globStreamWithFileTypes, escape, unescape, hasMagic, analyzePattern, analyzePatterns } = nativeBinding
+const { globSync, matchesPath, matchesAll, explainMatch, canContainMatch, isIgnored, globSyncChecked, globSyncGrouped, globAggregate, globHistogram, globPlan, globPage, globSyncFirst, globSyncAnnotated, globSyncParsed, globSyncIndexed, globSyncDepths, globBatch, glob, globSyncWithFileTypes, globWithFileTypes, globStream, globStreamBatched, globStreamWithFileTypes, globIterateWithFileTypes, GlobFileTypesIterator, IgnoreSet, escape, unescape, escapeAll, unescapeAll, expandPattern, hasMagic, defaultIgnorePatterns, analyzePattern, analyzePatterns, analyzePatternsGrouped, setPatternCacheCapacity, patternCacheStats } = nativeBinding
 
 module.exports.globSync = globSync
+module.exports.matchesPath = matchesPath
+module.exports.matchesAll = matchesAll
+module.exports.explainMatch = explainMatch
+module.exports.canContainMatch = canContainMatch
+module.exports.isIgnored = isIgnored
+module.exports.globSyncChecked = globSyncChecked
+module.exports.globSyncGrouped = globSyncGrouped
+module.exports.globAggregate = globAggregate
+module.exports.globHistogram = globHistogram
+module.exports.globPlan = globPlan
+module.exports.globPage = globPage
+module.exports.globSyncFirst = globSyncFirst
+module.exports.globSyncAnnotated = globSyncAnnotated
+module.exports.globSyncParsed = globSyncParsed
+module.exports.globSyncIndexed = globSyncIndexed
+module.exports.globSyncDepths = globSyncDepths
+module.exports.globBatch = globBatch
 module.exports.glob = glob
 module.exports.globSyncWithFileTypes = globSyncWithFileTypes
 module.exports.globWithFileTypes = globWithFileTypes
 module.exports.globStream = globStream
+module.exports.globStreamBatched = globStreamBatched
 module.exports.globStreamWithFileTypes = globStreamWithFileTypes
+module.exports.globIterateWithFileTypes = globIterateWithFileTypes
+module.exports.GlobFileTypesIterator = GlobFileTypesIterator
+module.exports.IgnoreSet = IgnoreSet
 module.exports.escape = escape
 module.exports.unescape = unescape
+module.exports.escapeAll = escapeAll
+module.exports.unescapeAll = unescapeAll
+module.exports.expandPattern = expandPattern
 module.exports.hasMagic = hasMagic
+module.exports.defaultIgnorePatterns = defaultIgnorePatterns
 module.exports.analyzePattern = analyzePattern
 module.exports.analyzePatterns = analyzePatterns
+module.exports.analyzePatternsGrouped = analyzePatternsGrouped
+module.exports.setPatternCacheCapacity = setPatternCacheCapacity
+module.exports.patternCacheStats = patternCacheStats
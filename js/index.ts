@@ -22,30 +22,381 @@ export interface PatternWarningInfo {
   suggestion?: string
 }
 
+/**
+ * Warnings of one type collected across patterns, returned by analyzePatternsGrouped
+ */
+export interface GroupedPatternWarning {
+  /** The type of warning shared by the group (e.g., "performance", "empty_pattern") */
+  warningType: string
+  /** Human-readable message describing the group's problem, without naming a pattern */
+  message: string
+  /** Number of warnings collapsed into this entry */
+  count: number
+  /** The pattern behind each warning in the group, in input order (`''` for empty patterns) */
+  patterns: string[]
+}
+
+/**
+ * Options for matchesPath. The pattern options mean the same as in GlobOptions.
+ */
+export interface MatchOptions {
+  /** Include `.dot` files in normal matches and `globstar` matches. */
+  dot?: boolean
+  /** Do not expand `{a,b}` and `{1..3}` brace sets. */
+  nobrace?: boolean
+  /** Do not match `**` against multiple filenames. */
+  noglobstar?: boolean
+  /** Do not match "extglob" patterns such as `+(a|b)`. */
+  noext?: boolean
+  /** Perform a case-insensitive match. Defaults to `true` on macOS and Windows. */
+  nocase?: boolean
+  /** Match patterns without slashes against the basename of the path. */
+  matchBase?: boolean
+  /** Use `\\` as a path separator only, and never as an escape character. */
+  windowsPathsNoEscape?: boolean
+  /** Platform to use for path handling. Defaults to the current platform. */
+  platform?: 'linux' | 'darwin' | 'win32'
+  /**
+   * Lexically resolve `.` and `..` segments in the candidate path before
+   * matching (no filesystem access).
+   * @default false
+   */
+  normalizeCandidate?: boolean
+  /**
+   * Keep `\\` in the candidate path as an ordinary character. By default it is
+   * treated as `/`, like in walked paths. Ignored on Windows.
+   * @default false
+   */
+  preserveBackslashes?: boolean
+  /**
+   * Segment delimiter for matching non-path keys such as `a.b.c`. `*`, `?`
+   * and classes never match it and `**` spans segments; `/` and `\\` are
+   * ordinary characters.
+   * Must be a single character without glob meaning.
+   * @default '/'
+   */
+  separator?: string
+  /**
+   * Glob pattern(s) a candidate must not match, with the same semantics as
+   * the `ignore` glob option.
+   */
+  ignore?: string | string[]
+  /**
+   * Directory that absolute candidates are made relative to before matching,
+   * so `/project/src/a.ts` with base `/project` matches `src/*.ts`.
+   * Absolute candidates outside it never match.
+   */
+  base?: string
+  /**
+   * Throw for an absolute candidate outside `base` instead of not matching.
+   * @default false
+   */
+  strictBase?: boolean
+}
+
+/**
+ * How a single pattern fared against a candidate path in explainMatch
+ */
+export interface PatternExplanation {
+  /** The pattern after brace expansion */
+  pattern: string
+  /** True if the pattern matches the candidate (including the directory requirement) */
+  matched: boolean
+  /** The fast path that decided the result, or undefined if the regex was used */
+  fastPath?: string
+  /** The compiled regex for the whole pattern */
+  regex: string
+  /** True if the pattern ends in `/` and so only matches directories */
+  requiresDirectory: boolean
+}
+
+/**
+ * Diagnostic report returned by explainMatch
+ */
+export interface MatchExplanation {
+  /** Whether the path matches, exactly as matchesPath would answer */
+  matched: boolean
+  /** The candidate that was tested, after `./`, trailing `/` and normalization handling */
+  candidate: string
+  /** True if the path was treated as a directory (it ended in `/`) */
+  isDirectory: boolean
+  /** True if a dot segment in the path is not allowed by any pattern */
+  excludedByDot: boolean
+  /** True if an ignore pattern matches the path */
+  ignored: boolean
+  /** One entry per pattern, in the order they are tried */
+  patterns: PatternExplanation[]
+  /** A short human-readable summary of the outcome */
+  reason: string
+}
+
+/**
+ * Matches and pattern warnings returned together by globSyncChecked
+ */
+export interface CheckedGlobResult {
+  /** The matching paths, exactly as globSync would return them */
+  results: string[]
+  /** Warnings for the patterns (empty if no issues detected) */
+  warnings: PatternWarningInfo[]
+}
+
+/**
+ * A result path with the patterns that matched it, returned by globSyncAnnotated
+ */
+export interface AnnotatedPath {
+  /** The matching path, exactly as globSync would return it */
+  path: string
+  /** Indices into the input patterns of every pattern that matches the path */
+  matchedPatterns: number[]
+}
+
+/**
+ * A result path split into its parts, returned by globSyncParsed
+ */
+export interface ParsedPath {
+  /** Directory part, like `path.parse().dir` (empty for matches directly in cwd) */
+  dir: string
+  /** File name without its extension, like `path.parse().name` */
+  name: string
+  /** Extension including the leading dot, like `path.parse().ext` (empty if none) */
+  ext: string
+}
+
+/**
+ * A result path with its discovery position, returned by globSyncIndexed
+ */
+export interface IndexedPath {
+  /** The matching path, exactly as globSync would return it */
+  path: string
+  /** Zero-based position of the path in the order the walk produced it */
+  index: number
+}
+
+/**
+ * A result path with its depth below cwd, returned by globSyncDepths
+ */
+export interface PathDepth {
+  /** The matching path, exactly as globSync would return it */
+  path: string
+  /** Number of path segments below cwd (0 for cwd itself, 1 for entries directly in cwd) */
+  depth: number
+}
+
+/**
+ * Totals returned by globAggregate
+ */
+export interface GlobAggregate {
+  /** Number of matching paths */
+  count: number
+  /** Sum of the sizes of the matching regular files, in bytes */
+  totalSize: number
+}
+
+/**
+ * Compiled pattern cache statistics returned by patternCacheStats
+ */
+export interface PatternCacheStats {
+  /** Number of compiled patterns currently cached */
+  size: number
+  /** Maximum number of compiled patterns kept */
+  capacity: number
+  /** Lookups answered from the cache */
+  hits: number
+  /** Lookups that had to compile the pattern */
+  misses: number
+}
+
+/**
+ * Per-directory match count returned by globHistogram
+ */
+export interface DirCount {
+  /** Directory holding the matches, cut to the requested depth (`.` for cwd) */
+  dir: string
+  /** Number of matches in that directory or below it */
+  count: number
+}
+
+/**
+ * Execution plan returned by globPlan
+ */
+export interface GlobPlan {
+  /** `Static`, `Shallow`, `MultiBase`, `PrefixScoped` or `FullWalk` */
+  strategy: 'Static' | 'Shallow' | 'MultiBase' | 'PrefixScoped' | 'FullWalk'
+  /** Directory the walk starts from (cwd unless the strategy is `PrefixScoped`) */
+  walkRoot: string
+  /** Literal prefix shared by all patterns, for `PrefixScoped` */
+  prefix?: string
+}
+
+/**
+ * Answer returned by isIgnored
+ */
+export interface IgnoreCheck {
+  /** True if the path itself would be left out of results */
+  ignored: boolean
+  /** True if everything below the path is ignored, so a walk would not read it */
+  childrenIgnored: boolean
+}
+
+/**
+ * One page of results returned by globPage
+ */
+export interface GlobPage {
+  /** Matching paths, in walk order */
+  results: string[]
+  /** Pass to the next globPage call to continue; unset on the last page */
+  nextCursor?: string
+}
+
+/**
+ * Options that a single pattern may override on top of the call-wide `GlobOptions`.
+ * Unset fields inherit the call-wide value.
+ */
+export interface PatternOverrides {
+  /** Perform a case-insensitive match for this pattern only. */
+  nocase?: boolean
+  /** Include `.dot` files in matches for this pattern only. */
+  dot?: boolean
+  /** Do not expand extglob patterns such as `+(a|b)` for this pattern only. */
+  noext?: boolean
+}
+
+/**
+ * A pattern together with its per-pattern option overrides.
+ */
+export interface PatternInput {
+  /** The glob pattern */
+  pattern: string
+  /** Options that apply to this pattern only */
+  options?: PatternOverrides
+}
+
 const nativeBindings = require('../index.js') as {
-  globSync: (pattern: string | string[], options?: NativeGlobOptions) => string[]
-  glob: (pattern: string | string[], options?: NativeGlobOptions) => Promise<string[]>
+  globSync: (
+    pattern: string | Array<string | PatternInput>,
+    options?: NativeGlobOptions
+  ) => string[]
+  matchesPath: (
+    path: string,
+    pattern: string | Array<string | PatternInput>,
+    options?: MatchOptions
+  ) => boolean
+  matchesAll: (
+    path: string,
+    include: string[],
+    exclude: string[],
+    options?: MatchOptions
+  ) => boolean
+  explainMatch: (
+    path: string,
+    pattern: string | Array<string | PatternInput>,
+    options?: MatchOptions
+  ) => MatchExplanation
+  canContainMatch: (
+    dirPath: string,
+    pattern: string | Array<string | PatternInput>,
+    options?: MatchOptions
+  ) => boolean
+  isIgnored: (path: string, ignore: string[], options?: NativeGlobOptions) => IgnoreCheck
+  globSyncChecked: (
+    pattern: string | Array<string | PatternInput>,
+    options?: NativeGlobOptions
+  ) => CheckedGlobResult
+  globBatch: (
+    cwds: string[],
+    pattern: string | Array<string | PatternInput>,
+    options?: NativeGlobOptions
+  ) => string[][]
+  globSyncGrouped: (
+    patterns: Array<string | PatternInput>,
+    options?: NativeGlobOptions,
+    firstMatchOnly?: boolean
+  ) => string[][]
+  globAggregate: (
+    pattern: string | Array<string | PatternInput>,
+    options?: NativeGlobOptions
+  ) => GlobAggregate
+  globHistogram: (
+    pattern: string | Array<string | PatternInput>,
+    options: NativeGlobOptions | undefined,
+    depth: number
+  ) => DirCount[]
+  globPlan: (
+    pattern: string | Array<string | PatternInput>,
+    options?: NativeGlobOptions
+  ) => GlobPlan
+  globPage: (
+    pattern: string | Array<string | PatternInput>,
+    options: NativeGlobOptions | undefined,
+    cursor: string | undefined,
+    pageSize: number
+  ) => GlobPage
+  globSyncFirst: (
+    pattern: string | Array<string | PatternInput>,
+    options: NativeGlobOptions | undefined,
+    count: number
+  ) => string[]
+  globSyncAnnotated: (
+    patterns: Array<string | PatternInput>,
+    options?: NativeGlobOptions
+  ) => AnnotatedPath[]
+  globSyncParsed: (
+    pattern: string | Array<string | PatternInput>,
+    options?: NativeGlobOptions
+  ) => ParsedPath[]
+  globSyncIndexed: (
+    pattern: string | Array<string | PatternInput>,
+    options?: NativeGlobOptions
+  ) => IndexedPath[]
+  globSyncDepths: (
+    pattern: string | Array<string | PatternInput>,
+    options?: NativeGlobOptions
+  ) => PathDepth[]
+  glob: (
+    pattern: string | Array<string | PatternInput>,
+    options?: NativeGlobOptions
+  ) => Promise<string[]>
   globSyncWithFileTypes: (
-    pattern: string | string[],
+    pattern: string | Array<string | PatternInput>,
     options?: NativeGlobOptions
   ) => NativePathData[]
   globWithFileTypes: (
-    pattern: string | string[],
+    pattern: string | Array<string | PatternInput>,
     options?: NativeGlobOptions
   ) => Promise<NativePathData[]>
   globStream: (
-    pattern: string | string[],
+    pattern: string | Array<string | PatternInput>,
     options: NativeGlobOptions | undefined,
     callback: (result: string) => void
   ) => void
+  globStreamBatched: (
+    pattern: string | Array<string | PatternInput>,
+    options: NativeGlobOptions | undefined,
+    batchSize: number,
+    callback: (results: string[]) => void
+  ) => Promise<number>
   globStreamWithFileTypes: (
-    pattern: string | string[],
+    pattern: string | Array<string | PatternInput>,
     options: NativeGlobOptions | undefined,
     callback: (result: NativePathData) => void
   ) => void
-  escape: (pattern: string, windowsPathsNoEscape?: boolean) => string
+  globIterateWithFileTypes: (
+    pattern: string | Array<string | PatternInput>,
+    options?: NativeGlobOptions,
+    bufferSize?: number
+  ) => NativeGlobFileTypesIterator
+  IgnoreSet: new (patterns: string[], options?: NativeGlobOptions) => NativeIgnoreSet
+  escape: (pattern: string, windowsPathsNoEscape?: boolean, magicalBraces?: boolean) => string
   unescape: (pattern: string, windowsPathsNoEscape?: boolean) => string
+  escapeAll: (
+    patterns: string[],
+    windowsPathsNoEscape?: boolean,
+    magicalBraces?: boolean
+  ) => string[]
+  unescapeAll: (patterns: string[], windowsPathsNoEscape?: boolean) => string[]
+  expandPattern: (pattern: string, options?: NativeGlobOptions) => string[]
   hasMagic: (pattern: string, noext?: boolean, windowsPathsNoEscape?: boolean) => boolean
+  defaultIgnorePatterns: () => string[]
   analyzePattern: (
     pattern: string,
     windowsPathsNoEscape?: boolean,
@@ -56,6 +407,13 @@ const nativeBindings = require('../index.js') as {
     windowsPathsNoEscape?: boolean,
     platform?: string
   ) => PatternWarningInfo[]
+  analyzePatternsGrouped: (
+    patterns: string[],
+    windowsPathsNoEscape?: boolean,
+    platform?: string
+  ) => GroupedPatternWarning[]
+  setPatternCacheCapacity: (capacity: number) => void
+  patternCacheStats: () => PatternCacheStats
 }
 
 /**
@@ -66,20 +424,69 @@ interface NativePathData {
   isDirectory: boolean
   isFile: boolean
   isSymlink: boolean
+  linkTarget?: string
+  isEmptyDir?: boolean
+}
+
+/**
+ * Pull-based native iterator; the walk pauses while its buffer is full
+ */
+interface NativeGlobFileTypesIterator {
+  /**
+   * Resolves with the next buffered results, or an empty array when done;
+   * rejects instead if the walk stopped at the hard depth limit
+   */
+  nextBatch(max?: number): Promise<NativePathData[]>
+  /** Stop the walk early, once any pending nextBatch() has returned */
+  close(): Promise<void>
+}
+
+/**
+ * Compiled ignore patterns held by native code; only passed back as `ignoreSet`
+ */
+interface NativeIgnoreSet {
+  readonly __nativeIgnoreSet?: never
 }
 
 const {
   globSync: nativeGlobSync,
+  globSyncChecked: nativeGlobSyncChecked,
+  globSyncGrouped: nativeGlobSyncGrouped,
+  globBatch: nativeGlobBatch,
+  globAggregate: nativeGlobAggregate,
+  globHistogram: nativeGlobHistogram,
+  globPlan: nativeGlobPlan,
+  globPage: nativeGlobPage,
+  globSyncFirst: nativeGlobSyncFirst,
+  globSyncAnnotated: nativeGlobSyncAnnotated,
+  globSyncParsed: nativeGlobSyncParsed,
+  globSyncIndexed: nativeGlobSyncIndexed,
+  globSyncDepths: nativeGlobSyncDepths,
+  matchesPath: nativeMatchesPath,
+  matchesAll: nativeMatchesAll,
+  explainMatch: nativeExplainMatch,
+  canContainMatch: nativeCanContainMatch,
+  isIgnored: nativeIsIgnored,
   glob: nativeGlob,
   globSyncWithFileTypes: nativeGlobSyncWithFileTypes,
   globWithFileTypes: nativeGlobWithFileTypes,
   globStream: _nativeGlobStream,
+  globStreamBatched: nativeGlobStreamBatched,
   globStreamWithFileTypes: _nativeGlobStreamWithFileTypes,
+  globIterateWithFileTypes: nativeGlobIterateWithFileTypes,
+  IgnoreSet: NativeIgnoreSetClass,
   escape: nativeEscape,
   unescape: nativeUnescape,
+  escapeAll: nativeEscapeAll,
+  unescapeAll: nativeUnescapeAll,
+  expandPattern: nativeExpandPattern,
   hasMagic: nativeHasMagic,
+  defaultIgnorePatterns: nativeDefaultIgnorePatterns,
   analyzePattern: nativeAnalyzePattern,
   analyzePatterns: nativeAnalyzePatterns,
+  analyzePatternsGrouped: nativeAnalyzePatternsGrouped,
+  setPatternCacheCapacity: nativeSetPatternCacheCapacity,
+  patternCacheStats: nativePatternCacheStats,
 } = nativeBindings
 
 // Note: _nativeGlobStream and _nativeGlobStreamWithFileTypes are currently unused
@@ -113,6 +520,30 @@ interface NativeGlobOptions {
   nocase?: boolean
   magicalBraces?: boolean
   follow?: boolean
+  sortDirEntries?: boolean
+  dirOrder?: 'PreOrder' | 'PostOrder'
+  preserveBackslashes?: boolean
+  maxPatterns?: number
+  restrictToCwd?: boolean
+  defaultIgnore?: boolean
+  readLinkTargets?: boolean
+  detectEmptyDirs?: boolean
+  rootRelative?: boolean
+  preservePatternOrder?: boolean
+  noNegation?: boolean
+  basenameOnly?: boolean
+  hidden?: 'DotfilesOnly' | 'AttributeOnly' | 'Both'
+  unique?: boolean
+  sequential?: boolean
+  streamPerBase?: boolean
+  resultPrefix?: string
+  scopedDedup?: boolean
+  suppressErrors?: boolean
+  oneFileSystem?: boolean
+  maxOpenDirs?: number
+  hardDepthLimit?: number
+  trimPatterns?: boolean
+  disableOptimizations?: boolean
   maxDepth?: number
   matchBase?: boolean
   absolute?: boolean
@@ -123,7 +554,10 @@ interface NativeGlobOptions {
   withFileTypes?: boolean
   stat?: boolean
   realpath?: boolean
+  realpathKeepBroken?: boolean
+  canonicalOnly?: boolean
   ignore?: string | string[]
+  ignoreSet?: NativeIgnoreSet
   includeChildMatches?: boolean
   platform?: string
   windowsPathsNoEscape?: boolean
@@ -132,6 +566,7 @@ interface NativeGlobOptions {
   cache?: boolean
   useNativeIO?: boolean
   useGcd?: boolean
+  dedupStrategy?: 'FullString' | 'Hash64'
 }
 
 // Types
@@ -150,150 +585,521 @@ export interface GlobOptions {
 
   // Traversal options
   follow?: boolean
-  maxDepth?: number
-  matchBase?: boolean
-
-  // Output options
-  absolute?: boolean
-  dotRelative?: boolean
-  mark?: boolean
-  nodir?: boolean
-  posix?: boolean
-  withFileTypes?: boolean
-
-  // Performance options
-  stat?: boolean
-  realpath?: boolean
-
-  // Filtering options
-  ignore?: string | string[] | IgnorePattern
-  includeChildMatches?: boolean
-
-  // Platform options
-  platform?: 'linux' | 'darwin' | 'win32'
-  windowsPathsNoEscape?: boolean
-
-  // Control options
-  signal?: AbortSignal
-
-  // Performance options (globlin-specific)
   /**
-   * Enable parallel directory walking using multiple threads.
-   *
-   * When `true`, uses parallel traversal which can be faster on:
-   * - Spinning hard drives (HDDs)
-   * - Network filesystems (NFS, CIFS)
-   * - Very large directory trees (100k+ files)
+   * Sort the entries of each directory by name before matching them.
    *
-   * When `false` (default), uses serial traversal which is:
-   * - Faster on SSDs for small to medium directories
-   * - Deterministic result ordering
-   * - Lower memory overhead
+   * Results (and streamed results) come out in `ls`-like name order within
+   * every directory, without waiting for the walk to finish and sorting the
+   * whole result set. The order across directories still depends on the walker.
    *
    * **Note:** This is a globlin-specific option not present in the original glob package.
-   * Results may be returned in a different order when `parallel: true`.
    *
    * @default false
    */
-  parallel?: boolean
+  sortDirEntries?: boolean
 
   /**
-   * Enable directory caching for repeated glob operations.
+   * Whether a matched directory comes before (`PreOrder`) or after
+   * (`PostOrder`) the matches inside it.
    *
-   * When `true`, caches directory listings in memory with a TTL-based invalidation
-   * strategy. This provides significant speedup when:
-   * - Running multiple glob operations on the same directories
-   * - Using the Glob class with cache reuse (passing Glob as options)
-   * - Patterns with overlapping directory prefixes
+   * `PostOrder` suits recursive deletion: removing results in order empties
+   * each directory before it is removed. Walks then always read depth-first,
+   * so `useNativeIO` and `useGcd` are ignored, and literal patterns are walked
+   * instead of stat'ed. `globPage` always uses `PreOrder`. Default: `PreOrder`.
    *
-   * When `false` (default), directories are read fresh each time, which is:
-   * - More accurate if the filesystem is changing
-   * - Lower memory usage (no cached directory listings)
-   * - Slightly slower for repeated operations
+   * **Note:** This is a globlin-specific option not present in the original glob package.
    *
-   * The cache has a 5-second TTL to balance freshness with performance.
-   * Use `cache: false` when you expect filesystem changes during the operation.
+   * @default 'PreOrder'
+   */
+  dirOrder?: 'PreOrder' | 'PostOrder'
+
+  /**
+   * Keep backslashes in emitted paths instead of converting them to `/`.
+   *
+   * On Linux and macOS a filename may legitimately contain `\`; by default it
+   * is treated as a separator and the name is corrupted in the results. With
+   * this option, `\` in filesystem paths is an ordinary character and
+   * patterns match it literally (use `\\` or `*` to match it). Ignored on
+   * Windows, where `\` is always a separator.
    *
    * **Note:** This is a globlin-specific option not present in the original glob package.
    *
    * @default false
    */
-  cache?: boolean
+  preserveBackslashes?: boolean
 
   /**
-   * Use optimized native I/O operations on Linux and macOS.
+   * Maximum number of patterns after brace expansion, summed over all input
+   * patterns and `ignore` patterns (including `defaultIgnore`). Defaults to
+   * `100000`.
    *
-   * When `true`, uses platform-specific optimizations:
-   * - Linux: `getdents64` syscall for faster directory reading (bypasses libc overhead)
-   * - macOS: Low-level BSD directory APIs with d_type for early file type detection
+   * A pattern like `{a,b}{c,d}{e,f}...` grows exponentially; once the total would
+   * exceed this limit the call fails with an error naming the offending pattern
+   * instead of expanding without bound.
    *
-   * Performance characteristics:
-   * - Linux: Expected 1.3-1.5x speedup on directory-heavy workloads
-   * - macOS: May provide speedup on some filesystems; benchmark for your use case
+   * **Note:** This is a globlin-specific option not present in the original glob package.
    *
-   * When `false` (default), uses the standard `walkdir` library which is:
-   * - Cross-platform compatible
-   * - Well-tested and stable
-   * - Sufficient for most use cases
+   * @default 100000
+   */
+  maxPatterns?: number
+
+  /**
+   * Keep the glob inside `cwd`. Patterns that would reach above it (such as
+   * `../secrets/*` or an absolute path elsewhere) are rejected with an error,
+   * and results that resolve outside `cwd` through a symlink are dropped.
    *
-   * On Windows, this option is ignored and the standard walker is used.
+   * Use this when patterns come from untrusted input.
    *
    * **Note:** This is a globlin-specific option not present in the original glob package.
    *
    * @default false
    */
-  useNativeIO?: boolean
+  restrictToCwd?: boolean
 
   /**
-   * Use Grand Central Dispatch (GCD) for parallel walking on macOS.
-   *
-   * When `true` on macOS, uses Apple's Grand Central Dispatch framework for
-   * parallel directory traversal. This provides:
-   * - Native macOS scheduler integration
-   * - Automatic handling of efficiency vs performance cores on Apple Silicon
-   * - Better power management than generic thread pools
-   * - Lower overhead than rayon for I/O-bound workloads
+   * Also ignore the built-in list of heavy directories (`**/node_modules/**`
+   * and `**/.git/**`), in addition to any `ignore` patterns. Ignored
+   * directories are not traversed.
    *
-   * When `false` (default), uses the standard walker which is often faster
-   * on modern SSDs due to reduced coordination overhead.
+   * The list is available from `defaultIgnorePatterns()`.
    *
-   * **When to use:**
-   * - Large directory trees (100k+ files) on Macs with many cores
-   * - Network filesystems where I/O latency dominates
-   * - When power efficiency matters (GCD respects system power state)
+   * **Note:** This is a globlin-specific option not present in the original glob package.
    *
-   * **When NOT to use:**
-   * - Small to medium directories on SSD
-   * - When deterministic ordering is required
-   * - On non-macOS platforms (option is ignored)
+   * @default false
+   */
+  defaultIgnore?: boolean
+
+  /**
+   * With `withFileTypes`, read where each symlink points and report it as
+   * `linkTarget`. The target is returned as stored in the link, without
+   * resolving it. Only symlinks cost an extra `readlink` call.
    *
    * **Note:** This is a globlin-specific option not present in the original glob package.
    *
    * @default false
    */
-  useGcd?: boolean
-}
+  readLinkTargets?: boolean
 
-/**
- * Type guard to check if an object is a Glob instance (for cache reuse)
- */
-function isGlobInstance(obj: unknown): obj is Glob {
-  return obj instanceof Glob
-}
+  /**
+   * With `withFileTypes`, report whether each directory is empty as
+   * `isEmptyDir`. Only directories cost an extra `readdir` call.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  detectEmptyDirs?: boolean
 
-/**
- * Custom ignore pattern object. Compatible with glob v13's IgnoreLike interface.
- *
- * Both methods receive Path objects from path-scurry, allowing for rich
- * filtering based on file properties (name, isDirectory, isFile, etc.)
- */
-export interface IgnorePattern {
   /**
-   * Called for each path to determine if it should be excluded from results.
-   * @param path - The Path object representing the file/directory
-   * @returns true if the path should be ignored (excluded from results)
+   * Treat a leading `/` as anchoring the pattern at `cwd`, like in `.gitignore`,
+   * instead of the filesystem root. `/src/*.ts` then matches `src/a.ts` but not
+   * `lib/src/a.ts`. Applies to `ignore` patterns too.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
    */
-  ignored?: (path: Path) => boolean
+  rootRelative?: boolean
+
+  /**
+   * Keep patterns in input order instead of trying the ones with cheap
+   * matching first. Results are the same; this only matters for debugging or
+   * when relying on which pattern is tried first.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  preservePatternOrder?: boolean
+
+  /**
+   * Treat `!(...)` as literal text while still expanding the other extglobs
+   * (`+(...)`, `@(...)`, `*(...)`, `?(...)`). Finer-grained than `noext`.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  noNegation?: boolean
+
+  /**
+   * Match every pattern against only the final segment of each path, even
+   * patterns containing `/`: `src/*.ts` then matches any `*.ts` file at any
+   * depth. Stronger than `matchBase`, which leaves patterns with `/` anchored.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  basenameOnly?: boolean
+
+  /**
+   * What counts as hidden for `dot: false`.
+   *
+   * - `DotfilesOnly` (default): names starting with `.`, as in glob.
+   * - `AttributeOnly`: entries with the Windows hidden attribute
+   *   (`FILE_ATTRIBUTE_HIDDEN`); dotfiles are then matched like other files.
+   * - `Both`: either of the above.
+   *
+   * Attribute checks cost one metadata call per entry and skip the contents of
+   * hidden directories. Nothing has the attribute on other platforms.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default 'DotfilesOnly'
+   */
+  hidden?: 'DotfilesOnly' | 'AttributeOnly' | 'Both'
+
+  /**
+   * Drop duplicate results (default `true`). With `false`, a path is returned
+   * once for every input pattern it matches, e.g. for per-pattern accounting;
+   * each copy directly follows the first. Only `globSync`, `glob` and
+   * `globSyncChecked` repeat paths; other functions always deduplicate.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default true
+   */
+  unique?: boolean
+
+  /**
+   * Apply the patterns in order, like tsconfig `include`/`exclude` lists: a
+   * pattern starting with `!` removes the paths matched so far, and a later
+   * pattern can add them back. The last pattern matching a path decides.
+   * Without this option, a leading `!` is a literal character.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  sequential?: boolean
+
+  /**
+   * When patterns have several distinct bases (e.g. `src/**/*.js` and
+   * `test/**/*.js`), stream each base's results as soon as its walk finds them
+   * instead of after every base has been walked. Results from different bases
+   * interleave in no fixed order. Only affects `globStreamBatched`, the one
+   * function that walks off the JavaScript thread and delivers results while
+   * the walk is still running.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  streamPerBase?: boolean
+
+  /**
+   * String prepended to every returned path, after all other formatting
+   * (`absolute`, `mark`, `posix`, `dotRelative`) has been applied.
+   *
+   * Useful for feeding results straight into tools that expect a fixed
+   * prefix, such as a URL base or a remote host. The prefix is not applied
+   * to `Path` objects returned with `withFileTypes: true`.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default ''
+   */
+  resultPrefix?: string
+
+  /**
+   * Only remember the results of directories the walk is still inside, so
+   * deduplication memory stays bounded instead of growing with every result.
+   * Takes precedence over `dedupStrategy`.
+   *
+   * Duplicates from separate walks that reach the same path, or from a
+   * parallel walk, may not be removed; unique paths are never dropped.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  scopedDedup?: boolean
+
+  /**
+   * Return no results instead of throwing when a directory the walk starts
+   * from (the cwd, or a pattern's literal prefix such as `/srv/data`)
+   * exists but can't be read, e.g. because of permissions.
+   *
+   * By default such a root is an error, so "permission denied" can be told
+   * apart from "nothing matched". Missing roots are never an error, and
+   * unreadable directories below the root are always skipped. Streams report
+   * the error once the walk has finished.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  suppressErrors?: boolean
+
+  /**
+   * Don't descend into directories on a different filesystem than the walk
+   * root, like `find -xdev`. Keeps recursive patterns out of network mounts
+   * or `/proc`. Such directories are skipped together with their contents.
+   *
+   * Devices are compared by `st_dev` on Unix; on Windows this has no effect.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  oneFileSystem?: boolean
+
+  /**
+   * Maximum number of directory handles the walk keeps open at once.
+   * Lower it on systems with a small `ulimit -n`, where deep or `parallel`
+   * walks could otherwise fail with "too many open files".
+   *
+   * The cap covers the whole glob, including the concurrent walks of a
+   * multi-base pattern set. With `parallel`, this also caps the number of
+   * walker threads.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default undefined
+   */
+  maxOpenDirs?: number
+
+  /**
+   * Depth past which the walk never descends, whatever `maxDepth` allows
+   * (default 4096). Guards against pathologically deep trees and followed
+   * symlink loops. Reaching it is an error, since the results are incomplete;
+   * with `suppressErrors` the results gathered so far are returned instead.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default 4096
+   */
+  hardDepthLimit?: number
+
+  /**
+   * Handle patterns like lines of a `.gitignore` file: leading and trailing
+   * whitespace is trimmed, and blank patterns and `#` comments are skipped.
+   * A trailing space escaped with a backslash (`foo\ `) is kept.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  trimPatterns?: boolean
+
+  /**
+   * Turn off every matching and walking shortcut: pattern fast paths, the
+   * static, shallow and multi-base strategies, pattern-derived depth limits
+   * and directory pruning. Each glob then walks the tree and tests every
+   * path against the full pattern regex.
+   *
+   * Results should be identical either way, so this is a debugging aid for
+   * telling a fast-path bug from a pattern bug. It is much slower.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  disableOptimizations?: boolean
+  maxDepth?: number
+  matchBase?: boolean
+
+  // Output options
+  absolute?: boolean
+  dotRelative?: boolean
+  mark?: boolean
+  nodir?: boolean
+  posix?: boolean
+  withFileTypes?: boolean
+
+  // Performance options
+  stat?: boolean
+  realpath?: boolean
+
+  /**
+   * With `realpath`, keep broken symlinks in the results under their own
+   * path instead of dropping them. Entries that fail to resolve for any
+   * other reason (such as a symlink loop) are still omitted.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  realpathKeepBroken?: boolean
+
+  /**
+   * Report each file reachable through several paths only once, under its
+   * real path.
+   *
+   * With `follow`, a file under a symlinked directory is found both directly
+   * and through the link. This drops the paths that go through a symlink when
+   * the real path is also among the results. Unlike `realpath`, the results
+   * are not rewritten: a file only reachable through a symlink keeps its path.
+   * It applies to every API, `withFileTypes` and the counting ones included;
+   * streaming APIs buffer the whole walk first.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  canonicalOnly?: boolean
+
+  // Filtering options
+  ignore?: string | string[] | IgnorePattern
+
+  /**
+   * Precompiled ignore patterns, used in place of `ignore`. Build an
+   * `IgnoreSet` once and pass it to every call that shares the same ignore
+   * list, so the patterns aren't recompiled each time. Can't be combined
+   * with `ignore` or `defaultIgnore`; give those to the `IgnoreSet` instead.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default undefined
+   */
+  ignoreSet?: IgnoreSet
+  includeChildMatches?: boolean
+
+  // Platform options
+  platform?: 'linux' | 'darwin' | 'win32'
+  windowsPathsNoEscape?: boolean
+
+  // Control options
+  signal?: AbortSignal
+
+  // Performance options (globlin-specific)
+  /**
+   * Enable parallel directory walking using multiple threads.
+   *
+   * When `true`, uses parallel traversal which can be faster on:
+   * - Spinning hard drives (HDDs)
+   * - Network filesystems (NFS, CIFS)
+   * - Very large directory trees (100k+ files)
+   *
+   * When `false` (default), uses serial traversal which is:
+   * - Faster on SSDs for small to medium directories
+   * - Deterministic result ordering
+   * - Lower memory overhead
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   * Results may be returned in a different order when `parallel: true`.
+   *
+   * @default false
+   */
+  parallel?: boolean
+
+  /**
+   * Enable directory caching for repeated glob operations.
+   *
+   * When `true`, caches directory listings in memory with a TTL-based invalidation
+   * strategy. This provides significant speedup when:
+   * - Running multiple glob operations on the same directories
+   * - Using the Glob class with cache reuse (passing Glob as options)
+   * - Patterns with overlapping directory prefixes
+   *
+   * When `false` (default), directories are read fresh each time, which is:
+   * - More accurate if the filesystem is changing
+   * - Lower memory usage (no cached directory listings)
+   * - Slightly slower for repeated operations
+   *
+   * The cache has a 5-second TTL to balance freshness with performance.
+   * Use `cache: false` when you expect filesystem changes during the operation.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  cache?: boolean
+
+  /**
+   * Use optimized native I/O operations on Linux and macOS.
+   *
+   * When `true`, uses platform-specific optimizations:
+   * - Linux: `getdents64` syscall for faster directory reading (bypasses libc overhead)
+   * - macOS: Low-level BSD directory APIs with d_type for early file type detection
+   *
+   * Performance characteristics:
+   * - Linux: Expected 1.3-1.5x speedup on directory-heavy workloads
+   * - macOS: May provide speedup on some filesystems; benchmark for your use case
+   *
+   * When `false` (default), uses the standard `walkdir` library which is:
+   * - Cross-platform compatible
+   * - Well-tested and stable
+   * - Sufficient for most use cases
+   *
+   * On Windows, this option is ignored and the standard walker is used.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  useNativeIO?: boolean
+
+  /**
+   * Use Grand Central Dispatch (GCD) for parallel walking on macOS.
+   *
+   * When `true` on macOS, uses Apple's Grand Central Dispatch framework for
+   * parallel directory traversal. This provides:
+   * - Native macOS scheduler integration
+   * - Automatic handling of efficiency vs performance cores on Apple Silicon
+   * - Better power management than generic thread pools
+   * - Lower overhead than rayon for I/O-bound workloads
+   *
+   * When `false` (default), uses the standard walker which is often faster
+   * on modern SSDs due to reduced coordination overhead.
+   *
+   * **When to use:**
+   * - Large directory trees (100k+ files) on Macs with many cores
+   * - Network filesystems where I/O latency dominates
+   * - When power efficiency matters (GCD respects system power state)
+   *
+   * **When NOT to use:**
+   * - Small to medium directories on SSD
+   * - When deterministic ordering is required
+   * - On non-macOS platforms (option is ignored)
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default false
+   */
+  useGcd?: boolean
+
+  /**
+   * How results are deduplicated while walking.
+   *
+   * - `FullString` (default): remembers every result path. Exact, but memory
+   *   grows with the total length of all matched paths.
+   * - `Hash64`: remembers only a 64-bit hash of each path, using a fixed 8 bytes
+   *   per result regardless of path length.
+   *
+   * With `Hash64`, two different paths hashing to the same value would cause the
+   * second one to be dropped. The chance is roughly `n² / 2⁶⁵` for `n` results
+   * (about 1 in 37 million for 1 million results), so only use it for very
+   * large result sets where the memory saving matters.
+   *
+   * **Note:** This is a globlin-specific option not present in the original glob package.
+   *
+   * @default 'FullString'
+   */
+  dedupStrategy?: 'FullString' | 'Hash64'
+}
+
+/**
+ * Type guard to check if an object is a Glob instance (for cache reuse)
+ */
+function isGlobInstance(obj: unknown): obj is Glob {
+  return obj instanceof Glob
+}
+
+/**
+ * Custom ignore pattern object. Compatible with glob v13's IgnoreLike interface.
+ *
+ * Both methods receive Path objects from path-scurry, allowing for rich
+ * filtering based on file properties (name, isDirectory, isFile, etc.)
+ */
+export interface IgnorePattern {
+  /**
+   * Called for each path to determine if it should be excluded from results.
+   * @param path - The Path object representing the file/directory
+   * @returns true if the path should be ignored (excluded from results)
+   */
+  ignored?: (path: Path) => boolean
 
   /**
    * Called for each directory to determine if its children should be ignored.
@@ -361,6 +1167,7 @@ function toNativeOptions(options?: GlobOptions): NativeGlobOptions {
     cwd: rest.cwd || process.cwd(),
     ...rest,
     ignore: nativeIgnore,
+    ignoreSet: rest.ignoreSet?.native,
   }
 }
 
@@ -371,12 +1178,22 @@ function toNativeOptions(options?: GlobOptions): NativeGlobOptions {
 function applyCustomIgnoreFilter(
   results: string[],
   ignorePattern: IgnorePattern,
-  cwd: string
+  cwd: string,
+  resultPrefix?: string
 ): string[] {
   if (!ignorePattern.ignored && !ignorePattern.childrenIgnored) {
     return results
   }
 
+  // Results carry the resultPrefix string, which is not part of the path
+  if (resultPrefix) {
+    const stripped = results.map(r =>
+      r.startsWith(resultPrefix) ? r.slice(resultPrefix.length) : r
+    )
+    const kept = new Set(applyCustomIgnoreFilter(stripped, ignorePattern, cwd))
+    return results.filter((_, i) => kept.has(stripped[i]))
+  }
+
   const scurry = new PathScurry(cwd)
 
   // Build a set of ignored directories (for childrenIgnored)
@@ -677,13 +1494,21 @@ export class GloblinPath {
   /** The name (basename) of this path entry */
   readonly name: string
 
+  /** Where the symlink points, as stored in the link (only with `readLinkTargets`) */
+  readonly linkTarget?: string
+
+  /** Whether this directory has no entries (only with `detectEmptyDirs`) */
+  readonly isEmptyDir?: boolean
+
   constructor(
     relativePath: string,
     cwd: string,
     isDirectory: boolean,
     isFile: boolean,
     isSymlink: boolean,
-    stat: boolean = false
+    stat: boolean = false,
+    linkTarget?: string,
+    isEmptyDir?: boolean
   ) {
     this.path = relativePath
     this._cwd = cwd
@@ -692,222 +1517,739 @@ export class GloblinPath {
     this._isSymlinkVal = isSymlink
     this._stat = stat
     this.name = nodePath.basename(relativePath) || relativePath
+    this.linkTarget = linkTarget
+    this.isEmptyDir = isEmptyDir
+  }
+
+  /**
+   * Returns true if this is a regular file.
+   * Without stat: true, returns false (unknown type) to match PathScurry behavior.
+   * With stat: true, uses cached value from Rust - no filesystem access needed.
+   */
+  isFile(): boolean {
+    return this._stat ? this._isFileVal : false
+  }
+
+  /**
+   * Returns true if this is a directory.
+   * Without stat: true, returns false (unknown type) to match PathScurry behavior.
+   * With stat: true, uses cached value from Rust - no filesystem access needed.
+   */
+  isDirectory(): boolean {
+    return this._stat ? this._isDir : false
+  }
+
+  /**
+   * Returns true if this is a symbolic link.
+   * Uses cached value from Rust - no filesystem access needed.
+   */
+  isSymbolicLink(): boolean {
+    return this._isSymlinkVal
+  }
+
+  /**
+   * Returns the full absolute path.
+   * This is a fast string operation (no filesystem access).
+   */
+  fullpath(): string {
+    if (!this._fullpath) {
+      this._fullpath = nodePath.join(this._cwd, this.path)
+    }
+    return this._fullpath
+  }
+
+  /**
+   * Returns the path relative to the cwd.
+   * This is a fast string operation (no filesystem access).
+   */
+  relative(): string {
+    return this.path
+  }
+
+  /**
+   * Returns the full absolute path as a string.
+   * Alias for fullpath() for compatibility.
+   */
+  fullpathPosix(): string {
+    // Use forward slashes for posix compatibility
+    return this.fullpath().replace(/\\/g, '/')
+  }
+
+  /**
+   * Returns the relative path as a string.
+   * Alias for relative() for compatibility.
+   */
+  relativePosix(): string {
+    return this.path.replace(/\\/g, '/')
+  }
+
+  /**
+   * Convert to a full PathScurry Path object.
+   * This is an expensive operation (~16µs) that should only be called
+   * when you need advanced Path features not provided by GloblinPath.
+   *
+   * The PathScurry Path is cached, so subsequent calls are fast.
+   *
+   * @returns A full PathScurry Path object
+   */
+  toPath(): Path {
+    if (!this._pathScurryPath) {
+      if (!this._pathScurry) {
+        this._pathScurry = new PathScurry(this._cwd)
+      }
+      this._pathScurryPath = this._pathScurry.cwd.resolve(this.path)
+    }
+    return this._pathScurryPath
+  }
+
+  /**
+   * Get the parent directory as a GloblinPath.
+   * Note: This creates a new GloblinPath for the parent.
+   * For advanced parent traversal, use toPath().parent.
+   */
+  get parent(): GloblinPath | undefined {
+    const parentPath = nodePath.dirname(this.path)
+    if (parentPath === '.' || parentPath === this.path) {
+      return undefined
+    }
+    // Parent is always a directory
+    return new GloblinPath(parentPath, this._cwd, true, false, false, this._stat)
+  }
+
+  /**
+   * String representation (returns the relative path).
+   */
+  toString(): string {
+    return this.path
+  }
+}
+
+/**
+ * Type representing either a PathScurry Path or a GloblinPath wrapper.
+ * GloblinPath provides the same common interface with better performance.
+ */
+export type GlobPath = Path | GloblinPath
+
+/**
+ * Convert native PathData to GloblinPath objects.
+ * This is much faster than creating PathScurry Path objects for each result.
+ *
+ * @param data - Native path data from Rust
+ * @param cwd - Current working directory
+ * @param stat - If true, isFile/isDirectory return actual values; otherwise return false (like PathScurry)
+ * @returns Array of GloblinPath objects
+ */
+function convertToPathObjects(
+  data: NativePathData[],
+  cwd: string,
+  stat: boolean = false
+): GloblinPath[] {
+  return data.map(
+    d =>
+      new GloblinPath(
+        d.path,
+        cwd,
+        d.isDirectory,
+        d.isFile,
+        d.isSymlink,
+        stat,
+        d.linkTarget,
+        d.isEmptyDir
+      )
+  )
+}
+
+/**
+ * LEGACY: Convert native PathData to full PathScurry Path objects.
+ * This is the slow path (~16µs per result) that was used before optimization.
+ * Kept for compatibility and when full PathScurry features are needed.
+ *
+ * @deprecated Use convertToPathObjects() instead for better performance.
+ */
+function convertToFullPathObjects(
+  data: NativePathData[],
+  cwd: string,
+  performLstat: boolean = false
+): Path[] {
+  const scurry = new PathScurry(cwd)
+  return data.map(d => {
+    const p = scurry.cwd.resolve(d.path)
+    // If performLstat is true, call lstatSync to populate type information
+    // Otherwise, the Path object is returned without type info (saves syscalls)
+    if (performLstat) {
+      p.lstatSync()
+    }
+    return p
+  })
+}
+
+// Export for testing purposes
+export { convertToFullPathObjects }
+
+/**
+ * Synchronous glob pattern matching
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options
+ * @returns Array of matching file paths (or GloblinPath objects if withFileTypes: true)
+ *
+ * @remarks
+ * When `withFileTypes: true`, returns GloblinPath objects which are 85% faster to create
+ * than PathScurry Path objects. GloblinPath provides the same common interface (isFile,
+ * isDirectory, isSymbolicLink, fullpath, relative) using cached values from Rust.
+ * Call `.toPath()` on any result if you need the full PathScurry Path.
+ */
+export function globSync(
+  pattern: string | Array<string | PatternInput>,
+  options: GlobOptionsWithFileTypesTrue
+): GloblinPath[]
+export function globSync(
+  pattern: string | Array<string | PatternInput>,
+  options?: GlobOptionsWithFileTypesFalse
+): string[]
+export function globSync(
+  pattern: string | Array<string | PatternInput>,
+  options?: GlobOptions
+): string[] | GloblinPath[]
+export function globSync(
+  pattern: string | Array<string | PatternInput>,
+  options?: GlobOptions
+): string[] | GloblinPath[] {
+  // Check if signal is already aborted before starting
+  if (options?.signal?.aborted) {
+    throw options.signal.reason ?? new Error('The operation was aborted')
+  }
+
+  const opts = toNativeOptions(options)
+  const cwd = opts.cwd ?? process.cwd()
+  const hasCustomIgnore = options?.ignore && isIgnorePattern(options.ignore)
+
+  // Handle withFileTypes option
+  if (options?.withFileTypes) {
+    const data = nativeGlobSyncWithFileTypes(pattern, opts)
+    // Convert to GloblinPath objects (fast - uses cached type info from Rust)
+    let pathObjs = convertToPathObjects(data, cwd, options.stat)
+
+    // Apply custom ignore filter if present
+    if (hasCustomIgnore) {
+      pathObjs = applyCustomIgnoreFilterForGloblinPaths(pathObjs, options.ignore as IgnorePattern)
+    }
+
+    return pathObjs
+  }
+
+  // Pass patterns directly to native implementation (supports both string and array)
+  let results = nativeGlobSync(pattern, opts)
+
+  // Apply custom ignore filter if present
+  if (hasCustomIgnore) {
+    results = applyCustomIgnoreFilter(results, options.ignore as IgnorePattern, cwd, options.resultPrefix)
+  }
+
+  return results
+}
+
+/**
+ * Synchronous glob pattern matching that also returns pattern warnings
+ *
+ * Equivalent to calling `globSync` and `analyzePatterns` on the same input,
+ * but the patterns are only processed once.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options (`withFileTypes` is not supported)
+ * @returns The matching file paths and any warnings for the patterns
+ *
+ * @example
+ * ```ts
+ * import { globSyncChecked } from 'globlin'
+ *
+ * const { results, warnings } = globSyncChecked('*.txt   ')
+ * // warnings: [{ warningType: 'trailing_spaces', suggestion: '*.txt', ... }]
+ * ```
+ */
+export function globSyncChecked(
+  pattern: string | Array<string | PatternInput>,
+  options?: GlobOptions
+): CheckedGlobResult {
+  if (options?.signal?.aborted) {
+    throw options.signal.reason ?? new Error('The operation was aborted')
+  }
+  if (options?.withFileTypes) {
+    throw new TypeError('globSyncChecked does not support withFileTypes')
+  }
+
+  const opts = toNativeOptions(options)
+  const checked = nativeGlobSyncChecked(pattern, opts)
+
+  if (options?.ignore && isIgnorePattern(options.ignore)) {
+    const cwd = opts.cwd ?? process.cwd()
+    checked.results = applyCustomIgnoreFilter(
+      checked.results,
+      options.ignore as IgnorePattern,
+      cwd,
+      options.resultPrefix
+    )
+  }
+
+  return checked
+}
+
+/**
+ * Synchronous glob pattern matching with results grouped by pattern
+ *
+ * Walks the filesystem once and returns one result array per input pattern,
+ * in input order. A path matching several patterns appears in each of their
+ * groups, or only in the first one with `firstMatchOnly`.
+ *
+ * @param patterns - Array of glob patterns
+ * @param options - Glob options (`withFileTypes` is not supported)
+ * @param firstMatchOnly - Put each path only in the group of the first matching pattern
+ * @returns One array of matching file paths per pattern
+ *
+ * @example
+ * ```ts
+ * import { globSyncGrouped } from 'globlin'
+ *
+ * const [scripts, styles] = globSyncGrouped(['src/*.ts', 'src/*.css'])
+ * ```
+ */
+export function globSyncGrouped(
+  patterns: Array<string | PatternInput>,
+  options?: GlobOptions,
+  firstMatchOnly?: boolean
+): string[][] {
+  if (options?.signal?.aborted) {
+    throw options.signal.reason ?? new Error('The operation was aborted')
+  }
+  if (options?.withFileTypes) {
+    throw new TypeError('globSyncGrouped does not support withFileTypes')
   }
 
-  /**
-   * Returns true if this is a regular file.
-   * Without stat: true, returns false (unknown type) to match PathScurry behavior.
-   * With stat: true, uses cached value from Rust - no filesystem access needed.
-   */
-  isFile(): boolean {
-    return this._stat ? this._isFileVal : false
+  const opts = toNativeOptions(options)
+  const groups = nativeGlobSyncGrouped(patterns, opts, firstMatchOnly)
+
+  if (options?.ignore && isIgnorePattern(options.ignore)) {
+    const cwd = opts.cwd ?? process.cwd()
+    return groups.map(results =>
+      applyCustomIgnoreFilter(results, options.ignore as IgnorePattern, cwd, options.resultPrefix)
+    )
   }
 
-  /**
-   * Returns true if this is a directory.
-   * Without stat: true, returns false (unknown type) to match PathScurry behavior.
-   * With stat: true, uses cached value from Rust - no filesystem access needed.
-   */
-  isDirectory(): boolean {
-    return this._stat ? this._isDir : false
+  return groups
+}
+
+/**
+ * Run the same glob in several directories in one native call
+ *
+ * Patterns and options are validated and compiled once, so this is cheaper
+ * than calling `globSync` in a loop. With `parallel: true` the directories are
+ * walked concurrently.
+ *
+ * @param cwds - Directories to search (each overrides `options.cwd`)
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options (`withFileTypes` is not supported)
+ * @returns One array of matches per directory, in the order of `cwds`
+ *
+ * @example
+ * ```ts
+ * import { globBatch } from 'globlin'
+ *
+ * const [app, lib] = globBatch(['packages/app', 'packages/lib'], 'src/*.ts')
+ * ```
+ */
+export function globBatch(
+  cwds: string[],
+  pattern: string | Array<string | PatternInput>,
+  options?: GlobOptions
+): string[][] {
+  if (options?.signal?.aborted) {
+    throw options.signal.reason ?? new Error('The operation was aborted')
+  }
+  if (options?.withFileTypes) {
+    throw new TypeError('globBatch does not support withFileTypes')
   }
 
-  /**
-   * Returns true if this is a symbolic link.
-   * Uses cached value from Rust - no filesystem access needed.
-   */
-  isSymbolicLink(): boolean {
-    return this._isSymlinkVal
+  const opts = toNativeOptions(options)
+  const batches = nativeGlobBatch(cwds, pattern, opts)
+
+  if (options?.ignore && isIgnorePattern(options.ignore)) {
+    return batches.map((results, i) =>
+      applyCustomIgnoreFilter(
+        results,
+        options.ignore as IgnorePattern,
+        cwds[i],
+        options.resultPrefix
+      )
+    )
   }
 
-  /**
-   * Returns the full absolute path.
-   * This is a fast string operation (no filesystem access).
-   */
-  fullpath(): string {
-    if (!this._fullpath) {
-      this._fullpath = nodePath.join(this._cwd, this.path)
-    }
-    return this._fullpath
+  return batches
+}
+
+/**
+ * Synchronous glob pattern matching that reports every pattern matching each path
+ *
+ * Each path is checked against all patterns rather than stopping at the first
+ * match, which makes this slower than `globSync`. Use it to audit overlapping
+ * patterns.
+ *
+ * @param patterns - Array of glob patterns
+ * @param options - Glob options (`withFileTypes` is not supported)
+ * @returns The matching paths, each with the indices of the patterns that matched it
+ *
+ * @example
+ * ```ts
+ * import { globSyncAnnotated } from 'globlin'
+ *
+ * globSyncAnnotated(['src/*.js', 'src/main.*'])
+ * // [{ path: 'src/main.js', matchedPatterns: [0, 1] }, ...]
+ * ```
+ */
+export function globSyncAnnotated(
+  patterns: Array<string | PatternInput>,
+  options?: GlobOptions
+): AnnotatedPath[] {
+  if (options?.signal?.aborted) {
+    throw options.signal.reason ?? new Error('The operation was aborted')
+  }
+  if (options?.withFileTypes) {
+    throw new TypeError('globSyncAnnotated does not support withFileTypes')
   }
 
-  /**
-   * Returns the path relative to the cwd.
-   * This is a fast string operation (no filesystem access).
-   */
-  relative(): string {
-    return this.path
+  const opts = toNativeOptions(options)
+  const annotated = nativeGlobSyncAnnotated(patterns, opts)
+
+  if (options?.ignore && isIgnorePattern(options.ignore)) {
+    const cwd = opts.cwd ?? process.cwd()
+    const kept = new Set(
+      applyCustomIgnoreFilter(
+        annotated.map(a => a.path),
+        options.ignore as IgnorePattern,
+        cwd,
+        options.resultPrefix
+      )
+    )
+    return annotated.filter(a => kept.has(a.path))
   }
 
-  /**
-   * Returns the full absolute path as a string.
-   * Alias for fullpath() for compatibility.
-   */
-  fullpathPosix(): string {
-    // Use forward slashes for posix compatibility
-    return this.fullpath().replace(/\\/g, '/')
+  return annotated
+}
+
+/**
+ * Synchronous glob pattern matching that returns each result pre-split
+ *
+ * Every match comes back as `{ dir, name, ext }`, split the way `path.parse`
+ * would split it, which saves re-parsing large result sets in JavaScript.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options (`withFileTypes` and custom ignore objects are not supported)
+ * @returns The matching paths, each split into directory, name and extension
+ *
+ * @example
+ * ```ts
+ * import { globSyncParsed } from 'globlin'
+ *
+ * globSyncParsed('src/*.js')
+ * // [{ dir: 'src', name: 'main', ext: '.js' }, { dir: 'src', name: 'util', ext: '.js' }]
+ * ```
+ */
+export function globSyncParsed(
+  pattern: string | Array<string | PatternInput>,
+  options?: GlobOptions
+): ParsedPath[] {
+  if (options?.signal?.aborted) {
+    throw options.signal.reason ?? new Error('The operation was aborted')
+  }
+  if (options?.withFileTypes) {
+    throw new TypeError('globSyncParsed does not support withFileTypes')
+  }
+  if (options?.ignore && isIgnorePattern(options.ignore)) {
+    throw new TypeError('globSyncParsed does not support custom ignore objects')
   }
 
-  /**
-   * Returns the relative path as a string.
-   * Alias for relative() for compatibility.
-   */
-  relativePosix(): string {
-    return this.path.replace(/\\/g, '/')
+  return nativeGlobSyncParsed(pattern, toNativeOptions(options))
+}
+
+/**
+ * Synchronous glob pattern matching that numbers each result in discovery order
+ *
+ * Results come back in walk order with `index` counting up from 0, so the
+ * original order can be restored after sorting or grouping the paths.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options (`withFileTypes` is not supported)
+ * @returns The matching paths, each with its position in discovery order
+ *
+ * @example
+ * ```ts
+ * import { globSyncIndexed } from 'globlin'
+ *
+ * globSyncIndexed('src/*.js')
+ * // [{ path: 'src/main.js', index: 0 }, { path: 'src/util.js', index: 1 }]
+ * ```
+ */
+export function globSyncIndexed(
+  pattern: string | Array<string | PatternInput>,
+  options?: GlobOptions
+): IndexedPath[] {
+  if (options?.signal?.aborted) {
+    throw options.signal.reason ?? new Error('The operation was aborted')
+  }
+  if (options?.withFileTypes) {
+    throw new TypeError('globSyncIndexed does not support withFileTypes')
   }
 
-  /**
-   * Convert to a full PathScurry Path object.
-   * This is an expensive operation (~16µs) that should only be called
-   * when you need advanced Path features not provided by GloblinPath.
-   *
-   * The PathScurry Path is cached, so subsequent calls are fast.
-   *
-   * @returns A full PathScurry Path object
-   */
-  toPath(): Path {
-    if (!this._pathScurryPath) {
-      if (!this._pathScurry) {
-        this._pathScurry = new PathScurry(this._cwd)
-      }
-      this._pathScurryPath = this._pathScurry.cwd.resolve(this.path)
-    }
-    return this._pathScurryPath
+  const opts = toNativeOptions(options)
+  const indexed = nativeGlobSyncIndexed(pattern, opts)
+
+  if (options?.ignore && isIgnorePattern(options.ignore)) {
+    const cwd = opts.cwd ?? process.cwd()
+    const kept = new Set(
+      applyCustomIgnoreFilter(
+        indexed.map(r => r.path),
+        options.ignore as IgnorePattern,
+        cwd,
+        options.resultPrefix
+      )
+    )
+    // Renumber so indices stay contiguous after filtering
+    return indexed
+      .filter(r => kept.has(r.path))
+      .map((r, index) => ({ path: r.path, index }))
   }
 
-  /**
-   * Get the parent directory as a GloblinPath.
-   * Note: This creates a new GloblinPath for the parent.
-   * For advanced parent traversal, use toPath().parent.
-   */
-  get parent(): GloblinPath | undefined {
-    const parentPath = nodePath.dirname(this.path)
-    if (parentPath === '.' || parentPath === this.path) {
-      return undefined
-    }
-    // Parent is always a directory
-    return new GloblinPath(parentPath, this._cwd, true, false, false, this._stat)
+  return indexed
+}
+
+/**
+ * Synchronous glob pattern matching that reports how deep below cwd each result lies
+ *
+ * Depth counts the path segments below cwd, so cwd itself is depth 0, entries
+ * directly in cwd are depth 1 and `src/main.js` is depth 2. It is taken from
+ * the walked path before it is formatted, so `absolute`, `mark`, `dotRelative`
+ * and `resultPrefix` don't change it.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options (`withFileTypes` is not supported)
+ * @returns The matching paths, each with its depth below cwd
+ *
+ * @example
+ * ```ts
+ * import { globSyncDepths } from 'globlin'
+ *
+ * globSyncDepths(['*.txt', 'src/*.js', 'src/lib/*.js'])
+ * // [{ path: 'foo.txt', depth: 1 }, { path: 'src/main.js', depth: 2 }, { path: 'src/lib/helper.js', depth: 3 }]
+ * ```
+ */
+export function globSyncDepths(
+  pattern: string | Array<string | PatternInput>,
+  options?: GlobOptions
+): PathDepth[] {
+  if (options?.signal?.aborted) {
+    throw options.signal.reason ?? new Error('The operation was aborted')
+  }
+  if (options?.withFileTypes) {
+    throw new TypeError('globSyncDepths does not support withFileTypes')
   }
 
-  /**
-   * String representation (returns the relative path).
-   */
-  toString(): string {
-    return this.path
+  const opts = toNativeOptions(options)
+  const depths = nativeGlobSyncDepths(pattern, opts)
+
+  if (options?.ignore && isIgnorePattern(options.ignore)) {
+    const cwd = opts.cwd ?? process.cwd()
+    const kept = new Set(
+      applyCustomIgnoreFilter(
+        depths.map(r => r.path),
+        options.ignore as IgnorePattern,
+        cwd,
+        options.resultPrefix
+      )
+    )
+    return depths.filter(r => kept.has(r.path))
   }
+
+  return depths
 }
 
 /**
- * Type representing either a PathScurry Path or a GloblinPath wrapper.
- * GloblinPath provides the same common interface with better performance.
+ * Count matches and sum the sizes of matching files in one native walk
+ *
+ * Paths never cross into JavaScript, so this stays cheap for huge trees.
+ * Only regular files contribute to `totalSize`.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options (`withFileTypes` and custom ignore objects are not supported)
+ * @returns The number of matches and their total size in bytes
+ *
+ * @example
+ * ```ts
+ * import { globAggregate } from 'globlin'
+ *
+ * const { count, totalSize } = globAggregate('dist/*.js')
+ * ```
  */
-export type GlobPath = Path | GloblinPath
+export function globAggregate(
+  pattern: string | Array<string | PatternInput>,
+  options?: GlobOptions
+): GlobAggregate {
+  if (options?.signal?.aborted) {
+    throw options.signal.reason ?? new Error('The operation was aborted')
+  }
+  if (options?.withFileTypes) {
+    throw new TypeError('globAggregate does not support withFileTypes')
+  }
+  if (options?.ignore && isIgnorePattern(options.ignore)) {
+    throw new TypeError('globAggregate does not support custom ignore objects')
+  }
+
+  return nativeGlobAggregate(pattern, toNativeOptions(options))
+}
 
 /**
- * Convert native PathData to GloblinPath objects.
- * This is much faster than creating PathScurry Path objects for each result.
+ * Count matches per directory in one native walk
  *
- * @param data - Native path data from Rust
- * @param cwd - Current working directory
- * @param stat - If true, isFile/isDirectory return actual values; otherwise return false (like PathScurry)
- * @returns Array of GloblinPath objects
+ * Each match counts toward the directory holding it, cut to its first `depth`
+ * segments, so `depth: 1` groups by top-level directory. Matches directly in
+ * cwd are counted under `.`. Paths never cross into JavaScript.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options (`withFileTypes` and custom ignore objects are not supported)
+ * @param depth - Number of leading directory segments to group by
+ * @returns One entry per directory with matches, sorted by directory
+ *
+ * @example
+ * ```ts
+ * import { globHistogram } from 'globlin'
+ *
+ * globHistogram('**', { nodir: true }, 1)
+ * // [{ dir: '.', count: 4 }, { dir: 'docs', count: 12 }, { dir: 'src', count: 230 }]
+ * ```
  */
-function convertToPathObjects(
-  data: NativePathData[],
-  cwd: string,
-  stat: boolean = false
-): GloblinPath[] {
-  return data.map(d => new GloblinPath(d.path, cwd, d.isDirectory, d.isFile, d.isSymlink, stat))
+export function globHistogram(
+  pattern: string | Array<string | PatternInput>,
+  options: GlobOptions | undefined,
+  depth: number
+): DirCount[] {
+  if (options?.signal?.aborted) {
+    throw options.signal.reason ?? new Error('The operation was aborted')
+  }
+  if (options?.withFileTypes) {
+    throw new TypeError('globHistogram does not support withFileTypes')
+  }
+  if (options?.ignore && isIgnorePattern(options.ignore)) {
+    throw new TypeError('globHistogram does not support custom ignore objects')
+  }
+
+  return nativeGlobHistogram(pattern, toNativeOptions(options), depth)
 }
 
 /**
- * LEGACY: Convert native PathData to full PathScurry Path objects.
- * This is the slow path (~16µs per result) that was used before optimization.
- * Kept for compatibility and when full PathScurry features are needed.
+ * Report which optimization path globSync would take, without walking
  *
- * @deprecated Use convertToPathObjects() instead for better performance.
+ * `Static` stats each literal path, `Shallow` reads cwd once, `MultiBase` walks
+ * each literal base directory separately, `PrefixScoped` walks once from the
+ * shared literal prefix and `FullWalk` walks once from cwd. Streams and
+ * `withFileTypes` always do a single walk.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options
+ * @returns The strategy, walk root and shared prefix
+ *
+ * @example
+ * ```ts
+ * import { globPlan } from 'globlin'
+ *
+ * globPlan('src/*.ts')
+ * // { strategy: 'PrefixScoped', walkRoot: '/project/src', prefix: 'src' }
+ * ```
  */
-function convertToFullPathObjects(
-  data: NativePathData[],
-  cwd: string,
-  performLstat: boolean = false
-): Path[] {
-  const scurry = new PathScurry(cwd)
-  return data.map(d => {
-    const p = scurry.cwd.resolve(d.path)
-    // If performLstat is true, call lstatSync to populate type information
-    // Otherwise, the Path object is returned without type info (saves syscalls)
-    if (performLstat) {
-      p.lstatSync()
-    }
-    return p
-  })
+export function globPlan(
+  pattern: string | Array<string | PatternInput>,
+  options?: GlobOptions
+): GlobPlan {
+  return nativeGlobPlan(pattern, toNativeOptions(options))
 }
 
-// Export for testing purposes
-export { convertToFullPathObjects }
+/**
+ * Return one page of matches, resuming after the cursor of the previous page
+ *
+ * Results come in sorted walk order, so no walker is kept alive between calls
+ * and the cursor is a plain string that can be stored or sent to a client.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options (`withFileTypes`, custom ignore objects and
+ *   `includeChildMatches: false` are not supported)
+ * @param cursor - `nextCursor` from the previous page; omit for the first page
+ * @param pageSize - Maximum number of results per page
+ * @returns The page's results and the cursor for the next page, if any
+ *
+ * @example
+ * ```ts
+ * import { globPage } from 'globlin'
+ *
+ * let cursor: string | undefined
+ * do {
+ *   const page = globPage('src/*.ts', { cwd }, cursor, 100)
+ *   render(page.results)
+ *   cursor = page.nextCursor
+ * } while (cursor)
+ * ```
+ */
+export function globPage(
+  pattern: string | Array<string | PatternInput>,
+  options: GlobOptions | undefined,
+  cursor: string | undefined,
+  pageSize: number
+): GlobPage {
+  if (options?.signal?.aborted) {
+    throw options.signal.reason ?? new Error('The operation was aborted')
+  }
+  if (options?.withFileTypes) {
+    throw new TypeError('globPage does not support withFileTypes')
+  }
+  if (options?.ignore && isIgnorePattern(options.ignore)) {
+    throw new TypeError('globPage does not support custom ignore objects')
+  }
+
+  return nativeGlobPage(pattern, toNativeOptions(options), cursor, pageSize)
+}
 
 /**
- * Synchronous glob pattern matching
+ * Return the first `count` matches in lexical order
+ *
+ * The whole tree is walked, since any path could sort first, but only the
+ * `count` smallest results are kept along the way. Memory stays bounded by
+ * `count`, and the answer is the same whatever order the walk visits entries in.
  *
  * @param pattern - Glob pattern or array of patterns
- * @param options - Glob options
- * @returns Array of matching file paths (or GloblinPath objects if withFileTypes: true)
+ * @param options - Glob options (`withFileTypes` and custom ignore objects are not supported)
+ * @param count - Maximum number of results to return
+ * @returns Up to `count` matching paths, sorted by code point
  *
- * @remarks
- * When `withFileTypes: true`, returns GloblinPath objects which are 85% faster to create
- * than PathScurry Path objects. GloblinPath provides the same common interface (isFile,
- * isDirectory, isSymbolicLink, fullpath, relative) using cached values from Rust.
- * Call `.toPath()` on any result if you need the full PathScurry Path.
+ * @example
+ * ```ts
+ * import { globSyncFirst } from 'globlin'
+ *
+ * globSyncFirst('logs/*.log', undefined, 3)
+ * // ['logs/2024-01-01.log', 'logs/2024-01-02.log', 'logs/2024-01-03.log']
+ * ```
  */
-export function globSync(
-  pattern: string | string[],
-  options: GlobOptionsWithFileTypesTrue
-): GloblinPath[]
-export function globSync(
-  pattern: string | string[],
-  options?: GlobOptionsWithFileTypesFalse
-): string[]
-export function globSync(
-  pattern: string | string[],
-  options?: GlobOptions
-): string[] | GloblinPath[]
-export function globSync(
-  pattern: string | string[],
-  options?: GlobOptions
-): string[] | GloblinPath[] {
-  // Check if signal is already aborted before starting
+export function globSyncFirst(
+  pattern: string | Array<string | PatternInput>,
+  options: GlobOptions | undefined,
+  count: number
+): string[] {
   if (options?.signal?.aborted) {
     throw options.signal.reason ?? new Error('The operation was aborted')
   }
-
-  const opts = toNativeOptions(options)
-  const cwd = opts.cwd ?? process.cwd()
-  const hasCustomIgnore = options?.ignore && isIgnorePattern(options.ignore)
-
-  // Handle withFileTypes option
   if (options?.withFileTypes) {
-    const data = nativeGlobSyncWithFileTypes(pattern, opts)
-    // Convert to GloblinPath objects (fast - uses cached type info from Rust)
-    let pathObjs = convertToPathObjects(data, cwd, options.stat)
-
-    // Apply custom ignore filter if present
-    if (hasCustomIgnore) {
-      pathObjs = applyCustomIgnoreFilterForGloblinPaths(pathObjs, options.ignore as IgnorePattern)
-    }
-
-    return pathObjs
+    throw new TypeError('globSyncFirst does not support withFileTypes')
   }
-
-  // Pass patterns directly to native implementation (supports both string and array)
-  let results = nativeGlobSync(pattern, opts)
-
-  // Apply custom ignore filter if present
-  if (hasCustomIgnore) {
-    results = applyCustomIgnoreFilter(results, options.ignore as IgnorePattern, cwd)
+  if (options?.ignore && isIgnorePattern(options.ignore)) {
+    throw new TypeError('globSyncFirst does not support custom ignore objects')
   }
 
-  return results
+  return nativeGlobSyncFirst(pattern, toNativeOptions(options), count)
 }
 
 /**
@@ -924,19 +2266,19 @@ export function globSync(
  * Call `.toPath()` on any result if you need the full PathScurry Path.
  */
 export async function glob(
-  pattern: string | string[],
+  pattern: string | Array<string | PatternInput>,
   options: GlobOptionsWithFileTypesTrue
 ): Promise<GloblinPath[]>
 export async function glob(
-  pattern: string | string[],
+  pattern: string | Array<string | PatternInput>,
   options?: GlobOptionsWithFileTypesFalse
 ): Promise<string[]>
 export async function glob(
-  pattern: string | string[],
+  pattern: string | Array<string | PatternInput>,
   options?: GlobOptions
 ): Promise<string[] | GloblinPath[]>
 export async function glob(
-  pattern: string | string[],
+  pattern: string | Array<string | PatternInput>,
   options?: GlobOptions
 ): Promise<string[] | GloblinPath[]> {
   // Check if signal is already aborted before starting
@@ -1012,7 +2354,7 @@ export async function glob(
           } else {
             // Apply custom ignore filter if present
             const finalResults = hasCustomIgnore
-              ? applyCustomIgnoreFilter(results, options.ignore as IgnorePattern, cwd)
+              ? applyCustomIgnoreFilter(results, options.ignore as IgnorePattern, cwd, options.resultPrefix)
               : results
             resolve(finalResults)
           }
@@ -1028,7 +2370,7 @@ export async function glob(
 
   // Apply custom ignore filter if present
   if (hasCustomIgnore) {
-    results = applyCustomIgnoreFilter(results, options.ignore as IgnorePattern, cwd)
+    results = applyCustomIgnoreFilter(results, options.ignore as IgnorePattern, cwd, options.resultPrefix)
   }
 
   return results
@@ -1096,6 +2438,81 @@ export function globStream(
   return stream
 }
 
+/**
+ * Streaming glob pattern matching that delivers results in batches
+ *
+ * Results cross from native code in arrays of up to `batchSize` paths (the
+ * last one holds the remainder), which avoids the per-result call overhead
+ * of streaming millions of matches. No batch is delivered when nothing matches.
+ *
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Glob options (`withFileTypes` and custom ignore objects are not supported)
+ * @param batchSize - Maximum number of results per batch (at least 1)
+ * @param onBatch - Called with each batch, in discovery order
+ * @returns Promise that resolves once every batch has been delivered
+ *
+ * @example
+ * ```ts
+ * import { globStreamBatched } from 'globlin'
+ *
+ * let count = 0
+ * await globStreamBatched('**', { nodir: true }, 1000, batch => {
+ *   count += batch.length
+ * })
+ * ```
+ */
+export function globStreamBatched(
+  pattern: string | Array<string | PatternInput>,
+  options: GlobOptions | undefined,
+  batchSize: number,
+  onBatch: (results: string[]) => void
+): Promise<void> {
+  if (options?.signal?.aborted) {
+    return Promise.reject(options.signal.reason ?? new Error('The operation was aborted'))
+  }
+  if (options?.withFileTypes) {
+    return Promise.reject(new TypeError('globStreamBatched does not support withFileTypes'))
+  }
+  if (options?.ignore && isIgnorePattern(options.ignore)) {
+    return Promise.reject(
+      new TypeError('globStreamBatched does not support custom ignore objects')
+    )
+  }
+
+  return new Promise((resolve, reject) => {
+    // The walk runs off-thread; batches can still be arriving after the native
+    // promise resolves with the total
+    let expected = -1
+    let delivered = 0
+    let failed = false
+    const receive = (results: string[]) => {
+      if (failed) return
+      try {
+        onBatch(results)
+      } catch (err) {
+        failed = true
+        reject(err)
+        return
+      }
+      delivered++
+      if (delivered === expected) resolve()
+    }
+
+    let walk: Promise<number>
+    try {
+      walk = nativeGlobStreamBatched(pattern, toNativeOptions(options), batchSize, receive)
+    } catch (err) {
+      reject(err)
+      return
+    }
+    walk.then(total => {
+      if (failed) return
+      expected = total
+      if (delivered === expected) resolve()
+    }, reject)
+  })
+}
+
 /**
  * Synchronous streaming glob pattern matching
  *
@@ -1141,15 +2558,35 @@ export function globStreamSync(
 /**
  * Async iterator for glob results
  *
+ * With `withFileTypes: true` the results are GloblinPath objects, read from a
+ * native iterator in a single traversal. The walk runs ahead of the consumer by
+ * at most one buffer of results and stops when iteration ends early.
+ *
  * @param pattern - Glob pattern or array of patterns
  * @param options - Glob options
- * @yields Matching file paths
+ * @yields Matching file paths (or GloblinPath objects if withFileTypes: true)
  */
+export function globIterate(
+  pattern: string | Array<string | PatternInput>,
+  options: GlobOptionsWithFileTypesTrue
+): AsyncGenerator<GloblinPath, void, void>
+export function globIterate(
+  pattern: string | Array<string | PatternInput>,
+  options?: GlobOptionsWithFileTypesFalse
+): AsyncGenerator<string, void, void>
+export function globIterate(
+  pattern: string | Array<string | PatternInput>,
+  options?: GlobOptions
+): AsyncGenerator<string | GloblinPath, void, void>
 export async function* globIterate(
-  pattern: string | string[],
+  pattern: string | Array<string | PatternInput>,
   options?: GlobOptions
-): AsyncGenerator<string, void, void> {
-  // For iteration, always return strings (ignore withFileTypes)
+): AsyncGenerator<string | GloblinPath, void, void> {
+  if (options?.withFileTypes) {
+    yield* iterateWithFileTypes(pattern, options)
+    return
+  }
+
   const opts: GlobOptionsWithFileTypesFalse = { ...options, withFileTypes: false }
   const results = await glob(pattern, opts)
   for (const result of results) {
@@ -1157,6 +2594,42 @@ export async function* globIterate(
   }
 }
 
+/**
+ * Pull typed results from the native iterator one batch at a time.
+ */
+async function* iterateWithFileTypes(
+  pattern: string | Array<string | PatternInput>,
+  options: GlobOptions
+): AsyncGenerator<GloblinPath, void, void> {
+  const throwIfAborted = () => {
+    if (options.signal?.aborted) {
+      throw options.signal.reason ?? new Error('The operation was aborted')
+    }
+  }
+  throwIfAborted()
+
+  const opts = toNativeOptions(options)
+  const cwd = opts.cwd ?? process.cwd()
+  const customIgnore = isIgnorePattern(options.ignore) ? options.ignore : undefined
+  const iterator = nativeGlobIterateWithFileTypes(pattern, opts)
+
+  try {
+    for (;;) {
+      const batch = await iterator.nextBatch()
+      throwIfAborted()
+      if (batch.length === 0) return
+
+      let pathObjs = convertToPathObjects(batch, cwd, options.stat)
+      if (customIgnore) {
+        pathObjs = applyCustomIgnoreFilterForGloblinPaths(pathObjs, customIgnore)
+      }
+      yield* pathObjs
+    }
+  } finally {
+    await iterator.close()
+  }
+}
+
 /**
  * Sync iterator for glob results
  *
@@ -1176,6 +2649,180 @@ export function* globIterateSync(
   }
 }
 
+/**
+ * Test whether a path matches the pattern(s) without touching the filesystem.
+ *
+ * The path is interpreted relative to cwd (or as absolute), the same way glob
+ * results are. A trailing `/` marks the path as a directory.
+ *
+ * @param path - The candidate path
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Matching options
+ * @returns True if any pattern matches the path
+ *
+ * @example
+ * ```ts
+ * import { matchesPath } from 'globlin'
+ *
+ * matchesPath('src/./main.ts', 'src/*.ts', { normalizeCandidate: true }) // true
+ * ```
+ */
+export function matchesPath(
+  path: string,
+  pattern: string | Array<string | PatternInput>,
+  options?: MatchOptions
+): boolean {
+  return nativeMatchesPath(path, pattern, options)
+}
+
+/**
+ * Test a path against several patterns with AND semantics, without walking.
+ *
+ * The path must match every include pattern and no exclude pattern. Each
+ * pattern is matched exactly as matchesPath would match it on its own, so an
+ * empty include list only checks the exclusions.
+ *
+ * @param path - The candidate path
+ * @param include - Patterns that must all match
+ * @param exclude - Patterns that must not match
+ * @param options - Matching options, applied to every pattern
+ * @returns True if all includes match and no exclude does
+ *
+ * @example
+ * ```ts
+ * import { matchesAll } from 'globlin'
+ *
+ * matchesAll('src/app.ts', ['src/*', '*/*.ts'], ['src/*.test.ts'])      // true
+ * matchesAll('src/app.test.ts', ['src/*', '*/*.ts'], ['src/*.test.ts']) // false
+ * ```
+ */
+export function matchesAll(
+  path: string,
+  include: string[],
+  exclude: string[],
+  options?: MatchOptions
+): boolean {
+  return nativeMatchesAll(path, include, exclude, options)
+}
+
+/**
+ * Explain why a path does or doesn't match the pattern(s), for debugging glob rules.
+ *
+ * Takes the same arguments as matchesPath. The report lists every pattern with
+ * whether it matched, the fast path that decided it (if any) and its compiled
+ * regex, plus whether dot rules or ignore patterns excluded the path.
+ *
+ * @param path - The candidate path
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Matching options
+ * @returns A report describing the match decision
+ *
+ * @example
+ * ```ts
+ * import { explainMatch } from 'globlin'
+ *
+ * explainMatch('.env', '*').reason
+ * // "'.env' has a dot segment that no pattern explicitly matches (set dot: true to include it)"
+ * ```
+ */
+export function explainMatch(
+  path: string,
+  pattern: string | Array<string | PatternInput>,
+  options?: MatchOptions
+): MatchExplanation {
+  return nativeExplainMatch(path, pattern, options)
+}
+
+/**
+ * Test whether a directory could contain matches, for custom directory walkers.
+ *
+ * Uses the same pruning logic as globlin's own walker: when this returns false,
+ * nothing at or below the directory can match and it need not be read.
+ *
+ * @param dirPath - Directory relative to cwd (`.` for cwd itself)
+ * @param pattern - Glob pattern or array of patterns
+ * @param options - Matching options
+ * @returns False if the directory can be pruned
+ *
+ * @example
+ * ```ts
+ * import { canContainMatch } from 'globlin'
+ *
+ * canContainMatch('src', 'src/lib/*.ts')  // true
+ * canContainMatch('test', 'src/lib/*.ts') // false
+ * ```
+ */
+export function canContainMatch(
+  dirPath: string,
+  pattern: string | Array<string | PatternInput>,
+  options?: MatchOptions
+): boolean {
+  return nativeCanContainMatch(dirPath, pattern, options)
+}
+
+/**
+ * Test whether a path would be excluded by ignore patterns, without walking.
+ *
+ * Applies the same ignore rules as a glob call with these options
+ * (`defaultIgnore`, `rootRelative`, ...); `ignore` replaces `options.ignore`.
+ *
+ * @param path - Path relative to cwd (`.` for cwd itself)
+ * @param ignore - Ignore pattern or patterns to test
+ * @param options - Glob options
+ * @returns Whether the path is ignored and whether its children are
+ *
+ * @example
+ * ```ts
+ * import { isIgnored } from 'globlin'
+ *
+ * isIgnored('dist/app.js', 'dist/**')
+ * // { ignored: true, childrenIgnored: false }
+ * isIgnored('dist', 'dist/**')
+ * // { ignored: true, childrenIgnored: true }
+ * ```
+ */
+export function isIgnored(
+  path: string,
+  ignore: string | string[],
+  options?: GlobOptions
+): IgnoreCheck {
+  const patterns = typeof ignore === 'string' ? [ignore] : ignore
+  return nativeIsIgnored(path, patterns, toNativeOptions({ ...options, ignore: undefined }))
+}
+
+/**
+ * Ignore patterns compiled once and reused across glob calls.
+ *
+ * Pass the set as the `ignoreSet` option instead of repeating a long `ignore`
+ * list, so the patterns are compiled only once. The options given here
+ * (`defaultIgnore`, `rootRelative`, `noext`, ...) apply to the compiled patterns.
+ *
+ * @example
+ * ```ts
+ * import { IgnoreSet, globSync } from 'globlin'
+ *
+ * const ignoreSet = new IgnoreSet(['dist/**', '*.log'])
+ * const sources = globSync('src/**', { ignoreSet })
+ * const tests = globSync('test/**', { ignoreSet })
+ * ```
+ */
+export class IgnoreSet {
+  /** @internal The compiled native filter */
+  readonly native: NativeIgnoreSet
+
+  /**
+   * @param patterns - Ignore pattern or patterns
+   * @param options - Glob options the patterns are compiled with
+   */
+  constructor(patterns: string | string[], options?: GlobOptions) {
+    const list = typeof patterns === 'string' ? [patterns] : patterns
+    this.native = new NativeIgnoreSetClass(
+      list,
+      toNativeOptions({ ...options, ignore: undefined, ignoreSet: undefined })
+    )
+  }
+}
+
 /**
  * Check if a pattern contains any magic glob characters.
  * Takes into account escaped characters - escaped magic chars don't count.
@@ -1198,17 +2845,30 @@ export function hasMagic(pattern: string | string[], options?: GlobOptions): boo
   return false
 }
 
+/**
+ * Get the built-in ignore patterns used by the `defaultIgnore` option.
+ *
+ * @returns The default ignore patterns
+ */
+export function defaultIgnorePatterns(): string[] {
+  return nativeDefaultIgnorePatterns()
+}
+
 /**
  * Escape magic glob characters in a pattern.
  * After escaping, the pattern will match the literal string.
  *
+ * Braces are left alone, as in glob; pass `magicalBraces: true` to escape
+ * them too so the result is never brace-expanded.
+ *
  * @param pattern - Pattern to escape
- * @param options - Glob options (windowsPathsNoEscape affects escape style)
+ * @param options - Glob options (windowsPathsNoEscape affects escape style, magicalBraces escapes braces)
  * @returns Escaped pattern
  */
 export function escape(pattern: string, options?: GlobOptions): string {
   const windowsPathsNoEscape = options?.windowsPathsNoEscape ?? false
-  return nativeEscape(pattern, windowsPathsNoEscape)
+  const magicalBraces = options?.magicalBraces ?? false
+  return nativeEscape(pattern, windowsPathsNoEscape, magicalBraces)
 }
 
 /**
@@ -1224,6 +2884,54 @@ export function unescape(pattern: string, options?: GlobOptions): string {
   return nativeUnescape(pattern, windowsPathsNoEscape)
 }
 
+/**
+ * Escape magic glob characters in each of several patterns.
+ * Same as calling `escape()` on each, but crosses into native code once.
+ *
+ * @param patterns - Patterns to escape
+ * @param options - Glob options (windowsPathsNoEscape affects escape style, magicalBraces escapes braces)
+ * @returns Escaped patterns, in the same order
+ */
+export function escapeAll(patterns: string[], options?: GlobOptions): string[] {
+  const windowsPathsNoEscape = options?.windowsPathsNoEscape ?? false
+  const magicalBraces = options?.magicalBraces ?? false
+  return nativeEscapeAll(patterns, windowsPathsNoEscape, magicalBraces)
+}
+
+/**
+ * Unescape magic glob characters in each of several patterns.
+ * Same as calling `unescape()` on each, but crosses into native code once.
+ *
+ * @param patterns - Patterns to unescape
+ * @param options - Glob options (windowsPathsNoEscape affects unescape style)
+ * @returns Unescaped patterns, in the same order
+ */
+export function unescapeAll(patterns: string[], options?: GlobOptions): string[] {
+  const windowsPathsNoEscape = options?.windowsPathsNoEscape ?? false
+  return nativeUnescapeAll(patterns, windowsPathsNoEscape)
+}
+
+/**
+ * Expand a pattern into the concrete patterns its braces stand for.
+ * This is the list a glob matches against, so it shows whether brace syntax
+ * does what was intended before running a walk.
+ *
+ * @param pattern - The glob pattern to expand
+ * @param options - Glob options (`nobrace` and `maxPatterns` apply)
+ * @returns The expanded patterns, in expansion order
+ *
+ * @example
+ * ```ts
+ * import { expandPattern } from 'globlin'
+ *
+ * expandPattern('{src,test}/*.{js,ts}')
+ * // ['src/*.js', 'src/*.ts', 'test/*.js', 'test/*.ts']
+ * ```
+ */
+export function expandPattern(pattern: string, options?: GlobOptions): string[] {
+  return nativeExpandPattern(pattern, toNativeOptions(options))
+}
+
 /**
  * Analyze a pattern for potential issues and return warnings.
  * This is useful for providing helpful feedback about common mistakes.
@@ -1248,6 +2956,8 @@ export function unescape(pattern: string, options?: GlobOptions): string {
  * - Backslash path separators on Windows without windowsPathsNoEscape
  * - Performance issues (multiple globstars, redundant patterns)
  * - Trailing spaces in patterns
+ * - Parts that can never match (reversed ranges like `[z-a]`, empty `{}`)
+ * - `..` directly above an absolute root (`/../foo` is the same as `/foo`)
  * - Empty patterns
  * - Null bytes in patterns
  */
@@ -1278,6 +2988,62 @@ export function analyzePatterns(patterns: string[], options?: GlobOptions): Patt
   return nativeAnalyzePatterns(patterns, windowsPathsNoEscape, platform)
 }
 
+/**
+ * Analyze multiple patterns and collapse warnings of the same type and reason
+ * into one entry with a count, so a mistake repeated across many patterns is
+ * reported once.
+ *
+ * @param patterns - Array of glob patterns to analyze
+ * @param options - Options affecting analysis (windowsPathsNoEscape, platform)
+ * @returns One entry per warning type and reason, in order of first occurrence
+ *
+ * @example
+ * ```ts
+ * import { analyzePatternsGrouped } from 'globlin'
+ *
+ * const groups = analyzePatternsGrouped(['*.js   ', 'src', '*.ts   '])
+ * // [{ warningType: 'trailing_spaces', count: 2, patterns: ['*.js   ', '*.ts   '], ... }]
+ * ```
+ */
+export function analyzePatternsGrouped(
+  patterns: string[],
+  options?: GlobOptions
+): GroupedPatternWarning[] {
+  const windowsPathsNoEscape = options?.windowsPathsNoEscape ?? false
+  const platform = options?.platform
+  return nativeAnalyzePatternsGrouped(patterns, windowsPathsNoEscape, platform)
+}
+
+/**
+ * Set how many compiled patterns are cached between glob calls
+ *
+ * The cache evicts the least recently used pattern once full, and lowering the
+ * capacity evicts immediately. Long-running processes that see many unique
+ * patterns can use this to bound memory.
+ *
+ * @param capacity - Maximum number of cached patterns (at least 1, default 1024)
+ */
+export function setPatternCacheCapacity(capacity: number): void {
+  nativeSetPatternCacheCapacity(capacity)
+}
+
+/**
+ * Get the size, capacity and hit/miss counts of the compiled pattern cache
+ *
+ * @returns Statistics for the pattern cache
+ *
+ * @example
+ * ```ts
+ * import { patternCacheStats } from 'globlin'
+ *
+ * const { hits, misses } = patternCacheStats()
+ * console.log(`hit rate: ${hits / (hits + misses)}`)
+ * ```
+ */
+export function patternCacheStats(): PatternCacheStats {
+  return nativePatternCacheStats()
+}
+
 /**
  * Glob class for reusable glob operations
  *
@@ -1349,7 +3115,9 @@ export class Glob {
   }
 
   iterate(): AsyncGenerator<string, void, void> {
-    return globIterate(this.pattern, this.options)
+    // For the Glob class, always return strings (ignore withFileTypes)
+    const opts: GlobOptionsWithFileTypesFalse = { ...this.options, withFileTypes: false }
+    return globIterate(this.pattern, opts)
   }
 
   iterateSync(): Generator<string, void, void> {
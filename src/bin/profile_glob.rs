@@ -13,6 +13,11 @@
 //!
 //! This binary reimplements the glob logic using only walkdir + regex to avoid
 //! NAPI dependencies that require Node.js runtime.
+//!
+//! Comparing globlin's own walker strategies (serial, parallel, native I/O, GCD)
+//! needs the real `Glob`; that comparison lives in a criterion bench built on
+//! `Glob::walk_sync_timed` instead:
+//!   cargo bench --bench walker_strategies
 
 use std::env;
 use std::path::PathBuf;
@@ -1,8 +1,9 @@
 use lru::LruCache;
 use std::hash::{Hash, Hasher};
+use std::io;
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, OnceLock, RwLock};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 use std::time::{Duration, Instant};
 
 use crate::pattern::{Pattern, PatternOptions};
@@ -28,20 +29,24 @@ const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(5);
 struct PatternCacheKey {
     pattern: String,
     noext: bool,
+    no_negation: bool,
     windows_paths_no_escape: bool,
     nocase: bool,
     nobrace: bool,
     platform: String,
+    separator: Option<char>,
 }
 
 impl Hash for PatternCacheKey {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.pattern.hash(state);
         self.noext.hash(state);
+        self.no_negation.hash(state);
         self.windows_paths_no_escape.hash(state);
         self.nocase.hash(state);
         self.nobrace.hash(state);
         self.platform.hash(state);
+        self.separator.hash(state);
     }
 }
 
@@ -50,22 +55,72 @@ impl PatternCacheKey {
         Self {
             pattern: pattern.to_string(),
             noext: options.noext,
+            no_negation: options.no_negation,
             windows_paths_no_escape: options.windows_paths_no_escape,
             nocase: options.nocase,
             nobrace: options.nobrace,
             platform: options.platform.clone().unwrap_or_default(),
+            separator: options.separator,
+        }
+    }
+}
+
+/// A cache slot that is filled by whichever thread compiles the pattern first.
+/// Other threads asking for the same pattern wait on the slot instead of
+/// compiling it again.
+type PatternSlot = Arc<OnceLock<Pattern>>;
+
+/// Compiled patterns in least-recently-used order, with hit/miss counters.
+struct PatternCache {
+    entries: LruCache<PatternCacheKey, PatternSlot>,
+    hits: u64,
+    misses: u64,
+}
+
+impl PatternCache {
+    fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: LruCache::new(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Get the slot for a pattern, marking it as most recently used.
+    ///
+    /// An existing slot counts as a hit even if its pattern is still being
+    /// compiled. Otherwise an empty slot is added, evicting the least recently
+    /// used one when full, and the lookup counts as a miss.
+    fn slot(&mut self, key: PatternCacheKey) -> PatternSlot {
+        if let Some(slot) = self.entries.get(&key) {
+            self.hits += 1;
+            return Arc::clone(slot);
+        }
+        self.misses += 1;
+        let slot = PatternSlot::default();
+        self.entries.put(key, Arc::clone(&slot));
+        slot
+    }
+
+    fn stats(&self) -> PatternCacheStats {
+        PatternCacheStats {
+            size: self.entries.len() as u32,
+            capacity: self.entries.cap().get() as u32,
+            hits: self.hits as f64,
+            misses: self.misses as f64,
         }
     }
 }
 
 /// Global pattern cache instance.
-/// Uses RwLock for lock-free reads - multiple readers can access simultaneously.
-static PATTERN_CACHE: OnceLock<RwLock<LruCache<PatternCacheKey, Pattern>>> = OnceLock::new();
+/// Lookups move entries to the front of the LRU list, so every access takes the lock
+/// exclusively, but only long enough to fetch a slot; compilation happens outside it.
+static PATTERN_CACHE: OnceLock<Mutex<PatternCache>> = OnceLock::new();
 
 /// Initialize the global pattern cache with the default size.
-fn get_cache() -> &'static RwLock<LruCache<PatternCacheKey, Pattern>> {
+fn get_cache() -> &'static Mutex<PatternCache> {
     PATTERN_CACHE.get_or_init(|| {
-        RwLock::new(LruCache::new(
+        Mutex::new(PatternCache::new(
             NonZeroUsize::new(DEFAULT_CACHE_SIZE).unwrap(),
         ))
     })
@@ -73,9 +128,10 @@ fn get_cache() -> &'static RwLock<LruCache<PatternCacheKey, Pattern>> {
 
 /// Get a compiled pattern from the cache, or compile and cache it if not found.
 ///
-/// Uses a read-optimized locking strategy:
-/// 1. First try a read lock (allows concurrent readers)
-/// 2. Only upgrade to write lock if cache miss
+/// The lock is released while compiling, so a slow pattern doesn't block
+/// lookups of other patterns. Threads that ask for a pattern another thread is
+/// already compiling wait for that result, so each pattern is compiled once.
+/// When the cache is full, the least recently used pattern is evicted.
 ///
 /// This function provides significant speedup when the same patterns are used
 /// repeatedly, which is common in glob operations with brace expansion or
@@ -90,65 +146,51 @@ fn get_cache() -> &'static RwLock<LruCache<PatternCacheKey, Pattern>> {
 pub fn get_or_compile_pattern(pattern: &str, options: &PatternOptions) -> Pattern {
     let key = PatternCacheKey::new(pattern, options);
 
-    let cache = get_cache();
-
-    // Try to get from cache with READ lock (allows concurrent readers)
-    {
-        let guard = cache.read().unwrap();
-        if let Some(cached) = guard.peek(&key) {
-            return cached.clone();
-        }
-    }
-
-    // Cache miss - compile the pattern (outside lock)
-    let compiled = Pattern::with_pattern_options(pattern, options.clone());
-
-    // Store in cache with WRITE lock
-    {
-        let mut guard = cache.write().unwrap();
-        // Double-check: another thread might have compiled it
-        if let Some(cached) = guard.peek(&key) {
-            return cached.clone();
-        }
-        guard.put(key, compiled.clone());
-    }
+    let slot = get_cache().lock().unwrap().slot(key);
 
-    compiled
+    // Compile outside the lock; concurrent callers for this key block here
+    // until the first one finishes
+    slot.get_or_init(|| Pattern::with_pattern_options(pattern, options.clone()))
+        .clone()
 }
 
 /// Get the current number of cached patterns.
 /// Useful for debugging and monitoring.
 #[allow(dead_code)]
 pub fn cache_size() -> usize {
-    let cache = get_cache();
-    let guard = cache.read().unwrap();
-    guard.len()
+    get_cache().lock().unwrap().entries.len()
 }
 
 /// Clear the pattern cache.
 /// This is mainly useful for testing.
 #[allow(dead_code)]
 pub fn clear_cache() {
-    let cache = get_cache();
-    let mut guard = cache.write().unwrap();
-    guard.clear();
+    get_cache().lock().unwrap().entries.clear();
 }
 
-/// Get cache statistics for monitoring.
-#[allow(dead_code)]
-pub struct CacheStats {
-    pub size: usize,
-    pub capacity: usize,
+/// Change how many compiled patterns the cache keeps, evicting the least
+/// recently used ones if it now holds too many.
+pub fn set_cache_capacity(capacity: NonZeroUsize) {
+    get_cache().lock().unwrap().entries.resize(capacity);
 }
 
-#[allow(dead_code)]
-pub fn get_cache_stats() -> CacheStats {
-    let cache = get_cache();
-    let guard = cache.read().unwrap();
-    CacheStats {
-        size: guard.len(),
-        capacity: guard.cap().get(),
-    }
+/// Pattern cache statistics, returned by `patternCacheStats`.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternCacheStats {
+    /// Number of compiled patterns currently cached
+    pub size: u32,
+    /// Maximum number of compiled patterns kept
+    pub capacity: u32,
+    /// Lookups answered from the cache
+    pub hits: f64,
+    /// Lookups that had to compile the pattern
+    pub misses: f64,
+}
+
+/// Get cache statistics for monitoring.
+pub fn get_cache_stats() -> PatternCacheStats {
+    get_cache().lock().unwrap().stats()
 }
 
 // ============================================================================
@@ -166,6 +208,8 @@ pub struct CachedDirEntry {
     pub is_file: bool,
     /// True if this is a symbolic link
     pub is_symlink: bool,
+    /// Size of a regular file (the target's, for a followed symlink), if known
+    pub size: Option<u64>,
 }
 
 /// A cached directory listing with timestamp for TTL-based invalidation.
@@ -222,6 +266,12 @@ pub fn read_dir_cached(path: &Path, follow_symlinks: bool) -> Vec<CachedDirEntry
     read_dir_cached_with_ttl(path, follow_symlinks, DEFAULT_CACHE_TTL)
 }
 
+/// Like [`read_dir_cached`], but fails if the directory cannot be read.
+/// Failed reads are not cached.
+pub fn try_read_dir_cached(path: &Path, follow_symlinks: bool) -> io::Result<Vec<CachedDirEntry>> {
+    try_read_dir_cached_with_ttl(path, follow_symlinks, DEFAULT_CACHE_TTL)
+}
+
 /// Read a directory's contents with a custom TTL.
 ///
 /// Uses a read-optimized locking strategy:
@@ -237,6 +287,15 @@ pub fn read_dir_cached_with_ttl(
     follow_symlinks: bool,
     ttl: Duration,
 ) -> Vec<CachedDirEntry> {
+    try_read_dir_cached_with_ttl(path, follow_symlinks, ttl).unwrap_or_default()
+}
+
+/// Read a directory's contents with a custom TTL, failing if it cannot be read.
+fn try_read_dir_cached_with_ttl(
+    path: &Path,
+    follow_symlinks: bool,
+    ttl: Duration,
+) -> io::Result<Vec<CachedDirEntry>> {
     let cache = get_readdir_cache();
 
     // Create a canonical cache key to handle relative vs absolute paths
@@ -248,13 +307,13 @@ pub fn read_dir_cached_with_ttl(
         if let Some(cached) = guard.peek(&cache_key) {
             if !cached.is_expired(ttl) {
                 // Zero-copy return via Arc clone (just increments ref count)
-                return cached.entries.to_vec();
+                return Ok(cached.entries.to_vec());
             }
         }
     }
 
     // Cache miss or expired - read the directory (outside lock)
-    let entries = read_dir_uncached(path, follow_symlinks);
+    let entries = read_dir_uncached(path, follow_symlinks)?;
 
     // Store in cache with WRITE lock
     {
@@ -262,22 +321,19 @@ pub fn read_dir_cached_with_ttl(
         // Double-check: another thread might have populated it
         if let Some(cached) = guard.peek(&cache_key) {
             if !cached.is_expired(ttl) {
-                return cached.entries.to_vec();
+                return Ok(cached.entries.to_vec());
             }
         }
         guard.put(cache_key, CachedDirListing::new(entries.clone()));
     }
 
-    entries
+    Ok(entries)
 }
 
 /// Read a directory without using the cache.
 /// This is the underlying implementation used by the cache.
-fn read_dir_uncached(path: &Path, follow_symlinks: bool) -> Vec<CachedDirEntry> {
-    let read_dir = match std::fs::read_dir(path) {
-        Ok(rd) => rd,
-        Err(_) => return Vec::new(),
-    };
+fn read_dir_uncached(path: &Path, follow_symlinks: bool) -> io::Result<Vec<CachedDirEntry>> {
+    let read_dir = std::fs::read_dir(path)?;
 
     let mut entries = Vec::new();
 
@@ -295,7 +351,7 @@ fn read_dir_uncached(path: &Path, follow_symlinks: bool) -> Vec<CachedDirEntry>
         let entry_path = entry.path();
 
         // Get file type
-        let (is_dir, is_file, is_symlink) = if follow_symlinks {
+        let (is_dir, is_file, is_symlink, size) = if follow_symlinks {
             // When following symlinks, we need to:
             // 1. Check if it's a symlink (via symlink_metadata)
             // 2. Get the TARGET's type (via fs::metadata which follows symlinks)
@@ -311,11 +367,12 @@ fn read_dir_uncached(path: &Path, follow_symlinks: bool) -> Vec<CachedDirEntry>
             match std::fs::metadata(&entry_path) {
                 Ok(meta) => {
                     let ft = meta.file_type();
-                    (ft.is_dir(), ft.is_file(), is_symlink)
+                    let size = ft.is_file().then_some(meta.len());
+                    (ft.is_dir(), ft.is_file(), is_symlink, size)
                 }
                 Err(_) => {
                     // Broken symlink or permission error
-                    (false, false, is_symlink)
+                    (false, false, is_symlink, None)
                 }
             }
         } else {
@@ -323,9 +380,10 @@ fn read_dir_uncached(path: &Path, follow_symlinks: bool) -> Vec<CachedDirEntry>
             match entry_path.symlink_metadata() {
                 Ok(meta) => {
                     let ft = meta.file_type();
-                    (ft.is_dir(), ft.is_file(), ft.is_symlink())
+                    let size = ft.is_file().then_some(meta.len());
+                    (ft.is_dir(), ft.is_file(), ft.is_symlink(), size)
                 }
-                Err(_) => (false, false, false),
+                Err(_) => (false, false, false, None),
             }
         };
 
@@ -334,10 +392,11 @@ fn read_dir_uncached(path: &Path, follow_symlinks: bool) -> Vec<CachedDirEntry>
             is_dir,
             is_file,
             is_symlink,
+            size,
         });
     }
 
-    entries
+    Ok(entries)
 }
 
 /// Get the current number of cached directory listings.
@@ -661,7 +720,7 @@ mod tests {
         symlink(base.join("real_file.txt"), base.join("link_to_file")).unwrap();
 
         // Test the read_dir_uncached function directly for symlink behavior
-        let entries = read_dir_uncached(base, true);
+        let entries = read_dir_uncached(base, true).unwrap();
 
         let link_to_file = entries.iter().find(|e| e.name == "link_to_file").unwrap();
         assert!(link_to_file.is_symlink, "Should be detected as symlink");
@@ -877,6 +936,129 @@ mod tests {
         // Just verify the stats function works
         let stats = get_cache_stats();
         assert!(stats.size <= stats.capacity);
-        assert_eq!(stats.capacity, DEFAULT_CACHE_SIZE);
+        assert_eq!(stats.capacity as usize, DEFAULT_CACHE_SIZE);
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let options = default_options();
+        let key = |p: &str| PatternCacheKey::new(p, &options);
+        let mut cache = PatternCache::new(NonZeroUsize::new(2).unwrap());
+        let insert = |cache: &mut PatternCache, p: &str| {
+            cache.slot(key(p)).get_or_init(|| Pattern::new(p));
+        };
+
+        insert(&mut cache, "a");
+        insert(&mut cache, "b");
+        // Using `a` makes `b` the least recently used
+        assert!(cache.entries.get(&key("a")).is_some());
+        insert(&mut cache, "c");
+
+        assert!(!cache.entries.contains(&key("b")));
+        assert_eq!(
+            cache.entries.get(&key("a")).unwrap().get().unwrap().raw(),
+            "a"
+        );
+        assert_eq!(
+            cache.entries.get(&key("c")).unwrap().get().unwrap().raw(),
+            "c"
+        );
+
+        // Shrinking evicts from the least recently used end as well
+        cache.entries.resize(NonZeroUsize::new(1).unwrap());
+        assert!(!cache.entries.contains(&key("a")));
+        assert!(cache.entries.contains(&key("c")));
+    }
+
+    #[test]
+    fn test_cache_tracks_hits_and_misses() {
+        let options = default_options();
+        let key = |p: &str| PatternCacheKey::new(p, &options);
+        let mut cache = PatternCache::new(NonZeroUsize::new(4).unwrap());
+
+        cache.slot(key("*.js")).get_or_init(|| Pattern::new("*.js"));
+        assert!(cache.slot(key("*.js")).get().is_some());
+        assert!(cache.slot(key("*.js")).get().is_some());
+
+        assert_eq!(
+            cache.stats(),
+            PatternCacheStats {
+                size: 1,
+                capacity: 4,
+                hits: 2.0,
+                misses: 1.0,
+            }
+        );
+
+        // The global cache counts through get_or_compile_pattern
+        let before = get_cache_stats();
+        let pattern = "**/*.stats_test_unique_ext";
+        get_or_compile_pattern(pattern, &options);
+        get_or_compile_pattern(pattern, &options);
+        let after = get_cache_stats();
+        assert!(after.misses > before.misses);
+        assert!(after.hits > before.hits);
+    }
+
+    #[test]
+    fn test_concurrent_compiles_share_one_slot() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Barrier;
+        use std::thread;
+
+        const THREADS: usize = 16;
+        const PATTERNS: usize = 64;
+
+        // Every thread asks for the same patterns in a different order, all
+        // starting at once, so the same keys are contended from many threads
+        let barrier = Arc::new(Barrier::new(THREADS));
+        let matched = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let barrier = Arc::clone(&barrier);
+                let matched = Arc::clone(&matched);
+                thread::spawn(move || {
+                    let options = PatternOptions::default();
+                    barrier.wait();
+                    for j in 0..PATTERNS {
+                        let n = (j + t * 7) % PATTERNS;
+                        let pattern = format!("a/**/shared_slot_{n}.+(x|y)");
+                        let compiled = get_or_compile_pattern(&pattern, &options);
+                        assert_eq!(compiled.raw(), pattern);
+                        assert!(compiled.matches(&format!("a/deep/dir/shared_slot_{n}.xy")));
+                        assert!(!compiled.matches(&format!("c/shared_slot_{n}.x")));
+                        matched.fetch_add(1, Ordering::Relaxed);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(matched.load(Ordering::Relaxed), THREADS * PATTERNS);
+
+        // A slot handed out before compilation finishes is filled exactly once
+        let options = default_options();
+        let key = PatternCacheKey::new("slot_once_*.js", &options);
+        let mut cache = PatternCache::new(NonZeroUsize::new(4).unwrap());
+        let first = cache.slot(key.clone());
+        let second = cache.slot(key);
+        assert!(Arc::ptr_eq(&first, &second));
+        let compiles = AtomicUsize::new(0);
+        thread::scope(|s| {
+            for slot in [&first, &second] {
+                let compiles = &compiles;
+                s.spawn(move || {
+                    slot.get_or_init(|| {
+                        compiles.fetch_add(1, Ordering::SeqCst);
+                        Pattern::new("slot_once_*.js")
+                    });
+                });
+            }
+        });
+        assert_eq!(compiles.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.stats().misses, 1.0);
+        assert_eq!(cache.stats().hits, 1.0);
     }
 }
@@ -0,0 +1,539 @@
+//! Pluggable filesystem access for the walker.
+//!
+//! By default the walker reads the real filesystem through its own optimized
+//! backends. Passing a [`FileSystem`] through `WalkOptions::file_system` (or
+//! `Glob::with_file_system`) routes every directory listing and metadata lookup
+//! of the walk through it instead, so globs can match the virtual paths of any
+//! hierarchical source: the contents of a zip archive, an in-memory tree, a
+//! remote listing.
+//!
+//! # Example
+//!
+//! An archive-backed filesystem only needs to list a directory and describe a
+//! path. Here the archive's member names are kept in a set:
+//!
+//! ```
+//! use std::collections::BTreeSet;
+//! use std::io;
+//! use std::path::{Path, PathBuf};
+//! use std::sync::Arc;
+//!
+//! use globlin::fs::{FileSystem, FsEntry, FsMetadata};
+//! use globlin::glob::Glob;
+//! use globlin::options::GlobOptions;
+//!
+//! /// Member paths of an archive, mounted at `/archive`.
+//! #[derive(Debug)]
+//! struct Archive {
+//!     files: BTreeSet<PathBuf>,
+//! }
+//!
+//! impl Archive {
+//!     fn is_dir(&self, path: &Path) -> bool {
+//!         self.files.iter().any(|file| file.starts_with(path) && file != path)
+//!     }
+//! }
+//!
+//! impl FileSystem for Archive {
+//!     fn read_dir(&self, path: &Path) -> io::Result<Vec<FsEntry>> {
+//!         if !self.is_dir(path) {
+//!             return Err(io::ErrorKind::NotFound.into());
+//!         }
+//!         let mut names = BTreeSet::new();
+//!         for file in &self.files {
+//!             if let Ok(rest) = file.strip_prefix(path) {
+//!                 if let Some(name) = rest.components().next() {
+//!                     names.insert(name.as_os_str().to_string_lossy().into_owned());
+//!                 }
+//!             }
+//!         }
+//!         Ok(names
+//!             .into_iter()
+//!             .map(|name| {
+//!                 let is_dir = self.is_dir(&path.join(&name));
+//!                 FsEntry::new(name, FsMetadata::new(is_dir, !is_dir, false))
+//!             })
+//!             .collect())
+//!     }
+//!
+//!     fn metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+//!         if self.files.contains(path) {
+//!             Ok(FsMetadata::new(false, true, false))
+//!         } else if self.is_dir(path) {
+//!             Ok(FsMetadata::new(true, false, false))
+//!         } else {
+//!             Err(io::ErrorKind::NotFound.into())
+//!         }
+//!     }
+//! }
+//!
+//! let archive = Archive {
+//!     files: ["/archive/src/main.rs", "/archive/src/lib.rs", "/archive/README.md"]
+//!         .into_iter()
+//!         .map(PathBuf::from)
+//!         .collect(),
+//! };
+//! let options = GlobOptions {
+//!     cwd: Some("/archive".to_string()),
+//!     ..Default::default()
+//! };
+//! let glob = Glob::new("**/*.rs".to_string(), options).with_file_system(Arc::new(archive));
+//!
+//! let mut results = glob.walk_sync();
+//! results.sort();
+//! assert_eq!(results, ["src/lib.rs", "src/main.rs"]);
+//! ```
+
+use std::fmt::Debug;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::util::{has_hidden_attribute, normalize_path_lexically};
+
+/// The type of a path, as reported by a [`FileSystem`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FsMetadata {
+    /// The path is a directory
+    pub is_dir: bool,
+    /// The path is a regular file
+    pub is_file: bool,
+    /// The path is a symbolic link
+    pub is_symlink: bool,
+    /// Size in bytes, if known. Used for the aggregate's `totalSize`.
+    pub len: Option<u64>,
+}
+
+impl FsMetadata {
+    pub fn new(is_dir: bool, is_file: bool, is_symlink: bool) -> Self {
+        Self {
+            is_dir,
+            is_file,
+            is_symlink,
+            len: None,
+        }
+    }
+
+    /// Set the size of the path in bytes.
+    pub fn with_len(mut self, len: u64) -> Self {
+        self.len = Some(len);
+        self
+    }
+}
+
+impl From<std::fs::Metadata> for FsMetadata {
+    fn from(metadata: std::fs::Metadata) -> Self {
+        Self::from(metadata.file_type()).with_len(metadata.len())
+    }
+}
+
+impl From<std::fs::FileType> for FsMetadata {
+    fn from(file_type: std::fs::FileType) -> Self {
+        Self::new(
+            file_type.is_dir(),
+            file_type.is_file(),
+            file_type.is_symlink(),
+        )
+    }
+}
+
+/// One entry of a directory listing returned by [`FileSystem::read_dir`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FsEntry {
+    /// File name (not full path)
+    pub name: String,
+    /// The entry's own type; a symlink is reported as a symlink, not as its target
+    pub metadata: FsMetadata,
+}
+
+impl FsEntry {
+    pub fn new(name: impl Into<String>, metadata: FsMetadata) -> Self {
+        Self {
+            name: name.into(),
+            metadata,
+        }
+    }
+}
+
+/// Source of directory listings and path metadata for a walk.
+///
+/// Paths handed to the methods are the walk root joined with entry names, so a
+/// virtual filesystem decides its own layout by choosing the root (e.g. the
+/// glob's `cwd`). Errors are treated like unreadable or missing paths on disk:
+/// the path is skipped.
+pub trait FileSystem: Debug + Send + Sync {
+    /// List the entries of a directory, in the order they should be walked.
+    /// `.` and `..` must not be included.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<FsEntry>>;
+
+    /// The type of `path`, following symbolic links.
+    fn metadata(&self, path: &Path) -> io::Result<FsMetadata>;
+
+    /// The type of `path` itself, without following a final symbolic link.
+    ///
+    /// Defaults to [`FileSystem::metadata`], which is right for sources
+    /// without symlinks.
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        self.metadata(path)
+    }
+
+    /// The absolute path `path` resolves to, with every symbolic link resolved.
+    /// Used by `restrictToCwd` and `canonicalOnly`.
+    ///
+    /// Defaults to `path` with `.` and `..` dropped, once [`FileSystem::metadata`]
+    /// finds it, which is right for sources without symlinks.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.metadata(path).map(|_| normalize_path_lexically(path))
+    }
+
+    /// The target of the symbolic link at `path`, as stored in the link.
+    ///
+    /// Defaults to an `InvalidInput` error, as for a path that is not a symlink.
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        let _ = path;
+        Err(io::ErrorKind::InvalidInput.into())
+    }
+
+    /// Whether the directory at `path` has no entries, for `detectEmptyDirs`.
+    ///
+    /// Defaults to listing it with [`FileSystem::read_dir`].
+    fn is_empty_dir(&self, path: &Path) -> io::Result<bool> {
+        self.read_dir(path).map(|entries| entries.is_empty())
+    }
+
+    /// Whether `path` itself is marked hidden by an attribute rather than a
+    /// leading dot, for the `hidden` option.
+    ///
+    /// Defaults to `false`, for sources without such an attribute.
+    fn is_hidden(&self, path: &Path) -> bool {
+        let _ = path;
+        false
+    }
+
+    /// The device `path` lives on, following symbolic links, for `oneFileSystem`.
+    /// Directories whose device is unknown are never pruned.
+    ///
+    /// Defaults to `None`, so the whole source counts as a single device.
+    fn device(&self, path: &Path) -> Option<u64> {
+        let _ = path;
+        None
+    }
+}
+
+/// The real filesystem, read through `std::fs`.
+///
+/// The walker doesn't need it (its built-in backends are faster), but it is a
+/// starting point for filesystems that overlay or filter the real one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdFileSystem;
+
+impl FileSystem for StdFileSystem {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<FsEntry>> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            // Skip non-UTF8 names, like the cached walker
+            let Ok(name) = entry.file_name().into_string() else {
+                continue;
+            };
+            entries.push(FsEntry::new(name, entry.file_type()?.into()));
+        }
+        Ok(entries)
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        Ok(std::fs::metadata(path)?.into())
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        Ok(std::fs::symlink_metadata(path)?.into())
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::canonicalize(path)
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::read_link(path)
+    }
+
+    fn is_empty_dir(&self, path: &Path) -> io::Result<bool> {
+        // The first entry settles it; don't list the rest
+        Ok(std::fs::read_dir(path)?.next().is_none())
+    }
+
+    fn is_hidden(&self, path: &Path) -> bool {
+        has_hidden_attribute(path)
+    }
+
+    fn device(&self, path: &Path) -> Option<u64> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            std::fs::metadata(path).ok().map(|meta| meta.dev())
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = path;
+            None
+        }
+    }
+}
+
+/// A synthetic tree for tests, rooted at a path that doesn't exist on disk so
+/// any read that bypasses the `FileSystem` finds nothing. Counts every call.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub(crate) struct MemoryFileSystem {
+    nodes: std::collections::BTreeMap<std::path::PathBuf, MemoryNode>,
+    /// Directories that start a new device; everything else is device 0
+    mounts: std::collections::BTreeMap<std::path::PathBuf, u64>,
+    /// Paths marked hidden by attribute
+    hidden: std::collections::BTreeSet<std::path::PathBuf>,
+    /// Directories whose listing is denied
+    locked: std::collections::BTreeSet<std::path::PathBuf>,
+    pub(crate) calls: std::sync::atomic::AtomicUsize,
+}
+
+#[cfg(test)]
+#[derive(Debug)]
+enum MemoryNode {
+    Dir,
+    File,
+    Symlink(std::path::PathBuf),
+}
+
+#[cfg(test)]
+impl MemoryFileSystem {
+    /// A tree holding `files` (relative to `root`) and their parent directories.
+    /// Names ending in `/` are empty directories.
+    pub(crate) fn new(root: &str, files: &[&str]) -> Self {
+        let mut fs = Self::default();
+        fs.nodes.insert(root.into(), MemoryNode::Dir);
+        for file in files {
+            let path = Path::new(root).join(file.trim_end_matches('/'));
+            fs.add_parents(&path);
+            let node = if file.ends_with('/') {
+                MemoryNode::Dir
+            } else {
+                MemoryNode::File
+            };
+            fs.nodes.insert(path, node);
+        }
+        fs
+    }
+
+    /// Add a symlink at `link` (relative to `root`) pointing at the absolute `target`.
+    pub(crate) fn with_symlink(mut self, root: &str, link: &str, target: &str) -> Self {
+        let path = Path::new(root).join(link);
+        self.add_parents(&path);
+        self.nodes.insert(path, MemoryNode::Symlink(target.into()));
+        self
+    }
+
+    /// Put the directory `dir` (relative to `root`) and everything below it on `device`.
+    pub(crate) fn with_mount(mut self, root: &str, dir: &str, device: u64) -> Self {
+        self.mounts.insert(Path::new(root).join(dir), device);
+        self
+    }
+
+    /// Mark `path` (relative to `root`) hidden by attribute.
+    pub(crate) fn with_hidden(mut self, root: &str, path: &str) -> Self {
+        self.hidden.insert(Path::new(root).join(path));
+        self
+    }
+
+    /// Make listing the directory `dir` (relative to `root`) fail with `PermissionDenied`.
+    pub(crate) fn with_locked(mut self, root: &str, dir: &str) -> Self {
+        self.locked.insert(Path::new(root).join(dir));
+        self
+    }
+
+    fn add_parents(&mut self, path: &Path) {
+        for parent in path.ancestors().skip(1) {
+            self.nodes
+                .entry(parent.to_path_buf())
+                .or_insert(MemoryNode::Dir);
+        }
+    }
+
+    /// Resolve symlinks in `path`, including the last component only if `follow_last`.
+    fn resolve(&self, path: &Path, follow_last: bool) -> Option<std::path::PathBuf> {
+        let mut resolved = std::path::PathBuf::new();
+        let components: Vec<_> = path.components().collect();
+        for (i, component) in components.iter().enumerate() {
+            resolved.push(component);
+            let is_last = i + 1 == components.len();
+            if let Some(MemoryNode::Symlink(target)) = self.nodes.get(&resolved) {
+                if !is_last || follow_last {
+                    resolved = target.clone();
+                }
+            }
+        }
+        self.nodes.contains_key(&resolved).then_some(resolved)
+    }
+
+    fn node_metadata(&self, path: &Path) -> FsMetadata {
+        match self.nodes.get(path) {
+            Some(MemoryNode::Dir) => FsMetadata::new(true, false, false),
+            // A file is as many bytes long as its name
+            Some(MemoryNode::File) | None => {
+                let name = path.file_name().map_or(0, |name| name.len());
+                FsMetadata::new(false, true, false).with_len(name as u64)
+            }
+            Some(MemoryNode::Symlink(_)) => FsMetadata::new(false, false, true),
+        }
+    }
+
+    fn count_call(&self) {
+        self.calls
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+impl FileSystem for MemoryFileSystem {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<FsEntry>> {
+        self.count_call();
+        let dir = self
+            .resolve(path, true)
+            .filter(|dir| matches!(self.nodes.get(dir), Some(MemoryNode::Dir)))
+            .ok_or(io::ErrorKind::NotFound)?;
+        if self.locked.contains(&dir) {
+            return Err(io::ErrorKind::PermissionDenied.into());
+        }
+        Ok(self
+            .nodes
+            .keys()
+            .filter(|child| child.parent() == Some(dir.as_path()))
+            .map(|child| {
+                let name = child.file_name().unwrap().to_string_lossy().into_owned();
+                FsEntry::new(name, self.node_metadata(child))
+            })
+            .collect())
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        self.count_call();
+        let target = self.resolve(path, true).ok_or(io::ErrorKind::NotFound)?;
+        Ok(self.node_metadata(&target))
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        self.count_call();
+        let target = self.resolve(path, false).ok_or(io::ErrorKind::NotFound)?;
+        Ok(self.node_metadata(&target))
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.count_call();
+        Ok(self.resolve(path, true).ok_or(io::ErrorKind::NotFound)?)
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        self.count_call();
+        let link = self.resolve(path, false).ok_or(io::ErrorKind::NotFound)?;
+        match self.nodes.get(&link) {
+            Some(MemoryNode::Symlink(target)) => Ok(target.clone()),
+            _ => Err(io::ErrorKind::InvalidInput.into()),
+        }
+    }
+
+    fn is_hidden(&self, path: &Path) -> bool {
+        self.count_call();
+        self.hidden.contains(path)
+    }
+
+    fn device(&self, path: &Path) -> Option<u64> {
+        self.count_call();
+        let target = self.resolve(path, true)?;
+        Some(
+            target
+                .ancestors()
+                .find_map(|dir| self.mounts.get(dir))
+                .copied()
+                .unwrap_or(0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_std_file_system_reports_own_types() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir(base.join("dir")).unwrap();
+        File::create(base.join("file.txt")).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(base.join("dir"), base.join("link")).unwrap();
+
+        let mut entries = StdFileSystem.read_dir(base).unwrap();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut expected = vec![
+            FsEntry::new("dir", FsMetadata::new(true, false, false)),
+            FsEntry::new("file.txt", FsMetadata::new(false, true, false)),
+        ];
+        #[cfg(unix)]
+        expected.push(FsEntry::new("link", FsMetadata::new(false, false, true)));
+        assert_eq!(entries, expected);
+
+        #[cfg(unix)]
+        {
+            let link = base.join("link");
+            let metadata = StdFileSystem.metadata(&link).unwrap();
+            assert!(metadata.is_dir && !metadata.is_file && !metadata.is_symlink);
+            assert!(StdFileSystem.symlink_metadata(&link).unwrap().is_symlink);
+        }
+        fs::write(base.join("file.txt"), "12345").unwrap();
+        assert_eq!(
+            StdFileSystem.metadata(&base.join("file.txt")).unwrap().len,
+            Some(5)
+        );
+        assert!(StdFileSystem.read_dir(&base.join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_memory_file_system() {
+        let fs = MemoryFileSystem::new("/archive", &["a/b.txt", "empty/"]).with_symlink(
+            "/archive",
+            "link",
+            "/archive/a",
+        );
+        let names = |path: &str| -> Vec<String> {
+            fs.read_dir(Path::new(path))
+                .unwrap()
+                .into_iter()
+                .map(|e| e.name)
+                .collect()
+        };
+        assert_eq!(names("/archive"), ["a", "empty", "link"]);
+        assert_eq!(names("/archive/link"), ["b.txt"]);
+        assert!(names("/archive/empty").is_empty());
+        assert!(fs.read_dir(Path::new("/archive/a/b.txt")).is_err());
+        assert!(fs.metadata(Path::new("/archive/link")).unwrap().is_dir);
+        assert!(
+            fs.symlink_metadata(Path::new("/archive/link"))
+                .unwrap()
+                .is_symlink
+        );
+        assert!(
+            fs.metadata(Path::new("/archive/link/b.txt"))
+                .unwrap()
+                .is_file
+        );
+        assert_eq!(
+            fs.canonicalize(Path::new("/archive/link/b.txt")).unwrap(),
+            Path::new("/archive/a/b.txt")
+        );
+        assert_eq!(
+            fs.read_link(Path::new("/archive/link")).unwrap(),
+            Path::new("/archive/a")
+        );
+        assert!(fs.read_link(Path::new("/archive/a")).is_err());
+        assert_eq!(fs.device(Path::new("/archive/link")), Some(0));
+    }
+}
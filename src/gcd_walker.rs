@@ -16,13 +16,25 @@
 // Do not add a duplicate #![cfg(...)] attribute here.
 
 use std::collections::VecDeque;
+use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use dispatch::{Queue, QueuePriority};
 
 use crate::macos_walker::{read_dir_fast, read_dir_getattrlistbulk, RawDirEntry};
-use crate::walker::{WalkEntry, WalkOptions};
+use crate::walker::{normalize_path_str, DirHandleLimit, DirPruneFilter, WalkEntry, WalkOptions};
+
+/// A directory waiting to be read.
+#[derive(Clone)]
+struct PendingDir {
+    path: PathBuf,
+    /// Depth of the entries inside this directory
+    depth: usize,
+    /// Real paths of this directory and the directories above it in the walk.
+    /// Only tracked when following symlinks, to detect symlink loops like walkdir does.
+    ancestors: Arc<Vec<PathBuf>>,
+}
 
 /// GCD-based parallel directory walker for macOS.
 ///
@@ -32,27 +44,60 @@ use crate::walker::{WalkEntry, WalkOptions};
 /// - Automatic P-core vs E-core handling on Apple Silicon
 /// - Lower overhead for I/O-bound workloads
 /// - Better power management
-pub struct GcdWalker {
+///
+/// Entries match the serial walkdir-based walker: the same dot filtering, depth
+/// limit, directory pruning, symlink types and loop handling. Only the order differs.
+pub struct GcdWalker<'a> {
     root: PathBuf,
     options: WalkOptions,
+    dir_prune_filter: Option<&'a DirPruneFilter>,
+    /// Bounds concurrent directory reads when `max_open_dirs` is set, shared with
+    /// any other walker using the same options
+    dir_handles: Option<Arc<DirHandleLimit>>,
 }
 
-impl GcdWalker {
+impl<'a> GcdWalker<'a> {
     /// Create a new GCD-based walker
     pub fn new(root: PathBuf, options: WalkOptions) -> Self {
-        Self { root, options }
+        let dir_handles = options.dir_handles.clone().or_else(|| {
+            options
+                .max_open_dirs
+                .map(|max| Arc::new(DirHandleLimit::new(max)))
+        });
+        Self {
+            root,
+            options,
+            dir_prune_filter: None,
+            dir_handles,
+        }
     }
 
-    /// Read directory entries using the optimized macOS functions
-    fn read_dir(&self, path: &Path) -> Vec<RawDirEntry> {
-        // Try the optimized getattrlistbulk path first
-        match read_dir_getattrlistbulk(path) {
-            Ok(entries) => entries,
-            Err(_) => {
-                // Fall back to standard readdir
-                read_dir_fast(path).unwrap_or_default()
-            }
-        }
+    /// Skip directories (and their contents) for which the filter returns false.
+    /// The filter receives the path relative to root with forward slashes.
+    pub fn with_dir_prune_filter(mut self, filter: &'a DirPruneFilter) -> Self {
+        self.dir_prune_filter = Some(filter);
+        self
+    }
+
+    /// Read directory entries using the optimized macOS functions.
+    ///
+    /// `getattrlistbulk` is not supported by every filesystem (some network and
+    /// FUSE mounts return `ENOTSUP`), so fall back to plain readdir.
+    ///
+    /// Each read opens one handle and closes it before returning, so holding a
+    /// permit for the duration bounds the handles open across all GCD workers.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<RawDirEntry>> {
+        let _permit = self.dir_handles.as_deref().map(DirHandleLimit::acquire);
+        read_dir_getattrlistbulk(path).or_else(|_| read_dir_fast(path))
+    }
+
+    /// Read a directory below the root, recording it if it can't be read and the
+    /// walk reports unreadable directories.
+    fn read_subdir(&self, path: &Path) -> Vec<RawDirEntry> {
+        self.read_dir(path).unwrap_or_else(|err| {
+            self.options.note_unreadable(path, false, &err);
+            Vec::new()
+        })
     }
 
     /// Walk the directory tree using GCD for parallel processing.
@@ -61,47 +106,220 @@ impl GcdWalker {
     /// at each level. The parallelism is handled by GCD's global concurrent queue,
     /// which automatically manages thread pool sizing and scheduling.
     pub fn walk(&self) -> Vec<WalkEntry> {
-        if !self.root.exists() {
-            return Vec::new();
+        self.try_walk().unwrap_or_default()
+    }
+
+    /// Walk like [`GcdWalker::walk`], but fail if the root directory cannot be
+    /// listed by either reader so the caller can fall back to another walker.
+    pub fn try_walk(&self) -> io::Result<Vec<WalkEntry>> {
+        let (root_entry, root_dir) = match self.root_entry() {
+            Some(root) => root,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut entries = vec![root_entry];
+        let root_dir = match root_dir {
+            Some(dir) => dir,
+            None => return Ok(entries),
+        };
+
+        let listing = self.read_dir(&root_dir.path)?;
+        let (root_children, first_level) = self.process_dir(&root_dir, listing);
+        entries.extend(root_children);
+
+        let entries = Mutex::new(entries);
+        self.walk_parallel_bfs(first_level, &entries);
+        Ok(entries.into_inner().unwrap())
+    }
+
+    /// Build the depth-0 entry, plus the pending root directory if it should be read.
+    ///
+    /// Like walkdir, a symlinked root is always descended into, but without `follow`
+    /// the entry itself keeps the link's own type.
+    fn root_entry(&self) -> Option<(WalkEntry, Option<PendingDir>)> {
+        let meta = self.root.metadata().ok()?;
+        let ft = meta.file_type();
+        let root_is_symlink = self
+            .root
+            .symlink_metadata()
+            .map(|m| m.file_type().is_symlink())
+            .unwrap_or(false);
+
+        let (is_dir, is_file, is_symlink) = if root_is_symlink && !self.options.follow_symlinks {
+            (false, false, true)
+        } else {
+            (
+                ft.is_dir(),
+                ft.is_file(),
+                root_is_symlink && self.options.need_accurate_symlink_detection,
+            )
+        };
+
+        let entry = WalkEntry {
+            path: self.root.clone(),
+            depth: 0,
+            is_dir,
+            is_file,
+            is_symlink,
+            size: None,
+        };
+
+        let read_root = ft.is_dir()
+            && match self.options.max_depth {
+                Some(max) => max >= 1,
+                None => true,
+            };
+        let pending = read_root.then(|| {
+            let ancestors = if self.options.follow_symlinks {
+                vec![self
+                    .root
+                    .canonicalize()
+                    .unwrap_or_else(|_| self.root.clone())]
+            } else {
+                Vec::new()
+            };
+            PendingDir {
+                path: self.root.clone(),
+                depth: 1,
+                ancestors: Arc::new(ancestors),
+            }
+        });
+
+        Some((entry, pending))
+    }
+
+    /// Turn one directory listing into walk entries and the subdirectories to read next.
+    fn process_dir(
+        &self,
+        dir: &PendingDir,
+        mut listing: Vec<RawDirEntry>,
+    ) -> (Vec<WalkEntry>, Vec<PendingDir>) {
+        // Each directory's entries are appended as one block, so sorting the
+        // listing keeps them in name order even though directories run in parallel
+        if self.options.sort_dir_entries {
+            listing.sort_by(|a, b| a.name.cmp(&b.name));
         }
 
-        // Shared state for collecting results (thread-safe)
-        let entries = Arc::new(Mutex::new(Vec::new()));
+        let dot = self.options.dot;
+        let follow_symlinks = self.options.follow_symlinks;
+        let recurse = match self.options.max_depth {
+            Some(max) => dir.depth < max,
+            None => true,
+        };
+
+        let mut entries = Vec::with_capacity(listing.len());
+        let mut subdirs = Vec::new();
+
+        for raw_entry in listing {
+            let name_str = raw_entry.name.to_string_lossy();
+
+            // Filter dotfiles if needed
+            if !dot && name_str.starts_with('.') {
+                continue;
+            }
+
+            let entry_path = dir.path.join(&raw_entry.name);
 
-        // Add root entry
-        if let Ok(meta) = self.root.symlink_metadata() {
-            let is_symlink = meta.file_type().is_symlink();
-            let (is_dir, is_file) = if is_symlink && self.options.follow_symlinks {
-                match self.root.metadata() {
+            // When following, report the target type like walkdir does. A resolvable
+            // link is only flagged as a symlink when accurate detection is requested;
+            // a broken link is always reported as a bare symlink.
+            let (is_dir, is_file, is_symlink) = if raw_entry.is_symlink && follow_symlinks {
+                match entry_path.metadata() {
                     Ok(target_meta) => {
                         let ft = target_meta.file_type();
-                        (ft.is_dir(), ft.is_file())
+                        (
+                            ft.is_dir(),
+                            ft.is_file(),
+                            self.options.need_accurate_symlink_detection,
+                        )
                     }
-                    Err(_) => (false, false),
+                    Err(_) => (false, false, true),
                 }
             } else {
-                let ft = meta.file_type();
-                (ft.is_dir(), ft.is_file())
+                (raw_entry.is_dir, raw_entry.is_file, raw_entry.is_symlink)
             };
 
-            entries.lock().unwrap().push(WalkEntry {
-                path: self.root.clone(),
-                depth: 0,
+            let descend = is_dir && (follow_symlinks || !raw_entry.is_symlink);
+
+            // A followed link back to one of its own ancestors is reported as a
+            // bare symlink and not descended, like walkdir's loop error
+            let real_path = if descend && follow_symlinks {
+                let real = if raw_entry.is_symlink {
+                    entry_path
+                        .canonicalize()
+                        .unwrap_or_else(|_| entry_path.clone())
+                } else {
+                    dir.ancestors
+                        .last()
+                        .map(|parent| parent.join(&raw_entry.name))
+                        .unwrap_or_else(|| entry_path.clone())
+                };
+                if dir.ancestors.contains(&real) {
+                    entries.push(WalkEntry {
+                        path: entry_path,
+                        depth: dir.depth,
+                        is_dir: false,
+                        is_file: false,
+                        is_symlink: true,
+                        size: None,
+                    });
+                    continue;
+                }
+                Some(real)
+            } else {
+                None
+            };
+
+            // Pruned directories are dropped together with their contents
+            if is_dir && !self.passes_prune_filter(&entry_path) {
+                continue;
+            }
+
+            if descend && recurse {
+                let ancestors = match real_path {
+                    Some(real) => {
+                        let mut chain = Vec::with_capacity(dir.ancestors.len() + 1);
+                        chain.extend(dir.ancestors.iter().cloned());
+                        chain.push(real);
+                        Arc::new(chain)
+                    }
+                    None => dir.ancestors.clone(),
+                };
+                subdirs.push(PendingDir {
+                    path: entry_path.clone(),
+                    depth: dir.depth + 1,
+                    ancestors,
+                });
+            }
+
+            entries.push(WalkEntry {
+                path: entry_path,
+                depth: dir.depth,
                 is_dir,
                 is_file,
                 is_symlink,
+                size: None,
             });
-
-            if is_dir {
-                // Start parallel BFS traversal
-                self.walk_parallel_bfs(entries.clone());
-            }
         }
 
-        // Extract results from Arc<Mutex<>>
-        match Arc::try_unwrap(entries) {
-            Ok(mutex) => mutex.into_inner().unwrap(),
-            Err(arc) => arc.lock().unwrap().clone(),
+        (entries, subdirs)
+    }
+
+    /// Check a directory against the prune filter (true = keep and descend).
+    fn passes_prune_filter(&self, path: &Path) -> bool {
+        let filter = match self.dir_prune_filter {
+            Some(filter) => filter,
+            None => return true,
+        };
+        match path.strip_prefix(&self.root) {
+            Ok(rel_path) => {
+                let rel_lossy = rel_path.to_string_lossy();
+                filter(&normalize_path_str(
+                    &rel_lossy,
+                    self.options.preserve_backslashes,
+                ))
+            }
+            Err(_) => true,
         }
     }
 
@@ -109,108 +327,38 @@ impl GcdWalker {
     ///
     /// This processes directories level by level, with each level being
     /// processed in parallel using GCD's concurrent queue.
-    fn walk_parallel_bfs(&self, entries: Arc<Mutex<Vec<WalkEntry>>>) {
+    fn walk_parallel_bfs(&self, first_level: Vec<PendingDir>, entries: &Mutex<Vec<WalkEntry>>) {
         let queue = Queue::global(QueuePriority::Default);
 
         // Current level directories to process
-        let mut current_level: Vec<(PathBuf, usize)> = vec![(self.root.clone(), 1)];
+        let mut current_level = first_level;
 
         // Process level by level
         while !current_level.is_empty() {
-            // Check depth limit
-            if let Some(max_depth) = self.options.max_depth {
-                if current_level[0].1 > max_depth {
-                    break;
-                }
-            }
-
             // Shared state for next level directories
-            let next_level = Arc::new(Mutex::new(Vec::new()));
-
-            // Clone references for parallel closure
-            let entries_clone = entries.clone();
-            let next_level_clone = next_level.clone();
-            let dot = self.options.dot;
-            let follow_symlinks = self.options.follow_symlinks;
-            let max_depth = self.options.max_depth;
-
-            // Process all directories at current level in parallel
-            let num_dirs = current_level.len();
+            let next_level = Mutex::new(Vec::new());
 
             // Use dispatch_apply for parallel iteration
-            queue.apply(num_dirs, |i| {
-                let (dir_path, depth) = &current_level[i];
-
-                // Read directory entries
-                let dir_entries = match read_dir_getattrlistbulk(dir_path) {
-                    Ok(e) => e,
-                    Err(_) => read_dir_fast(dir_path).unwrap_or_default(),
-                };
-
-                // Collect entries for this directory
-                let mut local_entries = Vec::with_capacity(dir_entries.len());
-                let mut local_next_dirs = Vec::new();
-
-                for raw_entry in dir_entries {
-                    let name_str = raw_entry.name.to_string_lossy();
-
-                    // Filter dotfiles if needed
-                    if !dot && name_str.starts_with('.') {
-                        continue;
-                    }
-
-                    let entry_path = dir_path.join(&raw_entry.name);
-
-                    // Determine actual types (handle symlinks)
-                    let (is_dir, is_file, is_symlink) = if raw_entry.is_symlink && follow_symlinks {
-                        match entry_path.metadata() {
-                            Ok(target_meta) => {
-                                let ft = target_meta.file_type();
-                                (ft.is_dir(), ft.is_file(), true)
-                            }
-                            Err(_) => (false, false, true),
-                        }
-                    } else {
-                        (raw_entry.is_dir, raw_entry.is_file, raw_entry.is_symlink)
-                    };
-
-                    local_entries.push(WalkEntry {
-                        path: entry_path.clone(),
-                        depth: *depth,
-                        is_dir,
-                        is_file,
-                        is_symlink,
-                    });
+            queue.apply(current_level.len(), |i| {
+                let dir = &current_level[i];
 
-                    // Queue directories for next level
-                    if is_dir && (follow_symlinks || !raw_entry.is_symlink) {
-                        // Check if we should continue to next level
-                        let should_recurse = match max_depth {
-                            Some(max) => *depth < max,
-                            None => true,
-                        };
-                        if should_recurse {
-                            local_next_dirs.push((entry_path, depth + 1));
-                        }
-                    }
-                }
+                // Unreadable directories contribute no entries, like walkdir
+                let listing = self.read_subdir(&dir.path);
+                let (local_entries, local_next_dirs) = self.process_dir(dir, listing);
 
                 // Add local entries to shared collection
                 if !local_entries.is_empty() {
-                    entries_clone.lock().unwrap().extend(local_entries);
+                    entries.lock().unwrap().extend(local_entries);
                 }
 
                 // Add next level directories
                 if !local_next_dirs.is_empty() {
-                    next_level_clone.lock().unwrap().extend(local_next_dirs);
+                    next_level.lock().unwrap().extend(local_next_dirs);
                 }
             });
 
             // Move to next level
-            current_level = match Arc::try_unwrap(next_level) {
-                Ok(mutex) => mutex.into_inner().unwrap(),
-                Err(arc) => arc.lock().unwrap().clone(),
-            };
+            current_level = next_level.into_inner().unwrap();
         }
     }
 
@@ -220,55 +368,22 @@ impl GcdWalker {
     /// where directories are dynamically distributed to workers as they complete.
     /// This can be more efficient for unbalanced directory structures.
     pub fn walk_work_stealing(&self) -> Vec<WalkEntry> {
-        if !self.root.exists() {
-            return Vec::new();
-        }
+        let (root_entry, root_dir) = match self.root_entry() {
+            Some(root) => root,
+            None => return Vec::new(),
+        };
 
         let queue = Queue::global(QueuePriority::Default);
-        let entries = Arc::new(Mutex::new(Vec::new()));
-        let work_queue = Arc::new(Mutex::new(VecDeque::new()));
-
-        // Add root entry
-        if let Ok(meta) = self.root.symlink_metadata() {
-            let is_symlink = meta.file_type().is_symlink();
-            let (is_dir, is_file) = if is_symlink && self.options.follow_symlinks {
-                match self.root.metadata() {
-                    Ok(target_meta) => {
-                        let ft = target_meta.file_type();
-                        (ft.is_dir(), ft.is_file())
-                    }
-                    Err(_) => (false, false),
-                }
-            } else {
-                let ft = meta.file_type();
-                (ft.is_dir(), ft.is_file())
-            };
-
-            entries.lock().unwrap().push(WalkEntry {
-                path: self.root.clone(),
-                depth: 0,
-                is_dir,
-                is_file,
-                is_symlink,
-            });
-
-            if is_dir {
-                work_queue.lock().unwrap().push_back((self.root.clone(), 1));
-            }
-        }
+        let entries = Mutex::new(vec![root_entry]);
+        let work_queue = Mutex::new(VecDeque::from_iter(root_dir));
 
         // Number of workers (use number of CPUs)
         let num_workers = std::thread::available_parallelism()
             .map(|n| n.get())
             .unwrap_or(4)
             .max(1);
-        let active_workers = Arc::new(std::sync::atomic::AtomicUsize::new(num_workers));
-        let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
-
-        // Clone options for workers
-        let dot = self.options.dot;
-        let follow_symlinks = self.options.follow_symlinks;
-        let max_depth = self.options.max_depth;
+        let active_workers = std::sync::atomic::AtomicUsize::new(num_workers);
+        let done = std::sync::atomic::AtomicBool::new(false);
 
         // Spawn workers using GCD
         queue.apply(num_workers, |_worker_id| {
@@ -280,61 +395,9 @@ impl GcdWalker {
                 };
 
                 match work {
-                    Some((dir_path, depth)) => {
-                        // Check depth limit
-                        if max_depth.map(|m| depth > m).unwrap_or(false) {
-                            continue;
-                        }
-
-                        // Read directory entries
-                        let dir_entries = match read_dir_getattrlistbulk(&dir_path) {
-                            Ok(e) => e,
-                            Err(_) => read_dir_fast(&dir_path).unwrap_or_default(),
-                        };
-
-                        let mut local_entries = Vec::with_capacity(dir_entries.len());
-                        let mut new_dirs = VecDeque::new();
-
-                        for raw_entry in dir_entries {
-                            let name_str = raw_entry.name.to_string_lossy();
-
-                            if !dot && name_str.starts_with('.') {
-                                continue;
-                            }
-
-                            let entry_path = dir_path.join(&raw_entry.name);
-
-                            let (is_dir, is_file, is_symlink) =
-                                if raw_entry.is_symlink && follow_symlinks {
-                                    match entry_path.metadata() {
-                                        Ok(target_meta) => {
-                                            let ft = target_meta.file_type();
-                                            (ft.is_dir(), ft.is_file(), true)
-                                        }
-                                        Err(_) => (false, false, true),
-                                    }
-                                } else {
-                                    (raw_entry.is_dir, raw_entry.is_file, raw_entry.is_symlink)
-                                };
-
-                            local_entries.push(WalkEntry {
-                                path: entry_path.clone(),
-                                depth,
-                                is_dir,
-                                is_file,
-                                is_symlink,
-                            });
-
-                            if is_dir && (follow_symlinks || !raw_entry.is_symlink) {
-                                let should_recurse = match max_depth {
-                                    Some(max) => depth < max,
-                                    None => true,
-                                };
-                                if should_recurse {
-                                    new_dirs.push_back((entry_path, depth + 1));
-                                }
-                            }
-                        }
+                    Some(dir) => {
+                        let listing = self.read_subdir(&dir.path);
+                        let (local_entries, new_dirs) = self.process_dir(&dir, listing);
 
                         // Add collected entries
                         if !local_entries.is_empty() {
@@ -373,10 +436,7 @@ impl GcdWalker {
             }
         });
 
-        match Arc::try_unwrap(entries) {
-            Ok(mutex) => mutex.into_inner().unwrap(),
-            Err(arc) => arc.lock().unwrap().clone(),
-        }
+        entries.into_inner().unwrap()
     }
 }
 
@@ -584,4 +644,131 @@ mod tests {
         assert!(broken.is_some());
         assert!(broken.unwrap().is_symlink());
     }
+
+    /// Fixture with every kind of link the walkers treat differently:
+    /// a directory link, a file link, a broken link and a loop back to the root.
+    fn create_symlink_fixture() -> TempDir {
+        use std::os::unix::fs::symlink;
+
+        let temp = create_test_fixture();
+        let base = temp.path();
+
+        fs::create_dir_all(base.join(".hidden-dir")).unwrap();
+        File::create(base.join(".hidden-dir/secret.txt")).unwrap();
+        symlink(base.join("subdir1"), base.join("dir-link")).unwrap();
+        symlink(base.join("file1.txt"), base.join("file-link")).unwrap();
+        symlink("nonexistent-target", base.join("broken-link")).unwrap();
+        symlink(base, base.join("deep/level1/loop")).unwrap();
+
+        temp
+    }
+
+    /// Walk through `Walker` with and without `use_gcd`, comparing every entry field.
+    fn assert_gcd_matches_serial(
+        root: &Path,
+        options: WalkOptions,
+        prune: Option<fn(&str) -> bool>,
+    ) {
+        let walk = |use_gcd: bool| {
+            let mut walker =
+                crate::walker::Walker::new(root.to_path_buf(), options.clone().use_gcd(use_gcd));
+            if let Some(prune) = prune {
+                walker = walker.with_dir_prune_filter(Box::new(prune));
+            }
+            let mut entries: Vec<_> = walker
+                .walk_sync()
+                .into_iter()
+                .map(|e| (e.path, e.depth, e.is_dir, e.is_file, e.is_symlink))
+                .collect();
+            entries.sort();
+            entries
+        };
+
+        assert_eq!(walk(true), walk(false), "options: {options:?}");
+    }
+
+    #[test]
+    fn test_gcd_walker_cross_check_with_serial() {
+        let temp = create_symlink_fixture();
+        let root = temp.path();
+
+        let option_sets = [
+            WalkOptions::new(),
+            WalkOptions::new().dot(true),
+            WalkOptions::new().max_depth(Some(0)),
+            WalkOptions::new().max_depth(Some(1)),
+            WalkOptions::new().max_depth(Some(2)).dot(true),
+            WalkOptions::new().follow_symlinks(true),
+            WalkOptions::new().follow_symlinks(true).dot(true),
+            WalkOptions::new()
+                .follow_symlinks(true)
+                .need_accurate_symlink_detection(true),
+            WalkOptions::new().follow_symlinks(true).max_depth(Some(3)),
+        ];
+
+        for options in option_sets {
+            assert_gcd_matches_serial(root, options.clone(), None);
+            assert_gcd_matches_serial(root, options.clone(), Some(|p| !p.starts_with("deep")));
+            assert_gcd_matches_serial(root, options, Some(|p| p != "subdir1" && p != "dir-link"));
+        }
+    }
+
+    #[test]
+    fn test_gcd_walker_symlinked_root() {
+        use std::os::unix::fs::symlink;
+
+        let temp = create_test_fixture();
+        let link_parent = TempDir::new().unwrap();
+        let root = link_parent.path().join("root-link");
+        symlink(temp.path(), &root).unwrap();
+
+        // walkdir always resolves the root, with or without `follow`
+        assert_gcd_matches_serial(&root, WalkOptions::new(), None);
+        assert_gcd_matches_serial(&root, WalkOptions::new().follow_symlinks(true), None);
+        assert_gcd_matches_serial(
+            &root,
+            WalkOptions::new()
+                .follow_symlinks(true)
+                .need_accurate_symlink_detection(true),
+            None,
+        );
+    }
+
+    #[test]
+    fn test_gcd_walker_symlink_loop_terminates() {
+        let temp = create_symlink_fixture();
+        let walker = GcdWalker::new(
+            temp.path().to_path_buf(),
+            WalkOptions::new().follow_symlinks(true),
+        );
+        let entries = walker.walk();
+
+        // The loop link is reported once, as a bare symlink, and not descended
+        let loops: Vec<_> = entries
+            .iter()
+            .filter(|e| e.path().ends_with("loop"))
+            .collect();
+        assert_eq!(loops.len(), 1);
+        assert!(loops[0].is_symlink() && !loops[0].is_dir());
+        assert!(!entries
+            .iter()
+            .any(|e| e.path().to_string_lossy().contains("loop/")));
+    }
+
+    #[test]
+    fn test_gcd_try_walk_root_edge_cases() {
+        let walker = GcdWalker::new(
+            PathBuf::from("/nonexistent/path/that/does/not/exist"),
+            WalkOptions::default(),
+        );
+        // A missing root is not an error: there is simply nothing to walk
+        assert!(walker.try_walk().unwrap().is_empty());
+
+        let temp = create_test_fixture();
+        let file = temp.path().join("file1.txt");
+        let walker = GcdWalker::new(file.clone(), WalkOptions::default());
+        let entries = walker.try_walk().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path(), file);
+    }
 }
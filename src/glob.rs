@@ -1,18 +1,36 @@
 use std::borrow::Cow;
-use std::path::{Path, PathBuf};
+use std::collections::hash_map::Entry;
+use std::collections::BinaryHeap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use ahash::AHashSet;
+use ahash::{AHashMap, AHashSet};
 use napi::bindgen_prelude::*;
-use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
 use rayon::prelude::*;
+use tokio::sync::{mpsc, Mutex};
 
 use crate::cache::get_or_compile_pattern;
-use crate::ignore::IgnoreFilter;
-use crate::options::{validate_options, GlobOptions};
-use crate::pattern::{expand_braces, preprocess_pattern, Pattern, PatternOptions};
-use crate::util::strip_windows_extended_prefix;
-use crate::walker::{WalkOptions, Walker};
+use crate::fs::{FileSystem, StdFileSystem};
+use crate::ignore::{IgnoreFilter, DEFAULT_IGNORE_PATTERNS};
+use crate::options::{
+    option_pattern_warnings, validate_options, DedupStrategy, DirOrder, GlobOptions, HiddenMode,
+    MatchOptions, PatternInput, PatternOverrides,
+};
+#[cfg(target_os = "windows")]
+use crate::pattern::strip_trailing_dots_and_spaces;
+use crate::pattern::{
+    analyze_patterns, collapse_slashes, expand_braces_limited, fold_case, preprocess_pattern,
+    Pattern, PatternOptions, PatternPart,
+};
+use crate::util::{
+    normalize_lexically_with, normalize_path_lexically, normalize_separator, relative_path,
+    resolves_within_on, strip_candidate_base, strip_windows_extended_prefix,
+};
+use crate::walker::{DirPruneFilter, WalkEntry, WalkOptions, Walker, DEFAULT_HARD_DEPTH_LIMIT};
+use crate::PatternWarningInfo;
 
 /// Path data returned by glob with withFileTypes: true.
 /// This struct is converted to PathScurry Path objects in the JavaScript wrapper.
@@ -27,14 +45,138 @@ pub struct PathData {
     pub is_file: bool,
     /// True if this is a symbolic link
     pub is_symlink: bool,
+    /// Where the symlink points, as stored in the link (not resolved).
+    /// Only set for symlinks when `readLinkTargets` is enabled.
+    pub link_target: Option<String>,
+    /// True if this directory has no entries. Only set for directories when
+    /// `detectEmptyDirs` is enabled, and left unset if the directory can't be read.
+    pub is_empty_dir: Option<bool>,
 }
 
+/// Default cap on the total number of patterns after brace expansion (`maxPatterns`).
+pub const DEFAULT_MAX_PATTERNS: usize = 100_000;
+
+/// Default number of results an iterator buffers ahead of its consumer.
+pub const DEFAULT_ITERATOR_BUFFER: usize = 256;
+
+/// What a walk touched, as reported by [`Glob::walk_sync_timed`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalkStats {
+    /// Filesystem entries examined, including the walk root and entries that
+    /// didn't match. Static patterns count one entry per path that exists.
+    pub entries_visited: usize,
+    /// How many of the visited entries were directories
+    pub dirs_visited: usize,
+    /// Wall-clock time for the whole glob
+    pub elapsed: Duration,
+}
+
+/// Running counts behind [`WalkStats`], bumped as a walk consumes entries.
+///
+/// Each [`Glob::walk_sync_timed`] call counts into its own counter, so timed
+/// walks running concurrently on one glob don't mix their counts.
+#[derive(Default)]
+struct VisitCounter {
+    entries: AtomicUsize,
+    dirs: AtomicUsize,
+}
+
+impl VisitCounter {
+    #[inline]
+    fn record(&self, is_dir: bool) {
+        self.entries.fetch_add(1, Ordering::Relaxed);
+        if is_dir {
+            self.dirs.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Report what was counted, along with how long the walk took.
+    fn stats(&self, elapsed: Duration) -> WalkStats {
+        WalkStats {
+            entries_visited: self.entries.load(Ordering::Relaxed),
+            dirs_visited: self.dirs.load(Ordering::Relaxed),
+            elapsed,
+        }
+    }
+}
+
+/// A flag a walk sets and the caller reads back once it is done. A cloned
+/// glob starts with the flag clear.
+#[derive(Default)]
+struct WalkFlag(AtomicBool);
+
+impl Clone for WalkFlag {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+/// The result of a walk running on another thread, read back once its
+/// results are drained.
+#[derive(Clone, Default)]
+pub struct WalkOutcome(Arc<std::sync::Mutex<Option<Error>>>);
+
+impl WalkOutcome {
+    fn set(&self, outcome: Result<()>) {
+        if let Err(err) = outcome {
+            *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Some(err);
+        }
+    }
+
+    /// The walk's error, reported once.
+    fn take<T: Default>(&self) -> Result<T> {
+        match self.0.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            Some(err) => Err(err),
+            None => Ok(T::default()),
+        }
+    }
+}
+
+/// How `Glob::walk_sync` gathers results, as reported by [`Glob::strategy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalkStrategy {
+    /// Every pattern is a literal path: each one is checked with a single stat
+    Static,
+    /// Every pattern matches at the top level only: a single readdir of cwd
+    Shallow,
+    /// Patterns start with different literal directories, each walked separately
+    MultiBase,
+    /// One walk from the literal prefix shared by all patterns
+    PrefixScoped,
+    /// One walk from cwd (or an absolute root)
+    FullWalk,
+}
+
+impl WalkStrategy {
+    /// Name of the strategy, as reported by `globPlan`
+    pub fn name(&self) -> &'static str {
+        match self {
+            WalkStrategy::Static => "Static",
+            WalkStrategy::Shallow => "Shallow",
+            WalkStrategy::MultiBase => "MultiBase",
+            WalkStrategy::PrefixScoped => "PrefixScoped",
+            WalkStrategy::FullWalk => "FullWalk",
+        }
+    }
+}
+
+/// Compiled patterns and options, ready to walk.
+///
+/// Cloning is cheap: the compiled patterns and ignore filter are shared. A
+/// clone starts without any walk state (visit counts, the depth-limit flag).
+#[derive(Clone)]
 pub struct Glob {
     #[allow(dead_code)]
     pattern_strs: Vec<String>,
     cwd: PathBuf,
     /// Patterns stored in Arc for cheap cloning into closures
     patterns: Arc<[Pattern]>,
+    /// Indices of the input patterns each compiled pattern came from (parallel to
+    /// `patterns`); one input can expand to several patterns and deduplicated
+    /// patterns can belong to several inputs
+    pattern_origins: Arc<[Vec<usize>]>,
+    /// Number of input patterns, including empty ones
+    input_count: usize,
     absolute: bool,
     posix_explicit_true: bool,
     posix_explicit_false: bool,
@@ -66,18 +208,58 @@ pub struct Glob {
     /// Walker options for directory traversal
     walk_options: WalkOptions,
     /// Ignore filter for excluding paths
-    ignore_filter: Option<IgnoreFilter>,
+    ignore_filter: Option<Arc<IgnoreFilter>>,
     /// Pre-computed: true if any pattern requires directory matching (ends with /)
     any_pattern_requires_dir: bool,
     /// Pre-computed: number of fast-path patterns (for optimization decisions)
     fast_pattern_count: usize,
     /// When false, don't include children of matched paths
     include_child_matches: bool,
+    /// How already-emitted results are remembered for deduplication
+    dedup_strategy: DedupStrategy,
+    /// Only remember results of directories still being walked (scopedDedup)
+    scoped_dedup: bool,
+    /// What counts as hidden when `dot` is false
+    hidden: HiddenMode,
+    /// When false, `walk_sync` emits a path once per input pattern it matches
+    unique: bool,
+    /// Skip every fast path and pruning shortcut (disableOptimizations)
+    disable_optimizations: bool,
+    /// Every expanded pattern in input order, flagged when it came from a `!`
+    /// pattern (sequential). `patterns` then holds only the including ones.
+    sequential_rules: Option<Arc<[(Pattern, bool)]>>,
+    /// Stream multi-base results per base as they are found (streamPerBase)
+    stream_per_base: bool,
+    /// String prepended to every result string (resultPrefix)
+    result_prefix: Option<String>,
+    /// Keep `\\` in filesystem paths as a literal character (non-Windows only)
+    preserve_backslashes: bool,
+    /// Separator of the keys matched by `matches_path` (`/` for paths)
+    separator: char,
+    /// Canonical cwd that results must stay inside (restrictToCwd)
+    sandbox_root: Option<PathBuf>,
+    /// Whether to read symlink targets into `PathData::link_target`
+    read_link_targets: bool,
+    /// Whether to check directories for `PathData::is_empty_dir`
+    detect_empty_dirs: bool,
+    /// Where walks count visited entries; only set on the per-call copy
+    /// `walk_sync_timed` walks with
+    visits: Option<Arc<VisitCounter>>,
+    /// Don't report walk roots that can't be read (suppressErrors)
+    suppress_errors: bool,
+    /// Set when a walk stopped at the hard depth limit (hardDepthLimit)
+    depth_limit_hit: WalkFlag,
+    /// Replace string results with their resolved real paths
+    realpath: bool,
+    /// With `realpath`, keep broken symlinks instead of dropping them
+    realpath_keep_broken: bool,
+    /// Drop results that reach an already reported file through a symlink
+    canonical_only: bool,
 }
 
 #[napi]
 pub fn glob_sync(
-    pattern: Either<String, Vec<String>>,
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
     options: Option<GlobOptions>,
 ) -> Result<Vec<String>> {
     let opts = options.unwrap_or_default();
@@ -85,1690 +267,2324 @@ pub fn glob_sync(
     // Validate options using the centralized validation
     validate_options(&opts)?;
 
-    let patterns = match pattern {
-        Either::A(s) => vec![s],
-        Either::B(v) => v,
+    let patterns = collect_pattern_inputs(pattern);
+
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts.clone())?;
+    glob.check_walk(glob.walk_sync())
+}
+
+/// Test whether a path matches the pattern(s) without walking the filesystem.
+///
+/// The path is interpreted relative to the current directory (or as absolute), the
+/// same way glob results are. A trailing `/` marks the path as a directory.
+///
+/// @param path - The candidate path
+/// @param pattern - Glob pattern or array of patterns
+/// @param options - Matching options
+/// @returns True if any pattern matches the path
+#[napi]
+pub fn matches_path(
+    path: String,
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<MatchOptions>,
+) -> Result<bool> {
+    let (glob, path, normalize_candidate) = build_matcher(path, pattern, options, false)?;
+    Ok(path.is_some_and(|path| glob.matches_path(&path, normalize_candidate)))
+}
+
+/// Test a path against several patterns with AND semantics, without walking.
+///
+/// The path must match every `include` pattern and no `exclude` pattern. Each
+/// pattern is matched exactly as `matches_path` would match it on its own, so an
+/// empty `include` list only checks the exclusions.
+///
+/// @param path - The candidate path
+/// @param include - Patterns that must all match
+/// @param exclude - Patterns that must not match
+/// @param options - Matching options, applied to every pattern
+/// @returns True if all includes match and no exclude does
+#[napi]
+pub fn matches_all(
+    path: String,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    options: Option<MatchOptions>,
+) -> Result<bool> {
+    // Build every matcher first so an invalid pattern is reported regardless of
+    // which pattern would have decided the answer
+    let includes = include
+        .into_iter()
+        .map(|pattern| build_matcher(path.clone(), Either::A(pattern), options.clone(), false))
+        .collect::<Result<Vec<_>>>()?;
+    let exclude = if exclude.is_empty() {
+        None
+    } else {
+        let patterns = exclude.into_iter().map(Either::A).collect();
+        Some(build_matcher(path, Either::B(patterns), options, false)?)
+    };
+
+    // A path outside `base` matches nothing, exclusions included, so it fails
+    // even when there are no includes
+    if includes
+        .first()
+        .or(exclude.as_ref())
+        .is_some_and(|(_, path, _)| path.is_none())
+    {
+        return Ok(false);
+    }
+    let matches = |(glob, path, normalize_candidate): &(Glob, Option<String>, bool)| {
+        path.as_ref()
+            .is_some_and(|path| glob.matches_path(path, *normalize_candidate))
     };
+    Ok(includes.iter().all(matches) && !exclude.as_ref().is_some_and(matches))
+}
 
-    let glob = Glob::new_multi(patterns, opts.clone());
-    Ok(glob.walk_sync())
+/// Explain why a path does or doesn't match the pattern(s).
+///
+/// Takes the same arguments as `matches_path` and reports, per pattern in input
+/// order, whether it matched and whether a fast path or the regex decided, along
+/// with any dot rule or ignore pattern exclusion. Intended for debugging glob rules.
+///
+/// @param path - The candidate path
+/// @param pattern - Glob pattern or array of patterns
+/// @param options - Matching options
+/// @returns A report describing the match decision
+#[napi]
+pub fn explain_match(
+    path: String,
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<MatchOptions>,
+) -> Result<MatchExplanation> {
+    let base = options.as_ref().and_then(|o| o.base.clone());
+    let (glob, candidate, normalize_candidate) =
+        build_matcher(path.clone(), pattern, options, true)?;
+    Ok(match candidate {
+        Some(candidate) => glob.explain_match(&candidate, normalize_candidate),
+        None => {
+            let mut explanation = glob.explain_match("", normalize_candidate);
+            explanation.reason = format!("'{path}' is outside base '{}'", base.unwrap_or_default());
+            explanation.candidate = path;
+            explanation
+        }
+    })
 }
 
+/// Test whether a directory could contain matches for the pattern(s).
+///
+/// This is the check globlin's own walker uses to skip whole subtrees, exposed
+/// for callers that traverse the filesystem themselves: when it returns false,
+/// nothing at or below `dirPath` can match, so the directory need not be read.
+/// A true result only means a match is possible. Ignore patterns that exclude a
+/// directory's children (`dir/**`) also make it prunable.
+///
+/// @param dirPath - The directory, relative to the current directory (`.` or `""` for cwd itself)
+/// @param pattern - Glob pattern or array of patterns
+/// @param options - Matching options
+/// @returns False if the directory can be pruned
 #[napi]
-pub async fn glob(
-    pattern: Either<String, Vec<String>>,
+pub fn can_contain_match(
+    dir_path: String,
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<MatchOptions>,
+) -> Result<bool> {
+    let (glob, dir_path, normalize_candidate) = build_matcher(dir_path, pattern, options, false)?;
+    Ok(dir_path.is_some_and(|dir_path| glob.can_contain_match(&dir_path, normalize_candidate)))
+}
+
+/// Answer returned by `is_ignored`.
+#[napi(object)]
+pub struct IgnoreCheck {
+    /// True if the path itself would be left out of results
+    pub ignored: bool,
+    /// True if everything below the path is ignored (a `dir/**` pattern), so a
+    /// walk would not read the directory
+    #[napi(js_name = "childrenIgnored")]
+    pub children_ignored: bool,
+}
+
+/// Test whether a path would be excluded by ignore patterns, without walking.
+///
+/// Uses the same ignore filter as the glob functions, so `defaultIgnore`,
+/// `rootRelative`, `noext` and `windowsPathsNoEscape` in `options` are honored.
+/// `ignore` takes the place of `options.ignore`. Intended for debugging ignore rules.
+///
+/// @param path - The path, relative to `cwd` (`.` for cwd itself)
+/// @param ignore - Ignore patterns to test
+/// @param options - Glob options (`cwd` resolves absolute ignore patterns)
+/// @returns Whether the path and whether its children are ignored
+#[napi]
+pub fn is_ignored(
+    path: String,
+    ignore: Vec<String>,
     options: Option<GlobOptions>,
-) -> Result<Vec<String>> {
-    let opts = options.unwrap_or_default();
+) -> Result<IgnoreCheck> {
+    let opts = GlobOptions {
+        ignore: Some(Either::B(ignore)),
+        ..options.unwrap_or_default()
+    };
+    validate_options(&opts)?;
+
+    let Some(filter) = build_ignore_filter(&opts, None, pattern_limit(&opts))? else {
+        return Ok(IgnoreCheck {
+            ignored: false,
+            children_ignored: false,
+        });
+    };
+
+    let cwd = opts
+        .cwd
+        .as_deref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
+    let abs_cwd = strip_windows_extended_prefix(cwd.canonicalize().unwrap_or(cwd));
+
+    // Paths are compared the way the walk produces them: `/`-separated, no `./`
+    let path = if opts.effective_windows_paths_no_escape() || cfg!(windows) {
+        path.replace('\\', "/")
+    } else {
+        path
+    };
+    let rel = preprocess_pattern(&path);
+    let rel = match rel.trim_end_matches('/') {
+        "" if rel.starts_with('/') => "/",
+        "" => ".",
+        trimmed => trimmed,
+    };
+    let abs = if rel == "." {
+        abs_cwd
+    } else {
+        abs_cwd.join(rel)
+    };
+
+    Ok(IgnoreCheck {
+        ignored: filter.should_ignore(rel, &abs),
+        children_ignored: filter.children_ignored(rel, &abs),
+    })
+}
+
+/// Ignore patterns compiled once and shared by every glob given them as the
+/// `ignoreSet` option, so a long ignore list isn't recompiled on each call.
+#[napi]
+#[derive(Clone)]
+pub struct IgnoreSet {
+    filter: Arc<IgnoreFilter>,
+}
+
+#[napi]
+impl IgnoreSet {
+    /// Compile `patterns` as the `ignore` option would be compiled.
+    ///
+    /// `defaultIgnore`, `rootRelative`, `noext` and `windowsPathsNoEscape` in
+    /// `options` are honored; `options.ignore` is replaced by `patterns`.
+    ///
+    /// @param patterns - Ignore patterns
+    /// @param options - Glob options the patterns are compiled with
+    #[napi(constructor)]
+    pub fn new(patterns: Vec<String>, options: Option<GlobOptions>) -> Result<Self> {
+        let opts = GlobOptions {
+            ignore: Some(Either::B(patterns)),
+            ..options.unwrap_or_default()
+        };
+        validate_options(&opts)?;
+        let filter = build_ignore_filter(&opts, None, pattern_limit(&opts))?.unwrap_or_else(|| {
+            IgnoreFilter::new(
+                Vec::new(),
+                opts.noext.unwrap_or(false),
+                opts.effective_windows_paths_no_escape(),
+            )
+        });
+        Ok(Self {
+            filter: Arc::new(filter),
+        })
+    }
+}
+
+// Lets `GlobOptions` hold an `IgnoreSet` instance by value: the compiled filter
+// is shared, not copied
+impl FromNapiValue for IgnoreSet {
+    unsafe fn from_napi_value(env: sys::napi_env, napi_val: sys::napi_value) -> Result<Self> {
+        <&IgnoreSet as ValidateNapiValue>::validate(env, napi_val)?;
+        let set: &IgnoreSet = FromNapiRef::from_napi_ref(env, napi_val)?;
+        Ok(set.clone())
+    }
+}
+
+/// Build the `Glob` and candidate path shared by `matches_path` and `explain_match`.
+///
+/// With `keep_pattern_order` the patterns stay in input order instead of being
+/// sorted fast-path first, for reports that list them.
+fn build_matcher(
+    path: String,
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<MatchOptions>,
+    keep_pattern_order: bool,
+) -> Result<(Glob, Option<String>, bool)> {
+    let match_opts = options.unwrap_or_default();
+    let opts = GlobOptions {
+        preserve_pattern_order: Some(keep_pattern_order),
+        ..GlobOptions::from(&match_opts)
+    };
+    let separator = match_opts.effective_separator()?;
 
-    // Validate options using the centralized validation
     validate_options(&opts)?;
 
-    let patterns = match pattern {
-        Either::A(s) => vec![s],
-        Either::B(v) => v,
+    // Absolute candidates are made relative to `base`; `None` if they lie outside it
+    let path = match match_opts.base.as_deref() {
+        Some(base) => {
+            let keep_backslashes =
+                opts.preserve_backslashes.unwrap_or(false) && !cfg!(target_os = "windows");
+            let slashed = |s: &str| {
+                if keep_backslashes {
+                    s.to_string()
+                } else {
+                    s.replace('\\', "/")
+                }
+            };
+            let candidate = slashed(&path);
+            match strip_candidate_base(&candidate, &slashed(base), opts.effective_nocase()) {
+                Some(relative) => Some(relative.to_string()),
+                None if match_opts.strict_base.unwrap_or(false) => {
+                    return Err(Error::from_reason(format!(
+                        "Path '{path}' is outside base '{base}'"
+                    )));
+                }
+                None => None,
+            }
+        }
+        None => Some(path),
+    };
+
+    let patterns = collect_pattern_inputs(pattern);
+    let glob = match separator {
+        Some(separator) => Glob::try_new_with_separator(patterns, opts, separator)?,
+        None => Glob::try_new_multi_with_overrides(patterns, opts)?,
     };
+    Ok((glob, path, match_opts.normalize_candidate.unwrap_or(false)))
+}
 
-    let glob = Glob::new_multi(patterns, opts.clone());
-    Ok(glob.walk_sync())
+/// Matches and pattern warnings returned together by `glob_sync_checked`.
+#[napi(object)]
+pub struct CheckedGlobResult {
+    /// The matching paths, exactly as `globSync` would return them
+    pub results: Vec<String>,
+    /// Warnings for the patterns (empty if no issues detected)
+    pub warnings: Vec<PatternWarningInfo>,
 }
 
-/// Synchronous glob pattern matching with file type information.
-/// Returns PathData objects instead of strings.
+/// How a single pattern fared against a candidate path in `explain_match`.
+#[napi(object)]
+pub struct PatternExplanation {
+    /// The pattern after brace expansion
+    pub pattern: String,
+    /// True if the pattern matches the candidate (including the directory requirement)
+    pub matched: bool,
+    /// The fast path that decided the result, or `None` if the regex was used
+    pub fast_path: Option<String>,
+    /// The compiled regex for the whole pattern
+    pub regex: String,
+    /// True if the pattern ends in `/` and so only matches directories
+    pub requires_directory: bool,
+}
+
+/// Diagnostic report returned by `explain_match`.
+#[napi(object)]
+pub struct MatchExplanation {
+    /// Whether the path matches, exactly as `matchesPath` would answer
+    pub matched: bool,
+    /// The candidate that was tested, after `./`, trailing `/` and normalization handling
+    pub candidate: String,
+    /// True if the path was treated as a directory (it ended in `/`)
+    pub is_directory: bool,
+    /// True if a dot segment in the path is not allowed by any pattern
+    pub excluded_by_dot: bool,
+    /// True if an ignore pattern matches the path
+    pub ignored: bool,
+    /// One entry per pattern, in the order they are tried
+    pub patterns: Vec<PatternExplanation>,
+    /// A short human-readable summary of the outcome
+    pub reason: String,
+}
+
+/// Synchronous glob pattern matching that also reports pattern warnings.
+/// The patterns are analyzed once while the glob is constructed, so callers
+/// that want both matches and warnings don't need a separate `analyzePatterns` pass.
 #[napi]
-pub fn glob_sync_with_file_types(
-    pattern: Either<String, Vec<String>>,
+pub fn glob_sync_checked(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
     options: Option<GlobOptions>,
-) -> Result<Vec<PathData>> {
+) -> Result<CheckedGlobResult> {
     let opts = options.unwrap_or_default();
 
-    // Validate options using the centralized validation
     validate_options(&opts)?;
 
-    let patterns = match pattern {
-        Either::A(s) => vec![s],
-        Either::B(v) => v,
-    };
+    let patterns = collect_pattern_inputs(pattern);
+    let pattern_strs: Vec<String> = patterns.iter().map(|p| p.pattern.clone()).collect();
+    let warnings = analyze_patterns(
+        &pattern_strs,
+        opts.windows_paths_no_escape.unwrap_or(false),
+        opts.platform.as_deref(),
+    )
+    .into_iter()
+    .chain(option_pattern_warnings(&pattern_strs, &opts))
+    .map(PatternWarningInfo::from)
+    .collect();
+
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts.clone())?;
+    Ok(CheckedGlobResult {
+        results: glob.check_walk(glob.walk_sync())?,
+        warnings,
+    })
+}
 
-    let glob = Glob::new_multi(patterns, opts.clone());
-    Ok(glob.walk_sync_with_file_types())
+/// A result path with the input patterns that matched it, returned by `globSyncAnnotated`.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnotatedPath {
+    /// The matching path, formatted as `globSync` would return it
+    pub path: String,
+    /// Indices into the input patterns of every pattern that matches the path
+    pub matched_patterns: Vec<u32>,
 }
 
-/// Asynchronous glob pattern matching with file type information.
-/// Returns PathData objects instead of strings.
+/// Synchronous glob pattern matching that reports, for each result, every
+/// input pattern that matches it.
+///
+/// Each path is checked against all patterns instead of stopping at the first
+/// match, so this is slower than `globSync`; use it for auditing overlaps.
+///
+/// @param patterns - Glob patterns
+/// @param options - Glob options
+/// @returns The matching paths with the indices of the patterns that matched each
 #[napi]
-pub async fn glob_with_file_types(
-    pattern: Either<String, Vec<String>>,
+pub fn glob_sync_annotated(
+    patterns: Vec<Either<String, PatternInput>>,
     options: Option<GlobOptions>,
-) -> Result<Vec<PathData>> {
+) -> Result<Vec<AnnotatedPath>> {
     let opts = options.unwrap_or_default();
 
-    // Validate options using the centralized validation
     validate_options(&opts)?;
 
-    let patterns = match pattern {
-        Either::A(s) => vec![s],
-        Either::B(v) => v,
-    };
+    let patterns = collect_pattern_inputs(Either::B(patterns));
 
-    let glob = Glob::new_multi(patterns, opts.clone());
-    Ok(glob.walk_sync_with_file_types())
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts.clone())?;
+    glob.check_walk(glob.walk_sync_annotated())
 }
 
-/// Streaming glob pattern matching.
-/// Streams results back to JavaScript via a callback function.
-/// This reduces peak memory usage for large result sets by not collecting all results before sending.
+/// A result path split into its parts, returned by `globSyncParsed`.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedPath {
+    /// Directory part, like `path.parse().dir` (empty for matches directly in cwd)
+    pub dir: String,
+    /// File name without its extension, like `path.parse().name`
+    pub name: String,
+    /// Extension including the leading dot, like `path.parse().ext` (empty if none)
+    pub ext: String,
+}
+
+impl ParsedPath {
+    /// Split a result path the way Node's `path.parse` does. Separators are left
+    /// as they are, so `dir` uses the same ones as the result (see `posix`).
+    fn parse(path: &str) -> Self {
+        let path = Path::new(path);
+        let dir = path
+            .parent()
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        // `.` has no file name; path.parse reports it as the name
+        let name = match path.file_stem() {
+            Some(stem) => stem.to_string_lossy().into_owned(),
+            None => path.to_string_lossy().into_owned(),
+        };
+        let ext = path
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .unwrap_or_default();
+        Self { dir, name, ext }
+    }
+}
+
+/// Synchronous glob pattern matching that returns each result split into its
+/// directory, name and extension, saving a `path.parse()` per result in JavaScript.
 ///
 /// @param pattern - Glob pattern or array of patterns
 /// @param options - Glob options
-/// @param callback - Function called with each result string
-/// @returns Promise that resolves when all results have been streamed
+/// @returns The matching paths, each split like `path.parse` would
 #[napi]
-pub fn glob_stream(
-    pattern: Either<String, Vec<String>>,
+pub fn glob_sync_parsed(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
     options: Option<GlobOptions>,
-    #[napi(ts_arg_type = "(result: string) => void")] callback: ThreadsafeFunction<String>,
-) -> Result<()> {
+) -> Result<Vec<ParsedPath>> {
     let opts = options.unwrap_or_default();
 
-    // Validate options using the centralized validation
     validate_options(&opts)?;
 
-    let patterns = match pattern {
-        Either::A(s) => vec![s],
-        Either::B(v) => v,
-    };
-
-    let glob = Glob::new_multi(patterns, opts);
+    let patterns = collect_pattern_inputs(pattern);
 
-    // Stream results directly to JavaScript callback
-    // This avoids collecting all results into a Vec, reducing peak memory usage
-    glob.walk_stream(|result| {
-        // Call the JS callback with each result
-        // Use NonBlocking mode to avoid blocking the walking thread
-        callback.call(Ok(result), ThreadsafeFunctionCallMode::NonBlocking);
-    });
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts)?;
+    Ok(glob
+        .check_walk(glob.walk_sync())?
+        .iter()
+        .map(|path| ParsedPath::parse(path))
+        .collect())
+}
 
-    Ok(())
+/// A result path with its position in discovery order, returned by `globSyncIndexed`.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedPath {
+    /// The matching path, formatted as `globSync` would return it
+    pub path: String,
+    /// Zero-based position of the path in the order the walk produced it
+    pub index: u32,
 }
 
-/// Streaming glob pattern matching with file type information.
-/// Streams PathData results back to JavaScript via a callback function.
+/// Synchronous glob pattern matching that numbers each result in the order
+/// the walk discovered it.
 ///
-/// @param pattern - Glob pattern or array of patterns  
+/// Results are returned in walk order, so `index` is contiguous from 0. Use
+/// it to recover the original order after sorting or grouping the results.
+///
+/// @param pattern - Glob pattern or array of patterns
 /// @param options - Glob options
-/// @param callback - Function called with each PathData result
-/// @returns Promise that resolves when all results have been streamed
+/// @returns The matching paths with their discovery index
 #[napi]
-pub fn glob_stream_with_file_types(
-    pattern: Either<String, Vec<String>>,
+pub fn glob_sync_indexed(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
     options: Option<GlobOptions>,
-    #[napi(
-        ts_arg_type = "(result: { path: string, isDirectory: boolean, isFile: boolean, isSymlink: boolean }) => void"
-    )]
-    callback: ThreadsafeFunction<PathData>,
-) -> Result<()> {
+) -> Result<Vec<IndexedPath>> {
     let opts = options.unwrap_or_default();
 
-    // Validate options using the centralized validation
     validate_options(&opts)?;
 
-    let patterns = match pattern {
-        Either::A(s) => vec![s],
-        Either::B(v) => v,
-    };
-
-    let glob = Glob::new_multi(patterns, opts);
+    let patterns = collect_pattern_inputs(pattern);
 
-    // Stream results directly to JavaScript callback
-    glob.walk_stream_with_file_types(|result| {
-        callback.call(Ok(result), ThreadsafeFunctionCallMode::NonBlocking);
-    });
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts.clone())?;
+    glob.check_walk(glob.walk_sync_indexed())
+}
 
-    Ok(())
+/// A result path with its depth below cwd, returned by `globSyncDepths`.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathDepth {
+    /// The matching path, formatted as `globSync` would return it
+    pub path: String,
+    /// Number of path segments below cwd (0 for cwd itself, 1 for entries directly in cwd)
+    pub depth: u32,
 }
 
-impl Glob {
-    /// Create a new Glob from a single pattern string
-    pub fn new(pattern_str: String, options: GlobOptions) -> Self {
-        Self::new_multi(vec![pattern_str], options)
-    }
+/// Synchronous glob pattern matching that reports how deep below cwd each
+/// result lies.
+///
+/// Depth counts the path segments below cwd: cwd itself (`.`) is 0, `foo.txt`
+/// is 1 and `src/main.js` is 2. It is taken from the walked path before it is
+/// formatted, so `absolute`, `mark`, `dotRelative` and `resultPrefix` don't
+/// change it.
+///
+/// @param pattern - Glob pattern or array of patterns
+/// @param options - Glob options
+/// @returns The matching paths with their depth below cwd
+#[napi]
+pub fn glob_sync_depths(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+) -> Result<Vec<PathDepth>> {
+    let opts = options.unwrap_or_default();
 
-    /// Create a new Glob from multiple pattern strings
-    pub fn new_multi(pattern_strs: Vec<String>, options: GlobOptions) -> Self {
-        let cwd = options
-            .cwd
-            .clone()
-            .map(PathBuf::from)
-            .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
+    validate_options(&opts)?;
 
-        let absolute = options.absolute.unwrap_or(false);
-        let posix_explicit_true = options.posix == Some(true);
-        let posix_explicit_false = options.posix == Some(false);
-        let nobrace = options.nobrace.unwrap_or(false);
-        let noext = options.noext.unwrap_or(false);
-        let dot = options.dot.unwrap_or(false);
-        let follow = options.follow.unwrap_or(false);
-        let windows_paths_no_escape = options.effective_windows_paths_no_escape();
-        let max_depth = options.max_depth;
-        let nodir = options.nodir.unwrap_or(false);
-        let dot_relative = options.dot_relative.unwrap_or(false);
-        let mark = options.mark.unwrap_or(false);
-        let match_base = options.match_base.unwrap_or(false);
-        let noglobstar = options.noglobstar.unwrap_or(false);
-        let nocase = options.effective_nocase();
-        let platform = options.effective_platform();
-        let include_child_matches = options.effective_include_child_matches();
+    let patterns = collect_pattern_inputs(pattern);
 
-        // Create pattern options
-        let pattern_opts = PatternOptions {
-            noext,
-            windows_paths_no_escape,
-            platform: Some(platform.clone()),
-            nocase,
-            nobrace,
-        };
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts.clone())?;
+    glob.check_walk(glob.walk_sync_depths())
+}
 
-        // Process all input patterns and expand braces for each
-        // Use AHashSet to track already-seen pattern strings for deduplication (faster hashing)
-        let mut seen_patterns: AHashSet<String> = AHashSet::new();
-        let mut patterns: Vec<Pattern> = Vec::new();
+/// Totals for the matches of a glob, returned by `globAggregate`.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobAggregate {
+    /// Number of matching paths
+    pub count: u32,
+    /// Sum of the sizes of the matching regular files, in bytes
+    pub total_size: f64,
+}
 
-        for pattern_str in &pattern_strs {
-            // Skip empty patterns - they match nothing (like glob v13)
-            if pattern_str.is_empty() {
-                continue;
-            }
+/// Count the matches and sum the sizes of matching files in a single walk.
+///
+/// Paths are never collected or sent to JavaScript, which makes this much
+/// cheaper than `globSync` followed by a `stat` per result for large trees.
+/// Options like `nodir` and `ignore` apply as usual.
+///
+/// @param pattern - Glob pattern or array of patterns
+/// @param options - Glob options
+/// @returns The number of matches and their total size in bytes
+#[napi]
+pub fn glob_aggregate(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+) -> Result<GlobAggregate> {
+    let opts = options.unwrap_or_default();
 
-            // Check if the ORIGINAL pattern has path separators BEFORE brace expansion
-            // This is important because matchBase should only apply if the entire original
-            // pattern has no separators. If {a,b/c} is used, neither a nor b/c gets matchBase.
-            let original_has_slash = pattern_str.contains('/') || pattern_str.contains('\\');
-
-            // Helper function to apply matchBase transformation to a pattern
-            // Only applies if:
-            // 1. matchBase is true
-            // 2. The ORIGINAL pattern (before brace expansion) has no path separators
-            // 3. The expanded pattern has no path separators
-            let apply_match_base = |pattern: &str| -> String {
-                if match_base
-                    && !original_has_slash
-                    && !pattern.contains('/')
-                    && !pattern.contains('\\')
-                {
-                    format!("**/{pattern}")
-                } else {
-                    pattern.to_string()
-                }
-            };
+    validate_options(&opts)?;
 
-            // Expand braces unless nobrace is set
-            if nobrace {
-                let transformed = apply_match_base(pattern_str);
-                // Deduplicate: only add if we haven't seen this pattern before
-                if seen_patterns.insert(transformed.clone()) {
-                    // Use pattern cache for compiled patterns
-                    patterns.push(get_or_compile_pattern(&transformed, &pattern_opts));
-                }
-            } else {
-                let expanded = expand_braces(pattern_str);
-                if expanded.is_empty() {
-                    let transformed = apply_match_base(pattern_str);
-                    if seen_patterns.insert(transformed.clone()) {
-                        // Use pattern cache for compiled patterns
-                        patterns.push(get_or_compile_pattern(&transformed, &pattern_opts));
-                    }
-                } else {
-                    for p in expanded {
-                        let transformed = apply_match_base(&p);
-                        // Deduplicate: skip duplicate expanded patterns
-                        if seen_patterns.insert(transformed.clone()) {
-                            // Use pattern cache for compiled patterns
-                            patterns.push(get_or_compile_pattern(&transformed, &pattern_opts));
-                        }
-                    }
-                }
-            }
-        }
+    let patterns = collect_pattern_inputs(pattern);
 
-        // Optimization: Sort patterns so fast-path patterns come first.
-        // This allows early exit when using .any() since fast patterns are checked first.
-        // Patterns with fast-path matching are much quicker to evaluate.
-        patterns.sort_by(|a, b| {
-            // Fast-path patterns should come first
-            let a_fast = a.fast_path().is_fast();
-            let b_fast = b.fast_path().is_fast();
-            match (a_fast, b_fast) {
-                (true, false) => std::cmp::Ordering::Less,
-                (false, true) => std::cmp::Ordering::Greater,
-                _ => std::cmp::Ordering::Equal,
-            }
-        });
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts.clone())?;
+    glob.check_walk(glob.walk_aggregate())
+}
 
-        // Create ignore filter if ignore patterns provided
-        let ignore_filter = match &options.ignore {
-            Some(Either::A(pattern)) => Some(IgnoreFilter::new(
-                vec![pattern.clone()],
-                noext,
-                windows_paths_no_escape,
-            )),
-            Some(Either::B(patterns)) => {
-                if patterns.is_empty() {
-                    None
-                } else {
-                    Some(IgnoreFilter::new(
-                        patterns.clone(),
-                        noext,
-                        windows_paths_no_escape,
-                    ))
-                }
-            }
-            None => None,
-        };
+/// The number of matches in one directory, returned by `globHistogram`.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirCount {
+    /// Directory containing the matches, relative to cwd and cut to the
+    /// requested depth (`.` for matches directly in cwd)
+    pub dir: String,
+    /// Number of matches in that directory or below it
+    pub count: u32,
+}
 
-        // Create walk options
-        // Note: We always walk with dot=true in the walker, and handle dot filtering
-        // at the pattern matching level. This allows patterns with explicit dots
-        // (like ".hidden" or "**/.config") to match even when dot:false.
+/// Count matches per directory in a single walk.
+///
+/// Each match is counted against the directory that contains it, cut to its
+/// first `depth` segments, so `depth: 1` gives per-top-level-directory counts.
+/// Matches directly in cwd, and matches shallower than `depth`, are counted under
+/// their own parent. Paths are never sent to JavaScript.
+///
+/// @param pattern - Glob pattern or array of patterns
+/// @param options - Glob options
+/// @param depth - Number of leading directory segments to group by
+/// @returns One entry per directory with matches, sorted by directory
+#[napi]
+pub fn glob_histogram(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+    depth: u32,
+) -> Result<Vec<DirCount>> {
+    let opts = options.unwrap_or_default();
 
-        // Calculate effective max depth from patterns for optimization.
-        // If all patterns have a bounded depth (no **), we can limit the walker
-        // to avoid traversing deeper than necessary.
-        // Use the maximum depth required by any pattern.
-        let pattern_max_depth = {
-            let mut max_depth: Option<usize> = Some(0);
-            for p in &patterns {
-                match (max_depth, p.max_depth()) {
-                    (None, _) => break, // Already unlimited
-                    (_, None) => {
-                        max_depth = None;
-                        break;
-                    } // This pattern is unlimited
-                    (Some(a), Some(b)) => max_depth = Some(a.max(b)), // Take max of bounded depths
-                }
-            }
-            max_depth
-        };
+    validate_options(&opts)?;
 
-        // Combine user-provided max_depth with pattern-derived depth.
-        // User max_depth takes precedence (it's an explicit limit), but if user
-        // didn't specify one, use pattern-derived depth for optimization.
-        // Convert max_depth: negative values and Some(-1) will be handled in walk_sync
-        let walker_max_depth = match (max_depth, pattern_max_depth) {
-            (Some(d), _) if d < 0 => Some(0), // Negative values: will return empty in walk_sync
-            (Some(d), _) => Some(d as usize), // User-provided depth takes precedence
-            (None, Some(d)) => Some(d + 1),   // Pattern depth + 1 (for root directory)
-            (None, None) => None,             // Unlimited (has **)
-        };
+    // Bucket on plain cwd-relative, `/`-separated paths
+    let opts = GlobOptions {
+        absolute: None,
+        dot_relative: None,
+        posix: Some(true),
+        result_prefix: None,
+        ..opts
+    };
+    let patterns = collect_pattern_inputs(pattern);
 
-        // Optimization: Only enable accurate symlink detection when needed.
-        // The `mark` option requires knowing whether an entry is a symlink to avoid
-        // adding a trailing slash. When following symlinks, walkdir reports the TARGET
-        // type, so we need an extra syscall to detect the symlink. Skip this overhead
-        // when not needed.
-        let need_accurate_symlink_detection = mark && follow;
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts)?;
+    glob.check_walk(glob.walk_histogram(depth as usize))
+}
 
-        let parallel = options.parallel.unwrap_or(false);
-        let cache = options.cache.unwrap_or(false);
-        let use_native_io = options.use_native_io.unwrap_or(false);
-        let use_gcd = options.use_gcd.unwrap_or(false);
+/// How a glob would be executed, returned by `globPlan`.
+#[napi(object)]
+pub struct GlobPlan {
+    /// `Static`, `Shallow`, `MultiBase`, `PrefixScoped` or `FullWalk`
+    pub strategy: String,
+    /// Directory the walk starts from (cwd unless the strategy is `PrefixScoped`)
+    #[napi(js_name = "walkRoot")]
+    pub walk_root: String,
+    /// Literal prefix shared by all patterns, for `PrefixScoped`
+    pub prefix: Option<String>,
+}
 
-        let walk_options = WalkOptions::new()
-            .follow_symlinks(follow)
-            .max_depth(walker_max_depth)
-            .dot(true)
-            .need_accurate_symlink_detection(need_accurate_symlink_detection)
-            .parallel(parallel)
-            .cache(cache)
-            .use_native_io(use_native_io)
-            .use_gcd(use_gcd);
+/// Report which optimization path `globSync` would take, without walking.
+///
+/// `Static` stats each literal path, `Shallow` reads cwd once, `MultiBase` walks
+/// each distinct literal base directory separately, `PrefixScoped` walks once from
+/// the shared literal prefix and `FullWalk` walks once from cwd. Intended for
+/// checking that patterns hit the fast paths.
+///
+/// @param pattern - Glob pattern or array of patterns
+/// @param options - Glob options
+/// @returns The strategy, walk root and shared prefix
+#[napi]
+pub fn glob_plan(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+) -> Result<GlobPlan> {
+    let opts = options.unwrap_or_default();
 
-        // Pre-compute: check if any pattern requires directory matching (ends with /)
-        let any_pattern_requires_dir = patterns.iter().any(|p| p.requires_dir());
+    validate_options(&opts)?;
 
-        // Pre-compute: count patterns with fast-path matching for optimization decisions
-        let fast_pattern_count = patterns.iter().filter(|p| p.fast_path().is_fast()).count();
+    let patterns = collect_pattern_inputs(pattern);
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts)?;
+    Ok(glob.plan())
+}
 
-        // Convert to Arc<[Pattern]> for cheap cloning into closures
-        let patterns: Arc<[Pattern]> = patterns.into();
+/// Order of two cwd-relative, `/`-separated paths in a sorted depth-first walk:
+/// cwd (`.`) first, then component by component, so each directory sorts right
+/// before its contents.
+fn walk_order(a: &str, b: &str) -> std::cmp::Ordering {
+    match (a == ".", b == ".") {
+        (true, true) => std::cmp::Ordering::Equal,
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        (false, false) => a.split('/').cmp(b.split('/')),
+    }
+}
+
+/// True if `dir` and everything below it come before `cursor` in walk order.
+fn subtree_precedes(dir: &str, cursor: &str) -> bool {
+    let is_ancestor = cursor
+        .strip_prefix(dir)
+        .is_some_and(|rest| rest.starts_with('/'));
+    walk_order(dir, cursor).is_lt() && !is_ancestor
+}
+
+/// One page of results, returned by `globPage`.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobPage {
+    /// Matching paths, in walk order
+    pub results: Vec<String>,
+    /// Pass to the next `globPage` call to continue; unset on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Encode a cwd-relative path as an opaque page cursor (hex of its UTF-8 bytes).
+fn encode_cursor(path: &str) -> String {
+    path.bytes().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Decode a cursor produced by `encode_cursor`.
+fn decode_cursor(cursor: &str) -> Result<String> {
+    let invalid = || Error::from_reason(format!("Invalid cursor: {cursor}"));
+    if !cursor.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| {
+            cursor
+                .get(i..i + 2)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(invalid)?;
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+/// Return one page of results, resuming after `cursor`.
+///
+/// Directory entries are read in sorted order on a single thread, so the walk
+/// order is deterministic and a page can pick up where the previous one stopped
+/// without keeping a walker alive in between. Subtrees that lie entirely before
+/// the cursor are not walked again.
+///
+/// @param pattern - Glob pattern or array of patterns
+/// @param options - Glob options (`includeChildMatches: false` is not supported)
+/// @param cursor - `nextCursor` from the previous page, or unset for the first page
+/// @param pageSize - Maximum number of results in the page
+/// @returns The page's results and the cursor for the next page
+#[napi]
+pub fn glob_page(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+    cursor: Option<String>,
+    page_size: u32,
+) -> Result<GlobPage> {
+    let opts = options.unwrap_or_default();
+
+    validate_options(&opts)?;
 
+    if page_size == 0 {
+        return Err(Error::from_reason("pageSize must be at least 1"));
+    }
+    if opts.include_child_matches == Some(false) {
+        return Err(Error::from_reason(
+            "globPage does not support includeChildMatches: false",
+        ));
+    }
+    let start_after = cursor.as_deref().map(decode_cursor).transpose()?;
+
+    // Resuming needs the same order on every call: sorted, serial and with each
+    // directory before its contents, the order the cursor is compared in
+    let opts = GlobOptions {
+        sort_dir_entries: Some(true),
+        dir_order: Some(DirOrder::PreOrder),
+        parallel: Some(false),
+        cache: Some(false),
+        use_native_io: Some(false),
+        use_gcd: Some(false),
+        ..opts
+    };
+    let patterns = collect_pattern_inputs(pattern);
+
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts)?;
+    let (results, last) =
+        glob.check_walk(glob.walk_page(start_after.as_deref(), page_size as usize))?;
+    Ok(GlobPage {
+        results,
+        next_cursor: last.as_deref().map(encode_cursor),
+    })
+}
+
+/// Keeps the `limit` smallest items pushed into it.
+///
+/// The items are held in a max-heap, so the largest kept item is replaced
+/// whenever a smaller one arrives and memory stays bounded by `limit` however
+/// many items are pushed. `limit` comes from the caller, so only a small
+/// capacity is reserved up front and the heap grows with the items it holds.
+struct TopN<T> {
+    limit: usize,
+    heap: BinaryHeap<T>,
+}
+
+impl<T: Ord> TopN<T> {
+    fn new(limit: usize) -> Self {
         Self {
-            pattern_strs,
-            cwd,
-            patterns,
-            absolute,
-            posix_explicit_true,
-            posix_explicit_false,
-            nobrace,
-            noext,
-            dot,
-            follow,
-            windows_paths_no_escape,
-            max_depth,
-            nodir,
-            dot_relative,
-            mark,
-            match_base,
-            noglobstar,
-            nocase,
-            walk_options,
-            ignore_filter,
-            any_pattern_requires_dir,
-            fast_pattern_count,
-            include_child_matches,
+            limit,
+            heap: BinaryHeap::with_capacity(limit.min(1024)),
         }
     }
 
-    pub fn walk_sync(&self) -> Vec<String> {
-        // If maxDepth is negative, return empty results
-        if let Some(d) = self.max_depth {
-            if d < 0 {
-                return Vec::new();
+    fn push(&mut self, item: T) {
+        if self.heap.len() < self.limit {
+            self.heap.push(item);
+        } else if let Some(mut largest) = self.heap.peek_mut() {
+            if item < *largest {
+                *largest = item;
             }
         }
+    }
 
-        // OPTIMIZATION: Static pattern fast path
-        // If ALL patterns are static (no wildcards), we can use direct stat() instead of walking.
-        // This is 10-100x faster for patterns like "package.json" or "src/index.ts".
-        if self.all_patterns_static() {
-            return self.resolve_static_patterns();
-        }
+    /// The kept items, smallest first.
+    fn into_sorted_vec(self) -> Vec<T> {
+        self.heap.into_sorted_vec()
+    }
+}
 
-        // OPTIMIZATION: Shallow pattern fast path
-        // If ALL patterns have max_depth of 0 (root-level only), use direct readdir
-        // instead of the full walker machinery. This is 2-3x faster for patterns like "*.js".
-        // NOTE: We must respect user's maxDepth if specified. maxDepth: 0 means only "."
-        // which can't match shallow patterns like "*.js" - those need depth 1.
-        // If user specified maxDepth: 0, skip this optimization and let the walker handle it.
-        if self.all_patterns_shallow() && self.ignore_filter.is_none() && self.max_depth != Some(0)
-        {
-            return self.resolve_shallow_patterns();
-        }
+/// Return the first `count` matches in lexical order.
+///
+/// Any path could sort first, so the whole tree is still walked, but only the
+/// `count` smallest results seen so far are kept: memory is bounded by `count`
+/// rather than by the number of matches, and the answer doesn't depend on the
+/// walk order. Results are compared by Unicode code point.
+///
+/// @param pattern - Glob pattern or array of patterns
+/// @param options - Glob options
+/// @param count - Maximum number of results to return
+/// @returns Up to `count` matching paths, sorted
+#[napi]
+pub fn glob_sync_first(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+    count: u32,
+) -> Result<Vec<String>> {
+    let opts = options.unwrap_or_default();
 
-        // OPTIMIZATION: Multi-base walking
-        // If patterns have different prefixes (e.g., ['src/**/*.ts', 'test/**/*.ts']),
-        // walk from each prefix separately instead of from cwd.
-        // This avoids traversing unrelated directories.
-        if self.should_use_multi_base_walking() {
-            return self.walk_multi_base();
-        }
+    validate_options(&opts)?;
 
-        // Pre-allocate result vector with estimated capacity based on pattern depth.
-        // Simple patterns (depth 0-1) typically match fewer files than recursive patterns.
-        // This reduces reallocations during collection.
-        let estimated_capacity = self.estimate_result_capacity();
-        let mut results = Vec::with_capacity(estimated_capacity);
-        // Use AHashSet for faster hashing than std::collections::HashSet
-        let mut seen: AHashSet<String> = AHashSet::with_capacity(estimated_capacity);
-        let mut ignored_dirs: AHashSet<String> = AHashSet::with_capacity(8); // Most globs have few ignored dirs
+    if count == 0 {
+        return Err(Error::from_reason("count must be at least 1"));
+    }
+    let patterns = collect_pattern_inputs(pattern);
 
-        // When includeChildMatches is false, track (result, normalized) pairs for post-filtering
-        // This is needed because filesystem order may cause children to be seen before parents on Linux
-        let mut matched_with_normalized: Vec<(String, String)> = if self.include_child_matches {
-            Vec::new() // Empty, won't be used
-        } else {
-            Vec::with_capacity(estimated_capacity)
-        };
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts)?;
+    glob.check_walk(glob.walk_sync_first(count as usize))
+}
 
-        // Pre-allocate a reusable buffer for path formatting
-        let mut result_buffer = String::with_capacity(self.estimate_path_buffer_capacity());
+/// Run the same glob in several directories and return one result array per
+/// directory, in the order of `cwds`.
+///
+/// Patterns and options are validated and compiled once, then re-rooted at each
+/// directory, so this avoids the per-call setup of calling `globSync` in a loop.
+/// With `parallel: true` the directories are spread over the rayon thread pool
+/// and each one is walked serially.
+///
+/// @param cwds - Directories to search, overriding `options.cwd`
+/// @param pattern - Glob pattern or array of patterns
+/// @param options - Glob options
+/// @returns The matching paths for each directory, relative to that directory
+#[napi]
+pub fn glob_batch(
+    cwds: Vec<String>,
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+) -> Result<Vec<Vec<String>>> {
+    let opts = options.unwrap_or_default();
 
-        // Check if any pattern matches the cwd itself ("**" or ".").
-        // Cache this check since preprocess_pattern is called for each pattern.
-        let include_cwd = self.patterns.iter().any(|p| {
-            let raw = p.raw();
-            // Fast path: check common cases without calling preprocess_pattern
-            raw == "**" || raw == "." || raw == "./**" || {
-                let preprocessed = preprocess_pattern(raw);
-                preprocessed == "**" || preprocessed == "."
-            }
-        });
+    validate_options(&opts)?;
 
-        // Get the absolute cwd path, canonicalized
-        // Strip Windows extended-length prefix (\\?\) to match glob v13 behavior
-        let abs_cwd = strip_windows_extended_prefix(
-            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
-        );
+    let patterns = collect_pattern_inputs(pattern);
 
-        // Calculate the walk root based on literal prefixes of all patterns.
-        // If all patterns share a common literal prefix, we can start walking from there
-        // instead of the cwd, which can significantly reduce the number of files traversed.
-        let (walk_root, prefix_to_strip) = self.calculate_walk_root();
+    let Some(first_cwd) = cwds.first() else {
+        return Ok(Vec::new());
+    };
+    let parallel = opts.parallel.unwrap_or(false);
+    // Directories are already spread over the rayon pool; walking each with the
+    // parallel walker as well would block workers waiting on the same pool.
+    let opts = GlobOptions {
+        cwd: Some(first_cwd.clone()),
+        parallel: Some(false),
+        ..opts
+    };
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts)?;
 
-        // Pre-compute the prefix with trailing slash for efficient path concatenation.
-        // This avoids repeated format!() calls in the hot loop.
-        let prefix_with_slash: Option<String> =
-            prefix_to_strip.as_ref().map(|prefix| format!("{prefix}/"));
+    let walk_cwd = |cwd: &String| -> Result<Vec<String>> {
+        let glob = glob.with_cwd(cwd)?;
+        glob.check_walk(glob.walk_sync())
+    };
 
-        // Adjust walk options for prefix-based walking
-        // If we have a prefix, the user's max_depth is relative to cwd, but the walker
-        // is relative to walk_root. We need to reduce max_depth by the prefix depth.
-        let adjusted_walk_options = if let Some(ref prefix) = prefix_to_strip {
-            let prefix_depth = prefix.split('/').filter(|s| !s.is_empty()).count();
-            if let Some(max_d) = self.walk_options.max_depth {
-                // User specified max_depth, adjust for prefix
-                if max_d <= prefix_depth {
-                    // If max_depth is less than or equal to prefix depth,
-                    // we should only include the prefix directory itself
-                    self.walk_options.clone().max_depth(Some(0))
-                } else {
-                    self.walk_options
-                        .clone()
-                        .max_depth(Some(max_d - prefix_depth))
-                }
-            } else {
-                self.walk_options.clone()
-            }
-        } else {
-            self.walk_options.clone()
-        };
+    if parallel {
+        cwds.par_iter().map(walk_cwd).collect()
+    } else {
+        cwds.iter().map(walk_cwd).collect()
+    }
+}
 
-        // Create a directory pruning filter using the patterns' could_match_in_dir method.
-        // This allows us to skip entire directory subtrees that can't possibly contain matches.
-        //
-        // The filter receives the path relative to walk_root, but the patterns expect paths
-        // relative to cwd. When we have a prefix_to_strip, we need to prepend it.
-        // Use Arc::clone for cheap reference counting instead of deep cloning patterns.
-        let patterns_for_filter = Arc::clone(&self.patterns);
-        let prefix_for_filter = prefix_to_strip.clone();
-        // Pre-compute prefix with slash for the filter to avoid repeated format! calls
-        let prefix_slash_for_filter = prefix_with_slash.clone();
-
-        let prune_filter = Box::new(move |dir_path: &str| -> bool {
-            // Construct the path relative to cwd for pattern matching.
-            // Use Cow to avoid allocation when no prefix is needed.
-            let path_from_cwd: Cow<'_, str> = if let Some(ref prefix) = prefix_for_filter {
-                if dir_path.is_empty() {
-                    Cow::Borrowed(prefix.as_str())
-                } else {
-                    // Use pre-computed prefix with slash for efficiency
-                    if let Some(ref prefix_slash) = prefix_slash_for_filter {
-                        Cow::Owned(format!("{prefix_slash}{dir_path}"))
-                    } else {
-                        Cow::Owned(format!("{prefix}/{dir_path}"))
-                    }
-                }
-            } else {
-                Cow::Borrowed(dir_path)
-            };
-
-            // Check if ANY pattern could potentially match files in this directory.
-            // If no pattern can match, we can safely skip this directory.
-            patterns_for_filter
-                .iter()
-                .any(|p| p.could_match_in_dir(&path_from_cwd))
-        });
-
-        // Create walker with the optimized walk root, adjusted options, and pruning filter
-        let walker = Walker::new(walk_root.clone(), adjusted_walk_options)
-            .with_dir_prune_filter(prune_filter);
+/// Synchronous glob pattern matching with results grouped by input pattern.
+///
+/// The filesystem is walked once. The returned array has one entry per input
+/// pattern, in input order. A path matching several patterns appears in each of
+/// their groups, or only in the first one with `firstMatchOnly`.
+///
+/// @param patterns - Glob patterns
+/// @param options - Glob options
+/// @param firstMatchOnly - Put each path only in the group of the first matching pattern
+/// @returns One result array per input pattern
+#[napi]
+pub fn glob_sync_grouped(
+    patterns: Vec<Either<String, PatternInput>>,
+    options: Option<GlobOptions>,
+    first_match_only: Option<bool>,
+) -> Result<Vec<Vec<String>>> {
+    let opts = options.unwrap_or_default();
 
-        // Optimization: Check if we have any ignore patterns to avoid unnecessary work
-        let has_ignore_filter = self.ignore_filter.is_some();
+    validate_options(&opts)?;
 
-        for entry in walker.walk() {
-            let path = entry.path();
+    let patterns = collect_pattern_inputs(Either::B(patterns));
 
-            // Strip the walk_root prefix to get the path relative to walk_root
-            // Then prepend the prefix_to_strip to get the path relative to cwd
-            let rel_path_from_walk_root = match path.strip_prefix(&walk_root) {
-                Ok(p) => p,
-                Err(_) => continue, // Skip if can't strip prefix
-            };
-            let rel_str_from_walk_root = rel_path_from_walk_root.to_string_lossy();
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts.clone())?;
+    glob.check_walk(glob.walk_sync_grouped(first_match_only.unwrap_or(false)))
+}
 
-            // Cache whether this is the walk root (empty relative path)
-            let is_walk_root_entry = rel_str_from_walk_root.is_empty();
+#[napi]
+pub async fn glob(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+) -> Result<Vec<String>> {
+    let opts = options.unwrap_or_default();
 
-            // Construct the path relative to cwd by prepending the stripped prefix.
-            // Use Cow to avoid allocation when possible.
-            let normalized = self.normalize_path(
-                &rel_str_from_walk_root,
-                &prefix_to_strip,
-                is_walk_root_entry,
-            );
+    // Validate options using the centralized validation
+    validate_options(&opts)?;
 
-            // Check if this path is inside an ignored directory.
-            if self.is_in_ignored_dir(&normalized, &ignored_dirs) {
-                continue;
-            }
+    let patterns = collect_pattern_inputs(pattern);
 
-            // Check ignore patterns
-            // Optimization: Only create rel_path and abs_path when we have ignore patterns
-            if has_ignore_filter {
-                // For operations that need the actual relative path from cwd
-                let rel_path = if prefix_to_strip.is_some() {
-                    PathBuf::from(normalized.as_ref())
-                } else {
-                    rel_path_from_walk_root.to_path_buf()
-                };
-                let abs_path = abs_cwd.join(&rel_path);
-                let ignore_filter = self.ignore_filter.as_ref().unwrap();
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts.clone())?;
+    glob.check_walk(glob.walk_sync())
+}
 
-                // Check if this specific path should be ignored
-                if ignore_filter.should_ignore(&normalized, &abs_path) {
-                    // If children are also ignored, mark this directory
-                    if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
-                        ignored_dirs.insert(normalized.into_owned());
-                    }
-                    continue;
-                }
+/// Synchronous glob pattern matching with file type information.
+/// Returns PathData objects instead of strings.
+#[napi]
+pub fn glob_sync_with_file_types(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+) -> Result<Vec<PathData>> {
+    let opts = options.unwrap_or_default();
 
-                // Also check if this is a directory whose children should be ignored
-                // (for optimization - skip traversing)
-                if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
-                    ignored_dirs.insert(normalized.to_string());
-                }
-            }
+    // Validate options using the centralized validation
+    validate_options(&opts)?;
 
-            // Handle root of walk_root (which might be cwd or cwd/prefix)
-            if is_walk_root_entry && prefix_to_strip.is_none() {
-                // This is the cwd itself - handle specially
-                // Root directory - only include if pattern matches it
-                // With nodir: true, skip even the root directory since it's a directory
-                if include_cwd && !self.nodir {
-                    // Check if cwd itself is ignored
-                    if let Some(ref ignore_filter) = self.ignore_filter {
-                        if ignore_filter.should_ignore(".", &abs_cwd) {
-                            continue;
-                        }
-                    }
+    let patterns = collect_pattern_inputs(pattern);
 
-                    let result = if self.absolute {
-                        let formatted = self.format_path_into_buffer(&abs_cwd, &mut result_buffer);
-                        if self.mark {
-                            if formatted.ends_with('/') || formatted.ends_with('\\') {
-                                formatted.to_string()
-                            } else {
-                                let mut s = formatted.to_string();
-                                s.push('/');
-                                s
-                            }
-                        } else {
-                            formatted.to_string()
-                        }
-                    } else {
-                        // For relative paths, "." becomes "./" with mark:true
-                        if self.mark {
-                            "./".to_string()
-                        } else {
-                            ".".to_string()
-                        }
-                    };
-                    if seen.insert(result.clone()) {
-                        results.push(result);
-                    }
-                }
-                continue;
-            }
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts.clone())?;
+    glob.check_walk(glob.walk_sync_with_file_types())
+}
 
-            // Skip empty normalized paths that aren't the cwd
-            if normalized.is_empty() {
-                continue;
-            }
+/// Asynchronous glob pattern matching with file type information.
+/// Returns PathData objects instead of strings.
+#[napi]
+pub async fn glob_with_file_types(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+) -> Result<Vec<PathData>> {
+    let opts = options.unwrap_or_default();
 
-            // If nodir is true, skip directories
-            // Note: Symlinks are not considered directories unless follow is true
-            // When follow is true, walkdir reports symlinks-to-dirs as dirs
-            if self.nodir && entry.is_dir() {
-                continue;
-            }
+    // Validate options using the centralized validation
+    validate_options(&opts)?;
 
-            // If dot:false, check if this path contains dotfile segments
-            // that aren't explicitly allowed by any pattern
-            if !self.dot && !self.path_allowed_by_dot_rules(&normalized) {
-                continue;
-            }
+    let patterns = collect_pattern_inputs(pattern);
 
-            // Check if any pattern matches
-            // For patterns that end with /, only match if entry is a directory
-            let is_dir = entry.is_dir();
-            let is_symlink = entry.is_symlink();
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts.clone())?;
+    glob.check_walk(glob.walk_sync_with_file_types())
+}
 
-            // Optimization: Use specialized matching based on pattern characteristics.
-            // Patterns are already sorted with fast-path patterns first (in new_multi),
-            // so .any() will try fast patterns before falling back to regex patterns.
-            let matches = if !self.any_pattern_requires_dir {
-                // Fast path: no patterns require directory matching
-                self.patterns
-                    .iter()
-                    .any(|p| match p.matches_fast(&normalized) {
-                        Some(result) => result,
-                        None => p.matches(&normalized),
-                    })
-            } else {
-                // Standard path: some patterns require directory matching
-                self.patterns.iter().any(|p| {
-                    let path_matches = match p.matches_fast(&normalized) {
-                        Some(result) => result,
-                        None => p.matches(&normalized),
-                    };
-                    if path_matches && p.requires_dir() {
-                        is_dir
-                    } else {
-                        path_matches
-                    }
-                })
-            };
+/// Streaming glob pattern matching.
+/// Streams results back to JavaScript via a callback function.
+/// This reduces peak memory usage for large result sets by not collecting all results before sending.
+///
+/// @param pattern - Glob pattern or array of patterns
+/// @param options - Glob options
+/// @param callback - Function called with each result string
+/// @returns Promise that resolves when all results have been streamed
+#[napi]
+pub fn glob_stream(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+    #[napi(ts_arg_type = "(result: string) => void")] callback: ThreadsafeFunction<String>,
+) -> Result<()> {
+    let opts = options.unwrap_or_default();
 
-            if matches {
-                // Build the result path using optimized helper
-                let result = self.build_result_path(
-                    &normalized,
-                    is_dir,
-                    is_symlink,
-                    &abs_cwd,
-                    &mut result_buffer,
-                );
+    // Validate options using the centralized validation
+    validate_options(&opts)?;
 
-                // Deduplicate results (important for overlapping brace expansions)
-                if seen.insert(result.clone()) {
-                    // When includeChildMatches is false, track (result, normalized) for post-filtering
-                    if !self.include_child_matches {
-                        matched_with_normalized.push((result.clone(), normalized.into_owned()));
-                    }
-                    results.push(result);
-                }
-            }
-        }
+    let patterns = collect_pattern_inputs(pattern);
 
-        // When includeChildMatches is false, post-process to filter out children
-        // This handles cases where filesystem order causes children to be seen before parents
-        if !self.include_child_matches && !matched_with_normalized.is_empty() {
-            // Sort by path depth (number of segments) - shorter paths first
-            matched_with_normalized.sort_by_key(|(_, norm)| norm.matches('/').count());
-
-            // Filter out children using a set of matched parents
-            let mut parents: AHashSet<&str> =
-                AHashSet::with_capacity(matched_with_normalized.len());
-            let mut filtered_results: Vec<String> =
-                Vec::with_capacity(matched_with_normalized.len());
-
-            for (result, normalized) in &matched_with_normalized {
-                // Check if this path is a child of any already-matched parent
-                let is_child = parents.iter().any(|parent| {
-                    let parent_bytes = parent.as_bytes();
-                    let norm_bytes = normalized.as_bytes();
-                    norm_bytes.starts_with(parent_bytes)
-                        && norm_bytes.len() > parent_bytes.len()
-                        && norm_bytes.get(parent_bytes.len()) == Some(&b'/')
-                });
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts)?;
 
-                if !is_child {
-                    parents.insert(normalized.as_str());
-                    filtered_results.push(result.clone());
-                }
-            }
+    // Stream results directly to JavaScript callback
+    // This avoids collecting all results into a Vec, reducing peak memory usage
+    glob.walk_stream(|result| {
+        // Call the JS callback with each result
+        // Use NonBlocking mode to avoid blocking the walking thread
+        callback.call(Ok(result), ThreadsafeFunctionCallMode::NonBlocking);
+    });
 
-            return filtered_results;
-        }
+    glob.check_walk(())
+}
 
-        results
+/// Streaming glob pattern matching that delivers results in batches.
+/// The callback receives up to `batchSize` results per call, so a large result
+/// set crosses into JavaScript once per batch instead of once per path.
+///
+/// The walk runs off the JavaScript thread, so batches arrive while it is still
+/// in progress.
+///
+/// @param pattern - Glob pattern or array of patterns
+/// @param options - Glob options
+/// @param batchSize - Number of results per batch (the last batch may be smaller)
+/// @param callback - Function called with each batch of result strings
+/// @returns The number of batches the callback will receive; some may still be
+///   on their way when it resolves
+#[napi]
+pub async fn glob_stream_batched(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+    batch_size: u32,
+    #[napi(ts_arg_type = "(results: string[]) => void")] callback: ThreadsafeFunction<
+        Vec<String>,
+        ErrorStrategy::Fatal,
+    >,
+) -> Result<u32> {
+    if batch_size == 0 {
+        return Err(Error::from_reason("batchSize must be at least 1"));
     }
 
-    /// Walk the directory tree and return PathData objects.
-    /// This is used when withFileTypes: true is set.
-    pub fn walk_sync_with_file_types(&self) -> Vec<PathData> {
-        // If maxDepth is negative, return empty results
-        if let Some(d) = self.max_depth {
-            if d < 0 {
-                return Vec::new();
-            }
-        }
+    let opts = options.unwrap_or_default();
 
-        // Pre-allocate result vector with estimated capacity
-        let estimated_capacity = self.estimate_result_capacity();
-        let mut results = Vec::with_capacity(estimated_capacity);
-        // Use AHashSet for faster hashing
-        let mut seen: AHashSet<String> = AHashSet::with_capacity(estimated_capacity);
-        let mut ignored_dirs: AHashSet<String> = AHashSet::with_capacity(8);
+    validate_options(&opts)?;
 
-        // When includeChildMatches is false, track (result, normalized) pairs for post-filtering
-        let mut matched_with_normalized: Vec<(PathData, String)> = if self.include_child_matches {
-            Vec::new()
-        } else {
-            Vec::with_capacity(estimated_capacity)
-        };
+    let patterns = collect_pattern_inputs(pattern);
 
-        // Check if any pattern matches the cwd itself ("**" or ".").
-        let include_cwd = self.patterns.iter().any(|p| {
-            let raw = p.raw();
-            raw == "**" || raw == "." || raw == "./**" || {
-                let preprocessed = preprocess_pattern(raw);
-                preprocessed == "**" || preprocessed == "."
-            }
-        });
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts)?;
 
-        // Get the absolute cwd path, canonicalized
-        // Strip Windows extended-length prefix (\\?\) to match glob v13 behavior
-        let abs_cwd = strip_windows_extended_prefix(
-            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
-        );
-
-        // Calculate the walk root based on literal prefixes
-        let (walk_root, prefix_to_strip) = self.calculate_walk_root();
+    let batches = glob.walk_stream_batched(batch_size as usize, |batch| {
+        callback.call(batch, ThreadsafeFunctionCallMode::NonBlocking);
+    });
+    glob.check_walk(batches as u32)
+}
 
-        // Pre-compute the prefix with trailing slash for efficient path concatenation
-        let prefix_with_slash: Option<String> =
-            prefix_to_strip.as_ref().map(|prefix| format!("{prefix}/"));
+/// Streaming glob pattern matching with file type information.
+/// Streams PathData results back to JavaScript via a callback function.
+///
+/// @param pattern - Glob pattern or array of patterns  
+/// @param options - Glob options
+/// @param callback - Function called with each PathData result
+/// @returns Promise that resolves when all results have been streamed
+#[napi]
+pub fn glob_stream_with_file_types(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+    #[napi(
+        ts_arg_type = "(result: { path: string, isDirectory: boolean, isFile: boolean, isSymlink: boolean, linkTarget?: string, isEmptyDir?: boolean }) => void"
+    )]
+    callback: ThreadsafeFunction<PathData>,
+) -> Result<()> {
+    let opts = options.unwrap_or_default();
 
-        // Adjust walk options for prefix-based walking
-        let adjusted_walk_options = if let Some(ref prefix) = prefix_to_strip {
-            let prefix_depth = prefix.split('/').filter(|s| !s.is_empty()).count();
-            if let Some(max_d) = self.walk_options.max_depth {
-                if max_d <= prefix_depth {
-                    self.walk_options.clone().max_depth(Some(0))
-                } else {
-                    self.walk_options
-                        .clone()
-                        .max_depth(Some(max_d - prefix_depth))
-                }
-            } else {
-                self.walk_options.clone()
-            }
-        } else {
-            self.walk_options.clone()
-        };
+    // Validate options using the centralized validation
+    validate_options(&opts)?;
 
-        // Create directory pruning filter using Arc::clone for cheap reference counting
-        let patterns_for_filter = Arc::clone(&self.patterns);
-        let prefix_for_filter = prefix_to_strip.clone();
-        let prefix_slash_for_filter = prefix_with_slash.clone();
+    let patterns = collect_pattern_inputs(pattern);
 
-        let prune_filter = Box::new(move |dir_path: &str| -> bool {
-            // Use Cow to avoid allocation when no prefix is needed
-            let path_from_cwd: Cow<'_, str> = if let Some(ref prefix) = prefix_for_filter {
-                if dir_path.is_empty() {
-                    Cow::Borrowed(prefix.as_str())
-                } else if let Some(ref prefix_slash) = prefix_slash_for_filter {
-                    Cow::Owned(format!("{prefix_slash}{dir_path}"))
-                } else {
-                    Cow::Owned(format!("{prefix}/{dir_path}"))
-                }
-            } else {
-                Cow::Borrowed(dir_path)
-            };
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts)?;
 
-            patterns_for_filter
-                .iter()
-                .any(|p| p.could_match_in_dir(&path_from_cwd))
-        });
+    // Stream results directly to JavaScript callback
+    glob.walk_stream_with_file_types(|result| {
+        callback.call(Ok(result), ThreadsafeFunctionCallMode::NonBlocking);
+    });
 
-        // Create walker
-        let walker = Walker::new(walk_root.clone(), adjusted_walk_options)
-            .with_dir_prune_filter(prune_filter);
+    glob.check_walk(())
+}
 
-        // Check if we have ignore patterns
-        let has_ignore_filter = self.ignore_filter.is_some();
+/// Pull-based iterator over typed glob results, created by `glob_iterate_with_file_types`.
+///
+/// The walk runs on a background thread and pauses whenever the buffer is full,
+/// so results are produced only as fast as they are consumed.
+#[napi]
+pub struct GlobFileTypesIterator {
+    receiver: Arc<Mutex<mpsc::Receiver<PathData>>>,
+    outcome: WalkOutcome,
+}
 
-        for entry in walker.walk() {
-            let path = entry.path();
+#[napi]
+impl GlobFileTypesIterator {
+    /// Wait for the next results and return up to `max` of them (default: all
+    /// that are buffered). An empty array means the walk has finished; it is
+    /// an error instead if the walk stopped at the hard depth limit.
+    #[napi]
+    pub async fn next_batch(&self, max: Option<u32>) -> Result<Vec<PathData>> {
+        let max = max.map_or(usize::MAX, |m| m.max(1) as usize);
+        let mut receiver = self.receiver.lock().await;
+        let mut batch = Vec::new();
+        match receiver.recv().await {
+            Some(first) => batch.push(first),
+            None => return self.outcome.take(),
+        }
+        while batch.len() < max {
+            match receiver.try_recv() {
+                Ok(data) => batch.push(data),
+                Err(_) => break,
+            }
+        }
+        Ok(batch)
+    }
 
-            let rel_path_from_walk_root = match path.strip_prefix(&walk_root) {
-                Ok(p) => p,
-                Err(_) => continue,
-            };
-            let rel_str_from_walk_root = rel_path_from_walk_root.to_string_lossy();
+    /// Stop the walk early. Results already buffered are discarded.
+    ///
+    /// Waits for an in-flight `nextBatch()` to return rather than skipping the
+    /// close, so the walk is always told to stop.
+    #[napi]
+    pub async fn close(&self) {
+        self.receiver.lock().await.close();
+    }
+}
 
-            let is_walk_root_entry = rel_str_from_walk_root.is_empty();
+/// Streaming glob pattern matching with file type information, as a pull-based
+/// iterator with backpressure.
+///
+/// Unlike `glob_stream_with_file_types`, results are not pushed to JavaScript:
+/// the walk buffers at most `buffer_size` results and then waits for the
+/// consumer to call `nextBatch()`.
+///
+/// @param pattern - Glob pattern or array of patterns
+/// @param options - Glob options
+/// @param buffer_size - Results to buffer ahead of the consumer (default 256)
+/// @returns An iterator whose `nextBatch()` resolves with PathData results
+#[napi]
+pub fn glob_iterate_with_file_types(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+    options: Option<GlobOptions>,
+    buffer_size: Option<u32>,
+) -> Result<GlobFileTypesIterator> {
+    let opts = options.unwrap_or_default();
 
-            // Use optimized normalization with Cow
-            let normalized = self.normalize_path(
-                &rel_str_from_walk_root,
-                &prefix_to_strip,
-                is_walk_root_entry,
-            );
+    validate_options(&opts)?;
 
-            // Check if this path is inside an ignored directory
-            if self.is_in_ignored_dir(&normalized, &ignored_dirs) {
-                continue;
-            }
+    let patterns = collect_pattern_inputs(pattern);
 
-            // Check ignore patterns
-            if has_ignore_filter {
-                let rel_path = if prefix_to_strip.is_some() {
-                    PathBuf::from(normalized.as_ref())
-                } else {
-                    rel_path_from_walk_root.to_path_buf()
-                };
-                let abs_path = abs_cwd.join(&rel_path);
-                let ignore_filter = self.ignore_filter.as_ref().unwrap();
+    let glob = Glob::try_new_multi_with_overrides(patterns, opts)?;
+    let capacity = buffer_size.map_or(DEFAULT_ITERATOR_BUFFER, |n| n as usize);
 
-                if ignore_filter.should_ignore(&normalized, &abs_path) {
-                    if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
-                        ignored_dirs.insert(normalized.into_owned());
-                    }
-                    continue;
-                }
+    let (receiver, outcome) = glob.spawn_checked_stream_with_file_types(capacity);
 
-                if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
-                    ignored_dirs.insert(normalized.to_string());
-                }
-            }
+    Ok(GlobFileTypesIterator {
+        receiver: Arc::new(Mutex::new(receiver)),
+        outcome,
+    })
+}
 
-            // Handle root of walk_root
-            if is_walk_root_entry && prefix_to_strip.is_none() {
-                if include_cwd && !self.nodir {
-                    if let Some(ref ignore_filter) = self.ignore_filter {
-                        if ignore_filter.should_ignore(".", &abs_cwd) {
-                            continue;
-                        }
-                    }
+/// Flatten the `pattern` argument into per-pattern inputs.
+///
+/// Accepts a single string, or an array mixing plain strings with
+/// `{ pattern, options }` objects that override options for one pattern.
+fn collect_pattern_inputs(
+    pattern: Either<String, Vec<Either<String, PatternInput>>>,
+) -> Vec<PatternInput> {
+    match pattern {
+        Either::A(s) => vec![s.into()],
+        Either::B(v) => v
+            .into_iter()
+            .map(|p| match p {
+                Either::A(s) => s.into(),
+                Either::B(input) => input,
+            })
+            .collect(),
+    }
+}
 
-                    let result_path = ".".to_string();
-                    if seen.insert(result_path.clone()) {
-                        results.push(PathData {
-                            path: result_path,
-                            is_directory: true,
-                            is_file: false,
-                            is_symlink: entry.is_symlink(),
-                        });
-                    }
-                }
-                continue;
-            }
+/// The `maxPatterns` limit on brace expansion.
+fn pattern_limit(options: &GlobOptions) -> usize {
+    options
+        .max_patterns
+        .map_or(DEFAULT_MAX_PATTERNS, |n| n as usize)
+}
 
-            if normalized.is_empty() {
-                continue;
-            }
+/// Build the ignore filter for `ignore`, seeded with the `defaultIgnore` patterns
+/// and anchored at cwd with `rootRelative`. `None` when nothing is ignored.
+///
+/// Fails if the patterns expand to more than `max_patterns` (what is left of
+/// `maxPatterns` after the patterns being matched).
+fn build_ignore_filter(
+    options: &GlobOptions,
+    separator: Option<char>,
+    max_patterns: usize,
+) -> Result<Option<IgnoreFilter>> {
+    let windows_paths_no_escape = options.effective_windows_paths_no_escape();
+    let mut ignore_patterns: Vec<String> = if options.default_ignore.unwrap_or(false) {
+        DEFAULT_IGNORE_PATTERNS
+            .iter()
+            .map(|p| p.to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    match &options.ignore {
+        Some(Either::A(pattern)) => ignore_patterns.push(pattern.clone()),
+        Some(Either::B(patterns)) => ignore_patterns.extend(patterns.iter().cloned()),
+        None => {}
+    }
+    if options.root_relative.unwrap_or(false) {
+        for pattern in &mut ignore_patterns {
+            *pattern = anchor_to_cwd(pattern, windows_paths_no_escape).to_string();
+        }
+    }
+    if ignore_patterns.is_empty() {
+        return Ok(None);
+    }
+    IgnoreFilter::with_pattern_limit(
+        ignore_patterns,
+        options.noext.unwrap_or(false),
+        windows_paths_no_escape,
+        separator,
+        max_patterns,
+    )
+    .map(Some)
+    .map_err(|pattern| {
+        Error::from_reason(format!(
+            "Too many patterns: expanding ignore pattern '{pattern}' exceeds maxPatterns ({})",
+            pattern_limit(options)
+        ))
+    })
+}
 
-            // If nodir is true, skip directories
-            if self.nodir && entry.is_dir() {
-                continue;
-            }
+/// Trim a pattern the way `.gitignore` lines are trimmed (`trimPatterns`).
+///
+/// Returns `None` for blank patterns and `#` comments. Trailing whitespace is
+/// kept when its first character is escaped with a backslash, unless
+/// backslashes are path separators.
+fn trim_pattern_line(pattern: &str, windows_paths_no_escape: bool) -> Option<&str> {
+    let trimmed = pattern.trim_start();
+    if trimmed.starts_with('#') {
+        return None;
+    }
+
+    let mut end = trimmed.trim_end().len();
+    if !windows_paths_no_escape && end < trimmed.len() {
+        // An odd run of backslashes before the whitespace escapes its first character
+        let backslashes = trimmed[..end]
+            .bytes()
+            .rev()
+            .take_while(|&b| b == b'\\')
+            .count();
+        if backslashes % 2 == 1 {
+            end += trimmed[end..].chars().next().map_or(0, char::len_utf8);
+        }
+    }
 
-            // If dot:false, check if this path contains dotfile segments
-            if !self.dot && !self.path_allowed_by_dot_rules(&normalized) {
-                continue;
-            }
+    let trimmed = &trimmed[..end];
+    (!trimmed.is_empty()).then_some(trimmed)
+}
 
-            // Check if any pattern matches
-            let is_dir = entry.is_dir();
+/// Strip the leading separators of a pattern for `rootRelative`, so `/src/*.ts`
+/// is anchored at cwd (like in `.gitignore`) instead of the filesystem root.
+/// A bare `/` becomes `.`, the cwd itself.
+fn anchor_to_cwd(pattern: &str, windows_paths_no_escape: bool) -> &str {
+    let rest = if windows_paths_no_escape {
+        pattern.trim_start_matches(['/', '\\'])
+    } else {
+        pattern.trim_start_matches('/')
+    };
+    if rest.is_empty() && !pattern.is_empty() {
+        "."
+    } else {
+        rest
+    }
+}
 
-            let matches = if !self.any_pattern_requires_dir {
-                self.patterns
-                    .iter()
-                    .any(|p| match p.matches_fast(&normalized) {
-                        Some(result) => result,
-                        None => p.matches(&normalized),
-                    })
-            } else {
-                self.patterns.iter().any(|p| {
-                    let path_matches = match p.matches_fast(&normalized) {
-                        Some(result) => result,
-                        None => p.matches(&normalized),
-                    };
-                    if path_matches && p.requires_dir() {
-                        is_dir
-                    } else {
-                        path_matches
-                    }
-                })
-            };
+/// Reduce a pattern to its final segment under `**/` for `basenameOnly`, so the
+/// walk reaches every directory and the segment can match any path's basename.
+/// A trailing separator (directory-only match) is kept.
+///
+/// Examples: `src/*.ts` -> `**/*.ts`, `a/b/` -> `**/b/`, `foo.ts` -> `**/foo.ts`
+fn basename_pattern(pattern: &str, windows_paths_no_escape: bool) -> String {
+    let is_sep = |c: char| c == '/' || (windows_paths_no_escape && c == '\\');
+    let trimmed = pattern.trim_end_matches(is_sep);
+    let last = trimmed.rsplit(is_sep).next().unwrap_or(trimmed);
+    let trailing = if trimmed.len() < pattern.len() {
+        "/"
+    } else {
+        ""
+    };
+    format!("**/{last}{trailing}")
+}
 
-            if matches {
-                // For withFileTypes, we return the relative path (no dotRelative/mark modifications)
-                // The JavaScript wrapper handles path formatting via PathScurry
-                // Convert separators for output: use backslashes on Windows without posix
-                let output_path = if self.should_normalize_backslashes() {
-                    normalized.into_owned()
-                } else {
-                    normalized.replace('/', "\\")
-                };
-                if seen.insert(output_path.clone()) {
-                    let path_data = PathData {
-                        path: output_path.clone(),
-                        is_directory: is_dir,
-                        is_file: entry.is_file(),
-                        is_symlink: entry.is_symlink(),
-                    };
+/// Whether an entry satisfies a pattern ending in `/`: a directory, or a symlink
+/// to one. Without `follow` the walker reports a symlink's own type, so its
+/// target is only stat'd here (through `fs`), for symlinks that already matched
+/// such a pattern.
+#[inline]
+fn is_dir_or_dir_link(fs: &dyn FileSystem, is_dir: bool, is_symlink: bool, path: &Path) -> bool {
+    is_dir || (is_symlink && fs.metadata(path).is_ok_and(|meta| meta.is_dir))
+}
 
-                    // When includeChildMatches is false, track for post-filtering
-                    if !self.include_child_matches {
-                        let norm_path = output_path.replace('\\', "/");
-                        matched_with_normalized.push((path_data.clone(), norm_path));
-                    }
+/// Check whether a pattern matches the cwd itself (`**`, `.` or `./**`).
+///
+/// Single-segment wildcards like `*` or `?` never match the cwd: they only match
+/// named entries inside it, so `*` with `mark` must not produce `./`.
+fn pattern_matches_cwd(p: &Pattern) -> bool {
+    let raw = p.raw();
+    // Fast path: check common cases without calling preprocess_pattern
+    let matches = raw == "**" || raw == "." || raw == "./**" || {
+        let preprocessed = preprocess_pattern(raw);
+        preprocessed == "**" || preprocessed == "."
+    };
+    // Emitting the cwd for a wildcard part would turn `*` with `mark` into `./`
+    debug_assert!(
+        !matches || !p.parts().iter().any(|part| part.is_regexp()),
+        "pattern {raw:?} has a wildcard part but was taken to match the cwd"
+    );
+    matches
+}
 
-                    results.push(path_data);
-                }
-            }
-        }
+/// How many levels below cwd a walked path lies: cwd (`.`) is 0, an entry in
+/// cwd is 1, and every further segment adds one, `..` included. Absolute paths
+/// inside cwd are measured from cwd.
+fn walked_path_depth(normalized: &str, abs_cwd: &Path) -> u32 {
+    let path = Path::new(normalized);
+    let relative = path.strip_prefix(abs_cwd).unwrap_or(path);
+    relative
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_) | Component::ParentDir))
+        .count() as u32
+}
 
-        // When includeChildMatches is false, post-process to filter out children
-        if !self.include_child_matches && !matched_with_normalized.is_empty() {
-            // Sort by path depth (number of segments) - shorter paths first
-            matched_with_normalized.sort_by_key(|(_, norm)| norm.matches('/').count());
-
-            // Filter out children using a set of matched parents
-            let mut parents: AHashSet<&str> =
-                AHashSet::with_capacity(matched_with_normalized.len());
-            let mut filtered_results: Vec<PathData> =
-                Vec::with_capacity(matched_with_normalized.len());
-
-            for (path_data, normalized) in &matched_with_normalized {
-                // Check if this path is a child of any already-matched parent
-                let is_child = parents.iter().any(|parent| {
-                    let parent_bytes = parent.as_bytes();
-                    let norm_bytes = normalized.as_bytes();
-                    norm_bytes.starts_with(parent_bytes)
-                        && norm_bytes.len() > parent_bytes.len()
-                        && norm_bytes.get(parent_bytes.len()) == Some(&b'/')
-                });
+/// Result paths already emitted by a walk, stored according to `dedupStrategy`.
+enum SeenPaths {
+    /// Every path in full; exact.
+    Full(AHashSet<String>),
+    /// A 64-bit hash per path. The hasher state is kept so equal paths hash equally.
+    Hashed(AHashSet<u64>, ahash::RandomState),
+    /// Paths grouped by parent directory, keeping only the directories on the
+    /// current depth-first path (`scopedDedup`). A directory's set is dropped once
+    /// a path outside it arrives, so a path is only caught as a duplicate if it
+    /// repeats while its directory is still open.
+    Scoped(Vec<(String, AHashSet<String>)>),
+}
 
-                if !is_child {
-                    parents.insert(normalized.as_str());
-                    filtered_results.push(path_data.clone());
-                }
+impl SeenPaths {
+    fn with_capacity(strategy: DedupStrategy, capacity: usize) -> Self {
+        match strategy {
+            DedupStrategy::FullString => Self::Full(AHashSet::with_capacity(capacity)),
+            DedupStrategy::Hash64 => {
+                Self::Hashed(AHashSet::with_capacity(capacity), ahash::RandomState::new())
             }
-
-            return filtered_results;
         }
-
-        results
     }
 
-    /// Format a path according to options (posix, etc.)
+    /// Record a path, returning `true` if it was not seen before.
     ///
-    /// When posix: true on Windows, absolute paths are converted to UNC form
-    /// (e.g., `C:\foo\bar` → `//?/C:/foo/bar`) to match glob's behavior.
-    fn format_path(&self, path: &std::path::Path) -> String {
-        let path_str = path.to_string_lossy().to_string();
-        if self.posix_explicit_true {
-            // On Windows with posix: true, convert absolute paths to UNC form
-            #[cfg(target_os = "windows")]
-            {
-                let bytes = path_str.as_bytes();
-                if bytes.len() >= 2
-                    && bytes[0].is_ascii_alphabetic()
-                    && (bytes[1] == b':' || bytes[1] == b'\\' || bytes[1] == b'/')
-                {
-                    // Convert to UNC form: //?/C:/...
-                    let drive = bytes[0] as char;
-                    let rest = path_str[2..].replace('\\', "/");
-                    return format!("//?/{drive}:{rest}");
+    /// With `Hashed`, a hash collision makes a new path look seen and drops it.
+    #[inline]
+    fn insert(&mut self, path: &str) -> bool {
+        match self {
+            Self::Full(set) => set.insert(path.to_string()),
+            Self::Hashed(set, state) => set.insert(state.hash_one(path)),
+            Self::Scoped(scopes) => {
+                let trimmed = path.trim_end_matches(['/', '\\']);
+                let parent = trimmed.rfind(['/', '\\']).map_or("", |i| &trimmed[..i]);
+
+                // Close directories the walk has left
+                while let Some((dir, _)) = scopes.last() {
+                    if dir.is_empty() || is_same_or_descendant(parent, dir) {
+                        break;
+                    }
+                    scopes.pop();
+                }
+                match scopes.last_mut() {
+                    Some((dir, set)) if dir == parent => set.insert(path.to_string()),
+                    _ => {
+                        let mut set = AHashSet::new();
+                        set.insert(path.to_string());
+                        scopes.push((parent.to_string(), set));
+                        true
+                    }
                 }
             }
-            // Standard POSIX conversion: backslashes to forward slashes
-            path_str.replace('\\', "/")
-        } else {
-            path_str
         }
     }
+}
 
-    /// Ensure a path ends with a trailing slash
-    fn ensure_trailing_slash(&self, path: &str) -> String {
-        if path.ends_with('/') || path.ends_with('\\') {
-            path.to_string()
-        } else if self.should_normalize_backslashes() {
-            format!("{path}/")
-        } else {
-            // On Windows without posix option, use the native separator
-            format!("{path}\\")
-        }
+/// Whether `path` is `dir` or lies below it (either separator).
+#[inline]
+fn is_same_or_descendant(path: &str, dir: &str) -> bool {
+    path.strip_prefix(dir)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '\\']))
+}
+
+/// Create a walker over `root` that prunes with `prune_filter`, if there is one.
+fn new_walker(root: PathBuf, options: WalkOptions, prune_filter: Option<DirPruneFilter>) -> Walker {
+    let walker = Walker::new(root, options);
+    match prune_filter {
+        Some(filter) => walker.with_dir_prune_filter(filter),
+        None => walker,
     }
+}
 
-    /// Check if a path is allowed by dot filtering rules.
-    /// Returns true if:
-    /// - dot: true (always allow)
-    /// - The path has no dotfile segments
-    /// - Any pattern explicitly allows the dotfile segments in this path
-    fn path_allowed_by_dot_rules(&self, path: &str) -> bool {
-        // Check if path contains any dotfile segments
-        let has_dotfile = path
-            .split('/')
-            .any(|segment| segment.starts_with('.') && segment != "." && segment != "..");
+/// Append a `/` to a walk prefix unless it already ends with one.
+///
+/// Absolute roots such as `/` or the UNC `//server/share/` already carry their
+/// trailing separator; adding another would produce `//file` or `//server/share//file`.
+#[inline]
+fn prefix_with_separator(prefix: &str) -> String {
+    if prefix.ends_with('/') {
+        prefix.to_string()
+    } else {
+        format!("{prefix}/")
+    }
+}
 
-        if !has_dotfile {
-            return true;
+/// The directories walks start from, one per pattern: cwd, or the pattern's
+/// absolute root, joined with its literal prefix. Walks report these when they
+/// can't be read (see `Glob::check_walk`).
+fn walk_roots(cwd: &Path, patterns: &[Pattern]) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for pattern in patterns {
+        let base = if pattern.is_absolute() {
+            PathBuf::from(pattern.root())
+        } else {
+            cwd.to_path_buf()
+        };
+        let root = match pattern.literal_prefix() {
+            Some(prefix) => base.join(prefix),
+            None => base,
+        };
+        if !roots.contains(&root) {
+            roots.push(root);
         }
-
-        // Check if any pattern explicitly allows the dotfiles in this path
-        self.patterns.iter().any(|p| p.allows_dotfile(path))
     }
+    roots
+}
 
-    /// Estimate the capacity for the result vector based on pattern characteristics.
-    ///
-    /// This helps reduce reallocations during result collection. The estimate is
-    /// based on pattern depth and whether the pattern is recursive:
-    /// - Simple root patterns (*.txt): ~16 results expected
-    /// - One-level patterns (src/*.js): ~64 results expected  
-    /// - Recursive patterns (**/*.js): ~256 results expected
-    fn estimate_result_capacity(&self) -> usize {
-        // Find the maximum depth across all patterns
-        let max_pattern_depth = self.patterns.iter().filter_map(|p| p.max_depth()).max();
+/// The canonical cwd that `restrictToCwd` confines results to, or an error naming
+/// the first pattern that reaches outside it.
+fn sandbox_root_for(
+    cwd: &Path,
+    patterns: &[Pattern],
+    file_system: &dyn FileSystem,
+) -> Result<PathBuf> {
+    let root =
+        strip_windows_extended_prefix(file_system.canonicalize(cwd).unwrap_or(cwd.to_path_buf()));
+    let root_str = normalize_separator(&root.to_string_lossy());
+    if let Some(p) = patterns.iter().find(|p| pattern_escapes_root(p, &root_str)) {
+        return Err(Error::from_reason(format!(
+            "Pattern '{}' reaches outside cwd, which restrictToCwd does not allow",
+            p.raw()
+        )));
+    }
+    Ok(root)
+}
 
-        match max_pattern_depth {
-            Some(0) => 16,  // Root-level patterns: few files expected
-            Some(1) => 64,  // One directory level: moderate number
-            Some(2) => 128, // Two levels deep
-            Some(_) => 256, // Deeper patterns
-            None => 256,    // Recursive patterns (**): could be many files
+/// Check whether a pattern could reach outside `root`, a `/`-separated absolute path.
+///
+/// The walk starts at the pattern's literal prefix, so for absolute patterns that
+/// prefix must lie inside `root`. After that, no `..` may climb above `root`;
+/// wildcard segments count as one level and `**` as none, the worst case for `..`.
+fn pattern_escapes_root(pattern: &Pattern, root: &str) -> bool {
+    let root_segments: Vec<&str> = root.split('/').filter(|s| !s.is_empty()).collect();
+    // Segments reached so far; `None` stands for a wildcard segment
+    let mut reached: Vec<Option<&str>> = if pattern.is_absolute() {
+        Vec::new()
+    } else {
+        root_segments.iter().map(|s| Some(*s)).collect()
+    };
+    let mut in_literal_prefix = pattern.is_absolute();
+    let inside = |reached: &[Option<&str>]| {
+        reached.len() >= root_segments.len()
+            && reached
+                .iter()
+                .zip(&root_segments)
+                .all(|(r, s)| *r == Some(*s))
+    };
+
+    for part in pattern.parts() {
+        match part {
+            // Absolute roots such as `/`, `C:/` and `//server/share/`
+            PatternPart::Literal(s)
+                if in_literal_prefix && reached.is_empty() && s.ends_with('/') =>
+            {
+                reached.extend(s.split('/').filter(|s| !s.is_empty()).map(Some));
+                continue;
+            }
+            PatternPart::Literal(s) if s == "." || s.is_empty() => continue,
+            PatternPart::Literal(s) if s == ".." => {
+                reached.pop();
+            }
+            PatternPart::Literal(s) => reached.push(Some(s.as_str())),
+            PatternPart::Magic(..) => {
+                in_literal_prefix = false;
+                reached.push(None);
+            }
+            PatternPart::Globstar => in_literal_prefix = false,
+        }
+        // The literal prefix of an absolute pattern may pass through ancestors of root
+        if !in_literal_prefix && !inside(&reached) {
+            return true;
         }
     }
+    !inside(&reached)
+}
 
-    /// Estimate string buffer capacity based on pattern characteristics.
-    /// Used to pre-allocate string buffers for path construction.
-    #[inline]
-    fn estimate_path_buffer_capacity(&self) -> usize {
-        // Average path length: ~40-60 characters for typical project structures
-        // Add extra for absolute paths and prefix
-        if self.absolute {
-            128 // Absolute paths can be longer
-        } else if self.dot_relative {
-            64 // Relative with ./ prefix
-        } else {
-            48 // Simple relative paths
+/// Drop matches that are descendants of another match (includeChildMatches: false).
+///
+/// Takes `(result, normalized)` pairs, where `normalized` is the `/`-separated path
+/// relative to cwd. Entries are sorted by depth first because filesystem order may
+/// yield children before their parents. A path is a child only when the byte after
+/// the parent prefix is `/`, so `ab` is never treated as a child of `a`.
+fn filter_child_matches<T: Clone>(mut matched: Vec<(T, String)>) -> Vec<T> {
+    // Sort by path depth (number of segments) - shorter paths first
+    matched.sort_by_key(|(_, norm)| norm.matches('/').count());
+
+    // Filter out children using a set of matched parents
+    let mut parents: AHashSet<&str> = AHashSet::with_capacity(matched.len());
+    let mut filtered_results: Vec<T> = Vec::with_capacity(matched.len());
+
+    for (result, normalized) in &matched {
+        // Check if this path is a child of any already-matched parent
+        let is_child = parents.iter().any(|parent| {
+            let parent_bytes = parent.as_bytes();
+            let norm_bytes = normalized.as_bytes();
+            norm_bytes.starts_with(parent_bytes)
+                && norm_bytes.len() > parent_bytes.len()
+                && norm_bytes.get(parent_bytes.len()) == Some(&b'/')
+        });
+
+        if !is_child {
+            parents.insert(normalized.as_str());
+            filtered_results.push(result.clone());
         }
     }
 
-    /// Format a path into the provided buffer, returning a reference to the result.
-    /// This avoids allocations by reusing the buffer across iterations.
-    ///
-    /// When posix: true on Windows, absolute paths are converted to UNC form
-    /// (e.g., `C:\foo\bar` → `//?/C:/foo/bar`) to match glob's behavior.
-    #[inline]
-    fn format_path_into_buffer<'a>(&self, path: &Path, buffer: &'a mut String) -> &'a str {
-        buffer.clear();
-        let path_str = path.to_string_lossy();
+    filtered_results
+}
 
-        if self.posix_explicit_true {
-            // On Windows with posix: true, convert absolute paths to UNC form
-            // e.g., C:\foo\bar → //?/C:/foo/bar
-            #[cfg(target_os = "windows")]
-            {
-                // Check if this is a Windows absolute path (starts with drive letter)
-                let bytes = path_str.as_bytes();
-                if bytes.len() >= 2
-                    && bytes[0].is_ascii_alphabetic()
-                    && (bytes[1] == b':' || bytes[1] == b'\\' || bytes[1] == b'/')
-                {
-                    // Convert to UNC form: //?/C:/...
-                    buffer.push_str("//?/");
-                    buffer.push(bytes[0] as char);
-                    buffer.push(':');
-                    // Skip the drive letter and colon, convert rest with forward slashes
-                    for c in path_str[2..].chars() {
-                        buffer.push(if c == '\\' { '/' } else { c });
-                    }
-                    return buffer.as_str();
-                }
-            }
+impl Glob {
+    /// Create a new Glob from a single pattern string
+    pub fn new(pattern_str: String, options: GlobOptions) -> Self {
+        Self::new_multi(vec![pattern_str], options)
+    }
 
-            // Standard POSIX conversion: backslashes to forward slashes
-            for c in path_str.chars() {
-                buffer.push(if c == '\\' { '/' } else { c });
-            }
-        } else {
-            buffer.push_str(&path_str);
-        }
-        buffer.as_str()
+    /// Create a new Glob from multiple pattern strings
+    pub fn new_multi(pattern_strs: Vec<String>, options: GlobOptions) -> Self {
+        let inputs = pattern_strs.into_iter().map(PatternInput::from).collect();
+        Self::new_multi_with_overrides(inputs, options)
     }
 
-    /// Build a normalized path from walk entry, minimizing allocations.
-    /// Returns Cow::Borrowed when no transformation is needed, Cow::Owned otherwise.
+    /// Create a new Glob from multiple patterns, each with optional per-pattern
+    /// `nocase`, `dot` and `noext` overrides merged over the global options.
     ///
-    /// IMPORTANT: This function ALWAYS normalizes to forward slashes because it's used
-    /// for internal pattern matching. Pattern matching (`matches()`, `could_match_in_dir()`)
-    /// always expects forward slashes regardless of platform.
+    /// Brace expansion is not capped here; use `try_new_multi_with_overrides` to
+    /// enforce `maxPatterns`.
+    pub fn new_multi_with_overrides(inputs: Vec<PatternInput>, options: GlobOptions) -> Self {
+        Self::build(inputs, options, usize::MAX, None)
+            .expect("pattern count cannot exceed usize::MAX")
+    }
+
+    /// Like `new_multi_with_overrides`, but fails with a descriptive error when
+    /// brace expansion would produce more than `maxPatterns` patterns in total.
+    pub fn try_new_multi_with_overrides(
+        inputs: Vec<PatternInput>,
+        options: GlobOptions,
+    ) -> Result<Self> {
+        let max_patterns = pattern_limit(&options);
+        Self::build(inputs, options, max_patterns, None)
+    }
+
+    /// Like `try_new_multi_with_overrides`, but for keys whose segments are
+    /// separated by `separator` instead of `/` (`matchesPath` with `separator`).
+    /// `\` and `/` are then ordinary characters in candidates.
+    pub fn try_new_with_separator(
+        inputs: Vec<PatternInput>,
+        options: GlobOptions,
+        separator: char,
+    ) -> Result<Self> {
+        let max_patterns = pattern_limit(&options);
+        Self::build(inputs, options, max_patterns, Some(separator))
+    }
+
+    /// Walk `file_system` instead of the real filesystem (see [`crate::fs`]).
     ///
-    /// Output formatting (backslashes on Windows without posix) is handled separately
-    /// in `build_result_path`.
-    #[inline]
-    fn normalize_path<'a>(
-        &self,
-        rel_str_from_walk_root: &'a str,
-        prefix_to_strip: &Option<String>,
-        is_walk_root: bool,
-    ) -> Cow<'a, str> {
-        let has_backslash = rel_str_from_walk_root.contains('\\');
-
-        // Fast path: no prefix and no backslashes to convert
-        if prefix_to_strip.is_none() && !has_backslash {
-            return Cow::Borrowed(rel_str_from_walk_root);
+    /// Matching, ignores and result formatting work as usual, and the walk
+    /// shortcuts that stat or list paths directly are skipped. Everything else
+    /// that looks past a directory listing asks `file_system` too: links and
+    /// devices for `restrictToCwd`, `canonicalOnly`, `oneFileSystem`,
+    /// `realpath` and `readLinkTargets`, empty directories for
+    /// `detectEmptyDirs`, hidden attributes for `hidden`, the spelling of a
+    /// `nocase` prefix, unreadable walk roots, and file sizes for aggregates.
+    pub fn with_file_system(mut self, file_system: Arc<dyn FileSystem>) -> Self {
+        // restrictToCwd confines the walk to cwd as the new filesystem resolves it
+        if self.sandbox_root.is_some() {
+            let root = file_system
+                .canonicalize(&self.cwd)
+                .map_or_else(|_| self.cwd.clone(), strip_windows_extended_prefix);
+            self.walk_options = self.walk_options.clone().confine_to(Some(root.clone()));
+            self.sandbox_root = Some(root);
         }
+        self.walk_options = self.walk_options.clone().file_system(Some(file_system));
+        self
+    }
+
+    /// The filesystem the walk reads: the one given to `with_file_system`, or
+    /// the real one.
+    fn file_system(&self) -> &dyn FileSystem {
+        self.walk_options
+            .file_system
+            .as_deref()
+            .unwrap_or(&StdFileSystem)
+    }
+
+    fn build(
+        inputs: Vec<PatternInput>,
+        options: GlobOptions,
+        max_patterns: usize,
+        separator: Option<char>,
+    ) -> Result<Self> {
+        let pattern_strs: Vec<String> = inputs.iter().map(|p| p.pattern.clone()).collect();
+        let cwd = options
+            .cwd
+            .clone()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
 
-        // Helper to convert backslashes to forward slashes
-        let convert_to_forward = |path: &str| -> String {
-            if path.contains('\\') {
-                path.replace('\\', "/")
+        let absolute = options.absolute.unwrap_or(false);
+        let posix_explicit_true = options.posix == Some(true);
+        let posix_explicit_false = options.posix == Some(false);
+        let nobrace = options.nobrace.unwrap_or(false);
+        let noext = options.noext.unwrap_or(false);
+        let no_negation = options.no_negation.unwrap_or(false);
+        let dot = options.dot.unwrap_or(false);
+        let follow = options.follow.unwrap_or(false);
+        let windows_paths_no_escape = options.effective_windows_paths_no_escape();
+        let max_depth = options.max_depth;
+        let nodir = options.nodir.unwrap_or(false);
+        let dot_relative = options.dot_relative.unwrap_or(false);
+        let mark = options.mark.unwrap_or(false);
+        let match_base = options.match_base.unwrap_or(false);
+        let basename_only = options.basename_only.unwrap_or(false);
+        let noglobstar = options.noglobstar.unwrap_or(false);
+        let nocase = options.effective_nocase();
+        let platform = options.effective_platform();
+        let include_child_matches = options.effective_include_child_matches();
+        let root_relative = options.root_relative.unwrap_or(false);
+        let trim_patterns = options.trim_patterns.unwrap_or(false);
+        let sequential = options.sequential.unwrap_or(false);
+
+        // Create pattern options
+        let pattern_opts = PatternOptions {
+            noext,
+            no_negation,
+            windows_paths_no_escape,
+            platform: Some(platform.clone()),
+            nocase,
+            nobrace,
+            separator,
+        };
+
+        // Process all input patterns and expand braces for each
+        // Use AHashSet to track already-seen pattern strings for deduplication (faster hashing).
+        // Overrides are part of the key so `*.TXT` and `{ pattern: '*.TXT', nocase }` both survive.
+        // The map records where each pattern was stored so its origins can be extended.
+        let mut seen_patterns: AHashMap<(String, Option<PatternOverrides>), usize> =
+            AHashMap::new();
+        let mut patterns: Vec<Pattern> = Vec::new();
+        let mut origins: Vec<Vec<usize>> = Vec::new();
+        // Patterns produced so far, counted before deduplication (for maxPatterns)
+        let mut expanded_total = 0usize;
+        // Ordered include/exclude rules for `sequential`
+        let mut sequential_rules: Vec<(Pattern, bool)> = Vec::new();
+
+        for (input_index, input) in inputs.iter().enumerate() {
+            let pattern_str = if trim_patterns {
+                match trim_pattern_line(&input.pattern, windows_paths_no_escape) {
+                    Some(trimmed) => trimmed,
+                    None => continue,
+                }
             } else {
-                path.to_string()
+                &input.pattern
+            };
+            // In sequential mode a leading `!` turns the pattern into an exclusion,
+            // unless it opens a `!(...)` extglob (as in minimatch, noext makes it
+            // an exclusion again)
+            let pattern_noext = input
+                .options
+                .as_ref()
+                .and_then(|o| o.noext)
+                .unwrap_or(noext);
+            let (pattern_str, exclude) = match pattern_str.strip_prefix('!') {
+                Some(rest) if sequential && (pattern_noext || !rest.starts_with('(')) => {
+                    (rest, true)
+                }
+                _ => (pattern_str, false),
+            };
+            let pattern_str = if root_relative {
+                anchor_to_cwd(pattern_str, windows_paths_no_escape)
+            } else {
+                pattern_str
+            };
+            // Skip empty patterns - they match nothing (like glob v13)
+            if pattern_str.is_empty() {
+                continue;
             }
-        };
 
-        // Need to construct the path with forward slashes
-        match prefix_to_strip {
-            Some(prefix) => {
-                let prefix_converted = convert_to_forward(prefix);
-                if is_walk_root {
-                    Cow::Owned(prefix_converted)
+            // Merge per-pattern overrides over the global pattern options
+            let overrides = input.options.as_ref();
+            let pattern_opts = match overrides {
+                Some(o) => Cow::Owned(PatternOptions {
+                    noext: o.noext.unwrap_or(noext),
+                    nocase: o.nocase.unwrap_or(nocase),
+                    ..pattern_opts.clone()
+                }),
+                None => Cow::Borrowed(&pattern_opts),
+            };
+            let pattern_dot = overrides.and_then(|o| o.dot);
+
+            // Check if the ORIGINAL pattern has path separators BEFORE brace expansion
+            // This is important because matchBase should only apply if the entire original
+            // pattern has no separators. If {a,b/c} is used, neither a nor b/c gets matchBase.
+            // A `/` stripped by rootRelative still counts: anchored patterns don't float.
+            let has_separator = |p: &str| match separator {
+                Some(sep) => p.contains(sep),
+                None => p.contains('/') || p.contains('\\'),
+            };
+            let original_has_slash = has_separator(&input.pattern);
+
+            // Rewrite a pattern for the basename-matching options:
+            // - basenameOnly keeps only its last segment
+            // - matchBase prefixes `**/` when neither the ORIGINAL pattern (before
+            //   brace expansion) nor the expanded one has a path separator
+            let apply_basename_matching = |pattern: &str| -> String {
+                if basename_only {
+                    basename_pattern(pattern, windows_paths_no_escape)
+                } else if match_base && !original_has_slash && !has_separator(pattern) {
+                    format!("**{}{pattern}", separator.unwrap_or('/'))
                 } else {
-                    let rel_converted = convert_to_forward(rel_str_from_walk_root);
-                    Cow::Owned(format!("{prefix_converted}/{rel_converted}"))
+                    pattern.to_string()
+                }
+            };
+
+            // Deduplicate, then compile (through the pattern cache) and record it.
+            // Sequential exclusions only become rules; they never drive the walk.
+            let mut add_pattern = |transformed: String| {
+                let compile = || {
+                    let mut pattern = get_or_compile_pattern(&transformed, &pattern_opts);
+                    pattern.set_dot(pattern_dot);
+                    pattern.set_basename_only(basename_only);
+                    pattern
+                };
+                if exclude {
+                    sequential_rules.push((compile(), true));
+                    return;
+                }
+                let index = match seen_patterns.entry((transformed.clone(), overrides.cloned())) {
+                    Entry::Occupied(slot) => {
+                        let sources = &mut origins[*slot.get()];
+                        if sources.last() != Some(&input_index) {
+                            sources.push(input_index);
+                        }
+                        *slot.get()
+                    }
+                    Entry::Vacant(slot) => {
+                        slot.insert(patterns.len());
+                        patterns.push(compile());
+                        origins.push(vec![input_index]);
+                        patterns.len() - 1
+                    }
+                };
+                if sequential {
+                    sequential_rules.push((patterns[index].clone(), false));
+                }
+            };
+
+            let too_many = || {
+                Error::from_reason(format!(
+                    "Too many patterns: expanding '{pattern_str}' exceeds maxPatterns ({max_patterns})"
+                ))
+            };
+
+            // Expand braces unless nobrace is set
+            if nobrace {
+                if expanded_total == max_patterns {
+                    return Err(too_many());
+                }
+                expanded_total += 1;
+                add_pattern(apply_basename_matching(pattern_str));
+            } else {
+                let expanded = expand_braces_limited(pattern_str, max_patterns - expanded_total)
+                    .ok_or_else(too_many)?;
+                expanded_total += expanded.len().max(1);
+                if expanded.is_empty() {
+                    add_pattern(apply_basename_matching(pattern_str));
+                } else {
+                    for p in expanded {
+                        add_pattern(apply_basename_matching(&p));
+                    }
                 }
-            }
-            None => {
-                // Just convert backslashes to forward slashes
-                Cow::Owned(convert_to_forward(rel_str_from_walk_root))
             }
         }
-    }
 
-    /// Build a normalized path using a reusable buffer to minimize allocations.
-    /// This is the optimized hot path for scoped patterns where prefix concatenation
-    /// is needed for every file.
-    ///
-    /// # Arguments
-    /// * `rel_str_from_walk_root` - The path relative to the walk root
-    /// * `prefix_to_strip` - The original prefix (without trailing slash)
-    /// * `prefix_with_slash` - Pre-computed "prefix/" or "prefix\\" for fast concatenation
-    /// * `is_walk_root` - True if this is the walk root entry itself
-    /// * `use_forward_slashes` - Whether to use forward slashes (true) or backslashes (false)
-    /// * `buffer` - Reusable string buffer
-    #[inline]
-    fn normalize_path_buffered<'a>(
-        rel_str_from_walk_root: &str,
-        prefix_to_strip: &Option<String>,
-        prefix_with_slash: &Option<String>,
-        is_walk_root: bool,
-        use_forward_slashes: bool,
-        buffer: &'a mut String,
-    ) -> &'a str {
-        let has_backslash = rel_str_from_walk_root.contains('\\');
-        let has_forward_slash = rel_str_from_walk_root.contains('/');
-        let needs_to_forward = use_forward_slashes && has_backslash;
-        let needs_to_backslash = !use_forward_slashes && has_forward_slash;
-        let needs_conversion = needs_to_forward || needs_to_backslash;
+        // Reject patterns the regex engine could not compile rather than silently
+        // matching nothing
+        if let Some((raw, err)) = patterns
+            .iter()
+            .chain(sequential_rules.iter().map(|(p, _)| p))
+            .find_map(|p| p.compile_error().map(|e| (p.raw(), e)))
+        {
+            return Err(Error::from_reason(format!(
+                "Pattern '{raw}' is too complex to compile: {err}"
+            )));
+        }
 
-        // Helper closure to push a character with conversion
-        let convert_char = |c: char| -> char {
-            if needs_to_forward && c == '\\' {
-                '/'
-            } else if needs_to_backslash && c == '/' {
-                '\\'
-            } else {
-                c
+        // Per-pattern dot overrides: the glob-wide dot gate must let through anything
+        // some pattern accepts, so every pattern then enforces its own effective setting.
+        let dot = if patterns.iter().any(|p| p.dot().is_some()) {
+            for p in patterns.iter_mut() {
+                p.set_dot(Some(p.dot().unwrap_or(dot)));
             }
+            patterns.iter().any(|p| p.dot() == Some(true))
+        } else {
+            dot
         };
 
-        // Fast path: no prefix and no conversion needed
-        if prefix_to_strip.is_none() {
-            buffer.clear();
-            if needs_conversion {
-                for c in rel_str_from_walk_root.chars() {
-                    buffer.push(convert_char(c));
-                }
-            } else {
-                buffer.push_str(rel_str_from_walk_root);
+        // disableOptimizations: every path goes through the regex
+        let disable_optimizations = options.disable_optimizations.unwrap_or(false);
+        if disable_optimizations {
+            for p in patterns.iter_mut() {
+                p.disable_fast_path();
             }
-            return buffer.as_str();
         }
 
-        // Clear and reuse buffer
-        buffer.clear();
-
-        let prefix = prefix_to_strip.as_ref().unwrap();
+        // A per-pattern nocase override disables case-sensitive prefix shortcuts too
+        let nocase = nocase || patterns.iter().any(|p| p.nocase());
 
-        if is_walk_root {
-            // Convert prefix if needed
-            if needs_conversion {
-                for c in prefix.chars() {
-                    buffer.push(convert_char(c));
+        // Optimization: Sort patterns so fast-path patterns come first.
+        // This allows early exit when using .any() since fast patterns are checked first.
+        // Patterns with fast-path matching are much quicker to evaluate.
+        // Origins are sorted along with their patterns. preservePatternOrder skips this.
+        let mut sorted: Vec<(Pattern, Vec<usize>)> = patterns.into_iter().zip(origins).collect();
+        if !options.preserve_pattern_order.unwrap_or(false) {
+            sorted.sort_by(|(a, _), (b, _)| {
+                // Fast-path patterns should come first
+                let a_fast = a.fast_path().is_fast();
+                let b_fast = b.fast_path().is_fast();
+                match (a_fast, b_fast) {
+                    (true, false) => std::cmp::Ordering::Less,
+                    (false, true) => std::cmp::Ordering::Greater,
+                    _ => std::cmp::Ordering::Equal,
                 }
-            } else {
-                buffer.push_str(prefix);
-            }
+            });
+        }
+        let (patterns, pattern_origins): (Vec<Pattern>, Vec<Vec<usize>>) =
+            sorted.into_iter().unzip();
+
+        // Create ignore filter if ignore patterns provided, seeded with the defaults,
+        // unless a precompiled one was passed in
+        let ignore_filter = match options.ignore_set {
+            Some(ref set) => Some(set.filter.clone()).filter(|filter| !filter.is_empty()),
+            None => build_ignore_filter(&options, separator, max_patterns - expanded_total)?
+                .map(Arc::new),
+        };
+
+        // Create walk options
+        // Note: We always walk with dot=true in the walker, and handle dot filtering
+        // at the pattern matching level. This allows patterns with explicit dots
+        // (like ".hidden" or "**/.config") to match even when dot:false.
+
+        // Calculate effective max depth from patterns for optimization.
+        // If all patterns have a bounded depth (no **), we can limit the walker
+        // to avoid traversing deeper than necessary.
+        // Use the maximum depth required by any pattern.
+        let pattern_max_depth = if disable_optimizations {
+            None
         } else {
-            // Use pre-computed prefix with slash for efficiency
-            if let Some(ref prefix_slash) = prefix_with_slash {
-                if needs_conversion {
-                    for c in prefix_slash.chars() {
-                        buffer.push(convert_char(c));
-                    }
-                } else {
-                    buffer.push_str(prefix_slash);
-                }
-            } else {
-                if needs_conversion {
-                    for c in prefix.chars() {
-                        buffer.push(convert_char(c));
-                    }
-                } else {
-                    buffer.push_str(prefix);
+            let mut max_depth: Option<usize> = Some(0);
+            for p in &patterns {
+                match (max_depth, p.max_depth()) {
+                    (None, _) => break, // Already unlimited
+                    (_, None) => {
+                        max_depth = None;
+                        break;
+                    } // This pattern is unlimited
+                    (Some(a), Some(b)) => max_depth = Some(a.max(b)), // Take max of bounded depths
                 }
-                buffer.push(if use_forward_slashes { '/' } else { '\\' });
             }
+            max_depth
+        };
 
-            if needs_conversion {
-                for c in rel_str_from_walk_root.chars() {
-                    buffer.push(convert_char(c));
-                }
-            } else {
-                buffer.push_str(rel_str_from_walk_root);
-            }
-        }
-        buffer.as_str()
-    }
+        // Combine user-provided max_depth with pattern-derived depth.
+        // User max_depth takes precedence (it's an explicit limit), but if user
+        // didn't specify one, use pattern-derived depth for optimization.
+        // Convert max_depth: negative values and Some(-1) will be handled in walk_sync
+        let walker_max_depth = match (max_depth, pattern_max_depth) {
+            (Some(d), _) if d < 0 => Some(0), // Negative values: will return empty in walk_sync
+            (Some(d), _) => Some(d as usize), // User-provided depth takes precedence
+            (None, Some(d)) => Some(d + 1),   // Pattern depth + 1 (for root directory)
+            (None, None) => None,             // Unlimited (has **)
+        };
 
-    /// Build the final result path from the normalized path.
-    /// Uses the provided buffer to minimize allocations.
-    ///
-    /// The `normalized` path always uses forward slashes (for internal pattern matching).
-    /// This function converts to backslashes for output on Windows when `posix: false`.
-    #[inline]
-    fn build_result_path(
-        &self,
-        normalized: &str,
-        is_dir: bool,
-        is_symlink: bool,
-        abs_cwd: &Path,
-        result_buffer: &mut String,
-    ) -> String {
-        // When mark:true, add trailing slash to directories but NOT to symlinks
-        let should_mark_as_dir = is_dir && !is_symlink && self.mark;
-        let use_forward = self.should_normalize_backslashes();
-        let sep = if use_forward { '/' } else { '\\' };
-        let dot_prefix = if use_forward { "./" } else { ".\\" };
+        // Optimization: Only enable accurate symlink detection when needed.
+        // The `mark` option requires knowing whether an entry is a symlink to avoid
+        // adding a trailing slash. When following symlinks, walkdir reports the TARGET
+        // type, so we need an extra syscall to detect the symlink. Skip this overhead
+        // when not needed.
+        // Reading link targets has the same need: it only runs for entries seen as symlinks.
+        let read_link_targets = options.read_link_targets.unwrap_or(false);
+        let need_accurate_symlink_detection = (mark || read_link_targets) && follow;
 
-        if self.absolute {
-            // Build absolute path
-            result_buffer.clear();
-            let abs_path = abs_cwd.join(normalized);
-            let formatted = self.format_path_into_buffer(&abs_path, result_buffer);
+        let parallel = options.parallel.unwrap_or(false);
+        let cache = options.cache.unwrap_or(false);
+        let use_native_io = options.use_native_io.unwrap_or(false);
+        let use_gcd = options.use_gcd.unwrap_or(false);
+        let preserve_backslashes =
+            options.preserve_backslashes.unwrap_or(false) && !cfg!(target_os = "windows");
 
-            if should_mark_as_dir && !formatted.ends_with('/') && !formatted.ends_with('\\') {
-                let mut result = formatted.to_string();
-                result.push(sep);
-                result
-            } else {
-                formatted.to_string()
-            }
+        let sandbox_root = if options.restrict_to_cwd.unwrap_or(false) {
+            Some(sandbox_root_for(&cwd, &patterns, &StdFileSystem)?)
         } else {
-            // Build relative path
-            // First, convert separators if needed (normalized always uses forward slashes)
-            let output_normalized = if use_forward {
-                normalized.to_string()
-            } else {
-                normalized.replace('/', "\\")
-            };
+            None
+        };
 
-            let base = if self.dot_relative
-                && !output_normalized.starts_with("../")
-                && !output_normalized.starts_with("..\\")
-            {
-                result_buffer.clear();
-                result_buffer.push_str(dot_prefix);
-                result_buffer.push_str(&output_normalized);
-                result_buffer.clone()
-            } else {
-                output_normalized
-            };
+        let walk_options = WalkOptions::new()
+            .follow_symlinks(follow)
+            .max_depth(walker_max_depth)
+            .dot(true)
+            .need_accurate_symlink_detection(need_accurate_symlink_detection)
+            .parallel(parallel)
+            .cache(cache)
+            .use_native_io(use_native_io)
+            .use_gcd(use_gcd)
+            .sort_dir_entries(options.sort_dir_entries.unwrap_or(false))
+            .preserve_backslashes(preserve_backslashes)
+            .confine_to(sandbox_root.clone())
+            .one_file_system(options.one_file_system.unwrap_or(false))
+            .max_open_dirs(options.max_open_dirs)
+            .hard_depth_limit(options.hard_depth_limit.map(|limit| limit as usize))
+            .dir_order(options.dir_order.unwrap_or_default());
+        let suppress_errors = options.suppress_errors.unwrap_or(false);
+        let walk_options = if suppress_errors {
+            walk_options
+        } else {
+            walk_options.report_unreadable(walk_roots(&cwd, &patterns))
+        };
 
-            if should_mark_as_dir && !base.ends_with('/') && !base.ends_with('\\') {
-                let mut result = base;
-                result.push(sep);
-                result
-            } else {
-                base
-            }
-        }
-    }
+        // Pre-compute: check if any pattern requires directory matching (ends with /)
+        let any_pattern_requires_dir = patterns.iter().any(|p| p.requires_dir());
 
-    /// Check if a path is inside any of the ignored directories.
-    /// Uses byte-level comparison for performance.
-    #[inline]
-    fn is_in_ignored_dir(&self, normalized: &str, ignored_dirs: &AHashSet<String>) -> bool {
-        if ignored_dirs.is_empty() {
-            return false;
-        }
+        // Pre-compute: count patterns with fast-path matching for optimization decisions
+        let fast_pattern_count = patterns.iter().filter(|p| p.fast_path().is_fast()).count();
 
-        let normalized_bytes = normalized.as_bytes();
-        ignored_dirs.iter().any(|ignored_dir: &String| {
-            let ignored_bytes = ignored_dir.as_bytes();
-            normalized_bytes.starts_with(ignored_bytes)
-                && (normalized_bytes.len() == ignored_bytes.len()
-                    || normalized_bytes.get(ignored_bytes.len()) == Some(&b'/')
-                    || normalized_bytes.get(ignored_bytes.len()) == Some(&b'\\'))
+        // Convert to Arc<[Pattern]> for cheap cloning into closures
+        let patterns: Arc<[Pattern]> = patterns.into();
+
+        Ok(Self {
+            pattern_strs,
+            cwd,
+            patterns,
+            pattern_origins: pattern_origins.into(),
+            input_count: inputs.len(),
+            absolute,
+            posix_explicit_true,
+            posix_explicit_false,
+            nobrace,
+            noext,
+            dot,
+            follow,
+            windows_paths_no_escape,
+            max_depth,
+            nodir,
+            dot_relative,
+            mark,
+            match_base,
+            noglobstar,
+            nocase,
+            walk_options,
+            ignore_filter,
+            any_pattern_requires_dir,
+            fast_pattern_count,
+            include_child_matches,
+            dedup_strategy: options.dedup_strategy.unwrap_or_default(),
+            scoped_dedup: options.scoped_dedup.unwrap_or(false),
+            hidden: options.hidden.unwrap_or_default(),
+            unique: options.unique.unwrap_or(true),
+            disable_optimizations,
+            sequential_rules: sequential.then(|| sequential_rules.into()),
+            stream_per_base: options.stream_per_base.unwrap_or(false),
+            result_prefix: options.result_prefix.clone().filter(|p| !p.is_empty()),
+            preserve_backslashes,
+            separator: separator.unwrap_or('/'),
+            sandbox_root,
+            read_link_targets,
+            detect_empty_dirs: options.detect_empty_dirs.unwrap_or(false),
+            suppress_errors,
+            depth_limit_hit: WalkFlag::default(),
+            realpath: options.realpath.unwrap_or(false),
+            realpath_keep_broken: options.realpath_keep_broken.unwrap_or(false),
+            canonical_only: options.canonical_only.unwrap_or(false),
+            visits: None,
         })
     }
 
-    /// Check if a path is a child of any matched parent.
-    /// Used when includeChildMatches is false.
-    #[inline]
-    fn is_child_of_matched(&self, normalized: &str, matched_parents: &AHashSet<String>) -> bool {
-        if matched_parents.is_empty() {
-            return false;
+    /// A copy of this glob that searches `cwd` instead, reusing the compiled
+    /// patterns and ignore filter rather than building them again.
+    ///
+    /// Fails like construction does when `restrictToCwd` is set and a pattern
+    /// reaches outside the new cwd.
+    pub fn with_cwd(&self, cwd: impl Into<PathBuf>) -> Result<Self> {
+        let cwd = cwd.into();
+        let sandbox_root = match self.sandbox_root {
+            Some(_) => Some(sandbox_root_for(&cwd, &self.patterns, self.file_system())?),
+            None => None,
+        };
+        let mut glob = self.clone();
+        glob.walk_options = glob.walk_options.confine_to(sandbox_root.clone());
+        if !self.suppress_errors {
+            glob.walk_options = glob
+                .walk_options
+                .report_unreadable(walk_roots(&cwd, &self.patterns));
         }
-
-        let normalized_bytes = normalized.as_bytes();
-        matched_parents.iter().any(|matched_path: &String| {
-            let matched_bytes = matched_path.as_bytes();
-            normalized_bytes.starts_with(matched_bytes)
-                && normalized_bytes.len() > matched_bytes.len()
-                && (normalized_bytes.get(matched_bytes.len()) == Some(&b'/')
-                    || normalized_bytes.get(matched_bytes.len()) == Some(&b'\\'))
-        })
+        glob.sandbox_root = sandbox_root;
+        glob.cwd = cwd;
+        Ok(glob)
     }
 
-    /// Calculate the optimal walk root based on literal prefixes of patterns.
-    ///
-    /// Returns a tuple of (walk_root, prefix_to_strip, is_absolute_pattern):
-    /// - walk_root: The directory to start walking from (cwd, cwd/prefix, or absolute root)
-    /// - prefix_to_strip: If Some, this prefix was extracted and should be prepended
-    ///   to relative paths from walk_root to get the path relative to cwd
-    /// - is_absolute_pattern: True if we're walking from an absolute pattern root
-    ///
-    /// For patterns like `src/**/*.ts`, instead of walking from cwd and visiting
-    /// all directories, we can walk from `cwd/src` which is much faster.
-    ///
-    /// For absolute patterns like `C:/foo/**/*.ts` or `/usr/local/**`, we walk from
-    /// that absolute path directly.
+    /// Run [`walk_sync`](Self::walk_sync) and report how much of the filesystem it
+    /// touched and how long it took.
     ///
-    /// When patterns have different prefixes (e.g., `src/**` and `test/**`),
-    /// we find the longest common prefix, or fall back to cwd if there's no
-    /// common prefix.
-    fn calculate_walk_root(&self) -> (PathBuf, Option<String>) {
-        // If there are no patterns, just walk from cwd
-        if self.patterns.is_empty() {
-            return (self.cwd.clone(), None);
+    /// Meant for comparing walker strategies (`parallel`, `useNativeIo`, `useGcd`)
+    /// on a given tree; the `walker_strategies` bench is built on it. Each call
+    /// counts on its own, so concurrent calls and plain walks don't affect it.
+    pub fn walk_sync_timed(&self) -> (Vec<String>, WalkStats) {
+        let visits = Arc::new(VisitCounter::default());
+        let glob = Glob {
+            visits: Some(Arc::clone(&visits)),
+            ..self.clone()
+        };
+        let start = Instant::now();
+        let results = glob.walk_sync();
+        let stats = visits.stats(start.elapsed());
+        if glob.depth_limit_hit.0.load(Ordering::Relaxed) {
+            self.depth_limit_hit.0.store(true, Ordering::Relaxed);
         }
+        (results, stats)
+    }
 
-        // When nocase is true on a case-sensitive filesystem (Linux), we can't use
-        // literal prefix optimization because the prefix case might not match the
-        // actual filesystem case. For example, pattern "SRC/**" won't find directory
-        // "src" on Linux even with nocase:true.
-        // On case-insensitive filesystems (macOS, Windows), this is not an issue.
-        if self.nocase && !self.is_case_insensitive_platform() {
-            return (self.cwd.clone(), None);
+    /// Count one visited entry if this walk is being timed.
+    #[inline]
+    fn record_visit(&self, is_dir: bool) {
+        if let Some(visits) = &self.visits {
+            visits.record(is_dir);
         }
+    }
 
-        // Check if any pattern is absolute (has a root like C:/, /, or //server/share/)
-        // If we have absolute patterns, we need to handle them specially
-        let has_absolute_pattern = self.patterns.iter().any(|p| p.is_absolute());
-
-        if has_absolute_pattern {
-            // For absolute patterns, we need to check if ALL patterns are absolute
-            // and share a common root. If not, we can't optimize.
-            let all_absolute = self.patterns.iter().all(|p| p.is_absolute());
+    pub fn walk_sync(&self) -> Vec<String> {
+        let results = self.walk_sync_paths();
+        let results = if self.realpath {
+            self.resolve_realpaths(results)
+        } else {
+            results
+        };
+        if self.canonical_only {
+            self.keep_canonical_paths(results)
+        } else {
+            results
+        }
+    }
 
-            if all_absolute && self.patterns.len() == 1 {
-                // Single absolute pattern - walk from its root + literal prefix
-                let pattern = &self.patterns[0];
-                let root = pattern.root();
-
-                // Get the literal prefix (directories before any glob magic)
-                if let Some(prefix) = pattern.literal_prefix() {
-                    // Walk from root + prefix
-                    let walk_root = PathBuf::from(&root).join(&prefix);
-                    // The prefix to strip is the root + prefix
-                    let full_prefix = if root.ends_with('/') {
-                        format!("{root}{prefix}")
-                    } else {
-                        format!("{root}/{prefix}")
-                    };
-                    return (walk_root, Some(full_prefix));
-                } else {
-                    // No literal prefix, just walk from the root
-                    return (PathBuf::from(&root), Some(root.to_string()));
+    /// Keep one result per file for `canonicalOnly`.
+    ///
+    /// Results resolving to the same file are reported once, under the path
+    /// that reaches it without going through a symlink if there is one, and
+    /// otherwise under the first. Results that fail to resolve are kept.
+    fn keep_canonical_paths(&self, results: Vec<String>) -> Vec<String> {
+        let abs_cwd = self
+            .file_system()
+            .canonicalize(&self.cwd)
+            .map_or_else(|_| self.cwd.clone(), strip_windows_extended_prefix);
+        self.keep_canonical(results, |result| self.result_abs_path(result, &abs_cwd))
+    }
+
+    /// Keep one of `results` per file, as `keep_canonical_paths` does, given the
+    /// absolute path behind each result.
+    fn keep_canonical<T>(&self, results: Vec<T>, abs_path: impl Fn(&T) -> PathBuf) -> Vec<T> {
+        let mut chosen: AHashMap<PathBuf, (usize, bool)> = AHashMap::with_capacity(results.len());
+        let mut keys = Vec::with_capacity(results.len());
+        for (index, result) in results.iter().enumerate() {
+            let path = abs_path(result);
+            let Ok(real) = self
+                .file_system()
+                .canonicalize(&path)
+                .map(strip_windows_extended_prefix)
+            else {
+                keys.push(None);
+                continue;
+            };
+            // Dropping `.` and `..` leaves the path itself exactly when no symlink is on it
+            let is_real = normalize_path_lexically(&path) == real;
+            match chosen.get_mut(&real) {
+                Some(slot) if is_real && !slot.1 => *slot = (index, true),
+                Some(_) => {}
+                None => {
+                    chosen.insert(real.clone(), (index, is_real));
                 }
-            } else if all_absolute {
-                // Multiple absolute patterns - find common root
-                let roots: Vec<&str> = self.patterns.iter().map(|p| p.root()).collect();
-
-                // Check if all roots are the same
-                if !roots.is_empty() && roots.iter().all(|r| *r == roots[0]) {
-                    let common_root = roots[0];
-
-                    // Get literal prefixes after the root
-                    let prefixes: Vec<Option<String>> =
-                        self.patterns.iter().map(|p| p.literal_prefix()).collect();
-
-                    // If any pattern has no prefix, walk from the root
-                    if prefixes.iter().any(|p| p.is_none()) {
-                        return (PathBuf::from(common_root), Some(common_root.to_string()));
-                    }
-
-                    // Find common prefix among all patterns
-                    let prefix_strs: Vec<&str> = prefixes
-                        .iter()
-                        .filter_map(|p| p.as_ref().map(|s| s.as_str()))
-                        .collect();
-
-                    let common_prefix = Self::longest_common_prefix(&prefix_strs);
+            }
+            keys.push(Some(real));
+        }
+        results
+            .into_iter()
+            .zip(keys)
+            .enumerate()
+            .filter(|(index, (_, key))| key.as_ref().is_none_or(|real| chosen[real].0 == *index))
+            .map(|(_, (result, _))| result)
+            .collect()
+    }
+
+    /// The absolute path behind one formatted result, without `mark`'s separator.
+    fn result_abs_path(&self, result: &str, abs_cwd: &Path) -> PathBuf {
+        let prefix_len = self.result_prefix.as_ref().map_or(0, String::len);
+        let path_str = &result[prefix_len..];
+        let unmarked = match path_str.trim_end_matches(['/', '\\']) {
+            "" => path_str,
+            trimmed => trimmed,
+        };
+        abs_cwd.join(unmarked)
+    }
 
-                    if common_prefix.is_empty() {
-                        return (PathBuf::from(common_root), Some(common_root.to_string()));
-                    }
+    /// Resolve each result to its real path for `realpath`.
+    ///
+    /// Paths resolving to the same place are reported once (unless `unique` is
+    /// off). Broken symlinks are dropped, or kept under their own path with
+    /// `realpathKeepBroken`; paths that fail to resolve otherwise are dropped.
+    fn resolve_realpaths(&self, results: Vec<String>) -> Vec<String> {
+        let abs_cwd = strip_windows_extended_prefix(
+            self.file_system()
+                .canonicalize(&self.cwd)
+                .unwrap_or_else(|_| self.cwd.clone()),
+        );
+        let mut seen = AHashSet::with_capacity(if self.unique { results.len() } else { 0 });
+        results
+            .into_iter()
+            .filter_map(|result| self.resolve_realpath(result, &abs_cwd))
+            .filter(|result| !self.unique || seen.insert(result.clone()))
+            .collect()
+    }
+
+    /// Resolve one formatted result to its real path, formatted the same way.
+    fn resolve_realpath(&self, result: String, abs_cwd: &Path) -> Option<String> {
+        let prefix_len = self.result_prefix.as_ref().map_or(0, String::len);
+        // Drops the `mark` separator; the real path is re-marked below
+        let path = self.result_abs_path(&result, abs_cwd);
+
+        let fs = self.file_system();
+        let real = match fs.canonicalize(&path) {
+            Ok(real) => strip_windows_extended_prefix(real),
+            Err(_) => {
+                let broken_link = fs.symlink_metadata(&path).is_ok_and(|meta| meta.is_symlink)
+                    && fs
+                        .metadata(&path)
+                        .is_err_and(|err| err.kind() == std::io::ErrorKind::NotFound);
+                return (broken_link && self.realpath_keep_broken).then_some(result);
+            }
+        };
 
-                    let walk_root = PathBuf::from(common_root).join(&common_prefix);
-                    let full_prefix = if common_root.ends_with('/') {
-                        format!("{common_root}{common_prefix}")
-                    } else {
-                        format!("{common_root}/{common_prefix}")
-                    };
-                    return (walk_root, Some(full_prefix));
+        let use_forward = self.should_normalize_backslashes();
+        let mut resolved = String::with_capacity(result.len());
+        resolved.push_str(&result[..prefix_len]);
+        if self.absolute {
+            resolved.push_str(&self.format_path(&real));
+        } else {
+            let relative = relative_path(abs_cwd, &real);
+            let relative = relative.to_string_lossy();
+            if relative.is_empty() {
+                resolved.push('.');
+            } else {
+                if self.dot_relative && !relative.starts_with("..") {
+                    resolved.push_str(if use_forward { "./" } else { ".\\" });
+                }
+                if use_forward && !self.preserve_backslashes {
+                    resolved.push_str(&relative.replace('\\', "/"));
+                } else {
+                    resolved.push_str(&relative);
                 }
             }
-
-            // Mixed absolute and relative patterns, or different roots
-            // Fall back to walking from cwd for relative patterns
-            // This is a limitation - we can't efficiently handle mixed patterns
-            return (self.cwd.clone(), None);
         }
-
-        // Get literal prefixes from all patterns
-        let prefixes: Vec<Option<String>> =
-            self.patterns.iter().map(|p| p.literal_prefix()).collect();
-
-        // If any pattern has no prefix (e.g., `**/*.js` or `*.txt`), we must walk from cwd
-        if prefixes.iter().any(|p| p.is_none()) {
-            return (self.cwd.clone(), None);
+        if self.mark
+            && !resolved.ends_with(['/', '\\'])
+            && fs.metadata(&real).is_ok_and(|meta| meta.is_dir)
+        {
+            resolved.push(if use_forward { '/' } else { '\\' });
         }
+        Some(resolved)
+    }
 
-        // All patterns have prefixes - find the longest common prefix
-        let prefix_strs: Vec<&str> = prefixes
-            .iter()
-            .filter_map(|p| p.as_ref().map(|s| s.as_str()))
-            .collect();
+    /// The optimization path `walk_sync` takes for this glob.
+    ///
+    /// Other walks (streams, `withFileTypes`, `unique: false`) always do a single
+    /// walk, `PrefixScoped` or `FullWalk`.
+    pub fn strategy(&self) -> WalkStrategy {
+        self.fast_path_strategy()
+            .unwrap_or_else(|| match self.calculate_walk_root().1 {
+                Some(_) => WalkStrategy::PrefixScoped,
+                None => WalkStrategy::FullWalk,
+            })
+    }
 
-        if prefix_strs.is_empty() {
-            return (self.cwd.clone(), None);
+    /// The strategy `walk_sync` uses instead of a single walk, if any.
+    fn fast_path_strategy(&self) -> Option<WalkStrategy> {
+        if !self.unique || self.disable_optimizations || self.sequential_rules.is_some() {
+            return None;
+        }
+        // The shortcuts read the real filesystem directly
+        if self.walk_options.file_system.is_some() {
+            return None;
         }
 
-        // Find the longest common prefix among all pattern prefixes
-        let common_prefix = Self::longest_common_prefix(&prefix_strs);
-
-        if common_prefix.is_empty() {
-            return (self.cwd.clone(), None);
+        // OPTIMIZATION: Static pattern fast path
+        // If ALL patterns are static (no wildcards), we can use direct stat() instead of walking.
+        // This is 10-100x faster for patterns like "package.json" or "src/index.ts".
+        // Results come in pattern order, so post-order needs a real walk.
+        if self.all_patterns_static() && self.walk_options.dir_order == DirOrder::PreOrder {
+            return Some(WalkStrategy::Static);
         }
 
-        // Construct the walk root
-        let walk_root = self.cwd.join(&common_prefix);
+        // OPTIMIZATION: Shallow pattern fast path
+        // If ALL patterns have max_depth of 0 (root-level only), use direct readdir
+        // instead of the full walker machinery. This is 2-3x faster for patterns like "*.js".
+        // NOTE: We must respect user's maxDepth if specified. maxDepth: 0 means only "."
+        // which can't match shallow patterns like "*.js" - those need depth 1.
+        // If user specified maxDepth: 0, skip this optimization and let the walker handle it.
+        if self.all_patterns_shallow() && self.max_depth != Some(0) {
+            return Some(WalkStrategy::Shallow);
+        }
 
-        // Verify the walk root exists before using it
-        if !walk_root.exists() {
-            // If the prefix directory doesn't exist, we'll get empty results anyway
-            // But we still walk from there to get correct behavior
-            return (walk_root, Some(common_prefix));
+        // OPTIMIZATION: Multi-base walking
+        // If patterns have different prefixes (e.g., ['src/**/*.ts', 'test/**/*.ts']),
+        // walk from each prefix separately instead of from cwd.
+        // This avoids traversing unrelated directories.
+        if self.should_use_multi_base_walking() {
+            return Some(WalkStrategy::MultiBase);
         }
 
-        (walk_root, Some(common_prefix))
+        None
     }
 
-    /// Group patterns by their first-level literal prefix.
-    ///
-    /// This enables multi-base walking: instead of walking from cwd when patterns
-    /// have different prefixes, we walk from each unique prefix separately.
-    ///
-    /// Returns a map of prefix -> pattern indices.
-    /// Patterns without a prefix (e.g., `**/*.js`) go into the `None` group.
-    ///
-    /// # Example
-    /// ```ignore
-    /// patterns: ["src/**/*.ts", "src/lib/*.ts", "test/**/*.ts", "**/*.js"]
-    /// Result: {
-    ///   Some("src") -> [0, 1],
-    ///   Some("test") -> [2],
-    ///   None -> [3]
-    /// }
-    /// ```
-    fn group_patterns_by_base(&self) -> std::collections::HashMap<Option<String>, Vec<usize>> {
-        use std::collections::HashMap;
-        let mut groups: HashMap<Option<String>, Vec<usize>> = HashMap::new();
-
-        for (idx, pattern) in self.patterns.iter().enumerate() {
-            // Get the first component of the literal prefix
-            // This is more aggressive grouping than using the full prefix
-            let base = pattern.literal_prefix().map(|prefix| {
-                // Get just the first path component
-                prefix
-                    .split('/')
-                    .next()
-                    .map(|s| s.to_string())
-                    .unwrap_or(prefix)
-            });
-
-            groups.entry(base).or_default().push(idx);
+    /// The strategy `walk_sync` takes, with the root and prefix of its walk.
+    pub fn plan(&self) -> GlobPlan {
+        let (strategy, walk_root, prefix) = match self.fast_path_strategy() {
+            Some(strategy) => (strategy, self.cwd.clone(), None),
+            None => match self.calculate_walk_root() {
+                (walk_root, Some(prefix)) => (WalkStrategy::PrefixScoped, walk_root, Some(prefix)),
+                (walk_root, None) => (WalkStrategy::FullWalk, walk_root, None),
+            },
+        };
+        GlobPlan {
+            strategy: strategy.name().to_string(),
+            walk_root: walk_root.to_string_lossy().into_owned(),
+            prefix,
         }
-
-        groups
     }
 
-    /// Check if multi-base walking would be beneficial.
-    ///
-    /// Multi-base walking helps when:
-    /// 1. All patterns have literal prefixes (no patterns like `**/*.js`)
-    /// 2. There are multiple distinct first-level prefixes (e.g., `src` and `test`)
-    /// 3. All prefixes point to existing directories
-    fn should_use_multi_base_walking(&self) -> bool {
-        // When nocase is true on a case-sensitive filesystem (Linux), we can't use
-        // multi-base walking because the prefix case might not match the filesystem.
-        if self.nocase && !self.is_case_insensitive_platform() {
-            return false;
+    /// Walk the filesystem and collect the formatted results, before `realpath`.
+    fn walk_sync_paths(&self) -> Vec<String> {
+        // If maxDepth is negative, return empty results
+        if let Some(d) = self.max_depth {
+            if d < 0 {
+                return Vec::new();
+            }
         }
 
-        // Quick check: if any pattern has no prefix, we can't use multi-base
-        if self.patterns.iter().any(|p| p.literal_prefix().is_none()) {
-            return false;
+        // unique: false repeats each path once per matching input pattern
+        if !self.unique {
+            let mut results = Vec::with_capacity(self.estimate_result_capacity());
+            self.walk_sync_with_matching_inputs(|path, inputs| {
+                // Every walked path matched something, even if re-matching it here can't tell
+                results.extend(std::iter::repeat_n(path, inputs.len().max(1)));
+            });
+            return results;
         }
 
-        // Get first-level bases
-        let groups = self.group_patterns_by_base();
-
-        // Need at least 2 distinct bases to benefit from multi-base walking
-        if groups.len() < 2 {
-            return false;
+        // Static, shallow and multi-base patterns skip the single walk below
+        match self.fast_path_strategy() {
+            Some(WalkStrategy::Static) => return self.resolve_static_patterns(),
+            Some(WalkStrategy::Shallow) => return self.resolve_shallow_patterns(),
+            Some(WalkStrategy::MultiBase) => return self.walk_multi_base(),
+            _ => {}
         }
 
-        // All groups must have Some base (no None group)
-        if groups.contains_key(&None) {
-            return false;
-        }
+        // Pre-allocate result vector with estimated capacity based on pattern depth.
+        // Simple patterns (depth 0-1) typically match fewer files than recursive patterns.
+        // This reduces reallocations during collection.
+        let estimated_capacity = self.estimate_result_capacity();
+        let mut results = Vec::with_capacity(estimated_capacity);
+        // Use AHashSet for faster hashing than std::collections::HashSet
+        let mut seen = self.walk_seen_paths(estimated_capacity);
+        let mut ignored_dirs: AHashSet<String> = AHashSet::with_capacity(8); // Most globs have few ignored dirs
 
-        // Check that all base directories exist
-        groups.keys().all(|base| {
-            if let Some(base_str) = base {
-                self.cwd.join(base_str).exists()
-            } else {
-                false
-            }
-        })
-    }
+        // When includeChildMatches is false, track (result, normalized) pairs for post-filtering
+        // This is needed because filesystem order may cause children to be seen before parents on Linux
+        let mut matched_with_normalized: Vec<(String, String)> = if self.include_child_matches {
+            Vec::new() // Empty, won't be used
+        } else {
+            Vec::with_capacity(estimated_capacity)
+        };
 
-    /// Walk using multiple base directories in parallel using rayon.
-    ///
-    /// This is an optimization for patterns like `['src/**/*.ts', 'test/**/*.ts']`.
-    /// Instead of walking from cwd and visiting all directories, we walk from
-    /// `src/` and `test/` concurrently using rayon's parallel iterators.
-    ///
-    /// Each base directory is processed in parallel, and results are merged
-    /// with deduplication at the end.
-    fn walk_multi_base(&self) -> Vec<String> {
-        let groups = self.group_patterns_by_base();
+        // Pre-allocate a reusable buffer for path formatting
+        let mut result_buffer = String::with_capacity(self.estimate_path_buffer_capacity());
+
+        // Check if any pattern matches the cwd itself ("**" or ".").
+        // Cache this check since preprocess_pattern is called for each pattern.
+        let include_cwd = self.patterns.iter().any(pattern_matches_cwd);
+
+        // Get the absolute cwd path, canonicalized
         // Strip Windows extended-length prefix (\\?\) to match glob v13 behavior
         let abs_cwd = strip_windows_extended_prefix(
             self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
         );
 
-        // Convert groups to a Vec for parallel iteration
-        let groups_vec: Vec<(Option<String>, Vec<usize>)> = groups.into_iter().collect();
-
-        // Process each base group in parallel using rayon
-        // Each group returns its own Vec of results (local deduplication)
-        let group_results: Vec<Vec<String>> = groups_vec
-            .par_iter()
-            .filter_map(|(base, pattern_indices)| {
-                // Skip groups without a valid base
-                base.as_ref()?;
+        // Calculate the walk root based on literal prefixes of all patterns.
+        // If all patterns share a common literal prefix, we can start walking from there
+        // instead of the cwd, which can significantly reduce the number of files traversed.
+        let (walk_root, prefix_to_strip) = self.calculate_walk_root();
 
-                Some(self.walk_single_base_group(pattern_indices, &abs_cwd))
-            })
-            .collect();
+        // Pre-compute the prefix with trailing slash for efficient path concatenation.
+        // This avoids repeated format!() calls in the hot loop.
+        let prefix_with_slash: Option<String> =
+            prefix_to_strip.as_deref().map(prefix_with_separator);
 
-        // Merge all results and deduplicate
-        let estimated_capacity = self.estimate_result_capacity();
-        let mut seen: AHashSet<String> = AHashSet::with_capacity(estimated_capacity);
-        let mut results = Vec::with_capacity(estimated_capacity);
-
-        for group_result in group_results {
-            for result in group_result {
-                if seen.insert(result.clone()) {
-                    results.push(result);
-                }
-            }
-        }
-
-        results
-    }
-
-    /// Walk a single base directory group and return results.
-    ///
-    /// This method is designed to be called in parallel from `walk_multi_base`.
-    /// It handles all the logic for walking a single base directory and matching
-    /// patterns within that group.
-    fn walk_single_base_group(&self, pattern_indices: &[usize], abs_cwd: &Path) -> Vec<String> {
-        let estimated_capacity = self.estimate_result_capacity() / 4; // Smaller per-group
-        let mut results = Vec::with_capacity(estimated_capacity);
-        let mut seen: AHashSet<String> = AHashSet::with_capacity(estimated_capacity);
-        let mut ignored_dirs: AHashSet<String> = AHashSet::with_capacity(8);
-        let mut matched_parents: AHashSet<String> = if self.include_child_matches {
-            AHashSet::new()
-        } else {
-            AHashSet::with_capacity(estimated_capacity / 4)
-        };
-        let mut result_buffer = String::with_capacity(self.estimate_path_buffer_capacity());
-        let has_ignore_filter = self.ignore_filter.is_some();
-
-        // Get the patterns for this group
-        let group_patterns: Vec<&Pattern> =
-            pattern_indices.iter().map(|&i| &self.patterns[i]).collect();
-
-        // Find the longest common prefix within this group
-        let prefixes: Vec<Option<String>> =
-            group_patterns.iter().map(|p| p.literal_prefix()).collect();
-        let prefix_strs: Vec<&str> = prefixes
-            .iter()
-            .filter_map(|p| p.as_ref().map(|s| s.as_str()))
-            .collect();
-        let common_prefix = Self::longest_common_prefix(&prefix_strs);
-
-        // Walk from the common prefix (at least the base)
-        let walk_root = self.cwd.join(&common_prefix);
-        let prefix_to_strip = if common_prefix.is_empty() {
-            None
-        } else {
-            Some(common_prefix.clone())
-        };
-
-        // Pre-compute the prefix with trailing slash for efficient path concatenation
-        let prefix_with_slash: Option<String> =
-            prefix_to_strip.as_ref().map(|prefix| format!("{prefix}/"));
-
-        // Adjust walk options for this prefix
+        // Adjust walk options for prefix-based walking
+        // If we have a prefix, the user's max_depth is relative to cwd, but the walker
+        // is relative to walk_root. We need to reduce max_depth by the prefix depth.
         let adjusted_walk_options = if let Some(ref prefix) = prefix_to_strip {
             let prefix_depth = prefix.split('/').filter(|s| !s.is_empty()).count();
             if let Some(max_d) = self.walk_options.max_depth {
+                // User specified max_depth, adjust for prefix
                 if max_d <= prefix_depth {
+                    // If max_depth is less than or equal to prefix depth,
+                    // we should only include the prefix directory itself
                     self.walk_options.clone().max_depth(Some(0))
                 } else {
                     self.walk_options
@@ -1782,55 +2598,52 @@ impl Glob {
             self.walk_options.clone()
         };
 
-        // Create pruning filter for this group's patterns
-        let patterns_arc: Arc<[Pattern]> = group_patterns.iter().cloned().cloned().collect();
-        let prefix_for_filter = prefix_to_strip.clone();
-        let prefix_slash_for_filter = prefix_with_slash.clone();
-
-        let prune_filter = Box::new(move |dir_path: &str| -> bool {
-            let path_from_cwd: Cow<'_, str> = if let Some(ref prefix) = prefix_for_filter {
-                if dir_path.is_empty() {
-                    Cow::Borrowed(prefix.as_str())
-                } else if let Some(ref prefix_slash) = prefix_slash_for_filter {
-                    Cow::Owned(format!("{prefix_slash}{dir_path}"))
-                } else {
-                    Cow::Owned(format!("{prefix}/{dir_path}"))
-                }
-            } else {
-                Cow::Borrowed(dir_path)
-            };
+        // Create a directory pruning filter so subtrees that can't contain matches,
+        // or that are ignored wholesale, are never traversed.
+        let prune_filter = self.dir_prune_filter(
+            Arc::clone(&self.patterns),
+            prefix_to_strip.clone(),
+            prefix_with_slash.clone(),
+            &abs_cwd,
+        );
 
-            patterns_arc
-                .iter()
-                .any(|p| p.could_match_in_dir(&path_from_cwd))
-        });
+        // Create walker with the optimized walk root, adjusted options, and pruning filter
+        let walker = new_walker(walk_root.clone(), adjusted_walk_options, prune_filter);
 
-        // Create walker for this group
-        let walker = Walker::new(walk_root.clone(), adjusted_walk_options)
-            .with_dir_prune_filter(prune_filter);
+        // Optimization: Check if we have any ignore patterns to avoid unnecessary work
+        let has_ignore_filter = self.ignore_filter.is_some();
 
-        // Walk and collect results
-        for entry in walker.walk() {
+        for entry in walker.walk().inspect(|e| self.record_visit(e.is_dir())) {
             let path = entry.path();
 
+            // Strip the walk_root prefix to get the path relative to walk_root
+            // Then prepend the prefix_to_strip to get the path relative to cwd
             let rel_path_from_walk_root = match path.strip_prefix(&walk_root) {
                 Ok(p) => p,
-                Err(_) => continue,
+                Err(_) => continue, // Skip if can't strip prefix
             };
             let rel_str_from_walk_root = rel_path_from_walk_root.to_string_lossy();
+
+            // Cache whether this is the walk root (empty relative path)
             let is_walk_root_entry = rel_str_from_walk_root.is_empty();
 
+            // Construct the path relative to cwd by prepending the stripped prefix.
+            // Use Cow to avoid allocation when possible.
             let normalized = self.normalize_path(
                 &rel_str_from_walk_root,
                 &prefix_to_strip,
                 is_walk_root_entry,
             );
 
+            // Check if this path is inside an ignored directory.
             if self.is_in_ignored_dir(&normalized, &ignored_dirs) {
                 continue;
             }
 
+            // Check ignore patterns
+            // Optimization: Only create rel_path and abs_path when we have ignore patterns
             if has_ignore_filter {
+                // For operations that need the actual relative path from cwd
                 let rel_path = if prefix_to_strip.is_some() {
                     PathBuf::from(normalized.as_ref())
                 } else {
@@ -1839,3341 +2652,10396 @@ impl Glob {
                 let abs_path = abs_cwd.join(&rel_path);
                 let ignore_filter = self.ignore_filter.as_ref().unwrap();
 
+                // Check if this specific path should be ignored
                 if ignore_filter.should_ignore(&normalized, &abs_path) {
+                    // If children are also ignored, mark this directory
                     if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
                         ignored_dirs.insert(normalized.into_owned());
                     }
                     continue;
                 }
 
+                // Also check if this is a directory whose children should be ignored
+                // (for optimization - skip traversing)
                 if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
                     ignored_dirs.insert(normalized.to_string());
                 }
             }
 
-            // Handle root of walk_root - for multi-base, this is the base directory itself
-            if is_walk_root_entry {
-                // The base directory (e.g., "src") - check if any pattern matches it
-                let matches_base = group_patterns.iter().any(|p| {
-                    let path_matches = match p.matches_fast(&normalized) {
-                        Some(result) => result,
-                        None => p.matches(&normalized),
-                    };
-                    if path_matches && p.requires_dir() {
-                        true // It's the base dir, which is a directory
-                    } else {
-                        path_matches
-                    }
-                });
-
-                if matches_base && !self.nodir {
+            // Handle root of walk_root (which might be cwd or cwd/prefix)
+            if is_walk_root_entry && prefix_to_strip.is_none() {
+                // This is the cwd itself - handle specially
+                // Root directory - only include if pattern matches it
+                // With nodir: true, skip even the root directory since it's a directory
+                if include_cwd && !self.nodir {
+                    // Check if cwd itself is ignored
                     if let Some(ref ignore_filter) = self.ignore_filter {
-                        let abs_path = abs_cwd.join(&*normalized);
-                        if ignore_filter.should_ignore(&normalized, &abs_path) {
+                        if ignore_filter.should_ignore(".", &abs_cwd) {
                             continue;
                         }
                     }
 
-                    let result = self.build_result_path(
-                        &normalized,
-                        true, // is_dir
-                        entry.is_symlink(),
-                        abs_cwd,
-                        &mut result_buffer,
-                    );
-
-                    if seen.insert(result.clone()) {
-                        if !self.include_child_matches {
-                            matched_parents.insert(normalized.into_owned());
-                        }
+                    let result = self.build_cwd_result_path(&abs_cwd, &mut result_buffer);
+                    if seen.insert(&result) {
                         results.push(result);
                     }
                 }
                 continue;
             }
 
+            // Skip empty normalized paths that aren't the cwd
             if normalized.is_empty() {
                 continue;
             }
 
+            // If nodir is true, skip directories
+            // Note: Symlinks are not considered directories unless follow is true
+            // When follow is true, walkdir reports symlinks-to-dirs as dirs
             if self.nodir && entry.is_dir() {
                 continue;
             }
 
-            if !self.dot && !self.path_allowed_by_dot_rules(&normalized) {
-                continue;
-            }
-
-            if !self.include_child_matches
-                && self.is_child_of_matched(&normalized, &matched_parents)
+            // If dot:false, check if this path contains dotfile segments
+            // that aren't explicitly allowed by any pattern
+            if !self.dot
+                && (!self.path_allowed_by_dot_rules(&normalized)
+                    || self.hidden_by_attribute(&normalized))
             {
                 continue;
             }
 
+            // Check if any pattern matches
+            // For patterns that end with /, only match if entry is a directory
             let is_dir = entry.is_dir();
             let is_symlink = entry.is_symlink();
 
-            // Check if any pattern in this group matches
-            let matches = group_patterns.iter().any(|p| {
-                let path_matches = match p.matches_fast(&normalized) {
-                    Some(result) => result,
-                    None => p.matches(&normalized),
-                };
-                if path_matches && p.requires_dir() {
-                    is_dir
-                } else {
-                    path_matches
-                }
-            });
+            // Optimization: Use specialized matching based on pattern characteristics.
+            // Patterns are already sorted with fast-path patterns first (in new_multi),
+            // so .any() will try fast patterns before falling back to regex patterns.
+            let matches = if !self.any_pattern_requires_dir {
+                // Fast path: no patterns require directory matching
+                self.patterns
+                    .iter()
+                    .any(|p| match p.matches_fast(&normalized) {
+                        Some(result) => result,
+                        None => p.matches(&normalized),
+                    })
+            } else {
+                // Standard path: some patterns require directory matching
+                self.patterns.iter().any(|p| {
+                    let path_matches = match p.matches_fast(&normalized) {
+                        Some(result) => result,
+                        None => p.matches(&normalized),
+                    };
+                    if path_matches && p.requires_dir() {
+                        is_dir_or_dir_link(
+                            self.file_system(),
+                            is_dir,
+                            entry.is_symlink(),
+                            entry.path(),
+                        )
+                    } else {
+                        path_matches
+                    }
+                })
+            };
 
-            if matches {
+            if matches && !self.sequentially_excluded(&normalized, is_dir) {
+                // Build the result path using optimized helper
                 let result = self.build_result_path(
                     &normalized,
                     is_dir,
                     is_symlink,
-                    abs_cwd,
+                    &abs_cwd,
                     &mut result_buffer,
                 );
 
-                if seen.insert(result.clone()) {
+                // Deduplicate results (important for overlapping brace expansions)
+                if seen.insert(&result) {
+                    // When includeChildMatches is false, track (result, normalized) for post-filtering
                     if !self.include_child_matches {
-                        matched_parents.insert(normalized.into_owned());
+                        matched_with_normalized.push((result.clone(), normalized.into_owned()));
                     }
                     results.push(result);
                 }
             }
         }
 
+        self.note_depth_limit(&walker);
+
+        // When includeChildMatches is false, post-process to filter out children
+        // This handles cases where filesystem order causes children to be seen before parents
+        if !self.include_child_matches && !matched_with_normalized.is_empty() {
+            return filter_child_matches(matched_with_normalized);
+        }
+
         results
     }
 
-    /// Find the longest common prefix among a list of paths.
-    ///
-    /// For example:
-    /// - `["src/lib", "src/bin"]` -> `"src"`
-    /// - `["src", "test"]` -> `""`
-    /// - `["packages/foo", "packages/bar"]` -> `"packages"`
-    fn longest_common_prefix(paths: &[&str]) -> String {
-        if paths.is_empty() {
-            return String::new();
+    /// Walk the directory tree and return PathData objects.
+    /// This is used when withFileTypes: true is set.
+    pub fn walk_sync_with_file_types(&self) -> Vec<PathData> {
+        let results = self.walk_sync_path_data();
+        if self.canonical_only {
+            let abs_cwd = strip_windows_extended_prefix(
+                self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
+            );
+            self.keep_canonical(results, |data| abs_cwd.join(&data.path))
+        } else {
+            results
         }
+    }
 
-        if paths.len() == 1 {
-            return paths[0].to_string();
+    /// Walk the directory tree and collect the PathData results, before
+    /// `canonicalOnly`.
+    fn walk_sync_path_data(&self) -> Vec<PathData> {
+        // If maxDepth is negative, return empty results
+        if let Some(d) = self.max_depth {
+            if d < 0 {
+                return Vec::new();
+            }
         }
 
-        // Split all paths into components
-        let path_components: Vec<Vec<&str>> =
-            paths.iter().map(|p| p.split('/').collect()).collect();
+        // Pre-allocate result vector with estimated capacity
+        let estimated_capacity = self.estimate_result_capacity();
+        let mut results = Vec::with_capacity(estimated_capacity);
+        // Use AHashSet for faster hashing
+        let mut seen = self.walk_seen_paths(estimated_capacity);
+        let mut ignored_dirs: AHashSet<String> = AHashSet::with_capacity(8);
 
-        // Find the minimum length
-        let min_len = path_components.iter().map(|c| c.len()).min().unwrap_or(0);
+        // When includeChildMatches is false, track (result, normalized) pairs for post-filtering
+        let mut matched_with_normalized: Vec<(PathData, String)> = if self.include_child_matches {
+            Vec::new()
+        } else {
+            Vec::with_capacity(estimated_capacity)
+        };
 
-        // Find common prefix components
-        let mut common_components: Vec<&str> = Vec::new();
-        for i in 0..min_len {
-            let first = path_components[0][i];
-            if path_components.iter().all(|c| c[i] == first) {
-                common_components.push(first);
+        // Check if any pattern matches the cwd itself ("**" or ".").
+        let include_cwd = self.patterns.iter().any(pattern_matches_cwd);
+
+        // Get the absolute cwd path, canonicalized
+        // Strip Windows extended-length prefix (\\?\) to match glob v13 behavior
+        let abs_cwd = strip_windows_extended_prefix(
+            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
+        );
+
+        // Calculate the walk root based on literal prefixes
+        let (walk_root, prefix_to_strip) = self.calculate_walk_root();
+
+        // Pre-compute the prefix with trailing slash for efficient path concatenation
+        let prefix_with_slash: Option<String> =
+            prefix_to_strip.as_deref().map(prefix_with_separator);
+
+        // Adjust walk options for prefix-based walking
+        let adjusted_walk_options = if let Some(ref prefix) = prefix_to_strip {
+            let prefix_depth = prefix.split('/').filter(|s| !s.is_empty()).count();
+            if let Some(max_d) = self.walk_options.max_depth {
+                if max_d <= prefix_depth {
+                    self.walk_options.clone().max_depth(Some(0))
+                } else {
+                    self.walk_options
+                        .clone()
+                        .max_depth(Some(max_d - prefix_depth))
+                }
             } else {
-                break;
+                self.walk_options.clone()
             }
-        }
+        } else {
+            self.walk_options.clone()
+        };
 
-        common_components.join("/")
-    }
+        let prune_filter = self.dir_prune_filter(
+            Arc::clone(&self.patterns),
+            prefix_to_strip.clone(),
+            prefix_with_slash.clone(),
+            &abs_cwd,
+        );
 
-    /// Check if all patterns are static (no wildcards, can be resolved with stat()).
-    ///
-    /// Static patterns are patterns like `package.json` or `src/index.ts` that
-    /// resolve to a single path and can be checked with a direct stat() call
-    /// instead of walking the entire directory tree.
-    fn all_patterns_static(&self) -> bool {
-        // When nocase is true on a case-sensitive filesystem (Linux), we can't use
-        // the static pattern fast path because we need to scan directories to find
-        // case-insensitive matches.
-        if self.nocase && !self.is_case_insensitive_platform() {
-            return false;
-        }
-        !self.patterns.is_empty() && self.patterns.iter().all(|p| p.is_static())
-    }
+        // Create walker
+        let walker = new_walker(
+            walk_root.clone(),
+            self.with_accurate_symlinks(adjusted_walk_options),
+            prune_filter,
+        );
 
-    /// Check if all patterns are shallow (max_depth 0, root-level only).
-    ///
-    /// Shallow patterns like `*.js` or `*.{ts,tsx}` can be resolved with a single
-    /// readdir call instead of using the full walker machinery.
-    fn all_patterns_shallow(&self) -> bool {
-        if self.patterns.is_empty() {
-            return false;
-        }
-        // All patterns must have max_depth of 0 (no path separators, no **)
-        self.patterns.iter().all(|p| p.max_depth() == Some(0))
-    }
+        // Check if we have ignore patterns
+        let has_ignore_filter = self.ignore_filter.is_some();
 
-    /// Check if the current platform has a case-insensitive filesystem by default.
-    ///
-    /// This is used to determine if we can use prefix-based walking optimizations
-    /// with nocase:true. On macOS and Windows, the filesystem is typically case-insensitive,
-    /// so "SRC" and "src" refer to the same directory. On Linux, they're different.
-    #[inline]
-    fn is_case_insensitive_platform(&self) -> bool {
-        // macOS (darwin) and Windows (win32) have case-insensitive filesystems by default
-        cfg!(target_os = "macos") || cfg!(target_os = "windows")
-    }
+        for entry in walker.walk().inspect(|e| self.record_visit(e.is_dir())) {
+            let path = entry.path();
 
-    /// Check if backslashes should be normalized to forward slashes.
-    ///
-    /// On Windows with posix: false (the default), glob v13 outputs backslashes.
-    /// On Windows with posix: true, glob v13 outputs forward slashes.
-    /// On non-Windows, glob v13 always outputs forward slashes.
-    #[inline]
-    fn should_normalize_backslashes(&self) -> bool {
-        // Use forward slashes when:
-        // - On non-Windows platforms (always)
-        // - On Windows with posix: true
-        self.posix_explicit_true || !cfg!(target_os = "windows")
-    }
+            let rel_path_from_walk_root = match path.strip_prefix(&walk_root) {
+                Ok(p) => p,
+                Err(_) => continue,
+            };
+            let rel_str_from_walk_root = rel_path_from_walk_root.to_string_lossy();
 
-    /// Normalize path separators based on platform and posix option.
-    ///
-    /// When use_forward_slashes is true: converts backslashes to forward slashes
-    /// When use_forward_slashes is false: converts forward slashes to backslashes
-    ///
-    /// Returns the original string if no conversion is needed.
-    #[inline]
-    fn normalize_separators<'a>(&self, path: &'a str) -> Cow<'a, str> {
-        let use_forward = self.should_normalize_backslashes();
-        if use_forward {
-            if !path.contains('\\') {
-                Cow::Borrowed(path)
-            } else {
-                Cow::Owned(path.replace('\\', "/"))
-            }
-        } else {
-            // On Windows with posix: false, convert forward slashes to backslashes
-            if !path.contains('/') {
-                Cow::Borrowed(path)
-            } else {
-                Cow::Owned(path.replace('/', "\\"))
-            }
-        }
-    }
+            let is_walk_root_entry = rel_str_from_walk_root.is_empty();
 
-    /// Resolve shallow patterns using direct readdir.
-    ///
-    /// This is a fast path for patterns like `*.js` that only match at the root level.
-    /// Instead of using the full walker machinery with all its overhead, we do a
-    /// single readdir and filter the results.
-    fn resolve_shallow_patterns(&self) -> Vec<String> {
-        use std::fs;
+            // Use optimized normalization with Cow
+            let normalized = self.normalize_path(
+                &rel_str_from_walk_root,
+                &prefix_to_strip,
+                is_walk_root_entry,
+            );
 
-        let mut results = Vec::new();
-        let mut seen: AHashSet<String> = AHashSet::new();
+            // Check if this path is inside an ignored directory
+            if self.is_in_ignored_dir(&normalized, &ignored_dirs) {
+                continue;
+            }
 
-        // Read the directory entries directly
-        let entries = match fs::read_dir(&self.cwd) {
-            Ok(rd) => rd,
-            Err(_) => return results,
-        };
+            // Check ignore patterns
+            if has_ignore_filter {
+                let rel_path = if prefix_to_strip.is_some() {
+                    PathBuf::from(normalized.as_ref())
+                } else {
+                    rel_path_from_walk_root.to_path_buf()
+                };
+                let abs_path = abs_cwd.join(&rel_path);
+                let ignore_filter = self.ignore_filter.as_ref().unwrap();
 
-        // Strip Windows extended-length prefix (\\?\) to match glob v13 behavior
-        let abs_cwd = strip_windows_extended_prefix(
-            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
-        );
+                if ignore_filter.should_ignore(&normalized, &abs_path) {
+                    if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
+                        ignored_dirs.insert(normalized.into_owned());
+                    }
+                    continue;
+                }
 
-        for entry_result in entries {
-            let entry = match entry_result {
-                Ok(e) => e,
-                Err(_) => continue,
-            };
+                if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
+                    ignored_dirs.insert(normalized.to_string());
+                }
+            }
 
-            let file_name = match entry.file_name().into_string() {
-                Ok(n) => n,
-                Err(_) => continue,
-            };
+            // Handle root of walk_root
+            if is_walk_root_entry && prefix_to_strip.is_none() {
+                if include_cwd && !self.nodir {
+                    if let Some(ref ignore_filter) = self.ignore_filter {
+                        if ignore_filter.should_ignore(".", &abs_cwd) {
+                            continue;
+                        }
+                    }
 
-            // Filter dotfiles if dot option is false
-            if !self.dot && file_name.starts_with('.') {
+                    let result_path = ".".to_string();
+                    if seen.insert(&result_path) {
+                        results.push(PathData {
+                            path: result_path,
+                            is_directory: true,
+                            is_file: false,
+                            is_symlink: entry.is_symlink(),
+                            link_target: self.link_target(&entry),
+                            is_empty_dir: self.empty_dir_flag(&entry, true),
+                        });
+                    }
+                }
                 continue;
             }
 
-            // Get file type - use file_type() from DirEntry when possible
-            let file_type = match entry.file_type() {
-                Ok(ft) => ft,
-                Err(_) => continue,
-            };
-
-            let is_dir_raw = file_type.is_dir();
-            let is_symlink = file_type.is_symlink();
+            if normalized.is_empty() {
+                continue;
+            }
 
-            // If following symlinks and this is a symlink, get target type
-            // Note: entry.metadata() returns metadata for the symlink itself on macOS,
-            // not the target. Use fs::metadata() on the path to follow the symlink.
-            let is_dir = if is_symlink && self.follow {
-                match fs::metadata(entry.path()) {
-                    Ok(meta) => meta.is_dir(),
-                    Err(_) => false, // Broken symlink
-                }
-            } else {
-                is_dir_raw
-            };
+            // If nodir is true, skip directories
+            if self.nodir && entry.is_dir() {
+                continue;
+            }
 
-            // Skip directories if nodir is true
-            if self.nodir && is_dir {
+            // If dot:false, check if this path contains dotfile segments
+            if !self.dot
+                && (!self.path_allowed_by_dot_rules(&normalized)
+                    || self.hidden_by_attribute(&normalized))
+            {
                 continue;
             }
 
             // Check if any pattern matches
-            let matches = self.patterns.iter().any(|p| {
-                let path_matches = match p.matches_fast(&file_name) {
-                    Some(result) => result,
-                    None => p.matches(&file_name),
-                };
-                if path_matches && p.requires_dir() {
-                    is_dir
+            let is_dir = entry.is_dir();
+
+            let matches = if !self.any_pattern_requires_dir {
+                self.patterns
+                    .iter()
+                    .any(|p| match p.matches_fast(&normalized) {
+                        Some(result) => result,
+                        None => p.matches(&normalized),
+                    })
+            } else {
+                self.patterns.iter().any(|p| {
+                    let path_matches = match p.matches_fast(&normalized) {
+                        Some(result) => result,
+                        None => p.matches(&normalized),
+                    };
+                    if path_matches && p.requires_dir() {
+                        is_dir_or_dir_link(
+                            self.file_system(),
+                            is_dir,
+                            entry.is_symlink(),
+                            entry.path(),
+                        )
+                    } else {
+                        path_matches
+                    }
+                })
+            };
+
+            if matches && !self.sequentially_excluded(&normalized, is_dir) {
+                // For withFileTypes, we return the relative path (no dotRelative/mark modifications)
+                // The JavaScript wrapper handles path formatting via PathScurry
+                // Convert separators for output: use backslashes on Windows without posix
+                let output_path = if self.should_normalize_backslashes() {
+                    normalized.into_owned()
                 } else {
-                    path_matches
-                }
-            });
+                    normalized.replace('/', "\\")
+                };
+                if seen.insert(&output_path) {
+                    let path_data = PathData {
+                        path: output_path.clone(),
+                        is_directory: is_dir,
+                        is_file: entry.is_file(),
+                        is_symlink: entry.is_symlink(),
+                        link_target: self.link_target(&entry),
+                        is_empty_dir: self.empty_dir_flag(&entry, is_dir),
+                    };
 
-            if !matches {
-                continue;
+                    // When includeChildMatches is false, track for post-filtering
+                    if !self.include_child_matches {
+                        let norm_path = output_path.replace('\\', "/");
+                        matched_with_normalized.push((path_data.clone(), norm_path));
+                    }
+
+                    results.push(path_data);
+                }
             }
+        }
 
-            // Build result path
-            let result = if self.absolute {
-                let abs_path = abs_cwd.join(&file_name);
-                let formatted = self.format_path(&abs_path);
-                if self.mark && is_dir && !is_symlink && !formatted.ends_with('/') {
-                    format!("{formatted}/")
+        self.note_depth_limit(&walker);
+
+        // When includeChildMatches is false, post-process to filter out children
+        if !self.include_child_matches && !matched_with_normalized.is_empty() {
+            return filter_child_matches(matched_with_normalized);
+        }
+
+        results
+    }
+
+    /// Walk once and call `emit` with each result path and the indices of every
+    /// input pattern that matches it, sorted and deduplicated. Fast-path
+    /// reordering and deduplication of patterns are undone via `pattern_origins`.
+    /// Result paths are formatted the same way as `walk_sync`.
+    fn walk_sync_with_matching_inputs<F>(&self, mut emit: F)
+    where
+        F: FnMut(String, &[usize]),
+    {
+        let abs_cwd = strip_windows_extended_prefix(
+            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
+        );
+        let mut result_buffer = String::with_capacity(self.estimate_path_buffer_capacity());
+        let mut matched_inputs: Vec<usize> = Vec::with_capacity(self.input_count);
+
+        for entry in self.walk_sync_with_file_types() {
+            let is_cwd = entry.path == ".";
+            let normalized = entry.path.replace('\\', "/");
+
+            // Unlike the walk itself, every pattern is tried (no short-circuiting)
+            matched_inputs.clear();
+            for (pattern, origins) in self.patterns.iter().zip(self.pattern_origins.iter()) {
+                let matched = if is_cwd {
+                    pattern_matches_cwd(pattern)
                 } else {
-                    formatted
+                    self.pattern_allowed_by_dot_rules(pattern, &normalized)
+                        && Self::pattern_matches_candidate(pattern, &normalized, entry.is_directory)
+                            .0
+                };
+                if matched {
+                    matched_inputs.extend(origins);
                 }
+            }
+            matched_inputs.sort_unstable();
+            matched_inputs.dedup();
+
+            let result = if is_cwd {
+                self.build_cwd_result_path(&abs_cwd, &mut result_buffer)
             } else {
-                let sep = if self.should_normalize_backslashes() {
-                    '/'
-                } else {
-                    '\\'
-                };
-                let base = if self.dot_relative {
-                    format!(".{sep}{file_name}")
-                } else {
-                    file_name.clone()
-                };
-                if self.mark
-                    && is_dir
-                    && !is_symlink
-                    && !base.ends_with('/')
-                    && !base.ends_with('\\')
-                {
-                    format!("{base}{sep}")
-                } else {
-                    base
-                }
+                self.build_result_path(
+                    &normalized,
+                    entry.is_directory,
+                    entry.is_symlink,
+                    &abs_cwd,
+                    &mut result_buffer,
+                )
             };
+            emit(result, &matched_inputs);
+        }
+    }
 
-            if seen.insert(result.clone()) {
-                results.push(result);
-            }
+    /// Like `walk_sync_with_matching_inputs`, with `realpath` applied as in
+    /// `walk_sync`. Results resolving to the same path are reported once (unless
+    /// `unique` is off), with the inputs that matched any of them.
+    fn walk_sync_with_resolved_inputs<F>(&self, mut emit: F)
+    where
+        F: FnMut(String, &[usize]),
+    {
+        if !self.realpath {
+            return self.walk_sync_with_matching_inputs(emit);
         }
 
-        results
+        let abs_cwd = strip_windows_extended_prefix(
+            self.file_system()
+                .canonicalize(&self.cwd)
+                .unwrap_or_else(|_| self.cwd.clone()),
+        );
+        let mut resolved: Vec<(String, Vec<usize>)> = Vec::new();
+        let mut positions: AHashMap<String, usize> = AHashMap::new();
+        self.walk_sync_with_matching_inputs(|result, inputs| {
+            let Some(real) = self.resolve_realpath(result, &abs_cwd) else {
+                return;
+            };
+            if self.unique {
+                if let Some(&position) = positions.get(&real) {
+                    let merged = &mut resolved[position].1;
+                    merged.extend_from_slice(inputs);
+                    merged.sort_unstable();
+                    merged.dedup();
+                    return;
+                }
+                positions.insert(real.clone(), resolved.len());
+            }
+            resolved.push((real, inputs.to_vec()));
+        });
+        for (path, inputs) in resolved {
+            emit(path, &inputs);
+        }
     }
 
-    /// Resolve static patterns directly using stat() instead of walking.
+    /// Walk once and split the results by the input pattern that matched them.
     ///
-    /// This is a fast path for patterns like `package.json` or `src/index.ts`
-    /// that can be resolved to a single file path. Instead of walking the
-    /// directory tree and matching each file, we directly check if the file
-    /// exists.
+    /// Returns one group per input pattern, in input order (empty patterns get an
+    /// empty group). A result matching several patterns is placed in each of their
+    /// groups, or only in the first one when `first_match_only` is set.
+    pub fn walk_sync_grouped(&self, first_match_only: bool) -> Vec<Vec<String>> {
+        let mut groups: Vec<Vec<String>> = vec![Vec::new(); self.input_count];
+        self.walk_sync_with_resolved_inputs(|result, inputs| {
+            let inputs = if first_match_only {
+                &inputs[..inputs.len().min(1)]
+            } else {
+                inputs
+            };
+            for &index in inputs {
+                groups[index].push(result.clone());
+            }
+        });
+        groups
+    }
+
+    /// Walk once and report every input pattern that matches each result.
     ///
-    /// Returns a Vec of matching paths.
-    fn resolve_static_patterns(&self) -> Vec<String> {
-        use std::fs;
+    /// Results are the same as `walk_sync`; each comes with the indices of the
+    /// matching input patterns, in input order.
+    pub fn walk_sync_annotated(&self) -> Vec<AnnotatedPath> {
+        let mut results = Vec::with_capacity(self.estimate_result_capacity());
+        self.walk_sync_with_resolved_inputs(|path, inputs| {
+            results.push(AnnotatedPath {
+                path,
+                matched_patterns: inputs.iter().map(|&i| i as u32).collect(),
+            });
+        });
+        results
+    }
 
-        let mut results = Vec::with_capacity(self.patterns.len());
-        let mut seen: AHashSet<String> = AHashSet::with_capacity(self.patterns.len());
+    /// Walk the filesystem synchronously, numbering each result in the order
+    /// it was produced.
+    pub fn walk_sync_indexed(&self) -> Vec<IndexedPath> {
+        self.walk_sync()
+            .into_iter()
+            .enumerate()
+            .map(|(index, path)| IndexedPath {
+                path,
+                index: index as u32,
+            })
+            .collect()
+    }
 
-        for pattern in self.patterns.iter() {
-            if let Some(static_path) = pattern.static_path() {
-                // Construct the full path
-                let full_path = self.cwd.join(&static_path);
+    /// Walk the filesystem synchronously, pairing each result with its depth
+    /// below cwd.
+    ///
+    /// The depth is taken from the walked path before it is formatted, so the
+    /// output options never change it. With `realpath` it is the depth the match
+    /// was found at.
+    pub fn walk_sync_depths(&self) -> Vec<PathDepth> {
+        let abs_cwd = strip_windows_extended_prefix(
+            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
+        );
+        let mut result_buffer = String::with_capacity(self.estimate_path_buffer_capacity());
+        let mut seen = AHashSet::new();
+        let mut results = Vec::with_capacity(self.estimate_result_capacity());
+
+        for entry in self.walk_sync_with_file_types() {
+            let normalized = entry.path.replace('\\', "/");
+            let depth = walked_path_depth(&normalized, &abs_cwd);
+            let path = if entry.path == "." {
+                self.build_cwd_result_path(&abs_cwd, &mut result_buffer)
+            } else {
+                self.build_result_path(
+                    &normalized,
+                    entry.is_directory,
+                    entry.is_symlink,
+                    &abs_cwd,
+                    &mut result_buffer,
+                )
+            };
+            let path = if self.realpath {
+                match self.resolve_realpath(path, &abs_cwd) {
+                    Some(resolved) if !self.unique || seen.insert(resolved.clone()) => resolved,
+                    _ => continue,
+                }
+            } else {
+                path
+            };
+            results.push(PathDepth { path, depth });
+        }
+        results
+    }
 
-                // Check if the file exists
-                // When follow is true, first try metadata() which follows symlinks.
-                // If that fails (e.g., broken symlink), fall back to symlink_metadata().
-                // This matches glob's behavior of returning broken symlinks even with follow: true.
-                let metadata = if self.follow {
-                    fs::metadata(&full_path).or_else(|_| fs::symlink_metadata(&full_path))
+    /// Format a path according to options (posix, etc.)
+    ///
+    /// When posix: true on Windows, absolute paths are converted to UNC form
+    /// (e.g., `C:\foo\bar` → `//?/C:/foo/bar`) to match glob's behavior.
+    fn format_path(&self, path: &std::path::Path) -> String {
+        let path_str = path.to_string_lossy().to_string();
+        if self.posix_explicit_true {
+            // On Windows with posix: true, drive-letter paths take the //?/C:/ form
+            #[cfg(target_os = "windows")]
+            {
+                crate::util::windows_path_to_posix(&path_str)
+            }
+            // Standard POSIX conversion: backslashes to forward slashes
+            #[cfg(not(target_os = "windows"))]
+            {
+                if self.preserve_backslashes {
+                    path_str
                 } else {
-                    fs::symlink_metadata(&full_path)
-                };
+                    path_str.replace('\\', "/")
+                }
+            }
+        } else {
+            // Native separators: segments joined from patterns may still use `/`
+            #[cfg(target_os = "windows")]
+            {
+                path_str.replace('/', "\\")
+            }
+            #[cfg(not(target_os = "windows"))]
+            {
+                path_str
+            }
+        }
+    }
 
-                if let Ok(meta) = metadata {
-                    let is_dir = meta.is_dir();
-                    let is_symlink = meta.file_type().is_symlink();
+    /// Ensure a path ends with a trailing slash
+    fn ensure_trailing_slash(&self, path: &str) -> String {
+        if path.ends_with('/') || path.ends_with('\\') {
+            path.to_string()
+        } else if self.should_normalize_backslashes() {
+            format!("{path}/")
+        } else {
+            // On Windows without posix option, use the native separator
+            format!("{path}\\")
+        }
+    }
 
-                    // Check nodir option
-                    if self.nodir && is_dir {
-                        continue;
-                    }
+    /// `PathData.isSymlink` is reported for every entry, but when following links
+    /// walkdir reports the target's type. Ask the walker for the extra lstat so
+    /// followed links still come back as symlinks.
+    fn with_accurate_symlinks(&self, options: WalkOptions) -> WalkOptions {
+        let need = options.need_accurate_symlink_detection || self.follow;
+        options.need_accurate_symlink_detection(need)
+    }
 
-                    // Check if pattern requires directory (ends with /)
-                    if pattern.requires_dir() && !is_dir {
-                        continue;
-                    }
+    /// Read a symlink's target for `PathData` when `readLinkTargets` is set.
+    /// Only symlink entries pay for the `read_link` call.
+    #[inline]
+    fn link_target(&self, entry: &WalkEntry) -> Option<String> {
+        if !self.read_link_targets || !entry.is_symlink() {
+            return None;
+        }
+        self.file_system()
+            .read_link(entry.path())
+            .ok()
+            .map(|target| target.to_string_lossy().into_owned())
+    }
 
-                    // Apply ignore filter if present
-                    if let Some(ref filter) = self.ignore_filter {
-                        if filter.should_ignore(&static_path, &full_path) {
-                            continue;
-                        }
-                    }
+    /// Check whether a directory is empty for `PathData` when `detectEmptyDirs` is set.
+    /// Only directory entries pay for the extra `read_dir` call.
+    #[inline]
+    fn empty_dir_flag(&self, entry: &WalkEntry, is_dir: bool) -> Option<bool> {
+        if !self.detect_empty_dirs || !is_dir {
+            return None;
+        }
+        self.file_system().is_empty_dir(entry.path()).ok()
+    }
 
-                    // Check dot option
-                    if !self.dot {
-                        let has_hidden = static_path
-                            .split('/')
-                            .any(|seg| seg.starts_with('.') && seg != "." && seg != "..");
-                        if has_hidden && !pattern.allows_dotfile(&static_path) {
-                            continue;
-                        }
-                    }
+    /// Check that a path resolves inside cwd when `restrictToCwd` is set.
+    #[inline]
+    fn resolves_in_sandbox(&self, path: &Path) -> bool {
+        self.sandbox_root
+            .as_ref()
+            .is_none_or(|root| resolves_within_on(self.file_system(), path, root))
+    }
 
-                    // Strip trailing slash from static path (glob returns paths without trailing slash unless mark: true)
-                    let base_path = static_path.trim_end_matches('/');
+    /// Check if a path is allowed by dot filtering rules.
+    /// Returns true if:
+    /// - dot: true (always allow)
+    /// - `hidden: AttributeOnly` (dotfiles aren't hidden)
+    /// - The path has no dotfile segments
+    /// - Any pattern explicitly allows the dotfile segments in this path
+    fn path_allowed_by_dot_rules(&self, path: &str) -> bool {
+        if self.hidden == HiddenMode::AttributeOnly {
+            return true;
+        }
 
-                    // Format the result path
-                    let result = if self.absolute {
-                        // Strip Windows extended-length prefix (\\?\) to match glob v13 behavior
-                        let abs_path = strip_windows_extended_prefix(
-                            full_path.canonicalize().unwrap_or(full_path.clone()),
-                        );
-                        let formatted = self.format_path(&abs_path);
-                        if self.mark && is_dir && !is_symlink && !formatted.ends_with('/') {
-                            format!("{formatted}/")
-                        } else {
-                            formatted
-                        }
-                    } else {
-                        let use_forward = self.should_normalize_backslashes();
-                        let sep = if use_forward { '/' } else { '\\' };
-                        // Convert separators for output (static_path uses forward slashes internally)
-                        let output_base = if use_forward {
-                            base_path.to_string()
-                        } else {
-                            base_path.replace('/', "\\")
-                        };
-                        let base = if self.dot_relative
-                            && !output_base.starts_with("../")
-                            && !output_base.starts_with("..\\")
-                        {
-                            format!(".{sep}{output_base}")
-                        } else {
-                            output_base
-                        };
-                        if self.mark
-                            && is_dir
-                            && !is_symlink
-                            && !base.ends_with('/')
-                            && !base.ends_with('\\')
-                        {
-                            format!("{base}{sep}")
-                        } else {
-                            base
-                        }
-                    };
+        // Check if path contains any dotfile segments
+        let has_dotfile = path
+            .split(self.separator)
+            .any(|segment| segment.starts_with('.') && segment != "." && segment != "..");
 
-                    // Deduplicate (in case of brace expansion producing duplicates)
-                    if seen.insert(result.clone()) {
-                        results.push(result);
-                    }
-                }
-            }
+        if !has_dotfile {
+            return true;
         }
 
-        results
+        // Check if any pattern explicitly allows the dotfiles in this path
+        self.patterns.iter().any(|p| p.allows_dotfile(path))
     }
 
-    /// Walk the directory tree and stream results via callback.
-    /// This reduces peak memory usage by not collecting all results into a Vec.
-    pub fn walk_stream<F>(&self, mut callback: F)
-    where
-        F: FnMut(String),
-    {
-        // If maxDepth is negative, return empty results
-        if let Some(d) = self.max_depth {
-            if d < 0 {
-                return;
-            }
+    /// Check if a cwd-relative entry has the hidden attribute and the `hidden` mode
+    /// honors it. Ancestors aren't checked: hidden directories are pruned instead.
+    #[inline]
+    fn hidden_by_attribute(&self, path: &str) -> bool {
+        self.hidden != HiddenMode::DotfilesOnly
+            && self.file_system().is_hidden(&self.cwd.join(path))
+    }
+
+    /// Check a candidate path against the patterns without touching the filesystem.
+    ///
+    /// The path is interpreted relative to cwd (or as absolute) using the same rules
+    /// as walk results: a leading `./` is ignored, dot rules and ignore patterns
+    /// apply, and a trailing `/` marks the candidate as a directory for patterns
+    /// ending in `/`. With `normalize_candidate`, `.` and `..` segments are
+    /// resolved lexically first.
+    pub fn matches_path(&self, path: &str, normalize_candidate: bool) -> bool {
+        let Some((candidate, is_dir)) = self.prepare_candidate(path, normalize_candidate) else {
+            return false;
+        };
+
+        if !self.dot && !self.path_allowed_by_dot_rules(&candidate) {
+            return false;
         }
 
-        // Use AHashSet for deduplication (can't eliminate this for correctness)
-        let mut seen: AHashSet<String> = AHashSet::with_capacity(self.estimate_result_capacity());
-        let mut ignored_dirs: AHashSet<String> = AHashSet::with_capacity(8);
+        if self.candidate_ignored(&candidate) {
+            return false;
+        }
 
-        // When includeChildMatches is false, track matched paths to exclude their children
-        let mut matched_parents: AHashSet<String> = if self.include_child_matches {
-            AHashSet::new()
-        } else {
-            AHashSet::with_capacity(64)
+        self.patterns
+            .iter()
+            .any(|p| Self::pattern_matches_candidate(p, &candidate, is_dir).0)
+    }
+
+    /// Normalize a candidate for `matches_path`/`explain_match`, returning the path
+    /// to test and whether it was marked as a directory, or `None` if it's empty.
+    fn prepare_candidate(&self, path: &str, normalize_candidate: bool) -> Option<(String, bool)> {
+        // Keys split on a custom separator aren't paths: `\` and `/` are kept
+        let separator = self.separator;
+        let is_path = separator == '/';
+        // Like walked entries, `\` in a candidate is a separator unless preserved
+        let path: Cow<'_, str> =
+            if is_path && (self.windows_paths_no_escape || !self.preserve_backslashes) {
+                Cow::Owned(path.replace('\\', "/"))
+            } else {
+                Cow::Borrowed(path)
+            };
+        // Repeated slashes collapse, as they do in patterns
+        let path = match collapse_slashes(&path) {
+            Cow::Owned(collapsed) if is_path => Cow::Owned(collapsed),
+            _ => path,
+        };
+        // Windows names can't end in `.` or ` `: `foo.` is the file `foo`
+        #[cfg(target_os = "windows")]
+        let path = match strip_trailing_dots_and_spaces(&path) {
+            Cow::Owned(stripped) if is_path => Cow::Owned(stripped),
+            _ => path,
         };
+        let is_dir = path.len() > 1 && path.ends_with(separator);
 
-        // Pre-allocate a reusable buffer for path formatting
-        let mut result_buffer = String::with_capacity(self.estimate_path_buffer_capacity());
+        let candidate = if normalize_candidate {
+            normalize_lexically_with(&path, separator)
+        } else {
+            let trimmed = match path.strip_prefix("./") {
+                Some(rest) if is_path => rest,
+                _ => &path,
+            };
+            let trimmed = if trimmed.len() > 1 {
+                trimmed.trim_end_matches(separator)
+            } else {
+                trimmed
+            };
+            trimmed.to_string()
+        };
 
-        // Check if any pattern matches the cwd itself
-        let include_cwd = self.patterns.iter().any(|p| {
-            let raw = p.raw();
-            raw == "**" || raw == "." || raw == "./**" || {
-                let preprocessed = preprocess_pattern(raw);
-                preprocessed == "**" || preprocessed == "."
-            }
-        });
+        if candidate.is_empty() {
+            return None;
+        }
+        Some((candidate, is_dir))
+    }
 
-        // Strip Windows extended-length prefix (\\?\) to match glob v13 behavior
-        let abs_cwd = strip_windows_extended_prefix(
-            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
-        );
-        let (walk_root, prefix_to_strip) = self.calculate_walk_root();
+    /// Check whether the ignore filter excludes a candidate path.
+    fn candidate_ignored(&self, candidate: &str) -> bool {
+        self.ignore_filter.as_ref().is_some_and(|filter| {
+            let abs_path = self.cwd.join(candidate);
+            filter.should_ignore(candidate, &abs_path)
+        })
+    }
 
-        // Pre-compute the prefix with trailing slash for efficient path concatenation
-        let prefix_with_slash: Option<String> =
-            prefix_to_strip.as_ref().map(|prefix| format!("{prefix}/"));
+    /// Whether the `sequential` rules drop a path that an include pattern matched:
+    /// the last rule matching the path decides, and `!` rules exclude it. A rule
+    /// whose dot rules reject the path doesn't match it.
+    #[inline]
+    fn sequentially_excluded(&self, normalized: &str, is_dir: bool) -> bool {
+        self.sequential_rules.as_ref().is_some_and(|rules| {
+            rules
+                .iter()
+                .rev()
+                .find(|(pattern, _)| {
+                    self.pattern_allowed_by_dot_rules(pattern, normalized)
+                        && Self::pattern_matches_candidate(pattern, normalized, is_dir).0
+                })
+                .is_some_and(|&(_, exclude)| exclude)
+        })
+    }
 
-        // Adjust walk options for prefix-based walking
-        let adjusted_walk_options = if let Some(ref prefix) = prefix_to_strip {
-            let prefix_depth = prefix.split('/').filter(|s| !s.is_empty()).count();
-            if let Some(max_d) = self.walk_options.max_depth {
-                if max_d <= prefix_depth {
-                    self.walk_options.clone().max_depth(Some(0))
-                } else {
-                    self.walk_options
-                        .clone()
-                        .max_depth(Some(max_d - prefix_depth))
-                }
-            } else {
-                self.walk_options.clone()
-            }
-        } else {
-            self.walk_options.clone()
+    /// Whether the dot rules let one pattern claim a path: the per-pattern form of
+    /// `path_allowed_by_dot_rules`, which only asks whether some pattern does.
+    /// Patterns with their own `dot` setting enforce it while matching.
+    fn pattern_allowed_by_dot_rules(&self, pattern: &Pattern, path: &str) -> bool {
+        self.dot
+            || pattern.dot().is_some()
+            || self.hidden == HiddenMode::AttributeOnly
+            || !path
+                .split(self.separator)
+                .any(|segment| segment.starts_with('.') && segment != "." && segment != "..")
+            || pattern.allows_dotfile(path)
+    }
+
+    /// Check a single pattern against a prepared candidate, returning whether it
+    /// matched and whether its fast path decided the result.
+    fn pattern_matches_candidate(pattern: &Pattern, candidate: &str, is_dir: bool) -> (bool, bool) {
+        let (path_matches, used_fast_path) = match pattern.matches_fast(candidate) {
+            Some(result) => (result, true),
+            None => (pattern.matches(candidate), false),
         };
+        (
+            path_matches && (is_dir || !pattern.requires_dir()),
+            used_fast_path,
+        )
+    }
+
+    /// Check whether a directory could contain matches, using the same pruning
+    /// logic as the walker. The cwd itself is given as `.` or an empty path.
+    pub fn can_contain_match(&self, dir_path: &str, normalize_candidate: bool) -> bool {
+        let dir = match self.prepare_candidate(dir_path, normalize_candidate) {
+            Some((candidate, _)) if candidate != "." => candidate,
+            _ => String::new(),
+        };
+        self.dir_prune_filter(Arc::clone(&self.patterns), None, None, &self.cwd)
+            .is_none_or(|prune_filter| prune_filter(&dir))
+    }
 
-        // Create directory pruning filter
-        let patterns_for_filter = Arc::clone(&self.patterns);
-        let prefix_for_filter = prefix_to_strip.clone();
-        let prefix_slash_for_filter = prefix_with_slash.clone();
-
-        let prune_filter = Box::new(move |dir_path: &str| -> bool {
-            let path_from_cwd: Cow<'_, str> = if let Some(ref prefix) = prefix_for_filter {
-                if dir_path.is_empty() {
-                    Cow::Borrowed(prefix.as_str())
-                } else if let Some(ref prefix_slash) = prefix_slash_for_filter {
-                    Cow::Owned(format!("{prefix_slash}{dir_path}"))
+    /// Explain how `matches_path` reaches its answer for a candidate path.
+    ///
+    /// Every pattern is tried (no short-circuiting) so the report shows which
+    /// ones match, whether a fast path or the regex decided, and whether dot
+    /// rules or ignore patterns override the match.
+    pub fn explain_match(&self, path: &str, normalize_candidate: bool) -> MatchExplanation {
+        let (candidate, is_dir) = self
+            .prepare_candidate(path, normalize_candidate)
+            .unwrap_or_default();
+
+        let patterns: Vec<PatternExplanation> = self
+            .patterns
+            .iter()
+            .map(|p| {
+                let (matched, used_fast_path) = if candidate.is_empty() {
+                    (false, false)
                 } else {
-                    Cow::Owned(format!("{prefix}/{dir_path}"))
+                    Self::pattern_matches_candidate(p, &candidate, is_dir)
+                };
+                PatternExplanation {
+                    pattern: p.raw().to_string(),
+                    matched,
+                    fast_path: used_fast_path
+                        .then(|| p.fast_path().name())
+                        .flatten()
+                        .map(str::to_string),
+                    regex: p.regex_source().to_string(),
+                    requires_directory: p.requires_dir(),
                 }
-            } else {
-                Cow::Borrowed(dir_path)
-            };
-
-            patterns_for_filter
-                .iter()
-                .any(|p| p.could_match_in_dir(&path_from_cwd))
-        });
-
-        let walker = Walker::new(walk_root.clone(), adjusted_walk_options)
-            .with_dir_prune_filter(prune_filter);
-
-        let has_ignore_filter = self.ignore_filter.is_some();
-
-        for entry in walker.walk() {
-            let path = entry.path();
+            })
+            .collect();
 
-            let rel_path_from_walk_root = match path.strip_prefix(&walk_root) {
-                Ok(p) => p,
-                Err(_) => continue,
-            };
-            let rel_str_from_walk_root = rel_path_from_walk_root.to_string_lossy();
-            let is_walk_root_entry = rel_str_from_walk_root.is_empty();
+        let excluded_by_dot =
+            !candidate.is_empty() && !self.dot && !self.path_allowed_by_dot_rules(&candidate);
+        let ignored = !candidate.is_empty() && self.candidate_ignored(&candidate);
+        let first_match = patterns.iter().find(|p| p.matched);
+
+        let reason = if candidate.is_empty() {
+            "the candidate path is empty".to_string()
+        } else if excluded_by_dot {
+            format!(
+                "'{candidate}' has a dot segment that no pattern explicitly matches (set dot: true to include it)"
+            )
+        } else if ignored {
+            format!("'{candidate}' is excluded by an ignore pattern")
+        } else if let Some(p) = first_match {
+            format!("'{candidate}' matches '{}'", p.pattern)
+        } else if !is_dir
+            && patterns.iter().zip(self.patterns.iter()).any(|(e, p)| {
+                e.requires_directory && Self::pattern_matches_candidate(p, &candidate, true).0
+            })
+        {
+            format!("'{candidate}' matches only patterns ending in '/', which require a directory (add a trailing '/')")
+        } else {
+            format!("'{candidate}' does not match any pattern")
+        };
 
-            let normalized = self.normalize_path(
-                &rel_str_from_walk_root,
-                &prefix_to_strip,
-                is_walk_root_entry,
-            );
+        MatchExplanation {
+            matched: first_match.is_some() && !excluded_by_dot && !ignored,
+            candidate,
+            is_directory: is_dir,
+            excluded_by_dot,
+            ignored,
+            patterns,
+            reason,
+        }
+    }
 
-            if self.is_in_ignored_dir(&normalized, &ignored_dirs) {
-                continue;
-            }
+    /// Estimate the capacity for the result vector based on pattern characteristics.
+    ///
+    /// This helps reduce reallocations during result collection. The estimate is
+    /// based on pattern depth and whether the pattern is recursive:
+    /// - Simple root patterns (*.txt): ~16 results expected
+    /// - One-level patterns (src/*.js): ~64 results expected  
+    /// - Recursive patterns (**/*.js): ~256 results expected
+    fn estimate_result_capacity(&self) -> usize {
+        // Find the maximum depth across all patterns
+        let max_pattern_depth = self.patterns.iter().filter_map(|p| p.max_depth()).max();
 
-            if has_ignore_filter {
-                let rel_path = if prefix_to_strip.is_some() {
-                    PathBuf::from(normalized.as_ref())
-                } else {
-                    rel_path_from_walk_root.to_path_buf()
-                };
-                let abs_path = abs_cwd.join(&rel_path);
-                let ignore_filter = self.ignore_filter.as_ref().unwrap();
+        match max_pattern_depth {
+            Some(0) => 16,  // Root-level patterns: few files expected
+            Some(1) => 64,  // One directory level: moderate number
+            Some(2) => 128, // Two levels deep
+            Some(_) => 256, // Deeper patterns
+            None => 256,    // Recursive patterns (**): could be many files
+        }
+    }
 
-                if ignore_filter.should_ignore(&normalized, &abs_path) {
-                    if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
-                        ignored_dirs.insert(normalized.into_owned());
-                    }
-                    continue;
-                }
+    /// Estimate string buffer capacity based on pattern characteristics.
+    /// Used to pre-allocate string buffers for path construction.
+    #[inline]
+    fn estimate_path_buffer_capacity(&self) -> usize {
+        // Average path length: ~40-60 characters for typical project structures
+        // Add extra for absolute paths and prefix
+        if self.absolute {
+            128 // Absolute paths can be longer
+        } else if self.dot_relative {
+            64 // Relative with ./ prefix
+        } else {
+            48 // Simple relative paths
+        }
+    }
 
-                if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
-                    ignored_dirs.insert(normalized.to_string());
-                }
-            }
+    /// Format a path into the provided buffer, returning a reference to the result.
+    /// This avoids allocations by reusing the buffer across iterations.
+    ///
+    /// When posix: true on Windows, absolute paths are converted to UNC form
+    /// (e.g., `C:\foo\bar` → `//?/C:/foo/bar`) to match glob's behavior.
+    #[inline]
+    fn format_path_into_buffer<'a>(&self, path: &Path, buffer: &'a mut String) -> &'a str {
+        buffer.clear();
+        let path_str = path.to_string_lossy();
 
-            // Handle root
-            if is_walk_root_entry && prefix_to_strip.is_none() {
-                if include_cwd && !self.nodir {
-                    if let Some(ref ignore_filter) = self.ignore_filter {
-                        if ignore_filter.should_ignore(".", &abs_cwd) {
-                            continue;
-                        }
-                    }
+        if self.posix_explicit_true {
+            // On Windows with posix: true, drive-letter paths take the //?/C:/ form
+            // e.g., C:\foo\bar → //?/C:/foo/bar, while \\server\share → //server/share
+            #[cfg(target_os = "windows")]
+            buffer.push_str(&crate::util::windows_path_to_posix(&path_str));
 
-                    let result = if self.absolute {
-                        let formatted = self.format_path_into_buffer(&abs_cwd, &mut result_buffer);
-                        if self.mark {
-                            if formatted.ends_with('/') || formatted.ends_with('\\') {
-                                formatted.to_string()
-                            } else {
-                                format!("{formatted}/")
-                            }
-                        } else {
-                            formatted.to_string()
-                        }
-                    } else if self.mark {
-                        "./".to_string()
-                    } else {
-                        ".".to_string()
-                    };
-                    if seen.insert(result.clone()) {
-                        callback(result);
-                    }
+            // Standard POSIX conversion: backslashes to forward slashes
+            #[cfg(not(target_os = "windows"))]
+            if self.preserve_backslashes {
+                buffer.push_str(&path_str);
+            } else {
+                for c in path_str.chars() {
+                    buffer.push(if c == '\\' { '/' } else { c });
                 }
-                continue;
-            }
-
-            if normalized.is_empty() {
-                continue;
-            }
-
-            if self.nodir && entry.is_dir() {
-                continue;
             }
+        } else {
+            // Native separators: segments joined from patterns may still use `/`
+            #[cfg(target_os = "windows")]
+            buffer.extend(path_str.chars().map(|c| if c == '/' { '\\' } else { c }));
 
-            if !self.dot && !self.path_allowed_by_dot_rules(&normalized) {
-                continue;
-            }
+            #[cfg(not(target_os = "windows"))]
+            buffer.push_str(&path_str);
+        }
+        buffer.as_str()
+    }
 
-            if !self.include_child_matches
-                && self.is_child_of_matched(&normalized, &matched_parents)
-            {
-                continue;
-            }
+    /// Build a normalized path from walk entry, minimizing allocations.
+    /// Returns Cow::Borrowed when no transformation is needed, Cow::Owned otherwise.
+    ///
+    /// IMPORTANT: This function ALWAYS normalizes to forward slashes because it's used
+    /// for internal pattern matching. Pattern matching (`matches()`, `could_match_in_dir()`)
+    /// always expects forward slashes regardless of platform.
+    ///
+    /// Output formatting (backslashes on Windows without posix) is handled separately
+    /// in `build_result_path`.
+    #[inline]
+    fn normalize_path<'a>(
+        &self,
+        rel_str_from_walk_root: &'a str,
+        prefix_to_strip: &Option<String>,
+        is_walk_root: bool,
+    ) -> Cow<'a, str> {
+        let preserve_backslashes = self.preserve_backslashes;
+        let has_backslash = !preserve_backslashes && rel_str_from_walk_root.contains('\\');
 
-            let is_dir = entry.is_dir();
-            let is_symlink = entry.is_symlink();
+        // Fast path: no prefix and no backslashes to convert
+        if prefix_to_strip.is_none() && !has_backslash {
+            return Cow::Borrowed(rel_str_from_walk_root);
+        }
 
-            let matches = if !self.any_pattern_requires_dir {
-                self.patterns
-                    .iter()
-                    .any(|p| match p.matches_fast(&normalized) {
-                        Some(result) => result,
-                        None => p.matches(&normalized),
-                    })
+        // Helper to convert backslashes to forward slashes
+        let convert_to_forward = |path: &str| -> String {
+            if !preserve_backslashes && path.contains('\\') {
+                path.replace('\\', "/")
             } else {
-                self.patterns.iter().any(|p| {
-                    let path_matches = match p.matches_fast(&normalized) {
-                        Some(result) => result,
-                        None => p.matches(&normalized),
-                    };
-                    if path_matches && p.requires_dir() {
-                        is_dir
-                    } else {
-                        path_matches
-                    }
-                })
-            };
-
-            if matches {
-                let result = self.build_result_path(
-                    &normalized,
-                    is_dir,
-                    is_symlink,
-                    &abs_cwd,
-                    &mut result_buffer,
-                );
+                path.to_string()
+            }
+        };
 
-                if seen.insert(result.clone()) {
-                    if !self.include_child_matches {
-                        matched_parents.insert(normalized.into_owned());
-                    }
-                    callback(result);
+        // Need to construct the path with forward slashes
+        match prefix_to_strip {
+            Some(prefix) => {
+                let prefix_converted = convert_to_forward(prefix);
+                if is_walk_root {
+                    Cow::Owned(prefix_converted)
+                } else {
+                    let rel_converted = convert_to_forward(rel_str_from_walk_root);
+                    Cow::Owned(format!(
+                        "{}{rel_converted}",
+                        prefix_with_separator(&prefix_converted)
+                    ))
                 }
             }
+            None => {
+                // Just convert backslashes to forward slashes
+                Cow::Owned(convert_to_forward(rel_str_from_walk_root))
+            }
         }
     }
 
-    /// Walk the directory tree and stream PathData results via callback.
-    /// This reduces peak memory usage by not collecting all results into a Vec.
-    pub fn walk_stream_with_file_types<F>(&self, mut callback: F)
-    where
-        F: FnMut(PathData),
-    {
-        // If maxDepth is negative, return empty results
-        if let Some(d) = self.max_depth {
-            if d < 0 {
-                return;
-            }
-        }
+    /// Build a normalized path using a reusable buffer to minimize allocations.
+    /// This is the optimized hot path for scoped patterns where prefix concatenation
+    /// is needed for every file.
+    ///
+    /// # Arguments
+    /// * `rel_str_from_walk_root` - The path relative to the walk root
+    /// * `prefix_to_strip` - The original prefix (without trailing slash)
+    /// * `prefix_with_slash` - Pre-computed "prefix/" or "prefix\\" for fast concatenation
+    /// * `is_walk_root` - True if this is the walk root entry itself
+    /// * `use_forward_slashes` - Whether to use forward slashes (true) or backslashes (false)
+    /// * `buffer` - Reusable string buffer
+    #[inline]
+    fn normalize_path_buffered<'a>(
+        rel_str_from_walk_root: &str,
+        prefix_to_strip: &Option<String>,
+        prefix_with_slash: &Option<String>,
+        is_walk_root: bool,
+        use_forward_slashes: bool,
+        buffer: &'a mut String,
+    ) -> &'a str {
+        let has_backslash = rel_str_from_walk_root.contains('\\');
+        let has_forward_slash = rel_str_from_walk_root.contains('/');
+        let needs_to_forward = use_forward_slashes && has_backslash;
+        let needs_to_backslash = !use_forward_slashes && has_forward_slash;
+        let needs_conversion = needs_to_forward || needs_to_backslash;
 
-        let mut seen: AHashSet<String> = AHashSet::with_capacity(self.estimate_result_capacity());
-        let mut ignored_dirs: AHashSet<String> = AHashSet::with_capacity(8);
-        let mut matched_parents: AHashSet<String> = if self.include_child_matches {
-            AHashSet::new()
-        } else {
-            AHashSet::with_capacity(64)
+        // Helper closure to push a character with conversion
+        let convert_char = |c: char| -> char {
+            if needs_to_forward && c == '\\' {
+                '/'
+            } else if needs_to_backslash && c == '/' {
+                '\\'
+            } else {
+                c
+            }
         };
 
-        let include_cwd = self.patterns.iter().any(|p| {
-            let raw = p.raw();
-            raw == "**" || raw == "." || raw == "./**" || {
-                let preprocessed = preprocess_pattern(raw);
-                preprocessed == "**" || preprocessed == "."
+        // Fast path: no prefix and no conversion needed
+        if prefix_to_strip.is_none() {
+            buffer.clear();
+            if needs_conversion {
+                for c in rel_str_from_walk_root.chars() {
+                    buffer.push(convert_char(c));
+                }
+            } else {
+                buffer.push_str(rel_str_from_walk_root);
             }
-        });
+            return buffer.as_str();
+        }
 
-        // Strip Windows extended-length prefix (\\?\) to match glob v13 behavior
-        let abs_cwd = strip_windows_extended_prefix(
-            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
-        );
-        let (walk_root, prefix_to_strip) = self.calculate_walk_root();
+        // Clear and reuse buffer
+        buffer.clear();
 
-        let adjusted_walk_options = if let Some(ref prefix) = prefix_to_strip {
-            let prefix_depth = prefix.split('/').filter(|s| !s.is_empty()).count();
-            if let Some(max_d) = self.walk_options.max_depth {
-                if max_d <= prefix_depth {
-                    self.walk_options.clone().max_depth(Some(0))
-                } else {
-                    self.walk_options
-                        .clone()
-                        .max_depth(Some(max_d - prefix_depth))
+        let prefix = prefix_to_strip.as_ref().unwrap();
+
+        if is_walk_root {
+            // Convert prefix if needed
+            if needs_conversion {
+                for c in prefix.chars() {
+                    buffer.push(convert_char(c));
                 }
             } else {
-                self.walk_options.clone()
+                buffer.push_str(prefix);
             }
         } else {
-            self.walk_options.clone()
-        };
-
-        let patterns_for_filter = Arc::clone(&self.patterns);
-        let prefix_for_filter = prefix_to_strip.clone();
-
-        let prune_filter = Box::new(move |dir_path: &str| -> bool {
-            let path_from_cwd: Cow<'_, str> = if let Some(ref prefix) = prefix_for_filter {
-                if dir_path.is_empty() {
-                    Cow::Borrowed(prefix.as_str())
+            // Use pre-computed prefix with slash for efficiency
+            if let Some(ref prefix_slash) = prefix_with_slash {
+                if needs_conversion {
+                    for c in prefix_slash.chars() {
+                        buffer.push(convert_char(c));
+                    }
                 } else {
-                    Cow::Owned(format!("{prefix}/{dir_path}"))
+                    buffer.push_str(prefix_slash);
                 }
             } else {
-                Cow::Borrowed(dir_path)
-            };
-
-            patterns_for_filter
-                .iter()
-                .any(|p| p.could_match_in_dir(&path_from_cwd))
-        });
+                if needs_conversion {
+                    for c in prefix.chars() {
+                        buffer.push(convert_char(c));
+                    }
+                } else {
+                    buffer.push_str(prefix);
+                }
+                if !prefix.ends_with('/') {
+                    buffer.push(if use_forward_slashes { '/' } else { '\\' });
+                }
+            }
 
-        let walker = Walker::new(walk_root.clone(), adjusted_walk_options)
-            .with_dir_prune_filter(prune_filter);
+            if needs_conversion {
+                for c in rel_str_from_walk_root.chars() {
+                    buffer.push(convert_char(c));
+                }
+            } else {
+                buffer.push_str(rel_str_from_walk_root);
+            }
+        }
+        buffer.as_str()
+    }
 
-        let has_ignore_filter = self.ignore_filter.is_some();
+    /// Build the final result path from the normalized path.
+    /// Uses the provided buffer to minimize allocations.
+    ///
+    /// The `normalized` path always uses forward slashes (for internal pattern matching).
+    /// This function converts to backslashes for output on Windows when `posix: false`.
+    #[inline]
+    fn build_result_path(
+        &self,
+        normalized: &str,
+        is_dir: bool,
+        is_symlink: bool,
+        abs_cwd: &Path,
+        result_buffer: &mut String,
+    ) -> String {
+        // When mark:true, add trailing slash to directories but NOT to symlinks
+        let should_mark_as_dir = is_dir && !is_symlink && self.mark;
+        let use_forward = self.should_normalize_backslashes();
+        let sep = if use_forward { '/' } else { '\\' };
+        let dot_prefix = if use_forward { "./" } else { ".\\" };
 
-        for entry in walker.walk() {
-            let path = entry.path();
+        let result = if self.absolute {
+            // Build absolute path
+            result_buffer.clear();
+            let abs_path = abs_cwd.join(normalized);
+            let formatted = self.format_path_into_buffer(&abs_path, result_buffer);
 
-            let rel_path_from_walk_root = match path.strip_prefix(&walk_root) {
-                Ok(p) => p,
-                Err(_) => continue,
+            if should_mark_as_dir && !formatted.ends_with('/') && !formatted.ends_with('\\') {
+                let mut result = formatted.to_string();
+                result.push(sep);
+                result
+            } else {
+                formatted.to_string()
+            }
+        } else {
+            // Build relative path
+            // First, convert separators if needed (normalized always uses forward slashes)
+            let output_normalized = if use_forward {
+                normalized.to_string()
+            } else {
+                normalized.replace('/', "\\")
             };
-            let rel_str_from_walk_root = rel_path_from_walk_root.to_string_lossy();
-            let is_walk_root_entry = rel_str_from_walk_root.is_empty();
 
-            let normalized = self.normalize_path(
-                &rel_str_from_walk_root,
-                &prefix_to_strip,
-                is_walk_root_entry,
-            );
+            let base = if self.dot_relative
+                && !output_normalized.starts_with("../")
+                && !output_normalized.starts_with("..\\")
+            {
+                result_buffer.clear();
+                result_buffer.push_str(dot_prefix);
+                result_buffer.push_str(&output_normalized);
+                result_buffer.clone()
+            } else {
+                output_normalized
+            };
 
-            if self.is_in_ignored_dir(&normalized, &ignored_dirs) {
-                continue;
+            if should_mark_as_dir && !base.ends_with('/') && !base.ends_with('\\') {
+                let mut result = base;
+                result.push(sep);
+                result
+            } else {
+                base
             }
+        };
+        self.apply_result_prefix(result)
+    }
 
-            if has_ignore_filter {
-                let rel_path = if prefix_to_strip.is_some() {
-                    PathBuf::from(normalized.as_ref())
+    /// Build the result path for the cwd itself, matched by patterns like `**` or `.`.
+    fn build_cwd_result_path(&self, abs_cwd: &Path, result_buffer: &mut String) -> String {
+        let result = if self.absolute {
+            let formatted = self.format_path_into_buffer(abs_cwd, result_buffer);
+            if self.mark {
+                if formatted.ends_with('/') || formatted.ends_with('\\') {
+                    formatted.to_string()
                 } else {
-                    rel_path_from_walk_root.to_path_buf()
-                };
-                let abs_path = abs_cwd.join(&rel_path);
-                let ignore_filter = self.ignore_filter.as_ref().unwrap();
-
-                if ignore_filter.should_ignore(&normalized, &abs_path) {
-                    if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
-                        ignored_dirs.insert(normalized.into_owned());
-                    }
-                    continue;
-                }
-
-                if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
-                    ignored_dirs.insert(normalized.to_string());
+                    let mut s = formatted.to_string();
+                    s.push('/');
+                    s
                 }
+            } else {
+                formatted.to_string()
+            }
+        } else {
+            // For relative paths, "." becomes "./" with mark:true
+            if self.mark {
+                "./".to_string()
+            } else {
+                ".".to_string()
             }
+        };
+        self.apply_result_prefix(result)
+    }
 
-            if is_walk_root_entry && prefix_to_strip.is_none() {
-                if include_cwd && !self.nodir {
-                    if let Some(ref ignore_filter) = self.ignore_filter {
-                        if ignore_filter.should_ignore(".", &abs_cwd) {
-                            continue;
-                        }
-                    }
+    /// Remember that `walker` stopped at the hard depth limit, for `check_walk`.
+    fn note_depth_limit(&self, walker: &Walker) {
+        if walker.reached_depth_limit() {
+            self.depth_limit_hit.0.store(true, Ordering::Relaxed);
+        }
+    }
 
-                    let result_path = ".".to_string();
-                    if seen.insert(result_path.clone()) {
-                        callback(PathData {
-                            path: result_path,
-                            is_directory: true,
-                            is_file: false,
-                            is_symlink: entry.is_symlink(),
-                        });
-                    }
-                }
-                continue;
-            }
+    /// Pass `results` through, or fail if the walk that produced them couldn't
+    /// read a directory it starts from, or was cut short by the hard depth limit
+    /// (`hardDepthLimit`).
+    ///
+    /// Without the first an unreadable cwd or pattern root looks like a walk that
+    /// found nothing. Missing roots are not errors: they simply have no matches,
+    /// and errors deeper in the tree are still skipped, as in glob. The walkers
+    /// record the error on their first read of each root (see `walk_roots`), so
+    /// checking costs no extra reads.
+    ///
+    /// A tree reaching the depth limit is almost certainly pathological (or a
+    /// symlink loop being followed), and the results are incomplete.
+    /// `suppressErrors` returns the results anyway in both cases.
+    fn check_walk<T>(&self, results: T) -> Result<T> {
+        let depth_limit_hit = self.depth_limit_hit.0.swap(false, Ordering::Relaxed);
+        if let Some((root, e)) = self.walk_options.take_unreadable() {
+            return Err(Error::from_reason(format!(
+                "Cannot read directory '{}': {e}",
+                root.display()
+            )));
+        }
+        if depth_limit_hit && !self.suppress_errors {
+            return Err(Error::from_reason(format!(
+                "Walk stopped at the hard depth limit of {} levels; results are incomplete",
+                self.walk_options
+                    .hard_depth_limit
+                    .unwrap_or(DEFAULT_HARD_DEPTH_LIMIT)
+            )));
+        }
+        Ok(results)
+    }
 
-            if normalized.is_empty() {
-                continue;
+    /// The set that deduplicates results emitted in walk order.
+    ///
+    /// With `scopedDedup`, only directories on the current depth-first path are
+    /// remembered; the static, shallow and merge steps always use the full set.
+    fn walk_seen_paths(&self, capacity: usize) -> SeenPaths {
+        if self.scoped_dedup {
+            SeenPaths::Scoped(Vec::new())
+        } else {
+            SeenPaths::with_capacity(self.dedup_strategy, capacity)
+        }
+    }
+
+    /// Prepend `resultPrefix`, if set, to a fully formatted result.
+    #[inline]
+    fn apply_result_prefix(&self, result: String) -> String {
+        match &self.result_prefix {
+            Some(prefix) => {
+                let mut prefixed = String::with_capacity(prefix.len() + result.len());
+                prefixed.push_str(prefix);
+                prefixed.push_str(&result);
+                prefixed
             }
+            None => result,
+        }
+    }
+
+    /// Build the directory pruning filter for a walk.
+    ///
+    /// The filter receives paths relative to the walk root, but the patterns expect
+    /// paths relative to cwd, so `prefix_to_strip` is prepended. A directory is
+    /// skipped when no pattern could match anything inside it, or when the ignore
+    /// filter ignores the directory together with everything below it (`node_modules`
+    /// under `**/node_modules/**`), so ignored subtrees are never read.
+    fn dir_prune_filter(
+        &self,
+        patterns: Arc<[Pattern]>,
+        prefix_to_strip: Option<String>,
+        prefix_with_slash: Option<String>,
+        abs_cwd: &Path,
+    ) -> Option<DirPruneFilter> {
+        let ignore_filter = self.ignore_filter.clone();
+        let abs_cwd = abs_cwd.to_path_buf();
+        let prune_hidden = !self.dot && self.hidden != HiddenMode::DotfilesOnly;
+        let file_system = self.walk_options.file_system.clone();
+        let prune_by_pattern = !self.disable_optimizations;
+
+        // A leading `**` can match below any directory, so when every pattern
+        // starts with one and nothing else prunes, calling a filter per
+        // directory only costs time (and keeps the walk from streaming).
+        if ignore_filter.is_none()
+            && !prune_hidden
+            && (!prune_by_pattern || patterns.iter().all(|p| p.is_globstar()))
+        {
+            return None;
+        }
+
+        Some(Box::new(move |dir_path: &str| -> bool {
+            // Use Cow to avoid allocation when no prefix is needed
+            let path_from_cwd: Cow<'_, str> = if let Some(ref prefix) = prefix_to_strip {
+                if dir_path.is_empty() {
+                    Cow::Borrowed(prefix.as_str())
+                } else if let Some(ref prefix_slash) = prefix_with_slash {
+                    Cow::Owned(format!("{prefix_slash}{dir_path}"))
+                } else {
+                    Cow::Owned(format!("{}{dir_path}", prefix_with_separator(prefix)))
+                }
+            } else {
+                Cow::Borrowed(dir_path)
+            };
+
+            if let Some(ref filter) = ignore_filter {
+                let abs_path = abs_cwd.join(path_from_cwd.as_ref());
+                if filter.ignores_subtree(&path_from_cwd, &abs_path) {
+                    return false;
+                }
+            }
+
+            if prune_hidden
+                && file_system
+                    .as_deref()
+                    .unwrap_or(&StdFileSystem)
+                    .is_hidden(&abs_cwd.join(path_from_cwd.as_ref()))
+            {
+                return false;
+            }
+
+            // Check if ANY pattern could potentially match files in this directory.
+            // If no pattern can match, we can safely skip this directory.
+            !prune_by_pattern
+                || patterns
+                    .iter()
+                    .any(|p| p.could_match_in_dir(&path_from_cwd))
+        }))
+    }
+
+    /// Check if a path is inside any of the ignored directories.
+    /// Uses byte-level comparison for performance.
+    #[inline]
+    fn is_in_ignored_dir(&self, normalized: &str, ignored_dirs: &AHashSet<String>) -> bool {
+        if ignored_dirs.is_empty() {
+            return false;
+        }
+
+        let normalized_bytes = normalized.as_bytes();
+        ignored_dirs.iter().any(|ignored_dir: &String| {
+            let ignored_bytes = ignored_dir.as_bytes();
+            normalized_bytes.starts_with(ignored_bytes)
+                && (normalized_bytes.len() == ignored_bytes.len()
+                    || normalized_bytes.get(ignored_bytes.len()) == Some(&b'/')
+                    || normalized_bytes.get(ignored_bytes.len()) == Some(&b'\\'))
+        })
+    }
+
+    /// Check if a path is a child of any matched parent.
+    /// Used when includeChildMatches is false.
+    #[inline]
+    fn is_child_of_matched(&self, normalized: &str, matched_parents: &AHashSet<String>) -> bool {
+        if matched_parents.is_empty() {
+            return false;
+        }
+
+        let normalized_bytes = normalized.as_bytes();
+        matched_parents.iter().any(|matched_path: &String| {
+            let matched_bytes = matched_path.as_bytes();
+            normalized_bytes.starts_with(matched_bytes)
+                && normalized_bytes.len() > matched_bytes.len()
+                && (normalized_bytes.get(matched_bytes.len()) == Some(&b'/')
+                    || normalized_bytes.get(matched_bytes.len()) == Some(&b'\\'))
+        })
+    }
+
+    /// Calculate the optimal walk root based on literal prefixes of patterns.
+    ///
+    /// Returns a tuple of (walk_root, prefix_to_strip, is_absolute_pattern):
+    /// - walk_root: The directory to start walking from (cwd, cwd/prefix, or absolute root)
+    /// - prefix_to_strip: If Some, this prefix was extracted and should be prepended
+    ///   to relative paths from walk_root to get the path relative to cwd
+    /// - is_absolute_pattern: True if we're walking from an absolute pattern root
+    ///
+    /// For patterns like `src/**/*.ts`, instead of walking from cwd and visiting
+    /// all directories, we can walk from `cwd/src` which is much faster.
+    ///
+    /// For absolute patterns like `C:/foo/**/*.ts` or `/usr/local/**`, we walk from
+    /// that absolute path directly.
+    ///
+    /// When patterns have different prefixes (e.g., `src/**` and `test/**`),
+    /// we find the longest common prefix, or fall back to cwd if there's no
+    /// common prefix.
+    fn calculate_walk_root(&self) -> (PathBuf, Option<String>) {
+        let (walk_root, prefix) = self.calculate_literal_walk_root();
+
+        // When nocase is true on a case-sensitive filesystem (Linux), the prefix case
+        // might not match the actual filesystem case. For example, pattern "SRC/**"
+        // won't find directory "src" on Linux even with nocase:true, so the prefix is
+        // resolved against the real directory names first.
+        // On case-insensitive filesystems (macOS, Windows), this is not an issue.
+        let Some(prefix) = prefix else {
+            return (walk_root, None);
+        };
+        if !self.nocase || self.is_case_insensitive_platform() {
+            return (walk_root, Some(prefix));
+        }
+        let real = self.resolve_prefix_case(&prefix);
+        if real.is_empty() {
+            (self.cwd.clone(), None)
+        } else if real.starts_with('/') {
+            (PathBuf::from(&real), Some(real))
+        } else {
+            (self.cwd.join(&real), Some(real))
+        }
+    }
+
+    /// Find the on-disk spelling of a literal prefix by comparing each component
+    /// case-insensitively against the entries of its parent directory.
+    ///
+    /// Components that don't exist in any case are kept as written (the walk then
+    /// finds nothing). When a component matches several entries that differ only
+    /// in case, the prefix stops at their parent: a single walk root can't cover
+    /// them all, but walking from the deepest unambiguous directory still avoids
+    /// scanning the rest of cwd. An empty result means cwd itself.
+    fn resolve_prefix_case(&self, prefix: &str) -> String {
+        let (mut resolved, rest) = match prefix.strip_prefix('/') {
+            Some(rest) => ("/".to_string(), rest),
+            None => (String::new(), prefix),
+        };
+
+        for component in rest.split('/').filter(|c| !c.is_empty()) {
+            let real = if component == "." || component == ".." {
+                component.to_string()
+            } else {
+                let wanted = fold_case(component);
+                let mut matches = self
+                    .file_system()
+                    .read_dir(&self.cwd.join(&resolved))
+                    .into_iter()
+                    .flatten()
+                    .map(|entry| entry.name)
+                    .filter(|name| fold_case(name) == wanted);
+                match (matches.next(), matches.next()) {
+                    (Some(_), Some(_)) => return resolved,
+                    (Some(name), None) => name,
+                    (None, _) => component.to_string(),
+                }
+            };
+            if !resolved.is_empty() && !resolved.ends_with('/') {
+                resolved.push('/');
+            }
+            resolved.push_str(&real);
+        }
+
+        resolved
+    }
+
+    /// Calculate the walk root from the patterns' literal prefixes, as written.
+    fn calculate_literal_walk_root(&self) -> (PathBuf, Option<String>) {
+        // If there are no patterns, just walk from cwd
+        if self.patterns.is_empty() {
+            return (self.cwd.clone(), None);
+        }
+
+        // Check if any pattern is absolute (has a root like C:/, /, or //server/share/)
+        // If we have absolute patterns, we need to handle them specially
+        let has_absolute_pattern = self.patterns.iter().any(|p| p.is_absolute());
+
+        if has_absolute_pattern {
+            // For absolute patterns, we need to check if ALL patterns are absolute
+            // and share a common root. If not, we can't optimize.
+            let all_absolute = self.patterns.iter().all(|p| p.is_absolute());
+
+            if all_absolute && self.patterns.len() == 1 {
+                // Single absolute pattern - walk from its root + literal prefix
+                let pattern = &self.patterns[0];
+                let root = pattern.root();
+
+                // Get the literal prefix (directories before any glob magic)
+                if let Some(prefix) = pattern.literal_prefix() {
+                    // Walk from root + prefix
+                    let walk_root = PathBuf::from(&root).join(&prefix);
+                    // The prefix to strip is the root + prefix
+                    let full_prefix = if root.ends_with('/') {
+                        format!("{root}{prefix}")
+                    } else {
+                        format!("{root}/{prefix}")
+                    };
+                    return (walk_root, Some(full_prefix));
+                } else {
+                    // No literal prefix, just walk from the root
+                    return (PathBuf::from(&root), Some(root.to_string()));
+                }
+            } else if all_absolute {
+                // Multiple absolute patterns - find common root
+                let roots: Vec<&str> = self.patterns.iter().map(|p| p.root()).collect();
+
+                // Check if all roots are the same
+                if !roots.is_empty() && roots.iter().all(|r| *r == roots[0]) {
+                    let common_root = roots[0];
+
+                    // Get literal prefixes after the root
+                    let prefixes: Vec<Option<String>> =
+                        self.patterns.iter().map(|p| p.literal_prefix()).collect();
+
+                    // If any pattern has no prefix, walk from the root
+                    if prefixes.iter().any(|p| p.is_none()) {
+                        return (PathBuf::from(common_root), Some(common_root.to_string()));
+                    }
+
+                    // Find common prefix among all patterns
+                    let prefix_strs: Vec<&str> = prefixes
+                        .iter()
+                        .filter_map(|p| p.as_ref().map(|s| s.as_str()))
+                        .collect();
+
+                    let common_prefix = Self::longest_common_prefix(&prefix_strs);
+
+                    if common_prefix.is_empty() {
+                        return (PathBuf::from(common_root), Some(common_root.to_string()));
+                    }
+
+                    let walk_root = PathBuf::from(common_root).join(&common_prefix);
+                    let full_prefix = if common_root.ends_with('/') {
+                        format!("{common_root}{common_prefix}")
+                    } else {
+                        format!("{common_root}/{common_prefix}")
+                    };
+                    return (walk_root, Some(full_prefix));
+                }
+            }
+
+            // Mixed absolute and relative patterns, or different roots
+            // Fall back to walking from cwd for relative patterns
+            // This is a limitation - we can't efficiently handle mixed patterns
+            return (self.cwd.clone(), None);
+        }
+
+        // Get literal prefixes from all patterns
+        let prefixes: Vec<Option<String>> =
+            self.patterns.iter().map(|p| p.literal_prefix()).collect();
+
+        // If any pattern has no prefix (e.g., `**/*.js` or `*.txt`), we must walk from cwd
+        if prefixes.iter().any(|p| p.is_none()) {
+            return (self.cwd.clone(), None);
+        }
+
+        // All patterns have prefixes - find the longest common prefix
+        let prefix_strs: Vec<&str> = prefixes
+            .iter()
+            .filter_map(|p| p.as_ref().map(|s| s.as_str()))
+            .collect();
+
+        if prefix_strs.is_empty() {
+            return (self.cwd.clone(), None);
+        }
+
+        // Find the longest common prefix among all pattern prefixes
+        let common_prefix = Self::longest_common_prefix(&prefix_strs);
+
+        if common_prefix.is_empty() {
+            return (self.cwd.clone(), None);
+        }
+
+        // Construct the walk root
+        let walk_root = self.cwd.join(&common_prefix);
+
+        // Verify the walk root exists before using it
+        if !walk_root.exists() {
+            // If the prefix directory doesn't exist, we'll get empty results anyway
+            // But we still walk from there to get correct behavior
+            return (walk_root, Some(common_prefix));
+        }
+
+        (walk_root, Some(common_prefix))
+    }
+
+    /// Group patterns by their first-level literal prefix.
+    ///
+    /// This enables multi-base walking: instead of walking from cwd when patterns
+    /// have different prefixes, we walk from each unique prefix separately.
+    ///
+    /// Returns a map of prefix -> pattern indices.
+    /// Patterns without a prefix (e.g., `**/*.js`) go into the `None` group.
+    ///
+    /// # Example
+    /// ```ignore
+    /// patterns: ["src/**/*.ts", "src/lib/*.ts", "test/**/*.ts", "**/*.js"]
+    /// Result: {
+    ///   Some("src") -> [0, 1],
+    ///   Some("test") -> [2],
+    ///   None -> [3]
+    /// }
+    /// ```
+    fn group_patterns_by_base(&self) -> std::collections::HashMap<Option<String>, Vec<usize>> {
+        use std::collections::HashMap;
+        let mut groups: HashMap<Option<String>, Vec<usize>> = HashMap::new();
+
+        for (idx, pattern) in self.patterns.iter().enumerate() {
+            // Get the first component of the literal prefix
+            // This is more aggressive grouping than using the full prefix
+            let base = pattern.literal_prefix().map(|prefix| {
+                // Get just the first path component
+                prefix
+                    .split('/')
+                    .next()
+                    .map(|s| s.to_string())
+                    .unwrap_or(prefix)
+            });
+
+            groups.entry(base).or_default().push(idx);
+        }
+
+        groups
+    }
+
+    /// Check if multi-base walking would be beneficial.
+    ///
+    /// Multi-base walking helps when:
+    /// 1. All patterns have literal prefixes (no patterns like `**/*.js`)
+    /// 2. There are multiple distinct first-level prefixes (e.g., `src` and `test`)
+    /// 3. All prefixes point to existing directories
+    fn should_use_multi_base_walking(&self) -> bool {
+        // When nocase is true on a case-sensitive filesystem (Linux), we can't use
+        // multi-base walking because the prefix case might not match the filesystem.
+        if self.nocase && !self.is_case_insensitive_platform() {
+            return false;
+        }
+
+        // Quick check: if any pattern has no prefix, we can't use multi-base
+        if self.patterns.iter().any(|p| p.literal_prefix().is_none()) {
+            return false;
+        }
+
+        // Get first-level bases
+        let groups = self.group_patterns_by_base();
+
+        // Need at least 2 distinct bases to benefit from multi-base walking
+        if groups.len() < 2 {
+            return false;
+        }
+
+        // All groups must have Some base (no None group)
+        if groups.contains_key(&None) {
+            return false;
+        }
+
+        // Check that all base directories exist
+        groups.keys().all(|base| {
+            if let Some(base_str) = base {
+                self.cwd.join(base_str).exists()
+            } else {
+                false
+            }
+        })
+    }
+
+    /// Walk using multiple base directories in parallel using rayon.
+    ///
+    /// This is an optimization for patterns like `['src/**/*.ts', 'test/**/*.ts']`.
+    /// Instead of walking from cwd and visiting all directories, we walk from
+    /// `src/` and `test/` concurrently using rayon's parallel iterators.
+    ///
+    /// Each base directory is processed in parallel, and results are merged
+    /// with deduplication at the end.
+    fn walk_multi_base(&self) -> Vec<String> {
+        let groups = self.group_patterns_by_base();
+        // Strip Windows extended-length prefix (\\?\) to match glob v13 behavior
+        let abs_cwd = strip_windows_extended_prefix(
+            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
+        );
+
+        // Convert groups to a Vec for parallel iteration
+        let groups_vec: Vec<(Option<String>, Vec<usize>)> = groups.into_iter().collect();
+
+        // Process each base group in parallel using rayon
+        // Each group returns its own Vec of results (local deduplication)
+        let group_results: Vec<Vec<String>> = groups_vec
+            .par_iter()
+            .filter_map(|(base, pattern_indices)| {
+                // Skip groups without a valid base
+                base.as_ref()?;
+
+                Some(self.walk_single_base_group(pattern_indices, &abs_cwd))
+            })
+            .collect();
+
+        // Merge all results and deduplicate
+        let estimated_capacity = self.estimate_result_capacity();
+        let mut seen = SeenPaths::with_capacity(self.dedup_strategy, estimated_capacity);
+        let mut results = Vec::with_capacity(estimated_capacity);
+
+        for group_result in group_results {
+            for result in group_result {
+                if seen.insert(&result) {
+                    results.push(result);
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Like `walk_multi_base`, but results reach `callback` as soon as any base's
+    /// walk finds them (`streamPerBase`), instead of after every walk has finished.
+    ///
+    /// Each base is walked on its own thread and sends its results over a channel;
+    /// duplicates across bases are dropped here, so the order between bases varies.
+    fn walk_multi_base_streaming<F>(&self, mut callback: F)
+    where
+        F: FnMut(String),
+    {
+        let groups = self.group_patterns_by_base();
+        let abs_cwd = strip_windows_extended_prefix(
+            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
+        );
+        let mut seen =
+            SeenPaths::with_capacity(self.dedup_strategy, self.estimate_result_capacity());
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        // Plain threads rather than rayon: this thread blocks on the channel,
+        // which would starve a rayon pool it belonged to
+        std::thread::scope(|scope| {
+            for (base, pattern_indices) in &groups {
+                if base.is_none() {
+                    continue;
+                }
+                let sender = sender.clone();
+                let abs_cwd = &abs_cwd;
+                scope.spawn(move || {
+                    self.walk_single_base_group_with(pattern_indices, abs_cwd, |result| {
+                        // The receiver outlives every walk, so sending can't fail
+                        let _ = sender.send(result);
+                    });
+                });
+            }
+            drop(sender);
+
+            for result in receiver {
+                if seen.insert(&result) {
+                    callback(result);
+                }
+            }
+        });
+    }
+
+    /// Walk a single base directory group and return results.
+    ///
+    /// This method is designed to be called in parallel from `walk_multi_base`.
+    /// It handles all the logic for walking a single base directory and matching
+    /// patterns within that group.
+    fn walk_single_base_group(&self, pattern_indices: &[usize], abs_cwd: &Path) -> Vec<String> {
+        let mut results = Vec::with_capacity(self.estimate_result_capacity() / 4);
+        self.walk_single_base_group_with(pattern_indices, abs_cwd, |result| results.push(result));
+        results
+    }
+
+    /// Walk a single base directory group, passing each result to `emit`.
+    fn walk_single_base_group_with<E>(&self, pattern_indices: &[usize], abs_cwd: &Path, mut emit: E)
+    where
+        E: FnMut(String),
+    {
+        let estimated_capacity = self.estimate_result_capacity() / 4; // Smaller per-group
+        let mut seen = self.walk_seen_paths(estimated_capacity);
+        let mut ignored_dirs: AHashSet<String> = AHashSet::with_capacity(8);
+        let mut matched_parents: AHashSet<String> = if self.include_child_matches {
+            AHashSet::new()
+        } else {
+            AHashSet::with_capacity(estimated_capacity / 4)
+        };
+        let mut result_buffer = String::with_capacity(self.estimate_path_buffer_capacity());
+        let has_ignore_filter = self.ignore_filter.is_some();
+
+        // Get the patterns for this group
+        let group_patterns: Vec<&Pattern> =
+            pattern_indices.iter().map(|&i| &self.patterns[i]).collect();
+
+        // Find the longest common prefix within this group
+        let prefixes: Vec<Option<String>> =
+            group_patterns.iter().map(|p| p.literal_prefix()).collect();
+        let prefix_strs: Vec<&str> = prefixes
+            .iter()
+            .filter_map(|p| p.as_ref().map(|s| s.as_str()))
+            .collect();
+        let common_prefix = Self::longest_common_prefix(&prefix_strs);
+
+        // Walk from the common prefix (at least the base)
+        let walk_root = self.cwd.join(&common_prefix);
+        let prefix_to_strip = if common_prefix.is_empty() {
+            None
+        } else {
+            Some(common_prefix.clone())
+        };
+
+        // Pre-compute the prefix with trailing slash for efficient path concatenation
+        let prefix_with_slash: Option<String> =
+            prefix_to_strip.as_deref().map(prefix_with_separator);
+
+        // Adjust walk options for this prefix
+        let adjusted_walk_options = if let Some(ref prefix) = prefix_to_strip {
+            let prefix_depth = prefix.split('/').filter(|s| !s.is_empty()).count();
+            if let Some(max_d) = self.walk_options.max_depth {
+                if max_d <= prefix_depth {
+                    self.walk_options.clone().max_depth(Some(0))
+                } else {
+                    self.walk_options
+                        .clone()
+                        .max_depth(Some(max_d - prefix_depth))
+                }
+            } else {
+                self.walk_options.clone()
+            }
+        } else {
+            self.walk_options.clone()
+        };
+
+        // Create pruning filter for this group's patterns
+        let patterns_arc: Arc<[Pattern]> = group_patterns.iter().cloned().cloned().collect();
+        let prune_filter = self.dir_prune_filter(
+            patterns_arc,
+            prefix_to_strip.clone(),
+            prefix_with_slash.clone(),
+            abs_cwd,
+        );
+
+        // Create walker for this group
+        let walker = new_walker(walk_root.clone(), adjusted_walk_options, prune_filter);
+
+        // Walk and collect results
+        for entry in walker.walk().inspect(|e| self.record_visit(e.is_dir())) {
+            let path = entry.path();
+
+            let rel_path_from_walk_root = match path.strip_prefix(&walk_root) {
+                Ok(p) => p,
+                Err(_) => continue,
+            };
+            let rel_str_from_walk_root = rel_path_from_walk_root.to_string_lossy();
+            let is_walk_root_entry = rel_str_from_walk_root.is_empty();
+
+            let normalized = self.normalize_path(
+                &rel_str_from_walk_root,
+                &prefix_to_strip,
+                is_walk_root_entry,
+            );
+
+            if self.is_in_ignored_dir(&normalized, &ignored_dirs) {
+                continue;
+            }
+
+            if has_ignore_filter {
+                let rel_path = if prefix_to_strip.is_some() {
+                    PathBuf::from(normalized.as_ref())
+                } else {
+                    rel_path_from_walk_root.to_path_buf()
+                };
+                let abs_path = abs_cwd.join(&rel_path);
+                let ignore_filter = self.ignore_filter.as_ref().unwrap();
+
+                if ignore_filter.should_ignore(&normalized, &abs_path) {
+                    if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
+                        ignored_dirs.insert(normalized.into_owned());
+                    }
+                    continue;
+                }
+
+                if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
+                    ignored_dirs.insert(normalized.to_string());
+                }
+            }
+
+            // The base directory itself (e.g. "src") is matched like any other
+            // entry below, the same way the single-base walk handles its root.
+            if normalized.is_empty() {
+                continue;
+            }
+
+            if self.nodir && entry.is_dir() {
+                continue;
+            }
+
+            if !self.dot
+                && (!self.path_allowed_by_dot_rules(&normalized)
+                    || self.hidden_by_attribute(&normalized))
+            {
+                continue;
+            }
+
+            if !self.include_child_matches
+                && self.is_child_of_matched(&normalized, &matched_parents)
+            {
+                continue;
+            }
+
+            let is_dir = entry.is_dir();
+            let is_symlink = entry.is_symlink();
+
+            // Check if any pattern in this group matches
+            let matches = group_patterns.iter().any(|p| {
+                let path_matches = match p.matches_fast(&normalized) {
+                    Some(result) => result,
+                    None => p.matches(&normalized),
+                };
+                if path_matches && p.requires_dir() {
+                    is_dir_or_dir_link(self.file_system(), is_dir, entry.is_symlink(), entry.path())
+                } else {
+                    path_matches
+                }
+            });
+
+            if matches {
+                let result = self.build_result_path(
+                    &normalized,
+                    is_dir,
+                    is_symlink,
+                    abs_cwd,
+                    &mut result_buffer,
+                );
+
+                if seen.insert(&result) {
+                    if !self.include_child_matches {
+                        matched_parents.insert(normalized.into_owned());
+                    }
+                    emit(result);
+                }
+            }
+        }
+
+        self.note_depth_limit(&walker);
+    }
+
+    /// Find the longest common prefix among a list of paths.
+    ///
+    /// For example:
+    /// - `["src/lib", "src/bin"]` -> `"src"`
+    /// - `["src", "test"]` -> `""`
+    /// - `["packages/foo", "packages/bar"]` -> `"packages"`
+    fn longest_common_prefix(paths: &[&str]) -> String {
+        if paths.is_empty() {
+            return String::new();
+        }
+
+        if paths.len() == 1 {
+            return paths[0].to_string();
+        }
+
+        // Split all paths into components
+        let path_components: Vec<Vec<&str>> =
+            paths.iter().map(|p| p.split('/').collect()).collect();
+
+        // Find the minimum length
+        let min_len = path_components.iter().map(|c| c.len()).min().unwrap_or(0);
+
+        // Find common prefix components
+        let mut common_components: Vec<&str> = Vec::new();
+        for i in 0..min_len {
+            let first = path_components[0][i];
+            if path_components.iter().all(|c| c[i] == first) {
+                common_components.push(first);
+            } else {
+                break;
+            }
+        }
+
+        common_components.join("/")
+    }
+
+    /// Check if all patterns are static (no wildcards, can be resolved with stat()).
+    ///
+    /// Static patterns are patterns like `package.json` or `src/index.ts` that
+    /// resolve to a single path and can be checked with a direct stat() call
+    /// instead of walking the entire directory tree.
+    fn all_patterns_static(&self) -> bool {
+        // When nocase is true on a case-sensitive filesystem (Linux), we can't use
+        // the static pattern fast path because we need to scan directories to find
+        // case-insensitive matches.
+        if self.nocase && !self.is_case_insensitive_platform() {
+            return false;
+        }
+        !self.patterns.is_empty() && self.patterns.iter().all(|p| p.is_static())
+    }
+
+    /// Check if all patterns are shallow (max_depth 0, root-level only).
+    ///
+    /// Shallow patterns like `*.js` or `*.{ts,tsx}` can be resolved with a single
+    /// readdir call instead of using the full walker machinery.
+    fn all_patterns_shallow(&self) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+        // All patterns must have max_depth of 0 (no path separators, no **)
+        self.patterns.iter().all(|p| p.max_depth() == Some(0))
+    }
+
+    /// Check if the current platform has a case-insensitive filesystem by default.
+    ///
+    /// This is used to determine if we can use prefix-based walking optimizations
+    /// with nocase:true. On macOS and Windows, the filesystem is typically case-insensitive,
+    /// so "SRC" and "src" refer to the same directory. On Linux, they're different.
+    #[inline]
+    fn is_case_insensitive_platform(&self) -> bool {
+        // macOS (darwin) and Windows (win32) have case-insensitive filesystems by default
+        cfg!(target_os = "macos") || cfg!(target_os = "windows")
+    }
+
+    /// Check if backslashes should be normalized to forward slashes.
+    ///
+    /// On Windows with posix: false (the default), glob v13 outputs backslashes.
+    /// On Windows with posix: true, glob v13 outputs forward slashes.
+    /// On non-Windows, glob v13 always outputs forward slashes.
+    #[inline]
+    fn should_normalize_backslashes(&self) -> bool {
+        // Use forward slashes when:
+        // - On non-Windows platforms (always)
+        // - On Windows with posix: true
+        self.posix_explicit_true || !cfg!(target_os = "windows")
+    }
+
+    /// Normalize path separators based on platform and posix option.
+    ///
+    /// When use_forward_slashes is true: converts backslashes to forward slashes
+    /// When use_forward_slashes is false: converts forward slashes to backslashes
+    ///
+    /// Returns the original string if no conversion is needed.
+    #[inline]
+    fn normalize_separators<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        let use_forward = self.should_normalize_backslashes();
+        if use_forward {
+            if self.preserve_backslashes || !path.contains('\\') {
+                Cow::Borrowed(path)
+            } else {
+                Cow::Owned(path.replace('\\', "/"))
+            }
+        } else {
+            // On Windows with posix: false, convert forward slashes to backslashes
+            if !path.contains('/') {
+                Cow::Borrowed(path)
+            } else {
+                Cow::Owned(path.replace('/', "\\"))
+            }
+        }
+    }
+
+    /// Resolve shallow patterns using direct readdir.
+    ///
+    /// This is a fast path for patterns like `*.js` that only match at the root level.
+    /// Instead of using the full walker machinery with all its overhead, we do a
+    /// single readdir and filter the results.
+    fn resolve_shallow_patterns(&self) -> Vec<String> {
+        use std::fs;
+
+        let mut results = Vec::new();
+        let mut seen = SeenPaths::with_capacity(self.dedup_strategy, 0);
+
+        // Read the directory entries directly
+        let mut entries: Vec<fs::DirEntry> = match fs::read_dir(&self.cwd) {
+            Ok(rd) => rd.filter_map(|entry| entry.ok()).collect(),
+            Err(e) => {
+                self.walk_options.note_unreadable(&self.cwd, true, &e);
+                return results;
+            }
+        };
+        if self.walk_options.sort_dir_entries {
+            entries.sort_by_key(|entry| entry.file_name());
+        }
+
+        // Strip Windows extended-length prefix (\\?\) to match glob v13 behavior
+        let abs_cwd = strip_windows_extended_prefix(
+            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
+        );
+
+        // The directory read counts as visiting cwd, as the walk root does for the walker
+        self.record_visit(true);
+        for entry in entries {
+            self.record_visit(entry.file_type().is_ok_and(|ft| ft.is_dir()));
+            let file_name = match entry.file_name().into_string() {
+                Ok(n) => n,
+                Err(_) => continue,
+            };
+
+            // Filter dotfiles (or attribute-hidden entries) if dot option is false,
+            // unless a pattern names them explicitly (`.*`, `@(.env|x)`)
+            if !self.dot
+                && (!self.path_allowed_by_dot_rules(&file_name)
+                    || self.hidden_by_attribute(&file_name))
+            {
+                continue;
+            }
+
+            // Get file type - use file_type() from DirEntry when possible
+            let file_type = match entry.file_type() {
+                Ok(ft) => ft,
+                Err(_) => continue,
+            };
+
+            let is_dir_raw = file_type.is_dir();
+            let is_symlink = file_type.is_symlink();
+
+            if is_symlink && !self.resolves_in_sandbox(&entry.path()) {
+                continue;
+            }
+
+            // If following symlinks and this is a symlink, get target type
+            // Note: entry.metadata() returns metadata for the symlink itself on macOS,
+            // not the target. Use fs::metadata() on the path to follow the symlink.
+            let is_dir = if is_symlink && self.follow {
+                match fs::metadata(entry.path()) {
+                    Ok(meta) => meta.is_dir(),
+                    Err(_) => false, // Broken symlink
+                }
+            } else {
+                is_dir_raw
+            };
+
+            // Skip directories if nodir is true
+            if self.nodir && is_dir {
+                continue;
+            }
+
+            // Check if any pattern matches
+            let matches = self.patterns.iter().any(|p| {
+                let path_matches = match p.matches_fast(&file_name) {
+                    Some(result) => result,
+                    None => p.matches(&file_name),
+                };
+                if path_matches && p.requires_dir() {
+                    is_dir_or_dir_link(self.file_system(), is_dir, is_symlink, &entry.path())
+                } else {
+                    path_matches
+                }
+            });
+
+            if !matches {
+                continue;
+            }
+
+            // Apply ignore filter if present
+            if let Some(ref filter) = self.ignore_filter {
+                if filter.should_ignore(&file_name, &abs_cwd.join(&file_name)) {
+                    continue;
+                }
+            }
+
+            // Build result path
+            let result = if self.absolute {
+                let abs_path = abs_cwd.join(&file_name);
+                let formatted = self.format_path(&abs_path);
+                if self.mark && is_dir && !is_symlink {
+                    self.ensure_trailing_slash(&formatted)
+                } else {
+                    formatted
+                }
+            } else {
+                let sep = if self.should_normalize_backslashes() {
+                    '/'
+                } else {
+                    '\\'
+                };
+                let base = if self.dot_relative {
+                    format!(".{sep}{file_name}")
+                } else {
+                    file_name.clone()
+                };
+                if self.mark
+                    && is_dir
+                    && !is_symlink
+                    && !base.ends_with('/')
+                    && !base.ends_with('\\')
+                {
+                    format!("{base}{sep}")
+                } else {
+                    base
+                }
+            };
+            let result = self.apply_result_prefix(result);
+
+            if seen.insert(&result) {
+                results.push(result);
+            }
+        }
+
+        results
+    }
+
+    /// Resolve static patterns directly using stat() instead of walking.
+    ///
+    /// This is a fast path for patterns like `package.json` or `src/index.ts`
+    /// that can be resolved to a single file path. Instead of walking the
+    /// directory tree and matching each file, we directly check if the file
+    /// exists.
+    ///
+    /// Returns a Vec of matching paths.
+    fn resolve_static_patterns(&self) -> Vec<String> {
+        use std::fs;
+
+        let mut results = Vec::with_capacity(self.patterns.len());
+        let mut seen = SeenPaths::with_capacity(self.dedup_strategy, self.patterns.len());
+        // When includeChildMatches is false, track (result, path) pairs for post-filtering
+        let mut matched_with_normalized: Vec<(String, String)> = Vec::new();
+        // Only cwd is canonicalized, as in the walk: a symlink named by the pattern
+        // is reported where it is, and circular or broken links need no resolving
+        let abs_cwd = strip_windows_extended_prefix(
+            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
+        );
+
+        for pattern in self.patterns.iter() {
+            if let Some(static_path) = pattern.static_path() {
+                // Construct the full path
+                let full_path = self.cwd.join(&static_path);
+
+                // Check if the file exists
+                // When follow is true, first try metadata() which follows symlinks.
+                // If that fails (e.g., broken symlink), fall back to symlink_metadata().
+                // This matches glob's behavior of returning broken symlinks even with follow: true.
+                let metadata = if self.follow {
+                    fs::metadata(&full_path).or_else(|_| fs::symlink_metadata(&full_path))
+                } else {
+                    fs::symlink_metadata(&full_path)
+                };
+
+                // A path that can't be stat'ed (other than by being missing)
+                // lies in a directory that can't be read: this pattern's root
+                if let (Err(e), Some(parent)) = (&metadata, full_path.parent()) {
+                    self.walk_options.note_unreadable(parent, false, e);
+                }
+
+                if let Ok(meta) = metadata {
+                    let is_dir = meta.is_dir();
+                    // Followed metadata describes the target; `mark` must still
+                    // leave a symlink to a directory unmarked, as the walk does
+                    let is_symlink = meta.file_type().is_symlink()
+                        || (self.follow
+                            && is_dir
+                            && fs::symlink_metadata(&full_path)
+                                .is_ok_and(|m| m.file_type().is_symlink()));
+                    self.record_visit(is_dir);
+
+                    if !self.resolves_in_sandbox(&full_path) {
+                        continue;
+                    }
+
+                    // Check nodir option
+                    if self.nodir && is_dir {
+                        continue;
+                    }
+
+                    // Check if pattern requires directory (ends with /)
+                    if pattern.requires_dir()
+                        && !is_dir_or_dir_link(self.file_system(), is_dir, is_symlink, &full_path)
+                    {
+                        continue;
+                    }
+
+                    // Apply ignore filter if present
+                    if let Some(ref filter) = self.ignore_filter {
+                        if filter.should_ignore(&static_path, &full_path) {
+                            continue;
+                        }
+                    }
+
+                    // Check dot option
+                    if !self.dot {
+                        let has_hidden = static_path
+                            .split('/')
+                            .any(|seg| seg.starts_with('.') && seg != "." && seg != "..");
+                        if has_hidden && !pattern.allows_dotfile(&static_path) {
+                            continue;
+                        }
+                    }
+
+                    // Strip trailing slash from static path (glob returns paths without trailing slash unless mark: true)
+                    let base_path = static_path.trim_end_matches('/');
+
+                    // Format the result path
+                    let result = if self.absolute {
+                        let formatted =
+                            self.format_path(&normalize_path_lexically(&abs_cwd.join(base_path)));
+                        if self.mark && is_dir && !is_symlink {
+                            self.ensure_trailing_slash(&formatted)
+                        } else {
+                            formatted
+                        }
+                    } else {
+                        let use_forward = self.should_normalize_backslashes();
+                        let sep = if use_forward { '/' } else { '\\' };
+                        // Convert separators for output (static_path uses forward slashes internally)
+                        let output_base = if use_forward {
+                            base_path.to_string()
+                        } else {
+                            base_path.replace('/', "\\")
+                        };
+                        let base = if self.dot_relative
+                            && !output_base.starts_with("../")
+                            && !output_base.starts_with("..\\")
+                        {
+                            format!(".{sep}{output_base}")
+                        } else {
+                            output_base
+                        };
+                        if self.mark
+                            && is_dir
+                            && !is_symlink
+                            && !base.ends_with('/')
+                            && !base.ends_with('\\')
+                        {
+                            format!("{base}{sep}")
+                        } else {
+                            base
+                        }
+                    };
+                    let result = self.apply_result_prefix(result);
+
+                    // Deduplicate (in case of brace expansion producing duplicates)
+                    if seen.insert(&result) {
+                        if !self.include_child_matches {
+                            matched_with_normalized.push((result.clone(), base_path.to_string()));
+                        }
+                        results.push(result);
+                    }
+                }
+            }
+        }
+
+        // Patterns like `a` and `a/b.txt` can both resolve; keep only the parent
+        if !self.include_child_matches && !matched_with_normalized.is_empty() {
+            return filter_child_matches(matched_with_normalized);
+        }
+
+        results
+    }
+
+    /// Like `walk_stream`, but hands results to the callback in batches of
+    /// `batch_size` (the last batch holds the remainder). Returns the number of
+    /// batches delivered; no callback is made when nothing matches.
+    ///
+    /// This is the only walk `streamPerBase` applies to.
+    pub fn walk_stream_batched<F>(&self, batch_size: usize, mut callback: F) -> usize
+    where
+        F: FnMut(Vec<String>),
+    {
+        let batch_size = batch_size.max(1);
+        // Don't reserve a huge buffer for a batch size meant as "no limit"
+        let capacity = batch_size.min(4096);
+        let mut batch = Vec::with_capacity(capacity);
+        let mut batches = 0;
+        self.walk_stream_from_bases(self.stream_per_base, |result| {
+            batch.push(result);
+            if batch.len() == batch_size {
+                callback(std::mem::replace(&mut batch, Vec::with_capacity(capacity)));
+                batches += 1;
+            }
+        });
+        if !batch.is_empty() {
+            callback(batch);
+            batches += 1;
+        }
+        batches
+    }
+
+    /// Walk the directory tree, keeping only the `count` lexically smallest
+    /// results (see `glob_sync_first`).
+    pub fn walk_sync_first(&self, count: usize) -> Vec<String> {
+        let mut first = TopN::new(count);
+        self.walk_stream(|result| first.push(result));
+        first.into_sorted_vec()
+    }
+
+    /// Walk the directory tree and stream results via callback.
+    /// This reduces peak memory usage by not collecting all results into a Vec.
+    pub fn walk_stream<F>(&self, callback: F)
+    where
+        F: FnMut(String),
+    {
+        self.walk_stream_from_bases(false, callback);
+    }
+
+    /// `walk_stream`, with each base of a multi-base walk streamed as soon as
+    /// its own walk finds results when `per_base` is set (`streamPerBase`).
+    fn walk_stream_from_bases<F>(&self, per_base: bool, mut callback: F)
+    where
+        F: FnMut(String),
+    {
+        // Whether a symlinked path is kept depends on results that may come later
+        if self.canonical_only {
+            return self.walk_sync().into_iter().for_each(callback);
+        }
+        if !self.realpath {
+            return self.walk_stream_paths(per_base, callback);
+        }
+
+        let abs_cwd = strip_windows_extended_prefix(
+            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
+        );
+        let mut seen = AHashSet::new();
+        self.walk_stream_paths(per_base, |result| {
+            if let Some(resolved) = self.resolve_realpath(result, &abs_cwd) {
+                if !self.unique || seen.insert(resolved.clone()) {
+                    callback(resolved);
+                }
+            }
+        });
+    }
+
+    /// Stream the formatted results of a walk, before `realpath`.
+    fn walk_stream_paths<F>(&self, per_base: bool, mut callback: F)
+    where
+        F: FnMut(String),
+    {
+        // If maxDepth is negative, return empty results
+        if let Some(d) = self.max_depth {
+            if d < 0 {
+                return;
+            }
+        }
+
+        if per_base && self.fast_path_strategy() == Some(WalkStrategy::MultiBase) {
+            return self.walk_multi_base_streaming(callback);
+        }
+
+        // Use AHashSet for deduplication (can't eliminate this for correctness)
+        let mut seen = self.walk_seen_paths(self.estimate_result_capacity());
+        let mut ignored_dirs: AHashSet<String> = AHashSet::with_capacity(8);
+
+        // When includeChildMatches is false, track matched paths to exclude their children
+        let mut matched_parents: AHashSet<String> = if self.include_child_matches {
+            AHashSet::new()
+        } else {
+            AHashSet::with_capacity(64)
+        };
+
+        // Pre-allocate a reusable buffer for path formatting
+        let mut result_buffer = String::with_capacity(self.estimate_path_buffer_capacity());
+
+        // Check if any pattern matches the cwd itself
+        let include_cwd = self.patterns.iter().any(pattern_matches_cwd);
+
+        // Strip Windows extended-length prefix (\\?\) to match glob v13 behavior
+        let abs_cwd = strip_windows_extended_prefix(
+            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
+        );
+        let (walk_root, prefix_to_strip) = self.calculate_walk_root();
+
+        // Pre-compute the prefix with trailing slash for efficient path concatenation
+        let prefix_with_slash: Option<String> =
+            prefix_to_strip.as_deref().map(prefix_with_separator);
+
+        // Adjust walk options for prefix-based walking
+        let adjusted_walk_options = if let Some(ref prefix) = prefix_to_strip {
+            let prefix_depth = prefix.split('/').filter(|s| !s.is_empty()).count();
+            if let Some(max_d) = self.walk_options.max_depth {
+                if max_d <= prefix_depth {
+                    self.walk_options.clone().max_depth(Some(0))
+                } else {
+                    self.walk_options
+                        .clone()
+                        .max_depth(Some(max_d - prefix_depth))
+                }
+            } else {
+                self.walk_options.clone()
+            }
+        } else {
+            self.walk_options.clone()
+        };
+
+        let prune_filter = self.dir_prune_filter(
+            Arc::clone(&self.patterns),
+            prefix_to_strip.clone(),
+            prefix_with_slash.clone(),
+            &abs_cwd,
+        );
+
+        let walker = new_walker(walk_root.clone(), adjusted_walk_options, prune_filter);
+
+        let has_ignore_filter = self.ignore_filter.is_some();
+
+        for entry in walker.walk().inspect(|e| self.record_visit(e.is_dir())) {
+            let path = entry.path();
+
+            let rel_path_from_walk_root = match path.strip_prefix(&walk_root) {
+                Ok(p) => p,
+                Err(_) => continue,
+            };
+            let rel_str_from_walk_root = rel_path_from_walk_root.to_string_lossy();
+            let is_walk_root_entry = rel_str_from_walk_root.is_empty();
+
+            let normalized = self.normalize_path(
+                &rel_str_from_walk_root,
+                &prefix_to_strip,
+                is_walk_root_entry,
+            );
+
+            if self.is_in_ignored_dir(&normalized, &ignored_dirs) {
+                continue;
+            }
+
+            if has_ignore_filter {
+                let rel_path = if prefix_to_strip.is_some() {
+                    PathBuf::from(normalized.as_ref())
+                } else {
+                    rel_path_from_walk_root.to_path_buf()
+                };
+                let abs_path = abs_cwd.join(&rel_path);
+                let ignore_filter = self.ignore_filter.as_ref().unwrap();
+
+                if ignore_filter.should_ignore(&normalized, &abs_path) {
+                    if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
+                        ignored_dirs.insert(normalized.into_owned());
+                    }
+                    continue;
+                }
+
+                if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
+                    ignored_dirs.insert(normalized.to_string());
+                }
+            }
+
+            // Handle root
+            if is_walk_root_entry && prefix_to_strip.is_none() {
+                if include_cwd && !self.nodir {
+                    if let Some(ref ignore_filter) = self.ignore_filter {
+                        if ignore_filter.should_ignore(".", &abs_cwd) {
+                            continue;
+                        }
+                    }
+
+                    let result = if self.absolute {
+                        let formatted = self.format_path_into_buffer(&abs_cwd, &mut result_buffer);
+                        if self.mark {
+                            if formatted.ends_with('/') || formatted.ends_with('\\') {
+                                formatted.to_string()
+                            } else {
+                                format!("{formatted}/")
+                            }
+                        } else {
+                            formatted.to_string()
+                        }
+                    } else if self.mark {
+                        "./".to_string()
+                    } else {
+                        ".".to_string()
+                    };
+                    let result = self.apply_result_prefix(result);
+                    if seen.insert(&result) {
+                        callback(result);
+                    }
+                }
+                continue;
+            }
+
+            if normalized.is_empty() {
+                continue;
+            }
+
+            if self.nodir && entry.is_dir() {
+                continue;
+            }
+
+            if !self.dot
+                && (!self.path_allowed_by_dot_rules(&normalized)
+                    || self.hidden_by_attribute(&normalized))
+            {
+                continue;
+            }
+
+            if !self.include_child_matches
+                && self.is_child_of_matched(&normalized, &matched_parents)
+            {
+                continue;
+            }
+
+            let is_dir = entry.is_dir();
+            let is_symlink = entry.is_symlink();
+
+            let matches = if !self.any_pattern_requires_dir {
+                self.patterns
+                    .iter()
+                    .any(|p| match p.matches_fast(&normalized) {
+                        Some(result) => result,
+                        None => p.matches(&normalized),
+                    })
+            } else {
+                self.patterns.iter().any(|p| {
+                    let path_matches = match p.matches_fast(&normalized) {
+                        Some(result) => result,
+                        None => p.matches(&normalized),
+                    };
+                    if path_matches && p.requires_dir() {
+                        is_dir_or_dir_link(
+                            self.file_system(),
+                            is_dir,
+                            entry.is_symlink(),
+                            entry.path(),
+                        )
+                    } else {
+                        path_matches
+                    }
+                })
+            };
+
+            if matches && !self.sequentially_excluded(&normalized, is_dir) {
+                let result = self.build_result_path(
+                    &normalized,
+                    is_dir,
+                    is_symlink,
+                    &abs_cwd,
+                    &mut result_buffer,
+                );
+
+                if seen.insert(&result) {
+                    if !self.include_child_matches {
+                        matched_parents.insert(normalized.into_owned());
+                    }
+                    callback(result);
+                }
+            }
+        }
+
+        self.note_depth_limit(&walker);
+    }
+
+    /// Walk the directory tree and stream PathData results via callback.
+    /// This reduces peak memory usage by not collecting all results into a Vec.
+    pub fn walk_stream_with_file_types<F>(&self, mut callback: F)
+    where
+        F: FnMut(PathData),
+    {
+        self.walk_stream_with_file_types_until(None, |data, _| {
+            callback(data);
+            true
+        });
+    }
+
+    /// Walk once and total up the matches without collecting their paths.
+    ///
+    /// Every match is counted; only regular files contribute to `total_size`
+    /// (directories and unfollowed symlinks add nothing). Files that can't be
+    /// statted anymore are still counted. With `realpath`, matches are counted
+    /// and sized as the paths they resolve to.
+    pub fn walk_aggregate(&self) -> GlobAggregate {
+        let mut count: u32 = 0;
+        let mut total_size: u64 = 0;
+        let fs = self.file_system();
+
+        if self.realpath {
+            // Count and size what the results resolve to, as `walk_sync` reports them
+            let abs_cwd = strip_windows_extended_prefix(
+                self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
+            );
+            let mut result_buffer = String::with_capacity(self.estimate_path_buffer_capacity());
+            let mut seen = AHashSet::new();
+            self.walk_stream_with_file_types(|data| {
+                let (result, _) = self.format_path_data(&data, &abs_cwd, &mut result_buffer);
+                let Some(resolved) = self.resolve_realpath(result, &abs_cwd) else {
+                    return;
+                };
+                let real = self.result_abs_path(&resolved, &abs_cwd);
+                if self.unique && !seen.insert(resolved) {
+                    return;
+                }
+                count = count.saturating_add(1);
+                if let Some(len) = fs
+                    .metadata(&real)
+                    .ok()
+                    .filter(|m| m.is_file)
+                    .and_then(|m| m.len)
+                {
+                    total_size += len;
+                }
+            });
+            return GlobAggregate {
+                count,
+                total_size: total_size as f64,
+            };
+        }
+
+        self.walk_stream_with_file_types_until(None, |data, size| {
+            count = count.saturating_add(1);
+            if data.is_file {
+                // Only stat files the walker didn't already
+                let size = size.or_else(|| {
+                    fs.metadata(&self.cwd.join(&data.path))
+                        .ok()
+                        .and_then(|m| m.len)
+                });
+                total_size += size.unwrap_or(0);
+            }
+            true
+        });
+        GlobAggregate {
+            count,
+            total_size: total_size as f64,
+        }
+    }
+
+    /// Count matches per containing directory, cut to `depth` segments.
+    ///
+    /// Expects relative, `/`-separated result paths (see `glob_histogram`).
+    pub fn walk_histogram(&self, depth: usize) -> Vec<DirCount> {
+        let mut counts: AHashMap<String, u32> = AHashMap::new();
+        self.walk_stream_with_file_types(|data| {
+            let parent = data.path.rsplit_once('/').map_or("", |(parent, _)| parent);
+            let dir = match parent.match_indices('/').nth(depth.saturating_sub(1)) {
+                _ if parent.is_empty() || depth == 0 => ".",
+                Some((end, _)) => &parent[..end],
+                None => parent,
+            };
+            match counts.get_mut(dir) {
+                Some(count) => *count = count.saturating_add(1),
+                None => {
+                    counts.insert(dir.to_string(), 1);
+                }
+            }
+        });
+
+        let mut histogram: Vec<DirCount> = counts
+            .into_iter()
+            .map(|(dir, count)| DirCount { dir, count })
+            .collect();
+        histogram.sort_by(|a, b| walk_order(&a.dir, &b.dir));
+        histogram
+    }
+
+    /// Walk on a background thread, sending PathData results through a bounded
+    /// channel of `capacity` entries.
+    ///
+    /// The walk blocks while the channel is full, so it never runs more than
+    /// `capacity` results ahead of the consumer, and it stops at the next result
+    /// once the receiver is dropped or closed.
+    pub fn spawn_stream_with_file_types(self, capacity: usize) -> mpsc::Receiver<PathData> {
+        self.spawn_checked_stream_with_file_types(capacity).0
+    }
+
+    /// Like `spawn_stream_with_file_types`, also returning the outcome of the
+    /// walk (see `check_walk`). It is set before the channel closes.
+    pub fn spawn_checked_stream_with_file_types(
+        self,
+        capacity: usize,
+    ) -> (mpsc::Receiver<PathData>, WalkOutcome) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let outcome = WalkOutcome::default();
+        let walk_outcome = outcome.clone();
+        std::thread::spawn(move || {
+            self.walk_stream_with_file_types_until(None, |data, _| {
+                sender.blocking_send(data).is_ok()
+            });
+            walk_outcome.set(self.check_walk(()));
+        });
+        (receiver, outcome)
+    }
+
+    /// Return up to `page_size` results that come after `start_after` (a
+    /// cwd-relative path) in walk order, plus the relative path of the last
+    /// result when more results follow.
+    ///
+    /// Resuming is only well-defined when the walk order is deterministic, i.e.
+    /// with sorted directory entries and a serial walker (see `glob_page`). With
+    /// `realpath`, results are resolved and the cursor is still the walked path.
+    pub fn walk_page(
+        &self,
+        start_after: Option<&str>,
+        page_size: usize,
+    ) -> (Vec<String>, Option<String>) {
+        let abs_cwd = strip_windows_extended_prefix(
+            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
+        );
+        let mut result_buffer = String::with_capacity(self.estimate_path_buffer_capacity());
+        let mut results = Vec::with_capacity(page_size);
+        let mut last = None;
+        let mut has_more = false;
+
+        // A real path may repeat one reported on an earlier page, so with realpath
+        // the walk starts over and only results past the cursor are kept
+        let mut seen_real = AHashSet::new();
+        let dedup_real = self.realpath && self.unique;
+        let walk_from = if dedup_real { None } else { start_after };
+
+        self.walk_stream_with_file_types_until(walk_from, |data, _| {
+            let (result, normalized) = self.format_path_data(&data, &abs_cwd, &mut result_buffer);
+            let result = if self.realpath {
+                match self.resolve_realpath(result, &abs_cwd) {
+                    Some(resolved) if !dedup_real || seen_real.insert(resolved.clone()) => resolved,
+                    _ => return true,
+                }
+            } else {
+                result
+            };
+            if walk_from.is_none()
+                && start_after.is_some_and(|cursor| walk_order(&normalized, cursor).is_le())
+            {
+                return true;
+            }
+            if results.len() == page_size {
+                has_more = true;
+                return false;
+            }
+            results.push(result);
+            last = Some(normalized);
+            true
+        });
+
+        (results, if has_more { last } else { None })
+    }
+
+    /// Format a streamed result the way `walk_sync` does, returning it with the
+    /// `/`-separated path it was walked at.
+    fn format_path_data(
+        &self,
+        data: &PathData,
+        abs_cwd: &Path,
+        result_buffer: &mut String,
+    ) -> (String, String) {
+        let normalized = if self.should_normalize_backslashes() {
+            data.path.clone()
+        } else {
+            data.path.replace('\\', "/")
+        };
+        let result = if normalized == "." {
+            self.build_cwd_result_path(abs_cwd, result_buffer)
+        } else {
+            self.build_result_path(
+                &normalized,
+                data.is_directory,
+                data.is_symlink,
+                abs_cwd,
+                result_buffer,
+            )
+        };
+        (result, normalized)
+    }
+
+    /// Like `walk_stream_with_file_types`, but stops walking as soon as the
+    /// callback returns false. The callback also gets the size of a regular
+    /// file when the walker already knows it.
+    ///
+    /// With `start_after`, results up to and including that cwd-relative path in
+    /// walk order are skipped, and directories lying entirely before it are pruned.
+    fn walk_stream_with_file_types_until<F>(&self, start_after: Option<&str>, mut callback: F)
+    where
+        F: FnMut(PathData, Option<u64>) -> bool,
+    {
+        // Whether a symlinked path is kept depends on results that may come later
+        if self.canonical_only {
+            for data in self.walk_sync_with_file_types() {
+                // The same form of the path that `walk_page` hands out as a cursor
+                let after_cursor = start_after.is_none_or(|cursor| {
+                    let path = if self.should_normalize_backslashes() {
+                        Cow::Borrowed(data.path.as_str())
+                    } else {
+                        Cow::Owned(data.path.replace('\\', "/"))
+                    };
+                    walk_order(&path, cursor).is_gt()
+                });
+                if after_cursor && !callback(data, None) {
+                    break;
+                }
+            }
+            return;
+        }
+
+        // If maxDepth is negative, return empty results
+        if let Some(d) = self.max_depth {
+            if d < 0 {
+                return;
+            }
+        }
+
+        let mut seen = self.walk_seen_paths(self.estimate_result_capacity());
+        let mut ignored_dirs: AHashSet<String> = AHashSet::with_capacity(8);
+        let mut matched_parents: AHashSet<String> = if self.include_child_matches {
+            AHashSet::new()
+        } else {
+            AHashSet::with_capacity(64)
+        };
+
+        let include_cwd = self.patterns.iter().any(pattern_matches_cwd);
+
+        // Strip Windows extended-length prefix (\\?\) to match glob v13 behavior
+        let abs_cwd = strip_windows_extended_prefix(
+            self.cwd.canonicalize().unwrap_or_else(|_| self.cwd.clone()),
+        );
+        let (walk_root, prefix_to_strip) = self.calculate_walk_root();
+
+        let adjusted_walk_options = if let Some(ref prefix) = prefix_to_strip {
+            let prefix_depth = prefix.split('/').filter(|s| !s.is_empty()).count();
+            if let Some(max_d) = self.walk_options.max_depth {
+                if max_d <= prefix_depth {
+                    self.walk_options.clone().max_depth(Some(0))
+                } else {
+                    self.walk_options
+                        .clone()
+                        .max_depth(Some(max_d - prefix_depth))
+                }
+            } else {
+                self.walk_options.clone()
+            }
+        } else {
+            self.walk_options.clone()
+        };
+
+        let prune_filter = self.dir_prune_filter(
+            Arc::clone(&self.patterns),
+            prefix_to_strip.clone(),
+            None,
+            &abs_cwd,
+        );
+        let prune_filter: Option<DirPruneFilter> = match start_after {
+            Some(cursor) => {
+                let cursor = cursor.to_string();
+                let prefix = prefix_to_strip.clone();
+                Some(Box::new(move |dir_path: &str| {
+                    let path_from_cwd = match prefix {
+                        Some(ref prefix) if dir_path.is_empty() => Cow::Borrowed(prefix.as_str()),
+                        Some(ref prefix) => {
+                            Cow::Owned(format!("{}{dir_path}", prefix_with_separator(prefix)))
+                        }
+                        None => Cow::Borrowed(dir_path),
+                    };
+                    !subtree_precedes(&path_from_cwd, &cursor)
+                        && prune_filter.as_ref().is_none_or(|f| f(dir_path))
+                }))
+            }
+            None => prune_filter,
+        };
+
+        let walker = new_walker(
+            walk_root.clone(),
+            self.with_accurate_symlinks(adjusted_walk_options),
+            prune_filter,
+        );
+
+        let has_ignore_filter = self.ignore_filter.is_some();
+
+        for entry in walker.walk().inspect(|e| self.record_visit(e.is_dir())) {
+            let path = entry.path();
+
+            let rel_path_from_walk_root = match path.strip_prefix(&walk_root) {
+                Ok(p) => p,
+                Err(_) => continue,
+            };
+            let rel_str_from_walk_root = rel_path_from_walk_root.to_string_lossy();
+            let is_walk_root_entry = rel_str_from_walk_root.is_empty();
+
+            let normalized = self.normalize_path(
+                &rel_str_from_walk_root,
+                &prefix_to_strip,
+                is_walk_root_entry,
+            );
+
+            if self.is_in_ignored_dir(&normalized, &ignored_dirs) {
+                continue;
+            }
+
+            if has_ignore_filter {
+                let rel_path = if prefix_to_strip.is_some() {
+                    PathBuf::from(normalized.as_ref())
+                } else {
+                    rel_path_from_walk_root.to_path_buf()
+                };
+                let abs_path = abs_cwd.join(&rel_path);
+                let ignore_filter = self.ignore_filter.as_ref().unwrap();
+
+                if ignore_filter.should_ignore(&normalized, &abs_path) {
+                    if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
+                        ignored_dirs.insert(normalized.into_owned());
+                    }
+                    continue;
+                }
+
+                if entry.is_dir() && ignore_filter.children_ignored(&normalized, &abs_path) {
+                    ignored_dirs.insert(normalized.to_string());
+                }
+            }
+
+            if let Some(cursor) = start_after {
+                let path = if is_walk_root_entry && prefix_to_strip.is_none() {
+                    "."
+                } else {
+                    normalized.as_ref()
+                };
+                if walk_order(path, cursor).is_le() {
+                    continue;
+                }
+            }
+
+            if is_walk_root_entry && prefix_to_strip.is_none() {
+                if include_cwd && !self.nodir {
+                    if let Some(ref ignore_filter) = self.ignore_filter {
+                        if ignore_filter.should_ignore(".", &abs_cwd) {
+                            continue;
+                        }
+                    }
+
+                    let result_path = ".".to_string();
+                    if seen.insert(&result_path)
+                        && !callback(
+                            PathData {
+                                path: result_path,
+                                is_directory: true,
+                                is_file: false,
+                                is_symlink: entry.is_symlink(),
+                                link_target: self.link_target(&entry),
+                                is_empty_dir: self.empty_dir_flag(&entry, true),
+                            },
+                            None,
+                        )
+                    {
+                        return;
+                    }
+                }
+                continue;
+            }
+
+            if normalized.is_empty() {
+                continue;
+            }
+
+            if self.nodir && entry.is_dir() {
+                continue;
+            }
+
+            if !self.dot
+                && (!self.path_allowed_by_dot_rules(&normalized)
+                    || self.hidden_by_attribute(&normalized))
+            {
+                continue;
+            }
+
+            if !self.include_child_matches
+                && self.is_child_of_matched(&normalized, &matched_parents)
+            {
+                continue;
+            }
+
+            let is_dir = entry.is_dir();
+
+            let matches = if !self.any_pattern_requires_dir {
+                self.patterns
+                    .iter()
+                    .any(|p| match p.matches_fast(&normalized) {
+                        Some(result) => result,
+                        None => p.matches(&normalized),
+                    })
+            } else {
+                self.patterns.iter().any(|p| {
+                    let path_matches = match p.matches_fast(&normalized) {
+                        Some(result) => result,
+                        None => p.matches(&normalized),
+                    };
+                    if path_matches && p.requires_dir() {
+                        is_dir_or_dir_link(
+                            self.file_system(),
+                            is_dir,
+                            entry.is_symlink(),
+                            entry.path(),
+                        )
+                    } else {
+                        path_matches
+                    }
+                })
+            };
+
+            if matches && !self.sequentially_excluded(&normalized, is_dir) {
+                // Convert separators for output: use backslashes on Windows without posix
+                let output_path = if self.should_normalize_backslashes() {
+                    normalized.into_owned()
+                } else {
+                    normalized.replace('/', "\\")
+                };
+                if seen.insert(&output_path) {
+                    // When includeChildMatches is false, track this path to exclude its children
+                    // (use the normalized path with forward slashes for internal tracking)
+                    if !self.include_child_matches {
+                        matched_parents.insert(output_path.replace('\\', "/"));
+                    }
+
+                    let data = PathData {
+                        path: output_path,
+                        is_directory: is_dir,
+                        is_file: entry.is_file(),
+                        is_symlink: entry.is_symlink(),
+                        link_target: self.link_target(&entry),
+                        is_empty_dir: self.empty_dir_flag(&entry, is_dir),
+                    };
+                    if !callback(data, entry.size()) {
+                        return;
+                    }
+                }
+            }
+        }
+
+        self.note_depth_limit(&walker);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::TempDir;
+
+    /// Convert a forward-slash path to platform-appropriate separators for test assertions.
+    /// On Windows without posix mode, glob outputs backslashes.
+    /// On Unix, glob outputs forward slashes.
+    fn p(path: &str) -> String {
+        #[cfg(target_os = "windows")]
+        {
+            path.replace('/', "\\")
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            path.to_string()
+        }
+    }
+
+    fn create_test_fixture() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+
+        File::create(base.join("foo.txt")).unwrap();
+        File::create(base.join("bar.txt")).unwrap();
+        File::create(base.join("baz.js")).unwrap();
+
+        // Dotfiles at root
+        File::create(base.join(".hidden")).unwrap();
+        File::create(base.join(".gitignore")).unwrap();
+
+        fs::create_dir_all(base.join("src")).unwrap();
+        File::create(base.join("src/main.js")).unwrap();
+        File::create(base.join("src/util.js")).unwrap();
+
+        fs::create_dir_all(base.join("src/lib")).unwrap();
+        File::create(base.join("src/lib/helper.js")).unwrap();
+
+        // Hidden directory
+        fs::create_dir_all(base.join(".git")).unwrap();
+        File::create(base.join(".git/config")).unwrap();
+        File::create(base.join(".git/HEAD")).unwrap();
+
+        // Dotfile inside regular directory
+        File::create(base.join("src/.env")).unwrap();
+
+        temp
+    }
+
+    fn make_opts(cwd: &str) -> GlobOptions {
+        GlobOptions {
+            cwd: Some(cwd.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn make_opts_with_dot(cwd: &str, dot: bool) -> GlobOptions {
+        GlobOptions {
+            cwd: Some(cwd.to_string()),
+            dot: Some(dot),
+            ..Default::default()
+        }
+    }
+
+    fn make_opts_with_follow(cwd: &str, follow: bool) -> GlobOptions {
+        GlobOptions {
+            cwd: Some(cwd.to_string()),
+            follow: Some(follow),
+            ..Default::default()
+        }
+    }
+
+    fn make_opts_with_max_depth(cwd: &str, max_depth: i32) -> GlobOptions {
+        GlobOptions {
+            cwd: Some(cwd.to_string()),
+            max_depth: Some(max_depth),
+            ..Default::default()
+        }
+    }
+
+    fn make_opts_with_nodir(cwd: &str, nodir: bool) -> GlobOptions {
+        GlobOptions {
+            cwd: Some(cwd.to_string()),
+            nodir: Some(nodir),
+            ..Default::default()
+        }
+    }
+
+    fn make_opts_with_dot_relative(cwd: &str, dot_relative: bool) -> GlobOptions {
+        GlobOptions {
+            cwd: Some(cwd.to_string()),
+            dot_relative: Some(dot_relative),
+            ..Default::default()
+        }
+    }
+
+    fn make_opts_with_mark(cwd: &str, mark: bool) -> GlobOptions {
+        GlobOptions {
+            cwd: Some(cwd.to_string()),
+            mark: Some(mark),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_simple_wildcard() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*.txt".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        assert!(!results.contains(&"baz.js".to_string()));
+    }
+
+    #[test]
+    fn test_globstar() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*.js".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        assert!(results.contains(&"baz.js".to_string()));
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+        assert!(results.contains(&p("src/lib/helper.js")));
+        assert!(!results.contains(&"foo.txt".to_string()));
+    }
+
+    #[test]
+    fn test_question_mark() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "???.txt".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+    }
+
+    #[test]
+    fn test_nested_path() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "src/*.js".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+        assert!(!results.contains(&p("src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_double_globstar() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "src/**/*.js".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+        assert!(results.contains(&p("src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_trailing_globstar_forms_include_direct_children() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("a/b")).unwrap();
+        File::create(base.join("a/file.txt")).unwrap();
+        File::create(base.join("a/b/file.txt")).unwrap();
+        let cwd = base.to_string_lossy().to_string();
+        let walk = |pattern: &str| {
+            let mut results = Glob::new(pattern.to_string(), make_opts(&cwd)).walk_sync();
+            results.sort();
+            results
+        };
+
+        assert_eq!(
+            walk("a/**/*"),
+            vec![p("a/b"), p("a/b/file.txt"), p("a/file.txt")]
+        );
+        assert_eq!(
+            walk("a/**"),
+            vec![
+                "a".to_string(),
+                p("a/b"),
+                p("a/b/file.txt"),
+                p("a/file.txt")
+            ]
+        );
+        assert_eq!(walk("a/**/*.txt"), vec![p("a/b/file.txt"), p("a/file.txt")]);
+    }
+
+    #[test]
+    fn test_absolute_option() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let glob = Glob::new(
+            "*.txt".to_string(),
+            GlobOptions {
+                cwd: Some(cwd.clone()),
+                absolute: Some(true),
+                ..Default::default()
+            },
+        );
+        let results = glob.walk_sync();
+
+        // All results should be absolute paths
+        for result in &results {
+            assert!(
+                std::path::Path::new(result).is_absolute(),
+                "Path should be absolute: {result}"
+            );
+        }
+        assert_eq!(results.len(), 2); // foo.txt and bar.txt
+    }
+
+    #[test]
+    fn test_absolute_with_posix() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let glob = Glob::new(
+            "*.txt".to_string(),
+            GlobOptions {
+                cwd: Some(cwd.clone()),
+                absolute: Some(true),
+                posix: Some(true),
+                ..Default::default()
+            },
+        );
+        let results = glob.walk_sync();
+
+        // All results should use forward slashes (POSIX style)
+        for result in &results {
+            assert!(
+                !result.contains('\\'),
+                "Path should use forward slashes: {result}"
+            );
+        }
+        assert_eq!(results.len(), 2); // foo.txt and bar.txt
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_native_separators_by_default_on_windows() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let walk = |pattern: &str, opts: GlobOptions| {
+            let glob = Glob::new(pattern.to_string(), opts);
+            let mut results = glob.walk_sync();
+            let mut streamed = Vec::new();
+            glob.walk_stream(|r| streamed.push(r));
+            results.sort();
+            streamed.sort();
+            assert_eq!(results, streamed, "{pattern}");
+            results
+        };
+
+        // Static, shallow, prefix-scoped and full walks all use `\`
+        assert_eq!(
+            walk("src/lib/helper.js", make_opts(&cwd)),
+            vec!["src\\lib\\helper.js"]
+        );
+        assert_eq!(
+            walk("src/**/*.js", make_opts(&cwd)),
+            vec!["src\\lib\\helper.js", "src\\main.js", "src\\util.js"]
+        );
+        let marked = GlobOptions {
+            mark: Some(true),
+            dot_relative: Some(true),
+            ..make_opts(&cwd)
+        };
+        assert_eq!(walk("*/", marked.clone()), vec![".\\src\\"]);
+        assert_eq!(walk("src/*/", marked), vec![".\\src\\lib\\"]);
+
+        // Absolute results don't mix in the pattern's `/`
+        let absolute = GlobOptions {
+            absolute: Some(true),
+            mark: Some(true),
+            ..make_opts(&cwd)
+        };
+        for result in walk("src/lib/", absolute.clone())
+            .into_iter()
+            .chain(walk("src/**/*.js", absolute.clone()))
+            .chain(walk("*", absolute))
+        {
+            assert!(!result.contains('/'), "{result}");
+        }
+
+        // posix: true forces forward slashes
+        let posix = GlobOptions {
+            posix: Some(true),
+            ..make_opts(&cwd)
+        };
+        assert_eq!(
+            walk("src/lib/helper.js", posix.clone()),
+            vec!["src/lib/helper.js"]
+        );
+        assert_eq!(
+            walk("src/**/*.js", posix),
+            vec!["src/lib/helper.js", "src/main.js", "src/util.js"]
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_absolute_with_posix_drive_letter_root() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let glob = Glob::new(
+            "*.txt".to_string(),
+            GlobOptions {
+                absolute: Some(true),
+                posix: Some(true),
+                ..make_opts(&cwd)
+            },
+        );
+
+        // C:\...\foo.txt comes back as //?/C:/.../foo.txt, drive intact
+        let abs_cwd = strip_windows_extended_prefix(temp.path().canonicalize().unwrap());
+        let expected_dir = format!("//?/{}", abs_cwd.to_string_lossy().replace('\\', "/"));
+        let mut results = glob.walk_sync();
+        results.sort();
+        assert_eq!(
+            results,
+            vec![
+                format!("{expected_dir}/bar.txt"),
+                format!("{expected_dir}/foo.txt")
+            ]
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_absolute_with_posix_unc_root() {
+        let glob = Glob::new(
+            "*.txt".to_string(),
+            GlobOptions {
+                absolute: Some(true),
+                posix: Some(true),
+                ..Default::default()
+            },
+        );
+
+        // UNC roots keep their leading // and share instead of gaining a drive prefix
+        let unc = Path::new(r"\\server\share\dir\foo.txt");
+        assert_eq!(glob.format_path(unc), "//server/share/dir/foo.txt");
+        let mut buffer = String::new();
+        assert_eq!(
+            glob.format_path_into_buffer(unc, &mut buffer),
+            "//server/share/dir/foo.txt"
+        );
+
+        let drive = Path::new(r"C:\dir\foo.txt");
+        assert_eq!(glob.format_path(drive), "//?/C:/dir/foo.txt");
+        assert_eq!(
+            glob.format_path_into_buffer(drive, &mut buffer),
+            "//?/C:/dir/foo.txt"
+        );
+    }
+
+    #[test]
+    fn test_brace_expansion() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*.{txt,js}".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        assert!(results.contains(&"baz.js".to_string()));
+    }
+
+    #[test]
+    fn test_brace_expansion_paths() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "{src,lib}/**/*.js".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // src/ matches
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+        assert!(results.contains(&p("src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_brace_empty_alternative() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        for name in ["file.js", "file.min.js", "file.map.js", "src.js"] {
+            File::create(base.join(name)).unwrap();
+        }
+        fs::create_dir(base.join("src")).unwrap();
+        File::create(base.join("src/index.js")).unwrap();
+
+        let opts = make_opts(&base.to_string_lossy());
+        let mut results = Glob::new("file{,.min}.js".to_string(), opts.clone()).walk_sync();
+        results.sort();
+        assert_eq!(results, vec!["file.js", "file.min.js"]);
+
+        // Optional path segment
+        let mut results = Glob::new("{,src/}*.js".to_string(), opts).walk_sync();
+        results.sort();
+        assert_eq!(
+            results,
+            vec![
+                "file.js".to_string(),
+                "file.map.js".to_string(),
+                "file.min.js".to_string(),
+                "src.js".to_string(),
+                p("src/index.js"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nobrace_option() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*.{txt,js}".to_string(),
+            GlobOptions {
+                cwd: Some(temp.path().to_string_lossy().to_string()),
+                nobrace: Some(true),
+                ..Default::default()
+            },
+        );
+        let results = glob.walk_sync();
+
+        // With nobrace, {txt,js} is treated literally, so nothing should match
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_brace_numeric_sequence() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+
+        // Create files matching a numeric sequence
+        File::create(base.join("file1.txt")).unwrap();
+        File::create(base.join("file2.txt")).unwrap();
+        File::create(base.join("file3.txt")).unwrap();
+        File::create(base.join("file4.txt")).unwrap();
+
+        let glob = Glob::new(
+            "file{1..3}.txt".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        assert!(results.contains(&"file1.txt".to_string()));
+        assert!(results.contains(&"file2.txt".to_string()));
+        assert!(results.contains(&"file3.txt".to_string()));
+        assert!(!results.contains(&"file4.txt".to_string())); // not in {1..3}
+    }
+
+    // Dot file handling tests
+
+    #[test]
+    fn test_dot_false_excludes_dotfiles() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*".to_string(),
+            make_opts_with_dot(&temp.path().to_string_lossy(), false),
+        );
+        let results = glob.walk_sync();
+
+        // Should include regular files
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+
+        // Should NOT include dotfiles
+        assert!(!results.contains(&".hidden".to_string()));
+        assert!(!results.contains(&".gitignore".to_string()));
+    }
+
+    #[test]
+    fn test_dot_true_includes_dotfiles() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*".to_string(),
+            make_opts_with_dot(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // Should include regular files
+        assert!(results.contains(&"foo.txt".to_string()));
+
+        // Should include dotfiles
+        assert!(results.contains(&".hidden".to_string()));
+        assert!(results.contains(&".gitignore".to_string()));
+        assert!(results.contains(&".git".to_string()));
+    }
+
+    #[test]
+    fn test_dot_false_excludes_dotdirs_content() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*".to_string(),
+            make_opts_with_dot(&temp.path().to_string_lossy(), false),
+        );
+        let results = glob.walk_sync();
+
+        // Should include regular nested files
+        assert!(results.contains(&p("src/main.js")));
+
+        // Should NOT include files inside .git
+        assert!(!results.contains(&p(".git/config")));
+        assert!(!results.contains(&p(".git/HEAD")));
+    }
+
+    #[test]
+    fn test_dot_true_includes_dotdirs_content() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*".to_string(),
+            make_opts_with_dot(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // Should include files inside .git
+        assert!(results.contains(&p(".git/config")));
+        assert!(results.contains(&p(".git/HEAD")));
+    }
+
+    #[test]
+    fn test_explicit_dot_pattern_matches_without_dot_option() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            ".hidden".to_string(),
+            make_opts_with_dot(&temp.path().to_string_lossy(), false),
+        );
+        let results = glob.walk_sync();
+
+        // Explicit .hidden pattern should match even with dot:false
+        assert!(results.contains(&".hidden".to_string()));
+    }
+
+    #[test]
+    fn test_explicit_dotdir_pattern_matches_without_dot_option() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            ".git/*".to_string(),
+            make_opts_with_dot(&temp.path().to_string_lossy(), false),
+        );
+        let results = glob.walk_sync();
+
+        // Explicit .git/* pattern should match even with dot:false
+        assert!(results.contains(&p(".git/config")));
+        assert!(results.contains(&p(".git/HEAD")));
+    }
+
+    #[test]
+    fn test_globstar_dotdir_pattern() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/.env".to_string(),
+            make_opts_with_dot(&temp.path().to_string_lossy(), false),
+        );
+        let results = glob.walk_sync();
+
+        // **/.env should match src/.env even with dot:false
+        assert!(results.contains(&p("src/.env")));
+    }
+
+    #[test]
+    fn test_extglob_dot_alternative_matches_without_dot_option() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let walk = |pattern: &str| {
+            let glob = Glob::new(pattern.to_string(), make_opts_with_dot(&cwd, false));
+            let mut results = glob.walk_sync();
+            results.sort();
+            results
+        };
+
+        assert_eq!(walk("src/@(.env|config)"), vec![p("src/.env")]);
+        assert_eq!(
+            walk("**/@(.env|main.js)"),
+            vec![p("src/.env"), p("src/main.js")]
+        );
+        assert_eq!(walk("?(.)hidden"), vec![".hidden"]);
+        assert!(walk("src/@(env|config)").is_empty());
+
+        // Single-segment patterns take the shallow readdir, which must agree
+        assert_eq!(walk(".*"), vec![".git", ".gitignore", ".hidden"]);
+    }
+
+    #[test]
+    fn test_default_dot_is_false() {
+        let temp = create_test_fixture();
+        let glob = Glob::new("*".to_string(), make_opts(&temp.path().to_string_lossy()));
+        let results = glob.walk_sync();
+
+        // Default should be dot:false - no dotfiles
+        assert!(!results.contains(&".hidden".to_string()));
+        assert!(results.contains(&"foo.txt".to_string()));
+    }
+
+    // Symlink tests (Unix only)
+
+    #[cfg(unix)]
+    fn create_symlink_fixture() -> TempDir {
+        use std::os::unix::fs::symlink;
+
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+
+        // Create regular directories and files
+        fs::create_dir_all(base.join("a/b/c")).unwrap();
+        File::create(base.join("a/b/c/file.txt")).unwrap();
+        File::create(base.join("a/b/file2.txt")).unwrap();
+
+        // Create a symlink from a/symlink -> a/b
+        symlink(base.join("a/b"), base.join("a/symlink")).unwrap();
+
+        // Create a broken symlink
+        fs::create_dir_all(base.join("broken")).unwrap();
+        symlink("this-does-not-exist", base.join("broken/link")).unwrap();
+
+        temp
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_realpath_broken_symlinks() {
+        let temp = create_symlink_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let run = |pattern: &str, keep_broken: Option<bool>| {
+            let opts = GlobOptions {
+                realpath: Some(true),
+                realpath_keep_broken: keep_broken,
+                ..make_opts(&cwd)
+            };
+            let glob = Glob::new(pattern.to_string(), opts);
+            let mut streamed = Vec::new();
+            glob.walk_stream(|r| streamed.push(r));
+            let mut results = glob.walk_sync();
+            results.sort();
+            streamed.sort();
+            assert_eq!(results, streamed);
+            results
+        };
+
+        // Broken symlinks can't be resolved, so they are dropped by default
+        assert!(run("broken/*", None).is_empty());
+        // ...or kept under their own path
+        assert_eq!(run("broken/*", Some(true)), vec!["broken/link"]);
+
+        // Links that resolve are reported at their target, once
+        assert_eq!(run("a/symlink", None), vec!["a/b"]);
+        assert_eq!(run("a/*/file2.txt", Some(true)), vec!["a/b/file2.txt"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_canonical_only_drops_symlinked_duplicates() {
+        let temp = create_symlink_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let run = |pattern: &str, canonical_only: bool| {
+            let opts = GlobOptions {
+                follow: Some(true),
+                canonical_only: Some(canonical_only),
+                ..make_opts(&cwd)
+            };
+            let glob = Glob::new(pattern.to_string(), opts);
+            let mut streamed = Vec::new();
+            glob.walk_stream(|r| streamed.push(r));
+            let mut results = glob.walk_sync();
+            results.sort();
+            streamed.sort();
+            assert_eq!(results, streamed);
+            results
+        };
+
+        // With plain follow the file is reported under both paths
+        assert_eq!(
+            run("a/**/file2.txt", false),
+            vec!["a/b/file2.txt", "a/symlink/file2.txt"]
+        );
+        assert_eq!(run("a/**/file2.txt", true), vec!["a/b/file2.txt"]);
+        assert_eq!(
+            run("a/**", true),
+            vec!["a", "a/b", "a/b/c", "a/b/c/file.txt", "a/b/file2.txt"]
+        );
+
+        // Only reachable through the link: kept under its own path, not rewritten
+        assert_eq!(run("a/symlink/*.txt", true), vec!["a/symlink/file2.txt"]);
+        // Broken links can't be resolved and are kept
+        assert_eq!(run("broken/*", true), vec!["broken/link"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_canonical_only_applies_to_every_entry_point() {
+        let temp = create_symlink_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let expected = ["a", "a/b", "a/b/c", "a/b/c/file.txt", "a/b/file2.txt"];
+        let sorted = |mut paths: Vec<String>| {
+            paths.sort();
+            paths
+        };
+
+        let opts = GlobOptions {
+            follow: Some(true),
+            canonical_only: Some(true),
+            sort_dir_entries: Some(true),
+            ..make_opts(&cwd)
+        };
+        let glob = Glob::new("a/**".to_string(), opts.clone());
+        let paths = |data: Vec<PathData>| sorted(data.into_iter().map(|d| d.path).collect());
+
+        assert_eq!(sorted(glob.walk_sync()), expected);
+        assert_eq!(paths(glob.walk_sync_with_file_types()), expected);
+        let mut streamed = Vec::new();
+        glob.walk_stream_with_file_types(|d| streamed.push(d));
+        assert_eq!(paths(streamed), expected);
+        assert_eq!(
+            sorted(
+                glob.walk_sync_annotated()
+                    .into_iter()
+                    .map(|a| a.path)
+                    .collect()
+            ),
+            expected
+        );
+        assert_eq!(sorted(glob.walk_sync_grouped(false).remove(0)), expected);
+        assert_eq!(
+            sorted(
+                glob.walk_sync_depths()
+                    .into_iter()
+                    .map(|d| d.path)
+                    .collect()
+            ),
+            expected
+        );
+        assert_eq!(glob.walk_aggregate().count, 5);
+        let histogram = glob.walk_histogram(1);
+        assert_eq!(histogram.iter().map(|d| d.count).sum::<u32>(), 5);
+
+        // Paging resumes after the cursor over the same filtered results
+        let mut paged = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let (page, next) = glob.walk_page(cursor.as_deref(), 2);
+            paged.extend(page);
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(sorted(paged), expected);
+
+        let mut receiver = Glob::new("a/**".to_string(), opts).spawn_stream_with_file_types(2);
+        let mut iterated = Vec::new();
+        while let Some(data) = receiver.blocking_recv() {
+            iterated.push(data);
+        }
+        assert_eq!(paths(iterated), expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_realpath_applies_to_every_entry_point() {
+        let temp = create_symlink_fixture();
+        fs::write(temp.path().join("a/b/c/file.txt"), "abc").unwrap();
+        fs::write(temp.path().join("a/b/file2.txt"), "12345").unwrap();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let expected = ["a", "a/b", "a/b/c", "a/b/c/file.txt", "a/b/file2.txt"];
+        let sorted = |mut paths: Vec<String>| {
+            paths.sort();
+            paths
+        };
+
+        // Paths through a/symlink resolve to (and are merged with) the ones under a/b
+        let opts = GlobOptions {
+            follow: Some(true),
+            realpath: Some(true),
+            sort_dir_entries: Some(true),
+            ..make_opts(&cwd)
+        };
+        let glob = Glob::new("a/**".to_string(), opts);
+        assert_eq!(sorted(glob.walk_sync()), expected);
+        assert_eq!(
+            sorted(
+                glob.walk_sync_annotated()
+                    .into_iter()
+                    .map(|a| a.path)
+                    .collect()
+            ),
+            expected
+        );
+        assert_eq!(sorted(glob.walk_sync_grouped(false).remove(0)), expected);
+        assert_eq!(
+            glob.walk_aggregate(),
+            GlobAggregate {
+                count: 5,
+                total_size: 8.0
+            }
+        );
+
+        // Paging resumes after the cursor without repeating a real path
+        let mut paged = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let (page, next) = glob.walk_page(cursor.as_deref(), 2);
+            paged.extend(page);
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(sorted(paged), expected);
+
+        // Broken links are dropped, or kept with realpathKeepBroken
+        for (keep_broken, expected) in [(None, vec![]), (Some(true), vec!["broken/link"])] {
+            let glob = Glob::new(
+                "broken/*".to_string(),
+                GlobOptions {
+                    realpath: Some(true),
+                    realpath_keep_broken: keep_broken,
+                    ..make_opts(&cwd)
+                },
+            );
+            assert_eq!(glob.walk_sync(), expected);
+            assert_eq!(glob.walk_sync_grouped(false).remove(0), expected);
+            assert_eq!(
+                glob.walk_sync_annotated()
+                    .into_iter()
+                    .map(|a| a.path)
+                    .collect::<Vec<_>>(),
+                expected
+            );
+            assert_eq!(glob.walk_page(None, 10).0, expected);
+            assert_eq!(glob.walk_aggregate().count, expected.len() as u32);
+        }
+    }
+
+    // A file literally named `**` is only reachable through an escaped pattern
+    #[cfg(unix)]
+    fn create_globstar_name_fixture() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("a/b")).unwrap();
+        File::create(base.join("**")).unwrap();
+        File::create(base.join("a/**")).unwrap();
+        File::create(base.join("a/b/c.txt")).unwrap();
+        File::create(base.join("foo.txt")).unwrap();
+        temp
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_escaped_globstar_matches_literal_name() {
+        use crate::pattern::escape_pattern;
+
+        let temp = create_globstar_name_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let run = |pattern: String, opts: GlobOptions| {
+            let mut results = Glob::new(pattern, opts).walk_sync();
+            results.sort();
+            results
+        };
+
+        for windows_paths_no_escape in [false, true] {
+            let opts = || GlobOptions {
+                windows_paths_no_escape: Some(windows_paths_no_escape),
+                ..make_opts(&cwd)
+            };
+            let escaped = escape_pattern("**", windows_paths_no_escape);
+            assert_eq!(run(escaped.clone(), opts()), vec!["**"]);
+            assert_eq!(run(format!("a/{escaped}"), opts()), vec!["a/**"]);
+            assert_eq!(
+                run(format!("{escaped}/../foo.txt"), opts()),
+                Vec::<String>::new()
+            );
+        }
+
+        // Unescaped, `**` stays the globstar and picks up everything
+        assert_eq!(
+            run("**".to_string(), make_opts(&cwd)),
+            vec!["**", ".", "a", "a/**", "a/b", "a/b/c.txt", "foo.txt"]
+        );
+        assert_eq!(
+            run("a/**".to_string(), make_opts(&cwd)),
+            vec!["a", "a/**", "a/b", "a/b/c.txt"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_nocase_escaped_patterns_match_literal_names() {
+        use crate::pattern::escape_pattern;
+
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("dir[1]")).unwrap();
+        for file in ["foo*.txt", "foo.txt", "Bar(1).js", "dir[1]/a?.MD"] {
+            File::create(base.join(file)).unwrap();
+        }
+        let cwd = base.to_string_lossy().to_string();
+
+        for windows_paths_no_escape in [false, true] {
+            let run = |raw: &str, prefix: &str| {
+                let pattern = format!("{prefix}{}", escape_pattern(raw, windows_paths_no_escape));
+                let opts = GlobOptions {
+                    nocase: Some(true),
+                    windows_paths_no_escape: Some(windows_paths_no_escape),
+                    ..make_opts(&cwd)
+                };
+                Glob::new(pattern, opts).walk_sync()
+            };
+            for prefix in ["", "**/"] {
+                assert_eq!(run("Foo*.TXT", prefix), vec!["foo*.txt"]);
+                assert_eq!(run("bar(1).JS", prefix), vec!["Bar(1).js"]);
+                assert_eq!(run("DIR[1]/A?.md", prefix), vec!["dir[1]/a?.MD"]);
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_realpath_formatting() {
+        use std::os::unix::fs::symlink;
+
+        let temp = create_symlink_fixture();
+        let outside = TempDir::new().unwrap();
+        File::create(outside.path().join("out.txt")).unwrap();
+        symlink(
+            outside.path().join("out.txt"),
+            temp.path().join("a/out-link"),
+        )
+        .unwrap();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let run = |pattern: &str, opts: GlobOptions| {
+            let opts = GlobOptions {
+                realpath: Some(true),
+                ..opts
+            };
+            let mut results = Glob::new(pattern.to_string(), opts).walk_sync();
+            results.sort();
+            results
+        };
+
+        // Targets outside cwd are reached with ..
+        let outside_rel = relative_path(
+            &temp.path().canonicalize().unwrap(),
+            &outside.path().canonicalize().unwrap(),
+        );
+        let out = format!("{}/out.txt", outside_rel.to_string_lossy());
+        assert_eq!(run("a/out-link", make_opts(&cwd)), vec![out.clone()]);
+        // ...and dotRelative leaves those alone
+        let dot_relative = GlobOptions {
+            dot_relative: Some(true),
+            ..make_opts(&cwd)
+        };
+        assert_eq!(run("a/out-link", dot_relative), vec![out]);
+
+        // absolute gives the canonical target
+        let absolute = GlobOptions {
+            absolute: Some(true),
+            ..make_opts(&cwd)
+        };
+        let real = outside.path().canonicalize().unwrap().join("out.txt");
+        assert_eq!(
+            run("a/out-link", absolute),
+            vec![real.to_string_lossy().to_string()]
+        );
+
+        // A link to a directory is marked once it resolves to one
+        let mark = GlobOptions {
+            mark: Some(true),
+            ..make_opts(&cwd)
+        };
+        assert_eq!(run("a/symlink", mark), vec!["a/b/"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_no_follow() {
+        let temp = create_symlink_fixture();
+        let glob = Glob::new(
+            "a/**/*.txt".to_string(),
+            make_opts_with_follow(&temp.path().to_string_lossy(), false),
+        );
+        let results = glob.walk_sync();
+
+        // Without follow, we should only get files in a/b/, not through symlink
+        assert!(results.contains(&p("a/b/c/file.txt")));
+        assert!(results.contains(&p("a/b/file2.txt")));
+
+        // We should NOT see files through the symlink (symlink/...)
+        assert!(!results.iter().any(|r| r.contains("symlink")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fast_paths_with_circular_and_broken_symlinks() {
+        let temp = create_symlink_fixture();
+        let base = temp.path();
+        std::os::unix::fs::symlink("loop", base.join("loop")).unwrap();
+        std::os::unix::fs::symlink("ping", base.join("pong")).unwrap();
+        std::os::unix::fs::symlink("pong", base.join("ping")).unwrap();
+        let cwd = base.to_string_lossy().to_string();
+        let abs_cwd = base.canonicalize().unwrap().to_string_lossy().to_string();
+
+        for follow in [false, true] {
+            let run = |pattern: &str, absolute: bool| {
+                let opts = GlobOptions {
+                    absolute: Some(absolute),
+                    mark: Some(true),
+                    ..make_opts_with_follow(&cwd, follow)
+                };
+                let glob = Glob::new(pattern.to_string(), opts);
+                assert!(matches!(
+                    glob.strategy(),
+                    WalkStrategy::Static | WalkStrategy::Shallow
+                ));
+                let mut results = glob.walk_sync();
+                results.sort();
+                results
+            };
+
+            // Links that can't be resolved are reported as themselves, not as directories
+            for link in ["loop", "ping", "broken/link"] {
+                assert_eq!(run(link, false), vec![link.to_string()], "follow {follow}");
+                assert_eq!(
+                    run(link, true),
+                    vec![format!("{abs_cwd}/{link}")],
+                    "follow {follow}"
+                );
+                assert!(run(&format!("{link}/"), false).is_empty());
+                assert!(run(&format!("{link}/x"), false).is_empty());
+            }
+            assert_eq!(run("l[o]op", false), vec!["loop"]);
+            assert_eq!(
+                run("p?ng", true),
+                vec![format!("{abs_cwd}/ping"), format!("{abs_cwd}/pong")]
+            );
+
+            // An absolute result names the symlink, not its target
+            assert_eq!(run("a/symlink", true), vec![format!("{abs_cwd}/a/symlink")]);
+
+            // `..` is still resolved, lexically
+            let name = base.file_name().unwrap().to_string_lossy();
+            assert_eq!(
+                run(&format!("../{name}/a/symlink"), true),
+                vec![format!("{abs_cwd}/a/symlink")]
+            );
+        }
+
+        // The reported case: `../rp/real/f` from `rp` is `rp/real/f`
+        let rp = TempDir::new().unwrap();
+        fs::create_dir_all(rp.path().join("rp/real")).unwrap();
+        File::create(rp.path().join("rp/real/f")).unwrap();
+        let cwd = rp.path().join("rp").to_string_lossy().to_string();
+        let opts = GlobOptions {
+            absolute: Some(true),
+            ..make_opts(&cwd)
+        };
+        let glob = Glob::new("../rp/real/f".to_string(), opts);
+        assert_eq!(glob.strategy(), WalkStrategy::Static);
+        let real = rp.path().canonicalize().unwrap().join("rp/real/f");
+        assert_eq!(glob.walk_sync(), vec![real.to_string_lossy().to_string()]);
+    }
+
+    #[test]
+    fn test_detect_empty_dirs() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("empty")).unwrap();
+        fs::create_dir_all(base.join("full/nested")).unwrap();
+        fs::create_dir_all(base.join("hidden_only")).unwrap();
+        File::create(base.join("hidden_only/.keep")).unwrap();
+        File::create(base.join("file.txt")).unwrap();
+        let cwd = base.to_string_lossy().to_string();
+        let flag_of = |results: &[PathData], path: &str| {
+            results
+                .iter()
+                .find(|r| r.path == p(path))
+                .unwrap_or_else(|| panic!("{path} not in results"))
+                .is_empty_dir
+        };
+
+        let glob = Glob::new(
+            "**".to_string(),
+            GlobOptions {
+                detect_empty_dirs: Some(true),
+                ..make_opts(&cwd)
+            },
+        );
+        let mut streamed = Vec::new();
+        glob.walk_stream_with_file_types(|d| streamed.push(d));
+
+        for results in [glob.walk_sync_with_file_types(), streamed] {
+            assert_eq!(flag_of(&results, "."), Some(false));
+            assert_eq!(flag_of(&results, "empty"), Some(true));
+            assert_eq!(flag_of(&results, "full"), Some(false));
+            assert_eq!(flag_of(&results, "full/nested"), Some(true));
+            assert_eq!(flag_of(&results, "hidden_only"), Some(false));
+            assert_eq!(flag_of(&results, "file.txt"), None);
+        }
+
+        // Off by default
+        let results = Glob::new("**".to_string(), make_opts(&cwd)).walk_sync_with_file_types();
+        assert_eq!(flag_of(&results, "empty"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_link_targets() {
+        let temp = create_symlink_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let target_of = |results: &[PathData], path: &str| {
+            results
+                .iter()
+                .find(|r| r.path == path)
+                .unwrap_or_else(|| panic!("{path} not in results"))
+                .link_target
+                .clone()
+        };
+
+        for follow in [false, true] {
+            let glob = Glob::new(
+                "{a,broken}/*".to_string(),
+                GlobOptions {
+                    read_link_targets: Some(true),
+                    ..make_opts_with_follow(&cwd, follow)
+                },
+            );
+            let mut streamed = Vec::new();
+            glob.walk_stream_with_file_types(|d| streamed.push(d));
+
+            for results in [glob.walk_sync_with_file_types(), streamed] {
+                let expected = temp.path().join("a/b").to_string_lossy().to_string();
+                assert_eq!(target_of(&results, "a/symlink"), Some(expected));
+                assert_eq!(
+                    target_of(&results, "broken/link"),
+                    Some("this-does-not-exist".to_string())
+                );
+                assert_eq!(target_of(&results, "a/b"), None);
+            }
+        }
+
+        // Off by default
+        let glob = Glob::new("a/*".to_string(), make_opts(&cwd));
+        let results = glob.walk_sync_with_file_types();
+        assert_eq!(target_of(&results, "a/symlink"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_types_symlink_flag_when_following() {
+        let temp = create_symlink_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+
+        for follow in [false, true] {
+            let glob = Glob::new("a/*".to_string(), make_opts_with_follow(&cwd, follow));
+            let mut streamed = Vec::new();
+            glob.walk_stream_with_file_types(|d| streamed.push(d));
+
+            for results in [glob.walk_sync_with_file_types(), streamed] {
+                let link = results.iter().find(|r| r.path == "a/symlink").unwrap();
+                assert!(link.is_symlink, "follow: {follow}");
+                assert_eq!(link.is_directory, follow);
+                let dir = results.iter().find(|r| r.path == "a/b").unwrap();
+                assert!(!dir.is_symlink);
+            }
+        }
+    }
+
+    #[test]
+    fn test_trailing_slash_forms_are_equivalent() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        for mark in [false, true] {
+            let run = |pattern: &str| {
+                let opts = GlobOptions {
+                    mark: Some(mark),
+                    ..make_opts(&cwd)
+                };
+                let glob = Glob::new(pattern.to_string(), opts);
+                if mark {
+                    // Typed results are never marked, so only compare string walks
+                    let mut results = glob.walk_sync();
+                    results.sort();
+                    return results;
+                }
+                let [first, rest @ ..] = walk_all_four(&glob);
+                for other in rest {
+                    assert_eq!(first, other, "{pattern}");
+                }
+                first
+            };
+            for (canonical, forms) in [
+                ("src/", ["src//", "src/.", "src/./"]),
+                ("*/", ["*//", "*/.", "*/./"]),
+                ("src/*/", ["src/*//", "src/*/.", "src/*/./"]),
+            ] {
+                let expected = run(canonical);
+                assert!(!expected.is_empty(), "{canonical}");
+                for form in forms {
+                    assert_eq!(run(form), expected, "{form} (mark: {mark})");
+                }
+            }
+            // A file never matches, whichever form asks for a directory
+            for form in ["foo.txt/", "foo.txt//", "foo.txt/."] {
+                assert!(run(form).is_empty(), "{form}");
+            }
+        }
+        let matches = |path: &str, pattern: &str| {
+            matches_path(path.to_string(), Either::A(pattern.to_string()), None).unwrap()
+        };
+        assert!(matches("src/", "src/."));
+        assert!(!matches("src", "src/."));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_trailing_slash_matches_symlinked_dir() {
+        let temp = create_symlink_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let run = |pattern: &str, follow: bool| {
+            let glob = Glob::new(pattern.to_string(), make_opts_with_follow(&cwd, follow));
+            let [first, rest @ ..] = walk_all_four(&glob);
+            for other in rest {
+                assert_eq!(first, other, "{pattern}");
+            }
+            first
+        };
+
+        for follow in [false, true] {
+            assert_eq!(run("a/*/", follow), vec![p("a/b"), p("a/symlink")]);
+            assert_eq!(run("a/symlink/", follow), vec![p("a/symlink")]);
+            assert_eq!(run("{a,broken}/*/", follow), vec![p("a/b"), p("a/symlink")]);
+            // A broken symlink is not a directory
+            assert!(run("broken/*/", follow).is_empty());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_with_follow() {
+        let temp = create_symlink_fixture();
+        let glob = Glob::new(
+            "a/**/*.txt".to_string(),
+            make_opts_with_follow(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // With follow, we should see files through the symlink too
+        assert!(results.contains(&p("a/b/c/file.txt")));
+        assert!(results.contains(&p("a/b/file2.txt")));
+
+        // We should also see the same files through the symlink
+        assert!(results.contains(&p("a/symlink/c/file.txt")));
+        assert!(results.contains(&p("a/symlink/file2.txt")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_follow_canonical_only_no_duplicates() {
+        let temp = create_symlink_fixture();
+        let opts = GlobOptions {
+            cwd: Some(temp.path().to_string_lossy().to_string()),
+            follow: Some(true),
+            canonical_only: Some(true),
+            ..Default::default()
+        };
+        let glob = Glob::new("a/**/*.txt".to_string(), opts);
+        let results = glob.walk_sync();
+
+        // Files are reported under their real path only
+        assert!(results.contains(&p("a/b/c/file.txt")));
+        assert!(results.contains(&p("a/b/file2.txt")));
+        assert!(!results.iter().any(|r| r.contains("symlink")));
+
+        // No two results point at the same file
+        let mut real_paths: Vec<PathBuf> = results
+            .iter()
+            .map(|r| fs::canonicalize(temp.path().join(r)).unwrap())
+            .collect();
+        let total = real_paths.len();
+        real_paths.sort();
+        real_paths.dedup();
+        assert_eq!(real_paths.len(), total);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_canonical_only_keeps_unreported_targets() {
+        use std::os::unix::fs::symlink;
+
+        let temp = create_symlink_fixture();
+        symlink(temp.path().join("a/b"), temp.path().join("top-link")).unwrap();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let run = |pattern: &str, max_depth: Option<i32>| {
+            let opts = GlobOptions {
+                follow: Some(true),
+                canonical_only: Some(true),
+                max_depth,
+                ..make_opts(&cwd)
+            };
+            let mut results = Glob::new(pattern.to_string(), opts).walk_sync();
+            results.sort();
+            results
+        };
+
+        // The pattern never reaches the real path, so the link path is the only match
+        assert_eq!(run("a/s*/*.txt", None), vec![p("a/symlink/file2.txt")]);
+
+        // maxDepth cuts off the real path but not the shorter route through the link
+        assert_eq!(run("**/file2.txt", Some(2)), vec![p("top-link/file2.txt")]);
+        assert_eq!(run("**/file2.txt", None), vec![p("a/b/file2.txt")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_broken_symlink_handled_gracefully() {
+        let temp = create_symlink_fixture();
+        let glob = Glob::new(
+            "broken/*".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // Should include the broken symlink itself (not crash)
+        assert!(results.contains(&p("broken/link")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_broken_symlink_with_follow() {
+        let temp = create_symlink_fixture();
+        let glob = Glob::new(
+            "broken/**".to_string(),
+            make_opts_with_follow(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // Should include the directory and symlink, not crash
+        assert!(results.contains(&"broken".to_string()));
+        assert!(results.contains(&p("broken/link")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_explicit_pattern() {
+        let temp = create_symlink_fixture();
+        let glob = Glob::new(
+            "a/symlink/**/*.txt".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let _results = glob.walk_sync();
+
+        // When explicitly matching through a symlink, we should traverse it
+        // even without follow:true (default behavior)
+        // Note: This test may fail until we implement more nuanced symlink handling
+        // For now, follow:false means no symlinks are followed
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_default_follow_is_false() {
+        let temp = create_symlink_fixture();
+        let glob = Glob::new(
+            "a/**/*.txt".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // Default should be follow:false - don't traverse symlinks
+        assert!(!results.iter().any(|r| r.contains("symlink")));
+    }
+
+    // maxDepth tests
+
+    #[test]
+    fn test_max_depth_negative() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*".to_string(),
+            make_opts_with_max_depth(&temp.path().to_string_lossy(), -1),
+        );
+        let results = glob.walk_sync();
+
+        // Negative maxDepth should return empty results
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_max_depth_zero() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**".to_string(),
+            make_opts_with_max_depth(&temp.path().to_string_lossy(), 0),
+        );
+        let results = glob.walk_sync();
+
+        // maxDepth: 0 with ** should return just "." (cwd)
+        assert_eq!(results.len(), 1);
+        assert!(results.contains(&".".to_string()));
+    }
+
+    #[test]
+    fn test_max_depth_one() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*".to_string(),
+            make_opts_with_max_depth(&temp.path().to_string_lossy(), 1),
+        );
+        let results = glob.walk_sync();
+
+        // maxDepth: 1 should return only immediate children (depth 1)
+        // Should include: foo.txt, bar.txt, baz.js, src (but not .hidden due to dot:false default)
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        assert!(results.contains(&"baz.js".to_string()));
+        assert!(results.contains(&"src".to_string()));
+
+        // Should NOT include nested files
+        assert!(!results.contains(&p("src/main.js")));
+        assert!(!results.contains(&p("src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_max_depth_two() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*.js".to_string(),
+            make_opts_with_max_depth(&temp.path().to_string_lossy(), 2),
+        );
+        let results = glob.walk_sync();
+
+        // maxDepth: 2 should include depth 1 and 2
+        assert!(results.contains(&"baz.js".to_string())); // depth 1
+        assert!(results.contains(&p("src/main.js"))); // depth 2
+        assert!(results.contains(&p("src/util.js"))); // depth 2
+
+        // Should NOT include depth 3+
+        assert!(!results.contains(&p("src/lib/helper.js"))); // depth 3
+    }
+
+    #[test]
+    fn test_max_depth_unlimited() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*.js".to_string(),
+            make_opts(&temp.path().to_string_lossy()), // no maxDepth = unlimited
+        );
+        let results = glob.walk_sync();
+
+        // Without maxDepth, should include all levels
+        assert!(results.contains(&"baz.js".to_string()));
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+        assert!(results.contains(&p("src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_max_depth_with_scoped_pattern() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "src/**/*.js".to_string(),
+            make_opts_with_max_depth(&temp.path().to_string_lossy(), 2),
+        );
+        let results = glob.walk_sync();
+
+        // maxDepth: 2 with src/** should get src/* (depth 2)
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+
+        // Should NOT include src/lib/* (depth 3)
+        assert!(!results.contains(&p("src/lib/helper.js")));
+    }
+
+    // nodir tests
+
+    #[test]
+    fn test_nodir_true_excludes_directories() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*".to_string(),
+            make_opts_with_nodir(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // Should include files
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        assert!(results.contains(&"baz.js".to_string()));
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/lib/helper.js")));
+
+        // Should NOT include directories
+        assert!(!results.contains(&"src".to_string()));
+        assert!(!results.contains(&p("src/lib")));
+    }
+
+    #[test]
+    fn test_nodir_false_includes_directories() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*".to_string(),
+            make_opts_with_nodir(&temp.path().to_string_lossy(), false),
+        );
+        let results = glob.walk_sync();
+
+        // Should include both files and directories
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"src".to_string()));
+        assert!(results.contains(&p("src/lib")));
+    }
+
+    #[test]
+    fn test_nodir_default_includes_directories() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*".to_string(),
+            make_opts(&temp.path().to_string_lossy()), // no nodir = includes dirs
+        );
+        let results = glob.walk_sync();
+
+        // Default behavior should include directories
+        assert!(results.contains(&"src".to_string()));
+        assert!(results.contains(&p("src/lib")));
+    }
+
+    #[test]
+    fn test_nodir_with_simple_pattern() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*".to_string(),
+            make_opts_with_nodir(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // Should include root files but not root directories
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        assert!(results.contains(&"baz.js".to_string()));
+
+        // Should NOT include src directory
+        assert!(!results.contains(&"src".to_string()));
+    }
+
+    #[test]
+    fn test_nodir_excludes_cwd_with_globstar() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**".to_string(),
+            make_opts_with_nodir(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // With nodir: true, "." (cwd) should NOT be included
+        // even though ** matches everything
+        assert!(!results.contains(&".".to_string()));
+
+        // But files should still be included
+        assert!(results.contains(&"foo.txt".to_string()));
+    }
+
+    #[test]
+    fn test_nodir_with_recursive_pattern() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*/**".to_string(),
+            make_opts_with_nodir(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // Should include nested files
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/lib/helper.js")));
+
+        // Should NOT include directory entries
+        assert!(!results.contains(&"src".to_string()));
+        assert!(!results.contains(&p("src/lib")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_nodir_with_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+
+        // Create directory structure
+        fs::create_dir_all(base.join("real_dir")).unwrap();
+        File::create(base.join("real_dir/file.txt")).unwrap();
+        File::create(base.join("normal.txt")).unwrap();
+
+        // Create a symlink to a directory
+        symlink(base.join("real_dir"), base.join("symlink_dir")).unwrap();
+
+        // Create a symlink to a file
+        symlink(base.join("normal.txt"), base.join("symlink_file")).unwrap();
+
+        // Test with nodir: true, follow: false (default)
+        // Symlinks are treated as files (not directories) when not followed
+        let glob = Glob::new(
+            "*".to_string(),
+            GlobOptions {
+                cwd: Some(base.to_string_lossy().to_string()),
+                nodir: Some(true),
+                follow: Some(false),
+                ..Default::default()
+            },
+        );
+        let results = glob.walk_sync();
+
+        // Should include the symlink to dir (since it's a symlink, not a dir, when not following)
+        assert!(results.contains(&"symlink_dir".to_string()));
+        // Should include symlink to file
+        assert!(results.contains(&"symlink_file".to_string()));
+        // Should include normal file
+        assert!(results.contains(&"normal.txt".to_string()));
+        // Should NOT include the real directory
+        assert!(!results.contains(&"real_dir".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_nodir_with_follow_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+
+        // Create directory structure
+        fs::create_dir_all(base.join("real_dir")).unwrap();
+        File::create(base.join("real_dir/file.txt")).unwrap();
+        File::create(base.join("normal.txt")).unwrap();
+
+        // Create a symlink to a directory
+        symlink(base.join("real_dir"), base.join("symlink_dir")).unwrap();
+
+        // Create a symlink to a file
+        symlink(base.join("normal.txt"), base.join("symlink_file")).unwrap();
+
+        // Test with nodir: true, follow: true
+        // When following symlinks, a symlink to a directory IS a directory
+        let glob = Glob::new(
+            "*".to_string(),
+            GlobOptions {
+                cwd: Some(base.to_string_lossy().to_string()),
+                nodir: Some(true),
+                follow: Some(true),
+                ..Default::default()
+            },
+        );
+        let results = glob.walk_sync();
+
+        // Should NOT include symlink to dir (because when followed, it's a directory)
+        assert!(!results.contains(&"symlink_dir".to_string()));
+        // Should include symlink to file (because when followed, it's a file)
+        assert!(results.contains(&"symlink_file".to_string()));
+        // Should include normal file
+        assert!(results.contains(&"normal.txt".to_string()));
+        // Should NOT include the real directory
+        assert!(!results.contains(&"real_dir".to_string()));
+    }
+
+    // dotRelative tests
+
+    #[test]
+    fn test_dot_relative_prepends_dot_slash() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*.txt".to_string(),
+            make_opts_with_dot_relative(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // All results should start with "./" or ".\\" on Windows
+        let expected_prefix = if cfg!(target_os = "windows") {
+            ".\\"
+        } else {
+            "./"
+        };
+        for result in &results {
+            assert!(
+                result.starts_with(expected_prefix),
+                "Path should start with '{expected_prefix}': {result}"
+            );
+        }
+        assert!(results.contains(&p("./foo.txt")));
+        assert!(results.contains(&p("./bar.txt")));
+    }
+
+    #[test]
+    fn test_dot_relative_false_no_prefix() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*.txt".to_string(),
+            make_opts_with_dot_relative(&temp.path().to_string_lossy(), false),
+        );
+        let results = glob.walk_sync();
+
+        // Results should NOT start with "./" or ".\"
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        for result in &results {
+            assert!(
+                !result.starts_with("./") && !result.starts_with(".\\"),
+                "Path should not start with './' or '.\\': {result}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_dot_relative_with_nested_paths() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*.js".to_string(),
+            make_opts_with_dot_relative(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // All results should start with "./"
+        assert!(results.contains(&p("./baz.js")));
+        assert!(results.contains(&p("./src/main.js")));
+        assert!(results.contains(&p("./src/util.js")));
+        assert!(results.contains(&p("./src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_dot_relative_default_is_false() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*.txt".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // Default should not have "./" prefix
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(!results.contains(&p("./foo.txt")));
+    }
+
+    // mark tests
+
+    #[test]
+    fn test_mark_appends_slash_to_directories() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*".to_string(),
+            make_opts_with_mark(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // Directories should end with "/"
+        assert!(results.contains(&p("src/")));
+
+        // Files should NOT end with "/"
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        assert!(!results.contains(&p("foo.txt/")));
+    }
+
+    #[test]
+    fn test_mark_false_no_trailing_slash() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*".to_string(),
+            make_opts_with_mark(&temp.path().to_string_lossy(), false),
+        );
+        let results = glob.walk_sync();
+
+        // Directories should NOT end with "/"
+        assert!(results.contains(&"src".to_string()));
+        assert!(!results.contains(&p("src/")));
+    }
+
+    #[test]
+    fn test_mark_with_nested_directories() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*".to_string(),
+            make_opts_with_mark(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // Nested directories should also have trailing slash
+        assert!(results.contains(&p("src/")));
+        assert!(results.contains(&p("src/lib/")));
+
+        // Files should not have trailing slash
+        assert!(results.contains(&p("src/main.js")));
+        assert!(!results.contains(&p("src/main.js/")));
+    }
+
+    #[test]
+    fn test_mark_with_globstar_cwd() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**".to_string(),
+            make_opts_with_mark(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // "." (cwd) should become "./" with mark:true
+        assert!(results.contains(&p("./")));
+        assert!(!results.contains(&".".to_string()));
+    }
+
+    #[test]
+    fn test_mark_default_is_false() {
+        let temp = create_test_fixture();
+        let glob = Glob::new("*".to_string(), make_opts(&temp.path().to_string_lossy()));
+        let results = glob.walk_sync();
+
+        // Default should not have trailing slash on directories
+        assert!(results.contains(&"src".to_string()));
+        assert!(!results.contains(&p("src/")));
+    }
+
+    #[test]
+    fn test_mark_with_dot_relative() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*".to_string(),
+            GlobOptions {
+                cwd: Some(temp.path().to_string_lossy().to_string()),
+                dot_relative: Some(true),
+                mark: Some(true),
+                ..Default::default()
+            },
+        );
+        let results = glob.walk_sync();
+
+        // Should have both "./" prefix and "/" suffix for directories
+        assert!(results.contains(&p("./src/")));
+
+        // Files should have "./" prefix but not "/" suffix
+        assert!(results.contains(&p("./foo.txt")));
+        assert!(!results.contains(&p("./foo.txt/")));
+    }
+
+    // matchBase tests
+
+    fn make_opts_with_match_base(cwd: &str, match_base: bool) -> GlobOptions {
+        GlobOptions {
+            cwd: Some(cwd.to_string()),
+            match_base: Some(match_base),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_match_base_true_matches_basename() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*.js".to_string(),
+            make_opts_with_match_base(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // With matchBase: true, *.js should match files at any depth
+        assert!(results.contains(&"baz.js".to_string()));
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+        assert!(results.contains(&p("src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_match_base_false_matches_root_only() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*.js".to_string(),
+            make_opts_with_match_base(&temp.path().to_string_lossy(), false),
+        );
+        let results = glob.walk_sync();
+
+        // With matchBase: false, *.js should only match at root level
+        assert!(results.contains(&"baz.js".to_string()));
+        assert!(!results.contains(&p("src/main.js")));
+        assert!(!results.contains(&p("src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_match_base_pattern_with_slash() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "src/*.js".to_string(),
+            make_opts_with_match_base(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // Pattern with / is used as-is even with matchBase: true
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+        // Should NOT match nested files (pattern has / so no **/ prepended)
+        assert!(!results.contains(&p("src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_match_base_default_is_false() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*.js".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // Default behavior should match only at root
+        assert!(results.contains(&"baz.js".to_string()));
+        assert!(!results.contains(&p("src/main.js")));
+    }
+
+    #[test]
+    fn test_match_base_with_brace_expansion_all_have_slash() {
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "{src,lib}/*.js".to_string(),
+            make_opts_with_match_base(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // Brace expansion with / in all parts - no matchBase transformation
+        assert!(results.contains(&p("src/main.js")));
+    }
+
+    #[test]
+    fn test_match_base_with_brace_expansion_one_has_slash() {
+        let temp = create_test_fixture();
+        // Pattern: b{*.js,/c} - one part has /, so matchBase doesn't apply to any
+        let glob = Glob::new(
+            "b{*.txt,/c}".to_string(),
+            make_opts_with_match_base(&temp.path().to_string_lossy(), true),
+        );
+        let results = glob.walk_sync();
+
+        // Original pattern has /, so matchBase doesn't apply
+        // b*.txt stays as b*.txt (matches at root)
+        // b/c stays as b/c
+        // So only exact matches at specified locations
+        // bar.txt matches b*.txt (at root)
+        assert!(results.contains(&"bar.txt".to_string()));
+    }
+
+    // Multiple patterns tests
+
+    #[test]
+    fn test_multiple_patterns_basic() {
+        let temp = create_test_fixture();
+        let glob = Glob::new_multi(
+            vec!["*.txt".to_string(), "*.js".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // Should match both .txt and .js files
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        assert!(results.contains(&"baz.js".to_string()));
+    }
+
+    #[test]
+    fn test_multiple_patterns_with_globstar() {
+        let temp = create_test_fixture();
+        let glob = Glob::new_multi(
+            vec!["*.txt".to_string(), "**/*.js".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // Should match root .txt and all .js files
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        assert!(results.contains(&"baz.js".to_string()));
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+        assert!(results.contains(&p("src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_multiple_patterns_deduplication() {
+        let temp = create_test_fixture();
+        let glob = Glob::new_multi(
+            vec!["*.txt".to_string(), "foo.txt".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // foo.txt should only appear once despite matching both patterns
+        let foo_count = results.iter().filter(|r| *r == "foo.txt").count();
+        assert_eq!(foo_count, 1);
+        assert!(results.contains(&"bar.txt".to_string()));
+    }
+
+    #[test]
+    fn test_multiple_patterns_disjoint() {
+        let temp = create_test_fixture();
+        let glob = Glob::new_multi(
+            vec!["foo.txt".to_string(), "baz.js".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        assert_eq!(results.len(), 2);
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"baz.js".to_string()));
+    }
+
+    #[test]
+    fn test_multiple_patterns_empty() {
+        let temp = create_test_fixture();
+        let glob = Glob::new_multi(Vec::new(), make_opts(&temp.path().to_string_lossy()));
+        let results = glob.walk_sync();
+
+        // Empty patterns array should match nothing
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_multiple_patterns_with_scoped() {
+        let temp = create_test_fixture();
+        let glob = Glob::new_multi(
+            vec!["src/*.js".to_string(), "*.txt".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // Should match src/*.js and root *.txt
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        // Should NOT match nested files
+        assert!(!results.contains(&p("src/lib/helper.js")));
+    }
+
+    // Depth-limited walking optimization tests (Task 2.5.1.3)
+
+    #[test]
+    fn test_depth_limited_simple_pattern() {
+        // Simple patterns like *.txt should only traverse root directory
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "*.txt".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // Should find files at root only
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        // Should NOT find nested files (and shouldn't even traverse there)
+        assert!(!results.iter().any(|r| r.contains('/')));
+    }
+
+    #[test]
+    fn test_depth_limited_one_level_pattern() {
+        // Pattern like src/*.js has depth 1
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "src/*.js".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // Should find src/*.js files
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+        // Should NOT find deeply nested files
+        assert!(!results.contains(&p("src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_depth_limited_two_level_pattern() {
+        // Pattern like src/lib/*.js has depth 2
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "src/lib/*.js".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // Should find src/lib/*.js files
+        assert!(results.contains(&p("src/lib/helper.js")));
+        // Should NOT find files at other depths
+        assert!(!results.contains(&"baz.js".to_string()));
+        assert!(!results.contains(&p("src/main.js")));
+    }
+
+    #[test]
+    fn test_depth_unlimited_with_globstar() {
+        // Pattern with ** should traverse unlimited depth
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*.js".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // Should find files at ALL depths
+        assert!(results.contains(&"baz.js".to_string()));
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+        assert!(results.contains(&p("src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_depth_limited_multiple_patterns_bounded() {
+        // Multiple patterns, all bounded - should use max depth
+        let temp = create_test_fixture();
+        let glob = Glob::new_multi(
+            vec!["*.txt".to_string(), "src/*.js".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // Should find root .txt and src/*.js
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        assert!(results.contains(&p("src/main.js")));
+        // Should NOT find deeply nested files
+        assert!(!results.contains(&p("src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_depth_limited_multiple_patterns_one_unlimited() {
+        // If any pattern has **, should traverse unlimited depth
+        let temp = create_test_fixture();
+        let glob = Glob::new_multi(
+            vec!["*.txt".to_string(), "**/*.js".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        // Should find files at all depths due to **/*.js pattern
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"baz.js".to_string()));
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/lib/helper.js")));
+    }
+
+    #[test]
+    fn test_depth_limited_user_max_depth_override() {
+        // User-provided maxDepth should take precedence over pattern depth
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "**/*.js".to_string(),
+            make_opts_with_max_depth(&temp.path().to_string_lossy(), 1),
+        );
+        let results = glob.walk_sync();
 
-            if self.nodir && entry.is_dir() {
-                continue;
-            }
+        // Even though pattern has **, maxDepth: 1 should limit to root only
+        assert!(results.contains(&"baz.js".to_string()));
+        assert!(!results.contains(&p("src/main.js")));
+    }
 
-            if !self.dot && !self.path_allowed_by_dot_rules(&normalized) {
-                continue;
-            }
+    // Prefix-based walk root optimization tests (Task 2.5.2.3)
 
-            if !self.include_child_matches
-                && self.is_child_of_matched(&normalized, &matched_parents)
-            {
-                continue;
-            }
+    #[test]
+    fn test_prefix_walk_root_scoped_pattern() {
+        // Pattern src/**/*.js should walk from src/ instead of cwd
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "src/**/*.js".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
 
-            let is_dir = entry.is_dir();
+        // Should find all js files under src/
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+        assert!(results.contains(&p("src/lib/helper.js")));
+        // Should NOT find root-level js
+        assert!(!results.contains(&"baz.js".to_string()));
+    }
 
-            let matches = if !self.any_pattern_requires_dir {
-                self.patterns
-                    .iter()
-                    .any(|p| match p.matches_fast(&normalized) {
-                        Some(result) => result,
-                        None => p.matches(&normalized),
-                    })
-            } else {
-                self.patterns.iter().any(|p| {
-                    let path_matches = match p.matches_fast(&normalized) {
-                        Some(result) => result,
-                        None => p.matches(&normalized),
-                    };
-                    if path_matches && p.requires_dir() {
-                        is_dir
-                    } else {
-                        path_matches
-                    }
-                })
-            };
+    #[test]
+    fn test_prefix_walk_root_deep_scoped_pattern() {
+        // Pattern src/lib/**/*.js should walk from src/lib/
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "src/lib/**/*.js".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
 
-            if matches {
-                // Convert separators for output: use backslashes on Windows without posix
-                let output_path = if self.should_normalize_backslashes() {
-                    normalized.into_owned()
-                } else {
-                    normalized.replace('/', "\\")
-                };
-                if seen.insert(output_path.clone()) {
-                    // When includeChildMatches is false, track this path to exclude its children
-                    // (use the normalized path with forward slashes for internal tracking)
-                    if !self.include_child_matches {
-                        matched_parents.insert(output_path.replace('\\', "/"));
-                    }
+        // Should find files under src/lib/
+        assert!(results.contains(&p("src/lib/helper.js")));
+        // Should NOT find files at other locations
+        assert!(!results.contains(&p("src/main.js")));
+        assert!(!results.contains(&"baz.js".to_string()));
+    }
 
-                    callback(PathData {
-                        path: output_path,
-                        is_directory: is_dir,
-                        is_file: entry.is_file(),
-                        is_symlink: entry.is_symlink(),
-                    });
-                }
-            }
-        }
+    #[test]
+    fn test_prefix_walk_root_nonexistent_prefix() {
+        // Pattern for non-existent directory should return empty
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "nonexistent/**/*.js".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
+
+        assert!(results.is_empty());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs::{self, File};
-    use tempfile::TempDir;
+    #[test]
+    fn test_prefix_walk_root_multiple_patterns_same_prefix() {
+        // Multiple patterns with same prefix should use that prefix
+        let temp = create_test_fixture();
+        let glob = Glob::new_multi(
+            vec!["src/**/*.js".to_string(), "src/**/*.ts".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
 
-    /// Convert a forward-slash path to platform-appropriate separators for test assertions.
-    /// On Windows without posix mode, glob outputs backslashes.
-    /// On Unix, glob outputs forward slashes.
-    fn p(path: &str) -> String {
-        #[cfg(target_os = "windows")]
-        {
-            path.replace('/', "\\")
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            path.to_string()
-        }
+        // Should find js files under src/
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/lib/helper.js")));
+        // Should NOT find root-level files
+        assert!(!results.contains(&"baz.js".to_string()));
     }
 
-    fn create_test_fixture() -> TempDir {
+    #[test]
+    fn test_prefix_walk_root_multiple_patterns_different_prefix() {
+        // Multiple patterns with different prefixes - should walk from common prefix or root
         let temp = TempDir::new().unwrap();
         let base = temp.path();
 
-        File::create(base.join("foo.txt")).unwrap();
-        File::create(base.join("bar.txt")).unwrap();
-        File::create(base.join("baz.js")).unwrap();
-
-        // Dotfiles at root
-        File::create(base.join(".hidden")).unwrap();
-        File::create(base.join(".gitignore")).unwrap();
-
-        fs::create_dir_all(base.join("src")).unwrap();
-        File::create(base.join("src/main.js")).unwrap();
-        File::create(base.join("src/util.js")).unwrap();
-
-        fs::create_dir_all(base.join("src/lib")).unwrap();
-        File::create(base.join("src/lib/helper.js")).unwrap();
-
-        // Hidden directory
-        fs::create_dir_all(base.join(".git")).unwrap();
-        File::create(base.join(".git/config")).unwrap();
-        File::create(base.join(".git/HEAD")).unwrap();
+        fs::create_dir_all(base.join("dir1")).unwrap();
+        fs::create_dir_all(base.join("dir2")).unwrap();
+        File::create(base.join("dir1/file.js")).unwrap();
+        File::create(base.join("dir2/file.ts")).unwrap();
+        File::create(base.join("root.txt")).unwrap();
 
-        // Dotfile inside regular directory
-        File::create(base.join("src/.env")).unwrap();
+        let glob = Glob::new_multi(
+            vec!["dir1/**/*.js".to_string(), "dir2/**/*.ts".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        let results = glob.walk_sync();
 
-        temp
+        // Should find files from both directories
+        assert!(results.contains(&p("dir1/file.js")));
+        assert!(results.contains(&p("dir2/file.ts")));
+        // Should NOT match root files
+        assert!(!results.contains(&"root.txt".to_string()));
     }
 
-    fn make_opts(cwd: &str) -> GlobOptions {
-        GlobOptions {
-            cwd: Some(cwd.to_string()),
-            ..Default::default()
-        }
-    }
+    #[test]
+    fn test_prefix_walk_root_with_max_depth() {
+        // Scoped pattern with maxDepth should adjust depth relative to cwd
+        let temp = create_test_fixture();
+        let glob = Glob::new(
+            "src/**/*.js".to_string(),
+            make_opts_with_max_depth(&temp.path().to_string_lossy(), 2),
+        );
+        let results = glob.walk_sync();
 
-    fn make_opts_with_dot(cwd: &str, dot: bool) -> GlobOptions {
-        GlobOptions {
-            cwd: Some(cwd.to_string()),
-            dot: Some(dot),
-            ..Default::default()
-        }
+        // maxDepth: 2 means up to depth 2 from cwd
+        // src is depth 1, src/* is depth 2, src/lib/* is depth 3
+        assert!(results.contains(&p("src/main.js")));
+        assert!(results.contains(&p("src/util.js")));
+        // src/lib/helper.js is depth 3, should be excluded
+        assert!(!results.contains(&p("src/lib/helper.js")));
     }
 
-    fn make_opts_with_follow(cwd: &str, follow: bool) -> GlobOptions {
-        GlobOptions {
-            cwd: Some(cwd.to_string()),
-            follow: Some(follow),
-            ..Default::default()
-        }
+    #[test]
+    fn test_longest_common_prefix() {
+        // Test the longest_common_prefix helper
+        assert_eq!(Glob::longest_common_prefix(&["src/lib", "src/bin"]), "src");
+        assert_eq!(Glob::longest_common_prefix(&["src", "test"]), "");
+        assert_eq!(
+            Glob::longest_common_prefix(&["packages/foo", "packages/bar"]),
+            "packages"
+        );
+        assert_eq!(Glob::longest_common_prefix(&["a/b/c", "a/b/d"]), "a/b");
+        assert_eq!(Glob::longest_common_prefix(&["x"]), "x");
+        assert_eq!(Glob::longest_common_prefix(&[]), "");
     }
 
-    fn make_opts_with_max_depth(cwd: &str, max_depth: i32) -> GlobOptions {
-        GlobOptions {
-            cwd: Some(cwd.to_string()),
-            max_depth: Some(max_depth),
-            ..Default::default()
-        }
-    }
+    // Directory pruning tests (Task 2.5.3.3)
 
-    fn make_opts_with_nodir(cwd: &str, nodir: bool) -> GlobOptions {
-        GlobOptions {
-            cwd: Some(cwd.to_string()),
-            nodir: Some(nodir),
-            ..Default::default()
-        }
-    }
+    #[test]
+    fn test_directory_pruning_scoped_pattern() {
+        // Pattern src/lib/**/*.js should only traverse src/lib, not test/ or other dirs
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
 
-    fn make_opts_with_dot_relative(cwd: &str, dot_relative: bool) -> GlobOptions {
-        GlobOptions {
-            cwd: Some(cwd.to_string()),
-            dot_relative: Some(dot_relative),
-            ..Default::default()
-        }
-    }
+        // Create a structure with multiple top-level directories
+        fs::create_dir_all(base.join("src/lib/deep")).unwrap();
+        fs::create_dir_all(base.join("test/unit")).unwrap();
+        fs::create_dir_all(base.join("docs")).unwrap();
 
-    fn make_opts_with_mark(cwd: &str, mark: bool) -> GlobOptions {
-        GlobOptions {
-            cwd: Some(cwd.to_string()),
-            mark: Some(mark),
-            ..Default::default()
-        }
-    }
+        File::create(base.join("src/lib/helper.js")).unwrap();
+        File::create(base.join("src/lib/deep/nested.js")).unwrap();
+        File::create(base.join("test/unit/test.js")).unwrap();
+        File::create(base.join("docs/readme.js")).unwrap();
 
-    #[test]
-    fn test_simple_wildcard() {
-        let temp = create_test_fixture();
         let glob = Glob::new(
-            "*.txt".to_string(),
+            "src/lib/**/*.js".to_string(),
             make_opts(&temp.path().to_string_lossy()),
         );
         let results = glob.walk_sync();
 
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-        assert!(!results.contains(&"baz.js".to_string()));
+        // Should find files under src/lib/
+        assert!(results.contains(&p("src/lib/helper.js")));
+        assert!(results.contains(&p("src/lib/deep/nested.js")));
+
+        // Should NOT find files in other directories
+        assert!(!results.contains(&p("test/unit/test.js")));
+        assert!(!results.contains(&p("docs/readme.js")));
     }
 
     #[test]
-    fn test_globstar() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "**/*.js".to_string(),
+    fn test_directory_pruning_multi_pattern() {
+        // Multiple patterns with different scopes - pruning should allow both paths
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+
+        fs::create_dir_all(base.join("src")).unwrap();
+        fs::create_dir_all(base.join("test")).unwrap();
+        fs::create_dir_all(base.join("docs")).unwrap();
+
+        File::create(base.join("src/main.js")).unwrap();
+        File::create(base.join("test/test.ts")).unwrap();
+        File::create(base.join("docs/readme.md")).unwrap();
+
+        let glob = Glob::new_multi(
+            vec!["src/**/*.js".to_string(), "test/**/*.ts".to_string()],
             make_opts(&temp.path().to_string_lossy()),
         );
         let results = glob.walk_sync();
 
-        assert!(results.contains(&"baz.js".to_string()));
+        // Should find files matching either pattern
         assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
-        assert!(results.contains(&p("src/lib/helper.js")));
-        assert!(!results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&p("test/test.ts")));
+
+        // Should NOT find files that don't match any pattern
+        assert!(!results.contains(&p("docs/readme.md")));
     }
 
     #[test]
-    fn test_question_mark() {
-        let temp = create_test_fixture();
+    fn test_directory_pruning_with_globstar_start() {
+        // Pattern **/*.js cannot prune directories (must visit all)
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+
+        fs::create_dir_all(base.join("a/b/c")).unwrap();
+        fs::create_dir_all(base.join("x/y/z")).unwrap();
+
+        File::create(base.join("a/b/c/file.js")).unwrap();
+        File::create(base.join("x/y/z/file.js")).unwrap();
+
         let glob = Glob::new(
-            "???.txt".to_string(),
+            "**/*.js".to_string(),
             make_opts(&temp.path().to_string_lossy()),
         );
         let results = glob.walk_sync();
 
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
+        // Should find files in both paths since ** matches anything
+        assert!(results.contains(&p("a/b/c/file.js")));
+        assert!(results.contains(&p("x/y/z/file.js")));
     }
 
     #[test]
-    fn test_nested_path() {
-        let temp = create_test_fixture();
+    fn test_directory_pruning_nested_match() {
+        // Pattern packages/*/src/**/*.ts - should only traverse packages/*/src paths
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+
+        fs::create_dir_all(base.join("packages/foo/src/utils")).unwrap();
+        fs::create_dir_all(base.join("packages/foo/test")).unwrap();
+        fs::create_dir_all(base.join("packages/bar/src")).unwrap();
+        fs::create_dir_all(base.join("other")).unwrap();
+
+        File::create(base.join("packages/foo/src/index.ts")).unwrap();
+        File::create(base.join("packages/foo/src/utils/helper.ts")).unwrap();
+        File::create(base.join("packages/foo/test/test.ts")).unwrap();
+        File::create(base.join("packages/bar/src/main.ts")).unwrap();
+        File::create(base.join("other/file.ts")).unwrap();
+
         let glob = Glob::new(
-            "src/*.js".to_string(),
+            "packages/*/src/**/*.ts".to_string(),
             make_opts(&temp.path().to_string_lossy()),
         );
         let results = glob.walk_sync();
 
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
-        assert!(!results.contains(&p("src/lib/helper.js")));
+        // Should find files under packages/*/src
+        assert!(results.contains(&p("packages/foo/src/index.ts")));
+        assert!(results.contains(&p("packages/foo/src/utils/helper.ts")));
+        assert!(results.contains(&p("packages/bar/src/main.ts")));
+
+        // Should NOT find files outside of packages/*/src
+        assert!(!results.contains(&p("packages/foo/test/test.ts")));
+        assert!(!results.contains(&p("other/file.ts")));
     }
 
+    // Multi-pattern optimization tests (Task 2.5.6.3)
+
     #[test]
-    fn test_double_globstar() {
+    fn test_multi_pattern_deduplication() {
+        // Duplicate patterns from brace expansion should be deduplicated
         let temp = create_test_fixture();
         let glob = Glob::new(
-            "src/**/*.js".to_string(),
+            "{*.txt,*.txt}".to_string(), // Brace expansion produces duplicates
             make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
 
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
-        assert!(results.contains(&p("src/lib/helper.js")));
-    }
+        // Only 1 pattern should be stored (duplicates removed)
+        assert_eq!(glob.patterns.len(), 1);
 
-    #[test]
-    fn test_absolute_option() {
-        let temp = create_test_fixture();
-        let cwd = temp.path().to_string_lossy().to_string();
-        let glob = Glob::new(
-            "*.txt".to_string(),
-            GlobOptions {
-                cwd: Some(cwd.clone()),
-                absolute: Some(true),
-                ..Default::default()
-            },
-        );
         let results = glob.walk_sync();
-
-        // All results should be absolute paths
-        for result in &results {
-            assert!(
-                std::path::Path::new(result).is_absolute(),
-                "Path should be absolute: {result}"
-            );
-        }
-        assert_eq!(results.len(), 2); // foo.txt and bar.txt
+        // foo.txt should only appear once
+        let foo_count = results.iter().filter(|r| *r == "foo.txt").count();
+        assert_eq!(foo_count, 1);
     }
 
     #[test]
-    fn test_absolute_with_posix() {
+    fn test_multi_pattern_fast_path_ordering() {
+        // Fast-path patterns should be sorted first for early matching
         let temp = create_test_fixture();
-        let cwd = temp.path().to_string_lossy().to_string();
-        let glob = Glob::new(
-            "*.txt".to_string(),
-            GlobOptions {
-                cwd: Some(cwd.clone()),
-                absolute: Some(true),
-                posix: Some(true),
-                ..Default::default()
-            },
+        let glob = Glob::new_multi(
+            vec![
+                "**/[a-z]*.js".to_string(), // Complex pattern (regex)
+                "*.txt".to_string(),        // Simple fast-path pattern
+                "**/*.ts".to_string(),      // Recursive fast-path pattern
+            ],
+            make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
 
-        // All results should use forward slashes (POSIX style)
-        for result in &results {
-            assert!(
-                !result.contains('\\'),
-                "Path should use forward slashes: {result}"
-            );
-        }
-        assert_eq!(results.len(), 2); // foo.txt and bar.txt
+        // Check that patterns are reordered with fast-path first
+        // First should be fast-path (*.txt or **/*.ts)
+        assert!(glob.patterns[0].fast_path().is_fast() || glob.patterns[1].fast_path().is_fast());
+
+        let results = glob.walk_sync();
+        // Should still find correct files
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        assert!(results.contains(&"baz.js".to_string()));
     }
 
     #[test]
-    fn test_brace_expansion() {
+    fn test_multi_pattern_cross_brace_deduplication() {
+        // Brace expansion across multiple patterns should deduplicate
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*.{txt,js}".to_string(),
+        let glob = Glob::new_multi(
+            vec![
+                "*.{txt,js}".to_string(), // Expands to *.txt, *.js
+                "*.txt".to_string(),      // Duplicate with above
+            ],
             make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
 
+        // Should have 2 unique patterns: *.txt, *.js (not 3)
+        assert_eq!(glob.patterns.len(), 2);
+
+        let results = glob.walk_sync();
         assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
         assert!(results.contains(&"baz.js".to_string()));
     }
 
     #[test]
-    fn test_brace_expansion_paths() {
+    fn test_multi_pattern_any_requires_dir() {
+        // Pre-computed field should correctly identify patterns requiring directories
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "{src,lib}/**/*.js".to_string(),
+
+        // Pattern without trailing slash
+        let glob1 = Glob::new("*".to_string(), make_opts(&temp.path().to_string_lossy()));
+        assert!(!glob1.any_pattern_requires_dir);
+
+        // Pattern with trailing slash
+        let glob2 = Glob::new("*/".to_string(), make_opts(&temp.path().to_string_lossy()));
+        assert!(glob2.any_pattern_requires_dir);
+
+        // Multiple patterns where only one requires dir
+        let glob3 = Glob::new_multi(
+            vec!["*.txt".to_string(), "src/".to_string()],
             make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
-
-        // src/ matches
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
-        assert!(results.contains(&p("src/lib/helper.js")));
+        assert!(glob3.any_pattern_requires_dir);
     }
 
     #[test]
-    fn test_nobrace_option() {
+    fn test_multi_pattern_fast_pattern_count() {
+        // Pre-computed fast pattern count
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*.{txt,js}".to_string(),
-            GlobOptions {
-                cwd: Some(temp.path().to_string_lossy().to_string()),
-                nobrace: Some(true),
-                ..Default::default()
-            },
+
+        // All fast-path patterns
+        let glob1 = Glob::new_multi(
+            vec!["*.txt".to_string(), "*.js".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
+        assert_eq!(glob1.fast_pattern_count, 2);
 
-        // With nobrace, {txt,js} is treated literally, so nothing should match
-        assert!(results.is_empty());
+        // Mix of fast and slow patterns
+        let glob2 = Glob::new_multi(
+            vec!["*.txt".to_string(), "**/[a-z]*.js".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        // *.txt is fast, **/[a-z]*.js is not
+        assert_eq!(glob2.fast_pattern_count, 1);
     }
 
     #[test]
-    fn test_brace_numeric_sequence() {
+    fn test_multi_pattern_many_patterns() {
+        // Test with many patterns to verify performance characteristics
         let temp = TempDir::new().unwrap();
         let base = temp.path();
 
-        // Create files matching a numeric sequence
-        File::create(base.join("file1.txt")).unwrap();
-        File::create(base.join("file2.txt")).unwrap();
-        File::create(base.join("file3.txt")).unwrap();
-        File::create(base.join("file4.txt")).unwrap();
-
-        let glob = Glob::new(
-            "file{1..3}.txt".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
+        // Create files for each pattern
+        for i in 0..10 {
+            File::create(base.join(format!("file{i}.txt"))).unwrap();
+            File::create(base.join(format!("file{i}.js"))).unwrap();
+            File::create(base.join(format!("file{i}.ts"))).unwrap();
+        }
 
-        assert!(results.contains(&"file1.txt".to_string()));
-        assert!(results.contains(&"file2.txt".to_string()));
-        assert!(results.contains(&"file3.txt".to_string()));
-        assert!(!results.contains(&"file4.txt".to_string())); // not in {1..3}
-    }
+        // Create glob with many patterns
+        let patterns: Vec<String> = (0..10)
+            .flat_map(|i| vec![format!("file{}.txt", i), format!("file{}.js", i)])
+            .collect();
 
-    // Dot file handling tests
+        let glob = Glob::new_multi(patterns, make_opts(&temp.path().to_string_lossy()));
 
-    #[test]
-    fn test_dot_false_excludes_dotfiles() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*".to_string(),
-            make_opts_with_dot(&temp.path().to_string_lossy(), false),
-        );
         let results = glob.walk_sync();
-
-        // Should include regular files
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-
-        // Should NOT include dotfiles
-        assert!(!results.contains(&".hidden".to_string()));
-        assert!(!results.contains(&".gitignore".to_string()));
+        assert_eq!(results.len(), 20); // 10 txt + 10 js files
     }
 
     #[test]
-    fn test_dot_true_includes_dotfiles() {
+    fn test_multi_pattern_all_match_same_file() {
+        // Multiple patterns that all match the same file
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*".to_string(),
-            make_opts_with_dot(&temp.path().to_string_lossy(), true),
+        let glob = Glob::new_multi(
+            vec![
+                "foo.txt".to_string(),
+                "*.txt".to_string(),
+                "foo.*".to_string(),
+                "**".to_string(),
+            ],
+            make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
 
-        // Should include regular files
-        assert!(results.contains(&"foo.txt".to_string()));
+        let results = glob.walk_sync();
 
-        // Should include dotfiles
-        assert!(results.contains(&".hidden".to_string()));
-        assert!(results.contains(&".gitignore".to_string()));
-        assert!(results.contains(&".git".to_string()));
+        // foo.txt should appear only once despite matching all patterns
+        let foo_count = results.iter().filter(|r| *r == "foo.txt").count();
+        assert_eq!(foo_count, 1);
     }
 
+    // Absolute pattern tests (Task 4.1.1)
+
+    #[cfg(unix)]
     #[test]
-    fn test_dot_false_excludes_dotdirs_content() {
+    fn test_absolute_pattern_unix() {
+        // Test absolute Unix path pattern
         let temp = create_test_fixture();
+        let abs_path = temp.path().to_string_lossy().to_string();
+
+        // Create an absolute pattern
+        let pattern = format!("{}/**/*.js", abs_path.replace('\\', "/"));
+
         let glob = Glob::new(
-            "**/*".to_string(),
-            make_opts_with_dot(&temp.path().to_string_lossy(), false),
+            pattern,
+            GlobOptions {
+                cwd: Some("/tmp".to_string()), // Different cwd shouldn't matter
+                ..Default::default()
+            },
         );
-        let results = glob.walk_sync();
 
-        // Should include regular nested files
-        assert!(results.contains(&p("src/main.js")));
+        let results = glob.walk_sync();
 
-        // Should NOT include files inside .git
-        assert!(!results.contains(&p(".git/config")));
-        assert!(!results.contains(&p(".git/HEAD")));
+        // Should find js files in the temp directory
+        // Results should be relative to the pattern root
+        assert!(!results.is_empty());
+        // Check that results contain the expected patterns
+        assert!(results
+            .iter()
+            .any(|r| r.contains("main.js") || r.contains("baz.js")));
     }
 
     #[test]
-    fn test_dot_true_includes_dotdirs_content() {
-        let temp = create_test_fixture();
+    fn test_absolute_pattern_nonexistent() {
+        // Absolute pattern pointing to nonexistent path should return empty
         let glob = Glob::new(
-            "**/*".to_string(),
-            make_opts_with_dot(&temp.path().to_string_lossy(), true),
+            "/nonexistent/path/**/*.txt".to_string(),
+            GlobOptions::default(),
         );
+
         let results = glob.walk_sync();
 
-        // Should include files inside .git
-        assert!(results.contains(&p(".git/config")));
-        assert!(results.contains(&p(".git/HEAD")));
+        assert!(results.is_empty());
     }
 
+    #[cfg(windows)]
     #[test]
-    fn test_explicit_dot_pattern_matches_without_dot_option() {
+    fn test_drive_letter_pattern() {
+        // Test Windows drive letter pattern
         let temp = create_test_fixture();
+        let abs_path = temp.path().to_string_lossy().to_string();
+
+        // Convert to POSIX-style path
+        let pattern = abs_path.replace('\\', "/");
+
         let glob = Glob::new(
-            ".hidden".to_string(),
-            make_opts_with_dot(&temp.path().to_string_lossy(), false),
+            format!("{}/**/*.txt", pattern),
+            GlobOptions {
+                platform: Some("win32".to_string()),
+                ..Default::default()
+            },
         );
+
         let results = glob.walk_sync();
 
-        // Explicit .hidden pattern should match even with dot:false
-        assert!(results.contains(&".hidden".to_string()));
+        // Should find txt files
+        assert!(!results.is_empty());
+        assert!(results
+            .iter()
+            .any(|r| r.contains("foo.txt") || r.contains("bar.txt")));
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_explicit_dotdir_pattern_matches_without_dot_option() {
+    fn test_absolute_pattern_with_literal_prefix() {
+        // Test that absolute patterns with literal prefixes work correctly
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            ".git/*".to_string(),
-            make_opts_with_dot(&temp.path().to_string_lossy(), false),
-        );
+        let abs_path = temp.path().to_string_lossy().to_string().replace('\\', "/");
+
+        // Pattern with absolute root + literal prefix
+        let pattern = format!("{abs_path}/src/**/*.js");
+
+        let glob = Glob::new(pattern, GlobOptions::default());
+
         let results = glob.walk_sync();
 
-        // Explicit .git/* pattern should match even with dot:false
-        assert!(results.contains(&p(".git/config")));
-        assert!(results.contains(&p(".git/HEAD")));
+        // Should find js files under src
+        assert!(results.iter().any(|r| r.contains("main.js")));
+        assert!(results.iter().any(|r| r.contains("helper.js")));
+        // Should NOT find root-level js
+        assert!(!results.iter().any(|r| r == "baz.js"));
     }
 
     #[test]
-    fn test_globstar_dotdir_pattern() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "**/.env".to_string(),
-            make_opts_with_dot(&temp.path().to_string_lossy(), false),
+    fn test_pattern_is_absolute() {
+        use crate::pattern::{Pattern, PatternOptions};
+
+        // Unix absolute path
+        let unix_pattern = Pattern::with_pattern_options(
+            "/usr/local/**/*.txt",
+            PatternOptions {
+                platform: Some("linux".to_string()),
+                ..Default::default()
+            },
         );
-        let results = glob.walk_sync();
+        assert!(unix_pattern.is_absolute());
+        assert_eq!(unix_pattern.root(), "/");
 
-        // **/.env should match src/.env even with dot:false
-        assert!(results.contains(&p("src/.env")));
+        // Windows drive pattern
+        let win_pattern = Pattern::with_pattern_options(
+            "C:/Users/**/*.txt",
+            PatternOptions {
+                platform: Some("win32".to_string()),
+                ..Default::default()
+            },
+        );
+        assert!(win_pattern.is_absolute());
+        assert!(win_pattern.is_drive());
+        assert_eq!(win_pattern.root(), "C:/");
+
+        // Relative pattern
+        let rel_pattern = Pattern::with_pattern_options("src/**/*.txt", PatternOptions::default());
+        assert!(!rel_pattern.is_absolute());
+        assert_eq!(rel_pattern.root(), "");
     }
 
     #[test]
-    fn test_default_dot_is_false() {
-        let temp = create_test_fixture();
-        let glob = Glob::new("*".to_string(), make_opts(&temp.path().to_string_lossy()));
-        let results = glob.walk_sync();
+    fn test_unc_pattern_detection() {
+        use crate::pattern::{Pattern, PatternOptions};
 
-        // Default should be dot:false - no dotfiles
-        assert!(!results.contains(&".hidden".to_string()));
-        assert!(results.contains(&"foo.txt".to_string()));
+        // UNC path
+        let unc_pattern = Pattern::with_pattern_options(
+            "//server/share/folder/**/*.txt",
+            PatternOptions {
+                platform: Some("win32".to_string()),
+                ..Default::default()
+            },
+        );
+        assert!(unc_pattern.is_absolute());
+        assert!(unc_pattern.is_unc());
+        assert!(unc_pattern.root().starts_with("//"));
     }
 
-    // Symlink tests (Unix only)
-
-    #[cfg(unix)]
-    fn create_symlink_fixture() -> TempDir {
-        use std::os::unix::fs::symlink;
+    #[test]
+    fn test_glob_double_dot_extension() {
+        use crate::options::GlobOptions;
 
-        let temp = TempDir::new().unwrap();
-        let base = temp.path();
+        // Create a temporary directory with test files
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_path = temp_dir.path();
 
-        // Create regular directories and files
-        fs::create_dir_all(base.join("a/b/c")).unwrap();
-        File::create(base.join("a/b/c/file.txt")).unwrap();
-        File::create(base.join("a/b/file2.txt")).unwrap();
+        // Create test/a.test.ts
+        std::fs::create_dir_all(temp_path.join("test")).unwrap();
+        std::fs::write(temp_path.join("test/a.test.ts"), "").unwrap();
+        std::fs::write(temp_path.join("test/b.test.tsx"), "").unwrap();
 
-        // Create a symlink from a/symlink -> a/b
-        symlink(base.join("a/b"), base.join("a/symlink")).unwrap();
+        let options = GlobOptions {
+            cwd: Some(temp_path.to_string_lossy().to_string()),
+            ..GlobOptions::default()
+        };
 
-        // Create a broken symlink
-        fs::create_dir_all(base.join("broken")).unwrap();
-        symlink("this-does-not-exist", base.join("broken/link")).unwrap();
+        let glob = Glob::new_multi(vec!["**/*.test.ts".to_string()], options);
+        let results = glob.walk_sync();
 
-        temp
+        assert!(
+            results.contains(&p("test/a.test.ts")),
+            "Should contain test/a.test.ts"
+        );
+        assert!(
+            !results.contains(&p("test/b.test.tsx")),
+            "Should not contain test/b.test.tsx"
+        );
     }
 
-    #[cfg(unix)]
+    // Static pattern tests - Task 5.10.1
+
     #[test]
-    fn test_symlink_no_follow() {
-        let temp = create_symlink_fixture();
+    fn test_static_pattern_single_file() {
+        let temp = create_test_fixture();
         let glob = Glob::new(
-            "a/**/*.txt".to_string(),
-            make_opts_with_follow(&temp.path().to_string_lossy(), false),
+            "foo.txt".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
         );
         let results = glob.walk_sync();
 
-        // Without follow, we should only get files in a/b/, not through symlink
-        assert!(results.contains(&p("a/b/c/file.txt")));
-        assert!(results.contains(&p("a/b/file2.txt")));
-
-        // We should NOT see files through the symlink (symlink/...)
-        assert!(!results.iter().any(|r| r.contains("symlink")));
+        // Should find the exact file
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert_eq!(results.len(), 1);
     }
 
-    #[cfg(unix)]
     #[test]
-    fn test_symlink_with_follow() {
-        let temp = create_symlink_fixture();
+    fn test_static_pattern_nested_file() {
+        let temp = create_test_fixture();
         let glob = Glob::new(
-            "a/**/*.txt".to_string(),
-            make_opts_with_follow(&temp.path().to_string_lossy(), true),
+            "src/main.js".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
         );
         let results = glob.walk_sync();
 
-        // With follow, we should see files through the symlink too
-        assert!(results.contains(&p("a/b/c/file.txt")));
-        assert!(results.contains(&p("a/b/file2.txt")));
-
-        // We should also see the same files through the symlink
-        assert!(results.contains(&p("a/symlink/c/file.txt")));
-        assert!(results.contains(&p("a/symlink/file2.txt")));
+        // Should find the nested file
+        assert!(results.contains(&p("src/main.js")));
+        assert_eq!(results.len(), 1);
     }
 
-    #[cfg(unix)]
     #[test]
-    fn test_broken_symlink_handled_gracefully() {
-        let temp = create_symlink_fixture();
+    fn test_static_pattern_deeply_nested() {
+        let temp = create_test_fixture();
         let glob = Glob::new(
-            "broken/*".to_string(),
+            "src/lib/helper.js".to_string(),
             make_opts(&temp.path().to_string_lossy()),
         );
         let results = glob.walk_sync();
 
-        // Should include the broken symlink itself (not crash)
-        assert!(results.contains(&p("broken/link")));
+        // Should find the deeply nested file
+        assert!(results.contains(&p("src/lib/helper.js")));
+        assert_eq!(results.len(), 1);
     }
 
-    #[cfg(unix)]
     #[test]
-    fn test_broken_symlink_with_follow() {
-        let temp = create_symlink_fixture();
-        let glob = Glob::new(
-            "broken/**".to_string(),
-            make_opts_with_follow(&temp.path().to_string_lossy(), true),
-        );
+    fn test_static_pattern_directory() {
+        let temp = create_test_fixture();
+        let glob = Glob::new("src".to_string(), make_opts(&temp.path().to_string_lossy()));
         let results = glob.walk_sync();
 
-        // Should include the directory and symlink, not crash
-        assert!(results.contains(&"broken".to_string()));
-        assert!(results.contains(&p("broken/link")));
+        // Should find the directory
+        assert!(results.contains(&"src".to_string()));
+        assert_eq!(results.len(), 1);
     }
 
-    #[cfg(unix)]
     #[test]
-    fn test_symlink_explicit_pattern() {
-        let temp = create_symlink_fixture();
+    fn test_static_pattern_nonexistent() {
+        let temp = create_test_fixture();
         let glob = Glob::new(
-            "a/symlink/**/*.txt".to_string(),
+            "does-not-exist.txt".to_string(),
             make_opts(&temp.path().to_string_lossy()),
         );
-        let _results = glob.walk_sync();
+        let results = glob.walk_sync();
 
-        // When explicitly matching through a symlink, we should traverse it
-        // even without follow:true (default behavior)
-        // Note: This test may fail until we implement more nuanced symlink handling
-        // For now, follow:false means no symlinks are followed
+        // Should return empty for non-existent files
+        assert!(results.is_empty());
     }
 
-    #[cfg(unix)]
     #[test]
-    fn test_default_follow_is_false() {
-        let temp = create_symlink_fixture();
-        let glob = Glob::new(
-            "a/**/*.txt".to_string(),
+    fn test_static_pattern_multiple() {
+        let temp = create_test_fixture();
+        let glob = Glob::new_multi(
+            vec!["foo.txt".to_string(), "bar.txt".to_string()],
             make_opts(&temp.path().to_string_lossy()),
         );
         let results = glob.walk_sync();
 
-        // Default should be follow:false - don't traverse symlinks
-        assert!(!results.iter().any(|r| r.contains("symlink")));
+        // Should find both files
+        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(results.contains(&"bar.txt".to_string()));
+        assert_eq!(results.len(), 2);
     }
 
-    // maxDepth tests
-
     #[test]
-    fn test_max_depth_negative() {
+    fn test_static_pattern_with_nodir() {
         let temp = create_test_fixture();
         let glob = Glob::new(
-            "**/*".to_string(),
-            make_opts_with_max_depth(&temp.path().to_string_lossy(), -1),
+            "src".to_string(),
+            make_opts_with_nodir(&temp.path().to_string_lossy(), true),
         );
         let results = glob.walk_sync();
 
-        // Negative maxDepth should return empty results
+        // Should NOT include directory when nodir: true
         assert!(results.is_empty());
     }
 
     #[test]
-    fn test_max_depth_zero() {
+    fn test_static_pattern_with_mark() {
         let temp = create_test_fixture();
         let glob = Glob::new(
-            "**".to_string(),
-            make_opts_with_max_depth(&temp.path().to_string_lossy(), 0),
+            "src".to_string(),
+            make_opts_with_mark(&temp.path().to_string_lossy(), true),
         );
         let results = glob.walk_sync();
 
-        // maxDepth: 0 with ** should return just "." (cwd)
-        assert_eq!(results.len(), 1);
-        assert!(results.contains(&".".to_string()));
+        // Should include trailing slash for directory
+        assert!(results.contains(&p("src/")));
     }
 
     #[test]
-    fn test_max_depth_one() {
+    fn test_static_pattern_with_dot_relative() {
         let temp = create_test_fixture();
         let glob = Glob::new(
-            "**/*".to_string(),
-            make_opts_with_max_depth(&temp.path().to_string_lossy(), 1),
+            "foo.txt".to_string(),
+            make_opts_with_dot_relative(&temp.path().to_string_lossy(), true),
         );
         let results = glob.walk_sync();
 
-        // maxDepth: 1 should return only immediate children (depth 1)
-        // Should include: foo.txt, bar.txt, baz.js, src (but not .hidden due to dot:false default)
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-        assert!(results.contains(&"baz.js".to_string()));
-        assert!(results.contains(&"src".to_string()));
+        // Should include ./ prefix
+        assert!(results.contains(&p("./foo.txt")));
+    }
 
-        // Should NOT include nested files
-        assert!(!results.contains(&p("src/main.js")));
-        assert!(!results.contains(&p("src/lib/helper.js")));
+    #[test]
+    fn test_static_pattern_with_absolute() {
+        let temp = create_test_fixture();
+        let mut opts = make_opts(&temp.path().to_string_lossy());
+        opts.absolute = Some(true);
+
+        let glob = Glob::new("foo.txt".to_string(), opts);
+        let results = glob.walk_sync();
+
+        // Should return absolute path
+        assert!(!results.is_empty());
+        let result = &results[0];
+        assert!(result.contains("foo.txt"));
+        // Absolute path should start with:
+        // - Unix: /
+        // - Windows: drive letter (C:) or UNC (\\) or extended-length (\\?\)
+        assert!(
+            result.starts_with('/')
+                || result.chars().nth(1) == Some(':')
+                || result.starts_with("\\\\")
+        );
     }
 
     #[test]
-    fn test_max_depth_two() {
+    fn test_static_pattern_deduplication() {
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "**/*.js".to_string(),
-            make_opts_with_max_depth(&temp.path().to_string_lossy(), 2),
+        // Same file referenced multiple times
+        let glob = Glob::new_multi(
+            vec!["foo.txt".to_string(), "foo.txt".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
         );
         let results = glob.walk_sync();
 
-        // maxDepth: 2 should include depth 1 and 2
-        assert!(results.contains(&"baz.js".to_string())); // depth 1
-        assert!(results.contains(&p("src/main.js"))); // depth 2
-        assert!(results.contains(&p("src/util.js"))); // depth 2
-
-        // Should NOT include depth 3+
-        assert!(!results.contains(&p("src/lib/helper.js"))); // depth 3
+        // Should only include once
+        assert_eq!(results.len(), 1);
+        assert!(results.contains(&"foo.txt".to_string()));
     }
 
     #[test]
-    fn test_max_depth_unlimited() {
+    fn test_all_patterns_static_detection() {
         let temp = create_test_fixture();
-        let glob = Glob::new(
+
+        // Static patterns
+        let glob1 = Glob::new(
+            "foo.txt".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        assert!(glob1.all_patterns_static());
+
+        let glob2 = Glob::new_multi(
+            vec!["foo.txt".to_string(), "src/main.js".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        assert!(glob2.all_patterns_static());
+
+        // Non-static patterns
+        let glob3 = Glob::new(
+            "*.txt".to_string(),
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        assert!(!glob3.all_patterns_static());
+
+        let glob4 = Glob::new(
             "**/*.js".to_string(),
-            make_opts(&temp.path().to_string_lossy()), // no maxDepth = unlimited
+            make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
+        assert!(!glob4.all_patterns_static());
 
-        // Without maxDepth, should include all levels
-        assert!(results.contains(&"baz.js".to_string()));
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
-        assert!(results.contains(&p("src/lib/helper.js")));
+        // Mixed - should be false
+        let glob5 = Glob::new_multi(
+            vec!["foo.txt".to_string(), "*.js".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
+        assert!(!glob5.all_patterns_static());
+    }
+
+    // Multi-base walking tests
+    fn create_multi_base_fixture() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+
+        // Create src directory with TypeScript files
+        fs::create_dir_all(base.join("src")).unwrap();
+        File::create(base.join("src/main.ts")).unwrap();
+        File::create(base.join("src/util.ts")).unwrap();
+        fs::create_dir_all(base.join("src/lib")).unwrap();
+        File::create(base.join("src/lib/helper.ts")).unwrap();
+
+        // Create test directory with TypeScript files
+        fs::create_dir_all(base.join("test")).unwrap();
+        File::create(base.join("test/main.test.ts")).unwrap();
+        File::create(base.join("test/util.test.ts")).unwrap();
+        fs::create_dir_all(base.join("test/fixtures")).unwrap();
+        File::create(base.join("test/fixtures/data.ts")).unwrap();
+
+        // Create lib directory with TypeScript files
+        fs::create_dir_all(base.join("lib")).unwrap();
+        File::create(base.join("lib/index.ts")).unwrap();
+
+        // Create other directories that should not be traversed
+        fs::create_dir_all(base.join("node_modules/pkg")).unwrap();
+        File::create(base.join("node_modules/pkg/index.ts")).unwrap();
+
+        fs::create_dir_all(base.join("dist")).unwrap();
+        File::create(base.join("dist/main.js")).unwrap();
+
+        // Create root level files
+        File::create(base.join("package.json")).unwrap();
+        File::create(base.join("tsconfig.json")).unwrap();
+
+        temp
     }
 
     #[test]
-    fn test_max_depth_with_scoped_pattern() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "src/**/*.js".to_string(),
-            make_opts_with_max_depth(&temp.path().to_string_lossy(), 2),
+    fn test_group_patterns_by_base() {
+        let temp = create_multi_base_fixture();
+
+        // Patterns with different bases
+        let glob = Glob::new_multi(
+            vec![
+                "src/**/*.ts".to_string(),
+                "src/lib/*.ts".to_string(),
+                "test/**/*.ts".to_string(),
+            ],
+            make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
 
-        // maxDepth: 2 with src/** should get src/* (depth 2)
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
+        let groups = glob.group_patterns_by_base();
+
+        // Should have 2 groups: src and test
+        assert_eq!(groups.len(), 2);
+        assert!(groups.contains_key(&Some("src".to_string())));
+        assert!(groups.contains_key(&Some("test".to_string())));
 
-        // Should NOT include src/lib/* (depth 3)
-        assert!(!results.contains(&p("src/lib/helper.js")));
+        // src group should have 2 patterns
+        assert_eq!(groups.get(&Some("src".to_string())).unwrap().len(), 2);
+        // test group should have 1 pattern
+        assert_eq!(groups.get(&Some("test".to_string())).unwrap().len(), 1);
     }
 
-    // nodir tests
-
     #[test]
-    fn test_nodir_true_excludes_directories() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "**/*".to_string(),
-            make_opts_with_nodir(&temp.path().to_string_lossy(), true),
+    fn test_group_patterns_with_none_prefix() {
+        let temp = create_multi_base_fixture();
+
+        // Patterns with and without prefixes
+        let glob = Glob::new_multi(
+            vec![
+                "src/**/*.ts".to_string(),
+                "**/*.json".to_string(), // No prefix
+            ],
+            make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
 
-        // Should include files
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-        assert!(results.contains(&"baz.js".to_string()));
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/lib/helper.js")));
+        let groups = glob.group_patterns_by_base();
 
-        // Should NOT include directories
-        assert!(!results.contains(&"src".to_string()));
-        assert!(!results.contains(&p("src/lib")));
+        // Should have 2 groups: src and None
+        assert_eq!(groups.len(), 2);
+        assert!(groups.contains_key(&Some("src".to_string())));
+        assert!(groups.contains_key(&None));
     }
 
     #[test]
-    fn test_nodir_false_includes_directories() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "**/*".to_string(),
-            make_opts_with_nodir(&temp.path().to_string_lossy(), false),
+    fn test_should_use_multi_base_walking_true() {
+        let temp = create_multi_base_fixture();
+
+        // All patterns have different bases
+        let glob = Glob::new_multi(
+            vec!["src/**/*.ts".to_string(), "test/**/*.ts".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
 
-        // Should include both files and directories
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"src".to_string()));
-        assert!(results.contains(&p("src/lib")));
+        assert!(glob.should_use_multi_base_walking());
     }
 
     #[test]
-    fn test_nodir_default_includes_directories() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "**/*".to_string(),
-            make_opts(&temp.path().to_string_lossy()), // no nodir = includes dirs
+    fn test_should_use_multi_base_walking_false_no_prefix() {
+        let temp = create_multi_base_fixture();
+
+        // One pattern has no prefix
+        let glob = Glob::new_multi(
+            vec![
+                "src/**/*.ts".to_string(),
+                "**/*.ts".to_string(), // No prefix
+            ],
+            make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
 
-        // Default behavior should include directories
-        assert!(results.contains(&"src".to_string()));
-        assert!(results.contains(&p("src/lib")));
+        assert!(!glob.should_use_multi_base_walking());
     }
 
     #[test]
-    fn test_nodir_with_simple_pattern() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*".to_string(),
-            make_opts_with_nodir(&temp.path().to_string_lossy(), true),
-        );
-        let results = glob.walk_sync();
+    fn test_should_use_multi_base_walking_false_same_base() {
+        let temp = create_multi_base_fixture();
 
-        // Should include root files but not root directories
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-        assert!(results.contains(&"baz.js".to_string()));
+        // All patterns have the same base
+        let glob = Glob::new_multi(
+            vec!["src/**/*.ts".to_string(), "src/lib/*.ts".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
 
-        // Should NOT include src directory
-        assert!(!results.contains(&"src".to_string()));
+        // Only one group, so no benefit from multi-base
+        assert!(!glob.should_use_multi_base_walking());
     }
 
     #[test]
-    fn test_nodir_excludes_cwd_with_globstar() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "**".to_string(),
-            make_opts_with_nodir(&temp.path().to_string_lossy(), true),
-        );
-        let results = glob.walk_sync();
+    fn test_should_use_multi_base_walking_false_nonexistent_dir() {
+        let temp = create_multi_base_fixture();
 
-        // With nodir: true, "." (cwd) should NOT be included
-        // even though ** matches everything
-        assert!(!results.contains(&".".to_string()));
+        // One base doesn't exist
+        let glob = Glob::new_multi(
+            vec!["src/**/*.ts".to_string(), "nonexistent/**/*.ts".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
+        );
 
-        // But files should still be included
-        assert!(results.contains(&"foo.txt".to_string()));
+        assert!(!glob.should_use_multi_base_walking());
     }
 
     #[test]
-    fn test_nodir_with_recursive_pattern() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*/**".to_string(),
-            make_opts_with_nodir(&temp.path().to_string_lossy(), true),
+    fn test_walk_multi_base_results() {
+        let temp = create_multi_base_fixture();
+
+        // Multi-base pattern
+        let glob = Glob::new_multi(
+            vec!["src/**/*.ts".to_string(), "test/**/*.ts".to_string()],
+            make_opts(&temp.path().to_string_lossy()),
         );
+
         let results = glob.walk_sync();
 
-        // Should include nested files
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/lib/helper.js")));
+        // Should find files in both src and test
+        assert!(results.contains(&p("src/main.ts")));
+        assert!(results.contains(&p("src/util.ts")));
+        assert!(results.contains(&p("src/lib/helper.ts")));
+        assert!(results.contains(&p("test/main.test.ts")));
+        assert!(results.contains(&p("test/util.test.ts")));
+        assert!(results.contains(&p("test/fixtures/data.ts")));
 
-        // Should NOT include directory entries
-        assert!(!results.contains(&"src".to_string()));
-        assert!(!results.contains(&p("src/lib")));
+        // Should NOT find files in other directories (node_modules, lib)
+        assert!(!results.iter().any(|r| r.contains("node_modules")));
+        assert!(!results.contains(&p("lib/index.ts")));
+
+        // Should have exactly 6 results
+        assert_eq!(results.len(), 6);
     }
 
-    #[cfg(unix)]
     #[test]
-    fn test_nodir_with_symlinks() {
-        use std::os::unix::fs::symlink;
+    fn test_scoped_globstar_includes_base_dir() {
+        let temp = create_multi_base_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let sorted = |glob: Glob| {
+            let mut results = glob.walk_sync();
+            results.sort();
+            results
+        };
 
-        let temp = TempDir::new().unwrap();
-        let base = temp.path();
+        // Single base: the walk starts at `src`, which `src/**` matches
+        let single = Glob::new("src/**".to_string(), make_opts(&cwd));
+        assert!(!single.should_use_multi_base_walking());
+        let single = sorted(single);
+        assert!(single.contains(&"src".to_string()));
 
-        // Create directory structure
-        fs::create_dir_all(base.join("real_dir")).unwrap();
-        File::create(base.join("real_dir/file.txt")).unwrap();
-        File::create(base.join("normal.txt")).unwrap();
+        // Multi-base: each base directory is matched the same way
+        let multi = Glob::new_multi(
+            vec!["src/**".to_string(), "test/**".to_string()],
+            make_opts(&cwd),
+        );
+        assert!(multi.should_use_multi_base_walking());
+        let multi = sorted(multi);
+        assert!(multi.contains(&"src".to_string()));
+        assert!(multi.contains(&"test".to_string()));
+        let multi_src: Vec<String> = multi
+            .into_iter()
+            .filter(|r| r == "src" || r.starts_with(&p("src/")))
+            .collect();
+        assert_eq!(multi_src, single);
 
-        // Create a symlink to a directory
-        symlink(base.join("real_dir"), base.join("symlink_dir")).unwrap();
+        // Under nodir neither walk reports the base directory
+        let nodir = |patterns: Vec<&str>| {
+            let opts = GlobOptions {
+                nodir: Some(true),
+                ..make_opts(&cwd)
+            };
+            Glob::new_multi(patterns.into_iter().map(String::from).collect(), opts).walk_sync()
+        };
+        assert!(!nodir(vec!["src/**"]).contains(&"src".to_string()));
+        assert!(!nodir(vec!["src/**", "test/**"]).contains(&"src".to_string()));
+
+        // An ignored base directory is left out of both
+        let ignored = |patterns: Vec<&str>| {
+            let opts = GlobOptions {
+                ignore: Some(Either::A("src".to_string())),
+                ..make_opts(&cwd)
+            };
+            Glob::new_multi(patterns.into_iter().map(String::from).collect(), opts).walk_sync()
+        };
+        assert!(!ignored(vec!["src/**"]).contains(&"src".to_string()));
+        assert!(!ignored(vec!["src/**", "test/**"]).contains(&"src".to_string()));
+
+        // Hidden bases named by the pattern are still matched with dot: false
+        fs::create_dir(temp.path().join(".config")).unwrap();
+        let hidden = Glob::new_multi(
+            vec![".config/**".to_string(), "src/**".to_string()],
+            make_opts(&cwd),
+        );
+        assert!(hidden.should_use_multi_base_walking());
+        assert!(hidden.walk_sync().contains(&".config".to_string()));
+    }
 
-        // Create a symlink to a file
-        symlink(base.join("normal.txt"), base.join("symlink_file")).unwrap();
+    #[test]
+    fn test_walk_multi_base_three_directories() {
+        let temp = create_multi_base_fixture();
 
-        // Test with nodir: true, follow: false (default)
-        // Symlinks are treated as files (not directories) when not followed
-        let glob = Glob::new(
-            "*".to_string(),
-            GlobOptions {
-                cwd: Some(base.to_string_lossy().to_string()),
-                nodir: Some(true),
-                follow: Some(false),
-                ..Default::default()
-            },
+        // Three different bases
+        let glob = Glob::new_multi(
+            vec![
+                "src/**/*.ts".to_string(),
+                "test/**/*.ts".to_string(),
+                "lib/**/*.ts".to_string(),
+            ],
+            make_opts(&temp.path().to_string_lossy()),
         );
+
         let results = glob.walk_sync();
 
-        // Should include the symlink to dir (since it's a symlink, not a dir, when not following)
-        assert!(results.contains(&"symlink_dir".to_string()));
-        // Should include symlink to file
-        assert!(results.contains(&"symlink_file".to_string()));
-        // Should include normal file
-        assert!(results.contains(&"normal.txt".to_string()));
-        // Should NOT include the real directory
-        assert!(!results.contains(&"real_dir".to_string()));
+        // Should find files in all three directories
+        assert!(results.contains(&p("src/main.ts")));
+        assert!(results.contains(&p("test/main.test.ts")));
+        assert!(results.contains(&p("lib/index.ts")));
+
+        // Should have exactly 7 results (3 in src, 3 in test, 1 in lib)
+        assert_eq!(results.len(), 7);
     }
 
-    #[cfg(unix)]
     #[test]
-    fn test_nodir_with_follow_symlinks() {
-        use std::os::unix::fs::symlink;
+    fn test_walk_multi_base_with_nodir() {
+        let temp = create_multi_base_fixture();
 
-        let temp = TempDir::new().unwrap();
-        let base = temp.path();
+        let mut opts = make_opts(&temp.path().to_string_lossy());
+        opts.nodir = Some(true);
 
-        // Create directory structure
-        fs::create_dir_all(base.join("real_dir")).unwrap();
-        File::create(base.join("real_dir/file.txt")).unwrap();
-        File::create(base.join("normal.txt")).unwrap();
+        let glob = Glob::new_multi(vec!["src/**/*".to_string(), "test/**/*".to_string()], opts);
 
-        // Create a symlink to a directory
-        symlink(base.join("real_dir"), base.join("symlink_dir")).unwrap();
+        let results = glob.walk_sync();
 
-        // Create a symlink to a file
-        symlink(base.join("normal.txt"), base.join("symlink_file")).unwrap();
+        // Should only contain files, not directories
+        assert!(results.contains(&p("src/main.ts")));
+        assert!(!results
+            .iter()
+            .any(|r| r == "src" || r == "src/" || r == "test" || r == "test/"));
+    }
 
-        // Test with nodir: true, follow: true
-        // When following symlinks, a symlink to a directory IS a directory
-        let glob = Glob::new(
-            "*".to_string(),
-            GlobOptions {
-                cwd: Some(base.to_string_lossy().to_string()),
-                nodir: Some(true),
-                follow: Some(true),
-                ..Default::default()
-            },
+    #[test]
+    fn test_walk_multi_base_deduplication() {
+        let temp = create_multi_base_fixture();
+
+        // Overlapping patterns that could produce duplicates
+        let glob = Glob::new_multi(
+            vec![
+                "src/**/*.ts".to_string(),
+                "src/lib/**/*.ts".to_string(), // More specific version
+            ],
+            make_opts(&temp.path().to_string_lossy()),
         );
+
+        // Note: These have the same base (src), so they won't use multi-base walking
+        // But this tests that deduplication works in general
         let results = glob.walk_sync();
 
-        // Should NOT include symlink to dir (because when followed, it's a directory)
-        assert!(!results.contains(&"symlink_dir".to_string()));
-        // Should include symlink to file (because when followed, it's a file)
-        assert!(results.contains(&"symlink_file".to_string()));
-        // Should include normal file
-        assert!(results.contains(&"normal.txt".to_string()));
-        // Should NOT include the real directory
-        assert!(!results.contains(&"real_dir".to_string()));
+        // Count occurrences of helper.ts
+        let helper_count = results.iter().filter(|r| r.contains("helper.ts")).count();
+        assert_eq!(helper_count, 1, "Should not have duplicate entries");
     }
 
-    // dotRelative tests
-
     #[test]
-    fn test_dot_relative_prepends_dot_slash() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*.txt".to_string(),
-            make_opts_with_dot_relative(&temp.path().to_string_lossy(), true),
+    fn test_walk_multi_base_empty_results() {
+        let temp = create_multi_base_fixture();
+
+        // Pattern for non-existent file types
+        let glob = Glob::new_multi(
+            vec![
+                "src/**/*.py".to_string(), // No Python files
+                "test/**/*.py".to_string(),
+            ],
+            make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
 
-        // All results should start with "./" or ".\\" on Windows
-        let expected_prefix = if cfg!(target_os = "windows") {
-            ".\\"
-        } else {
-            "./"
-        };
-        for result in &results {
-            assert!(
-                result.starts_with(expected_prefix),
-                "Path should start with '{expected_prefix}': {result}"
-            );
-        }
-        assert!(results.contains(&p("./foo.txt")));
-        assert!(results.contains(&p("./bar.txt")));
+        // Should still use multi-base walking but return empty results
+        let results = glob.walk_sync();
+        assert!(results.is_empty());
     }
 
     #[test]
-    fn test_dot_relative_false_no_prefix() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*.txt".to_string(),
-            make_opts_with_dot_relative(&temp.path().to_string_lossy(), false),
+    fn test_walk_multi_base_parallel_results_match() {
+        let temp = create_multi_base_fixture();
+
+        // Test that parallel multi-base walking produces correct results
+        // by comparing with expected results
+        let glob = Glob::new_multi(
+            vec![
+                "src/**/*.ts".to_string(),
+                "test/**/*.ts".to_string(),
+                "lib/**/*.ts".to_string(),
+            ],
+            make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
 
-        // Results should NOT start with "./" or ".\"
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-        for result in &results {
+        // Run multiple times to test parallel execution consistency
+        for _ in 0..5 {
+            let results = glob.walk_sync();
+
+            // Verify expected files are present (order may vary due to parallelism)
+            let results_set: std::collections::HashSet<_> = results.iter().collect();
+
             assert!(
-                !result.starts_with("./") && !result.starts_with(".\\"),
-                "Path should not start with './' or '.\\': {result}"
+                results_set.contains(&String::from("src/main.ts")),
+                "Should contain src/main.ts"
+            );
+            assert!(
+                results_set.contains(&String::from("src/util.ts")),
+                "Should contain src/util.ts"
             );
+            assert!(
+                results_set.contains(&String::from("src/lib/helper.ts")),
+                "Should contain src/lib/helper.ts"
+            );
+            assert!(
+                results_set.contains(&String::from("test/main.test.ts")),
+                "Should contain test/main.test.ts"
+            );
+            assert!(
+                results_set.contains(&String::from("test/util.test.ts")),
+                "Should contain test/util.test.ts"
+            );
+            assert!(
+                results_set.contains(&String::from("test/fixtures/data.ts")),
+                "Should contain test/fixtures/data.ts"
+            );
+            assert!(
+                results_set.contains(&String::from("lib/index.ts")),
+                "Should contain lib/index.ts"
+            );
+
+            // Total should be 7 files
+            assert_eq!(results.len(), 7, "Should have exactly 7 results");
         }
     }
 
     #[test]
-    fn test_dot_relative_with_nested_paths() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "**/*.js".to_string(),
-            make_opts_with_dot_relative(&temp.path().to_string_lossy(), true),
+    fn test_walk_multi_base_parallel_with_ignore() {
+        let temp = create_multi_base_fixture();
+
+        let mut opts = make_opts(&temp.path().to_string_lossy());
+        opts.ignore = Some(napi::Either::A("**/util*".to_string()));
+
+        let glob = Glob::new_multi(
+            vec!["src/**/*.ts".to_string(), "test/**/*.ts".to_string()],
+            opts,
         );
+
         let results = glob.walk_sync();
 
-        // All results should start with "./"
-        assert!(results.contains(&p("./baz.js")));
-        assert!(results.contains(&p("./src/main.js")));
-        assert!(results.contains(&p("./src/util.js")));
-        assert!(results.contains(&p("./src/lib/helper.js")));
+        // Should have files except util-related ones
+        assert!(results.contains(&p("src/main.ts")));
+        assert!(!results.contains(&p("src/util.ts"))); // ignored
+        assert!(results.contains(&p("test/main.test.ts")));
+        assert!(!results.contains(&p("test/util.test.ts"))); // ignored
     }
 
     #[test]
-    fn test_dot_relative_default_is_false() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*.txt".to_string(),
+    fn test_walk_multi_base_parallel_consistency() {
+        let temp = create_multi_base_fixture();
+
+        // Run multi-base walking several times and verify results are consistent
+        let glob = Glob::new_multi(
+            vec!["src/**/*.ts".to_string(), "test/**/*.ts".to_string()],
             make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
 
-        // Default should not have "./" prefix
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(!results.contains(&p("./foo.txt")));
-    }
+        let first_results: std::collections::HashSet<_> = glob.walk_sync().into_iter().collect();
 
-    // mark tests
+        for _ in 0..10 {
+            let results: std::collections::HashSet<_> = glob.walk_sync().into_iter().collect();
+            assert_eq!(
+                first_results, results,
+                "Parallel results should be consistent across runs"
+            );
+        }
+    }
 
     #[test]
-    fn test_mark_appends_slash_to_directories() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*".to_string(),
-            make_opts_with_mark(&temp.path().to_string_lossy(), true),
+    fn test_walk_single_base_group_returns_correct_results() {
+        let temp = create_multi_base_fixture();
+        let cwd = temp.path();
+        let abs_cwd = strip_windows_extended_prefix(cwd.canonicalize().unwrap());
+
+        let glob = Glob::new_multi(
+            vec![
+                "src/**/*.ts".to_string(),
+                "src/lib/*.ts".to_string(),
+                "test/**/*.ts".to_string(),
+            ],
+            make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
 
-        // Directories should end with "/"
-        assert!(results.contains(&p("src/")));
+        // Walk just the src group (indices 0 and 1)
+        let results = glob.walk_single_base_group(&[0, 1], &abs_cwd);
 
-        // Files should NOT end with "/"
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-        assert!(!results.contains(&p("foo.txt/")));
+        assert!(results.contains(&p("src/main.ts")));
+        assert!(results.contains(&p("src/util.ts")));
+        assert!(results.contains(&p("src/lib/helper.ts")));
+        assert!(!results.contains(&p("test/main.test.ts"))); // Not in this group
     }
 
     #[test]
-    fn test_mark_false_no_trailing_slash() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*".to_string(),
-            make_opts_with_mark(&temp.path().to_string_lossy(), false),
+    fn test_stream_per_base_streams_every_multi_base_result() {
+        let temp = create_multi_base_fixture();
+        let patterns = vec![
+            "src/**/*.ts".to_string(),
+            "test/**/*.ts".to_string(),
+            "lib/*.ts".to_string(),
+            "src/lib/*.ts".to_string(),
+        ];
+
+        let glob = Glob::new_multi(patterns.clone(), make_opts(&temp.path().to_string_lossy()));
+        assert_eq!(glob.fast_path_strategy(), Some(WalkStrategy::MultiBase));
+        let mut expected = glob.walk_multi_base();
+        expected.sort();
+
+        let mut opts = make_opts(&temp.path().to_string_lossy());
+        opts.stream_per_base = Some(true);
+        let glob = Glob::new_multi(patterns.clone(), opts);
+        let mut streamed = Vec::new();
+        glob.walk_stream_batched(1, |batch| streamed.extend(batch));
+        streamed.sort();
+
+        // `src/lib/helper.ts` is matched from two groups but streamed once
+        assert_eq!(streamed, expected);
+        assert_eq!(
+            streamed
+                .iter()
+                .filter(|r| **r == p("src/lib/helper.ts"))
+                .count(),
+            1
         );
-        let results = glob.walk_sync();
 
-        // Directories should NOT end with "/"
-        assert!(results.contains(&"src".to_string()));
-        assert!(!results.contains(&p("src/")));
+        // Other streams keep their usual order
+        let sorted_opts = GlobOptions {
+            sort_dir_entries: Some(true),
+            ..make_opts(&temp.path().to_string_lossy())
+        };
+        let stream = |stream_per_base: Option<bool>| {
+            let opts = GlobOptions {
+                stream_per_base,
+                ..sorted_opts.clone()
+            };
+            let mut streamed = Vec::new();
+            Glob::new_multi(patterns.clone(), opts).walk_stream(|r| streamed.push(r));
+            streamed
+        };
+        assert_eq!(stream(Some(true)), stream(None));
     }
 
-    #[test]
-    fn test_mark_with_nested_directories() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "**/*".to_string(),
-            make_opts_with_mark(&temp.path().to_string_lossy(), true),
-        );
-        let results = glob.walk_sync();
+    fn create_child_matches_fixture() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
 
-        // Nested directories should also have trailing slash
-        assert!(results.contains(&p("src/")));
-        assert!(results.contains(&p("src/lib/")));
+        // `a` is a directory whose contents also match `**/*.txt`
+        fs::create_dir_all(base.join("a/c")).unwrap();
+        File::create(base.join("a/b.txt")).unwrap();
+        File::create(base.join("a/c/d.txt")).unwrap();
 
-        // Files should not have trailing slash
-        assert!(results.contains(&p("src/main.js")));
-        assert!(!results.contains(&p("src/main.js/")));
+        // Siblings that share `a` (or a matched file name) as a byte prefix
+        fs::create_dir_all(base.join("ab")).unwrap();
+        File::create(base.join("ab/b.txt")).unwrap();
+        File::create(base.join("f.txt")).unwrap();
+        fs::create_dir_all(base.join("f.txt-dir")).unwrap();
+        File::create(base.join("f.txt-dir/g.txt")).unwrap();
+
+        temp
     }
 
-    #[test]
-    fn test_mark_with_globstar_cwd() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "**".to_string(),
-            make_opts_with_mark(&temp.path().to_string_lossy(), true),
-        );
-        let results = glob.walk_sync();
+    /// Run the same glob through all four walk functions, returning sorted paths.
+    fn walk_all_four(glob: &Glob) -> [Vec<String>; 4] {
+        let mut sync = glob.walk_sync();
 
-        // "." (cwd) should become "./" with mark:true
-        assert!(results.contains(&p("./")));
-        assert!(!results.contains(&".".to_string()));
-    }
+        let mut sync_types: Vec<String> = glob
+            .walk_sync_with_file_types()
+            .into_iter()
+            .map(|d| d.path)
+            .collect();
 
-    #[test]
-    fn test_mark_default_is_false() {
-        let temp = create_test_fixture();
-        let glob = Glob::new("*".to_string(), make_opts(&temp.path().to_string_lossy()));
-        let results = glob.walk_sync();
+        let mut stream = Vec::new();
+        glob.walk_stream(|r| stream.push(r));
 
-        // Default should not have trailing slash on directories
-        assert!(results.contains(&"src".to_string()));
-        assert!(!results.contains(&p("src/")));
+        let mut stream_types = Vec::new();
+        glob.walk_stream_with_file_types(|d| stream_types.push(d.path));
+
+        sync.sort();
+        sync_types.sort();
+        stream.sort();
+        stream_types.sort();
+        [sync, sync_types, stream, stream_types]
     }
 
     #[test]
-    fn test_mark_with_dot_relative() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*".to_string(),
-            GlobOptions {
-                cwd: Some(temp.path().to_string_lossy().to_string()),
-                dot_relative: Some(true),
-                mark: Some(true),
-                ..Default::default()
-            },
-        );
-        let results = glob.walk_sync();
+    fn test_top_n_keeps_smallest() {
+        let mut top = TopN::new(3);
+        for n in [7, 3, 9, 1, 8, 2, 2, 6] {
+            top.push(n);
+        }
+        assert_eq!(top.heap.len(), 3);
+        assert_eq!(top.into_sorted_vec(), vec![1, 2, 2]);
 
-        // Should have both "./" prefix and "/" suffix for directories
-        assert!(results.contains(&p("./src/")));
+        let mut short = TopN::new(5);
+        short.push("b");
+        short.push("a");
+        assert_eq!(short.into_sorted_vec(), vec!["a", "b"]);
 
-        // Files should have "./" prefix but not "/" suffix
-        assert!(results.contains(&p("./foo.txt")));
-        assert!(!results.contains(&p("./foo.txt/")));
+        // A huge limit doesn't reserve room for that many items
+        let mut huge = TopN::new(u32::MAX as usize);
+        assert!(huge.heap.capacity() <= 1024);
+        huge.push(2);
+        huge.push(1);
+        assert_eq!(huge.into_sorted_vec(), vec![1, 2]);
     }
 
-    // matchBase tests
+    #[test]
+    fn test_walk_sync_first_is_lexical_prefix() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
 
-    fn make_opts_with_match_base(cwd: &str, match_base: bool) -> GlobOptions {
-        GlobOptions {
-            cwd: Some(cwd.to_string()),
-            match_base: Some(match_base),
-            ..Default::default()
+        for opts in [
+            make_opts(&cwd),
+            GlobOptions {
+                parallel: Some(true),
+                ..make_opts(&cwd)
+            },
+            GlobOptions {
+                dot: Some(true),
+                ..make_opts(&cwd)
+            },
+        ] {
+            let glob = Glob::new("**".to_string(), opts);
+            let mut all = glob.walk_sync();
+            all.sort();
+            assert!(all.len() > 5);
+
+            assert_eq!(glob.walk_sync_first(5), all[..5].to_vec());
+            assert_eq!(glob.walk_sync_first(1), all[..1].to_vec());
+            assert_eq!(glob.walk_sync_first(all.len() + 10), all);
+            assert_eq!(glob.walk_sync_first(u32::MAX as usize), all);
         }
+
+        assert!(glob_sync_first(Either::A("**".to_string()), Some(make_opts(&cwd)), 0).is_err());
     }
 
     #[test]
-    fn test_match_base_true_matches_basename() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*.js".to_string(),
-            make_opts_with_match_base(&temp.path().to_string_lossy(), true),
-        );
-        let results = glob.walk_sync();
+    fn test_glob_over_in_memory_file_system() {
+        use crate::fs::MemoryFileSystem;
+
+        let root = "/globlin-virtual-archive";
+        let archive = Arc::new(MemoryFileSystem::new(
+            root,
+            &[
+                "README.md",
+                "src/main.rs",
+                "src/lib/util.rs",
+                "docs/guide.md",
+                "docs/api/index.md",
+            ],
+        ));
+        let run = |patterns: &[&str], opts: GlobOptions| {
+            let patterns = patterns.iter().map(|p| p.to_string()).collect();
+            let glob = Glob::new_multi(
+                patterns,
+                GlobOptions {
+                    cwd: Some(root.to_string()),
+                    ..opts
+                },
+            )
+            .with_file_system(archive.clone());
+            let mut results = glob.walk_sync();
+            results.sort();
+            let mut streamed = Vec::new();
+            glob.walk_stream(|r| streamed.push(r));
+            streamed.sort();
+            assert_eq!(streamed, results);
+            results
+        };
 
-        // With matchBase: true, *.js should match files at any depth
-        assert!(results.contains(&"baz.js".to_string()));
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
-        assert!(results.contains(&p("src/lib/helper.js")));
+        assert_eq!(
+            run(&["**/*.rs"], GlobOptions::default()),
+            ["src/lib/util.rs", "src/main.rs"]
+        );
+        // Patterns that would otherwise stat or list paths directly
+        assert_eq!(run(&["README.md"], GlobOptions::default()), ["README.md"]);
+        assert_eq!(run(&["*.md"], GlobOptions::default()), ["README.md"]);
+        assert_eq!(
+            run(&["src/**/*.rs", "docs/**/*.md"], GlobOptions::default()),
+            [
+                "docs/api/index.md",
+                "docs/guide.md",
+                "src/lib/util.rs",
+                "src/main.rs"
+            ]
+        );
+        assert_eq!(
+            run(
+                &["**"],
+                GlobOptions {
+                    ignore: Some(Either::A("docs/**".to_string())),
+                    nodir: Some(true),
+                    ..Default::default()
+                }
+            ),
+            ["README.md", "src/lib/util.rs", "src/main.rs"]
+        );
+        assert_eq!(
+            run(
+                &["*"],
+                GlobOptions {
+                    mark: Some(true),
+                    ..Default::default()
+                }
+            ),
+            ["README.md", "docs/", "src/"]
+        );
+        assert_eq!(
+            run(
+                &["src/*.rs"],
+                GlobOptions {
+                    absolute: Some(true),
+                    posix: Some(true),
+                    ..Default::default()
+                }
+            ),
+            [format!("{root}/src/main.rs")]
+        );
     }
 
     #[test]
-    fn test_match_base_false_matches_root_only() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*.js".to_string(),
-            make_opts_with_match_base(&temp.path().to_string_lossy(), false),
+    fn test_in_memory_file_system_resolves_links_and_devices() {
+        use crate::fs::MemoryFileSystem;
+
+        // Nothing exists on disk here, so every answer has to come from the archive
+        let root = "/globlin-virtual-links";
+        let cwd = format!("{root}/proj");
+        let archive = Arc::new(
+            MemoryFileSystem::new(
+                root,
+                &["proj/real/a.txt", "proj/mnt/b.txt", "secret/key.txt"],
+            )
+            .with_symlink(root, "proj/alias", &format!("{root}/proj/real"))
+            .with_symlink(root, "proj/escape", &format!("{root}/secret"))
+            .with_mount(root, "proj/mnt", 7),
         );
-        let results = glob.walk_sync();
+        let run = |opts: GlobOptions| {
+            let glob = Glob::new(
+                "**/*.txt".to_string(),
+                GlobOptions {
+                    cwd: Some(cwd.clone()),
+                    ..opts
+                },
+            )
+            .with_file_system(archive.clone());
+            let mut results = glob.walk_sync();
+            results.sort();
+            results
+        };
 
-        // With matchBase: false, *.js should only match at root level
-        assert!(results.contains(&"baz.js".to_string()));
-        assert!(!results.contains(&p("src/main.js")));
-        assert!(!results.contains(&p("src/lib/helper.js")));
+        assert_eq!(
+            run(GlobOptions {
+                follow: Some(true),
+                ..Default::default()
+            }),
+            ["alias/a.txt", "escape/key.txt", "mnt/b.txt", "real/a.txt"]
+        );
+        assert_eq!(
+            run(GlobOptions {
+                follow: Some(true),
+                restrict_to_cwd: Some(true),
+                ..Default::default()
+            }),
+            ["alias/a.txt", "mnt/b.txt", "real/a.txt"]
+        );
+        assert_eq!(
+            run(GlobOptions {
+                follow: Some(true),
+                canonical_only: Some(true),
+                ..Default::default()
+            }),
+            ["escape/key.txt", "mnt/b.txt", "real/a.txt"]
+        );
+        assert_eq!(
+            run(GlobOptions {
+                one_file_system: Some(true),
+                ..Default::default()
+            }),
+            ["real/a.txt"]
+        );
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_match_base_pattern_with_slash() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "src/*.js".to_string(),
-            make_opts_with_match_base(&temp.path().to_string_lossy(), true),
-        );
-        let results = glob.walk_sync();
+    fn test_in_memory_file_system_resolves_cwd_for_canonical_only() {
+        use crate::fs::MemoryFileSystem;
+        use std::os::unix::fs::symlink;
 
-        // Pattern with / is used as-is even with matchBase: true
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
-        // Should NOT match nested files (pattern has / so no **/ prepended)
-        assert!(!results.contains(&p("src/lib/helper.js")));
+        // On disk cwd is a link elsewhere, but the archive has it as a plain directory
+        let temp = TempDir::new().unwrap();
+        fs::create_dir(temp.path().join("elsewhere")).unwrap();
+        symlink(temp.path().join("elsewhere"), temp.path().join("proj")).unwrap();
+        let cwd = temp.path().join("proj").to_string_lossy().to_string();
+        let archive = Arc::new(MemoryFileSystem::new(&cwd, &["real/a.txt"]).with_symlink(
+            &cwd,
+            "alias",
+            &format!("{cwd}/real"),
+        ));
+        let opts = GlobOptions {
+            follow: Some(true),
+            canonical_only: Some(true),
+            ..make_opts(&cwd)
+        };
+        let glob = Glob::new("**/*.txt".to_string(), opts).with_file_system(archive);
+
+        assert_eq!(glob.walk_sync(), ["real/a.txt"]);
     }
 
     #[test]
-    fn test_match_base_default_is_false() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*.js".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
+    fn test_in_memory_file_system_answers_path_details() {
+        use crate::fs::MemoryFileSystem;
+
+        let root = "/globlin-virtual-details";
+        let archive = Arc::new(
+            MemoryFileSystem::new(root, &["real/a.txt", "empty/", "secret.txt"])
+                .with_symlink(root, "alias", &format!("{root}/real"))
+                .with_symlink(root, "broken", &format!("{root}/missing"))
+                .with_hidden(root, "secret.txt"),
         );
-        let results = glob.walk_sync();
+        let glob = |pattern: &str, opts: GlobOptions| {
+            Glob::new(
+                pattern.to_string(),
+                GlobOptions {
+                    cwd: Some(root.to_string()),
+                    ..opts
+                },
+            )
+            .with_file_system(archive.clone())
+        };
+        let run = |pattern: &str, opts: GlobOptions| {
+            let mut results = glob(pattern, opts).walk_sync();
+            results.sort();
+            results
+        };
 
-        // Default behavior should match only at root
-        assert!(results.contains(&"baz.js".to_string()));
-        assert!(!results.contains(&p("src/main.js")));
-    }
+        // A symlink to a directory satisfies a trailing `/`
+        assert_eq!(
+            run("*/", GlobOptions::default()),
+            ["alias", "empty", "real"]
+        );
 
-    #[test]
-    fn test_match_base_with_brace_expansion_all_have_slash() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "{src,lib}/*.js".to_string(),
-            make_opts_with_match_base(&temp.path().to_string_lossy(), true),
+        // realpath resolves links, marks directories and keeps broken links on request
+        let realpath = || GlobOptions {
+            realpath: Some(true),
+            mark: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(run("alias", realpath()), ["real/"]);
+        assert_eq!(run("alias/a.txt", GlobOptions::default()), ["alias/a.txt"]);
+        assert_eq!(run("alias/a.txt", realpath()), ["real/a.txt"]);
+        assert!(run("broken", realpath()).is_empty());
+        let keep_broken = GlobOptions {
+            realpath_keep_broken: Some(true),
+            ..realpath()
+        };
+        assert_eq!(run("broken", keep_broken), ["broken"]);
+
+        // Link targets and empty directories come from the archive
+        let typed = glob(
+            "*",
+            GlobOptions {
+                read_link_targets: Some(true),
+                detect_empty_dirs: Some(true),
+                ..Default::default()
+            },
+        )
+        .walk_sync_with_file_types();
+        let find = |path: &str| typed.iter().find(|d| d.path == path).unwrap();
+        assert_eq!(
+            find("alias").link_target.as_deref(),
+            Some(format!("{root}/real").as_str())
         );
-        let results = glob.walk_sync();
+        assert_eq!(find("empty").is_empty_dir, Some(true));
+        assert_eq!(find("real").is_empty_dir, Some(false));
 
-        // Brace expansion with / in all parts - no matchBase transformation
-        assert!(results.contains(&p("src/main.js")));
-    }
+        // So does the hidden attribute
+        let hidden = GlobOptions {
+            hidden: Some(HiddenMode::AttributeOnly),
+            ..Default::default()
+        };
+        assert_eq!(run("*.txt", hidden), Vec::<String>::new());
+        assert_eq!(run("*.txt", GlobOptions::default()), ["secret.txt"]);
 
-    #[test]
-    fn test_match_base_with_brace_expansion_one_has_slash() {
-        let temp = create_test_fixture();
-        // Pattern: b{*.js,/c} - one part has /, so matchBase doesn't apply to any
-        let glob = Glob::new(
-            "b{*.txt,/c}".to_string(),
-            make_opts_with_match_base(&temp.path().to_string_lossy(), true),
+        // A nocase prefix is spelled the way the archive lists it
+        let nocase = GlobOptions {
+            nocase: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(run("REAL/*.txt", nocase), ["real/a.txt"]);
+
+        // Aggregate sizes are the archive's (each file is as long as its name)
+        assert_eq!(
+            glob("**/*.txt", GlobOptions::default()).walk_aggregate(),
+            GlobAggregate {
+                count: 2,
+                total_size: 15.0
+            }
         );
-        let results = glob.walk_sync();
 
-        // Original pattern has /, so matchBase doesn't apply
-        // b*.txt stays as b*.txt (matches at root)
-        // b/c stays as b/c
-        // So only exact matches at specified locations
-        // bar.txt matches b*.txt (at root)
-        assert!(results.contains(&"bar.txt".to_string()));
+        // The sizes come with the listings, so aggregating reads no more than walking
+        let calls = || archive.calls.load(Ordering::Relaxed);
+        let before = calls();
+        glob("**/*.txt", GlobOptions::default()).walk_stream_with_file_types(|_| {});
+        let walked = calls() - before;
+        let before = calls();
+        glob("**/*.txt", GlobOptions::default()).walk_aggregate();
+        assert_eq!(calls() - before, walked);
     }
 
-    // Multiple patterns tests
-
     #[test]
-    fn test_multiple_patterns_basic() {
-        let temp = create_test_fixture();
-        let glob = Glob::new_multi(
-            vec!["*.txt".to_string(), "*.js".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
+    fn test_unreadable_walk_root_is_reported_by_the_walk() {
+        use crate::fs::MemoryFileSystem;
+
+        let root = "/globlin-virtual-locked";
+        let archive = Arc::new(
+            MemoryFileSystem::new(root, &["locked/secret.txt", "open/a.txt"])
+                .with_locked(root, "locked"),
         );
-        let results = glob.walk_sync();
+        let run = |pattern: &str, suppress: Option<bool>| {
+            let glob = Glob::new(
+                pattern.to_string(),
+                GlobOptions {
+                    cwd: Some(root.to_string()),
+                    suppress_errors: suppress,
+                    ..Default::default()
+                },
+            )
+            .with_file_system(archive.clone());
+            glob.check_walk(glob.walk_sync())
+        };
 
-        // Should match both .txt and .js files
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-        assert!(results.contains(&"baz.js".to_string()));
+        let err = run("locked/**", None).unwrap_err();
+        assert!(err.reason.contains("locked"), "{}", err.reason);
+        // Also when the walk starts above it, at the root of another pattern
+        assert!(run("{locked,open}/*.txt", None).is_err());
+        // The error doesn't outlive the walk that found it
+        assert_eq!(run("open/*.txt", None).unwrap(), ["open/a.txt"]);
+
+        assert_eq!(run("locked/**", Some(true)).unwrap(), ["locked"]);
+        // Unreadable directories below the roots are skipped
+        assert_eq!(run("**/*.txt", None).unwrap(), ["open/a.txt"]);
     }
 
     #[test]
-    fn test_multiple_patterns_with_globstar() {
+    fn test_dir_order_post_order() {
         let temp = create_test_fixture();
-        let glob = Glob::new_multi(
-            vec!["*.txt".to_string(), "**/*.js".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let position = |results: &[String], path: &str| {
+            results
+                .iter()
+                .position(|r| r == path)
+                .unwrap_or_else(|| panic!("missing {path} in {results:?}"))
+        };
 
-        // Should match root .txt and all .js files
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-        assert!(results.contains(&"baz.js".to_string()));
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
-        assert!(results.contains(&p("src/lib/helper.js")));
+        for patterns in [vec!["**"], vec!["src/**"], vec!["src", "src/main.js"]] {
+            let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+            let glob = |dir_order| {
+                Glob::new_multi(
+                    patterns.clone(),
+                    GlobOptions {
+                        dir_order,
+                        ..make_opts(&cwd)
+                    },
+                )
+            };
+
+            let pre = glob(None).walk_sync();
+            assert!(position(&pre, "src") < position(&pre, "src/main.js"));
+
+            let post_glob = glob(Some(DirOrder::PostOrder));
+            let post = post_glob.walk_sync();
+            assert!(position(&post, "src") > position(&post, "src/main.js"));
+            let mut streamed = Vec::new();
+            post_glob.walk_stream(|r| streamed.push(r));
+            assert_eq!(streamed, post);
+            let typed: Vec<String> = post_glob
+                .walk_sync_with_file_types()
+                .into_iter()
+                .map(|d| d.path)
+                .collect();
+            assert_eq!(typed, post);
+
+            let (mut pre, mut post) = (pre, post);
+            pre.sort();
+            post.sort();
+            assert_eq!(post, pre);
+        }
+
+        // A walk including cwd itself ends with it
+        let post = Glob::new(
+            "**".to_string(),
+            GlobOptions {
+                dir_order: Some(DirOrder::PostOrder),
+                ..make_opts(&cwd)
+            },
+        )
+        .walk_sync();
+        assert_eq!(post.last().map(String::as_str), Some("."));
     }
 
     #[test]
-    fn test_multiple_patterns_deduplication() {
-        let temp = create_test_fixture();
-        let glob = Glob::new_multi(
-            vec!["*.txt".to_string(), "foo.txt".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
+    fn test_glob_plan_strategy() {
+        let temp = create_multi_base_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let strategy = |patterns: &[&str], opts: GlobOptions| {
+            let patterns = patterns.iter().map(|p| p.to_string()).collect();
+            Glob::new_multi(patterns, opts).strategy()
+        };
+
+        let opts = || make_opts(&cwd);
+        assert_eq!(strategy(&["package.json"], opts()), WalkStrategy::Static);
+        assert_eq!(
+            strategy(&["src/main.ts", "lib/index.ts"], opts()),
+            WalkStrategy::Static
         );
-        let results = glob.walk_sync();
+        assert_eq!(strategy(&["*.json"], opts()), WalkStrategy::Shallow);
+        assert_eq!(
+            strategy(&["src/**/*.ts", "test/**/*.ts"], opts()),
+            WalkStrategy::MultiBase
+        );
+        assert_eq!(
+            strategy(&["src/**/*.ts"], opts()),
+            WalkStrategy::PrefixScoped
+        );
+        assert_eq!(strategy(&["**/*.ts"], opts()), WalkStrategy::FullWalk);
 
-        // foo.txt should only appear once despite matching both patterns
-        let foo_count = results.iter().filter(|r| *r == "foo.txt").count();
-        assert_eq!(foo_count, 1);
-        assert!(results.contains(&"bar.txt".to_string()));
+        // maxDepth: 0 and unique: false bypass the fast paths
+        let depth_zero = GlobOptions {
+            max_depth: Some(0),
+            ..opts()
+        };
+        assert_eq!(strategy(&["*.json"], depth_zero), WalkStrategy::FullWalk);
+        let not_unique = GlobOptions {
+            unique: Some(false),
+            ..opts()
+        };
+        assert_eq!(strategy(&["*.json"], not_unique), WalkStrategy::FullWalk);
+
+        let plan = glob_plan(Either::A("src/**/*.ts".to_string()), Some(opts())).unwrap();
+        assert_eq!(plan.strategy, "PrefixScoped");
+        assert_eq!(plan.prefix.as_deref(), Some("src"));
+        assert_eq!(PathBuf::from(&plan.walk_root), temp.path().join("src"));
+
+        let plan = glob_plan(Either::A("**/*.ts".to_string()), Some(opts())).unwrap();
+        assert_eq!(plan.strategy, "FullWalk");
+        assert_eq!(plan.prefix, None);
+        assert_eq!(PathBuf::from(&plan.walk_root), temp.path());
+
+        let plan = glob_plan(Either::A("*.json".to_string()), Some(opts())).unwrap();
+        assert_eq!(plan.strategy, "Shallow");
+        assert_eq!(PathBuf::from(&plan.walk_root), temp.path());
     }
 
     #[test]
-    fn test_multiple_patterns_disjoint() {
+    fn test_shallow_fast_path_with_ignore() {
         let temp = create_test_fixture();
-        let glob = Glob::new_multi(
-            vec!["foo.txt".to_string(), "baz.js".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
+        let cwd = temp.path().to_string_lossy().to_string();
+        File::create(temp.path().join("qux.js")).unwrap();
+
+        let with_ignore = |pattern: &str, ignore: &[&str], extra: GlobOptions| {
+            let glob = Glob::new(
+                pattern.to_string(),
+                GlobOptions {
+                    ignore: Some(Either::B(ignore.iter().map(|s| s.to_string()).collect())),
+                    ..extra
+                },
+            );
+            assert!(glob.all_patterns_shallow());
+            walk_all_four(&glob)
+        };
 
-        assert_eq!(results.len(), 2);
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"baz.js".to_string()));
+        // The shallow readdir (walk_sync) agrees with the walker (the other three)
+        for results in with_ignore("*.js", &["baz.js"], make_opts(&cwd)) {
+            assert_eq!(results, vec!["qux.js"]);
+        }
+        for results in with_ignore("*", &["*.txt", "src/**"], make_opts(&cwd)) {
+            assert_eq!(results, vec!["baz.js", "qux.js"]);
+        }
+
+        // Absolute ignore patterns resolve against cwd
+        let abs_ignore = format!("{}/qux.js", cwd.replace('\\', "/"));
+        for results in with_ignore("*.js", &[&abs_ignore], make_opts(&cwd)) {
+            assert_eq!(results, vec!["baz.js"]);
+        }
+
+        let dot = GlobOptions {
+            dot: Some(true),
+            ..make_opts(&cwd)
+        };
+        for results in with_ignore(".*", &[".git"], dot) {
+            assert_eq!(results, vec![".gitignore", ".hidden"]);
+        }
     }
 
     #[test]
-    fn test_multiple_patterns_empty() {
+    fn test_cwd_only_matched_by_globstar_or_dot() {
         let temp = create_test_fixture();
-        let glob = Glob::new_multi(Vec::new(), make_opts(&temp.path().to_string_lossy()));
-        let results = glob.walk_sync();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let is_cwd = |r: &String| r == "." || r == "./" || r == ".\\";
+
+        for pattern in ["*", "*.txt", "*/", "./*", "?", "[.]", "**", ".", "./**"] {
+            for nodir in [None, Some(false), Some(true)] {
+                for mark in [false, true] {
+                    for dot in [false, true] {
+                        let glob = Glob::new(
+                            pattern.to_string(),
+                            GlobOptions {
+                                nodir,
+                                mark: Some(mark),
+                                ..make_opts_with_dot(&cwd, dot)
+                            },
+                        );
+                        let expected =
+                            matches!(pattern, "**" | "." | "./**") && nodir != Some(true);
+                        for results in walk_all_four(&glob) {
+                            assert_eq!(
+                                results.iter().any(is_cwd),
+                                expected,
+                                "pattern {pattern:?}, nodir {nodir:?}, mark {mark}, dot {dot}: {results:?}"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
 
-        // Empty patterns array should match nothing
-        assert!(results.is_empty());
+    #[test]
+    fn test_include_child_matches_false_all_walk_functions() {
+        let temp = create_child_matches_fixture();
+        let opts = GlobOptions {
+            cwd: Some(temp.path().to_string_lossy().to_string()),
+            include_child_matches: Some(false),
+            ..Default::default()
+        };
+        let glob = Glob::new_multi(vec!["a".to_string(), "**/*.txt".to_string()], opts);
+
+        let names = [
+            "walk_sync",
+            "walk_sync_with_file_types",
+            "walk_stream",
+            "walk_stream_with_file_types",
+        ];
+        for (name, results) in names.iter().zip(walk_all_four(&glob)) {
+            // The matched directory suppresses its descendants
+            assert!(results.contains(&"a".to_string()), "{name}: {results:?}");
+            assert!(!results.contains(&p("a/b.txt")), "{name}: {results:?}");
+            assert!(!results.contains(&p("a/c/d.txt")), "{name}: {results:?}");
+
+            // `ab` shares a byte prefix with `a` but is a sibling, not a child
+            assert!(results.contains(&p("ab/b.txt")), "{name}: {results:?}");
+
+            // A matched file does not suppress siblings that start with its name
+            assert!(
+                results.contains(&"f.txt".to_string()),
+                "{name}: {results:?}"
+            );
+            assert!(
+                results.contains(&p("f.txt-dir/g.txt")),
+                "{name}: {results:?}"
+            );
+        }
     }
 
     #[test]
-    fn test_multiple_patterns_with_scoped() {
-        let temp = create_test_fixture();
+    fn test_include_child_matches_true_all_walk_functions() {
+        let temp = create_child_matches_fixture();
         let glob = Glob::new_multi(
-            vec!["src/*.js".to_string(), "*.txt".to_string()],
+            vec!["a".to_string(), "**/*.txt".to_string()],
             make_opts(&temp.path().to_string_lossy()),
         );
-        let results = glob.walk_sync();
 
-        // Should match src/*.js and root *.txt
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-        // Should NOT match nested files
-        assert!(!results.contains(&p("src/lib/helper.js")));
+        let names = [
+            "walk_sync",
+            "walk_sync_with_file_types",
+            "walk_stream",
+            "walk_stream_with_file_types",
+        ];
+        for (name, results) in names.iter().zip(walk_all_four(&glob)) {
+            assert!(results.contains(&"a".to_string()), "{name}: {results:?}");
+            assert!(results.contains(&p("a/b.txt")), "{name}: {results:?}");
+            assert!(results.contains(&p("a/c/d.txt")), "{name}: {results:?}");
+        }
     }
 
-    // Depth-limited walking optimization tests (Task 2.5.1.3)
+    #[test]
+    fn test_include_child_matches_false_static_patterns() {
+        let temp = create_child_matches_fixture();
+        let opts = GlobOptions {
+            cwd: Some(temp.path().to_string_lossy().to_string()),
+            include_child_matches: Some(false),
+            ..Default::default()
+        };
+        let glob = Glob::new_multi(vec!["a".to_string(), "a/b.txt".to_string()], opts);
+
+        let names = [
+            "walk_sync",
+            "walk_sync_with_file_types",
+            "walk_stream",
+            "walk_stream_with_file_types",
+        ];
+        for (name, results) in names.iter().zip(walk_all_four(&glob)) {
+            assert_eq!(results, vec!["a".to_string()], "{name}");
+        }
+    }
 
     #[test]
-    fn test_depth_limited_simple_pattern() {
-        // Simple patterns like *.txt should only traverse root directory
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "*.txt".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
+    fn test_is_child_of_matched_sibling_prefix() {
+        let glob = Glob::new("**".to_string(), GlobOptions::default());
+        let mut parents = AHashSet::new();
+        parents.insert("a".to_string());
 
-        // Should find files at root only
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-        // Should NOT find nested files (and shouldn't even traverse there)
-        assert!(!results.iter().any(|r| r.contains('/')));
+        assert!(glob.is_child_of_matched("a/b", &parents));
+        assert!(glob.is_child_of_matched("a/b/c", &parents));
+        assert!(glob.is_child_of_matched("a\\b", &parents));
+
+        // Same path and byte-prefix siblings are not children
+        assert!(!glob.is_child_of_matched("a", &parents));
+        assert!(!glob.is_child_of_matched("ab", &parents));
+        assert!(!glob.is_child_of_matched("ab/c", &parents));
+        assert!(!glob.is_child_of_matched("b/a", &parents));
     }
 
     #[test]
-    fn test_depth_limited_one_level_pattern() {
-        // Pattern like src/*.js has depth 1
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "src/*.js".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
+    fn test_normalize_path_preserves_root_separators() {
+        let glob = Glob::new("**".to_string(), GlobOptions::default());
+
+        // UNC root already ends with a separator
+        let unc = Some("//server/share/".to_string());
+        assert_eq!(
+            glob.normalize_path("dir/file.txt", &unc, false),
+            "//server/share/dir/file.txt"
         );
-        let results = glob.walk_sync();
+        assert_eq!(glob.normalize_path("", &unc, true), "//server/share/");
 
-        // Should find src/*.js files
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
-        // Should NOT find deeply nested files
-        assert!(!results.contains(&p("src/lib/helper.js")));
+        // Posix filesystem root
+        let root = Some("/".to_string());
+        assert_eq!(glob.normalize_path("tmp", &root, false), "/tmp");
+
+        // Ordinary prefixes still get a separator
+        let prefix = Some("//server/share/dir".to_string());
+        assert_eq!(
+            glob.normalize_path("file.txt", &prefix, false),
+            "//server/share/dir/file.txt"
+        );
+
+        let mut buffer = String::new();
+        let with_slash = unc.as_deref().map(prefix_with_separator);
+        assert_eq!(
+            Glob::normalize_path_buffered(
+                "dir/file.txt",
+                &unc,
+                &with_slash,
+                false,
+                true,
+                &mut buffer
+            ),
+            "//server/share/dir/file.txt"
+        );
+        assert_eq!(
+            Glob::normalize_path_buffered("dir/file.txt", &unc, &None, false, true, &mut buffer),
+            "//server/share/dir/file.txt"
+        );
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_depth_limited_two_level_pattern() {
-        // Pattern like src/lib/*.js has depth 2
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "src/lib/*.js".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
+    fn test_build_result_path_preserves_unc_root() {
+        let abs_cwd = PathBuf::from("/cwd");
+        let mut buffer = String::new();
+
+        for posix in [true, false] {
+            for absolute in [true, false] {
+                let opts = GlobOptions {
+                    posix: Some(posix),
+                    absolute: Some(absolute),
+                    ..Default::default()
+                };
+                let glob = Glob::new("**".to_string(), opts);
+                let result = glob.build_result_path(
+                    "//server/share/dir/file.txt",
+                    false,
+                    false,
+                    &abs_cwd,
+                    &mut buffer,
+                );
+                assert_eq!(
+                    result, "//server/share/dir/file.txt",
+                    "posix: {posix}, absolute: {absolute}"
+                );
+            }
+        }
+    }
 
-        // Should find src/lib/*.js files
-        assert!(results.contains(&p("src/lib/helper.js")));
-        // Should NOT find files at other depths
-        assert!(!results.contains(&"baz.js".to_string()));
-        assert!(!results.contains(&p("src/main.js")));
+    /// On posix systems `//a/b` resolves like `/a/b`, so a temp dir can stand in
+    /// for a mapped share when the pattern is parsed with win32 UNC rules.
+    #[cfg(unix)]
+    #[test]
+    fn test_unc_pattern_walk_preserves_share_root() {
+        let temp = tempfile::Builder::new().prefix("share").tempdir().unwrap();
+        fs::create_dir_all(temp.path().join("dir")).unwrap();
+        File::create(temp.path().join("dir/file.txt")).unwrap();
+        File::create(temp.path().join("top.txt")).unwrap();
+
+        let share = format!("/{}", temp.path().to_string_lossy());
+        assert!(share.starts_with("//"));
+
+        for posix in [true, false] {
+            let opts = GlobOptions {
+                platform: Some("win32".to_string()),
+                nocase: Some(false),
+                posix: Some(posix),
+                ..Default::default()
+            };
+            let glob = Glob::new(format!("{share}/**/*.txt"), opts);
+            let mut results = glob.walk_sync();
+            results.sort();
+
+            assert_eq!(
+                results,
+                vec![format!("{share}/dir/file.txt"), format!("{share}/top.txt")],
+                "posix: {posix}"
+            );
+        }
     }
 
     #[test]
-    fn test_depth_unlimited_with_globstar() {
-        // Pattern with ** should traverse unlimited depth
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "**/*.js".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
+    #[cfg(unix)]
+    fn test_escaped_literals_reach_the_disk() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir(base.join("d*r")).unwrap();
+        File::create(base.join("d*r/x.js")).unwrap();
+        File::create(base.join(r"a\b.txt")).unwrap();
+        File::create(base.join("a*b")).unwrap();
+        File::create(base.join("ab.txt")).unwrap();
+        let cwd = base.to_string_lossy().to_string();
+        let run = |pattern: &str, disable_optimizations: Option<bool>| {
+            let opts = GlobOptions {
+                disable_optimizations,
+                preserve_backslashes: Some(true),
+                ..make_opts(&cwd)
+            };
+            let mut results = Glob::new(pattern.to_string(), opts).walk_sync();
+            results.sort();
+            results
+        };
 
-        // Should find files at ALL depths
-        assert!(results.contains(&"baz.js".to_string()));
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
-        assert!(results.contains(&p("src/lib/helper.js")));
+        for disable_optimizations in [None, Some(true)] {
+            // A static path and a walk prefix are unescaped before the disk is read
+            assert_eq!(run(r"a\*b", disable_optimizations), ["a*b"]);
+            assert_eq!(run(r"d\*r/*.js", disable_optimizations), ["d*r/x.js"]);
+            // Without a brace pair, `\\` stays an escaped backslash
+            assert_eq!(run(r"a\\*", disable_optimizations), [r"a\b.txt"]);
+        }
     }
 
     #[test]
-    fn test_depth_limited_multiple_patterns_bounded() {
-        // Multiple patterns, all bounded - should use max depth
-        let temp = create_test_fixture();
-        let glob = Glob::new_multi(
-            vec!["*.txt".to_string(), "src/*.js".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
+    fn test_ignore_escaped_bang_literal_file() {
+        let temp = TempDir::new().unwrap();
+        File::create(temp.path().join("!important.txt")).unwrap();
+        File::create(temp.path().join("important.txt")).unwrap();
+
+        let opts = GlobOptions {
+            cwd: Some(temp.path().to_string_lossy().to_string()),
+            ignore: Some(Either::A("\\!important.txt".to_string())),
+            ..Default::default()
+        };
+        let results = Glob::new("*.txt".to_string(), opts).walk_sync();
+
+        assert_eq!(results, vec!["important.txt".to_string()]);
+
+        // An escaped `!(` is literal text, not a negated extglob
+        for name in ["!(draft).md", "draft.md", "a.md", "!a.txt"] {
+            File::create(temp.path().join(name)).unwrap();
+        }
+        let run = |ignore: &str| {
+            let opts = GlobOptions {
+                cwd: Some(temp.path().to_string_lossy().to_string()),
+                ignore: Some(Either::A(ignore.to_string())),
+                ..Default::default()
+            };
+            let mut results = Glob::new("*".to_string(), opts).walk_sync();
+            results.sort();
+            results
+        };
+        assert_eq!(
+            run("\\!(draft).md"),
+            [
+                "!a.txt",
+                "!important.txt",
+                "a.md",
+                "draft.md",
+                "important.txt"
+            ]
         );
-        let results = glob.walk_sync();
+        assert_eq!(
+            run("\\!*.txt"),
+            ["!(draft).md", "a.md", "draft.md", "important.txt"]
+        );
+    }
 
-        // Should find root .txt and src/*.js
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-        assert!(results.contains(&p("src/main.js")));
-        // Should NOT find deeply nested files
-        assert!(!results.contains(&p("src/lib/helper.js")));
+    fn pattern_input(pattern: &str, options: Option<PatternOverrides>) -> PatternInput {
+        PatternInput {
+            pattern: pattern.to_string(),
+            options,
+        }
     }
 
     #[test]
-    fn test_depth_limited_multiple_patterns_one_unlimited() {
-        // If any pattern has **, should traverse unlimited depth
-        let temp = create_test_fixture();
-        let glob = Glob::new_multi(
-            vec!["*.txt".to_string(), "**/*.js".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
+    fn test_per_pattern_nocase_override() {
+        let temp = TempDir::new().unwrap();
+        File::create(temp.path().join("README.MD")).unwrap();
+        File::create(temp.path().join("notes.TXT")).unwrap();
+        File::create(temp.path().join("other.txt")).unwrap();
 
-        // Should find files at all depths due to **/*.js pattern
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"baz.js".to_string()));
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/lib/helper.js")));
+        let opts = GlobOptions {
+            cwd: Some(temp.path().to_string_lossy().to_string()),
+            nocase: Some(false),
+            ..Default::default()
+        };
+        let inputs = vec![
+            // Case-sensitive: must not match README.MD
+            pattern_input("*.md", None),
+            // Case-insensitive: matches notes.TXT and other.txt
+            pattern_input(
+                "*.txt",
+                Some(PatternOverrides {
+                    nocase: Some(true),
+                    ..Default::default()
+                }),
+            ),
+        ];
+        let mut results = Glob::new_multi_with_overrides(inputs, opts).walk_sync();
+        results.sort();
+
+        assert_eq!(results, vec!["notes.TXT", "other.txt"]);
     }
 
     #[test]
-    fn test_depth_limited_user_max_depth_override() {
-        // User-provided maxDepth should take precedence over pattern depth
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "**/*.js".to_string(),
-            make_opts_with_max_depth(&temp.path().to_string_lossy(), 1),
-        );
-        let results = glob.walk_sync();
+    fn test_per_pattern_nocase_same_pattern_not_deduplicated() {
+        let temp = TempDir::new().unwrap();
+        File::create(temp.path().join("a.TXT")).unwrap();
+        File::create(temp.path().join("b.txt")).unwrap();
 
-        // Even though pattern has **, maxDepth: 1 should limit to root only
-        assert!(results.contains(&"baz.js".to_string()));
-        assert!(!results.contains(&p("src/main.js")));
+        let opts = GlobOptions {
+            cwd: Some(temp.path().to_string_lossy().to_string()),
+            nocase: Some(false),
+            ..Default::default()
+        };
+        let inputs = vec![
+            pattern_input("*.TXT", None),
+            pattern_input(
+                "*.TXT",
+                Some(PatternOverrides {
+                    nocase: Some(true),
+                    ..Default::default()
+                }),
+            ),
+        ];
+        let mut results = Glob::new_multi_with_overrides(inputs, opts).walk_sync();
+        results.sort();
+
+        assert_eq!(results, vec!["a.TXT", "b.txt"]);
     }
 
-    // Prefix-based walk root optimization tests (Task 2.5.2.3)
+    #[test]
+    fn test_per_pattern_dot_override() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".config")).unwrap();
+        File::create(temp.path().join(".config/app.json")).unwrap();
+        File::create(temp.path().join("package.json")).unwrap();
+        File::create(temp.path().join(".env.txt")).unwrap();
+        File::create(temp.path().join("notes.txt")).unwrap();
+
+        let opts = GlobOptions {
+            cwd: Some(temp.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let inputs = vec![
+            pattern_input(
+                "**/*.json",
+                Some(PatternOverrides {
+                    dot: Some(true),
+                    ..Default::default()
+                }),
+            ),
+            pattern_input("*.txt", None),
+        ];
+        let mut results = Glob::new_multi_with_overrides(inputs, opts.clone()).walk_sync();
+        results.sort();
+
+        // The dot override applies to the json pattern only; .env.txt stays hidden
+        assert_eq!(
+            results,
+            vec![".config/app.json", "notes.txt", "package.json"]
+        );
+
+        // The inverse: dot is on globally but one pattern opts out
+        let opts = GlobOptions {
+            dot: Some(true),
+            ..opts
+        };
+        let inputs = vec![
+            pattern_input(
+                "**/*.json",
+                Some(PatternOverrides {
+                    dot: Some(false),
+                    ..Default::default()
+                }),
+            ),
+            pattern_input("*.txt", None),
+        ];
+        let mut results = Glob::new_multi_with_overrides(inputs, opts).walk_sync();
+        results.sort();
+
+        assert_eq!(results, vec![".env.txt", "notes.txt", "package.json"]);
+    }
 
     #[test]
-    fn test_prefix_walk_root_scoped_pattern() {
-        // Pattern src/**/*.js should walk from src/ instead of cwd
+    fn test_dedup_strategies_produce_same_results() {
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "src/**/*.js".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
+        let cwd = temp.path().to_string_lossy().to_string();
 
-        // Should find all js files under src/
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
-        assert!(results.contains(&p("src/lib/helper.js")));
-        // Should NOT find root-level js
-        assert!(!results.contains(&"baz.js".to_string()));
+        // Overlapping patterns so deduplication actually has work to do
+        let pattern_sets: Vec<Vec<&str>> = vec![
+            vec!["**/*.js", "src/**/*.js", "**/main.js"],
+            vec!["**/*.{js,txt}", "*.txt"],
+            vec!["*.txt", "foo.txt", "*"],
+            vec!["src/main.js", "src/main.js", "foo.txt"],
+            vec!["**"],
+        ];
+
+        for patterns in pattern_sets {
+            for dot in [false, true] {
+                let strings: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+                let full = Glob::new_multi(
+                    strings.clone(),
+                    GlobOptions {
+                        dedup_strategy: Some(DedupStrategy::FullString),
+                        ..make_opts_with_dot(&cwd, dot)
+                    },
+                );
+                let hashed = Glob::new_multi(
+                    strings,
+                    GlobOptions {
+                        dedup_strategy: Some(DedupStrategy::Hash64),
+                        ..make_opts_with_dot(&cwd, dot)
+                    },
+                );
+
+                let full_results = walk_all_four(&full);
+                assert!(!full_results[0].is_empty(), "patterns: {patterns:?}");
+                assert_eq!(
+                    full_results,
+                    walk_all_four(&hashed),
+                    "patterns: {patterns:?}, dot: {dot}"
+                );
+            }
+        }
     }
 
     #[test]
-    fn test_prefix_walk_root_deep_scoped_pattern() {
-        // Pattern src/lib/**/*.js should walk from src/lib/
+    fn test_glob_sync_checked_returns_results_and_warnings() {
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "src/lib/**/*.js".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
+        let cwd = temp.path().to_string_lossy().to_string();
+
+        let checked = glob_sync_checked(
+            Either::A("**/**/**/*.js".to_string()),
+            Some(make_opts(&cwd)),
+        )
+        .unwrap();
+
+        let mut results = checked.results;
+        results.sort();
+        assert_eq!(
+            results,
+            vec![
+                p("baz.js"),
+                p("src/lib/helper.js"),
+                p("src/main.js"),
+                p("src/util.js")
+            ]
+        );
+        assert_eq!(checked.warnings.len(), 1);
+        assert_eq!(checked.warnings[0].warning_type, "performance");
+        assert_eq!(
+            checked.warnings[0].pattern.as_deref(),
+            Some("**/**/**/*.js")
         );
-        let results = glob.walk_sync();
 
-        // Should find files under src/lib/
-        assert!(results.contains(&p("src/lib/helper.js")));
-        // Should NOT find files at other locations
-        assert!(!results.contains(&p("src/main.js")));
-        assert!(!results.contains(&"baz.js".to_string()));
+        // A clean pattern yields results and no warnings
+        let checked =
+            glob_sync_checked(Either::A("*.txt".to_string()), Some(make_opts(&cwd))).unwrap();
+        assert_eq!(checked.results.len(), 2);
+        assert!(checked.warnings.is_empty());
     }
 
     #[test]
-    fn test_prefix_walk_root_nonexistent_prefix() {
-        // Pattern for non-existent directory should return empty
+    fn test_nodir_with_dir_only_patterns() {
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "nonexistent/**/*.js".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let opts = || GlobOptions {
+            nodir: Some(true),
+            ..make_opts(&cwd)
+        };
 
+        // `*/` can only match directories, so on its own it matches nothing, as in glob
+        let results = glob_sync(Either::A("*/".to_string()), Some(opts())).unwrap();
         assert!(results.is_empty());
+
+        // Alongside a file pattern it contributes nothing and is flagged
+        let patterns = vec![Either::A("*/".to_string()), Either::A("*.txt".to_string())];
+        let checked = glob_sync_checked(Either::B(patterns), Some(opts())).unwrap();
+        let mut results = checked.results;
+        results.sort();
+        assert_eq!(results, vec!["bar.txt", "foo.txt"]);
+        assert_eq!(checked.warnings.len(), 1);
+        assert_eq!(checked.warnings[0].warning_type, "impossible_pattern");
+        assert_eq!(checked.warnings[0].pattern.as_deref(), Some("*/"));
+        assert_eq!(checked.warnings[0].suggestion.as_deref(), Some("*"));
     }
 
     #[test]
-    fn test_prefix_walk_root_multiple_patterns_same_prefix() {
-        // Multiple patterns with same prefix should use that prefix
+    fn test_nobrace_matches_literal_brace_range() {
         let temp = create_test_fixture();
-        let glob = Glob::new_multi(
-            vec!["src/**/*.js".to_string(), "src/**/*.ts".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
+        File::create(temp.path().join("{1..5}")).unwrap();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let opts = GlobOptions {
+            nobrace: Some(true),
+            ..make_opts(&cwd)
+        };
+
+        // The range is literal text, so it finds the file named after it
+        let checked = glob_sync_checked(Either::A("{1..5}".to_string()), Some(opts)).unwrap();
+        assert_eq!(checked.results, vec!["{1..5}"]);
+        assert_eq!(checked.warnings.len(), 1);
+        assert_eq!(checked.warnings[0].warning_type, "literal_brace_range");
+        assert_eq!(
+            checked.warnings[0].suggestion.as_deref(),
+            Some("\\{1..5\\}")
         );
-        let results = glob.walk_sync();
+    }
 
-        // Should find js files under src/
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/lib/helper.js")));
-        // Should NOT find root-level files
-        assert!(!results.contains(&"baz.js".to_string()));
+    #[test]
+    fn test_matches_all() {
+        let v = |ps: &[&str]| ps.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        let check = |path: &str, include: &[&str], exclude: &[&str]| {
+            matches_all(path.into(), v(include), v(exclude), None).unwrap()
+        };
+
+        // Every include must match, not just one
+        let include = ["src/**", "**/*.ts"];
+        assert!(check("src/lib/util.ts", &include, &[]));
+        assert!(!check("src/lib/util.js", &include, &[]));
+        assert!(!check("test/util.ts", &include, &[]));
+
+        // Any exclude rejects the path
+        let exclude = ["**/*.test.ts", "**/fixtures/**"];
+        assert!(check("src/lib/util.ts", &include, &exclude));
+        assert!(!check("src/lib/util.test.ts", &include, &exclude));
+        assert!(!check("src/fixtures/data.ts", &include, &exclude));
+
+        // No includes: only the exclusions apply
+        assert!(check("anything.js", &[], &["*.ts"]));
+        assert!(!check("anything.ts", &[], &["*.ts"]));
+
+        // Options apply to every pattern
+        let dot = Some(MatchOptions {
+            dot: Some(true),
+            ..Default::default()
+        });
+        assert!(!check(".env", &["*"], &[]));
+        assert!(matches_all(".env".into(), v(&["*"]), v(&[]), dot).unwrap());
+
+        // An invalid pattern is an error even when another pattern already failed
+        let huge = "?".repeat(50_000);
+        assert!(matches_all("a".into(), v(&["b", &huge]), v(&[]), None).is_err());
+    }
+
+    #[test]
+    fn test_matches_path_backslash_candidate() {
+        let pat = |s: &str| Either::A(s.to_string());
+        let matches = |path: &str, pattern: &str, opts: Option<MatchOptions>| {
+            matches_path(path.into(), pat(pattern), opts).unwrap()
+        };
+
+        // Backslash-separated candidates match forward-slash patterns
+        assert!(matches("src\\main.ts", "src/*.ts", None));
+        assert!(matches("src\\lib\\util.ts", "src/**/*.ts", None));
+        assert!(matches(".\\src\\main.ts", "src/*.ts", None));
+        assert!(matches("src\\", "*/", None));
+        assert!(!matches("src\\main.ts", "lib/*.ts", None));
+        assert!(can_contain_match("src\\lib".into(), pat("src/lib/*.ts"), None).unwrap());
+        let v = |p: &str| vec![p.to_string()];
+        assert!(matches_all("src\\main.ts".into(), v("src/*"), v("src/*.js"), None).unwrap());
+
+        assert!(matches(
+            "src\\main.ts",
+            "src/*.ts",
+            Some(MatchOptions {
+                normalize_candidate: Some(true),
+                ..Default::default()
+            })
+        ));
+
+        // preserveBackslashes keeps `\` as part of the name (non-Windows only)
+        #[cfg(not(windows))]
+        {
+            let preserve = || {
+                Some(MatchOptions {
+                    preserve_backslashes: Some(true),
+                    ..Default::default()
+                })
+            };
+            assert!(!matches("src\\main.ts", "src/*.ts", preserve()));
+            assert!(matches("src\\main.ts", "*.ts", preserve()));
+        }
+
+        // With a custom separator, `\` in a key is an ordinary character
+        let dotted = Some(MatchOptions {
+            separator: Some(".".to_string()),
+            ..Default::default()
+        });
+        assert!(matches("a\\b.c", "*.c", dotted.clone()));
+        assert!(!matches("a\\b.c", "a.*.c", dotted));
     }
 
+    #[cfg(windows)]
     #[test]
-    fn test_prefix_walk_root_multiple_patterns_different_prefix() {
-        // Multiple patterns with different prefixes - should walk from common prefix or root
+    fn test_trailing_dot_and_space_on_windows() {
         let temp = TempDir::new().unwrap();
-        let base = temp.path();
-
-        fs::create_dir_all(base.join("dir1")).unwrap();
-        fs::create_dir_all(base.join("dir2")).unwrap();
-        File::create(base.join("dir1/file.js")).unwrap();
-        File::create(base.join("dir2/file.ts")).unwrap();
-        File::create(base.join("root.txt")).unwrap();
+        fs::create_dir(temp.path().join("dir")).unwrap();
+        File::create(temp.path().join("dir/foo")).unwrap();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let walk = |pattern: &str| {
+            let opts = GlobOptions {
+                posix: Some(true),
+                ..make_opts(&cwd)
+            };
+            Glob::new(pattern.to_string(), opts).walk_sync()
+        };
 
-        let glob = Glob::new_multi(
-            vec!["dir1/**/*.js".to_string(), "dir2/**/*.ts".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
+        for pattern in ["dir/foo.", "dir/foo ", "dir./foo", "d*/foo.", "dir /f*"] {
+            assert_eq!(walk(pattern), vec!["dir/foo"], "{pattern}");
+        }
 
-        // Should find files from both directories
-        assert!(results.contains(&p("dir1/file.js")));
-        assert!(results.contains(&p("dir2/file.ts")));
-        // Should NOT match root files
-        assert!(!results.contains(&"root.txt".to_string()));
+        let pat = |s: &str| Either::A(s.to_string());
+        assert!(matches_path("dir/foo.".into(), pat("dir/foo"), None).unwrap());
+        assert!(matches_path("dir/foo".into(), pat("dir/foo."), None).unwrap());
     }
 
     #[test]
-    fn test_prefix_walk_root_with_max_depth() {
-        // Scoped pattern with maxDepth should adjust depth relative to cwd
+    fn test_walk_stream_batched() {
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "src/**/*.js".to_string(),
-            make_opts_with_max_depth(&temp.path().to_string_lossy(), 2),
-        );
-        let results = glob.walk_sync();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let glob = Glob::new("**/*".to_string(), make_opts_with_dot(&cwd, true));
+        let mut expected = glob.walk_sync();
+        expected.sort();
+        let total = expected.len();
+        assert!(total > 3);
 
-        // maxDepth: 2 means up to depth 2 from cwd
-        // src is depth 1, src/* is depth 2, src/lib/* is depth 3
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("src/util.js")));
-        // src/lib/helper.js is depth 3, should be excluded
-        assert!(!results.contains(&p("src/lib/helper.js")));
-    }
+        for batch_size in [1, 2, 3, total, total + 5] {
+            let mut batches: Vec<Vec<String>> = Vec::new();
+            let delivered = glob.walk_stream_batched(batch_size, |batch| batches.push(batch));
 
-    #[test]
-    fn test_longest_common_prefix() {
-        // Test the longest_common_prefix helper
-        assert_eq!(Glob::longest_common_prefix(&["src/lib", "src/bin"]), "src");
-        assert_eq!(Glob::longest_common_prefix(&["src", "test"]), "");
+            assert_eq!(
+                delivered,
+                total.div_ceil(batch_size),
+                "batch size {batch_size}"
+            );
+            assert_eq!(batches.len(), delivered);
+            let (last, full) = batches.split_last().unwrap();
+            assert!(full.iter().all(|b| b.len() == batch_size));
+            assert!(!last.is_empty() && last.len() <= batch_size);
+
+            let mut streamed: Vec<String> = batches.into_iter().flatten().collect();
+            streamed.sort();
+            assert_eq!(streamed, expected, "batch size {batch_size}");
+        }
+
+        // Nothing matched: no callback at all
+        let empty = Glob::new("*.nothing".to_string(), make_opts(&cwd));
         assert_eq!(
-            Glob::longest_common_prefix(&["packages/foo", "packages/bar"]),
-            "packages"
+            empty.walk_stream_batched(10, |_| panic!("unexpected batch")),
+            0
         );
-        assert_eq!(Glob::longest_common_prefix(&["a/b/c", "a/b/d"]), "a/b");
-        assert_eq!(Glob::longest_common_prefix(&["x"]), "x");
-        assert_eq!(Glob::longest_common_prefix(&[]), "");
     }
 
-    // Directory pruning tests (Task 2.5.3.3)
-
     #[test]
-    fn test_directory_pruning_scoped_pattern() {
-        // Pattern src/lib/**/*.js should only traverse src/lib, not test/ or other dirs
+    fn test_double_star_inside_segment() {
         let temp = TempDir::new().unwrap();
         let base = temp.path();
+        for file in ["a.js", "ab.js", "axb.js", "src/a.js", "a/b.js"] {
+            let path = base.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            File::create(path).unwrap();
+        }
+        let cwd = base.to_string_lossy().to_string();
+        let walk = |pattern: &str| {
+            let mut results = Glob::new(pattern.to_string(), make_opts(&cwd)).walk_sync();
+            results.sort();
+            results
+        };
 
-        // Create a structure with multiple top-level directories
-        fs::create_dir_all(base.join("src/lib/deep")).unwrap();
-        fs::create_dir_all(base.join("test/unit")).unwrap();
-        fs::create_dir_all(base.join("docs")).unwrap();
-
-        File::create(base.join("src/lib/helper.js")).unwrap();
-        File::create(base.join("src/lib/deep/nested.js")).unwrap();
-        File::create(base.join("test/unit/test.js")).unwrap();
-        File::create(base.join("docs/readme.js")).unwrap();
-
-        let glob = Glob::new(
-            "src/lib/**/*.js".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
-
-        // Should find files under src/lib/
-        assert!(results.contains(&p("src/lib/helper.js")));
-        assert!(results.contains(&p("src/lib/deep/nested.js")));
+        assert_eq!(walk("a**b.js"), walk("a*b.js"));
+        assert_eq!(walk("a**b.js"), vec!["ab.js", "axb.js"]);
+        assert_eq!(walk("**.js"), walk("*.js"));
+        assert_eq!(walk("src/**.js"), vec![p("src/a.js")]);
 
-        // Should NOT find files in other directories
-        assert!(!results.contains(&p("test/unit/test.js")));
-        assert!(!results.contains(&p("docs/readme.js")));
+        let strategy = Glob::new("**.js".to_string(), make_opts(&cwd)).strategy();
+        assert_eq!(strategy, WalkStrategy::Shallow);
     }
 
     #[test]
-    fn test_directory_pruning_multi_pattern() {
-        // Multiple patterns with different scopes - pruning should allow both paths
+    fn test_compound_suffix_does_not_match_plain_extension() {
         let temp = TempDir::new().unwrap();
         let base = temp.path();
+        for file in ["foo.ts", "foo.d.ts", "lib/bar.ts", "lib/bar.d.ts", "x.js"] {
+            let path = base.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            File::create(path).unwrap();
+        }
+        let cwd = base.to_string_lossy().to_string();
+        let walk = |pattern: &str| {
+            let mut results = Glob::new(pattern.to_string(), make_opts(&cwd)).walk_sync();
+            results.sort();
+            results
+        };
 
-        fs::create_dir_all(base.join("src")).unwrap();
-        fs::create_dir_all(base.join("test")).unwrap();
-        fs::create_dir_all(base.join("docs")).unwrap();
-
-        File::create(base.join("src/main.js")).unwrap();
-        File::create(base.join("test/test.ts")).unwrap();
-        File::create(base.join("docs/readme.md")).unwrap();
-
-        let glob = Glob::new_multi(
-            vec!["src/**/*.js".to_string(), "test/**/*.ts".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
+        assert_eq!(walk("*.d.ts"), vec!["foo.d.ts"]);
+        assert_eq!(walk("**/*.d.ts"), vec!["foo.d.ts", &p("lib/bar.d.ts")]);
+        assert_eq!(walk("*.{d.ts,js}"), vec!["foo.d.ts", "x.js"]);
+        assert_eq!(
+            walk("**/*.{d.ts,js}"),
+            vec!["foo.d.ts", &p("lib/bar.d.ts"), "x.js"]
         );
-        let results = glob.walk_sync();
+    }
 
-        // Should find files matching either pattern
-        assert!(results.contains(&p("src/main.js")));
-        assert!(results.contains(&p("test/test.ts")));
+    #[test]
+    fn test_repeated_slashes_collapse() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let walk = |pattern: &str| {
+            let mut results = Glob::new(pattern.to_string(), make_opts(&cwd)).walk_sync();
+            results.sort();
+            results
+        };
 
-        // Should NOT find files that don't match any pattern
-        assert!(!results.contains(&p("docs/readme.md")));
+        for (doubled, single) in [
+            ("src//*.js", "src/*.js"),
+            ("src///lib//*.js", "src/lib/*.js"),
+            ("src//**//*.js", "src/**/*.js"),
+            ("**//*.js", "**/*.js"),
+            ("src//main.js", "src/main.js"),
+            ("./src//*.js", "src/*.js"),
+        ] {
+            assert!(!walk(single).is_empty(), "{single}");
+            assert_eq!(walk(doubled), walk(single), "{doubled}");
+        }
+
+        // Candidates collapse too
+        let pat = |s: &str| Either::A(s.to_string());
+        assert!(matches_path("src//main.js".into(), pat("src/*.js"), None).unwrap());
+        assert!(matches_path("src/main.js".into(), pat("src//*.js"), None).unwrap());
+        assert!(matches_path("src\\\\main.js".into(), pat("src/*.js"), None).unwrap());
     }
 
     #[test]
-    fn test_directory_pruning_with_globstar_start() {
-        // Pattern **/*.js cannot prune directories (must visit all)
-        let temp = TempDir::new().unwrap();
-        let base = temp.path();
+    fn test_matches_path_normalize_candidate() {
+        let pat = |s: &str| Either::A(s.to_string());
+        let normalize = Some(MatchOptions {
+            normalize_candidate: Some(true),
+            ..Default::default()
+        });
 
-        fs::create_dir_all(base.join("a/b/c")).unwrap();
-        fs::create_dir_all(base.join("x/y/z")).unwrap();
+        // Without normalization `.`/`..` are ordinary segments
+        assert!(!matches_path("src/./main.ts".into(), pat("src/*.ts"), None).unwrap());
+        assert!(matches_path("src/./main.ts".into(), pat("src/*.ts"), normalize.clone()).unwrap());
+        assert!(matches_path(
+            "src/./lib/../main.ts".into(),
+            pat("src/*.ts"),
+            normalize.clone()
+        )
+        .unwrap());
+        assert!(!matches_path(
+            "src/lib/./util.ts".into(),
+            pat("src/*.ts"),
+            normalize.clone()
+        )
+        .unwrap());
+        assert!(matches_path("./a/../b.js".into(), pat("*.js"), normalize.clone()).unwrap());
+        assert!(matches_path("lib/../../up.js".into(), pat("../*.js"), normalize.clone()).unwrap());
+
+        // Leading ./ is always ignored, like the walk results
+        assert!(matches_path("./main.ts".into(), pat("*.ts"), None).unwrap());
+    }
 
-        File::create(base.join("a/b/c/file.js")).unwrap();
-        File::create(base.join("x/y/z/file.js")).unwrap();
+    #[test]
+    fn test_matches_path_with_base() {
+        let pat = |s: &str| Either::A(s.to_string());
+        let base = |strict: Option<bool>| {
+            Some(MatchOptions {
+                base: Some("/project".to_string()),
+                strict_base: strict,
+                ..Default::default()
+            })
+        };
 
-        let glob = Glob::new(
-            "**/*.js".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
+        assert!(matches_path("/project/src/a.ts".into(), pat("src/*.ts"), base(None)).unwrap());
+        assert!(matches_path("/project/src/".into(), pat("*/"), base(None)).unwrap());
+        assert!(matches_path("/project".into(), pat("**"), base(None)).unwrap());
+        assert!(matches_path("\\project\\src\\a.ts".into(), pat("src/*.ts"), base(None)).unwrap());
+        // Relative candidates are matched as given
+        assert!(matches_path("src/a.ts".into(), pat("src/*.ts"), base(None)).unwrap());
+        // Without a base the absolute path is matched as written
+        assert!(!matches_path("/project/src/a.ts".into(), pat("src/*.ts"), None).unwrap());
+
+        // Outside the base nothing matches, not even `**`
+        for path in ["/other/src/a.ts", "/projects/src/a.ts"] {
+            assert!(!matches_path(path.into(), pat("**"), base(None)).unwrap());
+            assert!(!matches_path(path.into(), pat("src/*.ts"), base(Some(false))).unwrap());
+            assert!(!can_contain_match(path.into(), pat("**"), base(None)).unwrap());
+            assert!(!matches_all(path.into(), vec![], vec!["x".into()], base(None)).unwrap());
+            let err = matches_path(path.into(), pat("src/*.ts"), base(Some(true))).unwrap_err();
+            assert!(
+                err.reason.contains("outside base '/project'"),
+                "{}",
+                err.reason
+            );
+        }
+        let explanation = explain_match("/other/a.ts".into(), pat("*.ts"), base(None)).unwrap();
+        assert!(!explanation.matched);
+        assert_eq!(explanation.candidate, "/other/a.ts");
+        assert!(
+            explanation.reason.contains("outside base"),
+            "{}",
+            explanation.reason
         );
-        let results = glob.walk_sync();
 
-        // Should find files in both paths since ** matches anything
-        assert!(results.contains(&p("a/b/c/file.js")));
-        assert!(results.contains(&p("x/y/z/file.js")));
+        assert!(matches_all(
+            "/project/src/a.ts".into(),
+            vec!["src/**".into()],
+            vec!["**/*.test.ts".into()],
+            base(Some(true))
+        )
+        .unwrap());
     }
 
     #[test]
-    fn test_directory_pruning_nested_match() {
-        // Pattern packages/*/src/**/*.ts - should only traverse packages/*/src paths
-        let temp = TempDir::new().unwrap();
-        let base = temp.path();
+    fn test_matches_path_dot_and_dir_rules() {
+        let pat = |s: &str| Either::A(s.to_string());
+
+        assert!(!matches_path(".env".into(), pat("*"), None).unwrap());
+        assert!(matches_path(
+            ".env".into(),
+            pat("*"),
+            Some(MatchOptions {
+                dot: Some(true),
+                ..Default::default()
+            })
+        )
+        .unwrap());
+        assert!(matches_path(".env".into(), pat(".*"), None).unwrap());
 
-        fs::create_dir_all(base.join("packages/foo/src/utils")).unwrap();
-        fs::create_dir_all(base.join("packages/foo/test")).unwrap();
-        fs::create_dir_all(base.join("packages/bar/src")).unwrap();
-        fs::create_dir_all(base.join("other")).unwrap();
+        // Patterns ending in / only match candidates marked as directories
+        assert!(matches_path("src/".into(), pat("*/"), None).unwrap());
+        assert!(!matches_path("src".into(), pat("*/"), None).unwrap());
+        assert!(matches_path("src/".into(), pat("*"), None).unwrap());
+    }
 
-        File::create(base.join("packages/foo/src/index.ts")).unwrap();
-        File::create(base.join("packages/foo/src/utils/helper.ts")).unwrap();
-        File::create(base.join("packages/foo/test/test.ts")).unwrap();
-        File::create(base.join("packages/bar/src/main.ts")).unwrap();
-        File::create(base.join("other/file.ts")).unwrap();
+    #[test]
+    fn test_spawn_stream_with_file_types() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let glob = Glob::new_multi(vec!["**".to_string()], make_opts(&cwd));
+        let expected = glob.walk_sync_with_file_types();
 
-        let glob = Glob::new(
-            "packages/*/src/**/*.ts".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
+        // A one-slot buffer forces the walker to wait for every read
+        let mut receiver = glob.spawn_stream_with_file_types(1);
+        let mut results = Vec::new();
+        while let Some(data) = receiver.blocking_recv() {
+            results.push(data);
+        }
 
-        // Should find files under packages/*/src
-        assert!(results.contains(&p("packages/foo/src/index.ts")));
-        assert!(results.contains(&p("packages/foo/src/utils/helper.ts")));
-        assert!(results.contains(&p("packages/bar/src/main.ts")));
+        assert_eq!(results.len(), expected.len());
+        let find = |path: &str| results.iter().find(|d| d.path == p(path)).unwrap();
+        assert!(find("src").is_directory && !find("src").is_file);
+        assert!(find("src/lib").is_directory);
+        assert!(find("src/main.js").is_file && !find("src/main.js").is_directory);
+        assert!(find("foo.txt").is_file);
+        assert!(find(".").is_directory);
 
-        // Should NOT find files outside of packages/*/src
-        assert!(!results.contains(&p("packages/foo/test/test.ts")));
-        assert!(!results.contains(&p("other/file.ts")));
+        // Closing the receiver stops the walk instead of blocking it forever
+        let glob = Glob::new_multi(vec!["**".to_string()], make_opts(&cwd));
+        let mut receiver = glob.spawn_stream_with_file_types(1);
+        assert!(receiver.blocking_recv().is_some());
+        receiver.close();
+        while receiver.blocking_recv().is_some() {}
     }
 
-    // Multi-pattern optimization tests (Task 2.5.6.3)
-
     #[test]
-    fn test_multi_pattern_deduplication() {
-        // Duplicate patterns from brace expansion should be deduplicated
+    fn test_iterator_close_waits_for_pending_batch() {
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "{*.txt,*.txt}".to_string(), // Brace expansion produces duplicates
-            make_opts(&temp.path().to_string_lossy()),
-        );
-
-        // Only 1 pattern should be stored (duplicates removed)
-        assert_eq!(glob.patterns.len(), 1);
-
-        let results = glob.walk_sync();
-        // foo.txt should only appear once
-        let foo_count = results.iter().filter(|r| *r == "foo.txt").count();
-        assert_eq!(foo_count, 1);
+        let cwd = temp.path().to_string_lossy().to_string();
+        let glob = Glob::new_multi(vec!["**".to_string()], make_opts(&cwd));
+        let (receiver, outcome) = glob.spawn_checked_stream_with_file_types(1);
+        let iterator = GlobFileTypesIterator {
+            receiver: Arc::new(Mutex::new(receiver)),
+            outcome,
+        };
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            // Hold the receiver as an in-flight nextBatch() would; close() must
+            // wait for it instead of giving up
+            let guard = iterator.receiver.lock().await;
+            let mut close = std::pin::pin!(iterator.close());
+            let pending = std::future::poll_fn(|cx| {
+                std::task::Poll::Ready(std::future::Future::poll(close.as_mut(), cx).is_pending())
+            })
+            .await;
+            assert!(pending);
+            drop(guard);
+            close.await;
+
+            // The walk was told to stop: the buffer drains and then ends
+            let mut drained = 0;
+            while !iterator.next_batch(None).await.unwrap().is_empty() {
+                drained += 1;
+                assert!(drained < 10, "walk kept producing after close()");
+            }
+        });
     }
 
     #[test]
-    fn test_multi_pattern_fast_path_ordering() {
-        // Fast-path patterns should be sorted first for early matching
+    fn test_restrict_to_cwd_rejects_escaping_patterns() {
         let temp = create_test_fixture();
-        let glob = Glob::new_multi(
-            vec![
-                "**/[a-z]*.js".to_string(), // Complex pattern (regex)
-                "*.txt".to_string(),        // Simple fast-path pattern
-                "**/*.ts".to_string(),      // Recursive fast-path pattern
-            ],
-            make_opts(&temp.path().to_string_lossy()),
-        );
+        let cwd = temp.path().to_string_lossy().to_string();
+        let restricted = || GlobOptions {
+            restrict_to_cwd: Some(true),
+            ..make_opts(&cwd)
+        };
+        let run = |pattern: &str| glob_sync(Either::A(pattern.to_string()), Some(restricted()));
+
+        for pattern in [
+            "..",
+            "../*",
+            "../../../etc/passwd",
+            "src/../../*",
+            "*/../../x",
+            "**/../../x",
+            "/etc/*",
+            "{src,..}/*.js",
+        ] {
+            let err = run(pattern).unwrap_err();
+            assert!(
+                err.reason.contains("restrictToCwd"),
+                "{pattern}: {}",
+                err.reason
+            );
+        }
 
-        // Check that patterns are reordered with fast-path first
-        // First should be fast-path (*.txt or **/*.ts)
-        assert!(glob.patterns[0].fast_path().is_fast() || glob.patterns[1].fast_path().is_fast());
+        // `..` that stays inside cwd is fine
+        assert_eq!(run("src/../*.txt").unwrap().len(), 2);
+        assert_eq!(run("src/lib/../*.js").unwrap().len(), 2);
+
+        // Absolute patterns are allowed when they start inside cwd
+        let root = temp.path().canonicalize().unwrap();
+        let inside = format!("{}/src/*.js", normalize_separator(&root.to_string_lossy()));
+        assert_eq!(run(&inside).unwrap().len(), 2);
+        let ancestor = format!(
+            "{}/*/src/*.js",
+            normalize_separator(&root.parent().unwrap().to_string_lossy())
+        );
+        assert!(run(&ancestor).is_err());
 
-        let results = glob.walk_sync();
-        // Should still find correct files
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-        assert!(results.contains(&"baz.js".to_string()));
+        // Without the option the same patterns are accepted
+        assert!(glob_sync(Either::A("../*".to_string()), Some(make_opts(&cwd))).is_ok());
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_multi_pattern_cross_brace_deduplication() {
-        // Brace expansion across multiple patterns should deduplicate
-        let temp = create_test_fixture();
-        let glob = Glob::new_multi(
+    fn test_restrict_to_cwd_drops_symlinks_leaving_cwd() {
+        use std::os::unix::fs::symlink;
+
+        let outside = TempDir::new().unwrap();
+        File::create(outside.path().join("secret.txt")).unwrap();
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("src")).unwrap();
+        File::create(base.join("src/a.txt")).unwrap();
+        symlink(outside.path(), base.join("out_dir")).unwrap();
+        symlink(outside.path().join("secret.txt"), base.join("out.txt")).unwrap();
+        symlink(
+            outside.path().join("missing.txt"),
+            base.join("broken_out.txt"),
+        )
+        .unwrap();
+        symlink(base.join("src"), base.join("in_dir")).unwrap();
+        let cwd = base.to_string_lossy().to_string();
+
+        let run = |pattern: &str, follow: bool, restrict: bool| {
+            let mut results = glob_sync(
+                Either::A(pattern.to_string()),
+                Some(GlobOptions {
+                    follow: Some(follow),
+                    restrict_to_cwd: Some(restrict),
+                    ..make_opts(&cwd)
+                }),
+            )
+            .unwrap();
+            results.sort();
+            results
+        };
+
+        assert_eq!(
+            run("**/*.txt", true, false),
             vec![
-                "*.{txt,js}".to_string(), // Expands to *.txt, *.js
-                "*.txt".to_string(),      // Duplicate with above
-            ],
-            make_opts(&temp.path().to_string_lossy()),
+                "broken_out.txt",
+                "in_dir/a.txt",
+                "out.txt",
+                "out_dir/secret.txt",
+                "src/a.txt"
+            ]
         );
+        assert_eq!(
+            run("**/*.txt", true, true),
+            vec!["in_dir/a.txt", "src/a.txt"]
+        );
+        assert_eq!(run("**/*.txt", false, true), vec!["src/a.txt"]);
 
-        // Should have 2 unique patterns: *.txt, *.js (not 3)
-        assert_eq!(glob.patterns.len(), 2);
+        // Walking through the link itself, and the static and shallow fast paths
+        assert!(run("out_dir/*", false, true).is_empty());
+        assert!(run("out_dir/**", true, true).is_empty());
+        assert!(run("out_dir/secret.txt", false, true).is_empty());
+        assert!(run("out.txt", true, true).is_empty());
+        assert_eq!(run("*", false, true), vec!["in_dir", "src"]);
+        assert_eq!(run("in_dir/a.txt", false, true), vec!["in_dir/a.txt"]);
+    }
 
-        let results = glob.walk_sync();
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"baz.js".to_string()));
+    #[test]
+    fn test_negated_extglob_top_level_directory() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("node_modules/pkg")).unwrap();
+        File::create(base.join("node_modules/pkg/index.js")).unwrap();
+        fs::create_dir_all(base.join("src/lib")).unwrap();
+        fs::create_dir_all(base.join("src/node_modules")).unwrap();
+        File::create(base.join("src/main.js")).unwrap();
+        File::create(base.join("src/lib/util.js")).unwrap();
+        File::create(base.join("src/node_modules/nested.js")).unwrap();
+        File::create(base.join("root.js")).unwrap();
+        let cwd = base.to_string_lossy().to_string();
+
+        let glob = Glob::new("!(node_modules)/**/*.js".to_string(), make_opts(&cwd));
+        let expected = vec![
+            p("src/lib/util.js"),
+            p("src/main.js"),
+            p("src/node_modules/nested.js"),
+        ];
+        for results in walk_all_four(&glob) {
+            assert_eq!(results, expected);
+        }
+
+        // The negated segment prunes node_modules but not src
+        let pattern = &glob.patterns[0];
+        assert!(!pattern.could_match_in_dir("node_modules"));
+        assert!(pattern.could_match_in_dir("src"));
+
+        // A `*` between globstars likewise needs a directory of its own
+        let glob = Glob::new("**/*/**/*.js".to_string(), make_opts(&cwd));
+        let expected = vec![
+            p("node_modules/pkg/index.js"),
+            p("src/lib/util.js"),
+            p("src/main.js"),
+            p("src/node_modules/nested.js"),
+        ];
+        for results in walk_all_four(&glob) {
+            assert_eq!(results, expected);
+        }
     }
 
     #[test]
-    fn test_multi_pattern_any_requires_dir() {
-        // Pre-computed field should correctly identify patterns requiring directories
-        let temp = create_test_fixture();
+    fn test_nocase_literal_prefix_uses_on_disk_case() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("src/Lib")).unwrap();
+        File::create(base.join("src/a.ts")).unwrap();
+        File::create(base.join("src/Lib/b.ts")).unwrap();
+        fs::create_dir_all(base.join("other")).unwrap();
+        File::create(base.join("other/c.ts")).unwrap();
+        let cwd = base.to_string_lossy().to_string();
+        let nocase_opts = || GlobOptions {
+            nocase: Some(true),
+            ..make_opts(&cwd)
+        };
 
-        // Pattern without trailing slash
-        let glob1 = Glob::new("*".to_string(), make_opts(&temp.path().to_string_lossy()));
-        assert!(!glob1.any_pattern_requires_dir);
+        let glob = Glob::new("SRC/**/*.ts".to_string(), nocase_opts());
+        let (walk_root, prefix) = glob.calculate_walk_root();
+        assert_eq!(walk_root, base.join("src"));
+        assert_eq!(prefix.as_deref(), Some("src"));
+        for results in walk_all_four(&glob) {
+            assert_eq!(results, vec![p("src/Lib/b.ts"), p("src/a.ts")]);
+        }
 
-        // Pattern with trailing slash
-        let glob2 = Glob::new("*/".to_string(), make_opts(&temp.path().to_string_lossy()));
-        assert!(glob2.any_pattern_requires_dir);
+        let glob = Glob::new("sRc/lib/*.TS".to_string(), nocase_opts());
+        assert_eq!(glob.calculate_walk_root().0, base.join("src/Lib"));
+        for results in walk_all_four(&glob) {
+            assert_eq!(results, vec![p("src/Lib/b.ts")]);
+        }
 
-        // Multiple patterns where only one requires dir
-        let glob3 = Glob::new_multi(
-            vec!["*.txt".to_string(), "src/".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        assert!(glob3.any_pattern_requires_dir);
+        // Without nocase the prefix is used as written and finds nothing
+        let glob = Glob::new("SRC/**/*.ts".to_string(), make_opts(&cwd));
+        assert!(glob.walk_sync().is_empty());
     }
 
     #[test]
-    fn test_multi_pattern_fast_pattern_count() {
-        // Pre-computed fast pattern count
-        let temp = create_test_fixture();
+    fn test_dot_segments_are_literal() {
+        let matches = |path: &str, pattern: &str| {
+            matches_path(path.to_string(), Either::A(pattern.to_string()), None).unwrap()
+        };
+        assert!(matches("a/../b.txt", "a/../b.txt"));
+        assert!(!matches("b.txt", "a/../b.txt"));
+        assert!(!matches("a/../b.txt", "a/*/b.txt"));
+        assert!(!matches("a/../b.txt", "a/**/b.txt"));
+        assert!(!matches("a/../b.txt", "**/b.txt"));
+
+        // The walker never yields `..` entries, so only a literal `..` reaches
+        // past it; a wildcard before `..` has nothing to match on disk
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("a")).unwrap();
+        fs::create_dir_all(temp.path().join("b")).unwrap();
+        File::create(temp.path().join("b/c.txt")).unwrap();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let walk = |pattern: &str| Glob::new(pattern.to_string(), make_opts(&cwd)).walk_sync();
+        assert_eq!(walk("a/../b/*.txt"), vec![p("a/../b/c.txt")]);
+        assert!(walk("*/../b/*.txt").is_empty());
+        assert!(walk("**/../b/*.txt").is_empty());
+    }
 
-        // All fast-path patterns
-        let glob1 = Glob::new_multi(
-            vec!["*.txt".to_string(), "*.js".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        assert_eq!(glob1.fast_pattern_count, 2);
+    #[test]
+    fn test_nocase_character_range_matches_uppercase() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("LIB")).unwrap();
+        for file in ["FOO.TXT", "Bar.txt", "1.txt", "LIB/BAZ.TXT"] {
+            File::create(base.join(file)).unwrap();
+        }
+        let cwd = base.to_string_lossy().to_string();
+        let walk = |pattern: &str, nocase: bool| {
+            let opts = GlobOptions {
+                nocase: Some(nocase),
+                ..make_opts(&cwd)
+            };
+            let mut results = Glob::new(pattern.to_string(), opts).walk_sync();
+            results.sort();
+            results
+        };
 
-        // Mix of fast and slow patterns
-        let glob2 = Glob::new_multi(
-            vec!["*.txt".to_string(), "**/[a-z]*.js".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        // *.txt is fast, **/[a-z]*.js is not
-        assert_eq!(glob2.fast_pattern_count, 1);
+        assert_eq!(walk("[a-z]*.txt", true), vec!["Bar.txt", "FOO.TXT"]);
+        assert_eq!(walk("[a-z]*/[a-z]*.txt", true), vec![p("LIB/BAZ.TXT")]);
+        assert!(walk("[a-z]*.txt", false).is_empty());
     }
 
+    #[cfg(target_os = "linux")]
     #[test]
-    fn test_multi_pattern_many_patterns() {
-        // Test with many patterns to verify performance characteristics
+    fn test_nocase_literal_prefix_ambiguous_case() {
         let temp = TempDir::new().unwrap();
         let base = temp.path();
+        fs::create_dir_all(base.join("src")).unwrap();
+        fs::create_dir_all(base.join("SRC")).unwrap();
+        File::create(base.join("src/a.ts")).unwrap();
+        File::create(base.join("SRC/b.ts")).unwrap();
+        let cwd = base.to_string_lossy().to_string();
 
-        // Create files for each pattern
-        for i in 0..10 {
-            File::create(base.join(format!("file{i}.txt"))).unwrap();
-            File::create(base.join(format!("file{i}.js"))).unwrap();
-            File::create(base.join(format!("file{i}.ts"))).unwrap();
+        // Two directories differ only in case, so the walk has to start above both
+        let glob = Glob::new(
+            "Src/*.ts".to_string(),
+            GlobOptions {
+                nocase: Some(true),
+                ..make_opts(&cwd)
+            },
+        );
+        assert_eq!(glob.calculate_walk_root(), (base.to_path_buf(), None));
+        for results in walk_all_four(&glob) {
+            assert_eq!(results, vec![p("SRC/b.ts"), p("src/a.ts")]);
         }
 
-        // Create glob with many patterns
-        let patterns: Vec<String> = (0..10)
-            .flat_map(|i| vec![format!("file{}.txt", i), format!("file{}.js", i)])
-            .collect();
-
-        let glob = Glob::new_multi(patterns, make_opts(&temp.path().to_string_lossy()));
-
-        let results = glob.walk_sync();
-        assert_eq!(results.len(), 20); // 10 txt + 10 js files
+        // Deeper down, the walk starts from the last unambiguous directory
+        // rather than falling back to cwd
+        fs::create_dir_all(base.join("pkg/Lib/lib")).unwrap();
+        fs::create_dir_all(base.join("pkg/Lib/LIB")).unwrap();
+        fs::create_dir_all(base.join("other")).unwrap();
+        File::create(base.join("pkg/Lib/lib/c.ts")).unwrap();
+        File::create(base.join("pkg/Lib/LIB/d.ts")).unwrap();
+        File::create(base.join("other/e.ts")).unwrap();
+        let glob = Glob::new(
+            "PKG/lib/Lib/*.ts".to_string(),
+            GlobOptions {
+                nocase: Some(true),
+                ..make_opts(&cwd)
+            },
+        );
+        assert_eq!(
+            glob.calculate_walk_root(),
+            (base.join("pkg/Lib"), Some("pkg/Lib".to_string()))
+        );
+        for results in walk_all_four(&glob) {
+            assert_eq!(results, vec![p("pkg/Lib/LIB/d.ts"), p("pkg/Lib/lib/c.ts")]);
+        }
     }
 
     #[test]
-    fn test_multi_pattern_all_match_same_file() {
-        // Multiple patterns that all match the same file
+    fn test_walk_sync_annotated() {
         let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
         let glob = Glob::new_multi(
             vec![
-                "foo.txt".to_string(),
+                "src/*.js".to_string(),
                 "*.txt".to_string(),
-                "foo.*".to_string(),
-                "**".to_string(),
+                "**/main.js".to_string(),
             ],
-            make_opts(&temp.path().to_string_lossy()),
+            make_opts(&cwd),
         );
 
-        let results = glob.walk_sync();
+        let mut annotated = glob.walk_sync_annotated();
+        annotated.sort_by(|a, b| a.path.cmp(&b.path));
+        let summary: Vec<(String, Vec<u32>)> = annotated
+            .into_iter()
+            .map(|a| (a.path, a.matched_patterns))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("bar.txt".to_string(), vec![1]),
+                ("foo.txt".to_string(), vec![1]),
+                (p("src/main.js"), vec![0, 2]),
+                (p("src/util.js"), vec![0]),
+            ]
+        );
 
-        // foo.txt should appear only once despite matching all patterns
-        let foo_count = results.iter().filter(|r| *r == "foo.txt").count();
-        assert_eq!(foo_count, 1);
+        // Only patterns whose dot rules allow a dotfile are reported for it
+        fs::create_dir(temp.path().join(".config")).unwrap();
+        File::create(temp.path().join(".config/main.js")).unwrap();
+        let glob = Glob::new_multi(
+            vec![".config/*.js".to_string(), "**/main.js".to_string()],
+            make_opts(&cwd),
+        );
+        let mut annotated = glob.walk_sync_annotated();
+        annotated.sort_by(|a, b| a.path.cmp(&b.path));
+        let summary: Vec<(String, Vec<u32>)> = annotated
+            .into_iter()
+            .map(|a| (a.path, a.matched_patterns))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(p(".config/main.js"), vec![0]), (p("src/main.js"), vec![1])]
+        );
     }
 
-    // Absolute pattern tests (Task 4.1.1)
-
-    #[cfg(unix)]
     #[test]
-    fn test_absolute_pattern_unix() {
-        // Test absolute Unix path pattern
-        let temp = create_test_fixture();
-        let abs_path = temp.path().to_string_lossy().to_string();
-
-        // Create an absolute pattern
-        let pattern = format!("{}/**/*.js", abs_path.replace('\\', "/"));
-
-        let glob = Glob::new(
-            pattern,
-            GlobOptions {
-                cwd: Some("/tmp".to_string()), // Different cwd shouldn't matter
+    fn test_glob_batch() {
+        let first = create_test_fixture();
+        let second = TempDir::new().unwrap();
+        File::create(second.path().join("only.txt")).unwrap();
+        fs::create_dir(second.path().join("src")).unwrap();
+        File::create(second.path().join("src/app.js")).unwrap();
+        let cwds = vec![
+            first.path().to_string_lossy().to_string(),
+            second.path().to_string_lossy().to_string(),
+        ];
+
+        for parallel in [false, true] {
+            let opts = GlobOptions {
+                parallel: Some(parallel),
                 ..Default::default()
-            },
-        );
-
-        let results = glob.walk_sync();
+            };
+            let patterns = vec![
+                Either::A("*.txt".to_string()),
+                Either::A("src/*.js".to_string()),
+            ];
+            let mut batches = glob_batch(cwds.clone(), Either::B(patterns), Some(opts)).unwrap();
+            for results in &mut batches {
+                results.sort();
+            }
+            assert_eq!(
+                batches,
+                vec![
+                    vec![
+                        "bar.txt".to_string(),
+                        "foo.txt".to_string(),
+                        p("src/main.js"),
+                        p("src/util.js"),
+                    ],
+                    vec!["only.txt".to_string(), p("src/app.js")],
+                ]
+            );
+        }
 
-        // Should find js files in the temp directory
-        // Results should be relative to the pattern root
-        assert!(!results.is_empty());
-        // Check that results contain the expected patterns
-        assert!(results
-            .iter()
-            .any(|r| r.contains("main.js") || r.contains("baz.js")));
+        // restrictToCwd is checked against each directory, not the first one
+        let opts = GlobOptions {
+            restrict_to_cwd: Some(true),
+            ..Default::default()
+        };
+        let absolute = format!("{}/*.txt", normalize_separator(&cwds[0]));
+        assert!(glob_batch(cwds.clone(), Either::A(absolute), Some(opts)).is_err());
     }
 
     #[test]
-    fn test_absolute_pattern_nonexistent() {
-        // Absolute pattern pointing to nonexistent path should return empty
-        let glob = Glob::new(
-            "/nonexistent/path/**/*.txt".to_string(),
-            GlobOptions::default(),
-        );
-
-        let results = glob.walk_sync();
+    fn test_with_cwd_reuses_compiled_patterns() {
+        let first = create_test_fixture();
+        let second = TempDir::new().unwrap();
+        File::create(second.path().join("only.txt")).unwrap();
+        let opts = GlobOptions {
+            absolute: Some(true),
+            ..make_opts(&first.path().to_string_lossy())
+        };
+        let glob = Glob::new("*.txt".to_string(), opts);
 
-        assert!(results.is_empty());
+        let rerooted = glob.with_cwd(second.path()).unwrap();
+        assert!(Arc::ptr_eq(&glob.patterns, &rerooted.patterns));
+        let expected = second.path().canonicalize().unwrap().join("only.txt");
+        assert_eq!(
+            rerooted.walk_sync(),
+            vec![normalize_separator(&expected.to_string_lossy())]
+        );
+        assert_eq!(glob.walk_sync().len(), 2);
     }
 
-    #[cfg(windows)]
     #[test]
-    fn test_drive_letter_pattern() {
-        // Test Windows drive letter pattern
+    fn test_walk_sync_indexed() {
         let temp = create_test_fixture();
-        let abs_path = temp.path().to_string_lossy().to_string();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let glob = Glob::new("**/*".to_string(), make_opts(&cwd));
 
-        // Convert to POSIX-style path
-        let pattern = abs_path.replace('\\', "/");
+        let indexed = glob.walk_sync_indexed();
+        assert!(!indexed.is_empty());
+        let indices: Vec<u32> = indexed.iter().map(|r| r.index).collect();
+        let expected: Vec<u32> = (0..indexed.len() as u32).collect();
+        assert_eq!(indices, expected);
 
-        let glob = Glob::new(
-            format!("{}/**/*.txt", pattern),
+        let paths: Vec<String> = indexed.into_iter().map(|r| r.path).collect();
+        assert_eq!(paths, glob.walk_sync());
+    }
+
+    #[test]
+    fn test_walk_sync_depths() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let pattern = || {
+            vec![
+                "foo.txt".to_string(),
+                "src".to_string(),
+                "src/main.js".to_string(),
+                "src/lib/helper.js".to_string(),
+                ".".to_string(),
+            ]
+        };
+        let expected = [
+            ("foo.txt", 1),
+            ("src", 1),
+            ("src/main.js", 2),
+            ("src/lib/helper.js", 3),
+        ];
+
+        let variants = [
+            make_opts(&cwd),
             GlobOptions {
-                platform: Some("win32".to_string()),
-                ..Default::default()
+                absolute: Some(true),
+                ..make_opts(&cwd)
             },
-        );
-
-        let results = glob.walk_sync();
-
-        // Should find txt files
-        assert!(!results.is_empty());
-        assert!(results
-            .iter()
-            .any(|r| r.contains("foo.txt") || r.contains("bar.txt")));
+            GlobOptions {
+                mark: Some(true),
+                dot_relative: Some(true),
+                ..make_opts(&cwd)
+            },
+            GlobOptions {
+                result_prefix: Some("out:".to_string()),
+                ..make_opts(&cwd)
+            },
+        ];
+        for opts in variants {
+            let glob = Glob::new_multi(pattern(), opts);
+            let depths = glob.walk_sync_depths();
+            assert_eq!(depths.len(), expected.len() + 1);
+            // Only cwd itself is at depth 0
+            assert_eq!(
+                depths.iter().filter(|d| d.depth == 0).count(),
+                1,
+                "{depths:?}"
+            );
+            for (name, depth) in expected {
+                let found = depths
+                    .iter()
+                    .find(|d| d.path.trim_end_matches('/').ends_with(name))
+                    .unwrap_or_else(|| panic!("missing {name} in {depths:?}"));
+                assert_eq!(found.depth, depth, "{}", found.path);
+            }
+        }
     }
 
-    #[cfg(unix)]
     #[test]
-    fn test_absolute_pattern_with_literal_prefix() {
-        // Test that absolute patterns with literal prefixes work correctly
+    fn test_glob_sync_parsed() {
         let temp = create_test_fixture();
-        let abs_path = temp.path().to_string_lossy().to_string().replace('\\', "/");
-
-        // Pattern with absolute root + literal prefix
-        let pattern = format!("{abs_path}/src/**/*.js");
-
-        let glob = Glob::new(pattern, GlobOptions::default());
+        File::create(temp.path().join("src/lib/Makefile")).unwrap();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let parsed = |dir: &str, name: &str, ext: &str| ParsedPath {
+            dir: dir.to_string(),
+            name: name.to_string(),
+            ext: ext.to_string(),
+        };
 
-        let results = glob.walk_sync();
+        let mut results = glob_sync_parsed(
+            Either::B(vec![
+                Either::A("src/lib/*".to_string()),
+                Either::A("*.txt".to_string()),
+                Either::A(".hidden".to_string()),
+            ]),
+            Some(GlobOptions {
+                posix: Some(true),
+                ..make_opts(&cwd)
+            }),
+        )
+        .unwrap();
+        results.sort_by(|a, b| (&a.dir, &a.name).cmp(&(&b.dir, &b.name)));
+        assert_eq!(
+            results,
+            vec![
+                parsed("", ".hidden", ""),
+                parsed("", "bar", ".txt"),
+                parsed("", "foo", ".txt"),
+                parsed("src/lib", "Makefile", ""),
+                parsed("src/lib", "helper", ".js"),
+            ]
+        );
 
-        // Should find js files under src
-        assert!(results.iter().any(|r| r.contains("main.js")));
-        assert!(results.iter().any(|r| r.contains("helper.js")));
-        // Should NOT find root-level js
-        assert!(!results.iter().any(|r| r == "baz.js"));
+        // Same splitting rules as path.parse
+        assert_eq!(ParsedPath::parse("a/b.tar.gz"), parsed("a", "b.tar", ".gz"));
+        assert_eq!(ParsedPath::parse("a/b."), parsed("a", "b", "."));
+        assert_eq!(ParsedPath::parse("src/lib/"), parsed("src", "lib", ""));
+        assert_eq!(ParsedPath::parse("./a.js"), parsed(".", "a", ".js"));
+        assert_eq!(ParsedPath::parse("/abs/a.js"), parsed("/abs", "a", ".js"));
+        assert_eq!(ParsedPath::parse("."), parsed("", ".", ""));
     }
 
     #[test]
-    fn test_pattern_is_absolute() {
-        use crate::pattern::{Pattern, PatternOptions};
+    fn test_match_base_with_extglobs() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("src/deep")).unwrap();
+        for file in [
+            "a.ts",
+            "c.ts",
+            "src/a.ts",
+            "src/b.ts",
+            "src/deep/b.ts",
+            "src/deep/ab.ts",
+            "test.js",
+            "main.js",
+            "src/test.js",
+            "src/util.js",
+            "src/deep/test.js",
+            "src/deep/tests.js",
+        ] {
+            File::create(base.join(file)).unwrap();
+        }
+        let cwd = base.to_string_lossy().to_string();
+        let run = |pattern: &str| {
+            let glob = Glob::new(
+                pattern.to_string(),
+                GlobOptions {
+                    match_base: Some(true),
+                    ..make_opts(&cwd)
+                },
+            );
+            let [first, rest @ ..] = walk_all_four(&glob);
+            for other in rest {
+                assert_eq!(first, other, "{pattern}");
+            }
+            first
+        };
 
-        // Unix absolute path
-        let unix_pattern = Pattern::with_pattern_options(
-            "/usr/local/**/*.txt",
-            PatternOptions {
-                platform: Some("linux".to_string()),
-                ..Default::default()
-            },
+        assert_eq!(
+            run("@(a|b).ts"),
+            vec![p("a.ts"), p("src/a.ts"), p("src/b.ts"), p("src/deep/b.ts")]
+        );
+        assert_eq!(
+            run("!(test).js"),
+            vec![p("main.js"), p("src/deep/tests.js"), p("src/util.js")]
+        );
+        assert_eq!(
+            run("+(a|b).ts"),
+            vec![
+                p("a.ts"),
+                p("src/a.ts"),
+                p("src/b.ts"),
+                p("src/deep/ab.ts"),
+                p("src/deep/b.ts")
+            ]
+        );
+        assert_eq!(run("?(c).ts"), vec![p("c.ts")]);
+        // Braces around extglobs expand before matchBase is applied
+        assert_eq!(
+            run("{@(a).ts,!(*s).js}"),
+            vec![
+                p("a.ts"),
+                p("main.js"),
+                p("src/a.ts"),
+                p("src/deep/test.js"),
+                p("src/test.js"),
+                p("src/util.js"),
+                p("test.js")
+            ]
         );
-        assert!(unix_pattern.is_absolute());
-        assert_eq!(unix_pattern.root(), "/");
 
-        // Windows drive pattern
-        let win_pattern = Pattern::with_pattern_options(
-            "C:/Users/**/*.txt",
-            PatternOptions {
-                platform: Some("win32".to_string()),
-                ..Default::default()
+        // matches_path agrees with the walk
+        let glob = Glob::new(
+            "!(test).js".to_string(),
+            GlobOptions {
+                match_base: Some(true),
+                ..make_opts(&cwd)
             },
         );
-        assert!(win_pattern.is_absolute());
-        assert!(win_pattern.is_drive());
-        assert_eq!(win_pattern.root(), "C:/");
-
-        // Relative pattern
-        let rel_pattern = Pattern::with_pattern_options("src/**/*.txt", PatternOptions::default());
-        assert!(!rel_pattern.is_absolute());
-        assert_eq!(rel_pattern.root(), "");
+        assert!(glob.matches_path("src/deep/tests.js", false));
+        assert!(!glob.matches_path("src/deep/test.js", false));
     }
 
     #[test]
-    fn test_unc_pattern_detection() {
-        use crate::pattern::{Pattern, PatternOptions};
+    fn test_no_negation() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        for file in ["!(foo)", "foo", "bar", "a.ts", "b.ts", "c.ts"] {
+            File::create(base.join(file)).unwrap();
+        }
+        let cwd = base.to_string_lossy().to_string();
+
+        let run = |pattern: &str, no_negation: Option<bool>| {
+            let glob = Glob::new(
+                pattern.to_string(),
+                GlobOptions {
+                    no_negation,
+                    ..make_opts(&cwd)
+                },
+            );
+            let [first, rest @ ..] = walk_all_four(&glob);
+            for other in rest {
+                assert_eq!(first, other);
+            }
+            first
+        };
 
-        // UNC path
-        let unc_pattern = Pattern::with_pattern_options(
-            "//server/share/folder/**/*.txt",
-            PatternOptions {
-                platform: Some("win32".to_string()),
-                ..Default::default()
-            },
+        assert_eq!(
+            run("!(foo)", None),
+            vec!["!(foo)", "a.ts", "b.ts", "bar", "c.ts"]
         );
-        assert!(unc_pattern.is_absolute());
-        assert!(unc_pattern.is_unc());
-        assert!(unc_pattern.root().starts_with("//"));
+        assert_eq!(run("!(foo)", Some(true)), vec!["!(foo)"]);
+        assert_eq!(run("@(a|b).ts", Some(true)), vec!["a.ts", "b.ts"]);
+        assert_eq!(run("+(a|c).ts", Some(true)), vec!["a.ts", "c.ts"]);
     }
 
     #[test]
-    fn test_glob_double_dot_extension() {
-        use crate::options::GlobOptions;
-
-        // Create a temporary directory with test files
-        let temp_dir = tempfile::tempdir().unwrap();
-        let temp_path = temp_dir.path();
-
-        // Create test/a.test.ts
-        std::fs::create_dir_all(temp_path.join("test")).unwrap();
-        std::fs::write(temp_path.join("test/a.test.ts"), "").unwrap();
-        std::fs::write(temp_path.join("test/b.test.tsx"), "").unwrap();
-
-        let options = GlobOptions {
-            cwd: Some(temp_path.to_string_lossy().to_string()),
-            ..GlobOptions::default()
+    fn test_basename_only() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("a/b")).unwrap();
+        fs::create_dir_all(base.join("x/foo.ts")).unwrap();
+        for file in ["foo.ts", "a/b/c.ts", "a/b/foo.ts", "a/foo.js"] {
+            File::create(base.join(file)).unwrap();
+        }
+        let cwd = base.to_string_lossy().to_string();
+
+        let run = |pattern: &str| {
+            let glob = Glob::new(
+                pattern.to_string(),
+                GlobOptions {
+                    basename_only: Some(true),
+                    ..make_opts(&cwd)
+                },
+            );
+            let [first, rest @ ..] = walk_all_four(&glob);
+            for other in rest {
+                assert_eq!(first, other);
+            }
+            first
         };
 
-        let glob = Glob::new_multi(vec!["**/*.test.ts".to_string()], options);
-        let results = glob.walk_sync();
-
-        assert!(
-            results.contains(&p("test/a.test.ts")),
-            "Should contain test/a.test.ts"
+        assert_eq!(
+            run("*.ts"),
+            vec![
+                p("a/b/c.ts"),
+                p("a/b/foo.ts"),
+                "foo.ts".to_string(),
+                p("x/foo.ts")
+            ]
         );
-        assert!(
-            !results.contains(&p("test/b.test.tsx")),
-            "Should not contain test/b.test.tsx"
+        assert_eq!(
+            run("foo.ts"),
+            vec![p("a/b/foo.ts"), "foo.ts".to_string(), p("x/foo.ts")]
         );
+        // Directories in the pattern are ignored; a trailing slash still means dirs only
+        assert_eq!(run("src/c.ts"), vec![p("a/b/c.ts")]);
+        assert_eq!(run("nope/foo.ts/"), vec![p("x/foo.ts")]);
+        assert_eq!(run("x/{c,foo}.js"), vec![p("a/foo.js")]);
     }
 
-    // Static pattern tests - Task 5.10.1
-
     #[test]
-    fn test_static_pattern_single_file() {
+    fn test_hidden_mode_dotfiles() {
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "foo.txt".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
+        let cwd = temp.path().to_string_lossy().to_string();
+        let run = |pattern: &str, hidden: Option<HiddenMode>| {
+            let glob = Glob::new(
+                pattern.to_string(),
+                GlobOptions {
+                    hidden,
+                    ..make_opts(&cwd)
+                },
+            );
+            let [first, rest @ ..] = walk_all_four(&glob);
+            for other in rest {
+                assert_eq!(first, other, "{pattern}");
+            }
+            first
+        };
+
+        // Dotfiles are only hidden by name when the mode includes names
+        for hidden in [None, Some(HiddenMode::DotfilesOnly), Some(HiddenMode::Both)] {
+            assert_eq!(
+                run("*", hidden),
+                vec!["bar.txt", "baz.js", "foo.txt", "src"]
+            );
+        }
+        assert_eq!(
+            run("*", Some(HiddenMode::AttributeOnly)),
+            vec![
+                ".git",
+                ".gitignore",
+                ".hidden",
+                "bar.txt",
+                "baz.js",
+                "foo.txt",
+                "src"
+            ]
         );
-        let results = glob.walk_sync();
+        assert_eq!(
+            run("**/config", Some(HiddenMode::AttributeOnly)),
+            vec![p(".git/config")]
+        );
+    }
 
-        // Should find the exact file
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert_eq!(results.len(), 1);
+    #[cfg(windows)]
+    #[test]
+    fn test_hidden_mode_windows_attribute() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("hidden_dir")).unwrap();
+        for file in [
+            "visible.txt",
+            "secret.txt",
+            ".dot.txt",
+            "hidden_dir/inner.txt",
+        ] {
+            File::create(base.join(file)).unwrap();
+        }
+        for path in ["secret.txt", "hidden_dir"] {
+            let status = std::process::Command::new("attrib")
+                .arg("+h")
+                .arg(base.join(path))
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+        let cwd = base.to_string_lossy().to_string();
+        let run = |pattern: &str, hidden: HiddenMode| {
+            let glob = Glob::new(
+                pattern.to_string(),
+                GlobOptions {
+                    hidden: Some(hidden),
+                    ..make_opts(&cwd)
+                },
+            );
+            let [first, rest @ ..] = walk_all_four(&glob);
+            for other in rest {
+                assert_eq!(first, other, "{pattern}");
+            }
+            first
+        };
+
+        assert_eq!(
+            run("**/*.txt", HiddenMode::DotfilesOnly),
+            vec![
+                p("hidden_dir/inner.txt"),
+                "secret.txt".into(),
+                "visible.txt".into()
+            ]
+        );
+        assert_eq!(
+            run("**/*.txt", HiddenMode::AttributeOnly),
+            vec![".dot.txt", "visible.txt"]
+        );
+        assert_eq!(run("**/*.txt", HiddenMode::Both), vec!["visible.txt"]);
+        assert_eq!(run("*.txt", HiddenMode::Both), vec!["visible.txt"]);
     }
 
     #[test]
-    fn test_static_pattern_nested_file() {
+    fn test_unique_false_repeats_per_pattern() {
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "src/main.js".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
+        let cwd = temp.path().to_string_lossy().to_string();
+        let run = |patterns: &[&str], unique: Option<bool>| {
+            let patterns = patterns.iter().map(|p| Either::A(p.to_string())).collect();
+            let opts = GlobOptions {
+                unique,
+                ..make_opts(&cwd)
+            };
+            let mut results = glob_sync(Either::B(patterns), Some(opts)).unwrap();
+            results.sort();
+            results
+        };
+
+        let patterns = ["src/*.js", "**/main.js", "*.txt"];
+        assert_eq!(
+            run(&patterns, None),
+            vec!["bar.txt", "foo.txt", &p("src/main.js"), &p("src/util.js")]
+        );
+        assert_eq!(run(&patterns, Some(true)), run(&patterns, None));
+        assert_eq!(
+            run(&patterns, Some(false)),
+            vec![
+                "bar.txt",
+                "foo.txt",
+                &p("src/main.js"),
+                &p("src/main.js"),
+                &p("src/util.js")
+            ]
+        );
+        // Identical input patterns each count
+        assert_eq!(
+            run(&["foo.txt", "foo.txt", "f*.txt"], Some(false)),
+            vec!["foo.txt"; 3]
         );
-        let results = glob.walk_sync();
 
-        // Should find the nested file
-        assert!(results.contains(&p("src/main.js")));
-        assert_eq!(results.len(), 1);
+        // A pattern whose dot rules reject a path adds no copy of it
+        fs::create_dir(temp.path().join(".config")).unwrap();
+        File::create(temp.path().join(".config/x.js")).unwrap();
+        assert_eq!(
+            run(&[".config/*.js", "**/*.js"], Some(false)),
+            vec![
+                p(".config/x.js"),
+                "baz.js".to_string(),
+                p("src/lib/helper.js"),
+                p("src/main.js"),
+                p("src/util.js")
+            ]
+        );
     }
 
     #[test]
-    fn test_static_pattern_deeply_nested() {
+    #[cfg(unix)]
+    fn test_parent_of_root_in_absolute_pattern() {
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "src/lib/helper.js".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
+        let root = temp.path().canonicalize().unwrap();
+        let root = root.to_string_lossy();
+        let cwd = std::env::temp_dir().to_string_lossy().to_string();
+
+        for pattern in [
+            format!("{root}/*.txt"),
+            format!("/..{root}/*.txt"),
+            format!("/../..{root}/*.txt"),
+        ] {
+            let glob = Glob::new(pattern.clone(), make_opts(&cwd));
+            assert_eq!(
+                glob.calculate_walk_root().0,
+                PathBuf::from(&*root),
+                "{pattern}"
+            );
+            let mut results = glob.walk_sync();
+            results.sort();
+            assert_eq!(
+                results,
+                vec![format!("{root}/bar.txt"), format!("{root}/foo.txt")],
+                "{pattern}"
+            );
+        }
 
-        // Should find the deeply nested file
-        assert!(results.contains(&p("src/lib/helper.js")));
-        assert_eq!(results.len(), 1);
+        // Static patterns resolve from the root, not the cwd
+        for pattern in [format!("{root}/foo.txt"), format!("/..{root}/foo.txt")] {
+            let glob = Glob::new(pattern.clone(), make_opts(&cwd));
+            assert_eq!(
+                glob.walk_sync(),
+                vec![format!("{root}/foo.txt")],
+                "{pattern}"
+            );
+        }
     }
 
     #[test]
-    fn test_static_pattern_directory() {
+    fn test_scoped_dedup() {
         let temp = create_test_fixture();
-        let glob = Glob::new("src".to_string(), make_opts(&temp.path().to_string_lossy()));
-        let results = glob.walk_sync();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let run = |patterns: &[&str], extra: GlobOptions| {
+            let inputs: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+            let full = Glob::new_multi(inputs.clone(), extra.clone());
+            let scoped = Glob::new_multi(
+                inputs,
+                GlobOptions {
+                    scoped_dedup: Some(true),
+                    ..extra
+                },
+            );
+            let [sync, _, stream, _] = walk_all_four(&scoped);
+            let [expected, _, expected_stream, _] = walk_all_four(&full);
+            assert_eq!(sync, expected, "{patterns:?}");
+            assert_eq!(stream, expected_stream, "{patterns:?}");
+        };
 
-        // Should find the directory
-        assert!(results.contains(&"src".to_string()));
-        assert_eq!(results.len(), 1);
+        for patterns in [
+            &["**"][..],
+            &["**/*.js"],
+            &["*.txt", "src/**"],
+            &["src/**/*.js", "src/*.js", "**/*.js"],
+            &["{src,src/lib}/**"],
+            &["**/", "**/*.{js,txt}"],
+            &["src/lib/**", "src/**", "src/lib/*.js"],
+        ] {
+            run(patterns, make_opts(&cwd));
+            run(
+                patterns,
+                GlobOptions {
+                    dot: Some(true),
+                    mark: Some(true),
+                    ..make_opts(&cwd)
+                },
+            );
+            run(
+                patterns,
+                GlobOptions {
+                    absolute: Some(true),
+                    parallel: Some(true),
+                    ..make_opts(&cwd)
+                },
+            );
+        }
+
+        // Scopes are closed as the walk leaves directories
+        let mut seen = SeenPaths::Scoped(Vec::new());
+        for path in ["a", "a/b", "a/b/c", "a/b/d", "a/e"] {
+            assert!(seen.insert(path));
+        }
+        assert!(!seen.insert("a/b"));
+        assert!(!seen.insert("a/e"));
+        assert!(seen.insert("f"));
+        assert!(!seen.insert("a"));
+        let SeenPaths::Scoped(scopes) = &seen else {
+            unreachable!()
+        };
+        assert_eq!(scopes.len(), 1);
     }
 
     #[test]
-    fn test_static_pattern_nonexistent() {
+    fn test_result_prefix() {
         let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let prefix = "remote:";
+        let opts = |extra: GlobOptions| GlobOptions {
+            result_prefix: Some(prefix.to_string()),
+            ..extra
+        };
+
+        let cases = [
+            // static, shallow, recursive, multi-base and cwd-matching patterns
+            (vec!["foo.txt"], make_opts(&cwd)),
+            (vec!["*.txt"], make_opts(&cwd)),
+            (vec!["**/*.js"], make_opts(&cwd)),
+            (vec!["src/*.js", "*.txt"], make_opts(&cwd)),
+            (vec!["**"], make_opts(&cwd)),
+            (
+                vec!["**"],
+                GlobOptions {
+                    mark: Some(true),
+                    dot_relative: Some(true),
+                    ..make_opts(&cwd)
+                },
+            ),
+            (
+                vec!["**/*.js", "foo.txt"],
+                GlobOptions {
+                    absolute: Some(true),
+                    ..make_opts(&cwd)
+                },
+            ),
+        ];
+
+        for (patterns, extra) in cases {
+            let inputs: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+            let plain = Glob::new_multi(inputs.clone(), extra.clone()).walk_sync();
+            let glob = Glob::new_multi(inputs, opts(extra));
+
+            let mut sync = glob.walk_sync();
+            let mut stream = Vec::new();
+            glob.walk_stream(|r| stream.push(r));
+            assert!(!sync.is_empty(), "{patterns:?}");
+            for r in sync.iter().chain(&stream) {
+                assert!(r.starts_with(prefix), "{patterns:?}: {r}");
+            }
+
+            // The prefix goes in front of the otherwise unchanged result
+            let mut expected: Vec<String> = plain.iter().map(|r| format!("{prefix}{r}")).collect();
+            expected.sort();
+            sync.sort();
+            stream.sort();
+            assert_eq!(sync, expected, "{patterns:?}");
+            assert_eq!(stream, expected, "{patterns:?}");
+        }
+
+        // An empty prefix changes nothing
         let glob = Glob::new(
-            "does-not-exist.txt".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
+            "**/*.js".to_string(),
+            GlobOptions {
+                result_prefix: Some(String::new()),
+                ..make_opts(&cwd)
+            },
         );
-        let results = glob.walk_sync();
-
-        // Should return empty for non-existent files
-        assert!(results.is_empty());
+        assert!(glob.walk_sync().iter().all(|r| !r.starts_with(prefix)));
     }
 
     #[test]
-    fn test_static_pattern_multiple() {
+    fn test_preserve_pattern_order() {
         let temp = create_test_fixture();
-        let glob = Glob::new_multi(
-            vec!["foo.txt".to_string(), "bar.txt".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
+        let cwd = temp.path().to_string_lossy().to_string();
+        let inputs = || vec!["src/**/*.js".to_string(), "*.txt".to_string()];
+
+        // By default the fast-path pattern is moved to the front
+        let reordered = Glob::new_multi(inputs(), make_opts(&cwd));
+        assert_eq!(reordered.patterns[0].raw(), "*.txt");
+
+        let preserved = Glob::new_multi(
+            inputs(),
+            GlobOptions {
+                preserve_pattern_order: Some(true),
+                ..make_opts(&cwd)
+            },
         );
-        let results = glob.walk_sync();
+        assert_eq!(preserved.patterns[0].raw(), "src/**/*.js");
+        assert_eq!(preserved.patterns[1].raw(), "*.txt");
+        assert_eq!(preserved.pattern_origins[0], vec![0]);
 
-        // Should find both files
-        assert!(results.contains(&"foo.txt".to_string()));
-        assert!(results.contains(&"bar.txt".to_string()));
-        assert_eq!(results.len(), 2);
+        // Only the order changes, not the results
+        assert_eq!(walk_all_four(&preserved), walk_all_four(&reordered));
     }
 
     #[test]
-    fn test_static_pattern_with_nodir() {
-        let temp = create_test_fixture();
-        let glob = Glob::new(
-            "src".to_string(),
-            make_opts_with_nodir(&temp.path().to_string_lossy(), true),
-        );
-        let results = glob.walk_sync();
-
-        // Should NOT include directory when nodir: true
-        assert!(results.is_empty());
+    fn test_trim_pattern_line() {
+        assert_eq!(trim_pattern_line("  *.txt  ", false), Some("*.txt"));
+        assert_eq!(trim_pattern_line("\t*.txt\r", false), Some("*.txt"));
+        assert_eq!(trim_pattern_line("# comment", false), None);
+        assert_eq!(trim_pattern_line("   # indented comment", false), None);
+        assert_eq!(trim_pattern_line("   ", false), None);
+        assert_eq!(trim_pattern_line("", false), None);
+
+        // An escaped space survives, but only the one that is escaped
+        assert_eq!(trim_pattern_line("foo\\ ", false), Some("foo\\ "));
+        assert_eq!(trim_pattern_line("foo\\   ", false), Some("foo\\ "));
+        // An escaped backslash doesn't escape the space after it
+        assert_eq!(trim_pattern_line("foo\\\\ ", false), Some("foo\\\\"));
+        // Escaped comment markers are literal
+        assert_eq!(trim_pattern_line("\\#file", false), Some("\\#file"));
+
+        // With backslash separators there is nothing to escape
+        assert_eq!(trim_pattern_line("foo\\ ", true), Some("foo\\"));
     }
 
     #[test]
-    fn test_static_pattern_with_mark() {
+    fn test_trim_patterns_option() {
         let temp = create_test_fixture();
-        let glob = Glob::new(
-            "src".to_string(),
-            make_opts_with_mark(&temp.path().to_string_lossy(), true),
-        );
-        let results = glob.walk_sync();
+        File::create(temp.path().join("with space ")).unwrap();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let run = |patterns: &[&str], trim: Option<bool>| {
+            let opts = GlobOptions {
+                trim_patterns: trim,
+                ..make_opts(&cwd)
+            };
+            let mut results =
+                Glob::new_multi(patterns.iter().map(|p| p.to_string()).collect(), opts).walk_sync();
+            results.sort();
+            results
+        };
 
-        // Should include trailing slash for directory
-        assert!(results.contains(&p("src/")));
+        let txt = vec!["bar.txt".to_string(), "foo.txt".to_string()];
+        assert_eq!(run(&["  *.txt  "], Some(true)), txt);
+        assert!(run(&["  *.txt  "], None).is_empty());
+
+        // Comments and blank lines are skipped instead of matched literally
+        assert_eq!(run(&["# *.js", "", "   ", "*.txt"], Some(true)), txt);
+        assert_eq!(run(&["# comment"], Some(true)), Vec::<String>::new());
+
+        // Escaped trailing spaces are part of the pattern
+        assert_eq!(run(&["with space\\ "], Some(true)), vec!["with space "]);
     }
 
     #[test]
-    fn test_static_pattern_with_dot_relative() {
-        let temp = create_test_fixture();
+    fn test_root_relative_anchors_at_cwd() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("src")).unwrap();
+        fs::create_dir_all(base.join("lib/src")).unwrap();
+        File::create(base.join("src/a.ts")).unwrap();
+        File::create(base.join("lib/src/b.ts")).unwrap();
+        let cwd = base.to_string_lossy().to_string();
+        let opts = |ignore: Option<&str>| GlobOptions {
+            root_relative: Some(true),
+            ignore: ignore.map(|i| Either::A(i.to_string())),
+            ..make_opts(&cwd)
+        };
+
+        let glob = Glob::new("/src/*.ts".to_string(), opts(None));
+        assert!(!glob.patterns[0].is_absolute());
+        for results in walk_all_four(&glob) {
+            assert_eq!(results, vec![p("src/a.ts")]);
+        }
+
+        let glob = Glob::new("/**/*.ts".to_string(), opts(Some("/lib/**")));
+        for results in walk_all_four(&glob) {
+            assert_eq!(results, vec![p("src/a.ts")]);
+        }
+
+        // Anchored patterns don't get matchBase
         let glob = Glob::new(
-            "foo.txt".to_string(),
-            make_opts_with_dot_relative(&temp.path().to_string_lossy(), true),
+            "/a.ts".to_string(),
+            GlobOptions {
+                match_base: Some(true),
+                ..opts(None)
+            },
         );
-        let results = glob.walk_sync();
+        assert!(glob.walk_sync().is_empty());
 
-        // Should include ./ prefix
-        assert!(results.contains(&p("./foo.txt")));
+        let glob = Glob::new("/".to_string(), opts(None));
+        assert_eq!(glob.walk_sync(), vec!["."]);
+
+        // Without the option a leading `/` is filesystem-absolute
+        let glob = Glob::new("/src/*.ts".to_string(), make_opts(&cwd));
+        assert!(glob.patterns[0].is_absolute());
     }
 
     #[test]
-    fn test_static_pattern_with_absolute() {
+    fn test_walk_sync_timed() {
         let temp = create_test_fixture();
-        let mut opts = make_opts(&temp.path().to_string_lossy());
-        opts.absolute = Some(true);
+        let cwd = temp.path().to_string_lossy().to_string();
 
-        let glob = Glob::new("foo.txt".to_string(), opts);
-        let results = glob.walk_sync();
+        let glob = Glob::new("**/*.js".to_string(), make_opts(&cwd));
+        let (mut results, stats) = glob.walk_sync_timed();
+        let mut expected = glob.walk_sync();
+        results.sort();
+        expected.sort();
+        assert_eq!(results, expected);
+        assert!(stats.dirs_visited >= 1);
+        assert!(stats.entries_visited >= stats.dirs_visited + 3);
+
+        // A literal prefix walks only that subtree: src, src/lib and their 4 files
+        let glob = Glob::new("src/**/*.js".to_string(), make_opts(&cwd));
+        let (_, stats) = glob.walk_sync_timed();
+        assert_eq!((stats.entries_visited, stats.dirs_visited), (6, 2));
+        // Counts start over on every call
+        let (_, again) = glob.walk_sync_timed();
+        assert_eq!((again.entries_visited, again.dirs_visited), (6, 2));
+        // Concurrent timed walks of one glob each get their own counts
+        std::thread::scope(|s| {
+            let walks: Vec<_> = (0..4).map(|_| s.spawn(|| glob.walk_sync_timed())).collect();
+            for walk in walks {
+                let (_, stats) = walk.join().unwrap();
+                assert_eq!((stats.entries_visited, stats.dirs_visited), (6, 2));
+            }
+        });
 
-        // Should return absolute path
-        assert!(!results.is_empty());
-        let result = &results[0];
-        assert!(result.contains("foo.txt"));
-        // Absolute path should start with:
-        // - Unix: /
-        // - Windows: drive letter (C:) or UNC (\\) or extended-length (\\?\)
-        assert!(
-            result.starts_with('/')
-                || result.chars().nth(1) == Some(':')
-                || result.starts_with("\\\\")
-        );
+        // The shallow fast path reads cwd once (cwd, src and .git are dirs);
+        // static patterns only stat
+        let (_, stats) = Glob::new("*.txt".to_string(), make_opts(&cwd)).walk_sync_timed();
+        assert_eq!((stats.entries_visited, stats.dirs_visited), (8, 3));
+        let (_, stats) = Glob::new("foo.txt".to_string(), make_opts(&cwd)).walk_sync_timed();
+        assert_eq!((stats.entries_visited, stats.dirs_visited), (1, 0));
     }
 
     #[test]
-    fn test_static_pattern_deduplication() {
+    fn test_glob_page() {
         let temp = create_test_fixture();
-        // Same file referenced multiple times
-        let glob = Glob::new_multi(
-            vec!["foo.txt".to_string(), "foo.txt".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        let results = glob.walk_sync();
+        let cwd = temp.path().to_string_lossy().to_string();
 
-        // Should only include once
-        assert_eq!(results.len(), 1);
-        assert!(results.contains(&"foo.txt".to_string()));
+        // dirOrder is overridden: post-order pages would lose directories
+        for (pattern, dir_order) in [
+            ("**", None),
+            ("**/*.js", None),
+            ("src/**", None),
+            ("*.txt", None),
+            ("**", Some(DirOrder::PostOrder)),
+        ] {
+            let opts = || GlobOptions {
+                dot: Some(true),
+                dir_order,
+                ..make_opts(&cwd)
+            };
+            let mut expected = Glob::new(pattern.to_string(), opts()).walk_sync();
+            expected.sort();
+
+            for page_size in [1, 2, 3, 100] {
+                let mut all = Vec::new();
+                let mut cursor = None;
+                let mut pages = 0;
+                loop {
+                    let page = glob_page(
+                        Either::A(pattern.to_string()),
+                        Some(opts()),
+                        cursor,
+                        page_size,
+                    )
+                    .unwrap();
+                    assert!(page.results.len() <= page_size as usize);
+                    all.extend(page.results);
+                    pages += 1;
+                    cursor = page.next_cursor;
+                    if cursor.is_none() {
+                        break;
+                    }
+                }
+                assert_eq!(
+                    pages,
+                    expected.len().max(1).div_ceil(page_size as usize).max(1)
+                );
+                let unique: AHashSet<&String> = all.iter().collect();
+                assert_eq!(
+                    unique.len(),
+                    all.len(),
+                    "{pattern} / {page_size}: duplicates"
+                );
+                all.sort();
+                assert_eq!(all, expected, "{pattern} / {page_size}");
+            }
+        }
+
+        // Resuming inside a subdirectory continues with the entries after the cursor
+        let opts = GlobOptions {
+            sort_dir_entries: Some(true),
+            parallel: Some(false),
+            ..make_opts(&cwd)
+        };
+        let glob = Glob::new("**/*.js".to_string(), opts);
+        let (results, next) = glob.walk_page(Some("src/lib/helper.js"), 10);
+        assert_eq!(results, vec![p("src/main.js"), p("src/util.js")]);
+        assert_eq!(next, None);
+
+        let opts = Some(make_opts(&cwd));
+        assert!(glob_page(Either::A("**".to_string()), opts.clone(), None, 0).is_err());
+        let bad_cursor = Some("zz".to_string());
+        assert!(glob_page(Either::A("**".to_string()), opts, bad_cursor, 1).is_err());
+        assert_eq!(
+            decode_cursor(&encode_cursor("src/é.js")).unwrap(),
+            "src/é.js"
+        );
     }
 
     #[test]
-    fn test_all_patterns_static_detection() {
-        let temp = create_test_fixture();
-
-        // Static patterns
-        let glob1 = Glob::new(
-            "foo.txt".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        assert!(glob1.all_patterns_static());
+    fn test_glob_histogram() {
+        let temp = create_multi_base_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let histogram = |pattern: &str, depth: u32| {
+            glob_histogram(Either::A(pattern.to_string()), Some(make_opts(&cwd)), depth)
+                .unwrap()
+                .into_iter()
+                .map(|c| (c.dir, c.count))
+                .collect::<Vec<_>>()
+        };
+        let counts = |expected: &[(&str, u32)]| {
+            expected
+                .iter()
+                .map(|&(dir, count)| (dir.to_string(), count))
+                .collect::<Vec<_>>()
+        };
 
-        let glob2 = Glob::new_multi(
-            vec!["foo.txt".to_string(), "src/main.js".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
+        // Per top-level directory; nested matches roll up into their ancestor
+        assert_eq!(
+            histogram("**/*.ts", 1),
+            counts(&[("lib", 1), ("node_modules", 1), ("src", 3), ("test", 3)])
         );
-        assert!(glob2.all_patterns_static());
 
-        // Non-static patterns
-        let glob3 = Glob::new(
-            "*.txt".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
+        // Deeper buckets keep shallower matches under their own directory
+        assert_eq!(
+            histogram("{src,test}/**/*.ts", 2),
+            counts(&[
+                ("src", 2),
+                ("src/lib", 1),
+                ("test", 2),
+                ("test/fixtures", 1)
+            ])
         );
-        assert!(!glob3.all_patterns_static());
 
-        let glob4 = Glob::new(
-            "**/*.js".to_string(),
-            make_opts(&temp.path().to_string_lossy()),
-        );
-        assert!(!glob4.all_patterns_static());
+        // Matches directly in cwd are counted under `.`
+        assert_eq!(histogram("*", 1), counts(&[(".", 7)]));
+        assert_eq!(histogram("**/*.ts", 0), counts(&[(".", 8)]));
 
-        // Mixed - should be false
-        let glob5 = Glob::new_multi(
-            vec!["foo.txt".to_string(), "*.js".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
+        // The output format options don't change the buckets
+        let opts = GlobOptions {
+            absolute: Some(true),
+            dot_relative: Some(true),
+            ..make_opts(&cwd)
+        };
+        let buckets = glob_histogram(Either::A("src/**/*.ts".to_string()), Some(opts), 1).unwrap();
+        assert_eq!(
+            buckets,
+            vec![DirCount {
+                dir: "src".to_string(),
+                count: 3
+            }]
         );
-        assert!(!glob5.all_patterns_static());
     }
 
-    // Multi-base walking tests
-    fn create_multi_base_fixture() -> TempDir {
+    #[test]
+    fn test_walk_aggregate() {
         let temp = TempDir::new().unwrap();
         let base = temp.path();
-
-        // Create src directory with TypeScript files
-        fs::create_dir_all(base.join("src")).unwrap();
-        File::create(base.join("src/main.ts")).unwrap();
-        File::create(base.join("src/util.ts")).unwrap();
         fs::create_dir_all(base.join("src/lib")).unwrap();
-        File::create(base.join("src/lib/helper.ts")).unwrap();
-
-        // Create test directory with TypeScript files
-        fs::create_dir_all(base.join("test")).unwrap();
-        File::create(base.join("test/main.test.ts")).unwrap();
-        File::create(base.join("test/util.test.ts")).unwrap();
-        fs::create_dir_all(base.join("test/fixtures")).unwrap();
-        File::create(base.join("test/fixtures/data.ts")).unwrap();
-
-        // Create lib directory with TypeScript files
-        fs::create_dir_all(base.join("lib")).unwrap();
-        File::create(base.join("lib/index.ts")).unwrap();
-
-        // Create other directories that should not be traversed
-        fs::create_dir_all(base.join("node_modules/pkg")).unwrap();
-        File::create(base.join("node_modules/pkg/index.ts")).unwrap();
-
-        fs::create_dir_all(base.join("dist")).unwrap();
-        File::create(base.join("dist/main.js")).unwrap();
+        fs::create_dir_all(base.join("vendor")).unwrap();
+        fs::write(base.join("src/a.js"), [0u8; 100]).unwrap();
+        fs::write(base.join("src/lib/b.js"), [0u8; 250]).unwrap();
+        fs::write(base.join("src/empty.js"), []).unwrap();
+        fs::write(base.join("src/notes.md"), [0u8; 7]).unwrap();
+        fs::write(base.join("vendor/c.js"), [0u8; 4000]).unwrap();
+        let cwd = base.to_string_lossy().to_string();
+
+        let aggregate = |pattern: &str, opts: GlobOptions| {
+            Glob::new(pattern.to_string(), opts).walk_aggregate()
+        };
 
-        // Create root level files
-        File::create(base.join("package.json")).unwrap();
-        File::create(base.join("tsconfig.json")).unwrap();
+        assert_eq!(
+            aggregate("**/*.js", make_opts(&cwd)),
+            GlobAggregate {
+                count: 4,
+                total_size: 4350.0
+            }
+        );
+        // Directories are counted but have no size
+        assert_eq!(
+            aggregate("src/**", make_opts(&cwd)),
+            GlobAggregate {
+                count: 6,
+                total_size: 357.0
+            }
+        );
+        assert_eq!(
+            aggregate(
+                "src/**",
+                GlobOptions {
+                    nodir: Some(true),
+                    ..make_opts(&cwd)
+                }
+            ),
+            GlobAggregate {
+                count: 4,
+                total_size: 357.0
+            }
+        );
+        assert_eq!(
+            aggregate(
+                "**/*.js",
+                GlobOptions {
+                    ignore: Some(Either::A("vendor/**".to_string())),
+                    ..make_opts(&cwd)
+                }
+            ),
+            GlobAggregate {
+                count: 3,
+                total_size: 350.0
+            }
+        );
+        assert_eq!(
+            aggregate("*.nothing", make_opts(&cwd)),
+            GlobAggregate {
+                count: 0,
+                total_size: 0.0
+            }
+        );
 
-        temp
+        // Walkers that already stat'ed a file report the same sizes
+        for opts in [
+            GlobOptions {
+                cache: Some(true),
+                ..make_opts(&cwd)
+            },
+            GlobOptions {
+                cache: Some(true),
+                follow: Some(true),
+                ..make_opts(&cwd)
+            },
+            GlobOptions {
+                mark: Some(true),
+                follow: Some(true),
+                ..make_opts(&cwd)
+            },
+            GlobOptions {
+                parallel: Some(true),
+                mark: Some(true),
+                follow: Some(true),
+                ..make_opts(&cwd)
+            },
+        ] {
+            assert_eq!(
+                aggregate("**/*.js", opts),
+                GlobAggregate {
+                    count: 4,
+                    total_size: 4350.0
+                }
+            );
+        }
     }
 
     #[test]
-    fn test_group_patterns_by_base() {
-        let temp = create_multi_base_fixture();
+    fn test_walk_sync_grouped() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let sorted = |mut v: Vec<String>| {
+            v.sort();
+            v
+        };
 
-        // Patterns with different bases
+        // Fast-path patterns are reordered internally; groups keep input order
         let glob = Glob::new_multi(
             vec![
-                "src/**/*.ts".to_string(),
-                "src/lib/*.ts".to_string(),
-                "test/**/*.ts".to_string(),
+                "src/**/*.js".to_string(),
+                "*.txt".to_string(),
+                "".to_string(),
+                "{foo,baz}.*".to_string(),
+                "**/*.js".to_string(),
             ],
-            make_opts(&temp.path().to_string_lossy()),
+            make_opts(&cwd),
+        );
+        let groups: Vec<Vec<String>> = glob
+            .walk_sync_grouped(false)
+            .into_iter()
+            .map(sorted)
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                vec![p("src/lib/helper.js"), p("src/main.js"), p("src/util.js")],
+                vec!["bar.txt".to_string(), "foo.txt".to_string()],
+                vec![],
+                vec!["baz.js".to_string(), "foo.txt".to_string()],
+                vec![
+                    "baz.js".to_string(),
+                    p("src/lib/helper.js"),
+                    p("src/main.js"),
+                    p("src/util.js")
+                ],
+            ]
         );
 
-        let groups = glob.group_patterns_by_base();
+        // Each path lands only in the first group that matches it
+        let groups: Vec<Vec<String>> = glob
+            .walk_sync_grouped(true)
+            .into_iter()
+            .map(sorted)
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                vec![p("src/lib/helper.js"), p("src/main.js"), p("src/util.js")],
+                vec!["bar.txt".to_string(), "foo.txt".to_string()],
+                vec![],
+                vec!["baz.js".to_string()],
+                vec![],
+            ]
+        );
 
-        // Should have 2 groups: src and test
-        assert_eq!(groups.len(), 2);
-        assert!(groups.contains_key(&Some("src".to_string())));
-        assert!(groups.contains_key(&Some("test".to_string())));
+        // Duplicate input patterns each get their own group; output options apply
+        let glob = Glob::new_multi(
+            vec!["*.txt".to_string(), "*.txt".to_string(), "src".to_string()],
+            GlobOptions {
+                mark: Some(true),
+                dot_relative: Some(true),
+                ..make_opts(&cwd)
+            },
+        );
+        let groups: Vec<Vec<String>> = glob
+            .walk_sync_grouped(false)
+            .into_iter()
+            .map(sorted)
+            .collect();
+        assert_eq!(groups[0], vec![p("./bar.txt"), p("./foo.txt")]);
+        assert_eq!(groups[1], groups[0]);
+        assert_eq!(groups[2], vec![p("./src/")]);
 
-        // src group should have 2 patterns
-        assert_eq!(groups.get(&Some("src".to_string())).unwrap().len(), 2);
-        // test group should have 1 pattern
-        assert_eq!(groups.get(&Some("test".to_string())).unwrap().len(), 1);
+        // A dotfile only lands in the groups of patterns that name its dot segment
+        fs::create_dir(temp.path().join(".config")).unwrap();
+        File::create(temp.path().join(".config/x.js")).unwrap();
+        let glob = Glob::new_multi(
+            vec![".config/*.js".to_string(), "**/*.js".to_string()],
+            make_opts(&cwd),
+        );
+        let groups: Vec<Vec<String>> = glob
+            .walk_sync_grouped(false)
+            .into_iter()
+            .map(sorted)
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                vec![p(".config/x.js")],
+                vec![
+                    "baz.js".to_string(),
+                    p("src/lib/helper.js"),
+                    p("src/main.js"),
+                    p("src/util.js")
+                ],
+            ]
+        );
     }
 
     #[test]
-    fn test_group_patterns_with_none_prefix() {
-        let temp = create_multi_base_fixture();
+    fn test_default_ignore() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("node_modules/pkg")).unwrap();
+        File::create(base.join("node_modules/pkg/index.js")).unwrap();
+        fs::create_dir_all(base.join("src/node_modules/dep")).unwrap();
+        File::create(base.join("src/node_modules/dep/a.js")).unwrap();
+        fs::create_dir_all(base.join(".git")).unwrap();
+        File::create(base.join(".git/hook.js")).unwrap();
+        File::create(base.join("src/main.js")).unwrap();
+        let cwd = base.to_string_lossy().to_string();
+
+        let run = |default_ignore: Option<bool>, ignore: Option<&str>| {
+            let glob = Glob::new(
+                "**/*.js".to_string(),
+                GlobOptions {
+                    default_ignore,
+                    ignore: ignore.map(|i| Either::A(i.to_string())),
+                    ..make_opts_with_dot(&cwd, true)
+                },
+            );
+            let [first, rest @ ..] = walk_all_four(&glob);
+            for other in rest {
+                assert_eq!(first, other);
+            }
+            first
+        };
 
-        // Patterns with and without prefixes
-        let glob = Glob::new_multi(
+        assert_eq!(
+            run(None, None),
             vec![
-                "src/**/*.ts".to_string(),
-                "**/*.json".to_string(), // No prefix
-            ],
-            make_opts(&temp.path().to_string_lossy()),
+                p(".git/hook.js"),
+                p("node_modules/pkg/index.js"),
+                p("src/main.js"),
+                p("src/node_modules/dep/a.js")
+            ]
         );
+        assert_eq!(run(Some(true), None), vec![p("src/main.js")]);
+        // User ignores are merged with the defaults
+        assert!(run(Some(true), Some("src/**")).is_empty());
+        assert_eq!(run(Some(false), Some("src/**")).len(), 2);
 
-        let groups = glob.group_patterns_by_base();
-
-        // Should have 2 groups: src and None
-        assert_eq!(groups.len(), 2);
-        assert!(groups.contains_key(&Some("src".to_string())));
-        assert!(groups.contains_key(&None));
+        let filter = IgnoreFilter::new(
+            DEFAULT_IGNORE_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            false,
+            false,
+        );
+        assert!(filter.children_ignored("node_modules", &base.join("node_modules")));
+        assert!(filter.children_ignored("a/b/.git", &base.join("a/b/.git")));
+        assert!(!filter.children_ignored("src", &base.join("src")));
     }
 
     #[test]
-    fn test_should_use_multi_base_walking_true() {
-        let temp = create_multi_base_fixture();
+    fn test_no_prune_filter_for_leading_globstar() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let abs_cwd = temp.path().canonicalize().unwrap();
+        let prune_filter = |patterns: &[&str], opts: GlobOptions| {
+            let glob = Glob::new_multi(patterns.iter().map(|p| p.to_string()).collect(), opts);
+            glob.dir_prune_filter(Arc::clone(&glob.patterns), None, None, &abs_cwd)
+        };
 
-        // All patterns have different bases
-        let glob = Glob::new_multi(
-            vec!["src/**/*.ts".to_string(), "test/**/*.ts".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
-        );
+        // A leading ** can match under any directory, so there is nothing to prune
+        assert!(prune_filter(&["**/*.js"], make_opts(&cwd)).is_none());
+        assert!(prune_filter(&["**/*.js", "**/lib/*.txt"], make_opts(&cwd)).is_none());
 
-        assert!(glob.should_use_multi_base_walking());
+        // Any pattern that can rule out a directory still gets a filter
+        assert!(prune_filter(&["src/**/*.js"], make_opts(&cwd)).is_some());
+        assert!(prune_filter(&["*/*.js"], make_opts(&cwd)).is_some());
+        assert!(prune_filter(&["**/*.js", "*.txt"], make_opts(&cwd)).is_some());
+
+        // Ignored subtrees are pruned whatever the patterns look like
+        let ignoring = GlobOptions {
+            ignore: Some(Either::A("src/lib/**".to_string())),
+            ..make_opts(&cwd)
+        };
+        assert!(prune_filter(&["**/*.js"], ignoring).is_some());
+
+        // Without a filter the walk and its results are unchanged
+        let glob = Glob::new("**/*.js".to_string(), make_opts(&cwd));
+        for results in walk_all_four(&glob) {
+            assert_eq!(
+                results,
+                vec![
+                    p("baz.js"),
+                    p("src/lib/helper.js"),
+                    p("src/main.js"),
+                    p("src/util.js")
+                ]
+            );
+        }
     }
 
     #[test]
-    fn test_should_use_multi_base_walking_false_no_prefix() {
-        let temp = create_multi_base_fixture();
+    fn test_ignored_container_is_pruned() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("node_modules/pkg/lib")).unwrap();
+        File::create(base.join("node_modules/pkg/lib/deep.js")).unwrap();
+        fs::create_dir_all(base.join("src/node_modules/dep")).unwrap();
+        File::create(base.join("src/node_modules/dep/a.js")).unwrap();
+        fs::create_dir_all(base.join("dist")).unwrap();
+        File::create(base.join("dist/out.js")).unwrap();
+        File::create(base.join("src/main.js")).unwrap();
+        let cwd = base.to_string_lossy().to_string();
+        let abs_cwd = base.canonicalize().unwrap();
 
-        // One pattern has no prefix
-        let glob = Glob::new_multi(
-            vec![
-                "src/**/*.ts".to_string(),
-                "**/*.ts".to_string(), // No prefix
-            ],
-            make_opts(&temp.path().to_string_lossy()),
+        let glob = Glob::new(
+            "**/*.js".to_string(),
+            GlobOptions {
+                ignore: Some(Either::B(vec![
+                    "**/node_modules/**".to_string(),
+                    "dist".to_string(),
+                ])),
+                ..make_opts(&cwd)
+            },
         );
+        // The walker is told not to descend into the ignored containers at all
+        let prune = glob
+            .dir_prune_filter(Arc::clone(&glob.patterns), None, None, &abs_cwd)
+            .unwrap();
+        assert!(!prune("node_modules"));
+        assert!(!prune("src/node_modules"));
+        assert!(prune("src"));
+        // Ignoring just the directory entry still walks its contents
+        assert!(prune("dist"));
+
+        // With a literal prefix the walk root is below cwd
+        let prune = glob
+            .dir_prune_filter(
+                Arc::clone(&glob.patterns),
+                Some("src".to_string()),
+                Some("src/".to_string()),
+                &abs_cwd,
+            )
+            .unwrap();
+        assert!(!prune("node_modules"));
+
+        for results in walk_all_four(&glob) {
+            assert_eq!(results, vec![p("dist/out.js"), p("src/main.js")]);
+        }
+    }
 
-        assert!(!glob.should_use_multi_base_walking());
+    #[test]
+    fn test_explain_match_reports_match() {
+        let patterns = Either::B(vec![
+            Either::A("src/**/[a-c]*.ts".to_string()),
+            Either::A("**/*.ts".to_string()),
+        ]);
+        let explanation = explain_match("src/lib/main.ts".into(), patterns, None).unwrap();
+
+        assert!(explanation.matched);
+        assert_eq!(explanation.candidate, "src/lib/main.ts");
+        assert!(!explanation.excluded_by_dot);
+        assert!(!explanation.ignored);
+        assert_eq!(explanation.reason, "'src/lib/main.ts' matches '**/*.ts'");
+
+        // Patterns are reported in input order, not in the fast-path-first order
+        // used for matching
+        let fast = &explanation.patterns[1];
+        assert_eq!(fast.pattern, "**/*.ts");
+        assert!(fast.matched);
+        assert_eq!(fast.fast_path.as_deref(), Some("RecursiveExtension"));
+
+        let slow = &explanation.patterns[0];
+        assert_eq!(slow.pattern, "src/**/[a-c]*.ts");
+        assert!(!slow.matched);
+        assert_eq!(slow.fast_path, None);
+        assert!(!slow.regex.is_empty());
     }
 
     #[test]
-    fn test_should_use_multi_base_walking_false_same_base() {
-        let temp = create_multi_base_fixture();
+    fn test_explain_match_dot_rule_exclusion() {
+        let explanation =
+            explain_match(".github/ci.yml".into(), Either::A("**/*.yml".into()), None).unwrap();
+
+        assert!(!explanation.matched);
+        assert!(explanation.excluded_by_dot);
+        assert!(!explanation.ignored);
+        // The pattern itself matches; only the dot rule rejects the path
+        assert!(explanation.patterns[0].matched);
+        assert!(explanation.reason.contains("dot: true"));
+
+        let explanation = explain_match(
+            ".github/ci.yml".into(),
+            Either::A("**/*.yml".into()),
+            Some(MatchOptions {
+                dot: Some(true),
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+        assert!(explanation.matched);
+        assert!(!explanation.excluded_by_dot);
+    }
 
-        // All patterns have the same base
-        let glob = Glob::new_multi(
-            vec!["src/**/*.ts".to_string(), "src/lib/*.ts".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
+    #[test]
+    fn test_explain_match_ignore_exclusion() {
+        let opts = || {
+            Some(MatchOptions {
+                ignore: Some(Either::A("node_modules/**".to_string())),
+                ..Default::default()
+            })
+        };
+        let explanation = explain_match(
+            "node_modules/x/index.js".into(),
+            Either::A("**/*.js".into()),
+            opts(),
+        )
+        .unwrap();
+
+        assert!(!explanation.matched);
+        assert!(explanation.ignored);
+        assert!(!explanation.excluded_by_dot);
+        assert!(explanation.patterns[0].matched);
+        assert_eq!(
+            explanation.reason,
+            "'node_modules/x/index.js' is excluded by an ignore pattern"
         );
+        assert!(!matches_path(
+            "node_modules/x/index.js".into(),
+            Either::A("**/*.js".into()),
+            opts()
+        )
+        .unwrap());
+        assert!(matches_path("src/index.js".into(), Either::A("**/*.js".into()), opts()).unwrap());
 
-        // Only one group, so no benefit from multi-base
-        assert!(!glob.should_use_multi_base_walking());
+        // A directory-only pattern explains the missing trailing slash
+        let explanation = explain_match("src".into(), Either::A("*/".into()), None).unwrap();
+        assert!(!explanation.matched);
+        assert!(explanation.patterns[0].requires_directory);
+        assert!(explanation.reason.contains("require a directory"));
     }
 
     #[test]
-    fn test_should_use_multi_base_walking_false_nonexistent_dir() {
-        let temp = create_multi_base_fixture();
+    fn test_hard_depth_limit_is_an_error() {
+        let temp = TempDir::new().unwrap();
+        let mut dir = temp.path().to_path_buf();
+        for _ in 0..40 {
+            dir.push("d");
+            fs::create_dir(&dir).unwrap();
+        }
+        File::create(dir.join("deep.txt")).unwrap();
+        let cwd = temp.path().to_string_lossy().to_string();
 
-        // One base doesn't exist
-        let glob = Glob::new_multi(
-            vec!["src/**/*.ts".to_string(), "nonexistent/**/*.ts".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
+        let run = |pattern: &str, limit: Option<u32>, suppress: Option<bool>| {
+            glob_sync(
+                Either::A(pattern.to_string()),
+                Some(GlobOptions {
+                    hard_depth_limit: limit,
+                    suppress_errors: suppress,
+                    ..make_opts(&cwd)
+                }),
+            )
+        };
+
+        assert_eq!(run("**/*.txt", None, None).unwrap().len(), 1);
+        let err = run("**/*.txt", Some(20), None).unwrap_err();
+        assert!(
+            err.reason.contains("hard depth limit of 20"),
+            "{}",
+            err.reason
         );
 
-        assert!(!glob.should_use_multi_base_walking());
+        // suppressErrors returns what was found above the limit
+        assert_eq!(run("**", Some(20), Some(true)).unwrap().len(), 21);
+        assert!(run("**/*.txt", Some(20), Some(true)).unwrap().is_empty());
+
+        // Patterns that can't go as deep as the limit never reach it
+        assert_eq!(run("d/d/*", Some(20), None).unwrap(), vec![p("d/d/d")]);
+
+        // The iterator hands out what it found, then fails on the final batch
+        let iterate = |suppress: Option<bool>| {
+            let iterator = glob_iterate_with_file_types(
+                Either::A("**".to_string()),
+                Some(GlobOptions {
+                    hard_depth_limit: Some(20),
+                    suppress_errors: suppress,
+                    ..make_opts(&cwd)
+                }),
+                Some(4),
+            )
+            .unwrap();
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap();
+            runtime.block_on(async {
+                let mut found = 0;
+                loop {
+                    match iterator.next_batch(None).await {
+                        Ok(batch) if batch.is_empty() => return Ok(found),
+                        Ok(batch) => found += batch.len(),
+                        Err(err) => return Err((found, err)),
+                    }
+                }
+            })
+        };
+        let (found, err) = iterate(None).unwrap_err();
+        assert_eq!(found, 21);
+        assert!(
+            err.reason.contains("hard depth limit of 20"),
+            "{}",
+            err.reason
+        );
+        assert_eq!(iterate(Some(true)).unwrap(), 21);
     }
 
     #[test]
-    fn test_walk_multi_base_results() {
-        let temp = create_multi_base_fixture();
+    #[cfg(unix)]
+    fn test_unreadable_walk_root_is_an_error() {
+        use std::os::unix::fs::PermissionsExt;
 
-        // Multi-base pattern
-        let glob = Glob::new_multi(
-            vec!["src/**/*.ts".to_string(), "test/**/*.ts".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
-        );
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let locked = temp.path().join("locked");
+        fs::create_dir(&locked).unwrap();
+        File::create(locked.join("secret.txt")).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        if fs::read_dir(&locked).is_ok() {
+            // Running as root: permissions are not enforced
+            fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+            return;
+        }
 
-        let results = glob.walk_sync();
+        let run = |pattern: &str, cwd: &str, suppress: Option<bool>| {
+            glob_sync(
+                Either::A(pattern.to_string()),
+                Some(GlobOptions {
+                    suppress_errors: suppress,
+                    ..make_opts(cwd)
+                }),
+            )
+        };
+        let locked_cwd = locked.to_string_lossy().to_string();
 
-        // Should find files in both src and test
-        assert!(results.contains(&p("src/main.ts")));
-        assert!(results.contains(&p("src/util.ts")));
-        assert!(results.contains(&p("src/lib/helper.ts")));
-        assert!(results.contains(&p("test/main.test.ts")));
-        assert!(results.contains(&p("test/util.test.ts")));
-        assert!(results.contains(&p("test/fixtures/data.ts")));
+        let err = run("locked/**", &cwd, None).unwrap_err();
+        assert!(err.reason.contains("locked"), "{}", err.reason);
+        assert!(run("*.txt", &locked_cwd, None).is_err());
+        assert!(run("locked/secret.txt", &cwd, None).is_err());
+        let abs = format!("{}/*.txt", locked.to_string_lossy());
+        assert!(run(&abs, &cwd, None).is_err());
+
+        // Every walker reports the root it couldn't read
+        for backend in [
+            GlobOptions {
+                parallel: Some(true),
+                ..make_opts(&cwd)
+            },
+            GlobOptions {
+                cache: Some(true),
+                ..make_opts(&cwd)
+            },
+            GlobOptions {
+                use_native_io: Some(true),
+                ..make_opts(&cwd)
+            },
+        ] {
+            for pattern in ["locked/**", "locked/*.txt"] {
+                let result = glob_sync(Either::A(pattern.to_string()), Some(backend.clone()));
+                assert!(result.is_err(), "{pattern}");
+            }
+        }
 
-        // Should NOT find files in other directories (node_modules, lib)
-        assert!(!results.iter().any(|r| r.contains("node_modules")));
-        assert!(!results.contains(&p("lib/index.ts")));
+        // suppressErrors skips the root's contents as before
+        assert_eq!(run("locked/**", &cwd, Some(true)).unwrap(), vec!["locked"]);
+        assert_eq!(
+            run("*.txt", &locked_cwd, Some(true)).unwrap(),
+            Vec::<String>::new()
+        );
 
-        // Should have exactly 6 results
-        assert_eq!(results.len(), 6);
+        // Missing roots and unreadable directories below the root are not errors
+        assert_eq!(run("missing/**", &cwd, None).unwrap(), Vec::<String>::new());
+        assert!(run("**/*.txt", &cwd, None).is_ok());
+
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
     }
 
     #[test]
-    fn test_walk_multi_base_three_directories() {
-        let temp = create_multi_base_fixture();
+    fn test_ignore_set_matches_string_ignore() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let ignore = vec!["**/*.txt".to_string(), "src/lib/**".to_string()];
+        let set = IgnoreSet::new(ignore.clone(), Some(make_opts(&cwd))).unwrap();
+
+        for pattern in ["**", "src/**/*.js"] {
+            let with_strings = glob_sync(
+                Either::A(pattern.to_string()),
+                Some(GlobOptions {
+                    ignore: Some(Either::B(ignore.clone())),
+                    ..make_opts(&cwd)
+                }),
+            )
+            .unwrap();
+            let with_set = glob_sync(
+                Either::A(pattern.to_string()),
+                Some(GlobOptions {
+                    ignore_set: Some(set.clone()),
+                    ..make_opts(&cwd)
+                }),
+            )
+            .unwrap();
+            assert_eq!(with_set, with_strings, "{pattern}");
+            assert!(!with_set.iter().any(|r| r.ends_with(".txt")));
+        }
+        // The glob shares the compiled filter instead of building its own
+        let glob = Glob::new(
+            "**".to_string(),
+            GlobOptions {
+                ignore_set: Some(set.clone()),
+                ..make_opts(&cwd)
+            },
+        );
+        assert!(Arc::ptr_eq(
+            glob.ignore_filter.as_ref().unwrap(),
+            &set.filter
+        ));
+
+        let err = glob_sync(
+            Either::A("**".to_string()),
+            Some(GlobOptions {
+                ignore_set: Some(set),
+                default_ignore: Some(true),
+                ..make_opts(&cwd)
+            }),
+        )
+        .unwrap_err();
+        assert!(err.reason.contains("ignoreSet"), "{}", err.reason);
+    }
 
-        // Three different bases
-        let glob = Glob::new_multi(
-            vec![
-                "src/**/*.ts".to_string(),
-                "test/**/*.ts".to_string(),
-                "lib/**/*.ts".to_string(),
-            ],
-            make_opts(&temp.path().to_string_lossy()),
+    #[test]
+    fn test_is_ignored() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let abs_cwd = temp.path().canonicalize().unwrap();
+        let check = |path: &str, ignore: &[&str], extra: GlobOptions| {
+            let ignore = ignore.iter().map(|p| p.to_string()).collect();
+            let result = is_ignored(path.to_string(), ignore, Some(extra)).unwrap();
+            (result.ignored, result.children_ignored)
+        };
+        let ignore = ["*.txt", "src/lib/**"];
+
+        assert_eq!(check("foo.txt", &ignore, make_opts(&cwd)), (true, false));
+        assert_eq!(check("./foo.txt", &ignore, make_opts(&cwd)), (true, false));
+        assert_eq!(check("src/lib", &ignore, make_opts(&cwd)), (true, true));
+        assert_eq!(check("src/lib/", &ignore, make_opts(&cwd)), (true, true));
+        assert_eq!(
+            check("src/lib/helper.js", &ignore, make_opts(&cwd)),
+            (true, false)
+        );
+        // Not ignored
+        assert_eq!(
+            check("src/main.js", &ignore, make_opts(&cwd)),
+            (false, false)
         );
+        assert_eq!(check("src", &ignore, make_opts(&cwd)), (false, false));
+        assert_eq!(check("baz.js", &[], make_opts(&cwd)), (false, false));
 
-        let results = glob.walk_sync();
+        // Absolute ignore patterns resolve against cwd
+        let abs_ignore = format!("{}/baz.js", abs_cwd.to_string_lossy());
+        assert_eq!(
+            check("baz.js", &[&abs_ignore], make_opts(&cwd)),
+            (true, false)
+        );
 
-        // Should find files in all three directories
-        assert!(results.contains(&p("src/main.ts")));
-        assert!(results.contains(&p("test/main.test.ts")));
-        assert!(results.contains(&p("lib/index.ts")));
+        // The same options as a glob call apply
+        let defaults = GlobOptions {
+            default_ignore: Some(true),
+            ..make_opts(&cwd)
+        };
+        assert_eq!(check(".git", &[], defaults), (true, true));
+        let root_relative = GlobOptions {
+            root_relative: Some(true),
+            ..make_opts(&cwd)
+        };
+        assert_eq!(
+            check("foo.txt", &["/foo.txt"], root_relative),
+            (true, false)
+        );
 
-        // Should have exactly 7 results (3 in src, 3 in test, 1 in lib)
-        assert_eq!(results.len(), 7);
+        // The results agree with a walk using the same ignore patterns
+        let walked = Glob::new(
+            "**".to_string(),
+            GlobOptions {
+                ignore: Some(Either::B(ignore.iter().map(|p| p.to_string()).collect())),
+                ..make_opts(&cwd)
+            },
+        )
+        .walk_sync();
+        for path in [
+            "foo.txt",
+            "bar.txt",
+            "baz.js",
+            "src/main.js",
+            "src/lib/helper.js",
+        ] {
+            let ignored = check(path, &ignore, make_opts(&cwd)).0;
+            assert_eq!(ignored, !walked.contains(&p(path)), "{path}");
+        }
     }
 
     #[test]
-    fn test_walk_multi_base_with_nodir() {
-        let temp = create_multi_base_fixture();
+    fn test_can_contain_match() {
+        let check = |dir: &str, pattern: &str, options: Option<MatchOptions>| {
+            can_contain_match(dir.into(), Either::A(pattern.into()), options).unwrap()
+        };
 
-        let mut opts = make_opts(&temp.path().to_string_lossy());
-        opts.nodir = Some(true);
+        // The examples from `Pattern::could_match_in_dir`
+        assert!(check("src", "src/lib/**/*.ts", None));
+        assert!(check("src/lib", "src/lib/**/*.ts", None));
+        assert!(!check("test", "src/lib/**/*.ts", None));
+        assert!(!check("docs", "src/lib/**/*.ts", None));
+        assert!(check("any/path", "**/*.ts", None));
+
+        // cwd itself, leading ./ and trailing /
+        assert!(check(".", "src/*.ts", None));
+        assert!(check("", "src/*.ts", None));
+        assert!(check("./src/", "src/*.ts", None));
+        assert!(!check("src/lib", "src/*.ts", None));
+
+        // Any of several patterns keeps the directory
+        let patterns = vec![Either::A("src/**".into()), Either::A("test/*.ts".into())];
+        assert!(can_contain_match("test".into(), Either::B(patterns), None).unwrap());
+
+        // Directories whose contents are all ignored are prunable
+        let ignore = Some(MatchOptions {
+            ignore: Some(Either::A("node_modules/**".into())),
+            ..Default::default()
+        });
+        assert!(!check("node_modules", "**/*.js", ignore.clone()));
+        assert!(check("src", "**/*.js", ignore));
+    }
 
-        let glob = Glob::new_multi(vec!["src/**/*".to_string(), "test/**/*".to_string()], opts);
+    #[cfg(unix)]
+    #[test]
+    fn test_preserve_backslashes_in_filenames() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        File::create(base.join("back\\slash.txt")).unwrap();
+        File::create(base.join("plain.txt")).unwrap();
+        fs::create_dir_all(base.join("dir\\x")).unwrap();
+        File::create(base.join("dir\\x/f.js")).unwrap();
+        let cwd = base.to_string_lossy().to_string();
+
+        let run = |pattern: &str, preserve: Option<bool>| {
+            let glob = Glob::new(
+                pattern.to_string(),
+                GlobOptions {
+                    preserve_backslashes: preserve,
+                    ..make_opts(&cwd)
+                },
+            );
+            let mut results = glob.walk_sync();
+            results.sort();
+            results
+        };
 
-        let results = glob.walk_sync();
+        // Default: the walker converts backslashes as if they were separators
+        assert_eq!(run("**/*.txt", None), vec!["back/slash.txt", "plain.txt"]);
+        assert_eq!(run("**/*.js", None), vec!["dir/x/f.js"]);
 
-        // Should only contain files, not directories
-        assert!(results.contains(&p("src/main.ts")));
-        assert!(!results
-            .iter()
-            .any(|r| r == "src" || r == "src/" || r == "test" || r == "test/"));
+        assert_eq!(
+            run("*.txt", Some(true)),
+            vec!["back\\slash.txt", "plain.txt"]
+        );
+        assert_eq!(
+            run("**/*.txt", Some(true)),
+            vec!["back\\slash.txt", "plain.txt"]
+        );
+        assert_eq!(run("**/*.js", Some(true)), vec!["dir\\x/f.js"]);
+        assert_eq!(run("*/*.js", Some(true)), vec!["dir\\x/f.js"]);
+        // An escaped backslash in the pattern matches the literal character
+        assert_eq!(
+            run("back\\\\slash.txt", Some(true)),
+            vec!["back\\slash.txt"]
+        );
+        assert_eq!(run("back\\\\*", Some(true)), vec!["back\\slash.txt"]);
+        assert_eq!(run("dir\\\\x/*.js", Some(true)), vec!["dir\\x/f.js"]);
+        assert_eq!(
+            run("dir\\\\x/**", Some(true)),
+            vec!["dir\\x", "dir\\x/f.js"]
+        );
     }
 
     #[test]
-    fn test_walk_multi_base_deduplication() {
-        let temp = create_multi_base_fixture();
+    fn test_matches_path_custom_separator() {
+        let pat = |s: &str| Either::A(s.to_string());
+        let sep = |c: &str| {
+            Some(MatchOptions {
+                separator: Some(c.to_string()),
+                ..Default::default()
+            })
+        };
 
-        // Overlapping patterns that could produce duplicates
-        let glob = Glob::new_multi(
-            vec![
-                "src/**/*.ts".to_string(),
-                "src/lib/**/*.ts".to_string(), // More specific version
-            ],
-            make_opts(&temp.path().to_string_lossy()),
-        );
+        assert!(matches_path("a.b.c".into(), pat("a.*.c"), sep(".")).unwrap());
+        assert!(!matches_path("a.b.x.c".into(), pat("a.*.c"), sep(".")).unwrap());
+        assert!(matches_path("a.b.x.c".into(), pat("a.**.c"), sep(".")).unwrap());
+        assert!(matches_path("a.b.c".into(), pat("a.{b,x}.c"), sep(".")).unwrap());
+        // `/` is an ordinary character with another separator
+        assert!(matches_path("a/b.c".into(), pat("*.c"), sep(".")).unwrap());
+        assert!(!matches_path("a/b.c".into(), pat("a.*"), sep(".")).unwrap());
+        assert!(matches_path("ns:cache:key".into(), pat("ns:**"), sep(":")).unwrap());
+        assert!(matches_path(
+            "a.b.c".into(),
+            pat("c"),
+            Some(MatchOptions {
+                separator: Some(".".to_string()),
+                match_base: Some(true),
+                ..Default::default()
+            })
+        )
+        .unwrap());
+
+        // Classes and wildcards never match the separator, whatever the key holds
+        assert!(!matches_path("a.b".into(), pat("a[.]b"), sep(".")).unwrap());
+        assert!(!matches_path("a.b".into(), pat("a?b"), sep(".")).unwrap());
+        assert!(!matches_path("a\u{F8FF}b".into(), pat("a/b"), sep(".")).unwrap());
+        assert!(matches_path("./a".into(), pat("./a"), sep(":")).unwrap());
+        assert!(!matches_path("./a".into(), pat("a"), sep(":")).unwrap());
+        assert!(!matches_path(
+            "cache:user:1".into(),
+            pat("cache:**"),
+            Some(MatchOptions {
+                separator: Some(":".to_string()),
+                ignore: Some(Either::A("cache:user:**".to_string())),
+                ..Default::default()
+            })
+        )
+        .unwrap());
 
-        // Note: These have the same base (src), so they won't use multi-base walking
-        // But this tests that deduplication works in general
-        let results = glob.walk_sync();
+        // With the default `/`, `*` crosses dots
+        assert!(matches_path("a.b.x.c".into(), pat("a.*.c"), None).unwrap());
+        assert!(matches_path("a/b/c".into(), pat("a/*/c"), sep("/")).unwrap());
 
-        // Count occurrences of helper.ts
-        let helper_count = results.iter().filter(|r| r.contains("helper.ts")).count();
-        assert_eq!(helper_count, 1, "Should not have duplicate entries");
+        assert!(matches_path("a.b".into(), pat("*"), sep("**")).is_err());
+        assert!(matches_path("a.b".into(), pat("*"), sep("*")).is_err());
+        assert!(matches_path("a.b".into(), pat("*"), sep("")).is_err());
     }
 
     #[test]
-    fn test_walk_multi_base_empty_results() {
-        let temp = create_multi_base_fixture();
+    fn test_uncompilable_pattern_is_an_error() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
 
-        // Pattern for non-existent file types
-        let glob = Glob::new_multi(
-            vec![
-                "src/**/*.py".to_string(), // No Python files
-                "test/**/*.py".to_string(),
-            ],
-            make_opts(&temp.path().to_string_lossy()),
-        );
+        // Far beyond the regex engine's size limit
+        let huge = "?".repeat(50_000);
+        let err = glob_sync(Either::A(huge.clone()), Some(make_opts(&cwd))).unwrap_err();
+        assert!(err.reason.contains("too complex"), "{}", err.reason);
 
-        // Should still use multi-base walking but return empty results
-        let results = glob.walk_sync();
-        assert!(results.is_empty());
+        // One bad pattern fails the whole call, like maxPatterns
+        let mixed = Either::B(vec![Either::A("*.txt".to_string()), Either::A(huge)]);
+        assert!(glob_sync(mixed, Some(make_opts(&cwd))).is_err());
+
+        assert!(Pattern::new(&"?".repeat(50_000)).compile_error().is_some());
+        assert!(Pattern::new("*.txt").compile_error().is_none());
     }
 
     #[test]
-    fn test_walk_multi_base_parallel_results_match() {
-        let temp = create_multi_base_fixture();
+    fn test_max_patterns_limits_brace_expansion() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
 
-        // Test that parallel multi-base walking produces correct results
-        // by comparing with expected results
-        let glob = Glob::new_multi(
-            vec![
-                "src/**/*.ts".to_string(),
-                "test/**/*.ts".to_string(),
-                "lib/**/*.ts".to_string(),
-            ],
-            make_opts(&temp.path().to_string_lossy()),
-        );
+        // 2^40 expansions: must fail fast rather than exhaust memory
+        let pathological = "{a,b}".repeat(40);
+        let err = glob_sync(Either::A(pathological.clone()), Some(make_opts(&cwd))).unwrap_err();
+        assert!(err.reason.contains("maxPatterns"), "{}", err.reason);
+        assert!(err.reason.contains(&pathological), "{}", err.reason);
 
-        // Run multiple times to test parallel execution consistency
-        for _ in 0..5 {
-            let results = glob.walk_sync();
+        let with_limit = |max_patterns: u32| GlobOptions {
+            max_patterns: Some(max_patterns),
+            ..make_opts(&cwd)
+        };
+        let three = || Either::A("{foo,bar,baz}.txt".to_string());
+        assert!(glob_sync(three(), Some(with_limit(2))).is_err());
+        assert_eq!(glob_sync(three(), Some(with_limit(3))).unwrap().len(), 2);
+
+        // The limit applies to the total across all patterns
+        let many = || {
+            Either::B(vec![
+                Either::A("{foo,bar}.txt".to_string()),
+                Either::A("{baz,qux}.js".to_string()),
+            ])
+        };
+        let err = glob_sync(many(), Some(with_limit(3))).unwrap_err();
+        assert!(err.reason.contains("{baz,qux}.js"), "{}", err.reason);
+        assert_eq!(glob_sync(many(), Some(with_limit(4))).unwrap().len(), 3);
+
+        // Ignore patterns count towards it too
+        let ignoring = |max_patterns: u32| GlobOptions {
+            ignore: Some(Either::A("{a,b}/{c,d}/**".to_string())),
+            ..with_limit(max_patterns)
+        };
+        let err = glob_sync(three(), Some(ignoring(6))).unwrap_err();
+        assert!(err.reason.contains("{a,b}/{c,d}/**"), "{}", err.reason);
+        assert!(glob_sync(three(), Some(ignoring(7))).is_ok());
+        let err = IgnoreSet::new(vec![pathological.clone()], None)
+            .err()
+            .unwrap();
+        assert!(err.reason.contains("maxPatterns"), "{}", err.reason);
+    }
 
-            // Verify expected files are present (order may vary due to parallelism)
-            let results_set: std::collections::HashSet<_> = results.iter().collect();
+    #[test]
+    fn test_disable_optimizations_skips_fast_paths() {
+        let temp = create_test_fixture();
+        let cwd = temp.path().to_string_lossy().to_string();
+        let slow = |pattern: &str| {
+            Glob::new(
+                pattern.to_string(),
+                GlobOptions {
+                    disable_optimizations: Some(true),
+                    ..make_opts(&cwd)
+                },
+            )
+        };
 
+        for pattern in ["foo.txt", "*.js", "{src,.git}/**/*.js", "**/*.js"] {
+            let glob = slow(pattern);
             assert!(
-                results_set.contains(&String::from("src/main.ts")),
-                "Should contain src/main.ts"
-            );
-            assert!(
-                results_set.contains(&String::from("src/util.ts")),
-                "Should contain src/util.ts"
-            );
-            assert!(
-                results_set.contains(&String::from("src/lib/helper.ts")),
-                "Should contain src/lib/helper.ts"
-            );
-            assert!(
-                results_set.contains(&String::from("test/main.test.ts")),
-                "Should contain test/main.test.ts"
-            );
-            assert!(
-                results_set.contains(&String::from("test/util.test.ts")),
-                "Should contain test/util.test.ts"
-            );
-            assert!(
-                results_set.contains(&String::from("test/fixtures/data.ts")),
-                "Should contain test/fixtures/data.ts"
-            );
-            assert!(
-                results_set.contains(&String::from("lib/index.ts")),
-                "Should contain lib/index.ts"
+                matches!(
+                    glob.strategy(),
+                    WalkStrategy::FullWalk | WalkStrategy::PrefixScoped
+                ),
+                "{pattern} used {:?}",
+                glob.strategy()
             );
-
-            // Total should be 7 files
-            assert_eq!(results.len(), 7, "Should have exactly 7 results");
+            assert!(glob.patterns.iter().all(|p| !p.fast_path().is_fast()));
         }
+        assert_eq!(slow("foo.txt").walk_sync(), vec!["foo.txt"]);
     }
 
     #[test]
-    fn test_walk_multi_base_parallel_with_ignore() {
-        let temp = create_multi_base_fixture();
-
-        let mut opts = make_opts(&temp.path().to_string_lossy());
-        opts.ignore = Some(napi::Either::A("**/util*".to_string()));
-
-        let glob = Glob::new_multi(
-            vec!["src/**/*.ts".to_string(), "test/**/*.ts".to_string()],
-            opts,
-        );
-
-        let results = glob.walk_sync();
+    fn test_disable_optimizations_matches_optimized_results() {
+        // Small xorshift generator so failures reproduce from the seed
+        struct Rng(u64);
+        impl Rng {
+            fn next(&mut self) -> usize {
+                self.0 ^= self.0 << 13;
+                self.0 ^= self.0 >> 7;
+                self.0 ^= self.0 << 17;
+                self.0 as usize
+            }
+            fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+                items[self.next() % items.len()]
+            }
+        }
 
-        // Should have files except util-related ones
-        assert!(results.contains(&p("src/main.ts")));
-        assert!(!results.contains(&p("src/util.ts"))); // ignored
-        assert!(results.contains(&p("test/main.test.ts")));
-        assert!(!results.contains(&p("test/util.test.ts"))); // ignored
+        let dirs = ["src", "lib", "a", "b", ".cache", "node_modules", "Src"];
+        let files = [
+            "foo.js",
+            "bar.ts",
+            "x.d.ts",
+            "README.md",
+            "a.test.js",
+            ".env",
+            "index.TS",
+            "abc",
+        ];
+        let patterns = [
+            "*.js",
+            "**/*.js",
+            "**/*.{js,ts}",
+            "*.d.ts",
+            "**/*.d.ts",
+            "src/**/*.ts",
+            "{src,lib}/**",
+            "a/*/foo.js",
+            "**/README.md",
+            "README.md",
+            "src/foo.js",
+            "*/",
+            "**/.*",
+            "?/**/*.js",
+            "**/*.test.js",
+            "lib/*",
+            "**",
+        ];
+
+        for seed in 1..=12u64 {
+            let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+            let temp = TempDir::new().unwrap();
+            let base = temp.path();
+            for _ in 0..25 {
+                let mut path = base.to_path_buf();
+                for _ in 0..rng.next() % 4 {
+                    path.push(rng.pick(&dirs));
+                }
+                fs::create_dir_all(&path).unwrap();
+                let file = rng.pick(&files);
+                if !path.join(file).is_dir() {
+                    File::create(path.join(file)).unwrap();
+                }
+            }
+            let cwd = base.to_string_lossy().to_string();
+
+            for _ in 0..8 {
+                let picked: Vec<String> = (0..1 + rng.next() % 3)
+                    .map(|_| rng.pick(&patterns).to_string())
+                    .collect();
+                let opts = GlobOptions {
+                    dot: Some(rng.next().is_multiple_of(2)),
+                    nocase: Some(rng.next().is_multiple_of(3)),
+                    nodir: Some(rng.next().is_multiple_of(3)),
+                    mark: Some(rng.next().is_multiple_of(3)),
+                    ..make_opts(&cwd)
+                };
+                let run = |disable: bool| {
+                    let opts = GlobOptions {
+                        disable_optimizations: Some(disable),
+                        ..opts.clone()
+                    };
+                    let mut results = Glob::new_multi(picked.clone(), opts).walk_sync();
+                    results.sort();
+                    results
+                };
+                assert_eq!(
+                    run(false),
+                    run(true),
+                    "seed {seed}, patterns {picked:?}, dot {:?}, nocase {:?}, nodir {:?}, mark {:?}",
+                    opts.dot,
+                    opts.nocase,
+                    opts.nodir,
+                    opts.mark
+                );
+            }
+        }
     }
 
     #[test]
-    fn test_walk_multi_base_parallel_consistency() {
-        let temp = create_multi_base_fixture();
+    fn test_sequential_patterns_apply_in_order() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        for file in [
+            "a.ts",
+            "a.test.ts",
+            "keep.test.ts",
+            "src/b.ts",
+            "src/b.test.ts",
+            "src/keep.test.ts",
+        ] {
+            let path = base.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            File::create(path).unwrap();
+        }
+        let cwd = base.to_string_lossy().to_string();
+        let run = |patterns: &[&str], sequential: Option<bool>| {
+            let opts = GlobOptions {
+                sequential,
+                ..make_opts(&cwd)
+            };
+            let glob = Glob::new_multi(patterns.iter().map(|p| p.to_string()).collect(), opts);
+            let mut results = glob.walk_sync();
+            results.sort();
+            let mut streamed = Vec::new();
+            glob.walk_stream(|path| streamed.push(path));
+            streamed.sort();
+            assert_eq!(results, streamed);
+            results
+        };
 
-        // Run multi-base walking several times and verify results are consistent
-        let glob = Glob::new_multi(
-            vec!["src/**/*.ts".to_string(), "test/**/*.ts".to_string()],
-            make_opts(&temp.path().to_string_lossy()),
+        let patterns = ["**/*.ts", "!**/*.test.ts", "**/keep.test.ts"];
+        assert_eq!(
+            run(&patterns, Some(true)),
+            vec![
+                "a.ts".to_string(),
+                "keep.test.ts".to_string(),
+                p("src/b.ts"),
+                p("src/keep.test.ts"),
+            ]
         );
 
-        let first_results: std::collections::HashSet<_> = glob.walk_sync().into_iter().collect();
+        // The last matching pattern wins: excluding again removes keep.test.ts
+        assert_eq!(
+            run(&["**/*.ts", "**/keep.test.ts", "!**/*.test.ts"], Some(true)),
+            vec!["a.ts".to_string(), p("src/b.ts")]
+        );
 
-        for _ in 0..10 {
-            let results: std::collections::HashSet<_> = glob.walk_sync().into_iter().collect();
-            assert_eq!(
-                first_results, results,
-                "Parallel results should be consistent across runs"
-            );
-        }
-    }
+        // An exclusion only removes what earlier patterns added
+        assert_eq!(
+            run(&["!src/**", "src/*.ts"], Some(true)),
+            vec![p("src/b.test.ts"), p("src/b.ts"), p("src/keep.test.ts")]
+        );
 
-    #[test]
-    fn test_walk_single_base_group_returns_correct_results() {
-        let temp = create_multi_base_fixture();
-        let cwd = temp.path();
-        let abs_cwd = strip_windows_extended_prefix(cwd.canonicalize().unwrap());
+        // A `!(...)` extglob is still an include pattern
+        assert_eq!(
+            run(&["!(src)/**", "!**/*.test.ts"], Some(true)),
+            vec!["a.ts".to_string()]
+        );
 
-        let glob = Glob::new_multi(
+        // A later rule that can't match a dotfile doesn't undo its exclusion
+        fs::create_dir(base.join(".hidden")).unwrap();
+        File::create(base.join(".hidden/x.ts")).unwrap();
+        File::create(base.join(".hidden/y.ts")).unwrap();
+        assert_eq!(
+            run(&[".hidden/*.ts", "!.hidden/x.ts", "**/*.ts"], Some(true)),
             vec![
-                "src/**/*.ts".to_string(),
-                "src/lib/*.ts".to_string(),
-                "test/**/*.ts".to_string(),
-            ],
-            make_opts(&temp.path().to_string_lossy()),
+                p(".hidden/y.ts"),
+                "a.test.ts".to_string(),
+                "a.ts".to_string(),
+                "keep.test.ts".to_string(),
+                p("src/b.test.ts"),
+                p("src/b.ts"),
+                p("src/keep.test.ts"),
+            ]
         );
 
-        // Walk just the src group (indices 0 and 1)
-        let results = glob.walk_single_base_group(&[0, 1], &abs_cwd);
+        // Without sequential, `!` is literal and matches nothing here
+        assert_eq!(run(&["!**/*.test.ts"], None), Vec::<String>::new());
 
-        assert!(results.contains(&p("src/main.ts")));
-        assert!(results.contains(&p("src/util.ts")));
-        assert!(results.contains(&p("src/lib/helper.ts")));
-        assert!(!results.contains(&p("test/main.test.ts"))); // Not in this group
+        // With noext there is no extglob, so `!(src)/**` excludes a literal `(src)`
+        fs::create_dir(base.join("(src)")).unwrap();
+        File::create(base.join("(src)/c.ts")).unwrap();
+        let opts = GlobOptions {
+            sequential: Some(true),
+            noext: Some(true),
+            ..make_opts(&cwd)
+        };
+        let patterns = vec!["*/*.ts".to_string(), "!(src)/**".to_string()];
+        let mut results = Glob::new_multi(patterns, opts).walk_sync();
+        results.sort();
+        assert_eq!(
+            results,
+            vec![p("src/b.test.ts"), p("src/b.ts"), p("src/keep.test.ts")]
+        );
     }
 }
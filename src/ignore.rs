@@ -1,13 +1,21 @@
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::path::Path;
 
-use crate::pattern::{expand_braces, Pattern, PatternOptions};
+use crate::pattern::{expand_braces_limited, Pattern, PatternOptions};
+
+/// Patterns ignored when the `defaultIgnore` option is set: dependency and VCS
+/// directories that are rarely wanted in results and expensive to walk.
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &["**/node_modules/**", "**/.git/**"];
 
 /// Ignore filter for glob matching
 ///
 /// Ignores paths matching the ignore patterns.
 /// Ignore patterns are always parsed in dot:true mode.
 /// Patterns ending in /** can skip entire directory trees.
+///
+/// Besides the cwd-relative rules, the filter can hold rule sets scoped to a
+/// directory, the way a nested `.gitignore` applies only below its own folder.
 pub struct IgnoreFilter {
     /// Patterns that match against relative paths
     relative: Vec<Pattern>,
@@ -19,6 +27,9 @@ pub struct IgnoreFilter {
     absolute_children: Vec<Pattern>,
     /// Pattern options for creating patterns
     pattern_opts: PatternOptions,
+    /// Rule sets keyed by the cwd-relative directory they apply to. Their
+    /// relative patterns are matched against paths relative to that directory.
+    scoped: BTreeMap<String, IgnoreFilter>,
 }
 
 /// Normalize path separators, avoiding allocation when no backslashes are present.
@@ -32,18 +43,18 @@ fn normalize_path_separators(path: &str) -> Cow<'_, str> {
     }
 }
 
-/// Build path with trailing slash, avoiding allocation when already has slash.
+/// Build path with a trailing separator, avoiding allocation when it already has one.
 #[inline]
-fn with_trailing_slash<'a>(path: &'a str, buffer: &'a mut String) -> &'a str {
-    if path == "." {
+fn with_trailing_separator<'a>(path: &'a str, separator: char, buffer: &'a mut String) -> &'a str {
+    if path == "." && separator == '/' {
         "./"
-    } else if path.ends_with('/') {
+    } else if path.ends_with(separator) {
         path
     } else {
         buffer.clear();
-        buffer.reserve(path.len() + 1);
+        buffer.reserve(path.len() + separator.len_utf8());
         buffer.push_str(path);
-        buffer.push('/');
+        buffer.push(separator);
         buffer.as_str()
     }
 }
@@ -51,50 +62,163 @@ fn with_trailing_slash<'a>(path: &'a str, buffer: &'a mut String) -> &'a str {
 impl IgnoreFilter {
     /// Create a new IgnoreFilter from ignore patterns
     pub fn new(ignore_patterns: Vec<String>, noext: bool, windows_paths_no_escape: bool) -> Self {
+        Self::with_separator(ignore_patterns, noext, windows_paths_no_escape, None)
+    }
+
+    /// Create an IgnoreFilter for keys whose segments are separated by
+    /// `separator` instead of `/` (`None` keeps path handling)
+    pub fn with_separator(
+        ignore_patterns: Vec<String>,
+        noext: bool,
+        windows_paths_no_escape: bool,
+        separator: Option<char>,
+    ) -> Self {
         let pattern_opts = PatternOptions {
             noext,
             windows_paths_no_escape,
             nocase: false, // Ignore patterns are always case-sensitive per glob behavior
+            separator,
             ..Default::default()
         };
 
-        let mut filter = Self {
+        let mut filter = Self::with_pattern_options(pattern_opts);
+
+        for pattern_str in ignore_patterns {
+            filter.add(&pattern_str);
+        }
+
+        filter
+    }
+
+    /// Like [`with_separator`](Self::with_separator), but fail with the first
+    /// pattern whose brace expansion takes the total past `max_patterns`.
+    pub fn with_pattern_limit(
+        ignore_patterns: Vec<String>,
+        noext: bool,
+        windows_paths_no_escape: bool,
+        separator: Option<char>,
+        max_patterns: usize,
+    ) -> Result<Self, String> {
+        let mut filter =
+            Self::with_separator(Vec::new(), noext, windows_paths_no_escape, separator);
+        let mut remaining = max_patterns;
+        for pattern_str in ignore_patterns {
+            match filter.add_limited(&pattern_str, remaining) {
+                Some(added) => remaining -= added,
+                None => return Err(pattern_str),
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Create an empty filter whose patterns are built with `pattern_opts`
+    fn with_pattern_options(pattern_opts: PatternOptions) -> Self {
+        Self {
             relative: Vec::new(),
             relative_children: Vec::new(),
             absolute: Vec::new(),
             absolute_children: Vec::new(),
             pattern_opts,
+            scoped: BTreeMap::new(),
+        }
+    }
+
+    /// Add an ignore pattern that only applies inside `dir`
+    ///
+    /// `dir` is relative to cwd and the pattern follows the rules of a
+    /// `.gitignore` in that directory: a pattern without a slash matches at any
+    /// depth below `dir`, so `*.log` scoped to `src` ignores `src/a.log` and
+    /// `src/lib/a.log` but not `a.log` or `test/a.log`. A leading or inner slash
+    /// anchors the pattern to `dir` (`/a.log`, `lib/*.log`). Rules never match
+    /// `dir` itself. An empty `dir` (or `.`) adds an ordinary cwd-relative pattern.
+    pub fn add_scoped(&mut self, dir: &str, pattern_str: &str) {
+        let dir = normalize_path_separators(dir);
+        let dir = dir.trim_start_matches("./").trim_end_matches('/');
+        if dir.is_empty() || dir == "." {
+            self.add(pattern_str);
+            return;
+        }
+
+        let pattern = match pattern_str.strip_prefix('/') {
+            Some(anchored) => Cow::Borrowed(anchored),
+            None if !pattern_str.trim_end_matches('/').contains('/') => {
+                Cow::Owned(format!("**/{pattern_str}"))
+            }
+            None => Cow::Borrowed(pattern_str),
         };
+        let pattern_opts = self.pattern_opts.clone();
+        self.scoped
+            .entry(dir.to_string())
+            .or_insert_with(|| Self::with_pattern_options(pattern_opts))
+            .add(&pattern);
+    }
 
-        for pattern_str in ignore_patterns {
-            filter.add(&pattern_str);
+    /// The separator between segments of relative paths
+    fn separator(&self) -> char {
+        self.pattern_opts.separator.unwrap_or('/')
+    }
+
+    /// Normalize `\` to `/` in a relative path; keys with a custom separator
+    /// are matched as given
+    fn normalize_relative<'a>(&self, rel_path: &'a str) -> Cow<'a, str> {
+        match self.pattern_opts.separator {
+            Some(_) => Cow::Borrowed(rel_path),
+            None => normalize_path_separators(rel_path),
         }
+    }
 
-        filter
+    /// The scoped rule sets that apply to `rel_path`, each with the path
+    /// rewritten relative to its directory.
+    fn scopes_for<'a>(&'a self, rel_path: &'a str) -> impl Iterator<Item = (&'a Self, &'a str)> {
+        self.scoped.iter().filter_map(move |(dir, rules)| {
+            rel_path
+                .strip_prefix(dir.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+                .filter(|rest| !rest.is_empty())
+                .map(|rest| (rules, rest))
+        })
     }
 
     /// Add an ignore pattern
+    ///
+    /// An escaped `\!` is a literal `!`, so files such as `!important.txt` or
+    /// `!(draft).md` can still be ignored.
     pub fn add(&mut self, pattern_str: &str) {
+        self.add_limited(pattern_str, usize::MAX);
+    }
+
+    /// Add an ignore pattern unless it expands to more than `limit` patterns.
+    /// Returns how many it expanded to, or `None` (adding nothing) if too many.
+    fn add_limited(&mut self, pattern_str: &str, limit: usize) -> Option<usize> {
         // Expand braces first
-        let expanded = expand_braces(pattern_str);
+        let expanded = expand_braces_limited(pattern_str, limit)?;
         let patterns_to_process = if expanded.is_empty() {
+            if limit == 0 {
+                return None;
+            }
             vec![pattern_str.to_string()]
         } else {
             expanded
         };
+        let added = patterns_to_process.len();
 
+        let separator = self.pattern_opts.separator;
+        let children_suffix = format!("{}**", separator.unwrap_or('/'));
         for pattern in patterns_to_process {
-            // Strip leading ./ portions
-            let stripped = pattern.trim_start_matches("./");
+            // Strip leading ./ portions (keys with a custom separator aren't paths)
+            let stripped = match separator {
+                Some(_) => pattern.as_str(),
+                None => pattern.trim_start_matches("./"),
+            };
 
             // Check if this pattern ends with /** (children should be ignored)
-            let is_children = stripped.ends_with("/**");
+            let is_children = stripped.ends_with(&children_suffix);
 
             // For children patterns, we need to match the parent directory
             // e.g., "node_modules/**" should match "node_modules" and its children
             let children_pattern = if is_children {
                 // Create a pattern without the trailing /**
-                let base = stripped.trim_end_matches("/**");
+                let base = stripped.trim_end_matches(children_suffix.as_str());
                 if base.is_empty() {
                     None // "/**" alone doesn't make sense as a children pattern
                 } else {
@@ -105,9 +229,10 @@ impl IgnoreFilter {
             };
 
             // Check if pattern is absolute
-            let is_absolute = stripped.starts_with('/')
-                || (stripped.len() >= 2 && stripped.chars().nth(1) == Some(':'))
-                || stripped.starts_with("//");
+            let is_absolute = separator.is_none()
+                && (stripped.starts_with('/')
+                    || (stripped.len() >= 2 && stripped.chars().nth(1) == Some(':'))
+                    || stripped.starts_with("//"));
 
             // Create the pattern (ignore patterns always use dot:true mode internally)
             let pat = Pattern::with_pattern_options(stripped, self.pattern_opts.clone());
@@ -128,6 +253,7 @@ impl IgnoreFilter {
                 }
             }
         }
+        Some(added)
     }
 
     /// Check if a path should be ignored
@@ -137,11 +263,12 @@ impl IgnoreFilter {
     /// `abs_path` is the absolute path.
     pub fn should_ignore(&self, rel_path: &str, abs_path: &Path) -> bool {
         // Normalize the relative path (avoids allocation if no backslashes)
-        let rel_normalized = normalize_path_separators(rel_path);
+        let rel_normalized = self.normalize_relative(rel_path);
 
         // Build path with trailing slash using reusable buffer
         let mut slash_buffer = String::new();
-        let rel_with_slash = with_trailing_slash(&rel_normalized, &mut slash_buffer);
+        let rel_with_slash =
+            with_trailing_separator(&rel_normalized, self.separator(), &mut slash_buffer);
 
         // Check relative patterns
         for pattern in &self.relative {
@@ -157,7 +284,7 @@ impl IgnoreFilter {
 
         // Reuse buffer for absolute path with slash
         slash_buffer.clear();
-        let abs_with_slash = with_trailing_slash(&abs_str, &mut slash_buffer);
+        let abs_with_slash = with_trailing_separator(&abs_str, '/', &mut slash_buffer);
 
         for pattern in &self.absolute {
             if pattern.matches(&abs_str) || pattern.matches(abs_with_slash) {
@@ -165,6 +292,12 @@ impl IgnoreFilter {
             }
         }
 
+        for (rules, scoped_path) in self.scopes_for(&rel_normalized) {
+            if rules.should_ignore(scoped_path, abs_path) {
+                return true;
+            }
+        }
+
         false
     }
 
@@ -173,11 +306,12 @@ impl IgnoreFilter {
     /// This is used to skip traversing into directories that match patterns like "node_modules/**".
     pub fn children_ignored(&self, rel_path: &str, abs_path: &Path) -> bool {
         // Normalize the relative path (avoids allocation if no backslashes)
-        let rel_normalized = normalize_path_separators(rel_path);
+        let rel_normalized = self.normalize_relative(rel_path);
 
         // Build path with trailing slash using reusable buffer
         let mut slash_buffer = String::new();
-        let rel_with_slash = with_trailing_slash(&rel_normalized, &mut slash_buffer);
+        let rel_with_slash =
+            with_trailing_separator(&rel_normalized, self.separator(), &mut slash_buffer);
 
         // Check relative children patterns
         for pattern in &self.relative_children {
@@ -192,7 +326,7 @@ impl IgnoreFilter {
 
         // Reuse buffer for absolute path with slash
         slash_buffer.clear();
-        let abs_with_slash = with_trailing_slash(&abs_str, &mut slash_buffer);
+        let abs_with_slash = with_trailing_separator(&abs_str, '/', &mut slash_buffer);
 
         for pattern in &self.absolute_children {
             if pattern.matches(&abs_str) || pattern.matches(abs_with_slash) {
@@ -200,15 +334,32 @@ impl IgnoreFilter {
             }
         }
 
+        for (rules, scoped_path) in self.scopes_for(&rel_normalized) {
+            if rules.children_ignored(scoped_path, abs_path) {
+                return true;
+            }
+        }
+
         false
     }
 
+    /// Check if a directory is an ignored container: the directory itself and
+    /// everything below it are ignored, so the walk need not descend into it.
+    ///
+    /// `**/node_modules/**` ignores `node_modules` as well as its contents, while
+    /// `node_modules/**/*` only ignores the contents and `node_modules` alone
+    /// ignores just the directory entry; neither of those is a container.
+    pub fn ignores_subtree(&self, rel_path: &str, abs_path: &Path) -> bool {
+        self.children_ignored(rel_path, abs_path) && self.should_ignore(rel_path, abs_path)
+    }
+
     /// Check if this filter has any patterns
     pub fn is_empty(&self) -> bool {
         self.relative.is_empty()
             && self.absolute.is_empty()
             && self.relative_children.is_empty()
             && self.absolute_children.is_empty()
+            && self.scoped.values().all(IgnoreFilter::is_empty)
     }
 }
 
@@ -259,6 +410,18 @@ mod tests {
         assert!(!filter.children_ignored("bc", &PathBuf::from("/test/bc")));
     }
 
+    #[test]
+    fn test_ignores_subtree() {
+        let filter = make_filter(&["**/node_modules/**", "dist"]);
+
+        assert!(filter.ignores_subtree("node_modules", &PathBuf::from("/test/node_modules")));
+        assert!(filter.ignores_subtree("a/node_modules", &PathBuf::from("/test/a/node_modules")));
+        // Ignoring a directory entry alone doesn't ignore what's inside it
+        assert!(filter.should_ignore("dist", &PathBuf::from("/test/dist")));
+        assert!(!filter.ignores_subtree("dist", &PathBuf::from("/test/dist")));
+        assert!(!filter.ignores_subtree("src", &PathBuf::from("/test/src")));
+    }
+
     #[test]
     fn test_nested_pattern() {
         let filter = make_filter(&["b/c/d"]);
@@ -324,4 +487,82 @@ mod tests {
         let non_empty = make_filter(&["*.txt"]);
         assert!(!non_empty.is_empty());
     }
+
+    #[test]
+    fn test_escaped_bang_is_literal() {
+        let filter = make_filter(&["\\!important.txt"]);
+
+        assert!(filter.should_ignore("!important.txt", &PathBuf::from("/test/!important.txt")));
+        assert!(!filter.should_ignore("important.txt", &PathBuf::from("/test/important.txt")));
+        assert!(!filter.should_ignore("other.txt", &PathBuf::from("/test/other.txt")));
+
+        // The escaped `!` doesn't open a `!(...)` extglob
+        let filter = make_filter(&["\\!(draft).md"]);
+        assert!(filter.should_ignore("!(draft).md", &PathBuf::from("/test/!(draft).md")));
+        assert!(!filter.should_ignore("a.md", &PathBuf::from("/test/a.md")));
+        assert!(!filter.should_ignore("draft.md", &PathBuf::from("/test/draft.md")));
+
+        // Wildcards after it still apply
+        let filter = make_filter(&["\\!*.txt"]);
+        assert!(filter.should_ignore("!a.txt", &PathBuf::from("/test/!a.txt")));
+        assert!(!filter.should_ignore("a.txt", &PathBuf::from("/test/a.txt")));
+        assert!(!filter.should_ignore("!a.md", &PathBuf::from("/test/!a.md")));
+
+        // With windowsPathsNoEscape the backslash is a separator, not an escape
+        let filter = IgnoreFilter::new(vec!["\\!important.txt".to_string()], false, true);
+        assert!(!filter.should_ignore("!important.txt", &PathBuf::from("/test/!important.txt")));
+    }
+
+    #[test]
+    fn test_scoped_rules_apply_only_inside_their_directory() {
+        let mut filter = make_filter(&[]);
+        filter.add_scoped("src/", "*.log");
+        filter.add_scoped("./src/lib", "generated/**");
+        assert!(!filter.is_empty());
+
+        assert!(filter.should_ignore("src/a.log", &PathBuf::from("/test/src/a.log")));
+        // A slash-less rule matches at any depth below its directory, as in .gitignore
+        assert!(filter.should_ignore("src/lib/a.log", &PathBuf::from("/test/src/lib/a.log")));
+        // Siblings and cwd are unaffected
+        assert!(!filter.should_ignore("a.log", &PathBuf::from("/test/a.log")));
+        assert!(!filter.should_ignore("test/a.log", &PathBuf::from("/test/test/a.log")));
+        assert!(!filter.should_ignore("srcx/a.log", &PathBuf::from("/test/srcx/a.log")));
+
+        // A leading or inner slash anchors the rule to its directory
+        filter.add_scoped("pkg", "/a.tmp");
+        filter.add_scoped("pkg", "lib/*.out");
+        assert!(filter.should_ignore("pkg/a.tmp", &PathBuf::from("/test/pkg/a.tmp")));
+        assert!(!filter.should_ignore("pkg/sub/a.tmp", &PathBuf::from("/test/pkg/sub/a.tmp")));
+        assert!(filter.should_ignore("pkg/lib/b.out", &PathBuf::from("/test/pkg/lib/b.out")));
+        assert!(!filter.should_ignore(
+            "pkg/sub/lib/b.out",
+            &PathBuf::from("/test/pkg/sub/lib/b.out")
+        ));
+
+        // Nested scopes apply together, and children patterns prune the subtree
+        let generated = PathBuf::from("/test/src/lib/generated");
+        assert!(filter.ignores_subtree("src/lib/generated", &generated));
+        assert!(!filter.children_ignored("generated", &PathBuf::from("/test/generated")));
+
+        // The scope directory itself is never matched by its own rules
+        filter.add_scoped("docs", "**");
+        assert!(!filter.should_ignore("docs", &PathBuf::from("/test/docs")));
+        assert!(filter.should_ignore("docs/a.md", &PathBuf::from("/test/docs/a.md")));
+
+        // An empty scope is the cwd
+        filter.add_scoped("", "*.tmp");
+        assert!(filter.should_ignore("a.tmp", &PathBuf::from("/test/a.tmp")));
+    }
+
+    #[test]
+    fn test_escaped_bang_with_globstar() {
+        let filter = make_filter(&["\\!important.txt", "**/\\!keep/**"]);
+
+        assert!(filter.should_ignore("!important.txt", &PathBuf::from("/test/!important.txt")));
+        assert!(!filter.should_ignore(
+            "sub/!important.txt",
+            &PathBuf::from("/test/sub/!important.txt")
+        ));
+        assert!(filter.children_ignored("a/!keep", &PathBuf::from("/test/a/!keep")));
+    }
 }
@@ -24,7 +24,7 @@ use std::os::unix::ffi::OsStringExt;
 use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
 use std::path::{Path, PathBuf};
 
-use crate::walker::{WalkEntry, WalkOptions};
+use crate::walker::{DirHandleLimit, WalkEntry, WalkOptions};
 
 /// Default number of entries to batch in io_uring submission queue
 const DEFAULT_BATCH_SIZE: usize = 64;
@@ -239,6 +239,7 @@ impl IoUringWalker {
                 is_dir,
                 is_file,
                 is_symlink,
+                size: None,
             });
 
             if is_dir {
@@ -255,11 +256,28 @@ impl IoUringWalker {
                 }
             }
 
-            // Read directory entries using optimized syscall
-            let dir_entries = match read_dir_getdents64(&dir_path) {
+            // Read directory entries using optimized syscall; the handle is closed
+            // before returning, so the permit only covers the read
+            let read = {
+                let _permit = self
+                    .options
+                    .dir_handles
+                    .as_deref()
+                    .map(DirHandleLimit::acquire);
+                read_dir_getdents64(&dir_path)
+            };
+            let mut dir_entries = match read {
                 Ok(entries) => entries,
-                Err(_) => continue, // Skip unreadable directories
+                Err(err) => {
+                    // Skip unreadable directories, unless the walk reports them
+                    self.options
+                        .note_unreadable(&dir_path, dir_path == self.root, &err);
+                    continue;
+                }
             };
+            if self.options.sort_dir_entries {
+                dir_entries.sort_by(|a, b| a.name.cmp(&b.name));
+            }
 
             for raw_entry in dir_entries {
                 let name_str = raw_entry.name.to_string_lossy();
@@ -292,6 +310,7 @@ impl IoUringWalker {
                     is_dir,
                     is_file,
                     is_symlink,
+                    size: None,
                 });
 
                 // Queue directories for processing (unless symlink and not following)
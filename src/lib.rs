@@ -6,6 +6,7 @@ extern crate napi_derive;
 
 // Module declarations - made public for profiling binary
 pub mod cache;
+pub mod fs;
 pub mod glob;
 pub mod ignore;
 pub mod options;
@@ -37,10 +38,27 @@ pub use options::GlobOptions;
 ///
 /// @param pattern - The glob pattern to escape
 /// @param windowsPathsNoEscape - If true, use `[x]` wrapping instead of backslash escapes
+/// @param magicalBraces - If true, escape `{` and `}` too so the result is never brace-expanded
 /// @returns The escaped pattern
 #[napi]
-pub fn escape(pattern: String, windows_paths_no_escape: Option<bool>) -> String {
-    pattern::escape_pattern(&pattern, windows_paths_no_escape.unwrap_or(false))
+pub fn escape(
+    pattern: String,
+    windows_paths_no_escape: Option<bool>,
+    magical_braces: Option<bool>,
+) -> String {
+    escape_with(
+        &pattern,
+        windows_paths_no_escape.unwrap_or(false),
+        magical_braces.unwrap_or(false),
+    )
+}
+
+fn escape_with(pattern: &str, windows_paths_no_escape: bool, magical_braces: bool) -> String {
+    if magical_braces {
+        pattern::escape_pattern_with_braces(pattern, windows_paths_no_escape)
+    } else {
+        pattern::escape_pattern(pattern, windows_paths_no_escape)
+    }
 }
 
 /// Unescape magic glob characters in a pattern.
@@ -54,6 +72,71 @@ pub fn unescape(pattern: String, windows_paths_no_escape: Option<bool>) -> Strin
     pattern::unescape_pattern(&pattern, windows_paths_no_escape.unwrap_or(false))
 }
 
+/// Escape magic glob characters in each of several patterns.
+/// Equivalent to calling `escape()` on each, in a single call.
+///
+/// @param patterns - The glob patterns to escape
+/// @param windowsPathsNoEscape - If true, use `[x]` wrapping instead of backslash escapes
+/// @param magicalBraces - If true, escape `{` and `}` too so the results are never brace-expanded
+/// @returns The escaped patterns, in the same order
+#[napi]
+pub fn escape_all(
+    patterns: Vec<String>,
+    windows_paths_no_escape: Option<bool>,
+    magical_braces: Option<bool>,
+) -> Vec<String> {
+    let windows_paths_no_escape = windows_paths_no_escape.unwrap_or(false);
+    let magical_braces = magical_braces.unwrap_or(false);
+    patterns
+        .iter()
+        .map(|p| escape_with(p, windows_paths_no_escape, magical_braces))
+        .collect()
+}
+
+/// Unescape magic glob characters in each of several patterns.
+/// Equivalent to calling `unescape()` on each, in a single call.
+///
+/// @param patterns - The escaped patterns to unescape
+/// @param windowsPathsNoEscape - If true, remove `[x]` wrapping instead of backslash escapes
+/// @returns The unescaped patterns, in the same order
+#[napi]
+pub fn unescape_all(patterns: Vec<String>, windows_paths_no_escape: Option<bool>) -> Vec<String> {
+    let windows_paths_no_escape = windows_paths_no_escape.unwrap_or(false);
+    patterns
+        .iter()
+        .map(|p| pattern::unescape_pattern(p, windows_paths_no_escape))
+        .collect()
+}
+
+/// Expand a pattern into the concrete patterns its braces stand for, the same
+/// way a glob does before matching: `{a,b}/*.{js,ts}` gives four patterns.
+/// With `nobrace` the pattern is returned unchanged.
+///
+/// @param pattern - The glob pattern to expand
+/// @param options - Glob options (`nobrace` and `maxPatterns` apply)
+/// @returns The expanded patterns, in expansion order
+#[napi]
+pub fn expand_pattern(pattern: String, options: Option<GlobOptions>) -> napi::Result<Vec<String>> {
+    let options = options.unwrap_or_default();
+    if options.nobrace.unwrap_or(false) {
+        return Ok(vec![pattern]);
+    }
+
+    let max_patterns = options
+        .max_patterns
+        .map_or(glob::DEFAULT_MAX_PATTERNS, |max| max as usize);
+    let expanded = pattern::expand_braces_limited(&pattern, max_patterns).ok_or_else(|| {
+        napi::Error::from_reason(format!(
+            "Too many patterns: expanding '{pattern}' exceeds maxPatterns ({max_patterns})"
+        ))
+    })?;
+    Ok(if expanded.is_empty() {
+        vec![pattern]
+    } else {
+        expanded
+    })
+}
+
 /// Check if a pattern contains any magic glob characters.
 /// Takes into account escaped characters.
 ///
@@ -137,6 +220,34 @@ impl From<pattern::PatternWarning> for PatternWarningInfo {
                 pattern: Some(pattern),
                 suggestion: Some(suggestion),
             },
+            pattern::PatternWarning::ParentOfRoot {
+                pattern,
+                suggestion,
+            } => PatternWarningInfo {
+                warning_type: "parent_of_root".to_string(),
+                message,
+                pattern: Some(pattern),
+                suggestion: Some(suggestion),
+            },
+            pattern::PatternWarning::ImpossiblePattern {
+                pattern,
+                suggestion,
+                ..
+            } => PatternWarningInfo {
+                warning_type: "impossible_pattern".to_string(),
+                message,
+                pattern: Some(pattern),
+                suggestion: Some(suggestion),
+            },
+            pattern::PatternWarning::LiteralBraceRange {
+                pattern,
+                suggestion,
+            } => PatternWarningInfo {
+                warning_type: "literal_brace_range".to_string(),
+                message,
+                pattern: Some(pattern),
+                suggestion: Some(suggestion),
+            },
             pattern::PatternWarning::EmptyPattern => PatternWarningInfo {
                 warning_type: "empty_pattern".to_string(),
                 message,
@@ -152,6 +263,17 @@ impl From<pattern::PatternWarning> for PatternWarningInfo {
         }
     }
 }
+/// Get the built-in ignore patterns used by the `defaultIgnore` option.
+/// Useful for inspecting the list or extending it in a custom `ignore` array.
+///
+/// @returns The default ignore patterns
+#[napi]
+pub fn default_ignore_patterns() -> Vec<String> {
+    ignore::DEFAULT_IGNORE_PATTERNS
+        .iter()
+        .map(|p| p.to_string())
+        .collect()
+}
 
 /// Analyze a pattern for potential issues and return warnings.
 /// This is useful for providing helpful feedback about common mistakes.
@@ -198,10 +320,187 @@ pub fn analyze_patterns(
     .collect()
 }
 
+/// Warnings of one kind collected across patterns, returned by `analyzePatternsGrouped`.
+#[napi(object)]
+pub struct GroupedPatternWarning {
+    /// The type of warning shared by the group (e.g., "performance", "empty_pattern")
+    pub warning_type: String,
+    /// Human-readable message describing the group's problem, without naming a pattern
+    pub message: String,
+    /// Number of warnings collapsed into this entry
+    pub count: u32,
+    /// The pattern behind each warning in the group, in input order (`""` for
+    /// empty patterns), so there are always `count` of them
+    pub patterns: Vec<String>,
+}
+
+/// Analyze multiple patterns and collapse warnings of the same type and reason
+/// into one entry with a count, so a repeated mistake is reported once.
+///
+/// @param patterns - Array of glob patterns to analyze
+/// @param windowsPathsNoEscape - Whether backslashes are path separators (Windows mode)
+/// @param platform - The target platform ("win32", "darwin", "linux")
+/// @returns One entry per warning type and reason, in order of first occurrence
+#[napi]
+pub fn analyze_patterns_grouped(
+    patterns: Vec<String>,
+    windows_paths_no_escape: Option<bool>,
+    platform: Option<String>,
+) -> Vec<GroupedPatternWarning> {
+    pattern::analyze_patterns_grouped(
+        &patterns,
+        windows_paths_no_escape.unwrap_or(false),
+        platform.as_deref(),
+    )
+    .into_iter()
+    .map(|group| {
+        let count = group.len() as u32;
+        let message = group[0].summary();
+        let mut infos = group.into_iter().map(PatternWarningInfo::from);
+        let first = infos.next().expect("warning groups are never empty");
+        let patterns = std::iter::once(first.pattern)
+            .chain(infos.map(|info| info.pattern))
+            .map(Option::unwrap_or_default)
+            .collect();
+        GroupedPatternWarning {
+            warning_type: first.warning_type,
+            message,
+            count,
+            patterns,
+        }
+    })
+    .collect()
+}
+
+/// Set how many compiled patterns are cached between glob calls.
+///
+/// The cache evicts the least recently used pattern once full. Lowering the
+/// capacity evicts immediately; long-running processes that see many unique
+/// patterns can use it to bound memory. The default is 1024.
+///
+/// @param capacity - Maximum number of cached patterns (at least 1)
+#[napi]
+pub fn set_pattern_cache_capacity(capacity: u32) -> napi::Result<()> {
+    let capacity = std::num::NonZeroUsize::new(capacity as usize)
+        .ok_or_else(|| napi::Error::from_reason("pattern cache capacity must be at least 1"))?;
+    cache::set_cache_capacity(capacity);
+    Ok(())
+}
+
+/// Get the size, capacity and hit/miss counts of the compiled pattern cache.
+///
+/// @returns Statistics for the pattern cache
+#[napi]
+pub fn pattern_cache_stats() -> cache::PatternCacheStats {
+    cache::get_cache_stats()
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn it_works() {
         assert_eq!(2 + 2, 4);
     }
+
+    #[test]
+    fn test_escape_all_matches_escape() {
+        let names: Vec<String> = ["file*.txt", "dir[1]/a?.js", "plain.txt", "{a,b}", ""]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        for (windows_paths_no_escape, magical_braces) in [
+            (None, None),
+            (Some(false), Some(false)),
+            (Some(true), None),
+            (None, Some(true)),
+            (Some(true), Some(true)),
+        ] {
+            let escaped = escape_all(names.clone(), windows_paths_no_escape, magical_braces);
+            let expected: Vec<String> = names
+                .iter()
+                .map(|n| escape(n.clone(), windows_paths_no_escape, magical_braces))
+                .collect();
+            assert_eq!(escaped, expected);
+            assert_eq!(
+                escaped[3] != "{a,b}",
+                magical_braces == Some(true),
+                "{escaped:?}"
+            );
+
+            let unescaped = unescape_all(escaped.clone(), windows_paths_no_escape);
+            let expected: Vec<String> = escaped
+                .iter()
+                .map(|e| unescape(e.clone(), windows_paths_no_escape))
+                .collect();
+            assert_eq!(unescaped, expected);
+            assert_eq!(unescaped, names);
+        }
+
+        assert!(escape_all(vec![], None, None).is_empty());
+    }
+
+    #[test]
+    fn test_analyze_patterns_grouped_counts_same_type() {
+        let patterns: Vec<String> = ["**/**/**/*.ts", "*.txt", "**/**/**/*.js"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let grouped = analyze_patterns_grouped(patterns.clone(), None, None);
+        assert_eq!(grouped.len(), 1);
+        assert_eq!(grouped[0].warning_type, "performance");
+        assert_eq!(grouped[0].count, 2);
+        assert_eq!(grouped[0].patterns, ["**/**/**/*.ts", "**/**/**/*.js"]);
+        assert!(
+            !grouped[0].message.contains("*.ts"),
+            "{}",
+            grouped[0].message
+        );
+
+        // Empty patterns are listed too, so every warning has its pattern
+        let grouped = analyze_patterns_grouped(vec![String::new(), String::new()], None, None);
+        assert_eq!(grouped.len(), 1);
+        assert_eq!(grouped[0].warning_type, "empty_pattern");
+        assert_eq!(grouped[0].count, 2);
+        assert_eq!(grouped[0].patterns, ["", ""]);
+    }
+
+    #[test]
+    fn test_expand_pattern() {
+        let expand = |pattern: &str| expand_pattern(pattern.to_string(), None).unwrap();
+
+        assert_eq!(
+            expand("{a,b}/*.{js,ts}"),
+            ["a/*.js", "a/*.ts", "b/*.js", "b/*.ts"]
+        );
+        assert_eq!(expand("a{b,c{d,e}}f"), ["abf", "acdf", "acef"]);
+        assert_eq!(
+            expand("file{1..3}.txt"),
+            ["file1.txt", "file2.txt", "file3.txt"]
+        );
+        assert_eq!(expand("{a..c}"), ["a", "b", "c"]);
+        assert_eq!(expand("{01..03}"), ["01", "02", "03"]);
+        assert_eq!(expand("{,foo}"), ["foo"]);
+        assert_eq!(expand("src/**/*.js"), ["src/**/*.js"]);
+        assert_eq!(expand("{a}"), ["{a}"]);
+
+        let nobrace = GlobOptions {
+            nobrace: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            expand_pattern("{a,b}".to_string(), Some(nobrace)).unwrap(),
+            ["{a,b}"]
+        );
+
+        let limited = GlobOptions {
+            max_patterns: Some(3),
+            ..Default::default()
+        };
+        let err = expand_pattern("{a,b}{c,d}".to_string(), Some(limited)).unwrap_err();
+        assert!(err.reason.contains("maxPatterns (3)"));
+    }
 }
@@ -29,7 +29,7 @@ use std::os::unix::ffi::OsStrExt;
 use std::os::unix::ffi::OsStringExt;
 use std::path::{Path, PathBuf};
 
-use crate::walker::{WalkEntry, WalkOptions};
+use crate::walker::{DirHandleLimit, WalkEntry, WalkOptions};
 
 /// Default buffer size for getattrlistbulk (32KB for ~100-200 entries per call)
 const ATTR_BUFFER_SIZE: usize = 32768;
@@ -509,7 +509,12 @@ impl MacosWalker {
             }
             Err(_) => {
                 // Fall back to standard readdir with cache opts
-                read_dir_fast_with_opts(path, enable_read_ahead, disable_cache).unwrap_or_default()
+                read_dir_fast_with_opts(path, enable_read_ahead, disable_cache).unwrap_or_else(
+                    |err| {
+                        self.options.note_unreadable(path, path == self.root, &err);
+                        Vec::new()
+                    },
+                )
             }
         }
     }
@@ -564,6 +569,7 @@ impl MacosWalker {
                 is_dir,
                 is_file,
                 is_symlink,
+                size: None,
             });
 
             if is_dir {
@@ -571,6 +577,9 @@ impl MacosWalker {
             }
         }
 
+        // Cloned so permits don't borrow `self` across the `&mut self` reads
+        let dir_handles = self.options.dir_handles.clone();
+
         // Process directories level by level (BFS for better cache locality)
         while let Some((dir_path, depth)) = dirs_to_process.pop_front() {
             // Check depth limit
@@ -584,8 +593,14 @@ impl MacosWalker {
             // Root level directories are more likely to be large
             let expected_large = depth == 1;
 
-            // Read directory entries using optimized function with cache opts
-            let dir_entries = self.read_dir(&dir_path, expected_large);
+            // Read directory entries using optimized function with cache opts; the
+            // handle is closed before returning, so the permit only covers the read
+            let permit = dir_handles.as_deref().map(DirHandleLimit::acquire);
+            let mut dir_entries = self.read_dir(&dir_path, expected_large);
+            drop(permit);
+            if self.options.sort_dir_entries {
+                dir_entries.sort_by(|a, b| a.name.cmp(&b.name));
+            }
 
             for raw_entry in dir_entries {
                 let name_str = raw_entry.name.to_string_lossy();
@@ -618,6 +633,7 @@ impl MacosWalker {
                     is_dir,
                     is_file,
                     is_symlink,
+                    size: None,
                 });
 
                 // Queue directories for processing (unless symlink and not following)
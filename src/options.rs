@@ -1,5 +1,43 @@
 use napi::bindgen_prelude::*;
 
+use crate::glob::IgnoreSet;
+use crate::pattern::PatternWarning;
+
+/// Strategy used to drop duplicate results during a walk.
+#[napi(string_enum)]
+#[derive(Default, Debug, PartialEq, Eq)]
+pub enum DedupStrategy {
+    /// Store each result path in full.
+    #[default]
+    FullString,
+    /// Store only a 64-bit hash of each result path.
+    Hash64,
+}
+
+/// What makes a file or directory hidden when `dot` is false.
+#[napi(string_enum)]
+#[derive(Default, Debug, PartialEq, Eq)]
+pub enum HiddenMode {
+    /// Names starting with `.` are hidden.
+    #[default]
+    DotfilesOnly,
+    /// Entries with the Windows hidden attribute are hidden.
+    AttributeOnly,
+    /// Both dotfiles and entries with the hidden attribute are hidden.
+    Both,
+}
+
+/// Where a directory comes in a walk relative to the entries inside it.
+#[napi(string_enum)]
+#[derive(Default, Debug, PartialEq, Eq)]
+pub enum DirOrder {
+    /// A directory comes before its contents.
+    #[default]
+    PreOrder,
+    /// A directory comes after its contents.
+    PostOrder,
+}
+
 /// Complete GlobOptions struct with all glob v13 options.
 ///
 /// All options are optional and false by default unless otherwise noted.
@@ -30,6 +68,7 @@ pub struct GlobOptions {
     pub dot: Option<bool>,
 
     /// Do not expand `{a,b}` and `{1..3}` brace sets.
+    /// A brace range is then matched literally; only `globSyncChecked` warns about it.
     pub nobrace: Option<bool>,
 
     /// Do not match `**` against multiple filenames.
@@ -52,7 +91,7 @@ pub struct GlobOptions {
     /// Treat brace expansion like `{a,b}` as a "magic" pattern.
     /// Has no effect if `nobrace` is set.
     ///
-    /// Only affects the `hasMagic` function.
+    /// Only affects the `hasMagic` and `escape` functions; `escape` then escapes braces too.
     #[napi(js_name = "magicalBraces")]
     pub magical_braces: Option<bool>,
 
@@ -66,6 +105,166 @@ pub struct GlobOptions {
     /// pattern, following the same behavior as Bash.
     pub follow: Option<bool>,
 
+    /// Sort the entries of each directory by name before matching them.
+    ///
+    /// Results (and streamed results) come out in `ls`-like name order within
+    /// every directory, without waiting for the walk to finish and sorting the
+    /// whole result set. The order across directories still depends on the walker.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "sortDirEntries")]
+    pub sort_dir_entries: Option<bool>,
+
+    /// Whether a matched directory comes before (`PreOrder`) or after
+    /// (`PostOrder`) the matches inside it.
+    ///
+    /// `PostOrder` suits recursive deletion: removing results in order empties
+    /// each directory before it is removed. Walks then always read depth-first,
+    /// so `useNativeIO` and `useGcd` are ignored, and literal patterns are walked
+    /// instead of stat'ed. Default: `PreOrder`.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "dirOrder")]
+    pub dir_order: Option<DirOrder>,
+
+    /// Keep backslashes in emitted paths instead of converting them to `/`.
+    ///
+    /// On Linux and macOS a filename may legitimately contain `\\`; by default it
+    /// is treated as a separator and the name is corrupted in the results. With
+    /// this option, `\\` in filesystem paths is an ordinary character and
+    /// patterns match it literally (use `\\\\` or `*` to match it). Ignored on
+    /// Windows, where `\\` is always a separator.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "preserveBackslashes")]
+    pub preserve_backslashes: Option<bool>,
+
+    /// Maximum number of patterns after brace expansion, summed over all input
+    /// patterns and `ignore` patterns (including `defaultIgnore`). Defaults to
+    /// `100000`.
+    ///
+    /// A pattern like `{a,b}{c,d}{e,f}...` grows exponentially; once the total would
+    /// exceed this limit the call fails with an error naming the offending pattern
+    /// instead of expanding without bound.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "maxPatterns")]
+    pub max_patterns: Option<u32>,
+
+    /// Keep the glob inside `cwd`. Patterns that would reach above it (such as
+    /// `../secrets/*` or an absolute path elsewhere) are rejected with an error,
+    /// and results that resolve outside `cwd` through a symlink are dropped.
+    ///
+    /// Use this when patterns come from untrusted input.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "restrictToCwd")]
+    pub restrict_to_cwd: Option<bool>,
+
+    /// Also ignore the built-in list of heavy directories (`**/node_modules/**`
+    /// and `**/.git/**`), in addition to any `ignore` patterns. Ignored
+    /// directories are not traversed.
+    ///
+    /// The list is available from `defaultIgnorePatterns()`.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "defaultIgnore")]
+    pub default_ignore: Option<bool>,
+
+    /// With `withFileTypes`, read where each symlink points and report it as
+    /// `linkTarget`. The target is returned as stored in the link, without
+    /// resolving it. Only symlinks cost an extra `readlink` call.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "readLinkTargets")]
+    pub read_link_targets: Option<bool>,
+
+    /// With `withFileTypes`, report whether each directory is empty as
+    /// `isEmptyDir`. Only directories cost an extra `readdir` call.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "detectEmptyDirs")]
+    pub detect_empty_dirs: Option<bool>,
+
+    /// Treat a leading `/` as anchoring the pattern at `cwd`, like in `.gitignore`,
+    /// instead of the filesystem root. `/src/*.ts` then matches `src/a.ts` but not
+    /// `lib/src/a.ts`. Applies to `ignore` patterns too.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "rootRelative")]
+    pub root_relative: Option<bool>,
+
+    /// Keep patterns in input order instead of trying the ones with cheap
+    /// matching first. Results are the same; this only matters for debugging or
+    /// when relying on which pattern is tried first.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "preservePatternOrder")]
+    pub preserve_pattern_order: Option<bool>,
+
+    /// Treat `!(...)` as literal text while still expanding the other extglobs
+    /// (`+(...)`, `@(...)`, `*(...)`, `?(...)`). Finer-grained than `noext`.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "noNegation")]
+    pub no_negation: Option<bool>,
+
+    /// Match every pattern against only the final segment of each path, even
+    /// patterns containing `/`: `src/*.ts` then matches any `*.ts` file at any
+    /// depth. Stronger than `matchBase`, which leaves patterns with `/` anchored.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "basenameOnly")]
+    pub basename_only: Option<bool>,
+
+    /// What counts as hidden for `dot: false`.
+    ///
+    /// - `DotfilesOnly` (default): names starting with `.`, as in glob.
+    /// - `AttributeOnly`: entries with the Windows hidden attribute
+    ///   (`FILE_ATTRIBUTE_HIDDEN`); dotfiles are then matched like other files.
+    /// - `Both`: either of the above.
+    ///
+    /// Attribute checks cost one metadata call per entry and skip the contents of
+    /// hidden directories. Nothing has the attribute on other platforms.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    pub hidden: Option<HiddenMode>,
+
+    /// Drop duplicate results (default `true`). With `false`, a path is returned
+    /// once for every input pattern it matches, e.g. for per-pattern accounting;
+    /// each copy directly follows the first. Only `globSync`, `glob` and
+    /// `globSyncChecked` repeat paths; other functions always deduplicate.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    pub unique: Option<bool>,
+
+    /// Apply the patterns in order, like tsconfig `include`/`exclude` lists: a
+    /// pattern starting with `!` removes the paths matched so far, and a later
+    /// pattern can add them back. The last pattern matching a path decides.
+    /// Without this option, a leading `!` is a literal character.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    pub sequential: Option<bool>,
+
+    /// When patterns have several distinct bases (e.g. `src/**/*.js` and
+    /// `test/**/*.js`), stream each base's results as soon as its walk finds them
+    /// instead of after every base has been walked. Results from different bases
+    /// interleave in no fixed order. Only affects `globStreamBatched`, the one
+    /// function that walks off the JavaScript thread and delivers results while
+    /// the walk is still running.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "streamPerBase")]
+    pub stream_per_base: Option<bool>,
+
+    /// String prepended to every returned path, after all other formatting
+    /// (`absolute`, `mark`, `posix`, `dotRelative`) has been applied. Not applied
+    /// to `Path` objects returned with `withFileTypes`.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "resultPrefix")]
+    pub result_prefix: Option<String>,
+
     /// Limit the directory traversal to a given depth below the cwd.
     ///
     /// - `undefined`/`None`: No limit (traverse all levels)
@@ -117,6 +316,7 @@ pub struct GlobOptions {
 
     /// Do not match directories, only files.
     /// (Note: to match _only_ directories, put a `/` at the end of the pattern.)
+    /// Such a pattern matches nothing with `nodir`; only `globSyncChecked` warns about it.
     pub nodir: Option<bool>,
 
     /// Return `/` delimited paths, even on Windows.
@@ -152,6 +352,28 @@ pub struct GlobOptions {
     /// This incurs a slight performance penalty due to the added system calls.
     pub realpath: Option<bool>,
 
+    /// With `realpath`, keep broken symlinks in the results under their own
+    /// path instead of dropping them. Entries that fail to resolve for any
+    /// other reason (such as a symlink loop) are still omitted.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "realpathKeepBroken")]
+    pub realpath_keep_broken: Option<bool>,
+
+    /// Report each file reachable through several paths only once, under its
+    /// real path.
+    ///
+    /// With `follow`, a file under a symlinked directory is found both directly
+    /// and through the link. This drops the paths that go through a symlink when
+    /// the real path is also among the results. Unlike `realpath`, the results
+    /// are not rewritten: a file only reachable through a symlink keeps its path.
+    /// It applies to every API, `withFileTypes` and the counting ones included;
+    /// streaming APIs buffer the whole walk first.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "canonicalOnly")]
+    pub canonical_only: Option<bool>,
+
     // ==================== Filtering Options ====================
     /// Patterns to exclude from matching.
     /// Can be a single pattern string or an array of patterns.
@@ -164,8 +386,21 @@ pub struct GlobOptions {
     /// of any other settings.
     ///
     /// Patterns ending in `/**` will ignore the directory and all its children.
+    ///
+    /// A leading `\!` matches a literal `!`, e.g. `\!important.txt`, and keeps
+    /// `\!(draft).md` from being read as an extglob. With `windowsPathsNoEscape`
+    /// the `\` is a path separator instead.
     pub ignore: Option<Either<String, Vec<String>>>,
 
+    /// Precompiled ignore patterns, used in place of `ignore`. Build an
+    /// `IgnoreSet` once and pass it to every call that shares the same ignore
+    /// list, so the patterns aren't recompiled each time. Can't be combined
+    /// with `ignore` or `defaultIgnore`; give those to the `IgnoreSet` instead.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "ignoreSet", ts_type = "IgnoreSet")]
+    pub ignore_set: Option<IgnoreSet>,
+
     /// Do not match any children of any matches.
     ///
     /// For example, a recursive pattern would match "a/foo" but not "a/foo/b/foo"
@@ -279,6 +514,104 @@ pub struct GlobOptions {
     /// **Note:** This is a globlin-specific option not present in the original glob package.
     #[napi(js_name = "useGcd")]
     pub use_gcd: Option<bool>,
+
+    /// How results are deduplicated while walking.
+    ///
+    /// - `FullString` (default): remembers every result path. Exact, but memory
+    ///   grows with the total length of all matched paths.
+    /// - `Hash64`: remembers only a 64-bit hash of each path, using a fixed 8 bytes
+    ///   per result regardless of path length.
+    ///
+    /// With `Hash64`, two different paths hashing to the same value would cause the
+    /// second one to be dropped. The chance is roughly `n² / 2⁶⁵` for `n` results
+    /// (about 1 in 37 million for 1 million results), so only use it for very
+    /// large result sets where the memory saving matters.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "dedupStrategy")]
+    pub dedup_strategy: Option<DedupStrategy>,
+
+    /// Only remember the results of directories the walk is still inside.
+    ///
+    /// The walk is depth-first, so once it leaves a directory it never emits a path
+    /// from it again; the entries recorded for that directory are then dropped and
+    /// deduplication memory is bounded by the widest directories on the current
+    /// path instead of growing with every result. This matters most for streaming,
+    /// where results are not otherwise kept in memory. Takes precedence over
+    /// `dedupStrategy`.
+    ///
+    /// Duplicates that appear after their directory was left are not caught. A
+    /// single walk never produces those, but they can occur when separate walks
+    /// from different base directories reach the same path, or with a parallel
+    /// walk that interleaves directories. Unique paths are never dropped.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "scopedDedup")]
+    pub scoped_dedup: Option<bool>,
+
+    /// Return no results instead of an error when a directory the walk starts
+    /// from (the cwd, or the literal prefix of a pattern such as `/srv/data` in
+    /// `/srv/data/**`) exists but can't be read, e.g. because of permissions.
+    ///
+    /// By default such a root is an error, so "permission denied" can be told apart
+    /// from "nothing matched". Missing roots are never an error, and unreadable
+    /// directories below the root are always skipped silently. Streams report the
+    /// error once the walk has finished.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "suppressErrors")]
+    pub suppress_errors: Option<bool>,
+
+    /// Don't descend into directories on a different filesystem than the walk
+    /// root, like `find -xdev`. Keeps `**` out of network mounts or `/proc`.
+    ///
+    /// Such directories are skipped together with their contents. Devices are
+    /// compared by `st_dev` on Unix; on Windows this option has no effect.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "oneFileSystem")]
+    pub one_file_system: Option<bool>,
+
+    /// Maximum number of directory handles the walk keeps open at once.
+    /// Lower it on systems with a small `ulimit -n`, where deep or `parallel`
+    /// walks could otherwise fail with "too many open files".
+    ///
+    /// The cap covers the whole glob, including the concurrent walks of a
+    /// multi-base pattern set. With `parallel`, this also caps the number of
+    /// walker threads.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "maxOpenDirs")]
+    pub max_open_dirs: Option<u32>,
+
+    /// Depth past which the walk never descends, whatever `maxDepth` allows
+    /// (default 4096). Guards against pathologically deep trees and followed
+    /// symlink loops. Reaching it is an error, since the results are incomplete;
+    /// with `suppressErrors` the results gathered so far are returned instead.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "hardDepthLimit")]
+    pub hard_depth_limit: Option<u32>,
+
+    /// Handle patterns like lines of a `.gitignore` file: leading and trailing
+    /// whitespace is trimmed, and blank patterns and `#` comments are skipped.
+    /// A trailing space escaped with a backslash (`foo\ `) is kept.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "trimPatterns")]
+    pub trim_patterns: Option<bool>,
+
+    /// Turn off every matching and walking shortcut: pattern fast paths, the
+    /// static, shallow and multi-base strategies, pattern-derived depth limits
+    /// and directory pruning. Each glob then walks the tree and tests every
+    /// path against the full pattern regex.
+    ///
+    /// Results should be identical either way, so this is a debugging aid for
+    /// telling a fast-path bug from a pattern bug. It is much slower.
+    ///
+    /// **Note:** This is a globlin-specific option not present in the original glob package.
+    #[napi(js_name = "disableOptimizations")]
+    pub disable_optimizations: Option<bool>,
     // ==================== Not Supported in Rust ====================
     // The following options are handled in the JavaScript wrapper:
     // - signal: AbortSignal (JS-only)
@@ -332,6 +665,153 @@ impl GlobOptions {
     }
 }
 
+/// Options that a single pattern may override on top of the call-wide `GlobOptions`.
+///
+/// Unset fields inherit the call-wide value.
+#[napi(object)]
+#[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PatternOverrides {
+    /// Perform a case-insensitive match for this pattern only.
+    pub nocase: Option<bool>,
+
+    /// Include `.dot` files in matches for this pattern only.
+    pub dot: Option<bool>,
+
+    /// Do not expand extglob patterns such as `+(a|b)` for this pattern only.
+    pub noext: Option<bool>,
+}
+
+/// A pattern together with its per-pattern option overrides.
+#[napi(object)]
+#[derive(Default, Clone)]
+pub struct PatternInput {
+    /// The glob pattern.
+    pub pattern: String,
+
+    /// Options that apply to this pattern only.
+    pub options: Option<PatternOverrides>,
+}
+
+impl From<String> for PatternInput {
+    fn from(pattern: String) -> Self {
+        Self {
+            pattern,
+            options: None,
+        }
+    }
+}
+
+/// Options for matching candidate paths against patterns without walking the
+/// filesystem (see `matchesPath`).
+///
+/// The pattern options mean the same as in `GlobOptions`.
+#[napi(object)]
+#[derive(Default, Clone)]
+pub struct MatchOptions {
+    /// Include `.dot` files in normal matches and `globstar` matches.
+    pub dot: Option<bool>,
+
+    /// Do not expand `{a,b}` and `{1..3}` brace sets.
+    pub nobrace: Option<bool>,
+
+    /// Do not match `**` against multiple filenames.
+    pub noglobstar: Option<bool>,
+
+    /// Do not match "extglob" patterns such as `+(a|b)`.
+    pub noext: Option<bool>,
+
+    /// Perform a case-insensitive match.
+    ///
+    /// Defaults to `true` on macOS and Windows systems, and `false` on all others.
+    pub nocase: Option<bool>,
+
+    /// Match patterns without slashes against the basename of the path.
+    #[napi(js_name = "matchBase")]
+    pub match_base: Option<bool>,
+
+    /// Use `\\` as a path separator _only_, and _never_ as an escape character.
+    #[napi(js_name = "windowsPathsNoEscape")]
+    pub windows_paths_no_escape: Option<bool>,
+
+    /// Platform to use for path handling. Defaults to the current platform.
+    pub platform: Option<String>,
+
+    /// Lexically normalize the candidate path before matching: `.` segments
+    /// are dropped and `..` segments remove the preceding segment, so
+    /// `src/./lib/../main.ts` is matched as `src/main.ts`.
+    ///
+    /// No filesystem access is involved, so symlinks are not resolved.
+    #[napi(js_name = "normalizeCandidate")]
+    pub normalize_candidate: Option<bool>,
+
+    /// Keep backslashes in the candidate path instead of treating them as `/`.
+    ///
+    /// Same semantics as the `preserveBackslashes` glob option: by default a
+    /// candidate like `src\\main.ts` (from a Windows API) matches `src/*.ts`.
+    /// Ignored on Windows, where `\\` is always a separator.
+    #[napi(js_name = "preserveBackslashes")]
+    pub preserve_backslashes: Option<bool>,
+
+    /// The character that delimits segments, for matching keys that aren't
+    /// filesystem paths (`a.b.c`, `ns:key`). Defaults to `/`.
+    ///
+    /// `*`, `?` and character classes never match the separator and `**`
+    /// spans any number of segments, exactly as with `/`. Keys are taken as
+    /// given: `/` and `\\` are ordinary characters and `./` isn't stripped.
+    /// Must be a single character that has no glob meaning of its own.
+    pub separator: Option<String>,
+
+    /// Glob pattern or array of glob patterns; a candidate matching any of
+    /// them never matches. Same semantics as the `ignore` glob option.
+    pub ignore: Option<Either<String, Vec<String>>>,
+
+    /// Directory that absolute candidate paths are made relative to before
+    /// matching, so `/project/src/a.ts` with base `/project` is matched as
+    /// `src/a.ts`. Relative candidates are matched as given, and an absolute
+    /// candidate outside the base never matches.
+    pub base: Option<String>,
+
+    /// Report an absolute candidate outside `base` as an error instead of a
+    /// non-match.
+    #[napi(js_name = "strictBase")]
+    pub strict_base: Option<bool>,
+}
+
+impl MatchOptions {
+    /// Get the custom segment separator, or `None` for the default `/`.
+    pub fn effective_separator(&self) -> Result<Option<char>> {
+        let Some(separator) = self.separator.as_deref() else {
+            return Ok(None);
+        };
+        let mut chars = separator.chars();
+        match (chars.next(), chars.next()) {
+            (Some('/'), None) => Ok(None),
+            (Some(c), None) if !"\\*?[]{}()!+@|,".contains(c) => Ok(Some(c)),
+            _ => Err(Error::from_reason(format!(
+                "separator must be a single character without glob meaning, got {separator:?}"
+            ))),
+        }
+    }
+}
+
+impl From<&MatchOptions> for GlobOptions {
+    fn from(options: &MatchOptions) -> Self {
+        GlobOptions {
+            dot: options.dot,
+            nobrace: options.nobrace,
+            noglobstar: options.noglobstar,
+            noext: options.noext,
+            nocase: options.nocase,
+            match_base: options.match_base,
+            windows_paths_no_escape: options.windows_paths_no_escape,
+            platform: options.platform.clone(),
+            ignore: options.ignore.clone(),
+            preserve_backslashes: options.preserve_backslashes,
+            ..Default::default()
+        }
+    }
+}
+
 /// Validate glob options and return an error if invalid.
 /// This matches glob v13's validation behavior.
 pub fn validate_options(options: &GlobOptions) -> Result<()> {
@@ -347,9 +827,127 @@ pub fn validate_options(options: &GlobOptions) -> Result<()> {
         ));
     }
 
+    if options.ignore_set.is_some()
+        && (options.ignore.is_some() || options.default_ignore.unwrap_or(false))
+    {
+        return Err(napi::Error::from_reason(
+            "ignoreSet can't be combined with ignore or defaultIgnore",
+        ));
+    }
+
+    if options.max_open_dirs == Some(0) {
+        return Err(napi::Error::from_reason("maxOpenDirs must be at least 1"));
+    }
+
+    if options.hard_depth_limit == Some(0) {
+        return Err(napi::Error::from_reason(
+            "hardDepthLimit must be at least 1",
+        ));
+    }
+
     Ok(())
 }
 
+/// Warn about patterns whose meaning the options change in a way that is
+/// easy to miss.
+///
+/// Like glob, these combinations still run: a pattern ending in `/` under
+/// `nodir` matches nothing, and a brace range under `nobrace` only matches a
+/// name containing the braces. `globSyncChecked` reports them as warnings.
+pub fn option_pattern_warnings(patterns: &[String], options: &GlobOptions) -> Vec<PatternWarning> {
+    let nodir = options.nodir.unwrap_or(false);
+    let nobrace = options.nobrace.unwrap_or(false);
+    let mut warnings = Vec::new();
+    for pattern in patterns {
+        // nodir drops every directory, and a trailing `/` only matches directories
+        if nodir && pattern.ends_with('/') {
+            warnings.push(PatternWarning::ImpossiblePattern {
+                pattern: pattern.clone(),
+                reason: "a trailing `/`, which only matches directories, but nodir excludes every directory"
+                    .to_string(),
+                suggestion: pattern.trim_end_matches('/').to_string(),
+            });
+        }
+        // nobrace keeps `{1..3}` as literal text rather than expanding it to a range
+        if nobrace && has_brace_range(pattern) {
+            warnings.push(PatternWarning::LiteralBraceRange {
+                pattern: pattern.clone(),
+                suggestion: escape_braces(pattern),
+            });
+        }
+    }
+    warnings
+}
+
+/// Escape the unescaped `{` and `}` in a pattern, which keeps them literal
+/// whether or not braces are expanded.
+fn escape_braces(pattern: &str) -> String {
+    let mut escaped = String::with_capacity(pattern.len() + 4);
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                escaped.push(c);
+                if let Some(next) = chars.next() {
+                    escaped.push(next);
+                }
+            }
+            '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Check whether a pattern contains an unescaped brace range such as
+/// `{1..3}`, `{a..e}` or `{1..10..2}`.
+fn has_brace_range(pattern: &str) -> bool {
+    let mut chars = pattern.char_indices();
+    let mut open: Option<usize> = None;
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '{' => open = Some(i + 1),
+            '}' => {
+                if let Some(start) = open.take() {
+                    if is_range_body(&pattern[start..i]) {
+                        return true;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    false
+}
+
+/// Check whether the text between braces is a numeric or alpha sequence.
+fn is_range_body(body: &str) -> bool {
+    let parts: Vec<&str> = body.split("..").collect();
+    if parts.len() != 2 && parts.len() != 3 {
+        return false;
+    }
+
+    let is_int = |s: &str| {
+        let digits = s.strip_prefix('-').unwrap_or(s);
+        !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+    };
+    let is_alpha = |s: &str| s.len() == 1 && s.bytes().all(|b| b.is_ascii_alphabetic());
+
+    let bounds_ok =
+        (is_int(parts[0]) && is_int(parts[1])) || (is_alpha(parts[0]) && is_alpha(parts[1]));
+    let step_ok = parts.len() == 2 || is_int(parts[2]);
+
+    bounds_ok && step_ok
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -500,4 +1098,77 @@ mod tests {
         };
         assert!(validate_options(&opts).is_ok());
     }
+
+    #[test]
+    fn test_validate_options_max_open_dirs() {
+        let with_max = |max: u32| GlobOptions {
+            max_open_dirs: Some(max),
+            ..Default::default()
+        };
+        assert!(validate_options(&with_max(0)).is_err());
+        assert!(validate_options(&with_max(1)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_options_hard_depth_limit() {
+        let with_limit = |limit: u32| GlobOptions {
+            hard_depth_limit: Some(limit),
+            ..Default::default()
+        };
+        assert!(validate_options(&with_limit(0)).is_err());
+        assert!(validate_options(&with_limit(1)).is_ok());
+    }
+
+    fn strings(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_nodir_pattern_warnings() {
+        let opts = GlobOptions {
+            nodir: Some(true),
+            ..Default::default()
+        };
+        let warnings = option_pattern_warnings(&strings(&["*/", "*.txt", "src/**/"]), &opts);
+        let suggestions: Vec<String> = warnings
+            .into_iter()
+            .map(|w| match w {
+                PatternWarning::ImpossiblePattern { suggestion, .. } => suggestion,
+                other => panic!("unexpected warning {other:?}"),
+            })
+            .collect();
+        assert_eq!(suggestions, vec!["*", "src/**"]);
+
+        // Trailing slashes are only dead weight under nodir
+        let opts = GlobOptions::default();
+        assert!(option_pattern_warnings(&strings(&["*/"]), &opts).is_empty());
+    }
+
+    #[test]
+    fn test_nobrace_brace_range_warnings() {
+        let opts = GlobOptions {
+            nobrace: Some(true),
+            ..Default::default()
+        };
+        let suggestions = |patterns: &[&str]| -> Vec<String> {
+            option_pattern_warnings(&strings(patterns), &opts)
+                .into_iter()
+                .map(|w| match w {
+                    PatternWarning::LiteralBraceRange { suggestion, .. } => suggestion,
+                    other => panic!("unexpected warning {other:?}"),
+                })
+                .collect()
+        };
+        assert_eq!(
+            suggestions(&["file{1..3}.txt", "{a..e}/*.js", "{1..10..2}"]),
+            ["file\\{1..3\\}.txt", "\\{a..e\\}/*.js", "\\{1..10..2\\}"]
+        );
+
+        // Comma sets and escaped ranges are legitimate literal braces
+        assert!(suggestions(&["{a,b}.txt", "\\{1..3\\}", "{1..}"]).is_empty());
+
+        // Ranges are expected when braces are expanded
+        let opts = GlobOptions::default();
+        assert!(option_pattern_warnings(&strings(&["file{1..3}.txt"]), &opts).is_empty());
+    }
 }
@@ -1,6 +1,7 @@
 use fancy_regex::Regex;
 use std::borrow::Cow;
 use std::collections::HashSet;
+use std::ffi::OsStr;
 
 /// Fast-path matching strategies for common patterns.
 /// These allow skipping expensive regex matching for simple cases.
@@ -18,6 +19,10 @@ pub enum FastPath {
     /// Contains the exact filename to match
     LiteralName(String),
 
+    /// Pattern is `**/<literal>` (e.g., `**/package.json`) - compare basename only
+    /// Contains the exact filename to match at any depth
+    RecursiveLiteralName(String),
+
     /// Pattern is `**/*.ext` - recursive extension matching
     /// Contains the extension to match (without the dot)
     RecursiveExtension(String),
@@ -26,8 +31,8 @@ pub enum FastPath {
     /// Contains the set of valid extensions (without dots)
     RecursiveExtensionSet(HashSet<String>),
 
-    /// Pattern ends with a suffix (e.g., `*.test.js`, `**/*.spec.ts`)
-    /// Contains the suffix to match (with the dot, e.g., ".test.js")
+    /// Pattern ends with a suffix (e.g., `*.test.js`, `**/*.spec.ts`, `*rc`)
+    /// Contains the suffix to match (e.g., ".test.js")
     /// and whether it's recursive (matches at any depth)
     SuffixMatch { suffix: String, recursive: bool },
 
@@ -35,6 +40,10 @@ pub enum FastPath {
     /// Contains the prefix to match (without the wildcard)
     PrefixMatch(String),
 
+    /// Pattern is a simple substring match (e.g., `*test*`, `*.min.*`)
+    /// Contains the substring to find in the filename (without the wildcards)
+    Contains(String),
+
     /// Pattern requires full regex matching (complex patterns)
     None,
 }
@@ -44,6 +53,22 @@ impl FastPath {
     pub fn is_fast(&self) -> bool {
         !matches!(self, FastPath::None)
     }
+
+    /// Name of the fast path, or `None` when full regex matching is required
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            FastPath::ExtensionOnly(_) => Some("ExtensionOnly"),
+            FastPath::ExtensionSet(_) => Some("ExtensionSet"),
+            FastPath::LiteralName(_) => Some("LiteralName"),
+            FastPath::RecursiveLiteralName(_) => Some("RecursiveLiteralName"),
+            FastPath::RecursiveExtension(_) => Some("RecursiveExtension"),
+            FastPath::RecursiveExtensionSet(_) => Some("RecursiveExtensionSet"),
+            FastPath::SuffixMatch { .. } => Some("SuffixMatch"),
+            FastPath::PrefixMatch(_) => Some("PrefixMatch"),
+            FastPath::Contains(_) => Some("Contains"),
+            FastPath::None => None,
+        }
+    }
 }
 
 /// Options for pattern parsing
@@ -51,6 +76,8 @@ impl FastPath {
 pub struct PatternOptions {
     /// Disable extglob patterns (e.g., +(a|b), *(a|b), etc.)
     pub noext: bool,
+    /// Treat `!(` as literal text while keeping the other extglob types
+    pub no_negation: bool,
     /// On Windows, treat backslashes as path separators instead of escape characters
     pub windows_paths_no_escape: bool,
     /// Platform for path handling (win32, darwin, linux)
@@ -59,6 +86,44 @@ pub struct PatternOptions {
     pub nocase: bool,
     /// Treat braces as literal characters (disables brace expansion)
     pub nobrace: bool,
+    /// Segment separator for keys that aren't paths (`None` = `/`). With another
+    /// separator, `/` is an ordinary character and `./` prefixes, absolute roots
+    /// and fast paths don't apply.
+    pub separator: Option<char>,
+}
+
+/// Fold a string for `nocase` comparison.
+///
+/// Each character is mapped to uppercase and back to lowercase, which equates
+/// all case variants of a letter (`Σ`/`σ`/`ς`, `K`/`k`/`K` (Kelvin), `ẞ`/`ß`).
+/// Mappings that would change the number of characters are skipped, so `?`
+/// always matches one character: `ß` never equals `ss` and `İ` only matches
+/// itself. Without a locale, the dotless `ı` folds to `i`. Every nocase
+/// comparison (fast paths, regexes, directory pruning) goes through this.
+pub fn fold_case(s: &str) -> Cow<'_, str> {
+    if s.is_ascii() {
+        return if s.bytes().any(|b| b.is_ascii_uppercase()) {
+            Cow::Owned(s.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(s)
+        };
+    }
+    Cow::Owned(s.chars().map(fold_char).collect())
+}
+
+/// Fold one character (see [`fold_case`]).
+fn fold_char(c: char) -> char {
+    fn single(mut mapped: impl ExactSizeIterator<Item = char>) -> Option<char> {
+        if mapped.len() == 1 {
+            mapped.next()
+        } else {
+            None
+        }
+    }
+    let upper = single(c.to_uppercase()).unwrap_or(c);
+    single(upper.to_lowercase())
+        .or_else(|| single(c.to_lowercase()))
+        .unwrap_or(c)
 }
 
 /// Represents a segment of a parsed glob pattern.
@@ -132,7 +197,7 @@ impl PatternPart {
         match self {
             PatternPart::Literal(s) => {
                 if nocase {
-                    s.eq_ignore_ascii_case(segment)
+                    fold_case(s) == fold_case(segment)
                 } else {
                     s == segment
                 }
@@ -141,7 +206,7 @@ impl PatternPart {
                 // Try simple match first (string operations are faster than regex)
                 if let Some(simple) = simple_match {
                     let seg = if nocase {
-                        segment.to_lowercase()
+                        fold_case(segment).into_owned()
                     } else {
                         segment.to_string()
                     };
@@ -149,7 +214,7 @@ impl PatternPart {
                         SimpleMatch::Any => !seg.contains('/'),
                         SimpleMatch::Prefix(prefix) => {
                             let prefix = if nocase {
-                                prefix.to_lowercase()
+                                fold_case(prefix).into_owned()
                             } else {
                                 prefix.clone()
                             };
@@ -157,7 +222,7 @@ impl PatternPart {
                         }
                         SimpleMatch::Suffix(suffix) => {
                             let suffix = if nocase {
-                                suffix.to_lowercase()
+                                fold_case(suffix).into_owned()
                             } else {
                                 suffix.clone()
                             };
@@ -165,12 +230,12 @@ impl PatternPart {
                         }
                         SimpleMatch::PrefixSuffix(prefix, suffix) => {
                             let prefix = if nocase {
-                                prefix.to_lowercase()
+                                fold_case(prefix).into_owned()
                             } else {
                                 prefix.clone()
                             };
                             let suffix = if nocase {
-                                suffix.to_lowercase()
+                                fold_case(suffix).into_owned()
                             } else {
                                 suffix.clone()
                             };
@@ -183,7 +248,7 @@ impl PatternPart {
                 } else {
                     // Fall back to regex for complex patterns
                     let seg = if nocase {
-                        segment.to_lowercase()
+                        fold_case(segment).into_owned()
                     } else {
                         segment.to_string()
                     };
@@ -229,10 +294,43 @@ pub struct Pattern {
     platform: String,
     /// Whether to perform case-insensitive matching
     nocase: bool,
+    /// Character between segments (`/` unless matching custom keys)
+    separator: char,
     /// Whether this pattern ends with / (requires directory match)
     requires_dir: bool,
     /// Fast-path optimization for this pattern (if applicable)
     fast_path: FastPath,
+    /// Per-pattern dot override (None = defer to the glob-wide `dot` option)
+    dot: Option<bool>,
+    /// Match against the final path segment only (see `set_basename_only`)
+    basename_only: bool,
+    /// Why the full regex failed to compile (the pattern then matches nothing)
+    compile_error: Option<String>,
+}
+
+/// First of the 256 private-use characters that stand in for the bytes of a
+/// path that aren't valid UTF-8 (see `Pattern::matches_os`).
+#[cfg(unix)]
+const INVALID_BYTE_BASE: u32 = 0xF700;
+
+/// Decode path bytes for matching, replacing each byte that isn't part of a
+/// valid UTF-8 sequence with its own placeholder character.
+#[cfg(unix)]
+fn decode_path_bytes(bytes: &[u8]) -> Cow<'_, str> {
+    if let Ok(s) = std::str::from_utf8(bytes) {
+        return Cow::Borrowed(s);
+    }
+    let mut decoded = String::with_capacity(bytes.len() + 8);
+    for chunk in bytes.utf8_chunks() {
+        decoded.push_str(chunk.valid());
+        decoded.extend(
+            chunk
+                .invalid()
+                .iter()
+                .filter_map(|&b| char::from_u32(INVALID_BYTE_BASE + u32::from(b))),
+        );
+    }
+    Cow::Owned(decoded)
 }
 
 // Escape tokens for brace expansion (avoid collisions with actual content)
@@ -272,10 +370,13 @@ impl Pattern {
             .clone()
             .unwrap_or_else(|| std::env::consts::OS.to_string());
         let is_windows = platform == "win32" || platform == "windows";
+        let separator = options.separator.unwrap_or('/');
+        // Keys split on a custom separator aren't paths: no `./` or roots to handle
+        let is_path = separator == '/';
 
         // If windowsPathsNoEscape is true, convert backslashes to forward slashes
         // before any other processing
-        let processed_pattern = if options.windows_paths_no_escape {
+        let processed_pattern = if options.windows_paths_no_escape && is_path {
             pattern.replace('\\', "/")
         } else {
             pattern.to_string()
@@ -283,47 +384,72 @@ impl Pattern {
 
         // Preprocess to strip ./ prefix - this must happen before parsing into parts
         // so that parts don't include the leading "." segment
-        let preprocessed = preprocess_pattern(&processed_pattern);
+        let preprocessed = if is_path {
+            preprocess_pattern(&processed_pattern)
+        } else {
+            Cow::Borrowed(processed_pattern.as_str())
+        };
 
         // Check if pattern ends with / (requires directory match)
         // Strip the trailing slash for matching purposes
-        let requires_dir = preprocessed.ends_with('/');
+        let requires_dir = preprocessed.ends_with(separator);
         let pattern_for_matching: Cow<'_, str> = if requires_dir {
-            Cow::Owned(preprocessed.trim_end_matches('/').to_string())
+            Cow::Owned(preprocessed.trim_end_matches(separator).to_string())
         } else {
             preprocessed
         };
 
+        // The parent of an absolute root is the root itself: `/../foo` is `/foo`
+        let clamped = clamp_parent_of_root(&pattern_for_matching, is_windows).filter(|_| is_path);
+        let pattern_for_matching = match clamped {
+            Some(clamped) => Cow::Owned(clamped),
+            None => pattern_for_matching,
+        };
+
         // Parse pattern into parts
         let (glob_parts, parts, root, is_absolute, is_drive, is_unc) = parse_pattern_parts(
             &pattern_for_matching,
             options.noext,
+            options.no_negation,
             is_windows,
             options.nocase,
+            separator,
         );
 
-        // Compile the full regex
-        let regex = pattern_to_regex(
+        // Compile the full regex. A pattern too large for the regex engine matches
+        // nothing and records the error so callers can report it.
+        let (regex, compile_error) = match pattern_to_regex(
             &pattern_for_matching,
             options.noext,
+            options.no_negation,
             options.windows_paths_no_escape,
             options.nocase,
-        );
+            separator,
+        ) {
+            Ok(regex) => (regex, None),
+            Err(e) => (Regex::new("^$").unwrap(), Some(e)),
+        };
 
         // Check for magic characters
-        let has_magic = has_magic_in_pattern(
+        let has_magic = has_magic_with_extglobs(
             &pattern_for_matching,
             options.noext,
+            options.no_negation,
             options.windows_paths_no_escape,
         );
 
-        // Compute fast-path optimization
-        let fast_path = detect_fast_path(
-            &pattern_for_matching,
-            &parts,
-            options.nocase,
-            options.nobrace,
-        );
+        // Compute fast-path optimization (the fast paths assume `/` separators)
+        let fast_path = if is_path {
+            detect_fast_path(
+                &pattern_for_matching,
+                &parts,
+                options.nocase,
+                options.nobrace,
+                options.windows_paths_no_escape,
+            )
+        } else {
+            FastPath::None
+        };
 
         Self {
             raw: pattern.to_string(),
@@ -339,29 +465,74 @@ impl Pattern {
             windows_paths_no_escape: options.windows_paths_no_escape,
             platform,
             nocase: options.nocase,
+            separator,
             requires_dir,
             fast_path,
+            dot: None,
+            basename_only: false,
+            compile_error,
         }
     }
 
+    /// The regex compilation error for this pattern, if any.
+    ///
+    /// Such a pattern never matches; `Glob` turns this into an error instead.
+    pub fn compile_error(&self) -> Option<&str> {
+        self.compile_error.as_deref()
+    }
+
     /// Test if this pattern matches the given path.
     /// Path should use forward slashes and be relative.
     pub fn matches(&self, path: &str) -> bool {
+        let path = self.match_target(path);
+        if self.rejects_dotfiles(path) || self.rejects_dot_segments(path) {
+            return false;
+        }
+
         // For case-insensitive matching, we lowercase the path
         // The regex is already compiled with (?i) flag when nocase is true
         if self.nocase {
-            self.regex.is_match(&path.to_lowercase()).unwrap_or(false)
+            self.regex.is_match(&fold_case(path)).unwrap_or(false)
         } else {
             self.regex.is_match(path).unwrap_or(false)
         }
     }
 
+    /// Test if this pattern matches an OS path that may not be valid UTF-8.
+    ///
+    /// On Unix the raw bytes are matched: each byte of an invalid UTF-8 sequence
+    /// becomes one placeholder character, so `*` and `?` match it (one `?` per
+    /// byte) while literal pattern text never does. Elsewhere the path is
+    /// converted lossily, and on Windows `\` separators become `/`.
+    pub fn matches_os(&self, path: &OsStr) -> bool {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            self.matches(&decode_path_bytes(path.as_bytes()))
+        }
+
+        #[cfg(not(unix))]
+        {
+            let path = path.to_string_lossy();
+            if cfg!(windows) {
+                self.matches(&path.replace('\\', "/"))
+            } else {
+                self.matches(&path)
+            }
+        }
+    }
+
     /// Get the raw pattern string.
     #[allow(dead_code)]
     pub fn raw(&self) -> &str {
         &self.raw
     }
 
+    /// Get the source of the compiled full-path regex.
+    pub fn regex_source(&self) -> &str {
+        self.regex.as_str()
+    }
+
     /// Check if the pattern contains magic glob characters.
     /// Takes into account escaped characters.
     #[allow(dead_code)]
@@ -398,6 +569,93 @@ impl Pattern {
         self.requires_dir
     }
 
+    /// Check if the pattern performs case-insensitive matching.
+    pub fn nocase(&self) -> bool {
+        self.nocase
+    }
+
+    /// Get the per-pattern dot override, if any.
+    pub fn dot(&self) -> Option<bool> {
+        self.dot
+    }
+
+    /// Override the glob-wide `dot` option for this pattern.
+    ///
+    /// With `Some(false)`, `matches` and `matches_fast` reject paths containing dotfile
+    /// segments the pattern does not name explicitly. This lets one pattern exclude
+    /// dotfiles while another pattern in the same glob includes them.
+    pub fn set_dot(&mut self, dot: Option<bool>) {
+        self.dot = dot;
+    }
+
+    /// Drop the fast path so `matches_fast` always defers to the regex.
+    pub fn disable_fast_path(&mut self) {
+        self.fast_path = FastPath::None;
+    }
+
+    /// Whether only the final path segment is matched.
+    pub fn basename_only(&self) -> bool {
+        self.basename_only
+    }
+
+    /// Match only the final segment of each path, ignoring its directories.
+    ///
+    /// Unlike `for_match_base`, this applies to patterns containing `/` too, but
+    /// such a pattern can then only match a single segment (e.g. `**/foo.ts`).
+    pub fn set_basename_only(&mut self, basename_only: bool) {
+        self.basename_only = basename_only;
+    }
+
+    /// The part of `path` this pattern is matched against.
+    #[inline]
+    fn match_target<'a>(&self, path: &'a str) -> &'a str {
+        if self.basename_only {
+            path.rsplit(self.separator).next().unwrap_or(path)
+        } else {
+            path
+        }
+    }
+
+    /// Check if the path has a `.` or `..` segment that no literal `.` or `..`
+    /// segment of the pattern accounts for. A path of just `.` stands for the
+    /// cwd itself and is left to the pattern.
+    ///
+    /// Like minimatch, wildcards and globstars never match these segments, even
+    /// with `dot`; only the same literal does. Literal segments always consume a
+    /// segment of their own, so when the counts agree no wildcard matched one.
+    #[inline]
+    fn rejects_dot_segments(&self, path: &str) -> bool {
+        if path == "." || !path.split(self.separator).any(|seg| seg.starts_with('.')) {
+            return false;
+        }
+        let count = |segments: &mut dyn Iterator<Item = &str>| {
+            segments.fold((0, 0), |(dots, dot_dots), seg| match seg {
+                "." => (dots + 1, dot_dots),
+                ".." => (dots, dot_dots + 1),
+                _ => (dots, dot_dots),
+            })
+        };
+        let in_path = count(&mut path.split(self.separator));
+        if in_path == (0, 0) {
+            return false;
+        }
+        let in_pattern = count(&mut self.parts.iter().filter_map(|part| match part {
+            PatternPart::Literal(literal) => Some(literal.as_str()),
+            _ => None,
+        }));
+        in_path != in_pattern
+    }
+
+    /// Check if a `dot: false` override excludes the given path.
+    #[inline]
+    fn rejects_dotfiles(&self, path: &str) -> bool {
+        self.dot == Some(false)
+            && path
+                .split(self.separator)
+                .any(|seg| seg.starts_with('.') && seg != "." && seg != "..")
+            && !self.allows_dotfile(path)
+    }
+
     /// Get the fast-path optimization for this pattern.
     ///
     /// Returns the type of fast-path matching that can be used, or `FastPath::None`
@@ -442,9 +700,25 @@ impl Pattern {
     pub fn matches_fast(&self, path: &str) -> Option<bool> {
         use crate::simd;
 
+        let path = self.match_target(path);
+        if self.rejects_dotfiles(path) || self.rejects_dot_segments(path) {
+            return Some(false);
+        }
+
+        // The fast paths compare bytes case-insensitively, which is only valid for ASCII
+        if self.nocase && !path.is_ascii() {
+            return None;
+        }
+
         let path_bytes = path.as_bytes();
 
         match &self.fast_path {
+            // Without a globstar, `*` and literals only match root-level files
+            FastPath::ExtensionOnly(_) | FastPath::ExtensionSet(_) | FastPath::LiteralName(_)
+                if simd::has_separator(path_bytes) =>
+            {
+                Some(false)
+            }
             FastPath::ExtensionOnly(ext) => {
                 // Use SIMD-optimized extension checking
                 let ext_bytes = ext.as_bytes();
@@ -493,6 +767,22 @@ impl Pattern {
                     Some(simd::bytes_equal(file_name, name_bytes))
                 }
             }
+            FastPath::RecursiveLiteralName(name) => {
+                // Globstar matches any depth, so only the basename matters
+                let filename_start = simd::find_last_separator(path_bytes)
+                    .map(|i| i + 1)
+                    .unwrap_or(0);
+                let file_name = &path_bytes[filename_start..];
+
+                if self.nocase {
+                    Some(simd::eq_ignore_ascii_case_fast(
+                        std::str::from_utf8(file_name).unwrap_or(""),
+                        name,
+                    ))
+                } else {
+                    Some(simd::bytes_equal(file_name, name.as_bytes()))
+                }
+            }
             FastPath::RecursiveExtension(ext) => {
                 // Use SIMD-optimized extension checking
                 let ext_bytes = ext.as_bytes();
@@ -581,6 +871,25 @@ impl Pattern {
                     Some(simd::starts_with_fast(path_bytes, prefix_bytes))
                 }
             }
+            FastPath::Contains(needle) => {
+                // This only applies to root-level files (no path separators)
+                if simd::has_separator(path_bytes) {
+                    return Some(false);
+                }
+
+                let needle_bytes = needle.as_bytes();
+                if needle_bytes.len() > path_bytes.len() {
+                    Some(false)
+                } else if self.nocase {
+                    Some(
+                        path_bytes
+                            .windows(needle_bytes.len())
+                            .any(|w| w.eq_ignore_ascii_case(needle_bytes)),
+                    )
+                } else {
+                    Some(path.contains(needle.as_str()))
+                }
+            }
             FastPath::None => None, // Fall back to regex
         }
     }
@@ -693,14 +1002,24 @@ impl Pattern {
                 } else {
                     None
                 }
+            } else if self.root == "/" {
+                // Keep the root so absolute paths don't resolve against the cwd
+                Some(format!("/{}", self.unescape_literal(&path_parts.join("/"))))
             } else {
-                Some(path_parts.join("/"))
+                Some(self.unescape_literal(&path_parts.join("/")))
             }
         } else {
             None
         }
     }
 
+    /// Turn a literal pattern segment into the filesystem name it matches by
+    /// dropping backslash escapes (`\\\\` -> `\\`, `\\*` -> `*`). With
+    /// windowsPathsNoEscape there are no escapes, so the text is returned as-is.
+    fn unescape_literal(&self, literal: &str) -> String {
+        unescape_literal(literal, self.windows_paths_no_escape)
+    }
+
     /// Get the glob string representation.
     #[allow(dead_code)]
     pub fn glob_string(&self) -> String {
@@ -721,15 +1040,17 @@ impl Pattern {
     /// If the pattern has no path separators, it's treated as `**/<pattern>`.
     pub fn for_match_base(pattern: &str, options: PatternOptions) -> Self {
         // Check if pattern contains path separators
-        let has_slash = pattern.contains('/');
-        let has_backslash = !options.windows_paths_no_escape && pattern.contains('\\');
+        let separator = options.separator.unwrap_or('/');
+        let has_slash = pattern.contains(separator);
+        let has_backslash =
+            !options.windows_paths_no_escape && separator == '/' && pattern.contains('\\');
 
         if has_slash || has_backslash {
             // Pattern has path components, use as-is
             Self::with_pattern_options(pattern, options)
         } else {
             // No path separators - prepend **/ for basename matching
-            let new_pattern = format!("**/{pattern}");
+            let new_pattern = format!("**{separator}{pattern}");
             Self::with_pattern_options(&new_pattern, options)
         }
     }
@@ -824,7 +1145,7 @@ impl Pattern {
         if prefix_parts.is_empty() {
             None
         } else {
-            Some(prefix_parts.join("/"))
+            Some(self.unescape_literal(&prefix_parts.join("/")))
         }
     }
 
@@ -857,12 +1178,30 @@ impl Pattern {
             return true;
         }
 
+        // Absolute roots (`/`, `C:/`, `//server/share/`) are a single pattern part
+        // that spans several path segments, so match the root as a unit first
+        let (dir_path, parts) = match self.parts.first() {
+            Some(PatternPart::Literal(root)) if !self.root.is_empty() && root == &self.root => {
+                let dir_root = dir_path.get(..root.len()).unwrap_or("");
+                let root_matches = if self.nocase {
+                    dir_root.eq_ignore_ascii_case(root)
+                } else {
+                    dir_root == root
+                };
+                if root_matches {
+                    (&dir_path[root.len()..], &self.parts[1..])
+                } else {
+                    (dir_path, &self.parts[..])
+                }
+            }
+            _ => (dir_path, &self.parts[..]),
+        };
+
         // Split the directory path into segments
         let dir_segments: Vec<&str> = dir_path.split('/').filter(|s| !s.is_empty()).collect();
 
         // Get pattern parts (skip leading / for absolute paths)
-        let pattern_parts: Vec<&PatternPart> = self
-            .parts
+        let pattern_parts: Vec<&PatternPart> = parts
             .iter()
             .filter(|p| match p {
                 PatternPart::Literal(s) => s != "/",
@@ -934,7 +1273,7 @@ impl Pattern {
             PatternPart::Literal(lit) => {
                 // For case-insensitive matching, compare lowercase
                 let matches = if self.nocase {
-                    lit.to_lowercase() == dir_segment.to_lowercase()
+                    fold_case(lit) == fold_case(dir_segment)
                 } else {
                     lit == dir_segment
                 };
@@ -955,7 +1294,7 @@ impl Pattern {
                 // Try simple string matching first (faster than regex)
                 let matches = if let Some(simple) = simple_match {
                     let seg = if self.nocase {
-                        dir_segment.to_lowercase()
+                        fold_case(dir_segment).into_owned()
                     } else {
                         dir_segment.to_string()
                     };
@@ -963,7 +1302,7 @@ impl Pattern {
                         SimpleMatch::Any => !seg.contains('/'),
                         SimpleMatch::Prefix(prefix) => {
                             let prefix = if self.nocase {
-                                prefix.to_lowercase()
+                                fold_case(prefix).into_owned()
                             } else {
                                 prefix.clone()
                             };
@@ -971,7 +1310,7 @@ impl Pattern {
                         }
                         SimpleMatch::Suffix(suffix) => {
                             let suffix = if self.nocase {
-                                suffix.to_lowercase()
+                                fold_case(suffix).into_owned()
                             } else {
                                 suffix.clone()
                             };
@@ -979,12 +1318,12 @@ impl Pattern {
                         }
                         SimpleMatch::PrefixSuffix(prefix, suffix) => {
                             let prefix = if self.nocase {
-                                prefix.to_lowercase()
+                                fold_case(prefix).into_owned()
                             } else {
                                 prefix.clone()
                             };
                             let suffix = if self.nocase {
-                                suffix.to_lowercase()
+                                fold_case(suffix).into_owned()
                             } else {
                                 suffix.clone()
                             };
@@ -997,7 +1336,7 @@ impl Pattern {
                 } else {
                     // Fall back to regex for complex patterns
                     if self.nocase {
-                        regex.is_match(&dir_segment.to_lowercase()).unwrap_or(false)
+                        regex.is_match(&fold_case(dir_segment)).unwrap_or(false)
                     } else {
                         regex.is_match(dir_segment).unwrap_or(false)
                     }
@@ -1026,11 +1365,21 @@ impl Pattern {
     /// This is used to determine if a path with dotfile segments should be matched
     /// when `dot: false`.
     pub fn allows_dotfile(&self, path: &str) -> bool {
-        let path_parts: Vec<&str> = path.split('/').collect();
+        let path_parts: Vec<&str> = path.split(self.separator).collect();
 
         // Get preprocessed pattern parts (without ./ prefix if any)
-        let processed_raw = preprocess_pattern(&self.raw);
-        let pattern_parts: Vec<&str> = processed_raw.split('/').collect();
+        let mut processed_raw = if self.separator == '/' {
+            preprocess_pattern(&self.raw)
+        } else {
+            Cow::Borrowed(self.raw.as_str())
+        };
+        if self.is_absolute {
+            let is_windows = self.platform == "win32" || self.platform == "windows";
+            if let Some(clamped) = clamp_parent_of_root(&processed_raw, is_windows) {
+                processed_raw = Cow::Owned(clamped);
+            }
+        }
+        let pattern_parts: Vec<&str> = processed_raw.split(self.separator).collect();
 
         // Check each dotfile segment in the path
         for (i, path_part) in path_parts.iter().enumerate() {
@@ -1108,6 +1457,43 @@ impl Pattern {
             }
         }
 
+        // Check for an extglob whose alternatives can produce a leading dot,
+        // e.g. @(.env|.config) or ?(.)hidden. !(...) never matches dotfiles.
+        let bytes = part.as_bytes();
+        if bytes.len() > 1 && matches!(bytes[0], b'?' | b'*' | b'+' | b'@') && bytes[1] == b'(' {
+            let mut depth = 0;
+            let mut start = 2;
+            let mut alternatives = Vec::new();
+            let mut escaped = false;
+            for (i, b) in bytes.iter().enumerate().skip(1) {
+                if escaped {
+                    escaped = false;
+                    continue;
+                }
+                match b {
+                    b'\\' => escaped = true,
+                    b'(' => depth += 1,
+                    b'|' if depth == 1 => {
+                        alternatives.push(&part[start..i]);
+                        start = i + 1;
+                    }
+                    b')' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            alternatives.push(&part[start..i]);
+                            let rest = &part[i + 1..];
+                            // ?(...) and *(...) can match nothing, leaving the rest
+                            let optional = matches!(bytes[0], b'?' | b'*');
+                            return alternatives.iter().any(|alt| {
+                                Self::part_explicitly_matches_dot(&format!("{alt}{rest}"))
+                            }) || (optional && Self::part_explicitly_matches_dot(rest));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
         false
     }
 }
@@ -1117,17 +1503,19 @@ impl Pattern {
 fn parse_pattern_parts(
     pattern: &str,
     noext: bool,
+    no_negation: bool,
     is_windows: bool,
     nocase: bool,
+    separator: char,
 ) -> (Vec<String>, Vec<PatternPart>, String, bool, bool, bool) {
-    let mut glob_parts: Vec<String> = pattern.split('/').map(String::from).collect();
+    let mut glob_parts: Vec<String> = pattern.split(separator).map(String::from).collect();
     let mut root = String::new();
     let mut is_absolute = false;
     let mut is_drive = false;
     let mut is_unc = false;
 
     // Check for absolute paths
-    if !glob_parts.is_empty() {
+    if !glob_parts.is_empty() && separator == '/' {
         // Check for UNC path: //server/share or //./device or //?/device
         if glob_parts.len() >= 4
             && glob_parts[0].is_empty()
@@ -1190,9 +1578,9 @@ fn parse_pattern_parts(
     for part in &glob_parts {
         if part == "**" {
             pattern_parts.push(PatternPart::Globstar);
-        } else if has_magic_in_pattern(part, noext, false) {
+        } else if has_magic_with_extglobs(part, noext, no_negation, false) {
             // Create regex for this part
-            let part_regex = segment_to_regex(part, noext, nocase);
+            let part_regex = segment_to_regex(part, noext, no_negation, nocase, separator);
             // Detect if this is a simple pattern that can use string ops instead of regex
             let simple_match = detect_simple_match(part, noext);
             pattern_parts.push(PatternPart::Magic(part.clone(), part_regex, simple_match));
@@ -1211,18 +1599,80 @@ fn parse_pattern_parts(
     )
 }
 
+/// Byte length of the absolute root a pattern starts with: `/`, and on Windows
+/// also a drive (`C:/`) or UNC root (`//server/share/`). Zero for relative patterns.
+fn absolute_root_len(pattern: &str, is_windows: bool) -> usize {
+    if is_windows {
+        if let Some(rest) = pattern.strip_prefix("//") {
+            let mut parts = rest.splitn(3, '/');
+            if let (Some(server), Some(share), Some(_)) = (parts.next(), parts.next(), parts.next())
+            {
+                if !server.is_empty() && !share.is_empty() {
+                    return 2 + server.len() + 1 + share.len() + 1;
+                }
+            }
+        }
+        let bytes = pattern.as_bytes();
+        if bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && &bytes[1..3] == b":/" {
+            return 3;
+        }
+    }
+    usize::from(pattern.starts_with('/'))
+}
+
+/// Drop `..` segments directly after an absolute root, as the OS does when
+/// resolving paths: the parent of `/` (or `C:/`, or a UNC share) is itself.
+/// `..` elsewhere is kept as written, like glob does.
+///
+/// Returns `None` when there is nothing to drop.
+fn clamp_parent_of_root(pattern: &str, is_windows: bool) -> Option<String> {
+    let root_len = absolute_root_len(pattern, is_windows);
+    if root_len == 0 {
+        return None;
+    }
+
+    let mut rest = &pattern[root_len..];
+    loop {
+        if let Some(after) = rest.strip_prefix("../") {
+            rest = after.trim_start_matches('/');
+        } else if rest == ".." {
+            rest = "";
+        } else {
+            break;
+        }
+    }
+
+    if rest.len() == pattern.len() - root_len {
+        None
+    } else {
+        Some(format!("{}{rest}", &pattern[..root_len]))
+    }
+}
+
 /// Convert a single path segment to a regex (not a full pattern).
-fn segment_to_regex(segment: &str, noext: bool, nocase: bool) -> Regex {
+fn segment_to_regex(
+    segment: &str,
+    noext: bool,
+    no_negation: bool,
+    nocase: bool,
+    separator: char,
+) -> Regex {
     let mut regex_str = String::with_capacity(segment.len() * 2);
-    // Add case-insensitive flag if nocase is true
-    if nocase {
+    // With nocase, candidates are folded before matching, so the pattern is too;
+    // (?i) still lets classes like [[:upper:]] match the folded text
+    let segment = if nocase {
         regex_str.push_str("(?i)");
-    }
+        fold_case(segment)
+    } else {
+        Cow::Borrowed(segment)
+    };
     regex_str.push('^');
 
+    let not_separator = format!("[^{}]", escape_for_bracket(separator));
     let chars: Vec<char> = segment.chars().collect();
     let len = chars.len();
     let mut i = 0;
+    let mut negations: Vec<(usize, usize)> = Vec::new();
 
     while i < len {
         let c = chars[i];
@@ -1246,7 +1696,10 @@ fn segment_to_regex(segment: &str, noext: bool, nocase: bool) -> Regex {
 
         // Check for extglob patterns
         if !noext && EXTGLOB_TYPES.contains(&c) && i + 1 < len && chars[i + 1] == '(' {
-            if let Some((extglob_regex, new_pos)) = parse_extglob(&chars, i, noext) {
+            if let Some((extglob_regex, new_pos)) =
+                parse_extglob(&chars, i, noext, no_negation, separator)
+            {
+                track_negated_extglob(c, &extglob_regex, &regex_str, &mut negations, separator);
                 regex_str.push_str(&extglob_regex);
                 i = new_pos;
                 continue;
@@ -1255,13 +1708,14 @@ fn segment_to_regex(segment: &str, noext: bool, nocase: bool) -> Regex {
 
         match c {
             '*' => {
-                regex_str.push_str("[^/]*");
+                regex_str.push_str(&not_separator);
+                regex_str.push('*');
             }
             '?' => {
-                regex_str.push_str("[^/]");
+                regex_str.push_str(&not_separator);
             }
             '[' => {
-                if let Some((class_regex, new_pos)) = parse_character_class(&chars, i) {
+                if let Some((class_regex, new_pos)) = parse_character_class(&chars, i, separator) {
                     regex_str.push_str(&class_regex);
                     i = new_pos;
                     continue;
@@ -1280,11 +1734,52 @@ fn segment_to_regex(segment: &str, noext: bool, nocase: bool) -> Regex {
         i += 1;
     }
 
+    close_negated_extglobs(&mut regex_str, &mut negations);
     regex_str.push('$');
 
+    // The full-pattern regex contains every segment, so a segment too large to
+    // compile is reported through `Pattern::compile_error` instead
     Regex::new(&regex_str).unwrap_or_else(|_| Regex::new("^$").unwrap())
 }
 
+/// Lookahead tail that ends the segment tested by a `!(...)` extglob's regex.
+fn negation_tail(separator: char) -> String {
+    format!("(?:$|{})", escape_regex_char(separator))
+}
+
+/// Remember where a top-level `!(...)` extglob's lookahead ends, so the regex for
+/// the rest of its segment can be inserted there once it is known.
+///
+/// `!(test).js` must reject `test.js`: the lookahead has to test `test\.js`, not
+/// just `test`. Entries are `(insertion index, start of the rest of the segment)`.
+fn track_negated_extglob(
+    ext_type: char,
+    extglob_regex: &str,
+    regex_str: &str,
+    negations: &mut Vec<(usize, usize)>,
+    separator: char,
+) {
+    if ext_type != '!' {
+        return;
+    }
+    if let Some(tail) = extglob_regex.rfind(&negation_tail(separator)) {
+        negations.push((
+            regex_str.len() + tail,
+            regex_str.len() + extglob_regex.len(),
+        ));
+    }
+}
+
+/// At the end of a segment, copy the regex for the rest of the segment into the
+/// lookahead of each `!(...)` extglob recorded by `track_negated_extglob`.
+fn close_negated_extglobs(regex_str: &mut String, negations: &mut Vec<(usize, usize)>) {
+    // Later extglobs first, so earlier indices stay valid
+    while let Some((at, rest_start)) = negations.pop() {
+        let rest = regex_str[rest_start..].to_string();
+        regex_str.insert_str(at, &rest);
+    }
+}
+
 /// Detect if a pattern segment can use simple string matching instead of regex.
 ///
 /// This is an optimization for directory pruning. Common patterns like:
@@ -1361,6 +1856,16 @@ fn detect_simple_match(segment: &str, noext: bool) -> Option<SimpleMatch> {
 /// - `//?/` and `//./` prefixes contain `?` and `.` which are NOT magic
 /// - `//server/share/` UNC roots are not magic
 pub fn has_magic_in_pattern(pattern: &str, noext: bool, windows_paths_no_escape: bool) -> bool {
+    has_magic_with_extglobs(pattern, noext, false, windows_paths_no_escape)
+}
+
+/// [`has_magic_in_pattern`] where `no_negation` makes `!(` literal text.
+fn has_magic_with_extglobs(
+    pattern: &str,
+    noext: bool,
+    no_negation: bool,
+    windows_paths_no_escape: bool,
+) -> bool {
     let chars: Vec<char> = pattern.chars().collect();
     let mut i = 0;
 
@@ -1410,7 +1915,10 @@ pub fn has_magic_in_pattern(pattern: &str, noext: bool, windows_paths_no_escape:
             '*' | '?' | '[' => return true,
             // Check for extglob patterns when followed by (
             // All extglob types: +, @, !, *, ? - but * and ? are already caught above
-            '+' | '@' | '!' if !noext && i + 1 < chars.len() && chars[i + 1] == '(' => {
+            '+' | '@' if !noext && i + 1 < chars.len() && chars[i + 1] == '(' => {
+                return true;
+            }
+            '!' if !noext && !no_negation && i + 1 < chars.len() && chars[i + 1] == '(' => {
                 return true;
             }
             _ => {}
@@ -1433,17 +1941,112 @@ fn has_extglob(pattern: &str) -> bool {
     false
 }
 
+/// Collapse runs of `/` into a single `/`, like minimatch does, so a pattern or
+/// path built by concatenation (`src//lib///*.ts`) means `src/lib/*.ts`.
+///
+/// A leading `//` is kept since it starts a UNC path (`//server/share`).
+pub fn collapse_slashes(path: &str) -> Cow<'_, str> {
+    let start: usize = if path.starts_with("//") { 2 } else { 0 };
+    // From the last kept leading slash on, so `///x` still collapses to `//x`
+    if !path.as_bytes()[start.saturating_sub(1)..]
+        .windows(2)
+        .any(|w| w == b"//")
+    {
+        return Cow::Borrowed(path);
+    }
+
+    let mut collapsed = String::with_capacity(path.len());
+    collapsed.push_str(&path[..start]);
+    for c in path[start..].chars() {
+        if c == '/' && collapsed.ends_with('/') {
+            continue;
+        }
+        collapsed.push(c);
+    }
+    Cow::Owned(collapsed)
+}
+
+/// Drop trailing dots and spaces from each path segment, as Windows does for
+/// file names: `foo.` and `foo ` both name the file `foo`.
+///
+/// Segments made only of dots or spaces (`.`, `..`) are kept, and so are dots
+/// and spaces that follow a wildcard, class, extglob or escape (`*.`, `[ab].`),
+/// since those aren't the end of a literal name.
+pub fn strip_trailing_dots_and_spaces(path: &str) -> Cow<'_, str> {
+    let strip = |segment: &str| -> Option<usize> {
+        let trimmed = segment.trim_end_matches(['.', ' ']);
+        let last = trimmed.chars().last()?;
+        (trimmed.len() < segment.len() && !matches!(last, '*' | '?' | ']' | ')' | '}' | '\\'))
+            .then_some(trimmed.len())
+    };
+    if path.split('/').all(|segment| strip(segment).is_none()) {
+        return Cow::Borrowed(path);
+    }
+
+    let segments: Vec<&str> = path
+        .split('/')
+        .map(|segment| match strip(segment) {
+            Some(len) => &segment[..len],
+            None => segment,
+        })
+        .collect();
+    Cow::Owned(segments.join("/"))
+}
+
+/// Turn trailing `/.` segments into a trailing `/`, so `src/.` and `src/./`
+/// ask for the directory just like `src/` does.
+///
+/// The pattern `.` itself, roots such as `/.` or `//./`, and an escaped `\/.`
+/// keep their meaning.
+fn strip_trailing_dot_segments(pattern: Cow<'_, str>) -> Cow<'_, str> {
+    // Whether the `/` ending `head` separates a named directory from the `.`
+    let after_name = |head: &str| {
+        let dir = &head[..head.len() - 1];
+        !dir.ends_with('\\') && dir.bytes().any(|b| b != b'/')
+    };
+    let mut end = pattern.len();
+    loop {
+        let rest = &pattern[..end];
+        if rest.ends_with("/./") && after_name(&rest[..end - 2]) {
+            end -= 2;
+        } else if rest.ends_with("/.") && after_name(&rest[..end - 1]) {
+            end -= 1;
+        } else {
+            break;
+        }
+    }
+    match pattern {
+        _ if end == pattern.len() => pattern,
+        Cow::Borrowed(p) => Cow::Borrowed(&p[..end]),
+        Cow::Owned(mut p) => {
+            p.truncate(end);
+            Cow::Owned(p)
+        }
+    }
+}
+
 /// Preprocess a glob pattern for matching.
-/// Handles ./ prefix stripping and other normalization.
+/// Handles ./ prefix stripping, collapses repeated slashes and other normalization.
+/// Trailing `/.` segments become a trailing `/`, so `src/`, `src//` and `src/.`
+/// all match the directory `src`.
+/// On Windows, trailing dots and spaces are dropped from each segment, matching
+/// how the filesystem stores such names.
 /// Returns Cow::Borrowed when no transformation is needed to avoid allocation.
 pub fn preprocess_pattern(pattern: &str) -> Cow<'_, str> {
+    let pattern = strip_trailing_dot_segments(collapse_slashes(pattern));
+    #[cfg(target_os = "windows")]
+    let pattern = match strip_trailing_dots_and_spaces(&pattern) {
+        Cow::Borrowed(_) => pattern,
+        Cow::Owned(stripped) => Cow::Owned(stripped),
+    };
+
     // Fast path: no transformation needed
     if !pattern.starts_with("./") {
-        return Cow::Borrowed(pattern);
+        return pattern;
     }
 
     // Strip leading ./ (common in glob patterns)
-    let mut rest = pattern;
+    let mut rest: &str = &pattern;
     while let Some(stripped) = rest.strip_prefix("./") {
         rest = stripped;
     }
@@ -1466,8 +2069,15 @@ pub fn preprocess_pattern_owned(pattern: &str) -> String {
 
 /// Parse an extglob pattern starting at position i (which is the type character).
 /// Returns (regex_part, new_position) or None if not a valid extglob.
-fn parse_extglob(chars: &[char], start: usize, noext: bool) -> Option<(String, usize)> {
-    if noext {
+/// With `no_negation`, `!(...)` is not an extglob and None is returned for it.
+fn parse_extglob(
+    chars: &[char],
+    start: usize,
+    noext: bool,
+    no_negation: bool,
+    separator: char,
+) -> Option<(String, usize)> {
+    if noext || (no_negation && chars.get(start) == Some(&'!')) {
         return None;
     }
 
@@ -1486,6 +2096,7 @@ fn parse_extglob(chars: &[char], start: usize, noext: bool) -> Option<(String, u
     let mut i = start + 2;
     let mut alternatives: Vec<String> = Vec::new();
     let mut current = String::new();
+    let not_separator = format!("[^{}]", escape_for_bracket(separator));
 
     while i < chars.len() && depth > 0 {
         let c = chars[i];
@@ -1495,7 +2106,9 @@ fn parse_extglob(chars: &[char], start: usize, noext: bool) -> Option<(String, u
                 // Check if this is a nested extglob
                 if i > 0 && EXTGLOB_TYPES.contains(&chars[i - 1]) && !current.is_empty() {
                     // This is a nested extglob, recurse
-                    if let Some((nested, new_pos)) = parse_extglob(chars, i - 1, noext) {
+                    if let Some((nested, new_pos)) =
+                        parse_extglob(chars, i - 1, noext, no_negation, separator)
+                    {
                         // Remove the type char we already added
                         current.pop();
                         current.push_str(&nested);
@@ -1534,7 +2147,8 @@ fn parse_extglob(chars: &[char], start: usize, noext: bool) -> Option<(String, u
                     i += 1;
                 } else {
                     // Single * - match any chars except /
-                    current.push_str("[^/]*?");
+                    current.push_str(&not_separator);
+                    current.push_str("*?");
                 }
             }
             '?' if i + 1 < chars.len() && chars[i + 1] == '(' => {
@@ -1542,7 +2156,7 @@ fn parse_extglob(chars: &[char], start: usize, noext: bool) -> Option<(String, u
                 current.push(c);
             }
             '?' => {
-                current.push_str("[^/]");
+                current.push_str(&not_separator);
             }
             // Escape regex special characters (except | which we handle, and () which we track)
             '.' | '+' | '^' | '$' | '{' | '}' | '[' | ']' => {
@@ -1591,12 +2205,13 @@ fn parse_extglob(chars: &[char], start: usize, noext: bool) -> Option<(String, u
             // Uses negative lookahead to exclude the patterns
             if alt_regex.is_empty() {
                 // !() matches any non-empty string
-                "[^/]+".to_string()
+                format!("{not_separator}+")
             } else {
                 // Match any path segment that doesn't match the alternatives
                 // The negative lookahead checks if the next segment (up to / or end) matches
                 // (?:$|/) ensures we're checking a complete segment
-                format!("(?!(?:{alt_regex})(?:$|/))[^/]+")
+                let tail = negation_tail(separator);
+                format!("(?!(?:{alt_regex}){tail}){not_separator}+")
             }
         }
         _ => return None,
@@ -1631,7 +2246,10 @@ fn get_posix_class(name: &str) -> Option<(&'static str, bool)> {
 
 /// Parse a character class (bracket expression) starting at position i.
 /// Returns (regex_part, new_position) or None if not a valid character class.
-fn parse_character_class(chars: &[char], start: usize) -> Option<(String, usize)> {
+///
+/// Like minimatch, which splits the pattern before parsing segments, a class
+/// never spans a separator and never matches one.
+fn parse_character_class(chars: &[char], start: usize, separator: char) -> Option<(String, usize)> {
     if start >= chars.len() || chars[start] != '[' {
         return None;
     }
@@ -1653,10 +2271,15 @@ fn parse_character_class(chars: &[char], start: usize) -> Option<(String, usize)
     while i < chars.len() {
         let c = chars[i];
 
+        // The `[` is literal if the segment ends before the class does
+        if c == separator {
+            return None;
+        }
+
         // Handle ] as end of class (but not at very start)
         if c == ']' && saw_start && !escaping {
             // Build the final regex
-            let result = build_character_class_regex(&ranges, &negs, negate);
+            let result = build_character_class_regex(&ranges, &negs, negate, separator);
             return Some((result, i + 1));
         }
 
@@ -1751,13 +2374,14 @@ fn find_posix_class_end(chars: &[char], start: usize) -> Option<usize> {
 /// Escape special characters for use inside a bracket expression
 fn escape_for_bracket(c: char) -> String {
     match c {
-        '[' | ']' | '\\' | '-' | '^' => format!("\\{c}"),
+        // Doubled `&`, `~` and `-` are set operators
+        '[' | ']' | '\\' | '-' | '^' | '&' | '~' => format!("\\{c}"),
         _ => c.to_string(),
     }
 }
 
 /// Build the final regex for a character class
-fn build_character_class_regex(ranges: &str, negs: &str, negate: bool) -> String {
+fn build_character_class_regex(ranges: &str, negs: &str, negate: bool, separator: char) -> String {
     if ranges.is_empty() && negs.is_empty() {
         // Empty class - cannot match anything
         return r"\b\B".to_string(); // Matches nothing (word boundary followed by non-word boundary)
@@ -1776,14 +2400,25 @@ fn build_character_class_regex(ranges: &str, negs: &str, negate: bool) -> String
         }
     }
 
+    // Negated sets list the separator too; the others drop it by intersection,
+    // since a range or POSIX class may cover it
+    let separator = escape_for_bracket(separator);
+    let class = |set: &str, negated: bool| {
+        if negated {
+            format!("[^{set}{separator}]")
+        } else {
+            format!("[{set}&&[^{separator}]]")
+        }
+    };
+
     let sranges = if !ranges.is_empty() {
-        format!("[{}{}]", if negate { "^" } else { "" }, ranges)
+        class(ranges, negate)
     } else {
         String::new()
     };
 
     let snegs = if !negs.is_empty() {
-        format!("[{}{}]", if negate { "" } else { "^" }, negs)
+        class(negs, !negate)
     } else {
         String::new()
     };
@@ -1828,25 +2463,42 @@ fn escape_regex_char(c: char) -> String {
 fn pattern_to_regex(
     pattern: &str,
     noext: bool,
+    no_negation: bool,
     windows_paths_no_escape: bool,
     nocase: bool,
-) -> Regex {
+    separator: char,
+) -> Result<Regex, String> {
     // Preprocess: handle ./ prefix
-    let pattern = preprocess_pattern(pattern);
+    let pattern = if separator == '/' {
+        preprocess_pattern(pattern)
+    } else {
+        Cow::Borrowed(pattern)
+    };
     let mut regex_str = String::with_capacity(pattern.len() * 2);
-    // Add case-insensitive flag if nocase is true
-    if nocase {
+    // With nocase, candidates are folded before matching, so the pattern is too;
+    // (?i) still lets classes like [[:upper:]] match the folded text
+    let pattern = if nocase {
         regex_str.push_str("(?i)");
-    }
+        Cow::Owned(fold_case(&pattern).into_owned())
+    } else {
+        pattern
+    };
     regex_str.push('^');
 
+    let sep = escape_regex_char(separator);
+    let not_separator = format!("[^{}]", escape_for_bracket(separator));
     let chars: Vec<char> = pattern.chars().collect();
     let len = chars.len();
     let mut i = 0;
+    let mut negations: Vec<(usize, usize)> = Vec::new();
 
     while i < len {
         let c = chars[i];
 
+        if c == separator {
+            close_negated_extglobs(&mut regex_str, &mut negations);
+        }
+
         // Handle escape sequences (backslash)
         // If windowsPathsNoEscape is true, backslashes were already converted to /
         // so we won't see them here. But if false, handle escapes.
@@ -1878,7 +2530,10 @@ fn pattern_to_regex(
 
         // Check for extglob patterns
         if !noext && EXTGLOB_TYPES.contains(&c) && i + 1 < len && chars[i + 1] == '(' {
-            if let Some((extglob_regex, new_pos)) = parse_extglob(&chars, i, noext) {
+            if let Some((extglob_regex, new_pos)) =
+                parse_extglob(&chars, i, noext, no_negation, separator)
+            {
+                track_negated_extglob(c, &extglob_regex, &regex_str, &mut negations, separator);
                 regex_str.push_str(&extglob_regex);
                 i = new_pos;
                 continue;
@@ -1889,18 +2544,18 @@ fn pattern_to_regex(
             '*' => {
                 if i + 1 < len && chars[i + 1] == '*' {
                     // Check for proper globstar: must be bounded by / or start/end
-                    let at_start = i == 0 || chars[i - 1] == '/';
+                    let at_start = i == 0 || chars[i - 1] == separator;
                     let at_end = i + 2 >= len;
-                    let followed_by_slash = i + 2 < len && chars[i + 2] == '/';
+                    let followed_by_slash = i + 2 < len && chars[i + 2] == separator;
 
                     if at_start && (at_end || followed_by_slash) {
                         // Proper globstar - match any path segments (including empty)
-                        // If there's a leading / before **, check if we should remove it
-                        // Only remove if the last char in regex_str is actually '/'
-                        let has_leading_slash = i > 0 && chars[i - 1] == '/';
-                        if has_leading_slash && regex_str.ends_with('/') {
+                        // A trailing `/**` also matches the directory itself, so drop the
+                        // `/` before it; in `a/**/b` the `/` after `a` is still required.
+                        let has_leading_slash = i > 0 && chars[i - 1] == separator;
+                        if has_leading_slash && at_end && regex_str.ends_with(&sep) {
                             // Remove the trailing / we just added to regex
-                            regex_str.pop();
+                            regex_str.truncate(regex_str.len() - sep.len());
                         }
 
                         if i == 0 && at_end {
@@ -1909,15 +2564,15 @@ fn pattern_to_regex(
                         } else if at_end {
                             // Pattern ends with ** - match the directory itself plus anything below
                             // src/** should match: "src", "src/foo", "src/a/b/c"
-                            regex_str.push_str("(/.*)?");
+                            regex_str.push_str(&format!("({sep}.*)?"));
                         } else {
                             // ** followed by slash - match zero or more path segments
                             // **/foo should match: "foo", "a/foo", "a/b/foo"
-                            regex_str.push_str("(.*/)?");
+                            regex_str.push_str(&format!("(.*{sep})?"));
                         }
                         i += 2;
                         // Skip the trailing slash if present
-                        if i < len && chars[i] == '/' {
+                        if i < len && chars[i] == separator {
                             i += 1;
                         }
                         continue;
@@ -1925,31 +2580,33 @@ fn pattern_to_regex(
                     // Not a proper globstar - treat as two * wildcards
                     // Each * matches any chars except /
                     // e.g., b** becomes b[^/]*[^/]* which is equivalent to b[^/]*
-                    regex_str.push_str("[^/]*[^/]*");
+                    regex_str.push_str(&format!("{not_separator}*{not_separator}*"));
                     i += 2;
                     continue;
                 }
                 // Single * - check if it's a standalone segment or part of a segment
                 // Standalone segment (preceded by / or at start, followed by / or at end): [^/]+
                 // Part of a segment (suffix like a*, prefix like *a): [^/]*
-                let at_segment_start = i == 0 || chars[i - 1] == '/';
-                let at_segment_end = i + 1 >= len || chars[i + 1] == '/';
+                let at_segment_start = i == 0 || chars[i - 1] == separator;
+                let at_segment_end = i + 1 >= len || chars[i + 1] == separator;
 
                 if at_segment_start && at_segment_end {
                     // Standalone * as a complete segment - must match at least one char
-                    regex_str.push_str("[^/]+");
+                    regex_str.push_str(&not_separator);
+                    regex_str.push('+');
                 } else {
                     // * is part of a segment (e.g., a*, *a, a*b) - can match zero chars
-                    regex_str.push_str("[^/]*");
+                    regex_str.push_str(&not_separator);
+                    regex_str.push('*');
                 }
             }
             '?' => {
                 // Match single char except /
-                regex_str.push_str("[^/]");
+                regex_str.push_str(&not_separator);
             }
             '[' => {
                 // Try to parse as a character class
-                if let Some((class_regex, new_pos)) = parse_character_class(&chars, i) {
+                if let Some((class_regex, new_pos)) = parse_character_class(&chars, i, separator) {
                     regex_str.push_str(&class_regex);
                     i = new_pos;
                     continue;
@@ -1970,9 +2627,29 @@ fn pattern_to_regex(
         i += 1;
     }
 
+    close_negated_extglobs(&mut regex_str, &mut negations);
     regex_str.push('$');
 
-    Regex::new(&regex_str).unwrap_or_else(|_| Regex::new("^$").unwrap())
+    Regex::new(&regex_str).map_err(|e| e.to_string())
+}
+
+/// Turn a literal pattern segment into the filesystem name it matches by
+/// dropping backslash escapes. With windowsPathsNoEscape the text is returned as-is.
+fn unescape_literal(literal: &str, windows_paths_no_escape: bool) -> String {
+    if windows_paths_no_escape || !literal.contains('\\') {
+        return literal.to_string();
+    }
+    let mut result = String::with_capacity(literal.len());
+    let mut chars = literal.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            // A trailing backslash has nothing to escape and stays literal
+            result.push(chars.next().unwrap_or('\\'));
+        } else {
+            result.push(c);
+        }
+    }
+    result
 }
 
 /// Detect the fast-path optimization for a pattern.
@@ -1986,21 +2663,34 @@ fn pattern_to_regex(
 /// - `*.{ext1,ext2}` (after brace expansion) -> `ExtensionSet`
 /// - `**/*.{ext1,ext2}` (after brace expansion) -> `RecursiveExtensionSet`
 /// - `filename.ext` (no magic) -> `LiteralName("filename.ext")`
+/// - `**/filename.ext` -> `RecursiveLiteralName("filename.ext")`
 ///
 /// # Returns
 /// The detected `FastPath` variant, or `FastPath::None` if no optimization applies.
-fn detect_fast_path(pattern: &str, parts: &[PatternPart], nocase: bool, nobrace: bool) -> FastPath {
+fn detect_fast_path(
+    pattern: &str,
+    parts: &[PatternPart],
+    nocase: bool,
+    nobrace: bool,
+    windows_paths_no_escape: bool,
+) -> FastPath {
+    // Fast paths fold case byte by byte; non-ASCII text needs `fold_case` via the regex
+    if nocase && !pattern.is_ascii() {
+        return FastPath::None;
+    }
+
     // Preprocess the pattern (for documentation purposes, actual analysis uses parts)
     let _pattern = preprocess_pattern(pattern);
 
     // Check if pattern is a literal (no magic at all)
     if parts.len() == 1 {
         if let PatternPart::Literal(name) = &parts[0] {
-            // Pure literal pattern - fast string comparison
+            // Pure literal pattern - fast string comparison against the unescaped name
+            let name = unescape_literal(name, windows_paths_no_escape);
             let name_for_match = if nocase {
-                name.to_lowercase()
+                fold_case(&name).into_owned()
             } else {
-                name.clone()
+                name
             };
             return FastPath::LiteralName(name_for_match);
         }
@@ -2011,7 +2701,11 @@ fn detect_fast_path(pattern: &str, parts: &[PatternPart], nocase: bool, nobrace:
     if parts.len() == 1 {
         if let PatternPart::Magic(raw, ..) = &parts[0] {
             if let Some(ext) = parse_extension_pattern(raw) {
-                let ext_for_match = if nocase { ext.to_lowercase() } else { ext };
+                let ext_for_match = if nocase {
+                    fold_case(&ext).into_owned()
+                } else {
+                    ext
+                };
                 return FastPath::ExtensionOnly(ext_for_match);
             }
             // Only detect extension set patterns if nobrace is false
@@ -2019,7 +2713,9 @@ fn detect_fast_path(pattern: &str, parts: &[PatternPart], nocase: bool, nobrace:
             if !nobrace {
                 if let Some(exts) = parse_extension_set_pattern(raw) {
                     let exts_for_match: HashSet<String> = if nocase {
-                        exts.into_iter().map(|e| e.to_lowercase()).collect()
+                        exts.into_iter()
+                            .map(|e| fold_case(&e).into_owned())
+                            .collect()
                     } else {
                         exts
                     };
@@ -2029,19 +2725,39 @@ fn detect_fast_path(pattern: &str, parts: &[PatternPart], nocase: bool, nobrace:
         }
     }
 
+    // Check for `**/<literal>` pattern (find a filename at any depth)
+    // Should be: [Globstar, Literal("name")]
+    if parts.len() == 2 {
+        if let (PatternPart::Globstar, PatternPart::Literal(name)) = (&parts[0], &parts[1]) {
+            let name = unescape_literal(name, windows_paths_no_escape);
+            let name_for_match = if nocase {
+                fold_case(&name).into_owned()
+            } else {
+                name
+            };
+            return FastPath::RecursiveLiteralName(name_for_match);
+        }
+    }
+
     // Check for `**/*.ext` pattern (recursive extension matching)
     // Should be: [Globstar, Magic("*.ext")]
     if parts.len() == 2 {
         if let (PatternPart::Globstar, PatternPart::Magic(raw, ..)) = (&parts[0], &parts[1]) {
             if let Some(ext) = parse_extension_pattern(raw) {
-                let ext_for_match = if nocase { ext.to_lowercase() } else { ext };
+                let ext_for_match = if nocase {
+                    fold_case(&ext).into_owned()
+                } else {
+                    ext
+                };
                 return FastPath::RecursiveExtension(ext_for_match);
             }
             // Only detect extension set patterns if nobrace is false
             if !nobrace {
                 if let Some(exts) = parse_extension_set_pattern(raw) {
                     let exts_for_match: HashSet<String> = if nocase {
-                        exts.into_iter().map(|e| e.to_lowercase()).collect()
+                        exts.into_iter()
+                            .map(|e| fold_case(&e).into_owned())
+                            .collect()
                     } else {
                         exts
                     };
@@ -2051,7 +2767,7 @@ fn detect_fast_path(pattern: &str, parts: &[PatternPart], nocase: bool, nobrace:
             // Check for suffix patterns like **/*.test.js or **/*.spec.ts
             if let Some(suffix) = parse_suffix_pattern(raw) {
                 let suffix_for_match = if nocase {
-                    suffix.to_lowercase()
+                    fold_case(&suffix).into_owned()
                 } else {
                     suffix
                 };
@@ -2068,7 +2784,7 @@ fn detect_fast_path(pattern: &str, parts: &[PatternPart], nocase: bool, nobrace:
         if let PatternPart::Magic(raw, ..) = &parts[0] {
             if let Some(suffix) = parse_suffix_pattern(raw) {
                 let suffix_for_match = if nocase {
-                    suffix.to_lowercase()
+                    fold_case(&suffix).into_owned()
                 } else {
                     suffix
                 };
@@ -2080,12 +2796,21 @@ fn detect_fast_path(pattern: &str, parts: &[PatternPart], nocase: bool, nobrace:
             // Check for prefix patterns like foo*, test-*
             if let Some(prefix) = parse_prefix_pattern(raw) {
                 let prefix_for_match = if nocase {
-                    prefix.to_lowercase()
+                    fold_case(&prefix).into_owned()
                 } else {
                     prefix
                 };
                 return FastPath::PrefixMatch(prefix_for_match);
             }
+            // Check for substring patterns like *test*, *.min.*
+            if let Some(needle) = parse_contains_pattern(raw) {
+                let needle_for_match = if nocase {
+                    fold_case(&needle).into_owned()
+                } else {
+                    needle
+                };
+                return FastPath::Contains(needle_for_match);
+            }
         }
     }
 
@@ -2170,25 +2895,30 @@ fn parse_extension_set_pattern(pattern: &str) -> Option<HashSet<String>> {
         if ext.contains('/') || ext.contains('\\') {
             return None;
         }
+        // A compound suffix like `d.ts` can't be compared against the final
+        // extension alone, so leave it to the regex.
+        if ext.contains('.') {
+            return None;
+        }
     }
 
     Some(extensions.into_iter().map(String::from).collect())
 }
 
-/// Parse a pattern like `*.test.js` or `*.spec.ts` and return the suffix.
-/// Returns `Some(".test.js")` if the pattern is `*.something.ext`, otherwise `None`.
+/// Parse a pattern like `*.test.js` or `*rc` and return the suffix.
+/// Returns `Some(".test.js")` if the pattern is `*` followed by a literal, otherwise `None`.
 ///
 /// This handles patterns where:
-/// - Pattern starts with `*`
-/// - There are multiple dots after the `*`
+/// - Pattern starts with a single `*`
 /// - No other magic characters in the suffix
 ///
 /// Examples:
 /// - `*.test.js` -> Some(".test.js")
 /// - `*.spec.ts` -> Some(".spec.ts")
-/// - `*.config.json` -> Some(".config.json")
-/// - `*.js` -> None (single extension, handled by parse_extension_pattern)
+/// - `*rc` -> Some("rc")
+/// - `*.js` -> Some(".js") (but parse_extension_pattern is tried first)
 /// - `*.{js,ts}` -> None (has braces)
+/// - `*(a|b)` -> None (extglob)
 fn parse_suffix_pattern(pattern: &str) -> Option<String> {
     // Pattern must start with `*`
     if !pattern.starts_with('*') {
@@ -2198,19 +2928,13 @@ fn parse_suffix_pattern(pattern: &str) -> Option<String> {
     // Get the part after `*`
     let suffix = &pattern[1..];
 
-    // Suffix must start with `.` and contain at least one more `.`
-    if !suffix.starts_with('.') {
+    // Suffix must not be empty, and `*(` starts an extglob
+    if suffix.is_empty() || suffix.starts_with('(') {
         return None;
     }
 
-    // Must have at least two dots total (e.g., .test.js has dots at positions 0 and 5)
-    let dot_count = suffix.chars().filter(|c| *c == '.').count();
-    if dot_count < 2 {
-        return None;
-    }
-
-    // Suffix must not contain magic characters
-    if has_magic_in_pattern(suffix, false, false) {
+    // Suffix must not contain magic characters
+    if has_magic_in_pattern(suffix, false, false) {
         return None;
     }
 
@@ -2274,6 +2998,40 @@ fn parse_prefix_pattern(pattern: &str) -> Option<String> {
     Some(prefix.to_string())
 }
 
+/// Parse a pattern like `*test*` and return the substring.
+/// Returns `Some("test")` if the pattern is `*substring*`, otherwise `None`.
+///
+/// This handles patterns where:
+/// - Pattern starts and ends with a single `*`
+/// - No other magic characters in between
+///
+/// Examples:
+/// - `*test*` -> Some("test")
+/// - `*.min.*` -> Some(".min.")
+/// - `**` -> None (globstar)
+/// - `*a*b*` -> None (more than one substring)
+/// - `*(a)*` -> None (extglob)
+fn parse_contains_pattern(pattern: &str) -> Option<String> {
+    let needle = pattern.strip_prefix('*')?.strip_suffix('*')?;
+
+    // Needle must not be empty, and `*(` starts an extglob
+    if needle.is_empty() || needle.starts_with('(') {
+        return None;
+    }
+
+    // Needle must not contain magic characters
+    if has_magic_in_pattern(needle, false, false) {
+        return None;
+    }
+
+    // Needle must not contain path separators or braces
+    if needle.contains(['/', '\\', '{', '}']) {
+        return None;
+    }
+
+    Some(needle.to_string())
+}
+
 /// Expand brace expressions in a glob pattern.
 /// Supports:
 /// - Comma alternatives: `{a,b,c}` -> `["a", "b", "c"]`
@@ -2282,10 +3040,27 @@ fn parse_prefix_pattern(pattern: &str) -> Option<String> {
 /// - Step values: `{1..10..2}` -> `["1", "3", "5", "7", "9"]`
 /// - Zero-padding: `{01..03}` -> `["01", "02", "03"]`
 /// - Nested braces: `{a,{b,c}}` -> `["a", "b", "c"]`
-/// - Escaped braces: `\{a,b\}` stays as-is
+/// - Empty alternatives: `a{,.bak}` -> `["a", "a.bak"]`
+/// - Escaped braces: `\{a,b\}` and `[{]a,b[}]` stay as-is
 pub fn expand_braces(pattern: &str) -> Vec<String> {
+    expand_braces_limited(pattern, usize::MAX).unwrap_or_default()
+}
+
+/// Expand brace expressions like `expand_braces`, giving up as soon as the
+/// expansion would produce more than `limit` patterns.
+///
+/// Returns `None` when the limit is exceeded. Intermediate results are bounded
+/// by the limit as well, so a pathological pattern like `{a,b}{a,b}...` fails
+/// fast instead of exhausting memory.
+pub fn expand_braces_limited(pattern: &str, limit: usize) -> Option<Vec<String>> {
     if pattern.is_empty() {
-        return vec![];
+        return Some(vec![]);
+    }
+
+    // Like minimatch, only run brace expansion when there is a `{...}` pair;
+    // otherwise escapes such as `\\\\` must reach the matcher untouched.
+    if !has_brace_pair(pattern) {
+        return (limit >= 1).then(|| vec![pattern.to_string()]);
     }
 
     // Handle leading {} (bash quirk - preserve it)
@@ -2299,17 +3074,33 @@ pub fn expand_braces(pattern: &str) -> Vec<String> {
     let escaped = escape_braces(&pattern);
 
     // Expand and unescape
-    expand_internal(&escaped, true)
-        .into_iter()
-        .map(|s| unescape_braces(&s))
-        .collect()
+    let expanded = expand_internal(&escaped, true, limit)?;
+    Some(expanded.into_iter().map(|s| unescape_braces(&s)).collect())
 }
 
-/// Escape backslash sequences to prevent them from being processed
+/// Whether the pattern contains a `{` followed by a `}` with no other `{` in
+/// between (minimatch's `/\{(?:(?!\{).)*\}/` check).
+fn has_brace_pair(pattern: &str) -> bool {
+    let mut open = false;
+    for c in pattern.chars() {
+        match c {
+            '{' => open = true,
+            '}' if open => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Escape backslash sequences to prevent them from being processed.
+/// The bracket forms `[{]` and `[}]` produced by `escape_pattern` with
+/// `windowsPathsNoEscape` are treated as escaped braces as well.
 fn escape_braces(s: &str) -> String {
     s.replace("\\\\", ESC_SLASH)
         .replace("\\{", ESC_OPEN)
         .replace("\\}", ESC_CLOSE)
+        .replace("[{]", ESC_OPEN)
+        .replace("[}]", ESC_CLOSE)
         .replace("\\,", ESC_COMMA)
         .replace("\\.", ESC_PERIOD)
 }
@@ -2446,12 +3237,13 @@ fn parse_numeric(s: &str) -> i64 {
 }
 
 /// Generate a numeric or alpha sequence
-fn generate_sequence(parts: &[&str], is_alpha: bool) -> Vec<String> {
+fn generate_sequence(parts: &[&str], is_alpha: bool, limit: usize) -> Option<Vec<String>> {
     let x = parse_numeric(parts[0]);
     let y = parse_numeric(parts[1]);
     let width = parts[0].len().max(parts[1].len());
+    // A zero step would never terminate; treat it as 1 like brace-expansion does
     let mut incr = if parts.len() == 3 {
-        parse_numeric(parts[2]).abs()
+        parse_numeric(parts[2]).abs().max(1)
     } else {
         1
     };
@@ -2496,22 +3288,25 @@ fn generate_sequence(parts: &[&str], is_alpha: bool) -> Vec<String> {
         };
 
         if !s.is_empty() {
+            if result.len() == limit {
+                return None;
+            }
             result.push(s);
         }
 
         i += incr;
     }
 
-    result
+    Some(result)
 }
 
 /// Internal expansion function
-fn expand_internal(s: &str, is_top: bool) -> Vec<String> {
+fn expand_internal(s: &str, is_top: bool, limit: usize) -> Option<Vec<String>> {
     // Find the first balanced brace pair
     let matched = balanced_match(s);
 
     if matched.is_none() {
-        return vec![s.to_string()];
+        return (limit >= 1).then(|| vec![s.to_string()]);
     }
 
     let (pre, body, post) = matched.unwrap();
@@ -2520,15 +3315,17 @@ fn expand_internal(s: &str, is_top: bool) -> Vec<String> {
     let post_expansions = if post.is_empty() {
         vec!["".to_string()]
     } else {
-        expand_internal(&post, false)
+        expand_internal(&post, false, limit)?
     };
 
     // Check if pre ends with $ (bash variable syntax - don't expand)
     if pre.ends_with('$') {
-        return post_expansions
-            .iter()
-            .map(|p| format!("{pre}{{{body}}}{p}"))
-            .collect();
+        return Some(
+            post_expansions
+                .iter()
+                .map(|p| format!("{pre}{{{body}}}{p}"))
+                .collect(),
+        );
     }
 
     // Check what type of expansion we have
@@ -2542,38 +3339,49 @@ fn expand_internal(s: &str, is_top: bool) -> Vec<String> {
         // Check for {a},b} case - look for comma followed by } in post
         if post.contains(',') && post.contains('}') {
             let new_str = format!("{pre}{{{body}{ESC_CLOSE}{post}");
-            return expand_internal(&new_str, is_top);
+            return expand_internal(&new_str, is_top, limit);
         }
-        return vec![s.to_string()];
+        return (limit >= 1).then(|| vec![s.to_string()]);
     }
 
     // Generate the expansion parts
     let parts: Vec<String> = if is_sequence {
         let seq_parts: Vec<&str> = body.split("..").collect();
-        generate_sequence(&seq_parts, is_alpha_seq)
+        generate_sequence(&seq_parts, is_alpha_seq, limit)?
     } else {
         // Comma-separated options
         let comma_parts = parse_comma_parts(&body);
         if comma_parts.len() == 1 {
             // Single item - might be nested braces: x{{a,b}}y
-            let expanded = expand_internal(&comma_parts[0], false);
+            let expanded = expand_internal(&comma_parts[0], false, limit)?;
             let embraced: Vec<String> = expanded.iter().map(|e| format!("{{{e}}}")).collect();
             if embraced.len() == 1 {
-                return post_expansions
-                    .iter()
-                    .map(|p| format!("{}{}{}", pre, embraced[0], p))
-                    .collect();
+                return Some(
+                    post_expansions
+                        .iter()
+                        .map(|p| format!("{}{}{}", pre, embraced[0], p))
+                        .collect(),
+                );
             }
             embraced
         } else {
             // Multiple comma-separated items - expand each recursively
-            comma_parts
-                .into_iter()
-                .flat_map(|p| expand_internal(&p, false))
-                .collect()
+            let mut parts = Vec::new();
+            for p in comma_parts {
+                parts.extend(expand_internal(&p, false, limit)?);
+                if parts.len() > limit {
+                    return None;
+                }
+            }
+            parts
         }
     };
 
+    // Every part is combined with every post expansion
+    if parts.len().saturating_mul(post_expansions.len()) > limit {
+        return None;
+    }
+
     // Combine pre + parts + post_expansions
     let mut result = Vec::new();
     for part in &parts {
@@ -2585,7 +3393,7 @@ fn expand_internal(s: &str, is_top: bool) -> Vec<String> {
         }
     }
 
-    result
+    Some(result)
 }
 
 /// Magic glob characters that need escaping in glob patterns.
@@ -2597,6 +3405,9 @@ fn expand_internal(s: &str, is_top: bool) -> Vec<String> {
 /// - `+`, `@`, `!` only matter when followed by `(`
 const ESCAPE_CHARS: &[char] = &['*', '?', '[', ']', '(', ')'];
 
+/// Brace characters, escaped only on request (`magicalBraces`) but always unescaped.
+const BRACE_CHARS: &[char] = &['{', '}'];
+
 /// Escape magic glob characters in a pattern.
 ///
 /// This makes a pattern safe to use as a literal string match.
@@ -2604,7 +3415,8 @@ const ESCAPE_CHARS: &[char] = &['*', '?', '[', ']', '(', ')'];
 /// a file literally named `*.txt`.
 ///
 /// Note: This matches glob/minimatch behavior and only escapes
-/// `*`, `?`, `[`, `]`, `(`, `)`. Braces and other characters are not escaped.
+/// `*`, `?`, `[`, `]`, `(`, `)`. Braces and other characters are not escaped;
+/// use [`escape_pattern_with_braces`] for a result that is never brace-expanded.
 ///
 /// # Arguments
 /// * `pattern` - The pattern to escape
@@ -2613,10 +3425,23 @@ const ESCAPE_CHARS: &[char] = &['*', '?', '[', ']', '(', ')'];
 /// # Returns
 /// The escaped pattern string
 pub fn escape_pattern(pattern: &str, windows_paths_no_escape: bool) -> String {
+    escape_chars(pattern, windows_paths_no_escape, false)
+}
+
+/// Escape magic glob characters and braces in a pattern.
+///
+/// Like [`escape_pattern`], but `{` and `}` are escaped too, so the result
+/// matches the literal string even with brace expansion on. This is minimatch's
+/// `escape` with `magicalBraces: true`.
+pub fn escape_pattern_with_braces(pattern: &str, windows_paths_no_escape: bool) -> String {
+    escape_chars(pattern, windows_paths_no_escape, true)
+}
+
+fn escape_chars(pattern: &str, windows_paths_no_escape: bool, braces: bool) -> String {
     let mut result = String::with_capacity(pattern.len() * 2);
 
     for c in pattern.chars() {
-        if ESCAPE_CHARS.contains(&c) {
+        if ESCAPE_CHARS.contains(&c) || (braces && BRACE_CHARS.contains(&c)) {
             if windows_paths_no_escape {
                 // On Windows with windowsPathsNoEscape, wrap in brackets instead
                 // This makes `*` become `[*]` which matches literal `*`
@@ -2638,8 +3463,8 @@ pub fn escape_pattern(pattern: &str, windows_paths_no_escape: bool) -> String {
 
 /// Unescape magic glob characters in a pattern.
 ///
-/// This reverses the effect of `escape_pattern`, turning escaped
-/// magic characters back into literals.
+/// This reverses the effect of `escape_pattern` and `escape_pattern_with_braces`,
+/// turning escaped magic characters (and braces) back into literals.
 ///
 /// # Arguments
 /// * `pattern` - The pattern to unescape
@@ -2660,7 +3485,7 @@ pub fn unescape_pattern(pattern: &str, windows_paths_no_escape: bool) -> String
             if c == '['
                 && i + 2 < chars.len()
                 && chars[i + 2] == ']'
-                && ESCAPE_CHARS.contains(&chars[i + 1])
+                && is_unescapable(chars[i + 1])
             {
                 result.push(chars[i + 1]);
                 i += 3;
@@ -2668,7 +3493,7 @@ pub fn unescape_pattern(pattern: &str, windows_paths_no_escape: bool) -> String
             }
         } else {
             // Look for `\x` pattern where x is a magic character
-            if c == '\\' && i + 1 < chars.len() && ESCAPE_CHARS.contains(&chars[i + 1]) {
+            if c == '\\' && i + 1 < chars.len() && is_unescapable(chars[i + 1]) {
                 result.push(chars[i + 1]);
                 i += 2;
                 continue;
@@ -2682,6 +3507,10 @@ pub fn unescape_pattern(pattern: &str, windows_paths_no_escape: bool) -> String
     result
 }
 
+fn is_unescapable(c: char) -> bool {
+    ESCAPE_CHARS.contains(&c) || BRACE_CHARS.contains(&c)
+}
+
 /// Pattern warning types for helpful error messages.
 /// These warn users about potential issues with their patterns.
 #[derive(Debug, Clone, PartialEq)]
@@ -2709,6 +3538,22 @@ pub enum PatternWarning {
     /// Pattern has trailing spaces that may be unintentional
     TrailingSpaces { pattern: String, suggestion: String },
 
+    /// Absolute pattern climbs above its root with `..`, which is ignored
+    /// e.g., `/../etc/*` is the same as `/etc/*`
+    ParentOfRoot { pattern: String, suggestion: String },
+
+    /// Part of the pattern can never match, usually a typo
+    /// e.g., the reversed range in `[z-a]` or the empty alternation `{}`
+    ImpossiblePattern {
+        pattern: String,
+        reason: String,
+        suggestion: String,
+    },
+
+    /// Brace range kept as literal text because `nobrace` is set
+    /// e.g., `file{1..3}.txt` only matches a file named exactly that
+    LiteralBraceRange { pattern: String, suggestion: String },
+
     /// Empty pattern won't match anything
     EmptyPattern,
 
@@ -2757,6 +3602,29 @@ impl PatternWarning {
             } => {
                 format!("Pattern `{pattern}` has trailing spaces. Did you mean `{suggestion}`?")
             }
+            PatternWarning::ParentOfRoot {
+                pattern,
+                suggestion,
+            } => {
+                format!(
+                    "Pattern `{pattern}` uses `..` to go above the filesystem root, where it has no effect. Did you mean `{suggestion}`?"
+                )
+            }
+            PatternWarning::ImpossiblePattern {
+                pattern,
+                reason,
+                suggestion,
+            } => {
+                format!("Pattern `{pattern}` contains {reason}. Did you mean `{suggestion}`?")
+            }
+            PatternWarning::LiteralBraceRange {
+                pattern,
+                suggestion,
+            } => {
+                format!(
+                    "Pattern `{pattern}` has a brace range that nobrace keeps as literal text. Write `{suggestion}` to match it literally, or drop nobrace to expand it."
+                )
+            }
             PatternWarning::EmptyPattern => "Empty pattern will not match any files.".to_string(),
             PatternWarning::NullBytes { pattern } => {
                 format!(
@@ -2766,6 +3634,49 @@ impl PatternWarning {
             }
         }
     }
+
+    /// What sets this warning apart from others of the same kind, if anything
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            PatternWarning::PerformanceWarning { reason, .. }
+            | PatternWarning::ImpossiblePattern { reason, .. } => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// Get a human-readable message for this kind of warning that doesn't name
+    /// the pattern, for reporting many warnings at once
+    pub fn summary(&self) -> String {
+        match self {
+            PatternWarning::EscapedWildcardAtStart { .. } => {
+                "Pattern starts with an escaped wildcard and won't match anything.".to_string()
+            }
+            PatternWarning::DoubleEscaped { .. } => {
+                "Pattern has double-escaped characters.".to_string()
+            }
+            PatternWarning::BackslashOnWindows { .. } => {
+                "Pattern uses backslashes which are escape characters. For Windows paths, use forward slashes or set `windowsPathsNoEscape: true`.".to_string()
+            }
+            PatternWarning::PerformanceWarning { reason, .. } => {
+                format!("Pattern may be slow: {reason}.")
+            }
+            PatternWarning::TrailingSpaces { .. } => "Pattern has trailing spaces.".to_string(),
+            PatternWarning::ParentOfRoot { .. } => {
+                "Pattern uses `..` to go above the filesystem root, where it has no effect."
+                    .to_string()
+            }
+            PatternWarning::ImpossiblePattern { reason, .. } => {
+                format!("Pattern contains {reason}.")
+            }
+            PatternWarning::LiteralBraceRange { .. } => {
+                "Pattern has a brace range that nobrace keeps as literal text.".to_string()
+            }
+            PatternWarning::EmptyPattern => "Empty pattern will not match any files.".to_string(),
+            PatternWarning::NullBytes { .. } => {
+                "Pattern contains null bytes which are invalid in file paths.".to_string()
+            }
+        }
+    }
 }
 
 /// Analyze a pattern and return any warnings about potential issues.
@@ -2849,6 +3760,46 @@ pub fn analyze_pattern(
         }
     }
 
+    // Check for `..` directly above an absolute root
+    let separators = if windows_paths_no_escape {
+        Cow::Owned(pattern.replace('\\', "/"))
+    } else {
+        Cow::Borrowed(pattern)
+    };
+    if let Some(suggestion) = clamp_parent_of_root(&separators, is_windows) {
+        warnings.push(PatternWarning::ParentOfRoot {
+            pattern: pattern.to_string(),
+            suggestion,
+        });
+    }
+
+    // Check for parts that can never match
+    let escapes = !windows_paths_no_escape;
+    if let Some((start, lo, hi)) = find_reversed_range(pattern, escapes) {
+        let mut suggestion = pattern.to_string();
+        suggestion.replace_range(
+            start..start + lo.len_utf8() + 1 + hi.len_utf8(),
+            &format!("{hi}-{lo}"),
+        );
+        warnings.push(PatternWarning::ImpossiblePattern {
+            pattern: pattern.to_string(),
+            reason: format!("the reversed range `{lo}-{hi}`, which matches no characters"),
+            suggestion,
+        });
+    }
+    if let Some(start) = find_empty_braces(pattern, escapes) {
+        let mut suggestion = pattern.to_string();
+        suggestion.replace_range(start..start + 2, "");
+        if suggestion.is_empty() {
+            suggestion = "\\{\\}".to_string();
+        }
+        warnings.push(PatternWarning::ImpossiblePattern {
+            pattern: pattern.to_string(),
+            reason: "the empty alternation `{}`, which only matches a literal `{}`".to_string(),
+            suggestion,
+        });
+    }
+
     // Check for performance issues
     let globstar_count = pattern.matches("**").count();
     if globstar_count > 2 {
@@ -2875,6 +3826,86 @@ pub fn analyze_pattern(
     warnings
 }
 
+/// Find the first reversed range (like `z-a`) inside a character class.
+/// Returns the byte offset of the range and its two endpoints.
+/// `parse_character_class` silently drops such ranges.
+fn find_reversed_range(pattern: &str, escapes: bool) -> Option<(usize, char, char)> {
+    let chars: Vec<(usize, char)> = pattern.char_indices().collect();
+    let plain: Vec<char> = pattern.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i].1;
+        if escapes && c == '\\' {
+            i += 2;
+            continue;
+        }
+        if c != '[' {
+            i += 1;
+            continue;
+        }
+
+        // Inside a class: scan to the closing `]` (a leading `]` is literal)
+        let mut j = i + 1;
+        if j < chars.len() && matches!(chars[j].1, '!' | '^') {
+            j += 1;
+        }
+        let class_start = j;
+        let mut found = None;
+        while j < chars.len() {
+            let c = chars[j].1;
+            if c == ']' && j > class_start {
+                break;
+            }
+            if escapes && c == '\\' {
+                j += 2;
+                continue;
+            }
+            if c == '[' && j + 1 < chars.len() && chars[j + 1].1 == ':' {
+                if let Some(end) = find_posix_class_end(&plain, j) {
+                    j = end + 1;
+                    continue;
+                }
+            }
+            if j + 2 < chars.len() && chars[j + 1].1 == '-' && chars[j + 2].1 != ']' {
+                let hi = chars[j + 2].1;
+                if found.is_none() && hi < c && !(escapes && hi == '\\') {
+                    found = Some((chars[j].0, c, hi));
+                }
+                j += 3;
+                continue;
+            }
+            j += 1;
+        }
+        if j >= chars.len() {
+            // No closing bracket: not a class, `[` is literal
+            i += 1;
+            continue;
+        }
+        if found.is_some() {
+            return found;
+        }
+        i = j + 1;
+    }
+    None
+}
+
+/// Find the byte offset of the first unescaped `{}`.
+fn find_empty_braces(pattern: &str, escapes: bool) -> Option<usize> {
+    let bytes = pattern.as_bytes();
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        if escapes && bytes[i] == b'\\' {
+            i += 2;
+            continue;
+        }
+        if bytes[i] == b'{' && bytes[i + 1] == b'}' {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
 /// Analyze multiple patterns and return all warnings.
 pub fn analyze_patterns(
     patterns: &[String],
@@ -2887,6 +3918,30 @@ pub fn analyze_patterns(
         .collect()
 }
 
+/// Analyze multiple patterns and group the warnings by kind and reason.
+///
+/// A long pattern list that repeats one mistake yields a single group instead
+/// of one warning per pattern; warnings of one kind with different reasons
+/// (say, a reversed range and an empty `{}`) stay apart. Groups are ordered by
+/// their first warning, and each keeps its warnings in pattern order.
+pub fn analyze_patterns_grouped(
+    patterns: &[String],
+    windows_paths_no_escape: bool,
+    platform: Option<&str>,
+) -> Vec<Vec<PatternWarning>> {
+    let mut groups: Vec<Vec<PatternWarning>> = Vec::new();
+    for warning in analyze_patterns(patterns, windows_paths_no_escape, platform) {
+        let kind = std::mem::discriminant(&warning);
+        match groups.iter_mut().find(|group| {
+            std::mem::discriminant(&group[0]) == kind && group[0].reason() == warning.reason()
+        }) {
+            Some(group) => group.push(warning),
+            None => groups.push(vec![warning]),
+        }
+    }
+    groups
+}
+
 #[cfg(test)]
 mod warning_tests {
     use super::*;
@@ -2947,6 +4002,66 @@ mod warning_tests {
         assert!(matches!(&warnings[0], PatternWarning::NullBytes { .. }));
     }
 
+    #[test]
+    fn test_parent_of_root_warning() {
+        let warnings = analyze_pattern("/../etc/*", false, None);
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            &warnings[0],
+            PatternWarning::ParentOfRoot { suggestion, .. } if suggestion == "/etc/*"
+        ));
+
+        let warnings = analyze_pattern("C:\\..\\foo\\*", true, Some("win32"));
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            &warnings[0],
+            PatternWarning::ParentOfRoot { suggestion, .. } if suggestion == "C:/foo/*"
+        ));
+
+        assert!(analyze_pattern("../foo/*", false, None).is_empty());
+        assert!(analyze_pattern("/foo/../bar", false, None).is_empty());
+    }
+
+    #[test]
+    fn test_impossible_pattern_warning() {
+        let warnings = analyze_pattern("[z-a].txt", false, None);
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            &warnings[0],
+            PatternWarning::ImpossiblePattern { reason, suggestion, .. }
+                if reason.contains("`z-a`") && suggestion == "[a-z].txt"
+        ));
+
+        let warnings = analyze_pattern("src/{}*.js", false, None);
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            &warnings[0],
+            PatternWarning::ImpossiblePattern { suggestion, .. } if suggestion == "src/*.js"
+        ));
+        assert!(matches!(
+            &analyze_pattern("{}", false, None)[0],
+            PatternWarning::ImpossiblePattern { suggestion, .. } if suggestion == "\\{\\}"
+        ));
+
+        // Valid ranges, escapes and non-classes are fine
+        for pattern in [
+            "[a-z].txt",
+            "[a-a]",
+            "[!0-9]*",
+            "[[:alpha:]-]",
+            "[a-]",
+            "\\[z-a\\]",
+            "[z-a",
+            "{a,b}",
+            "\\{\\}",
+        ] {
+            assert!(
+                analyze_pattern(pattern, false, None).is_empty(),
+                "{pattern}"
+            );
+        }
+    }
+
     #[test]
     fn test_performance_warning_multiple_globstars() {
         let warnings = analyze_pattern("**/**/**/*.js", false, None);
@@ -3018,6 +4133,47 @@ mod warning_tests {
         let warnings = analyze_patterns(&patterns, false, None);
         assert_eq!(warnings.len(), 2); // escaped wildcard + performance
     }
+
+    #[test]
+    fn test_analyze_patterns_grouped_collapses_same_kind() {
+        let patterns = vec![
+            "**/**/**/*.ts".to_string(),
+            "\\*.js".to_string(),
+            "*.txt".to_string(),
+            "**/**/**/*.js".to_string(),
+        ];
+        let groups = analyze_patterns_grouped(&patterns, false, None);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].len(), 2);
+        assert!(groups[0]
+            .iter()
+            .all(|w| matches!(w, PatternWarning::PerformanceWarning { .. })));
+        assert!(matches!(
+            groups[0][1],
+            PatternWarning::PerformanceWarning { ref pattern, .. } if pattern == "**/**/**/*.js"
+        ));
+        assert_eq!(groups[1].len(), 1);
+        assert!(matches!(
+            groups[1][0],
+            PatternWarning::EscapedWildcardAtStart { .. }
+        ));
+
+        assert!(analyze_patterns_grouped(&["*.txt".to_string()], false, None).is_empty());
+
+        // The same kind with another reason is a separate group
+        let patterns = vec![
+            "[z-a].txt".to_string(),
+            "{}.txt".to_string(),
+            "[z-a].js".to_string(),
+        ];
+        let groups = analyze_patterns_grouped(&patterns, false, None);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].len(), 2);
+        assert_eq!(groups[1].len(), 1);
+        assert_eq!(groups[0][0].reason(), groups[0][1].reason());
+        assert_ne!(groups[0][0].reason(), groups[1][0].reason());
+        assert!(!groups[0][0].summary().contains("[z-a]"));
+    }
 }
 
 #[cfg(test)]
@@ -3120,6 +4276,23 @@ mod tests {
         assert_eq!(result, vec!["a1", "a2", "b1", "b2"]);
     }
 
+    #[test]
+    fn test_brace_empty_alternative() {
+        assert_eq!(
+            expand_braces("file{,.min}.js"),
+            vec!["file.js", "file.min.js"]
+        );
+        assert_eq!(expand_braces("a{,.bak}"), vec!["a", "a.bak"]);
+        assert_eq!(expand_braces("a{b,}c"), vec!["abc", "ac"]);
+        assert_eq!(expand_braces("a{,b}{,c}"), vec!["a", "ac", "ab", "abc"]);
+        assert_eq!(
+            expand_braces("src/{,lib/}*.js"),
+            vec!["src/*.js", "src/lib/*.js"]
+        );
+        // Like brace-expansion, a top-level expansion that is empty is dropped
+        assert_eq!(expand_braces("{,foo}"), vec!["foo"]);
+    }
+
     #[test]
     fn test_brace_escaped() {
         // Escaped braces should not expand
@@ -3250,6 +4423,23 @@ mod tests {
         assert!(pattern.matches("src/a/b/c.ts"));
     }
 
+    #[test]
+    fn test_globstar_then_star_matches_direct_children() {
+        // The globstar may match zero segments, leaving `*` for the file itself
+        let pattern = Pattern::new("a/**/*");
+        assert!(pattern.matches("a/file.txt"));
+        assert!(pattern.matches("a/b"));
+        assert!(pattern.matches("a/b/file.txt"));
+        assert!(!pattern.matches("a"));
+        assert!(!pattern.matches("b/file.txt"));
+
+        let pattern = Pattern::new("a/**");
+        assert!(pattern.matches("a"));
+        assert!(pattern.matches("a/file.txt"));
+        assert!(pattern.matches("a/b"));
+        assert!(pattern.matches("a/b/file.txt"));
+    }
+
     #[test]
     fn test_globstar_at_start() {
         let pattern = Pattern::new("**/test.js");
@@ -3258,6 +4448,52 @@ mod tests {
         assert!(pattern.matches("a/b/c/test.js"));
     }
 
+    #[test]
+    fn test_double_star_inside_segment() {
+        // `**` is only a globstar when it is the whole segment; otherwise it is `*`
+        let pattern = Pattern::new("a**b.js");
+        assert!(pattern.matches("ab.js"));
+        assert!(pattern.matches("axyzb.js"));
+        assert!(!pattern.matches("a/b.js"));
+        assert!(!pattern.matches("ax/yb.js"));
+
+        let pattern = Pattern::new("**.js");
+        assert!(pattern.matches("a.js"));
+        assert!(!pattern.matches("src/a.js"));
+
+        let pattern = Pattern::new("src/**.js");
+        assert!(pattern.matches("src/a.js"));
+        assert!(!pattern.matches("src/lib/a.js"));
+
+        let pattern = Pattern::new("a**/b");
+        assert!(pattern.matches("a/b"));
+        assert!(pattern.matches("ax/b"));
+        assert!(!pattern.matches("a/x/b"));
+    }
+
+    #[test]
+    fn test_extglob_alternative_allows_dotfile() {
+        let no_dot = |raw: &str| {
+            let mut pattern = Pattern::new(raw);
+            pattern.set_dot(Some(false));
+            pattern
+        };
+
+        let pattern = no_dot("@(.env|config)");
+        assert!(pattern.allows_dotfile(".env"));
+        assert!(pattern.matches(".env"));
+        assert!(pattern.matches("config"));
+
+        assert!(no_dot("?(.)hidden").matches(".hidden"));
+        assert!(no_dot("?(.)hidden").matches("hidden"));
+        assert!(no_dot("src/+(.env|.config)").matches("src/.config"));
+        assert!(no_dot("**/@(.env|x)").allows_dotfile("src/.env"));
+
+        // Alternatives that can't start with a dot still exclude dotfiles
+        assert!(!no_dot("@(env|config)").allows_dotfile(".env"));
+        assert!(!no_dot("!(.env)").allows_dotfile(".other"));
+    }
+
     #[test]
     fn test_multiple_wildcards() {
         let pattern = Pattern::new("*.test.*.ts");
@@ -3335,8 +4571,19 @@ mod tests {
         let pattern = Pattern::new("!(foo).js");
         assert!(pattern.matches("bar.js"), "bar.js should match");
         assert!(pattern.matches("baz.js"), "baz.js should match");
-        // Note: the full glob compatibility for !(pattern).suffix requires
-        // more complex handling that will be addressed in Phase 4
+        assert!(pattern.matches("foos.js"), "foos.js should match");
+        // The rest of the segment is part of what's negated
+        assert!(!pattern.matches("foo.js"), "foo.js should not match");
+        assert!(!pattern.matches("bar.ts"));
+
+        let pattern = Pattern::new("src/!(index).ts");
+        assert!(pattern.matches("src/main.ts"));
+        assert!(!pattern.matches("src/index.ts"));
+
+        let pattern = Pattern::new("!(a)-!(b).txt");
+        assert!(pattern.matches("x-y.txt"));
+        assert!(!pattern.matches("a-y.txt"));
+        assert!(!pattern.matches("x-b.txt"));
     }
 
     #[test]
@@ -3396,6 +4643,32 @@ mod tests {
         assert!(Pattern::with_options("*(a|b)", true).has_magic());
     }
 
+    #[test]
+    fn test_extglob_no_negation_option() {
+        let opts = PatternOptions {
+            no_negation: true,
+            ..Default::default()
+        };
+
+        // `!(...)` is matched literally
+        let pattern = Pattern::with_pattern_options("!(foo)", opts.clone());
+        assert!(pattern.matches("!(foo)"));
+        assert!(!pattern.matches("bar"));
+        assert!(!pattern.has_magic());
+        let pattern = Pattern::with_pattern_options("src/!(test).js", opts.clone());
+        assert!(pattern.matches("src/!(test).js"));
+        assert!(!pattern.matches("src/main.js"));
+
+        // Other extglobs still work
+        let pattern = Pattern::with_pattern_options("@(a|b).ts", opts.clone());
+        assert!(pattern.matches("a.ts"));
+        assert!(pattern.matches("b.ts"));
+        assert!(!pattern.matches("c.ts"));
+        let pattern = Pattern::with_pattern_options("+(x)-!(y)", opts);
+        assert!(pattern.matches("xx-!(y)"));
+        assert!(!pattern.matches("xx-z"));
+    }
+
     #[test]
     fn test_extglob_noext_option() {
         // With noext, extglob syntax is treated literally
@@ -3544,6 +4817,35 @@ mod tests {
         assert!(!pattern.matches("d"));
     }
 
+    #[test]
+    fn test_char_class_never_matches_separator() {
+        // Ranges, POSIX classes and negations stay within one segment
+        assert!(!Pattern::new("x[%-0]y").matches("x/y"));
+        assert!(Pattern::new("x[%-0]y").matches("x.y"));
+        assert!(!Pattern::new("x[[:punct:]]y").matches("x/y"));
+        assert!(!Pattern::new("x[!a]y").matches("x/y"));
+        assert!(Pattern::new("x[!a]y").matches("x-y"));
+        // A class can't span segments, so its `[` is literal
+        assert!(!Pattern::new("a[/]b").matches("a/b"));
+        assert!(Pattern::new("a[/]b").matches("a[/]b"));
+        // `&&` is literal text, not a set operation
+        assert!(Pattern::new("x[a&&b]y").matches("x&y"));
+
+        let dotted = |p: &str| {
+            Pattern::with_pattern_options(
+                p,
+                PatternOptions {
+                    separator: Some('.'),
+                    ..Default::default()
+                },
+            )
+        };
+        assert!(!dotted("a[.]b").matches("a.b"));
+        assert!(!dotted("a[!x]b").matches("a.b"));
+        assert!(dotted("a[!x]b").matches("a/b"));
+        assert!(!dotted("a[--/]b").matches("a.b"));
+    }
+
     #[test]
     fn test_char_class_escaped_bracket() {
         // Escaped bracket inside class
@@ -3708,6 +5010,39 @@ mod tests {
         assert!(!pattern2.matches("foo.txt"));
     }
 
+    #[test]
+    fn test_escaped_globstar_is_a_literal_name() {
+        for windows_paths_no_escape in [false, true] {
+            let escaped = escape_pattern("**", windows_paths_no_escape);
+            assert_eq!(unescape_pattern(&escaped, windows_paths_no_escape), "**");
+
+            let pattern = Pattern::with_pattern_options(
+                &escaped,
+                PatternOptions {
+                    windows_paths_no_escape,
+                    ..Default::default()
+                },
+            );
+            assert!(pattern.matches("**"), "{escaped}");
+            assert!(!pattern.matches("foo"), "{escaped}");
+            assert!(!pattern.matches("a/b"), "{escaped}");
+            assert!(!pattern.matches("a/**"), "{escaped}");
+        }
+
+        // Unescaped, `**` is always the globstar
+        let globstar = Pattern::new("**");
+        assert!(globstar.matches("foo"));
+        assert!(globstar.matches("a/b"));
+        assert!(globstar.matches("**"));
+        let nested = Pattern::new("a/**");
+        assert!(nested.matches("a/b/c"));
+        assert!(nested.matches("a/**"));
+        let escaped_nested = Pattern::new(&format!("a/{}", escape_pattern("**", false)));
+        assert!(escaped_nested.matches("a/**"));
+        assert!(!escaped_nested.matches("a/b"));
+        assert!(!escaped_nested.matches("a/b/c"));
+    }
+
     #[test]
     fn test_trailing_backslash() {
         // Trailing backslash should be literal
@@ -3723,6 +5058,45 @@ mod tests {
         assert!(!pattern.matches("foobar"));
     }
 
+    #[test]
+    fn test_static_path_and_prefix_drop_escapes() {
+        assert_eq!(
+            Pattern::new(r"back\\slash.txt").static_path().as_deref(),
+            Some(r"back\slash.txt")
+        );
+        assert_eq!(Pattern::new(r"a\*b").static_path().as_deref(), Some("a*b"));
+        assert_eq!(
+            Pattern::new(r"dir\\x/*.js").literal_prefix().as_deref(),
+            Some(r"dir\x")
+        );
+    }
+
+    #[test]
+    fn test_expand_braces_limited() {
+        assert_eq!(
+            expand_braces_limited("{a,b}{c,d}", 4),
+            Some(vec![
+                "ac".to_string(),
+                "ad".to_string(),
+                "bc".to_string(),
+                "bd".to_string()
+            ])
+        );
+        assert_eq!(expand_braces_limited("{a,b}{c,d}", 3), None);
+        assert_eq!(expand_braces_limited("{1..10}", 9), None);
+        assert_eq!(expand_braces_limited("plain", 0), None);
+        // Would be 2^60 patterns; the bound keeps this instant
+        assert_eq!(expand_braces_limited(&"{a,b}".repeat(60), 100_000), None);
+        // A zero step must terminate
+        assert_eq!(expand_braces("{1..3..0}"), vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_expand_braces_without_pair_keeps_escapes() {
+        assert_eq!(expand_braces(r"a\\b"), vec![r"a\\b"]);
+        assert_eq!(expand_braces(r"a\\{b,c}"), vec![r"a\b", r"a\c"]);
+    }
+
     #[test]
     fn test_escape_has_magic() {
         // Escaped chars should not be magic
@@ -3813,6 +5187,22 @@ mod tests {
         assert_eq!(escape_pattern("*.{js,ts}", false), r"\*.{js,ts}");
     }
 
+    #[test]
+    fn test_escape_pattern_with_braces() {
+        // With magicalBraces the result is never brace-expanded
+        assert_eq!(escape_pattern_with_braces("{a,b}", false), r"\{a,b\}");
+        assert_eq!(
+            escape_pattern_with_braces("*.{js,ts}", false),
+            r"\*.\{js,ts\}"
+        );
+        assert_eq!(escape_pattern_with_braces("{a,b}", true), "[{]a,b[}]");
+        for windows_paths_no_escape in [false, true] {
+            let escaped = escape_pattern_with_braces("{a,b}", windows_paths_no_escape);
+            assert_eq!(expand_braces(&escaped), vec!["{a,b}"]);
+            assert_eq!(unescape_pattern(&escaped, windows_paths_no_escape), "{a,b}");
+        }
+    }
+
     #[test]
     fn test_escape_pattern_extglob_prefix_not_escaped() {
         // +, @, ! are not escaped, only the parentheses
@@ -3884,10 +5274,86 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_escape_unescape_roundtrip_all_magic_chars() {
+        // Every string over the magic characters (plus a few ordinary ones)
+        // must survive escape -> unescape with and without magicalBraces, and
+        // the brace-escaped form must match the original as a literal after
+        // brace expansion.
+        const ALPHABET: &[char] = &[
+            '{', '}', '(', ')', '!', '+', '@', '[', ']', '*', '?', '|', ',', 'a',
+        ];
+        let mut inputs = vec![String::new()];
+        let mut frontier = vec![String::new()];
+        for _ in 0..3 {
+            frontier = frontier
+                .iter()
+                .flat_map(|s| ALPHABET.iter().map(move |c| format!("{s}{c}")))
+                .collect();
+            inputs.extend(frontier.iter().cloned());
+        }
+        inputs.extend(
+            [
+                "+(a|b)",
+                "@(x)",
+                "!(*.js)",
+                "{a,{b,c}}",
+                "{1..3}",
+                "[!a-z]",
+                "*(a|b)?",
+                "file[1].{js,ts}",
+            ]
+            .iter()
+            .map(|s| s.to_string()),
+        );
+
+        for windows_paths_no_escape in [false, true] {
+            let opts = PatternOptions {
+                windows_paths_no_escape,
+                platform: Some(
+                    if windows_paths_no_escape {
+                        "win32"
+                    } else {
+                        "linux"
+                    }
+                    .into(),
+                ),
+                ..Default::default()
+            };
+            for p in &inputs {
+                assert_eq!(
+                    unescape_pattern(
+                        &escape_pattern(p, windows_paths_no_escape),
+                        windows_paths_no_escape
+                    ),
+                    *p,
+                    "roundtrip failed for {p:?} (windowsPathsNoEscape={windows_paths_no_escape})"
+                );
+                let escaped = escape_pattern_with_braces(p, windows_paths_no_escape);
+                assert_eq!(
+                    unescape_pattern(&escaped, windows_paths_no_escape),
+                    *p,
+                    "brace roundtrip failed for {p:?} (windowsPathsNoEscape={windows_paths_no_escape})"
+                );
+
+                if p.is_empty() || p.len() > 3 {
+                    continue;
+                }
+                let expanded = expand_braces(&escaped);
+                assert_eq!(expanded.len(), 1, "{p:?} was brace-expanded: {expanded:?}");
+                assert!(
+                    Pattern::with_pattern_options(&expanded[0], opts.clone()).matches(p),
+                    "escaped {p:?} -> {escaped:?} does not match itself \
+                     (windowsPathsNoEscape={windows_paths_no_escape})"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_escaped_pattern_no_magic() {
         // After escaping, pattern should not have magic
-        let patterns = vec!["*.txt", "**/*.js", "file?.md", "[abc]"];
+        let patterns = vec!["*.txt", "**/*.js", "file?.md", "[abc]", "{a,b}", "+(a|b)"];
         for p in patterns {
             let escaped = escape_pattern(p, false);
             assert!(
@@ -3966,6 +5432,123 @@ mod tests {
         assert!(!pattern.is_drive());
     }
 
+    #[test]
+    fn test_collapse_repeated_slashes() {
+        assert_eq!(collapse_slashes("src//lib///*.ts"), "src/lib/*.ts");
+        assert_eq!(collapse_slashes("*//"), "*/");
+        assert_eq!(collapse_slashes("/a//b"), "/a/b");
+        assert!(matches!(collapse_slashes("src/*.ts"), Cow::Borrowed(_)));
+
+        // A leading `//` is a UNC root
+        assert_eq!(collapse_slashes("//server/share"), "//server/share");
+        assert_eq!(collapse_slashes("//server//share"), "//server/share");
+        assert_eq!(collapse_slashes("///x"), "//x");
+
+        assert_eq!(preprocess_pattern(".//src//*.ts"), "src/*.ts");
+        assert_eq!(preprocess_pattern(".//"), ".");
+
+        let pattern = Pattern::new("src//*.ts");
+        assert!(pattern.matches("src/main.ts"));
+        assert_eq!(pattern.parts().len(), 2);
+
+        let unc = Pattern::with_pattern_options(
+            "//server//share//file",
+            PatternOptions {
+                platform: Some("win32".to_string()),
+                ..Default::default()
+            },
+        );
+        assert!(unc.is_unc());
+        assert_eq!(unc.root(), "//server/share/");
+    }
+
+    #[test]
+    fn test_trailing_dot_segments_become_trailing_slash() {
+        for form in ["src/", "src//", "src/.", "src/./", "src/.//", "src/./."] {
+            assert_eq!(preprocess_pattern(form), "src/", "{form}");
+            let pattern = Pattern::new(form);
+            assert!(pattern.requires_dir(), "{form}");
+            assert!(pattern.matches("src"), "{form}");
+            assert!(!pattern.matches("src/."), "{form}");
+        }
+        assert_eq!(preprocess_pattern("*/."), "*/");
+        assert_eq!(preprocess_pattern("a/./b/."), "a/./b/");
+        assert!(matches!(preprocess_pattern("src/"), Cow::Borrowed(_)));
+
+        // `.` on its own, roots, `..` and escaped slashes are left alone
+        assert_eq!(preprocess_pattern("./."), ".");
+        assert_eq!(preprocess_pattern("/."), "/.");
+        assert_eq!(preprocess_pattern("//./"), "//./");
+        assert_eq!(preprocess_pattern("src/.."), "src/..");
+        assert_eq!(preprocess_pattern("src\\/."), "src\\/.");
+    }
+
+    #[test]
+    fn test_strip_trailing_dots_and_spaces() {
+        assert_eq!(strip_trailing_dots_and_spaces("foo."), "foo");
+        assert_eq!(strip_trailing_dots_and_spaces("foo . "), "foo");
+        assert_eq!(
+            strip_trailing_dots_and_spaces("dir./sub /*.js"),
+            "dir/sub/*.js"
+        );
+        // Dot-only segments and dots after magic characters are kept
+        assert!(matches!(
+            strip_trailing_dots_and_spaces("../*./[ab]./\\./a.b"),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(
+            strip_trailing_dots_and_spaces(".hidden/a.js"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_trailing_dot_and_space_match_stripped_name_on_windows() {
+        assert_eq!(preprocess_pattern("foo."), "foo");
+        assert!(Pattern::new("foo.").matches("foo"));
+        assert!(Pattern::new("dir /foo.").matches("dir/foo"));
+        assert!(Pattern::new("foo.").is_static());
+        assert!(Pattern::new("*.").matches("x."));
+    }
+
+    #[test]
+    fn test_pattern_parent_of_root_is_clamped() {
+        let build = |pattern: &str, platform: &str| {
+            Pattern::with_pattern_options(
+                pattern,
+                PatternOptions {
+                    platform: Some(platform.to_string()),
+                    ..Default::default()
+                },
+            )
+        };
+
+        let pattern = build("/../foo/*.txt", "linux");
+        assert!(pattern.is_absolute());
+        assert_eq!(pattern.root(), "/");
+        assert_eq!(pattern.glob_string(), "/foo/*.txt");
+        assert!(pattern.matches("/foo/a.txt"));
+        assert_eq!(build("/../../..//foo", "linux").glob_string(), "/foo");
+        assert_eq!(build("/..", "linux").root(), "/");
+        // Only `..` directly after the root is dropped
+        assert_eq!(build("/foo/../bar", "linux").glob_string(), "/foo/../bar");
+        assert_eq!(build("../foo", "linux").glob_string(), "../foo");
+
+        let pattern = build("C:/../foo/*.txt", "win32");
+        assert!(pattern.is_drive());
+        assert_eq!(pattern.root(), "C:/");
+        assert_eq!(pattern.glob_string(), "C:/foo/*.txt");
+        assert!(pattern.matches("C:/foo/a.txt"));
+
+        let pattern = build("//server/share/../../foo", "win32");
+        assert!(pattern.is_unc());
+        assert_eq!(pattern.glob_string(), "//server/share/foo");
+
+        // `C:` is not a drive on other platforms
+        assert_eq!(build("C:/../foo", "linux").glob_string(), "C:/../foo");
+    }
+
     #[test]
     fn test_pattern_not_absolute() {
         let pattern = Pattern::new("src/**/*.js");
@@ -4057,6 +5640,107 @@ mod tests {
         assert!(pattern.part(3).is_none());
     }
 
+    #[test]
+    fn test_fold_case() {
+        assert_eq!(fold_case("src/Main.TS"), "src/main.ts");
+        assert!(matches!(fold_case("already/lower"), Cow::Borrowed(_)));
+        assert_eq!(fold_case("STRAßE"), "straße");
+        assert_eq!(fold_case("STRAẞE"), "straße");
+        assert_eq!(fold_case("ΟΔΟΣ"), fold_case("οδος"));
+        assert_eq!(fold_case("\u{212A}"), "k"); // Kelvin sign
+        assert_eq!(fold_case("ſ"), "s"); // long s
+
+        // Length-changing mappings are not applied
+        assert_eq!(fold_case("ß"), "ß");
+        assert_eq!(fold_case("İ"), "İ");
+    }
+
+    #[test]
+    fn test_nocase_non_ascii_consistent() {
+        let nocase = PatternOptions {
+            nocase: true,
+            ..Default::default()
+        };
+        // The fast path (when it applies) must agree with the regex
+        let check = |pattern: &Pattern, path: &str| {
+            let slow = pattern.matches(path);
+            if let Some(fast) = pattern.matches_fast(path) {
+                assert_eq!(fast, slow, "{} vs {path}", pattern.raw());
+            }
+            slow
+        };
+
+        let strasse = Pattern::with_pattern_options("STRAßE.txt", nocase.clone());
+        assert!(check(&strasse, "straße.txt"));
+        assert!(check(&strasse, "STRAẞE.TXT"));
+        assert!(!check(&strasse, "strasse.txt"));
+        assert!(!check(&strasse, "STRASSE.txt"));
+
+        let umlaut_ext = Pattern::with_pattern_options("**/*.ÄX", nocase.clone());
+        assert!(check(&umlaut_ext, "a/b.äx"));
+        assert!(check(&umlaut_ext, "B.ÄX"));
+        assert!(!check(&umlaut_ext, "b.ax"));
+
+        // ASCII patterns still match non-ASCII case variants
+        let kelvin = Pattern::with_pattern_options("*.k", nocase.clone());
+        assert!(check(&kelvin, "a.\u{212A}"));
+        assert!(check(&kelvin, "a.K"));
+        let sigma = Pattern::with_pattern_options("ΟΔΟΣ/?.md", nocase.clone());
+        assert!(check(&sigma, "οδος/é.md"));
+        assert!(check(&sigma, "Οδος/É.MD"));
+
+        // Directory pruning folds the same way
+        let deep = Pattern::with_pattern_options("STRAßE/**/*.md", nocase.clone());
+        assert!(deep.could_match_in_dir("straße"));
+        assert!(deep.could_match_in_dir("STRAẞE/sub"));
+        assert!(!deep.could_match_in_dir("strasse"));
+
+        // Case-sensitive matching is unaffected
+        let exact = Pattern::new("STRAßE.txt");
+        assert!(!check(&exact, "straße.txt"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pattern_matches_os() {
+        use std::os::unix::ffi::OsStrExt;
+
+        // "caf\xe9.txt" is Latin-1, not UTF-8
+        let latin1 = OsStr::from_bytes(b"caf\xe9.txt");
+        assert!(Pattern::new("*.txt").matches_os(latin1));
+        assert!(Pattern::new("caf?.txt").matches_os(latin1));
+        assert!(!Pattern::new("caf??.txt").matches_os(latin1));
+        assert!(!Pattern::new("café.txt").matches_os(latin1));
+        assert!(!Pattern::new("caf\u{FFFD}.txt").matches_os(latin1));
+
+        // One `?` per invalid byte; separators are still honored
+        let nested = OsStr::from_bytes(b"src/\xff\xfe/main.rs");
+        assert!(Pattern::new("src/??/main.rs").matches_os(nested));
+        assert!(Pattern::new("src/**/*.rs").matches_os(nested));
+        assert!(!Pattern::new("src/*.rs").matches_os(nested));
+
+        // Valid UTF-8 behaves exactly like `matches`
+        let utf8 = OsStr::new("src/café.rs");
+        assert!(Pattern::new("src/café.rs").matches_os(utf8));
+        assert!(Pattern::new("src/caf?.rs").matches_os(utf8));
+    }
+
+    #[test]
+    fn test_pattern_basename_only() {
+        let mut pattern = Pattern::new("*.ts");
+        assert!(!pattern.matches("a/b/c.ts"));
+        pattern.set_basename_only(true);
+        assert!(pattern.matches("a/b/c.ts"));
+        assert_eq!(pattern.matches_fast("a/b/c.ts"), Some(true));
+        assert!(!pattern.matches("a/b.ts/c.js"));
+
+        let mut pattern = Pattern::new("**/foo.ts");
+        pattern.set_basename_only(true);
+        assert!(pattern.matches("foo.ts"));
+        assert!(pattern.matches("x/y/foo.ts"));
+        assert!(!pattern.matches("x/foo.ts/bar"));
+    }
+
     #[test]
     fn test_pattern_for_match_base() {
         // Pattern without slash - should prepend **/
@@ -4245,6 +5929,41 @@ mod tests {
             "foo//bar/* should have magic"
         );
     }
+
+    #[test]
+    fn test_dot_segments_only_match_literally() {
+        // `.` and `..` are ordinary literal segments, never resolved
+        let pat = Pattern::new("a/../b.txt");
+        assert!(pat.matches("a/../b.txt"));
+        assert!(!pat.matches("b.txt"));
+        assert!(!pat.matches("a/b.txt"));
+        let pat = Pattern::new("a/./b.txt");
+        assert!(pat.matches("a/./b.txt"));
+        assert!(!pat.matches("a/b.txt"));
+        assert!(Pattern::new("*/../b/*.txt").matches("a/../b/c.txt"));
+
+        // Wildcards and globstars never stand in for them, though they do
+        // match other dot-prefixed names at this level
+        for (pattern, path) in [
+            ("a/*/b.txt", "a/../b.txt"),
+            ("a/*/b.txt", "a/./b.txt"),
+            ("a/.*/b.txt", "a/../b.txt"),
+            ("a/??/b.txt", "a/../b.txt"),
+            ("a/**/b.txt", "a/../b.txt"),
+            ("a/**", "a/.."),
+            ("**", "../b.txt"),
+            ("**/*.txt", "../b.txt"),
+            ("*", ".."),
+        ] {
+            let pat = Pattern::new(pattern);
+            assert!(!pat.matches(path), "{pattern} vs {path}");
+            assert_ne!(pat.matches_fast(path), Some(true), "{pattern} vs {path}");
+        }
+
+        let pat = Pattern::new("a/*/b.txt");
+        assert!(pat.matches("a/.../b.txt"));
+        assert!(pat.matches("a/.x/b.txt"));
+    }
 }
 
 #[cfg(test)]
@@ -4354,6 +6073,33 @@ mod test_nocase {
         assert!(pat.matches("B"));
     }
 
+    #[test]
+    fn test_nocase_character_range() {
+        // Ranges are folded with the rest of the pattern, and (?i) covers the rest
+        let pat = make_pattern("[a-z]*.txt", true);
+        assert!(pat.matches("FOO.TXT"));
+        assert!(pat.matches("Foo.txt"));
+        assert!(!pat.matches("1foo.txt"));
+
+        let pat = make_pattern("[A-Z]*.txt", true);
+        assert!(pat.matches("foo.txt"));
+
+        // A negated range excludes both cases
+        let pat = make_pattern("[^a-z]*.txt", true);
+        assert!(!pat.matches("FOO.TXT"));
+        assert!(pat.matches("1foo.txt"));
+
+        // Per-segment regexes (used for directory pruning) carry nocase too
+        let pat = make_pattern("src/[a-z]*/*.js", true);
+        assert!(pat.parts()[1].matches("LIB"));
+        assert!(pat.parts()[1].matches_fast("LIB", true));
+        assert!(pat.could_match_in_dir("SRC/LIB"));
+        assert!(pat.matches("SRC/LIB/A.JS"));
+
+        let pat = make_pattern("[a-z]*.txt", false);
+        assert!(!pat.matches("FOO.TXT"));
+    }
+
     #[test]
     fn test_nocase_question_mark() {
         // Question mark with case-insensitive
@@ -4371,6 +6117,38 @@ mod test_nocase {
         assert!(pat.matches("FILE.TXT"));
         assert!(pat.matches("file.txt"));
     }
+
+    #[test]
+    fn test_nocase_escaped_literals() {
+        // Escaped magic characters stay literal and still fold case, on both
+        // the regex and the fast path
+        for windows_paths_no_escape in [false, true] {
+            for (prefix, raw, hit, miss) in [
+                ("", "Foo*.TXT", "foo*.txt", "foo.txt"),
+                ("**/", "Foo*.TXT", "a/FOO*.txt", "a/foo-bar.txt"),
+                ("", "Bar(1).JS", "bar(1).js", "bar1.js"),
+            ] {
+                let escaped = format!("{prefix}{}", escape_pattern(raw, windows_paths_no_escape));
+                let pat = Pattern::with_pattern_options(
+                    &escaped,
+                    PatternOptions {
+                        nocase: true,
+                        windows_paths_no_escape,
+                        ..Default::default()
+                    },
+                );
+                assert!(pat.matches(hit), "{escaped} should match {hit}");
+                assert!(!pat.matches(miss), "{escaped} should not match {miss}");
+                assert_ne!(pat.matches_fast(hit), Some(false), "{escaped} fast {hit}");
+                assert_ne!(pat.matches_fast(miss), Some(true), "{escaped} fast {miss}");
+            }
+        }
+
+        // Without nocase an escaped literal still needs the exact case
+        let pat = make_pattern(r"**/Foo\*.TXT", false);
+        assert_eq!(pat.matches_fast("a/Foo*.TXT"), Some(true));
+        assert_eq!(pat.matches_fast("a/foo*.txt"), Some(false));
+    }
 }
 
 #[cfg(test)]
@@ -4396,6 +6174,33 @@ mod test_could_match_in_dir {
         assert!(pattern.could_match_in_dir("src/lib/a/b/c"));
     }
 
+    #[test]
+    fn test_absolute_root_matches_as_unit() {
+        let opts = PatternOptions {
+            platform: Some("win32".to_string()),
+            ..Default::default()
+        };
+
+        // UNC root spans the server and share segments
+        let pattern = Pattern::with_pattern_options("//server/share/**/*.txt", opts.clone());
+        assert!(pattern.could_match_in_dir("//server/share/"));
+        assert!(pattern.could_match_in_dir("//server/share/dir"));
+        assert!(pattern.could_match_in_dir("//server/share/a/b"));
+
+        let pattern = Pattern::with_pattern_options("//server/share/src/*.txt", opts.clone());
+        assert!(pattern.could_match_in_dir("//server/share/src"));
+        assert!(!pattern.could_match_in_dir("//server/share/other"));
+
+        // Drive and posix roots
+        let pattern = Pattern::with_pattern_options("C:/x/**/*.txt", opts);
+        assert!(pattern.could_match_in_dir("C:/x/dir"));
+        assert!(!pattern.could_match_in_dir("C:/y"));
+
+        let pattern = Pattern::new("/tmp/**");
+        assert!(pattern.could_match_in_dir("/tmp/a"));
+        assert!(!pattern.could_match_in_dir("/var"));
+    }
+
     #[test]
     fn test_literal_prefix_no_match() {
         // Pattern with literal prefix should NOT match other directories
@@ -4633,14 +6438,14 @@ mod test_simple_match {
     #[test]
     fn test_pattern_part_matches_fast() {
         // Test the PatternPart::matches_fast method
-        let regex = segment_to_regex("*", false, false);
+        let regex = segment_to_regex("*", false, false, false, '/');
         let part = PatternPart::Magic("*".to_string(), regex, Some(SimpleMatch::Any));
         assert!(part.matches_fast("anything", false));
         assert!(part.matches_fast("foo", false));
         assert!(!part.matches_fast("foo/bar", false)); // Should not match segments with /
 
         // Prefix pattern
-        let regex = segment_to_regex("foo*", false, false);
+        let regex = segment_to_regex("foo*", false, false, false, '/');
         let part = PatternPart::Magic(
             "foo*".to_string(),
             regex,
@@ -4653,7 +6458,7 @@ mod test_simple_match {
         assert!(!part.matches_fast("barfoo", false));
 
         // Suffix pattern
-        let regex = segment_to_regex("*.js", false, false);
+        let regex = segment_to_regex("*.js", false, false, false, '/');
         let part = PatternPart::Magic(
             "*.js".to_string(),
             regex,
@@ -4666,7 +6471,7 @@ mod test_simple_match {
         assert!(!part.matches_fast("jsfile", false));
 
         // Prefix + Suffix pattern
-        let regex = segment_to_regex("test*.spec", false, false);
+        let regex = segment_to_regex("test*.spec", false, false, false, '/');
         let part = PatternPart::Magic(
             "test*.spec".to_string(),
             regex,
@@ -4685,7 +6490,7 @@ mod test_simple_match {
     #[test]
     fn test_pattern_part_matches_fast_nocase() {
         // Test case-insensitive matching
-        let regex = segment_to_regex("foo*", false, true);
+        let regex = segment_to_regex("foo*", false, false, true, '/');
         let part = PatternPart::Magic(
             "foo*".to_string(),
             regex,
@@ -4718,6 +6523,46 @@ mod test_simple_match {
         assert!(!pattern.could_match_in_dir("packages/foo/lib")); // Wrong subdir (not src)
     }
 
+    #[test]
+    fn test_could_match_in_dir_negated_extglob() {
+        let pattern = Pattern::new("!(node_modules|dist)/**");
+        assert!(pattern.could_match_in_dir("src"));
+        assert!(pattern.could_match_in_dir("src/lib"));
+        assert!(pattern.could_match_in_dir("node_modules_old"));
+        assert!(!pattern.could_match_in_dir("node_modules"));
+        assert!(!pattern.could_match_in_dir("node_modules/pkg"));
+        assert!(!pattern.could_match_in_dir("dist"));
+
+        // The negation only applies to the first segment
+        let pattern = Pattern::new("!(node_modules)/**/*.js");
+        assert!(pattern.could_match_in_dir("src/node_modules"));
+        assert!(!pattern.could_match_in_dir("node_modules"));
+
+        assert!(pattern.matches("src/main.js"));
+        assert!(pattern.matches("src/node_modules/x.js"));
+        assert!(!pattern.matches("node_modules/x.js"));
+        assert!(!pattern.matches("node_modules/pkg/index.js"));
+        assert!(!pattern.matches("main.js"));
+    }
+
+    #[test]
+    fn test_globstar_in_middle_keeps_separator() {
+        let pattern = Pattern::new("src/**/*.js");
+        assert!(pattern.matches("src/a.js"));
+        assert!(pattern.matches("src/x/y/a.js"));
+        assert!(!pattern.matches("srcx.js"));
+        assert!(!pattern.matches("src.js"));
+
+        let pattern = Pattern::new("/**/x");
+        assert!(pattern.matches("/x"));
+        assert!(pattern.matches("/a/x"));
+        assert!(!pattern.matches("x"));
+
+        // A trailing globstar still matches the directory itself
+        assert!(Pattern::new("src/**").matches("src"));
+        assert!(!Pattern::new("src/**").matches("srcx"));
+    }
+
     #[test]
     fn test_simple_match_with_real_patterns() {
         // Test with realistic glob patterns
@@ -4837,6 +6682,84 @@ mod test_fast_path {
         }
     }
 
+    #[test]
+    fn test_root_level_fast_paths_reject_nested_paths() {
+        for raw in ["*.js", "*.{js,ts}", "package.json"] {
+            let pattern = Pattern::new(raw);
+            assert!(pattern.fast_path().is_fast(), "{raw}");
+            assert_eq!(pattern.matches_fast("src/package.js"), Some(false), "{raw}");
+            assert_eq!(
+                pattern.matches_fast("src/package.json"),
+                Some(false),
+                "{raw}"
+            );
+            assert!(!pattern.matches("src/package.json"), "{raw}");
+        }
+        assert_eq!(Pattern::new("*.js").matches_fast("foo.js"), Some(true));
+    }
+
+    #[test]
+    fn test_compound_suffix_not_extension_fast_path() {
+        // `*` never matches across the final `.`, so `*.d.ts` must not be
+        // reduced to an extension check on `ts`.
+        for raw in ["*.d.ts", "*.test.ts", "*.tar.gz"] {
+            let pattern = Pattern::new(raw);
+            assert!(
+                !matches!(pattern.fast_path(), FastPath::ExtensionOnly(_)),
+                "{raw} should not be ExtensionOnly, got {:?}",
+                pattern.fast_path()
+            );
+            let recursive = Pattern::new(&format!("**/{raw}"));
+            assert!(
+                !matches!(recursive.fast_path(), FastPath::RecursiveExtension(_)),
+                "**/{raw} should not be RecursiveExtension, got {:?}",
+                recursive.fast_path()
+            );
+        }
+
+        let pattern = Pattern::new("*.d.ts");
+        assert!(pattern.matches("foo.d.ts"));
+        assert!(!pattern.matches("foo.ts"));
+        assert!(!pattern.matches("foo.d.tsx"));
+
+        let pattern = Pattern::new("**/*.d.ts");
+        assert!(pattern.matches("src/types/foo.d.ts"));
+        assert!(!pattern.matches("src/types/foo.ts"));
+
+        // Alternatives with a dot in them disable the extension-set fast path.
+        for raw in ["*.{d.ts,js}", "**/*.{d.ts,js}"] {
+            let pattern = Pattern::new(raw);
+            assert!(
+                !matches!(
+                    pattern.fast_path(),
+                    FastPath::ExtensionSet(_) | FastPath::RecursiveExtensionSet(_)
+                ),
+                "{raw} should not be an extension set, got {:?}",
+                pattern.fast_path()
+            );
+        }
+    }
+
+    #[test]
+    fn test_recursive_literal_name_pattern() {
+        // **/<literal> should use RecursiveLiteralName fast-path
+        let pattern = Pattern::new("**/package.json");
+        assert!(
+            matches!(pattern.fast_path(), FastPath::RecursiveLiteralName(name) if name == "package.json"),
+            "**/package.json should be RecursiveLiteralName, got {:?}",
+            pattern.fast_path()
+        );
+
+        let pattern = Pattern::new("**/.gitignore");
+        assert!(
+            matches!(pattern.fast_path(), FastPath::RecursiveLiteralName(name) if name == ".gitignore")
+        );
+
+        // A literal after the globstar plus more segments is not a basename match
+        let pattern = Pattern::new("**/node_modules/package.json");
+        assert!(matches!(pattern.fast_path(), FastPath::None));
+    }
+
     #[test]
     fn test_no_fast_path_for_complex_patterns() {
         // Patterns with literal prefixes should not use fast-path
@@ -4921,6 +6844,37 @@ mod test_fast_path {
         assert_eq!(pattern.matches_fast("src/foo.ts"), Some(false));
     }
 
+    #[test]
+    fn test_matches_fast_recursive_literal_name() {
+        let pattern = Pattern::new("**/package.json");
+
+        // Should match at depth 0, 1 and n
+        assert_eq!(pattern.matches_fast("package.json"), Some(true));
+        assert_eq!(pattern.matches_fast("pkg/package.json"), Some(true));
+        assert_eq!(pattern.matches_fast("a/b/c/d/package.json"), Some(true));
+
+        // Fast path agrees with the regex path
+        for path in ["package.json", "pkg/package.json", "a/b/c/d/package.json"] {
+            assert!(pattern.matches(path), "regex should also match {path}");
+        }
+
+        // Only the full basename counts
+        assert_eq!(pattern.matches_fast("package.json.bak"), Some(false));
+        assert_eq!(pattern.matches_fast("pkg/my-package.json"), Some(false));
+        assert_eq!(pattern.matches_fast("package.json/index.js"), Some(false));
+        assert_eq!(pattern.matches_fast("PACKAGE.JSON"), Some(false)); // case-sensitive
+
+        let opts = PatternOptions {
+            nocase: true,
+            ..Default::default()
+        };
+        let pattern = Pattern::with_pattern_options("**/Package.JSON", opts);
+        assert!(
+            matches!(pattern.fast_path(), FastPath::RecursiveLiteralName(name) if name == "package.json")
+        );
+        assert_eq!(pattern.matches_fast("src/PACKAGE.json"), Some(true));
+    }
+
     #[test]
     fn test_matches_fast_recursive_extension_set() {
         let pattern = Pattern::new("**/*.{js,ts}");
@@ -5182,10 +7136,14 @@ mod test_fast_path {
         );
         assert_eq!(parse_suffix_pattern("*.d.ts"), Some(".d.ts".to_string()));
 
-        // Invalid patterns - not enough dots
-        assert_eq!(parse_suffix_pattern("*.ts"), None); // single extension
-        assert_eq!(parse_suffix_pattern("*ts"), None); // no dot at all
-        assert_eq!(parse_suffix_pattern("foo"), None); // no star
+        // Any literal suffix works (`*.ts` is normally caught as ExtensionOnly first)
+        assert_eq!(parse_suffix_pattern("*.ts"), Some(".ts".to_string()));
+        assert_eq!(parse_suffix_pattern("*ts"), Some("ts".to_string()));
+
+        // Invalid patterns - no star, nothing after it, or an extglob
+        assert_eq!(parse_suffix_pattern("foo"), None);
+        assert_eq!(parse_suffix_pattern("*"), None);
+        assert_eq!(parse_suffix_pattern("*(ts)"), None);
 
         // Invalid patterns - has magic
         assert_eq!(parse_suffix_pattern("*.test.*"), None);
@@ -5220,11 +7178,93 @@ mod test_fast_path {
         assert_eq!(parse_prefix_pattern("src/foo*"), None);
     }
 
+    // Tests for Contains fast-path and plain `*X` suffixes
+
+    #[test]
+    fn test_substring_fast_path_detection() {
+        let pattern = Pattern::new("*test*");
+        assert!(pattern.fast_path().is_fast());
+        assert!(matches!(pattern.fast_path(), FastPath::Contains(s) if s == "test"));
+
+        let pattern = Pattern::new("*.min.*");
+        assert!(pattern.fast_path().is_fast());
+        assert!(matches!(pattern.fast_path(), FastPath::Contains(s) if s == ".min."));
+
+        let pattern = Pattern::new("*rc");
+        assert!(pattern.fast_path().is_fast());
+        assert!(matches!(
+            pattern.fast_path(),
+            FastPath::SuffixMatch { suffix, recursive } if suffix == "rc" && !*recursive
+        ));
+
+        let pattern = Pattern::new("**/*_test");
+        assert!(matches!(
+            pattern.fast_path(),
+            FastPath::SuffixMatch { suffix, recursive } if suffix == "_test" && *recursive
+        ));
+
+        let pattern = Pattern::new("test*");
+        assert!(pattern.fast_path().is_fast());
+
+        // Extglobs, other magic and separators still need the regex
+        for raw in [
+            "*(a|b)",
+            "*(a)*",
+            "*a*b*",
+            "*a?*",
+            "*[ab]*",
+            "*a",
+            "src/*test*",
+        ] {
+            let pattern = Pattern::new(raw);
+            assert!(
+                !matches!(pattern.fast_path(), FastPath::Contains(_)),
+                "{raw} should not be Contains"
+            );
+        }
+        assert!(!Pattern::new("*(a|b)").fast_path().is_fast());
+        assert!(!Pattern::new("*a*b*").fast_path().is_fast());
+    }
+
+    #[test]
+    fn test_substring_fast_path_matching() {
+        let pattern = Pattern::new("*test*");
+        for (path, expected) in [
+            ("test", true),
+            ("my-test.js", true),
+            ("testing", true),
+            ("contest", true),
+            ("tes", false),
+            ("TEST.js", false),
+            ("src/test.js", false),
+        ] {
+            assert_eq!(pattern.matches_fast(path), Some(expected), "{path}");
+            assert_eq!(pattern.matches(path), expected, "{path}");
+        }
+
+        let pattern = Pattern::with_pattern_options(
+            "*Test*",
+            PatternOptions {
+                nocase: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(pattern.matches_fast("MY-TEST.js"), Some(true));
+        assert_eq!(pattern.matches_fast("my-tset.js"), Some(false));
+
+        let pattern = Pattern::new("*rc");
+        assert_eq!(pattern.matches_fast("npmrc"), Some(true));
+        assert_eq!(pattern.matches_fast("rc"), Some(true));
+        assert_eq!(pattern.matches_fast("rc.js"), Some(false));
+        assert_eq!(pattern.matches_fast("a/npmrc"), Some(false));
+    }
+
     #[test]
     fn test_fast_path_is_fast_with_new_variants() {
         assert!(FastPath::ExtensionOnly("js".to_string()).is_fast());
         assert!(FastPath::ExtensionSet(HashSet::new()).is_fast());
         assert!(FastPath::LiteralName("foo".to_string()).is_fast());
+        assert!(FastPath::RecursiveLiteralName("foo".to_string()).is_fast());
         assert!(FastPath::RecursiveExtension("js".to_string()).is_fast());
         assert!(FastPath::RecursiveExtensionSet(HashSet::new()).is_fast());
         assert!(FastPath::SuffixMatch {
@@ -5233,6 +7273,7 @@ mod test_fast_path {
         }
         .is_fast());
         assert!(FastPath::PrefixMatch("foo".to_string()).is_fast());
+        assert!(FastPath::Contains("foo".to_string()).is_fast());
         assert!(!FastPath::None.is_fast());
     }
 }
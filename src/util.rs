@@ -2,7 +2,9 @@
 //
 // This module contains helper functions used across the crate.
 
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+
+use crate::fs::{FileSystem, StdFileSystem};
 
 /// Strip the Windows extended-length path prefix (\\?\) from a path.
 /// On Windows, `canonicalize()` returns paths with this prefix.
@@ -40,6 +42,71 @@ pub fn strip_windows_extended_prefix(path: PathBuf) -> PathBuf {
     }
 }
 
+/// Convert an absolute Windows path to its extended-length form (`\\?\`), so
+/// `std::fs` calls on paths below it aren't limited to MAX_PATH (260 chars).
+/// Verbatim paths skip Windows' own normalization, so `/` separators and `.`/`..`
+/// segments are resolved here. Relative and already-verbatim paths are returned
+/// unchanged; [`strip_windows_extended_prefix`] undoes the conversion.
+///
+/// Examples:
+/// - `C:\Users\foo` -> `\\?\C:\Users\foo`
+/// - `C:/a/./b/../c` -> `\\?\C:\a\c`
+/// - `\\server\share\dir` -> `\\?\UNC\server\share\dir`
+/// - `/path/to/dir` -> `/path/to/dir` (unchanged on non-Windows)
+pub fn to_windows_extended_path(path: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        use std::ffi::{OsStr, OsString};
+        use std::path::{Component, Prefix};
+
+        let mut components = path.components();
+        let mut extended = match components.next() {
+            Some(Component::Prefix(prefix)) => match prefix.kind() {
+                Prefix::Disk(_) => {
+                    let mut s = OsString::from(r"\\?\");
+                    s.push(prefix.as_os_str());
+                    s
+                }
+                Prefix::UNC(server, share) => {
+                    let mut s = OsString::from(r"\\?\UNC\");
+                    s.push(server);
+                    s.push(r"\");
+                    s.push(share);
+                    s
+                }
+                // Verbatim or device paths already bypass MAX_PATH
+                _ => return path.to_path_buf(),
+            },
+            _ => return path.to_path_buf(),
+        };
+
+        let mut segments: Vec<&OsStr> = Vec::new();
+        for component in components {
+            match component {
+                Component::Normal(segment) => segments.push(segment),
+                Component::ParentDir => {
+                    segments.pop();
+                }
+                _ => {}
+            }
+        }
+
+        if segments.is_empty() {
+            extended.push(r"\");
+        }
+        for segment in segments {
+            extended.push(r"\");
+            extended.push(segment);
+        }
+        PathBuf::from(extended)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        path.to_path_buf()
+    }
+}
+
 /// Checks if a filename starts with a dot (hidden file)
 pub fn is_dot_file(path: &Path) -> bool {
     path.file_name()
@@ -48,6 +115,26 @@ pub fn is_dot_file(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
+/// Checks whether a file or directory has the Windows hidden attribute
+/// (`FILE_ATTRIBUTE_HIDDEN`). Symlinks are not followed. Always false on other
+/// platforms, which have no such attribute.
+pub fn has_hidden_attribute(path: &Path) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+
+        std::fs::symlink_metadata(path)
+            .is_ok_and(|meta| meta.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        false
+    }
+}
+
 /// Normalizes a path separator to forward slashes
 pub fn normalize_separator(path: &str) -> String {
     path.replace('\\', "/")
@@ -68,10 +155,234 @@ pub fn join_path(base: &str, path: &str) -> String {
     }
 }
 
+/// Strips `base` from an absolute forward-slash candidate path, so absolute paths
+/// can be matched against relative patterns. Relative candidates are returned
+/// unchanged; `None` means the candidate is absolute but not inside `base`.
+///
+/// Examples:
+/// - `/project/src/a.ts` with base `/project` -> `src/a.ts`
+/// - `/project/src/` with base `/project/` -> `src/`
+/// - `/project` with base `/project` -> `.`
+/// - `/projects/a.ts` with base `/project` -> `None`
+/// - `src/a.ts` with base `/project` -> `src/a.ts`
+pub fn strip_candidate_base<'a>(path: &'a str, base: &str, ignore_case: bool) -> Option<&'a str> {
+    let bytes = path.as_bytes();
+    let is_absolute = path.starts_with('/')
+        || (bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && &bytes[1..3] == b":/");
+    if !is_absolute {
+        return Some(path);
+    }
+
+    let base = base.trim_end_matches('/');
+    let head = path.get(..base.len())?;
+    let inside = if ignore_case {
+        head.eq_ignore_ascii_case(base)
+    } else {
+        head == base
+    };
+    if !inside {
+        return None;
+    }
+    match &path[base.len()..] {
+        "" | "/" => Some("."),
+        rest => rest.strip_prefix('/'),
+    }
+}
+
+/// Lexically normalizes a forward-slash path: drops `.` and empty segments and
+/// resolves `..` against the preceding segment. No filesystem access is done.
+///
+/// Examples:
+/// - `src/./lib/../main.ts` -> `src/main.ts`
+/// - `../a/../../b` -> `../../b`
+/// - `/a/../../b` -> `/b` (cannot go above the root)
+/// - `a/..` -> `.`
+pub fn normalize_lexically(path: &str) -> String {
+    normalize_lexically_with(path, '/')
+}
+
+/// [`normalize_lexically`] for keys whose segments are separated by `separator`.
+pub fn normalize_lexically_with(path: &str, separator: char) -> String {
+    let absolute = path.starts_with(separator);
+    let mut segments: Vec<&str> = Vec::new();
+
+    for segment in path.split(separator) {
+        match segment {
+            "" | "." => {}
+            ".." => match segments.last() {
+                Some(&"..") | None if !absolute => segments.push(".."),
+                Some(_) => {
+                    segments.pop();
+                }
+                None => {}
+            },
+            _ => segments.push(segment),
+        }
+    }
+
+    let joined = segments.join(separator.encode_utf8(&mut [0; 4]));
+    if absolute {
+        format!("{separator}{joined}")
+    } else if joined.is_empty() {
+        ".".to_string()
+    } else {
+        joined
+    }
+}
+
+/// Lexically normalizes a path: drops `.` components and resolves `..` against
+/// the preceding component, without resolving symlinks. `..` at the root stays
+/// at the root; leading `..` in a relative path are dropped, so it is meant for
+/// absolute paths.
+pub fn normalize_path_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Checks whether `path` resolves to a location inside `root`, which must be
+/// canonical. Symlinks anywhere in the path are resolved; a broken symlink is
+/// judged by its target, resolved lexically against the link's directory.
+pub fn resolves_within(path: &Path, root: &Path) -> bool {
+    resolves_within_on(&StdFileSystem, path, root)
+}
+
+/// Like `resolves_within`, but resolves `path` through `file_system`.
+pub fn resolves_within_on(file_system: &dyn FileSystem, path: &Path, root: &Path) -> bool {
+    match file_system.canonicalize(path) {
+        Ok(real) => strip_windows_extended_prefix(real).starts_with(root),
+        Err(_) => match file_system.read_link(path) {
+            Ok(target) => {
+                let parent = path.parent().unwrap_or_else(|| Path::new(""));
+                let joined = normalize_separator(&parent.join(target).to_string_lossy());
+                Path::new(&normalize_lexically(&joined)).starts_with(root)
+            }
+            // Not a symlink: nothing to resolve
+            Err(_) => true,
+        },
+    }
+}
+
+/// Computes the path that leads from directory `from` to `to`, using `..` to
+/// climb out of `from` where needed. Both paths should be absolute and
+/// canonical; no filesystem access is done. Returns an empty path when they
+/// are the same.
+///
+/// Examples:
+/// - `/a/b` to `/a/b/c/d` -> `c/d`
+/// - `/a/b` to `/a/x/y` -> `../x/y`
+/// - `/a/b` to `/a/b` -> ``
+pub fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<_> = from.components().collect();
+    let to: Vec<_> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..from.len() {
+        relative.push("..");
+    }
+    for component in &to[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
+/// Formats a Windows path the way glob does for `posix: true`: backslashes
+/// become `/`, and drive-letter absolute paths get the `//?/` prefix so they
+/// stay absolute when read as POSIX paths. UNC and verbatim roots keep their
+/// leading `//`. Only meaningful for Windows paths; the string is not checked
+/// against the current platform.
+///
+/// Examples:
+/// - `C:\foo\bar.txt` -> `//?/C:/foo/bar.txt`
+/// - `\\server\share\dir` -> `//server/share/dir`
+/// - `\\?\C:\foo` -> `//?/C:/foo`
+/// - `a\b.txt` -> `a/b.txt`
+pub fn windows_path_to_posix(path: &str) -> String {
+    let slashed = path.replace('\\', "/");
+    let bytes = slashed.as_bytes();
+    if bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'/' {
+        format!("//?/{slashed}")
+    } else {
+        slashed
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_relative_path() {
+        let rel = |from: &str, to: &str| relative_path(Path::new(from), Path::new(to));
+        assert_eq!(rel("/a/b", "/a/b/c/d"), PathBuf::from("c/d"));
+        assert_eq!(rel("/a/b", "/a/x/y"), PathBuf::from("../x/y"));
+        assert_eq!(rel("/a/b/c", "/x"), PathBuf::from("../../../x"));
+        assert_eq!(rel("/a/b", "/a"), PathBuf::from(".."));
+        assert_eq!(rel("/a/b", "/a/b"), PathBuf::new());
+    }
+
+    #[test]
+    fn test_strip_candidate_base() {
+        fn strip<'a>(path: &'a str, base: &str) -> Option<&'a str> {
+            strip_candidate_base(path, base, false)
+        }
+        assert_eq!(strip("/project/src/a.ts", "/project"), Some("src/a.ts"));
+        assert_eq!(strip("/project/src/a.ts", "/project/"), Some("src/a.ts"));
+        assert_eq!(strip("/project/src/", "/project"), Some("src/"));
+        assert_eq!(strip("/project", "/project"), Some("."));
+        assert_eq!(strip("/project/", "/project"), Some("."));
+        assert_eq!(strip("/a.ts", "/"), Some("a.ts"));
+        assert_eq!(strip("C:/project/a.ts", "C:/project"), Some("a.ts"));
+
+        // Outside the base, including a sibling sharing the name as a prefix
+        assert_eq!(strip("/projects/a.ts", "/project"), None);
+        assert_eq!(strip("/other/a.ts", "/project"), None);
+        assert_eq!(strip("/Project/a.ts", "/project"), None);
+        assert_eq!(
+            strip_candidate_base("/Project/a.ts", "/project", true),
+            Some("a.ts")
+        );
+
+        // Relative candidates are left alone
+        assert_eq!(strip("src/a.ts", "/project"), Some("src/a.ts"));
+    }
+
+    #[test]
+    fn test_windows_path_to_posix() {
+        // Drive-letter roots keep the drive and gain the //?/ prefix
+        assert_eq!(
+            windows_path_to_posix(r"C:\foo\bar.txt"),
+            "//?/C:/foo/bar.txt"
+        );
+        assert_eq!(windows_path_to_posix(r"d:\"), "//?/d:/");
+        assert_eq!(windows_path_to_posix("C:/foo"), "//?/C:/foo");
+
+        // UNC roots keep both leading slashes and the share
+        assert_eq!(
+            windows_path_to_posix(r"\\server\share\dir\f.txt"),
+            "//server/share/dir/f.txt"
+        );
+        assert_eq!(windows_path_to_posix(r"\\server\share\"), "//server/share/");
+
+        // Verbatim paths already carry their prefix
+        assert_eq!(windows_path_to_posix(r"\\?\C:\foo"), "//?/C:/foo");
+
+        // Relative paths only have their separators converted, even when the
+        // first segment is a single letter
+        assert_eq!(windows_path_to_posix(r"a\b.txt"), "a/b.txt");
+        assert_eq!(windows_path_to_posix(r"src\lib"), "src/lib");
+        assert_eq!(windows_path_to_posix("C:foo"), "C:foo");
+    }
+
     #[test]
     fn test_strip_windows_extended_prefix() {
         // On non-Windows, should return path unchanged
@@ -103,6 +414,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_to_windows_extended_path() {
+        let path = PathBuf::from("/home/user/dir");
+        assert_eq!(to_windows_extended_path(&path), path);
+
+        #[cfg(target_os = "windows")]
+        {
+            let cases = [
+                (r"C:\Users\foo", r"\\?\C:\Users\foo"),
+                ("C:/a/./b/../c", r"\\?\C:\a\c"),
+                (r"C:\", r"\\?\C:\"),
+                (r"\\server\share\dir", r"\\?\UNC\server\share\dir"),
+                (r"\\?\C:\already", r"\\?\C:\already"),
+                (r"relative\dir", r"relative\dir"),
+            ];
+            for (input, expected) in cases {
+                let extended = to_windows_extended_path(Path::new(input));
+                assert_eq!(extended, PathBuf::from(expected), "{input}");
+            }
+
+            // Round trip
+            let path = PathBuf::from(r"C:\Users\foo");
+            assert_eq!(
+                strip_windows_extended_prefix(to_windows_extended_path(&path)),
+                path
+            );
+        }
+    }
+
     #[test]
     fn test_is_dot_file() {
         assert!(is_dot_file(Path::new(".hidden")));
@@ -111,6 +451,15 @@ mod tests {
         assert!(!is_dot_file(Path::new("file.txt")));
     }
 
+    #[test]
+    fn test_has_hidden_attribute() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("file.txt");
+        std::fs::File::create(&file).unwrap();
+        assert!(!has_hidden_attribute(&file));
+        assert!(!has_hidden_attribute(&temp.path().join("missing")));
+    }
+
     #[test]
     fn test_normalize_separator() {
         assert_eq!(normalize_separator("foo\\bar"), "foo/bar");
@@ -126,4 +475,26 @@ mod tests {
         assert_eq!(join_path("", "bar"), "bar");
         assert_eq!(join_path("foo", ""), "foo");
     }
+
+    #[test]
+    fn test_normalize_lexically() {
+        assert_eq!(normalize_lexically("src/./main.ts"), "src/main.ts");
+        assert_eq!(normalize_lexically("src/./lib/../main.ts"), "src/main.ts");
+        assert_eq!(normalize_lexically("./a//b/"), "a/b");
+        assert_eq!(normalize_lexically("a/.."), ".");
+        assert_eq!(normalize_lexically("../a/../../b"), "../../b");
+        assert_eq!(normalize_lexically("/a/../../b"), "/b");
+        assert_eq!(normalize_lexically("/"), "/");
+    }
+
+    #[test]
+    fn test_normalize_path_lexically() {
+        let normalize = |path: &str| normalize_path_lexically(Path::new(path));
+        assert_eq!(
+            normalize("/tmp/rp/../rp/real/./f"),
+            PathBuf::from("/tmp/rp/real/f")
+        );
+        assert_eq!(normalize("/a/../../b"), PathBuf::from("/b"));
+        assert_eq!(normalize("/"), PathBuf::from("/"));
+    }
 }
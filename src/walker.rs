@@ -1,24 +1,36 @@
 // Directory walking and filesystem traversal
 
 use std::borrow::Cow;
-use std::path::{Path, PathBuf};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
 use walkdir::{DirEntry, WalkDir};
 
-use crate::cache::read_dir_cached;
+use crate::cache::{try_read_dir_cached, CachedDirEntry};
+use crate::fs::{FileSystem, FsMetadata, StdFileSystem};
+use crate::options::DirOrder;
+use crate::util::{resolves_within_on, strip_windows_extended_prefix, to_windows_extended_path};
 
 // Parallel walking support via jwalk (jwalk::WalkDir is used directly)
 
 /// Normalize path separators from backslash to forward slash.
-/// Returns Cow::Borrowed when no backslashes are present (avoids allocation).
+/// Returns Cow::Borrowed when no backslashes are present (avoids allocation)
+/// or when `preserve_backslashes` keeps them as literal filename characters.
 #[inline]
-fn normalize_path_str(path: &str) -> Cow<'_, str> {
-    if path.contains('\\') {
+pub(crate) fn normalize_path_str(path: &str, preserve_backslashes: bool) -> Cow<'_, str> {
+    if !preserve_backslashes && path.contains('\\') {
         Cow::Owned(path.replace('\\', "/"))
     } else {
         Cow::Borrowed(path)
     }
 }
 
+/// Depth past which no walker descends unless `hard_depth_limit` says otherwise.
+/// Keeps pathological or maliciously nested trees from running unbounded.
+pub const DEFAULT_HARD_DEPTH_LIMIT: usize = 4096;
+
 /// Options for directory walking
 #[derive(Debug, Clone, Default)]
 pub struct WalkOptions {
@@ -49,6 +61,52 @@ pub struct WalkOptions {
     /// This provides better integration with the macOS scheduler and Apple Silicon cores.
     /// On other platforms, this option is ignored.
     pub use_gcd: bool,
+    /// Sort each directory's entries by file name before yielding them.
+    /// Gives a stable, `ls`-like order within every directory without sorting
+    /// the whole result set afterwards.
+    pub sort_dir_entries: bool,
+    /// Keep `\\` in relative paths handed to the prune filter instead of treating
+    /// it as a separator. Only meaningful on non-Windows platforms, where `\\` is
+    /// an ordinary filename character.
+    pub preserve_backslashes: bool,
+    /// Only yield entries that resolve inside this (canonical) directory.
+    /// Symlinks whose real path lies elsewhere are dropped along with everything
+    /// beneath them, and followed links are not descended into.
+    pub confine_to: Option<PathBuf>,
+    /// Don't descend into directories on a different device than the walk root
+    /// (like `find -xdev`). Such directories are pruned along with their contents.
+    /// Devices are compared by `st_dev` on Unix, or as reported by a custom
+    /// `file_system`; elsewhere this has no effect.
+    pub one_file_system: bool,
+    /// Maximum number of directory handles the walker keeps open at once
+    /// (None = the walker's own default). Keeps deep or parallel walks from
+    /// running into `EMFILE` ("too many open files") under a low `ulimit -n`.
+    /// Directories that can't be opened are skipped rather than reported, so
+    /// exhausting descriptors would otherwise silently drop results.
+    pub max_open_dirs: Option<u32>,
+    /// The semaphore enforcing `max_open_dirs`. Set by the builder and shared by
+    /// every walker using a clone of these options, so concurrent walks (one per
+    /// base of a multi-base glob, say) draw on the same budget.
+    pub(crate) dir_handles: Option<Arc<DirHandleLimit>>,
+    /// Absolute depth the walker never descends past, whatever `max_depth` says
+    /// (None = `DEFAULT_HARD_DEPTH_LIMIT`). Unlike `max_depth` this is a safety
+    /// net: `Walker::reached_depth_limit` reports when it cut a walk short.
+    pub hard_depth_limit: Option<usize>,
+    /// Whether directories are yielded before (`PreOrder`, the default) or after
+    /// (`PostOrder`) the entries inside them. Post-order walks always read
+    /// depth-first, so `use_native_io` and `use_gcd` are ignored with it.
+    pub dir_order: DirOrder,
+    /// Read directories and metadata through this filesystem instead of the
+    /// real one (None = the real filesystem). The walk then makes no direct
+    /// filesystem calls; `parallel`, `cache`, `use_native_io` and `use_gcd` are
+    /// ignored, and `confine_to` and `one_file_system` resolve links and devices
+    /// through its `canonicalize`, `read_link` and `device`.
+    pub file_system: Option<Arc<dyn FileSystem>>,
+    /// Where walks record the first directory they fail to read among their own
+    /// root and the directories given to `report_unreadable` (None = skip read
+    /// errors silently). Shared by every walker using a clone of these options,
+    /// like `dir_handles`. Missing directories are never recorded.
+    pub(crate) unreadable_dirs: Option<Arc<UnreadableDirs>>,
 }
 
 /// A filter function that can prune directories during walking.
@@ -99,6 +157,178 @@ impl WalkOptions {
         self.use_gcd = use_gcd;
         self
     }
+
+    pub fn sort_dir_entries(mut self, sort: bool) -> Self {
+        self.sort_dir_entries = sort;
+        self
+    }
+
+    pub fn preserve_backslashes(mut self, preserve: bool) -> Self {
+        self.preserve_backslashes = preserve;
+        self
+    }
+
+    pub fn confine_to(mut self, root: Option<PathBuf>) -> Self {
+        self.confine_to = root;
+        self
+    }
+
+    pub fn one_file_system(mut self, one_file_system: bool) -> Self {
+        self.one_file_system = one_file_system;
+        self
+    }
+
+    pub fn max_open_dirs(mut self, max: Option<u32>) -> Self {
+        self.max_open_dirs = max;
+        self.dir_handles = max.map(|max| Arc::new(DirHandleLimit::new(max)));
+        self
+    }
+
+    pub fn hard_depth_limit(mut self, limit: Option<usize>) -> Self {
+        self.hard_depth_limit = limit;
+        self
+    }
+
+    pub fn dir_order(mut self, order: DirOrder) -> Self {
+        self.dir_order = order;
+        self
+    }
+
+    pub fn file_system(mut self, file_system: Option<Arc<dyn FileSystem>>) -> Self {
+        self.file_system = file_system;
+        self
+    }
+
+    /// Record read errors on the walk root and on `dirs`, for
+    /// [`take_unreadable`](Self::take_unreadable), instead of skipping them.
+    pub fn report_unreadable(mut self, dirs: Vec<PathBuf>) -> Self {
+        self.unreadable_dirs = Some(Arc::new(UnreadableDirs {
+            dirs,
+            first: Mutex::new(None),
+        }));
+        self
+    }
+
+    /// The first directory a walk with these options couldn't read, and why.
+    /// Clears it, so the next walk starts afresh.
+    pub fn take_unreadable(&self) -> Option<(PathBuf, io::Error)> {
+        let unreadable = self.unreadable_dirs.as_deref()?;
+        unreadable.first.lock().unwrap().take()
+    }
+
+    /// Record that `dir` couldn't be read, if it is the walk root or one of the
+    /// directories given to `report_unreadable`.
+    pub(crate) fn note_unreadable(&self, dir: &Path, walk_root: bool, err: &io::Error) {
+        let Some(unreadable) = self.unreadable_dirs.as_deref() else {
+            return;
+        };
+        if matches!(
+            err.kind(),
+            io::ErrorKind::NotFound | io::ErrorKind::NotADirectory
+        ) {
+            return;
+        }
+        let dir = strip_windows_extended_prefix(dir.to_path_buf());
+        if walk_root || unreadable.dirs.contains(&dir) {
+            let mut first = unreadable.first.lock().unwrap();
+            first.get_or_insert_with(|| (dir, io::Error::new(err.kind(), err.to_string())));
+        }
+    }
+}
+
+/// The directories whose read errors a walk reports, for `report_unreadable`,
+/// and the first such error.
+#[derive(Debug)]
+pub(crate) struct UnreadableDirs {
+    dirs: Vec<PathBuf>,
+    first: Mutex<Option<(PathBuf, io::Error)>>,
+}
+
+/// Counting semaphore bounding how many directory handles are open at once,
+/// for `max_open_dirs`. Readers hold a permit while their handle is open.
+#[derive(Debug)]
+pub(crate) struct DirHandleLimit {
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+impl DirHandleLimit {
+    /// A limit of `max` handles. Zero is treated as one so readers can't deadlock.
+    pub(crate) fn new(max: u32) -> Self {
+        Self {
+            available: Mutex::new((max as usize).max(1)),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Block until a handle may be opened. The permit is returned when dropped.
+    pub(crate) fn acquire(&self) -> DirHandlePermit<'_> {
+        let mut available = self.available.lock().unwrap();
+        while *available == 0 {
+            available = self.released.wait(available).unwrap();
+        }
+        *available -= 1;
+        DirHandlePermit { limit: self }
+    }
+}
+
+/// A held slot of a [`DirHandleLimit`].
+pub(crate) struct DirHandlePermit<'a> {
+    limit: &'a DirHandleLimit,
+}
+
+impl Drop for DirHandlePermit<'_> {
+    fn drop(&mut self) {
+        *self.limit.available.lock().unwrap() += 1;
+        self.limit.released.notify_one();
+    }
+}
+
+/// The thread pool parallel walks with `max_open_dirs = threads` read directories
+/// on. Pools are built once per size and shared, so walks neither pay for
+/// spawning threads each time nor exceed the limit by running side by side.
+/// `None` if the pool can't be built; the walk then gets a pool of its own.
+fn bounded_walk_pool(threads: u32) -> Option<Arc<rayon::ThreadPool>> {
+    static POOLS: OnceLock<Mutex<HashMap<u32, Arc<rayon::ThreadPool>>>> = OnceLock::new();
+    let threads = threads.max(1);
+    let mut pools = POOLS.get_or_init(Default::default).lock().ok()?;
+    if let Some(pool) = pools.get(&threads) {
+        return Some(Arc::clone(pool));
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads as usize)
+        .thread_name(|i| format!("globlin-walk-{i}"))
+        .build()
+        .ok()?;
+    let pool = Arc::new(pool);
+    pools.insert(threads, Arc::clone(&pool));
+    Some(pool)
+}
+
+/// A lazy walk together with the directory-handle permit covering it, which is
+/// released when the walk is dropped.
+struct WithPermit<'a, I> {
+    entries: I,
+    _permit: Option<DirHandlePermit<'a>>,
+}
+
+impl<I: Iterator> Iterator for WithPermit<'_, I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.entries.next()
+    }
+}
+
+/// Looks up the device a path lives on, for `one_file_system`.
+/// Replaceable so the check can be tested without a second mount.
+pub(crate) type DeviceLookup = Arc<dyn Fn(&Path) -> Option<u64> + Send + Sync>;
+
+/// Whether `dir` may be descended into when the walk must stay on `root_device`.
+/// Directories whose device can't be determined are kept.
+#[inline]
+fn on_root_device(root_device: u64, dir: &Path, devices: &DeviceLookup) -> bool {
+    devices(dir).is_none_or(|device| device == root_device)
 }
 
 /// A single entry returned from the walker
@@ -109,6 +339,20 @@ pub struct WalkEntry {
     pub(crate) is_dir: bool,
     pub(crate) is_file: bool,
     pub(crate) is_symlink: bool,
+    /// Size of a regular file, when the walker already stat'ed it
+    pub(crate) size: Option<u64>,
+}
+
+/// Stat `path` without following a final symlink, returning whether it is a
+/// symlink and, for a regular file, its size.
+fn symlink_type_and_size(path: &Path) -> (bool, Option<u64>) {
+    match path.symlink_metadata() {
+        Ok(meta) => (
+            meta.file_type().is_symlink(),
+            meta.is_file().then_some(meta.len()),
+        ),
+        Err(_) => (false, None),
+    }
 }
 
 impl WalkEntry {
@@ -124,6 +368,7 @@ impl WalkEntry {
             is_dir: file_type.is_dir(),
             is_file: file_type.is_file(),
             is_symlink: file_type.is_symlink(),
+            size: None,
         }
     }
 
@@ -138,6 +383,7 @@ impl WalkEntry {
             is_dir: file_type.is_dir(),
             is_file: file_type.is_file(),
             is_symlink: file_type.is_symlink(),
+            size: None,
         }
     }
 
@@ -148,12 +394,10 @@ impl WalkEntry {
         let path = entry.path();
         // When following symlinks, jwalk reports the TARGET type, not the symlink type.
         // To detect if the entry is a symlink, we need to check symlink_metadata.
-        let is_symlink = if file_type.is_symlink() {
-            true
+        let (is_symlink, size) = if file_type.is_symlink() {
+            (true, None)
         } else {
-            path.symlink_metadata()
-                .map(|m| m.file_type().is_symlink())
-                .unwrap_or(false)
+            symlink_type_and_size(&path)
         };
         Self {
             path,
@@ -161,6 +405,7 @@ impl WalkEntry {
             is_dir: file_type.is_dir(),
             is_file: file_type.is_file(),
             is_symlink,
+            size,
         }
     }
 
@@ -173,15 +418,11 @@ impl WalkEntry {
         // When following symlinks, walkdir reports the TARGET type, not the symlink type.
         // To detect if the entry is a symlink, we need to check symlink_metadata.
         // This is needed for correct behavior of the `mark` option.
-        let is_symlink = if file_type.is_symlink() {
-            true
+        let (is_symlink, size) = if file_type.is_symlink() {
+            (true, None)
         } else {
             // Check with symlink_metadata since walkdir may have followed the link
-            entry
-                .path()
-                .symlink_metadata()
-                .map(|m| m.file_type().is_symlink())
-                .unwrap_or(false)
+            symlink_type_and_size(entry.path())
         };
         Self {
             path: entry.path().to_path_buf(),
@@ -189,6 +430,7 @@ impl WalkEntry {
             is_dir: file_type.is_dir(),
             is_file: file_type.is_file(),
             is_symlink,
+            size,
         }
     }
 
@@ -212,6 +454,11 @@ impl WalkEntry {
         self.is_symlink
     }
 
+    /// Size of a regular file, if the walker learned it while walking.
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
     pub fn file_name(&self) -> Option<&std::ffi::OsStr> {
         self.path.file_name()
     }
@@ -226,20 +473,71 @@ impl WalkEntry {
 /// Directory walker that can traverse filesystem trees
 pub struct Walker {
     root: PathBuf,
+    /// Root handed to the filesystem: the extended-length (`\\?\`) form of `root`
+    /// on Windows, so deep trees aren't cut off at MAX_PATH; `root` elsewhere
+    fs_root: PathBuf,
     options: WalkOptions,
     /// Optional filter to prune directories during traversal.
     /// The filter receives the path relative to root (as a string with forward slashes)
     /// and returns true if the directory should be traversed, false to skip it.
     dir_prune_filter: Option<DirPruneFilter>,
+    /// Where `confine_to` resolves symlinks and `has_children` lists directories:
+    /// the custom filesystem, or the real one
+    resolver: Arc<dyn FileSystem>,
+    /// Device lookup used for `one_file_system`
+    devices: DeviceLookup,
+    /// The hard depth limit, when it is lower than `max_depth` would allow
+    depth_limit: Option<usize>,
+    /// Set once a directory at `depth_limit` is yielded, as it won't be descended into
+    depth_limit_reached: AtomicBool,
 }
 
 impl Walker {
-    pub fn new(root: PathBuf, options: WalkOptions) -> Self {
-        Self {
+    pub fn new(root: PathBuf, mut options: WalkOptions) -> Self {
+        // Every backend honours `max_depth`, so the hard limit is enforced through it
+        let hard_limit = options.hard_depth_limit.unwrap_or(DEFAULT_HARD_DEPTH_LIMIT);
+        let depth_limit = options.max_depth.is_none_or(|max| max > hard_limit);
+        if depth_limit {
+            options.max_depth = Some(hard_limit);
+        }
+        // `max_open_dirs` may have been set without the builder
+        if options.dir_handles.is_none() {
+            options.dir_handles = options
+                .max_open_dirs
+                .map(|max| Arc::new(DirHandleLimit::new(max)));
+        }
+        let resolver: Arc<dyn FileSystem> = match options.file_system {
+            Some(ref file_system) => Arc::clone(file_system),
+            None => Arc::new(StdFileSystem),
+        };
+        let devices: DeviceLookup = {
+            let resolver = Arc::clone(&resolver);
+            Arc::new(move |path: &Path| resolver.device(path))
+        };
+        // Roots with `..` stay as given: entries must keep `root` as a literal prefix
+        let fs_root = if options.file_system.is_some() {
+            root.clone()
+        } else if root.is_absolute() && !root.components().any(|c| c == Component::ParentDir) {
+            to_windows_extended_path(&root)
+        } else {
+            root.clone()
+        };
+        let mut walker = Self {
             root,
+            fs_root,
             options,
             dir_prune_filter: None,
+            resolver,
+            devices,
+            depth_limit: depth_limit.then_some(hard_limit),
+            depth_limit_reached: AtomicBool::new(false),
+        };
+        // These prune on their own, even if no filter is set later
+        let confined = walker.options.follow_symlinks && walker.options.confine_to.is_some();
+        if walker.options.one_file_system || confined {
+            walker = walker.with_dir_prune_filter(Box::new(|_| true));
         }
+        walker
     }
 
     /// Create a walker with default options
@@ -250,11 +548,54 @@ impl Walker {
     /// Set a directory pruning filter.
     /// The filter receives the path relative to root (as a string with forward slashes)
     /// and returns true if the directory should be traversed, false to skip it.
+    ///
+    /// With `confine_to` and `follow_symlinks`, directories resolving outside the
+    /// boundary are pruned as well, so escaping links are never traversed.
     pub fn with_dir_prune_filter(mut self, filter: DirPruneFilter) -> Self {
+        let filter = match self.options.confine_to {
+            Some(ref boundary) if self.options.follow_symlinks => {
+                let root = self.root.clone();
+                let boundary = boundary.clone();
+                let resolver = Arc::clone(&self.resolver);
+                Box::new(move |rel: &str| {
+                    filter(rel) && resolves_within_on(&*resolver, &root.join(rel), &boundary)
+                }) as DirPruneFilter
+            }
+            _ => filter,
+        };
+        let filter = if self.options.one_file_system {
+            self.stay_on_root_device(filter)
+        } else {
+            filter
+        };
         self.dir_prune_filter = Some(filter);
         self
     }
 
+    /// Replace the device lookup used by `one_file_system`.
+    /// Must be called before `with_dir_prune_filter`.
+    #[cfg(test)]
+    pub(crate) fn with_device_lookup(mut self, devices: DeviceLookup) -> Self {
+        self.devices = devices;
+        if self.options.one_file_system {
+            self.dir_prune_filter = Some(self.stay_on_root_device(Box::new(|_| true)));
+        }
+        self
+    }
+
+    /// Wrap a prune filter so directories on another device than the root are
+    /// pruned as well.
+    fn stay_on_root_device(&self, filter: DirPruneFilter) -> DirPruneFilter {
+        let devices = self.devices.clone();
+        let Some(root_device) = devices(&self.root) else {
+            return filter;
+        };
+        let root = self.root.clone();
+        Box::new(move |rel: &str| {
+            filter(rel) && on_root_device(root_device, &root.join(rel), &devices)
+        })
+    }
+
     pub fn root(&self) -> &Path {
         &self.root
     }
@@ -263,6 +604,12 @@ impl Walker {
         &self.options
     }
 
+    /// Whether a walk stopped descending because of the hard depth limit, leaving
+    /// directories below it unvisited. Only meaningful once the walk has finished.
+    pub fn reached_depth_limit(&self) -> bool {
+        self.depth_limit_reached.load(Ordering::Relaxed)
+    }
+
     /// Walk the directory tree, returning an iterator over entries.
     ///
     /// Note: When a dir_prune_filter is set, the walk collects entries into a Vec
@@ -274,27 +621,83 @@ impl Walker {
     /// If `cache` is enabled in options, uses a cached directory reader.
     /// If `parallel` is enabled in options, uses jwalk for parallel traversal.
     /// Otherwise, uses walkdir for serial traversal.
+    ///
+    /// If `confine_to` is set, entries resolving outside that directory are dropped.
+    ///
+    /// On Windows the walk reads from the extended-length form of the root, and
+    /// the `\\?\` prefix is removed again from every entry's path.
+    ///
+    /// If `dir_order` is `PostOrder`, each directory is held back until the walk
+    /// has yielded everything inside it.
     pub fn walk(&self) -> Box<dyn Iterator<Item = WalkEntry> + '_> {
+        let entries = self.walk_entries();
+        let entries = if self.fs_root != self.root {
+            Box::new(entries.map(|mut entry| {
+                entry.path = strip_windows_extended_prefix(entry.path);
+                entry
+            }))
+        } else {
+            entries
+        };
+        let entries = match self.options.confine_to {
+            Some(ref boundary) => self.skip_escaping_entries(entries, boundary.clone()),
+            None => entries,
+        };
+        let entries = match self.depth_limit {
+            // Pruned directories are never yielded, so any non-empty directory at
+            // the limit is one the walk would have gone on into
+            Some(limit) => Box::new(entries.inspect(move |entry| {
+                if entry.depth == limit
+                    && entry.is_dir
+                    && !self.depth_limit_reached.load(Ordering::Relaxed)
+                    && self.has_children(&entry.path)
+                {
+                    self.depth_limit_reached.store(true, Ordering::Relaxed);
+                }
+            })),
+            None => entries,
+        };
+        match self.options.dir_order {
+            DirOrder::PreOrder => entries,
+            DirOrder::PostOrder => Box::new(PostOrder {
+                entries,
+                open: Vec::new(),
+                pending: None,
+            }),
+        }
+    }
+
+    /// Dispatch to the walker implementation selected by the options.
+    fn walk_entries(&self) -> Box<dyn Iterator<Item = WalkEntry> + '_> {
+        if let Some(ref file_system) = self.options.file_system {
+            return self.walk_listed(ListingSource::FileSystem(file_system.as_ref()));
+        }
+
+        // The native I/O and GCD walkers read breadth-first, which post-order
+        // can't be built from
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        let depth_first_only = self.options.dir_order == DirOrder::PostOrder;
+
         // On Linux, use optimized I/O if requested
         #[cfg(target_os = "linux")]
-        if self.options.use_native_io {
+        if self.options.use_native_io && !depth_first_only {
             return self.walk_native_io_linux();
         }
 
         // On macOS, use optimized I/O if requested
         #[cfg(target_os = "macos")]
-        if self.options.use_native_io {
+        if self.options.use_native_io && !depth_first_only {
             return self.walk_native_io_macos();
         }
 
         // On macOS, use GCD for parallel walking if requested
         #[cfg(target_os = "macos")]
-        if self.options.use_gcd {
+        if self.options.use_gcd && !depth_first_only {
             return self.walk_gcd();
         }
 
         if self.options.cache {
-            self.walk_cached()
+            self.walk_listed(ListingSource::Cache)
         } else if self.options.parallel {
             self.walk_parallel()
         } else {
@@ -302,6 +705,46 @@ impl Walker {
         }
     }
 
+    /// Drop symlinks whose real path lies outside `boundary`, along with every
+    /// entry reached through them. Nothing is yielded if the walk root itself
+    /// resolves outside the boundary.
+    ///
+    /// Relies on every walker yielding a directory before its descendants.
+    fn skip_escaping_entries<'a>(
+        &self,
+        entries: Box<dyn Iterator<Item = WalkEntry> + 'a>,
+        boundary: PathBuf,
+    ) -> Box<dyn Iterator<Item = WalkEntry> + 'a> {
+        if !resolves_within_on(&*self.resolver, &self.root, &boundary) {
+            return Box::new(std::iter::empty());
+        }
+        let follow = self.options.follow_symlinks;
+        let resolver = Arc::clone(&self.resolver);
+        // Looked up by ancestor, so each entry costs its depth, not the number of links
+        let mut skipped: HashSet<PathBuf> = HashSet::new();
+
+        Box::new(entries.filter(move |entry| {
+            if entry.depth == 0 {
+                return true;
+            }
+            if !skipped.is_empty() && entry.path.ancestors().any(|dir| skipped.contains(dir)) {
+                return false;
+            }
+
+            // When following, walkers report the target type, so check the link itself
+            let is_symlink = entry.is_symlink
+                || (follow
+                    && resolver
+                        .symlink_metadata(&entry.path)
+                        .is_ok_and(|meta| meta.is_symlink));
+            if !is_symlink || resolves_within_on(&*resolver, &entry.path, &boundary) {
+                return true;
+            }
+            skipped.insert(entry.path.clone());
+            false
+        }))
+    }
+
     /// Walk using Linux-specific I/O optimizations (getdents64 syscall).
     /// This provides 1.3-1.5x speedup over standard readdir.
     #[cfg(target_os = "linux")]
@@ -317,7 +760,7 @@ impl Walker {
             entries.retain(|entry| {
                 if let Ok(rel_path) = entry.path().strip_prefix(&root) {
                     let rel_lossy = rel_path.to_string_lossy();
-                    let rel_str = normalize_path_str(&rel_lossy);
+                    let rel_str = normalize_path_str(&rel_lossy, self.options.preserve_backslashes);
                     // Root directory always passes
                     if rel_str.is_empty() {
                         return true;
@@ -330,7 +773,10 @@ impl Walker {
                     if !entry.is_dir() {
                         if let Some(parent) = rel_path.parent() {
                             let parent_lossy = parent.to_string_lossy();
-                            let parent_str = normalize_path_str(&parent_lossy);
+                            let parent_str = normalize_path_str(
+                                &parent_lossy,
+                                self.options.preserve_backslashes,
+                            );
                             if !parent_str.is_empty() && !prune_filter(&parent_str) {
                                 return false;
                             }
@@ -359,7 +805,7 @@ impl Walker {
             entries.retain(|entry| {
                 if let Ok(rel_path) = entry.path().strip_prefix(&root) {
                     let rel_lossy = rel_path.to_string_lossy();
-                    let rel_str = normalize_path_str(&rel_lossy);
+                    let rel_str = normalize_path_str(&rel_lossy, self.options.preserve_backslashes);
                     // Root directory always passes
                     if rel_str.is_empty() {
                         return true;
@@ -372,7 +818,10 @@ impl Walker {
                     if !entry.is_dir() {
                         if let Some(parent) = rel_path.parent() {
                             let parent_lossy = parent.to_string_lossy();
-                            let parent_str = normalize_path_str(&parent_lossy);
+                            let parent_str = normalize_path_str(
+                                &parent_lossy,
+                                self.options.preserve_backslashes,
+                            );
                             if !parent_str.is_empty() && !prune_filter(&parent_str) {
                                 return false;
                             }
@@ -389,57 +838,52 @@ impl Walker {
     /// Walk the directory tree using Grand Central Dispatch (GCD) for parallel processing.
     /// This provides better integration with macOS scheduler and Apple Silicon cores.
     /// Expected speedup: 1.3-1.5x on multi-core Macs.
+    ///
+    /// The prune filter is applied while walking, so pruned directories are never read.
+    /// libdispatch ships with every macOS, but the directory readers may fail on some
+    /// filesystems; if the root cannot be listed at all, fall back to the serial walker.
     #[cfg(target_os = "macos")]
     fn walk_gcd(&self) -> Box<dyn Iterator<Item = WalkEntry> + '_> {
         use crate::gcd_walker::GcdWalker;
 
-        let gcd_walker = GcdWalker::new(self.root.clone(), self.options.clone());
-        let mut entries = gcd_walker.walk();
-
-        // Apply pruning filter if set
+        let mut gcd_walker = GcdWalker::new(self.root.clone(), self.options.clone());
         if let Some(ref prune_filter) = self.dir_prune_filter {
-            let root = self.root.clone();
-            entries.retain(|entry| {
-                if let Ok(rel_path) = entry.path().strip_prefix(&root) {
-                    let rel_lossy = rel_path.to_string_lossy();
-                    let rel_str = normalize_path_str(&rel_lossy);
-                    // Root directory always passes
-                    if rel_str.is_empty() {
-                        return true;
-                    }
-                    // For directories, check if they should be included
-                    if entry.is_dir() && !prune_filter(&rel_str) {
-                        return false;
-                    }
-                    // For files, check if their parent directory passes the filter
-                    if !entry.is_dir() {
-                        if let Some(parent) = rel_path.parent() {
-                            let parent_lossy = parent.to_string_lossy();
-                            let parent_str = normalize_path_str(&parent_lossy);
-                            if !parent_str.is_empty() && !prune_filter(&parent_str) {
-                                return false;
-                            }
-                        }
-                    }
-                }
-                true
-            });
+            gcd_walker = gcd_walker.with_dir_prune_filter(prune_filter);
         }
 
-        Box::new(entries.into_iter())
+        match gcd_walker.try_walk() {
+            Ok(entries) => Box::new(entries.into_iter()),
+            Err(_) => self.walk_serial(),
+        }
     }
 
     /// Walk the directory tree using serial (single-threaded) walkdir.
     /// This is the default mode, faster on SSDs for small to medium directories.
     fn walk_serial(&self) -> Box<dyn Iterator<Item = WalkEntry> + '_> {
-        let mut walker = WalkDir::new(&self.root).follow_links(self.options.follow_symlinks);
+        let mut walker = WalkDir::new(&self.fs_root).follow_links(self.options.follow_symlinks);
 
         if let Some(max_depth) = self.options.max_depth {
             walker = walker.max_depth(max_depth);
         }
 
+        // walkdir keeps one handle per level of the current path; past the limit
+        // it reads the remaining entries of the oldest handle into memory and closes
+        // it. Under max_open_dirs the walk keeps a single handle, covered by a permit
+        // held until the walk is done, so concurrent walks share the budget.
+        let permit = match self.options.dir_handles.as_deref() {
+            Some(limit) => {
+                walker = walker.max_open(1);
+                Some(limit.acquire())
+            }
+            None => None,
+        };
+
+        if self.options.sort_dir_entries {
+            walker = walker.sort_by_file_name();
+        }
+
         let dot = self.options.dot;
-        let root = self.root.clone();
+        let root = self.fs_root.clone();
         let need_accurate_symlink = self.options.need_accurate_symlink_detection;
 
         // Choose the appropriate entry creation function based on whether we need
@@ -472,7 +916,8 @@ impl Walker {
                         // Get the path relative to root
                         if let Ok(rel_path) = e.path().strip_prefix(&root) {
                             let rel_lossy = rel_path.to_string_lossy();
-                            let rel_str = normalize_path_str(&rel_lossy);
+                            let rel_str =
+                                normalize_path_str(&rel_lossy, self.options.preserve_backslashes);
                             // If the prune filter returns false, skip this directory and its descendants
                             if !prune_filter(&rel_str) {
                                 return false;
@@ -484,6 +929,7 @@ impl Walker {
                 .filter_map(|result| match result {
                     Ok(entry) => Some(create_entry(&entry)),
                     Err(err) => {
+                        self.note_walk_error(err.path(), err.io_error());
                         if let Some(path) = err.path() {
                             if let Ok(meta) = path.symlink_metadata() {
                                 if meta.file_type().is_symlink() {
@@ -493,6 +939,7 @@ impl Walker {
                                         is_dir: false,
                                         is_file: false,
                                         is_symlink: true,
+                                        size: None,
                                     });
                                 }
                             }
@@ -505,51 +952,70 @@ impl Walker {
             Box::new(entries.into_iter())
         } else {
             // No pruning filter - use lazy iteration
-            Box::new(
-                walker
-                    .into_iter()
-                    .filter_entry(move |e| {
-                        // Filter dot files if dot option is false
-                        if !dot {
-                            if let Some(name) = e.file_name().to_str() {
-                                // Allow the root entry to pass through
-                                if e.depth() > 0 && name.starts_with('.') {
-                                    return false;
-                                }
+            let entries = walker
+                .into_iter()
+                .filter_entry(move |e| {
+                    // Filter dot files if dot option is false
+                    if !dot {
+                        if let Some(name) = e.file_name().to_str() {
+                            // Allow the root entry to pass through
+                            if e.depth() > 0 && name.starts_with('.') {
+                                return false;
                             }
                         }
-                        true
-                    })
-                    .filter_map(move |result| {
-                        match result {
-                            Ok(entry) => Some(create_entry(&entry)),
-                            Err(err) => {
-                                // For broken symlinks (or other IO errors), try to extract the path
-                                // and return it as an entry. This handles the case where follow_links
-                                // is true but the symlink target doesn't exist.
-                                if let Some(path) = err.path() {
-                                    // Check if this is a symlink using symlink_metadata
-                                    if let Ok(meta) = path.symlink_metadata() {
-                                        if meta.file_type().is_symlink() {
-                                            return Some(WalkEntry {
-                                                path: path.to_path_buf(),
-                                                depth: err.depth(),
-                                                is_dir: false,
-                                                is_file: false,
-                                                is_symlink: true,
-                                            });
-                                        }
+                    }
+                    true
+                })
+                .filter_map(move |result| {
+                    match result {
+                        Ok(entry) => Some(create_entry(&entry)),
+                        Err(err) => {
+                            self.note_walk_error(err.path(), err.io_error());
+                            // For broken symlinks (or other IO errors), try to extract the path
+                            // and return it as an entry. This handles the case where follow_links
+                            // is true but the symlink target doesn't exist.
+                            if let Some(path) = err.path() {
+                                // Check if this is a symlink using symlink_metadata
+                                if let Ok(meta) = path.symlink_metadata() {
+                                    if meta.file_type().is_symlink() {
+                                        return Some(WalkEntry {
+                                            path: path.to_path_buf(),
+                                            depth: err.depth(),
+                                            is_dir: false,
+                                            is_file: false,
+                                            is_symlink: true,
+                                            size: None,
+                                        });
                                     }
                                 }
-                                // For other errors, skip the entry
-                                None
                             }
+                            // For other errors, skip the entry
+                            None
                         }
-                    }),
-            )
+                    }
+                });
+            Box::new(WithPermit {
+                entries,
+                _permit: permit,
+            })
+        }
+    }
+
+    /// Record a failed directory read reported by walkdir or jwalk, for
+    /// `WalkOptions::report_unreadable`.
+    fn note_walk_error(&self, path: Option<&Path>, err: Option<&io::Error>) {
+        if let (Some(path), Some(err)) = (path, err) {
+            self.options
+                .note_unreadable(path, path == self.fs_root, err);
         }
     }
 
+    /// Whether `dir` has any entries, read from the walk's filesystem. Directories
+    /// that can't be read count as empty.
+    fn has_children(&self, dir: &Path) -> bool {
+        self.resolver.read_dir(dir).is_ok_and(|e| !e.is_empty())
+    }
+
     /// Walk the directory tree synchronously, collecting all entries
     pub fn walk_sync(&self) -> Vec<WalkEntry> {
         self.walk().collect()
@@ -561,12 +1027,12 @@ impl Walker {
     fn walk_parallel(&self) -> Box<dyn Iterator<Item = WalkEntry> + '_> {
         let need_accurate_symlink = self.options.need_accurate_symlink_detection;
         let dot = self.options.dot;
-        let root = self.root.clone();
+        let root = self.fs_root.clone();
 
         // Build jwalk walker with parallel traversal
         // Note: jwalk has skip_hidden=true by default, so we must disable it
         // and handle dot filtering ourselves in process_read_dir
-        let mut builder = jwalk::WalkDir::new(&self.root)
+        let mut builder = jwalk::WalkDir::new(&self.fs_root)
             .follow_links(self.options.follow_symlinks)
             .skip_hidden(false); // Always read all files, filter manually
 
@@ -574,9 +1040,25 @@ impl Walker {
             builder = builder.max_depth(max_depth);
         }
 
-        // Use rayon's default thread pool for parallelism
-        builder = builder.parallelism(jwalk::Parallelism::RayonDefaultPool {
-            busy_timeout: std::time::Duration::from_secs(1),
+        if self.options.sort_dir_entries {
+            builder = builder.sort(true);
+        }
+
+        // Use rayon's default thread pool for parallelism. jwalk reads each directory
+        // in full on one thread, so with max_open_dirs a pool of that many threads
+        // bounds the open handles.
+        builder = builder.parallelism(match self.options.max_open_dirs {
+            Some(max_open) => match bounded_walk_pool(max_open) {
+                // The pool only runs directory reads, so it can't be blocked on itself
+                Some(pool) => jwalk::Parallelism::RayonExistingPool {
+                    pool,
+                    busy_timeout: None,
+                },
+                None => jwalk::Parallelism::RayonNewPool(max_open.max(1) as usize),
+            },
+            None => jwalk::Parallelism::RayonDefaultPool {
+                busy_timeout: std::time::Duration::from_secs(1),
+            },
         });
 
         // Since dir_prune_filter is a Box<dyn Fn>, we can't clone it directly.
@@ -611,20 +1093,22 @@ impl Walker {
             .into_iter()
             .filter_map(move |result| match result {
                 Ok(entry) => {
+                    // jwalk keeps a directory's read error on the directory's entry
+                    if let Some(ref err) = entry.read_children_error {
+                        self.note_walk_error(err.path(), err.io_error());
+                    }
                     let file_type = entry.file_type();
                     let path = entry.path();
 
                     // When following symlinks, we may need accurate symlink detection
-                    let is_symlink = if need_accurate_symlink {
+                    let (is_symlink, size) = if need_accurate_symlink {
                         if file_type.is_symlink() {
-                            true
+                            (true, None)
                         } else {
-                            path.symlink_metadata()
-                                .map(|m| m.file_type().is_symlink())
-                                .unwrap_or(false)
+                            symlink_type_and_size(&path)
                         }
                     } else {
-                        file_type.is_symlink()
+                        (file_type.is_symlink(), None)
                     };
 
                     Some(WalkEntry {
@@ -633,9 +1117,11 @@ impl Walker {
                         is_dir: file_type.is_dir(),
                         is_file: file_type.is_file(),
                         is_symlink,
+                        size,
                     })
                 }
                 Err(err) => {
+                    self.note_walk_error(err.path(), err.io_error());
                     // Handle broken symlinks
                     if let Some(path) = err.path() {
                         if let Ok(meta) = path.symlink_metadata() {
@@ -646,6 +1132,7 @@ impl Walker {
                                     is_dir: false,
                                     is_file: false,
                                     is_symlink: true,
+                                    size: None,
                                 });
                             }
                         }
@@ -664,7 +1151,8 @@ impl Walker {
                 .filter(|entry| {
                     if let Ok(rel_path) = entry.path().strip_prefix(&root) {
                         let rel_lossy = rel_path.to_string_lossy();
-                        let rel_str = normalize_path_str(&rel_lossy);
+                        let rel_str =
+                            normalize_path_str(&rel_lossy, self.options.preserve_backslashes);
                         // Root directory always passes
                         if rel_str.is_empty() {
                             return true;
@@ -678,7 +1166,10 @@ impl Walker {
                         if !entry.is_dir() {
                             if let Some(parent) = rel_path.parent() {
                                 let parent_lossy = parent.to_string_lossy();
-                                let parent_str = normalize_path_str(&parent_lossy);
+                                let parent_str = normalize_path_str(
+                                    &parent_lossy,
+                                    self.options.preserve_backslashes,
+                                );
                                 if !parent_str.is_empty() && !prune_filter(&parent_str) {
                                     return false;
                                 }
@@ -695,48 +1186,52 @@ impl Walker {
         }
     }
 
-    /// Walk the directory tree using cached directory reads.
-    /// This mode provides significant speedup for repeated glob operations
-    /// on the same directories by caching directory listings with TTL-based invalidation.
-    fn walk_cached(&self) -> Box<dyn Iterator<Item = WalkEntry> + '_> {
+    /// Walk the directory tree depth-first from whole directory listings.
+    ///
+    /// With `ListingSource::Cache` this is the `cache` mode, which provides
+    /// significant speedup for repeated glob operations on the same directories
+    /// by caching directory listings with TTL-based invalidation. With
+    /// `ListingSource::FileSystem` every read goes through a custom `FileSystem`.
+    fn walk_listed(&self, source: ListingSource<'_>) -> Box<dyn Iterator<Item = WalkEntry> + '_> {
         let need_accurate_symlink = self.options.need_accurate_symlink_detection;
         let dot = self.options.dot;
         let follow_symlinks = self.options.follow_symlinks;
         let max_depth = self.options.max_depth;
-        let root = self.root.clone();
+        let root = self.fs_root.clone();
 
-        // Collect entries using recursive cached walking
+        // Collect entries using depth-first walking
         let mut entries = Vec::new();
 
         // Add root entry
-        if let Ok(meta) = self.root.symlink_metadata() {
-            let is_symlink = meta.file_type().is_symlink();
-            let (is_dir, is_file) = if is_symlink && follow_symlinks {
-                // When following symlinks, get the target type
-                match self.root.metadata() {
-                    Ok(target_meta) => {
-                        let ft = target_meta.file_type();
-                        (ft.is_dir(), ft.is_file())
-                    }
-                    Err(_) => (false, false), // Broken symlink
-                }
+        let root_meta = source.symlink_metadata(&self.fs_root);
+        if let Err(ref err) = root_meta {
+            self.options.note_unreadable(&self.fs_root, true, err);
+        }
+        if let Ok(meta) = root_meta {
+            let is_symlink = meta.is_symlink;
+            // Like walkdir, a symlinked root is followed even without follow_symlinks
+            let meta = if is_symlink {
+                // A broken symlink is neither a file nor a directory
+                source.metadata(&self.fs_root).unwrap_or_default()
             } else {
-                let ft = meta.file_type();
-                (ft.is_dir(), ft.is_file())
+                meta
             };
+            let (is_dir, is_file) = (meta.is_dir, meta.is_file);
 
             entries.push(WalkEntry {
-                path: self.root.clone(),
+                path: self.fs_root.clone(),
                 depth: 0,
                 is_dir,
                 is_file,
                 is_symlink,
+                size: meta.len.filter(|_| is_file),
             });
 
             // If root is a directory, walk its contents
             if is_dir {
-                self.walk_cached_recursive(
-                    &self.root,
+                self.walk_listed_dir(
+                    source,
+                    &self.fs_root,
                     1,
                     &root,
                     dot,
@@ -755,7 +1250,8 @@ impl Walker {
                 .filter(|entry| {
                     if let Ok(rel_path) = entry.path().strip_prefix(&root) {
                         let rel_lossy = rel_path.to_string_lossy();
-                        let rel_str = normalize_path_str(&rel_lossy);
+                        let rel_str =
+                            normalize_path_str(&rel_lossy, self.options.preserve_backslashes);
                         // Root directory always passes
                         if rel_str.is_empty() {
                             return true;
@@ -768,7 +1264,10 @@ impl Walker {
                         if !entry.is_dir() {
                             if let Some(parent) = rel_path.parent() {
                                 let parent_lossy = parent.to_string_lossy();
-                                let parent_str = normalize_path_str(&parent_lossy);
+                                let parent_str = normalize_path_str(
+                                    &parent_lossy,
+                                    self.options.preserve_backslashes,
+                                );
                                 if !parent_str.is_empty() && !prune_filter(&parent_str) {
                                     return false;
                                 }
@@ -785,10 +1284,14 @@ impl Walker {
         }
     }
 
-    /// Recursive helper for cached walking.
+    /// Depth-first helper for `walk_listed`, yielding entries in walkdir order.
+    ///
+    /// Uses an explicit stack of pending listings rather than recursion, so deep
+    /// trees can't overflow the (possibly small) stack of a worker thread.
     #[allow(clippy::too_many_arguments)]
-    fn walk_cached_recursive(
+    fn walk_listed_dir(
         &self,
+        source: ListingSource<'_>,
         dir_path: &Path,
         depth: usize,
         root: &Path,
@@ -798,28 +1301,18 @@ impl Walker {
         need_accurate_symlink: bool,
         entries: &mut Vec<WalkEntry>,
     ) {
-        // Check depth limit
-        if let Some(max) = max_depth {
-            if depth > max {
-                return;
-            }
-        }
-
-        // Apply pruning filter before reading directory
-        if let Some(ref prune_filter) = self.dir_prune_filter {
-            if let Ok(rel_path) = dir_path.strip_prefix(root) {
-                let rel_lossy = rel_path.to_string_lossy();
-                let rel_str = normalize_path_str(&rel_lossy);
-                if !rel_str.is_empty() && !prune_filter(&rel_str) {
-                    return;
-                }
-            }
+        let mut stack: Vec<(PathBuf, usize, std::vec::IntoIter<CachedDirEntry>)> = Vec::new();
+        if let Some(listing) = self.read_listing(source, dir_path, depth, root, max_depth) {
+            stack.push((dir_path.to_path_buf(), depth, listing));
         }
 
-        // Read directory using cache
-        let cached_entries = read_dir_cached(dir_path, follow_symlinks);
+        while let Some((dir_path, depth, listing)) = stack.last_mut() {
+            let Some(cached_entry) = listing.next() else {
+                stack.pop();
+                continue;
+            };
+            let depth = *depth;
 
-        for cached_entry in cached_entries {
             // Filter dot files if dot option is false
             if !dot && cached_entry.name.starts_with('.') {
                 continue;
@@ -828,7 +1321,10 @@ impl Walker {
             let entry_path = dir_path.join(&cached_entry.name);
 
             // Determine entry types
-            let (is_dir, is_file, is_symlink) = if need_accurate_symlink && follow_symlinks {
+            let (is_dir, is_file, is_symlink) = if need_accurate_symlink
+                && follow_symlinks
+                && matches!(source, ListingSource::Cache)
+            {
                 // Need accurate symlink detection: check symlink_metadata
                 let is_symlink = entry_path
                     .symlink_metadata()
@@ -849,20 +1345,152 @@ impl Walker {
                 is_dir,
                 is_file,
                 is_symlink,
+                size: cached_entry.size,
             });
 
-            // Recurse into directories (unless it's a symlink and we're not following)
+            // Descend into directories (unless it's a symlink and we're not following)
             if is_dir && (follow_symlinks || !cached_entry.is_symlink) {
-                self.walk_cached_recursive(
-                    &entry_path,
-                    depth + 1,
-                    root,
-                    dot,
-                    follow_symlinks,
-                    max_depth,
-                    need_accurate_symlink,
-                    entries,
-                );
+                if let Some(listing) =
+                    self.read_listing(source, &entry_path, depth + 1, root, max_depth)
+                {
+                    stack.push((entry_path, depth + 1, listing));
+                }
+            }
+        }
+    }
+
+    /// The listing of a directory whose entries sit at `depth`, or None if the
+    /// depth limit or the prune filter rules the directory out.
+    fn read_listing(
+        &self,
+        source: ListingSource<'_>,
+        dir_path: &Path,
+        depth: usize,
+        root: &Path,
+        max_depth: Option<usize>,
+    ) -> Option<std::vec::IntoIter<CachedDirEntry>> {
+        // Check depth limit
+        if max_depth.is_some_and(|max| depth > max) {
+            return None;
+        }
+
+        // Apply pruning filter before reading directory
+        if let Some(ref prune_filter) = self.dir_prune_filter {
+            if let Ok(rel_path) = dir_path.strip_prefix(root) {
+                let rel_lossy = rel_path.to_string_lossy();
+                let rel_str = normalize_path_str(&rel_lossy, self.options.preserve_backslashes);
+                if !rel_str.is_empty() && !prune_filter(&rel_str) {
+                    return None;
+                }
+            }
+        }
+
+        let read = {
+            let _permit = self
+                .options
+                .dir_handles
+                .as_deref()
+                .map(DirHandleLimit::acquire);
+            source.read_dir(dir_path, self.options.follow_symlinks)
+        };
+        let mut cached_entries = read.unwrap_or_else(|err| {
+            self.options
+                .note_unreadable(dir_path, dir_path == root, &err);
+            Vec::new()
+        });
+        if self.options.sort_dir_entries {
+            cached_entries.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        Some(cached_entries.into_iter())
+    }
+}
+
+/// Where `Walker::walk_listed` reads directory listings and metadata from.
+#[derive(Clone, Copy)]
+enum ListingSource<'a> {
+    /// The real filesystem, with listings kept in the readdir cache
+    Cache,
+    /// A custom filesystem from `WalkOptions::file_system`
+    FileSystem(&'a dyn FileSystem),
+}
+
+impl ListingSource<'_> {
+    /// List a directory. With `follow_symlinks`, symlinks report their target's
+    /// type (neither a file nor a directory if broken), as `read_dir_cached` does.
+    fn read_dir(&self, dir_path: &Path, follow_symlinks: bool) -> io::Result<Vec<CachedDirEntry>> {
+        let file_system = match self {
+            ListingSource::Cache => return try_read_dir_cached(dir_path, follow_symlinks),
+            ListingSource::FileSystem(file_system) => *file_system,
+        };
+        Ok(file_system
+            .read_dir(dir_path)?
+            .into_iter()
+            .map(|entry| {
+                let mut meta = entry.metadata;
+                if follow_symlinks && meta.is_symlink {
+                    let target = file_system
+                        .metadata(&dir_path.join(&entry.name))
+                        .unwrap_or_default();
+                    meta.is_dir = target.is_dir;
+                    meta.is_file = target.is_file;
+                    meta.len = target.len;
+                }
+                CachedDirEntry {
+                    name: entry.name,
+                    is_dir: meta.is_dir,
+                    is_file: meta.is_file,
+                    is_symlink: meta.is_symlink,
+                    size: meta.len.filter(|_| meta.is_file),
+                }
+            })
+            .collect())
+    }
+
+    /// The type of `path`, following symlinks.
+    fn metadata(&self, path: &Path) -> Option<FsMetadata> {
+        match self {
+            ListingSource::Cache => path.metadata().ok().map(FsMetadata::from),
+            ListingSource::FileSystem(file_system) => file_system.metadata(path).ok(),
+        }
+    }
+
+    /// The type of `path` itself.
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        match self {
+            ListingSource::Cache => path.symlink_metadata().map(FsMetadata::from),
+            ListingSource::FileSystem(file_system) => file_system.symlink_metadata(path),
+        }
+    }
+}
+
+/// Reorders a depth-first walk so every directory follows its contents.
+///
+/// A directory is held back until an entry at its own depth or shallower
+/// arrives, which means the walk has left it. Only the directories on the
+/// current path are held, so memory is bounded by the depth of the tree.
+struct PostOrder<'a> {
+    entries: Box<dyn Iterator<Item = WalkEntry> + 'a>,
+    open: Vec<WalkEntry>,
+    pending: Option<WalkEntry>,
+}
+
+impl Iterator for PostOrder<'_> {
+    type Item = WalkEntry;
+
+    fn next(&mut self) -> Option<WalkEntry> {
+        loop {
+            let entry = match self.pending.take().or_else(|| self.entries.next()) {
+                Some(entry) => entry,
+                None => return self.open.pop(),
+            };
+            if self.open.last().is_some_and(|dir| dir.depth >= entry.depth) {
+                self.pending = Some(entry);
+                return self.open.pop();
+            }
+            if entry.is_dir {
+                self.open.push(entry);
+            } else {
+                return Some(entry);
             }
         }
     }
@@ -1669,6 +2297,84 @@ mod tests {
         assert!(!entries.iter().any(|e| e.path().ends_with("main.js")));
     }
 
+    #[test]
+    fn test_walker_one_file_system_prunes_other_devices() {
+        let temp = create_test_fixture();
+        let root = temp.path().to_path_buf();
+        // Pretend `a/b` is a mount point: it and everything below are on device 2
+        let mount = root.join("a/b");
+        let devices: DeviceLookup =
+            Arc::new(move |path: &Path| Some(if path.starts_with(&mount) { 2 } else { 1 }));
+
+        for parallel in [false, true] {
+            let walk = |one_file_system: bool, prune: bool| {
+                let options = WalkOptions::new()
+                    .parallel(parallel)
+                    .one_file_system(one_file_system);
+                let mut walker =
+                    Walker::new(root.clone(), options).with_device_lookup(devices.clone());
+                if prune {
+                    walker = walker.with_dir_prune_filter(Box::new(|rel| rel != "src/lib"));
+                }
+                let mut paths: Vec<_> = walker
+                    .walk_sync()
+                    .into_iter()
+                    .map(|e| e.path().strip_prefix(&root).unwrap().to_path_buf())
+                    .collect();
+                paths.sort();
+                paths
+            };
+
+            let all = walk(false, false);
+            assert!(all.contains(&PathBuf::from("a/b/c/deep.txt")));
+
+            let same_device = walk(true, false);
+            assert!(same_device.contains(&PathBuf::from("a")));
+            assert!(!same_device.iter().any(|p| p.starts_with("a/b")));
+            assert_eq!(
+                same_device.len(),
+                all.iter().filter(|p| !p.starts_with("a/b")).count()
+            );
+
+            // Combines with a prune filter
+            let pruned = walk(true, true);
+            assert!(!pruned
+                .iter()
+                .any(|p| p.starts_with("a/b") || p.starts_with("src/lib")));
+            assert!(pruned.contains(&PathBuf::from("src/main.js")));
+        }
+    }
+
+    #[test]
+    fn test_on_root_device() {
+        let devices: DeviceLookup = Arc::new(|path: &Path| match path.to_str() {
+            Some("/mnt") => Some(2),
+            Some("/unknown") => None,
+            _ => Some(1),
+        });
+        assert!(on_root_device(1, Path::new("/home"), &devices));
+        assert!(!on_root_device(1, Path::new("/mnt"), &devices));
+        // An unknown device doesn't prune
+        assert!(on_root_device(1, Path::new("/unknown"), &devices));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_walker_one_file_system_same_device() {
+        // Everything in a temp dir lives on one device, so nothing is pruned
+        let temp = create_test_fixture();
+        let walk = |one_file_system: bool| {
+            let walker = Walker::new(
+                temp.path().to_path_buf(),
+                WalkOptions::new().one_file_system(one_file_system),
+            );
+            let mut paths: Vec<_> = walker.walk_sync().into_iter().map(|e| e.path).collect();
+            paths.sort();
+            paths
+        };
+        assert_eq!(walk(true), walk(false));
+    }
+
     #[test]
     fn test_walker_parallel_matches_serial_results() {
         let temp = create_test_fixture();
@@ -1700,6 +2406,163 @@ mod tests {
         );
     }
 
+    /// Descriptor limit `walk_under_low_fd_limit` runs under. Low enough that
+    /// walkdir's default of 10 open handles runs into `EMFILE`.
+    #[cfg(unix)]
+    const LOW_FD_LIMIT: u32 = 12;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walker_max_open_dirs_completes_deep_walk() {
+        let temp = TempDir::new().unwrap();
+        let mut dir = temp.path().to_path_buf();
+        for depth in 0..40 {
+            dir.push(format!("d{depth}"));
+            fs::create_dir(&dir).unwrap();
+            File::create(dir.join("file.txt")).unwrap();
+        }
+
+        // Lowering RLIMIT_NOFILE here would starve the other tests, so the walks
+        // run in a copy of this test binary started under `ulimit -n`
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("ulimit -n {LOW_FD_LIMIT} && exec \"$0\" \"$@\""))
+            .arg(std::env::current_exe().unwrap())
+            .args(["--exact", "walker::tests::walk_under_low_fd_limit"])
+            .args(["--ignored", "--nocapture", "--test-threads=1"])
+            .env("GLOBLIN_LOW_FD_TREE", temp.path())
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{stdout}\n{stderr}");
+        assert!(stdout.contains("1 passed"), "{stdout}");
+    }
+
+    /// Run by `test_walker_max_open_dirs_completes_deep_walk` with the descriptor
+    /// limit lowered; does nothing when run directly.
+    #[cfg(unix)]
+    #[test]
+    #[ignore = "run under a lowered descriptor limit by the max_open_dirs test"]
+    fn walk_under_low_fd_limit() {
+        let Some(root) = std::env::var_os("GLOBLIN_LOW_FD_TREE") else {
+            return;
+        };
+        let root = PathBuf::from(root);
+        let walk = |options: WalkOptions| Walker::new(root.clone(), options.dot(true)).walk_sync();
+
+        // Without a limit the walk keeps more handles open than it may, and the
+        // directories it can't open are silently dropped
+        assert!(walk(WalkOptions::new()).len() < 81);
+
+        let mut backends = vec![
+            WalkOptions::new(),
+            WalkOptions::new().parallel(true),
+            WalkOptions::new().cache(true),
+        ];
+        #[cfg(target_os = "linux")]
+        backends.push(WalkOptions::new().use_native_io(true));
+        for options in backends {
+            for max_open in [1, 4] {
+                let options = options.clone().max_open_dirs(Some(max_open));
+                let label = format!("{options:?}");
+                assert_eq!(walk(options.clone()).len(), 81, "{label}");
+
+                // Walks sharing the options share the limit, so several at once
+                // still fit
+                std::thread::scope(|scope| {
+                    let walks: Vec<_> = (0..4)
+                        .map(|_| scope.spawn(|| walk(options.clone()).len()))
+                        .collect();
+                    for handle in walks {
+                        assert_eq!(handle.join().unwrap(), 81, "{label}");
+                    }
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn test_walker_hard_depth_limit_stops_deep_tree() {
+        let temp = TempDir::new().unwrap();
+        let mut dir = temp.path().to_path_buf();
+        for _ in 0..300 {
+            dir.push("d");
+            fs::create_dir(&dir).unwrap();
+        }
+
+        let mut backends = vec![
+            WalkOptions::new(),
+            WalkOptions::new().parallel(true),
+            WalkOptions::new().cache(true),
+        ];
+        #[cfg(target_os = "linux")]
+        backends.push(WalkOptions::new().use_native_io(true));
+
+        for options in backends {
+            let label = format!("{options:?}");
+            let walker = Walker::new(
+                temp.path().to_path_buf(),
+                options.clone().hard_depth_limit(Some(200)),
+            );
+            let entries = walker.walk_sync();
+            assert_eq!(
+                entries.iter().map(|e| e.depth()).max(),
+                Some(200),
+                "{label}"
+            );
+            assert_eq!(entries.len(), 201, "{label}");
+            assert!(walker.reached_depth_limit(), "{label}");
+
+            // A limit the tree doesn't reach, or a lower maxDepth, isn't reported
+            let walker = Walker::new(temp.path().to_path_buf(), options.clone());
+            assert_eq!(walker.walk_sync().len(), 301, "{label}");
+            assert!(!walker.reached_depth_limit(), "{label}");
+            let walker = Walker::new(
+                temp.path().to_path_buf(),
+                options
+                    .clone()
+                    .max_depth(Some(100))
+                    .hard_depth_limit(Some(200)),
+            );
+            assert_eq!(walker.walk_sync().len(), 101, "{label}");
+            assert!(!walker.reached_depth_limit(), "{label}");
+
+            // Nothing is cut off when the tree ends in an empty directory right
+            // at the limit
+            let walker = Walker::new(
+                temp.path().to_path_buf(),
+                options.hard_depth_limit(Some(300)),
+            );
+            assert_eq!(walker.walk_sync().len(), 301, "{label}");
+            assert!(!walker.reached_depth_limit(), "{label}");
+        }
+    }
+
+    #[test]
+    fn test_dir_handle_limit_bounds_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let limit = DirHandleLimit::new(2);
+        let open = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..20 {
+                        let _permit = limit.acquire();
+                        let now = open.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        std::thread::yield_now();
+                        open.fetch_sub(1, Ordering::SeqCst);
+                    }
+                });
+            }
+        });
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(*limit.available.lock().unwrap(), 2);
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_walker_parallel_with_symlinks() {
@@ -1724,4 +2587,224 @@ mod tests {
         assert!(entries.iter().any(|e| e.path().ends_with("real/file.txt")));
         assert!(entries.iter().any(|e| e.path().ends_with("link/file.txt")));
     }
+
+    /// Assert that entries sharing a parent directory were yielded in name order.
+    fn assert_sorted_within_dirs(entries: &[WalkEntry]) {
+        let mut last_name: std::collections::HashMap<&Path, &std::ffi::OsStr> =
+            std::collections::HashMap::new();
+        for entry in entries.iter().filter(|e| e.depth() > 0) {
+            let parent = entry.path().parent().unwrap();
+            let name = entry.file_name().unwrap();
+            if let Some(prev) = last_name.insert(parent, name) {
+                assert!(
+                    prev < name,
+                    "{prev:?} yielded before {name:?} in {parent:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_walker_sort_dir_entries() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+
+        // Create in reverse order so creation order can't produce a sorted listing
+        for dir in ["zeta", "mid", "alpha"] {
+            fs::create_dir_all(base.join(dir)).unwrap();
+            for file in ["z.txt", "m.txt", "b.txt", "a.txt"] {
+                File::create(base.join(dir).join(file)).unwrap();
+            }
+        }
+        for file in ["y.js", "c.js", "B.js"] {
+            File::create(base.join(file)).unwrap();
+        }
+
+        let option_sets = [
+            WalkOptions::new(),
+            WalkOptions::new().parallel(true),
+            WalkOptions::new().cache(true),
+            WalkOptions::new().use_native_io(true),
+        ];
+
+        for options in option_sets {
+            let walker = Walker::new(base.to_path_buf(), options.sort_dir_entries(true));
+            let entries = walker.walk_sync();
+            assert_eq!(entries.len(), 1 + 3 + 3 + 3 * 4);
+            assert_sorted_within_dirs(&entries);
+        }
+    }
+
+    #[test]
+    fn test_walker_reads_only_through_file_system() {
+        use crate::fs::MemoryFileSystem;
+        use std::sync::atomic::Ordering;
+
+        let root = "/globlin-virtual-archive";
+        let archive = Arc::new(
+            MemoryFileSystem::new(
+                root,
+                &[
+                    "README.md",
+                    ".hidden",
+                    "src/main.rs",
+                    "src/lib/util.rs",
+                    "docs/guide.md",
+                    "empty/",
+                ],
+            )
+            .with_symlink(root, "alias", "/globlin-virtual-archive/src/lib"),
+        );
+        let relative = |entries: Vec<WalkEntry>| {
+            let mut paths: Vec<String> = entries
+                .iter()
+                .map(|e| {
+                    let rel = e.path.strip_prefix(root).unwrap().to_string_lossy();
+                    let kind = if e.is_dir { "/" } else { "" };
+                    format!("{rel}{kind}")
+                })
+                .collect();
+            paths.sort();
+            paths
+        };
+        let all = [
+            ".hidden",
+            "/",
+            "README.md",
+            "alias",
+            "docs/",
+            "docs/guide.md",
+            "empty/",
+            "src/",
+            "src/lib/",
+            "src/lib/util.rs",
+            "src/main.rs",
+        ];
+
+        // The backend-selecting options are ignored: every walk reads the archive
+        let option_sets = [
+            WalkOptions::new().dot(true),
+            WalkOptions::new().dot(true).parallel(true),
+            WalkOptions::new().dot(true).cache(true),
+            WalkOptions::new().dot(true).use_native_io(true),
+            WalkOptions::new().dot(true).use_gcd(true),
+            WalkOptions::new().dot(true).sort_dir_entries(true),
+            WalkOptions::new().dot(true).dir_order(DirOrder::PostOrder),
+        ];
+        for options in option_sets {
+            let label = format!("{options:?}");
+            let walker = Walker::new(
+                PathBuf::from(root),
+                options.file_system(Some(archive.clone())),
+            );
+            assert_eq!(relative(walker.walk_sync()), all, "{label}");
+        }
+        assert!(archive.calls.load(Ordering::Relaxed) > 0);
+
+        let walk = |options: WalkOptions| {
+            let walker = Walker::new(
+                PathBuf::from(root),
+                options.file_system(Some(archive.clone())),
+            );
+            relative(walker.walk_sync())
+        };
+        assert_eq!(
+            walk(WalkOptions::new().max_depth(Some(1))),
+            ["/", "README.md", "alias", "docs/", "empty/", "src/"]
+        );
+
+        // Following the symlink descends through the archive's own metadata
+        let followed = walk(WalkOptions::new().follow_symlinks(true));
+        assert!(followed.contains(&"alias/".to_string()));
+        assert!(followed.contains(&"alias/util.rs".to_string()));
+
+        let pruned = Walker::new(
+            PathBuf::from(root),
+            WalkOptions::new().file_system(Some(archive.clone())),
+        )
+        .with_dir_prune_filter(Box::new(|rel| rel != "src"));
+        assert!(!relative(pruned.walk_sync())
+            .iter()
+            .any(|p| p.starts_with("src/")));
+    }
+
+    #[test]
+    fn test_walker_post_order_yields_dirs_after_contents() {
+        let temp = create_test_fixture();
+
+        let option_sets = [
+            WalkOptions::new(),
+            WalkOptions::new().parallel(true),
+            WalkOptions::new().cache(true),
+            WalkOptions::new().use_native_io(true),
+            WalkOptions::new().max_depth(Some(2)),
+        ];
+
+        for options in option_sets {
+            let label = format!("{options:?}");
+            let pre = Walker::new(temp.path().to_path_buf(), options.clone()).walk_sync();
+            let post = Walker::new(
+                temp.path().to_path_buf(),
+                options.dir_order(DirOrder::PostOrder),
+            )
+            .walk_sync();
+
+            // Same entries, only reordered
+            let sorted = |entries: &[WalkEntry]| {
+                let mut paths: Vec<PathBuf> = entries.iter().map(|e| e.path.clone()).collect();
+                paths.sort();
+                paths
+            };
+            assert_eq!(sorted(&post), sorted(&pre), "{label}");
+
+            for (i, entry) in post.iter().enumerate() {
+                assert!(
+                    post[i + 1..]
+                        .iter()
+                        .all(|later| !later.path.starts_with(&entry.path)),
+                    "{label}: {:?} yielded before its contents",
+                    entry.path
+                );
+            }
+            assert_eq!(post.last().unwrap().path, temp.path(), "{label}");
+        }
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_walker_paths_beyond_max_path() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path().canonicalize().unwrap();
+        let base = crate::util::strip_windows_extended_prefix(base);
+
+        // Well over MAX_PATH (260 chars) below the root
+        let segment = "d".repeat(40);
+        let deep = (0..8).fold(base.clone(), |dir, i| dir.join(format!("{segment}{i}")));
+        fs::create_dir_all(&deep).unwrap();
+        File::create(deep.join("leaf.txt")).unwrap();
+        assert!(deep.join("leaf.txt").as_os_str().len() > 260);
+
+        let option_sets = [
+            WalkOptions::new(),
+            WalkOptions::new().parallel(true),
+            WalkOptions::new().cache(true),
+        ];
+
+        for options in option_sets {
+            let walker = Walker::new(base.clone(), options);
+            let entries = walker.walk_sync();
+            // Root, 8 directories and the file
+            assert_eq!(entries.len(), 10);
+            let leaf = entries
+                .iter()
+                .find(|e| e.path().ends_with("leaf.txt"))
+                .expect("deep file should be walked");
+            assert!(leaf.is_file());
+            // The extended-length prefix never leaks into results
+            assert_eq!(leaf.path(), deep.join("leaf.txt"));
+            assert!(entries
+                .iter()
+                .all(|e| !e.path().to_string_lossy().starts_with(r"\\?\")));
+        }
+    }
 }
@@ -54,6 +54,12 @@ describe('escape', () => {
       expect(escape('{a,b}')).toBe('{a,b}')
     })
 
+    it('should escape braces with magicalBraces', () => {
+      expect(escape('{a,b}', { magicalBraces: true })).toBe('\\{a,b\\}')
+      expect(escape('{a,b}', { magicalBraces: true, windowsPathsNoEscape: true })).toBe('[{]a,b[}]')
+      expect(unescape(escape('{a,b}', { magicalBraces: true }))).toBe('{a,b}')
+    })
+
     it('should escape only parentheses in extglob patterns', () => {
       // Only ( and ) are escaped, not the prefix chars
       expect(escape('+(a|b)')).toBe('+\\(a|b\\)')
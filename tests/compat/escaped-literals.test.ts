@@ -0,0 +1,70 @@
+// Escaped characters in otherwise literal patterns must match the same
+// names as in glob
+
+import { describe, it, expect, beforeAll, afterAll } from 'vitest'
+import * as glob from 'glob'
+import * as fs from 'fs'
+import * as path from 'path'
+import { loadGloblin, GloblinModule } from '../harness'
+
+// Backslashes can't appear in file names on Windows
+describe.skipIf(process.platform === 'win32')('escaped literals', () => {
+  let globlin: GloblinModule | null = null
+  let fixtureDir: string
+
+  beforeAll(async () => {
+    globlin = await loadGloblin()
+
+    fixtureDir = path.join(__dirname, '..', '..', 'test-fixtures-escaped-literals')
+    if (fs.existsSync(fixtureDir)) {
+      fs.rmSync(fixtureDir, { recursive: true, force: true })
+    }
+
+    // test-fixtures-escaped-literals/
+    //   a\b.txt
+    //   a*b
+    //   ab.txt
+    //   d*r/
+    //     x.js
+    fs.mkdirSync(path.join(fixtureDir, 'd*r'), { recursive: true })
+    fs.writeFileSync(path.join(fixtureDir, 'a\\b.txt'), '')
+    fs.writeFileSync(path.join(fixtureDir, 'a*b'), '')
+    fs.writeFileSync(path.join(fixtureDir, 'ab.txt'), '')
+    fs.writeFileSync(path.join(fixtureDir, 'd*r', 'x.js'), '')
+  })
+
+  afterAll(() => {
+    if (fs.existsSync(fixtureDir)) {
+      fs.rmSync(fixtureDir, { recursive: true, force: true })
+    }
+  })
+
+  describe('patterns without braces keep their escapes', () => {
+    // An escaped backslash is a literal backslash; brace expansion must not
+    // collapse `\\` to `\` when there is nothing to expand
+    for (const pattern of ['a\\\\*', '*\\\\*']) {
+      it(`${pattern} matches like glob`, () => {
+        if (!globlin) throw new Error('globlin not loaded')
+        const globResults = glob.globSync(pattern, { cwd: fixtureDir })
+        // globlin reports `\` as `/` by default, so compare what matched
+        const globlinResults = globlin.globSync(pattern, { cwd: fixtureDir })
+        expect(globResults).toHaveLength(1)
+        expect(globlinResults).toHaveLength(globResults.length)
+      })
+    }
+  })
+
+  const sortResults = (arr: string[]) => arr.sort((a, b) => a.localeCompare(b, 'en'))
+
+  describe('literal patterns are unescaped before reading the disk', () => {
+    for (const pattern of ['a\\*b', 'd\\*r/*.js', 'd\\*r/x.js']) {
+      it(`${pattern} matches like glob`, async () => {
+        if (!globlin) throw new Error('globlin not loaded')
+        const globResults = sortResults(await glob.glob(pattern, { cwd: fixtureDir }))
+        const globlinResults = sortResults(await globlin.glob(pattern, { cwd: fixtureDir }))
+        expect(globResults).toHaveLength(1)
+        expect(globlinResults).toEqual(globResults)
+      })
+    }
+  })
+})
@@ -39,6 +39,7 @@ interface ExtendedGloblinModule extends GloblinModule {
 }
 
 let globlin: ExtendedGloblinModule
+let globIterateFull: typeof import('../../js/index.js').globIterate
 let fixturePath: string
 
 beforeAll(async () => {
@@ -51,6 +52,7 @@ beforeAll(async () => {
   globlin.globStreamSync = fullMod.globStreamSync
   globlin.globIterate = fullMod.globIterate
   globlin.globIterateSync = fullMod.globIterateSync
+  globIterateFull = fullMod.globIterate
 
   // Create fixture
   fixturePath = await createTestFixture('stream-test', {
@@ -216,6 +218,71 @@ describe('globIterate', () => {
   })
 })
 
+describe('globIterate with withFileTypes', () => {
+  it('should yield typed paths', async () => {
+    const types = new Map<string, string>()
+    for await (const entry of globIterateFull('**', {
+      cwd: fixturePath,
+      withFileTypes: true,
+      stat: true,
+    })) {
+      const type = entry.isDirectory() ? 'dir' : entry.isFile() ? 'file' : 'unknown'
+      types.set(entry.relative(), type)
+    }
+
+    expect(new Set(types.keys())).toEqual(new Set(globlin.globSync('**', { cwd: fixturePath })))
+    expect(types.get('z')).toBe('file')
+    expect(types.get(j(['cb/e/f'])[0])).toBe('file')
+    expect(types.get('cb')).toBe('dir')
+    expect(types.get(j(['cb/e'])[0])).toBe('dir')
+  })
+
+  it('should end the walk on an early break', async () => {
+    const iterator = globIterateFull('**', { cwd: fixturePath, withFileTypes: true })
+    const seen: string[] = []
+    for await (const entry of iterator) {
+      seen.push(entry.relative())
+      break
+    }
+
+    expect(seen).toHaveLength(1)
+    // The break closed the walk, so the generator yields nothing more
+    expect(await iterator.next()).toEqual({ done: true, value: undefined })
+  })
+
+  it('should throw if the signal is already aborted', async () => {
+    const ac = new AbortController()
+    const testError = new Error('test abort')
+    ac.abort(testError)
+
+    const iterator = globIterateFull('**', {
+      cwd: fixturePath,
+      withFileTypes: true,
+      signal: ac.signal,
+    })
+    await expect(iterator.next()).rejects.toThrow(testError)
+  })
+
+  it('should throw once the signal is aborted mid-walk', async () => {
+    const ac = new AbortController()
+    const testError = new Error('mid abort')
+    const iterator = globIterateFull('**', {
+      cwd: fixturePath,
+      withFileTypes: true,
+      signal: ac.signal,
+    })
+
+    expect((await iterator.next()).done).toBe(false)
+    ac.abort(testError)
+    const drain = async () => {
+      while (!(await iterator.next()).done) {
+        // entries already fetched before the abort may still arrive
+      }
+    }
+    await expect(drain()).rejects.toThrow(testError)
+  })
+})
+
 describe('globIterateSync', () => {
   it('should yield all matching files', () => {
     const results: string[] = []